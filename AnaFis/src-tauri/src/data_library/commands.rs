@@ -1,14 +1,18 @@
+use std::collections::HashMap;
 use std::fs::create_dir_all;
 use std::sync::Mutex;
 use tauri::{AppHandle, Manager, State, command};
 
 use super::database::DataLibraryDatabase;
 use super::models::{
-    BatchImportRequest, BatchImportResponse, DataSequence, SaveSequenceRequest, SearchRequest,
-    SequenceListResponse, SequenceStatistics, UpdateSequenceRequest,
+    BatchImportRequest, BatchImportResponse, CsvExportOptions, DataSequence, SaveSequenceRequest,
+    SearchFields, SearchRequest, SequenceDiffResult, SequenceListResponse, SequenceStatistics,
+    SequenceSummary, SequenceVersion, UpdateSequenceRequest,
+};
+use super::statistics::{calculate_statistics, diff_sequences as diff_sequence_data};
+use crate::error::{
+    CommandResult, database_error, export_error, import_error, internal_error, validation_error,
 };
-use super::statistics::calculate_statistics;
-use crate::error::{CommandResult, database_error, export_error, internal_error};
 
 pub struct DataLibraryState(pub Mutex<DataLibraryDatabase>);
 
@@ -66,6 +70,33 @@ pub fn get_sequences(
     })
 }
 
+/// Case-insensitive search with `*` wildcards over the chosen fields,
+/// most relevant first
+#[command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn search_sequences(
+    query: String,
+    search_fields: SearchFields,
+    state: State<DataLibraryState>,
+) -> CommandResult<Vec<SequenceSummary>> {
+    if query.trim().is_empty() {
+        return Err(validation_error(
+            "Search query cannot be empty",
+            Some("query".to_owned()),
+        ));
+    }
+    if search_fields.is_empty() {
+        return Err(validation_error(
+            "Select at least one field to search",
+            Some("search_fields".to_owned()),
+        ));
+    }
+    with_db(&state, move |db| {
+        db.search_sequences(&query, search_fields)
+            .map_err(|e| database_error(format!("Failed to search sequences: {e}")))
+    })
+}
+
 #[command]
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
 pub fn get_sequence(
@@ -99,6 +130,62 @@ pub fn delete_sequence(id: String, state: State<DataLibraryState>) -> CommandRes
     })
 }
 
+/// Earlier states of a sequence, most recently replaced first
+#[command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn get_sequence_history(
+    id: String,
+    state: State<DataLibraryState>,
+) -> CommandResult<Vec<SequenceVersion>> {
+    with_db(&state, move |db| {
+        db.get_sequence_history(&id)
+            .map_err(|e| database_error(format!("Failed to get sequence history: {e}")))
+    })
+}
+
+/// Make an earlier version of a sequence current again; the replaced state
+/// is kept in the history
+#[command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn restore_sequence_version(
+    id: String,
+    version_index: usize,
+    state: State<DataLibraryState>,
+) -> CommandResult<()> {
+    with_db(&state, move |db| {
+        let restored = db
+            .restore_sequence_version(&id, version_index)
+            .map_err(|e| database_error(format!("Failed to restore sequence version: {e}")))?;
+        if restored {
+            Ok(())
+        } else {
+            Err(validation_error(
+                format!("Sequence {id} has no version {version_index}"),
+                Some("version_index".to_owned()),
+            ))
+        }
+    })
+}
+
+#[command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn get_sequence_history_limit(state: State<DataLibraryState>) -> CommandResult<u32> {
+    with_db(&state, |db| {
+        db.history_limit()
+            .map_err(|e| database_error(format!("Failed to get history limit: {e}")))
+    })
+}
+
+/// Set how many versions are kept per sequence (default 10)
+#[command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn set_sequence_history_limit(limit: u32, state: State<DataLibraryState>) -> CommandResult<()> {
+    with_db(&state, move |db| {
+        db.set_history_limit(limit)
+            .map_err(|e| database_error(format!("Failed to set history limit: {e}")))
+    })
+}
+
 #[command]
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
 pub fn get_sequence_stats(
@@ -113,6 +200,38 @@ pub fn get_sequence_stats(
     })
 }
 
+/// Element-wise differences `second - first` over the common prefix of two
+/// sequences, with their summary, the indices changed by more than
+/// `threshold` (default 0) and the metadata differences
+#[command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn diff_sequences(
+    id1: String,
+    id2: String,
+    threshold: Option<f64>,
+    state: State<DataLibraryState>,
+) -> CommandResult<SequenceDiffResult> {
+    let threshold = threshold.unwrap_or(0.0);
+    if !(threshold.is_finite() && threshold >= 0.0) {
+        return Err(validation_error(
+            "Threshold must be a non-negative number",
+            Some("threshold".to_owned()),
+        ));
+    }
+    with_db(&state, move |db| {
+        let load = |id: &str, field: &str| -> CommandResult<DataSequence> {
+            db.get_sequence(id)
+                .map_err(|e| database_error(format!("Failed to get sequence: {e}")))?
+                .ok_or_else(|| {
+                    validation_error(format!("Sequence {id} not found"), Some(field.to_owned()))
+                })
+        };
+        let first = load(&id1, "id1")?;
+        let second = load(&id2, "id2")?;
+        Ok(diff_sequence_data(&first, &second, threshold))
+    })
+}
+
 #[command]
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
 pub fn pin_sequence(
@@ -146,6 +265,35 @@ pub fn duplicate_sequence(
     })
 }
 
+/// Create a sequence computed from `formula` over the sequences keyed by
+/// formula variable; it is re-evaluated when any of them changes, with
+/// propagated uncertainties unless `propagate_uncertainty` is false
+#[command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn create_derived_sequence(
+    name: String,
+    formula: String,
+    input_sequence_ids: HashMap<String, String>,
+    propagate_uncertainty: Option<bool>,
+    state: State<DataLibraryState>,
+) -> CommandResult<String> {
+    if input_sequence_ids.is_empty() {
+        return Err(validation_error(
+            "A derived sequence needs at least one input sequence",
+            Some("input_sequence_ids".to_owned()),
+        ));
+    }
+    with_db(&state, move |db| {
+        db.create_derived_sequence(
+            &name,
+            &formula,
+            &input_sequence_ids,
+            propagate_uncertainty.unwrap_or(true),
+        )
+        .map_err(|e| database_error(format!("Failed to create derived sequence: {e}")))
+    })
+}
+
 #[command]
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
 pub fn get_all_tags(state: State<DataLibraryState>) -> CommandResult<Vec<String>> {
@@ -160,10 +308,12 @@ pub fn get_all_tags(state: State<DataLibraryState>) -> CommandResult<Vec<String>
 pub fn export_sequences_csv(
     sequence_ids: Vec<String>,
     file_path: String,
+    options: Option<CsvExportOptions>,
     state: State<DataLibraryState>,
 ) -> CommandResult<()> {
+    let options = options.unwrap_or_default();
     with_db(&state, move |db| {
-        db.export_to_csv(&sequence_ids, &file_path)
+        db.export_to_csv(&sequence_ids, &file_path, &options)
             .map_err(|e| export_error(format!("Failed to export to CSV: {e}")))
     })
 }
@@ -174,5 +324,8 @@ pub fn batch_import_sequences(
     request: BatchImportRequest,
     state: State<DataLibraryState>,
 ) -> CommandResult<BatchImportResponse> {
-    with_db(&state, move |db| Ok(db.batch_import_sequences(request)))
+    with_db(&state, move |db| {
+        db.batch_import_sequences(request)
+            .map_err(|e| import_error(format!("Failed to import sequences: {e}")))
+    })
 }
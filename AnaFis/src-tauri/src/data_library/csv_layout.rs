@@ -0,0 +1,411 @@
+//! CSV layout shared by the data library export and batch import
+//!
+//! Each sequence is one row and each field one column, identified by the
+//! header so columns may appear in any order or be left out. List fields
+//! (tags, values, uncertainties) are joined with semicolons inside a single
+//! field; a backslash escapes semicolons and backslashes within tags.
+
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use serde_json::{from_str, to_string};
+use std::io::{Read, Write};
+
+use super::models::{
+    BatchImportError, CsvExportOptions, DataSequence, MetadataColumn, SaveSequenceRequest,
+};
+
+const NAME: &str = "name";
+const DESCRIPTION: &str = "description";
+const TAGS: &str = "tags";
+const TAGS_JSON: &str = "tags_json";
+const UNIT: &str = "unit";
+const SOURCE: &str = "source";
+const PINNED: &str = "is_pinned";
+const CREATED_AT: &str = "created_at";
+const MODIFIED_AT: &str = "modified_at";
+const DATA: &str = "data";
+const UNCERTAINTIES: &str = "uncertainties";
+
+/// Separator of the items of a list field
+const LIST_SEPARATOR: char = ';';
+/// Escape character for separators inside joined tags
+const ESCAPE: char = '\\';
+
+/// Single-byte delimiter accepted by the CSV reader and writer
+fn delimiter_byte(delimiter: char) -> Result<u8, String> {
+    u8::try_from(delimiter)
+        .ok()
+        .filter(|byte| byte.is_ascii() && *byte != b'"' && *byte != b'\n')
+        .ok_or_else(|| format!("Unsupported CSV delimiter: {delimiter:?}"))
+}
+
+/// Write `sequences` as one CSV row each
+pub fn write_sequences_csv<W: Write>(
+    sequences: &[DataSequence],
+    options: &CsvExportOptions,
+    writer: W,
+) -> Result<(), String> {
+    let mut writer = WriterBuilder::new()
+        .delimiter(delimiter_byte(options.delimiter)?)
+        .from_writer(writer);
+
+    let mut header = vec![NAME];
+    header.extend(options.columns.iter().map(|column| match column {
+        MetadataColumn::Description => DESCRIPTION,
+        MetadataColumn::Tags if options.join_tags => TAGS,
+        MetadataColumn::Tags => TAGS_JSON,
+        MetadataColumn::Unit => UNIT,
+        MetadataColumn::Source => SOURCE,
+        MetadataColumn::Pinned => PINNED,
+        MetadataColumn::CreatedAt => CREATED_AT,
+        MetadataColumn::ModifiedAt => MODIFIED_AT,
+    }));
+    if options.include_values {
+        header.extend([DATA, UNCERTAINTIES]);
+    }
+    writer
+        .write_record(&header)
+        .map_err(|e| format!("Failed to write CSV header: {e}"))?;
+
+    for seq in sequences {
+        let mut record = vec![seq.name.clone()];
+        for column in &options.columns {
+            record.push(match column {
+                MetadataColumn::Description => seq.description.clone(),
+                MetadataColumn::Tags if options.join_tags => join_tags(&seq.tags),
+                MetadataColumn::Tags => {
+                    to_string(&seq.tags).map_err(|e| format!("Failed to serialize tags: {e}"))?
+                }
+                MetadataColumn::Unit => seq.unit.clone(),
+                MetadataColumn::Source => seq.source.clone(),
+                MetadataColumn::Pinned => seq.is_pinned.to_string(),
+                MetadataColumn::CreatedAt => seq.created_at.to_rfc3339(),
+                MetadataColumn::ModifiedAt => seq.modified_at.to_rfc3339(),
+            });
+        }
+        if options.include_values {
+            record.push(join_values(&seq.data));
+            record.push(
+                seq.uncertainties
+                    .as_deref()
+                    .map(join_values)
+                    .unwrap_or_default(),
+            );
+        }
+        writer
+            .write_record(&record)
+            .map_err(|e| format!("Failed to write sequence '{}': {e}", seq.name))?;
+    }
+
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to write CSV file: {e}"))
+}
+
+/// Read the sequences of a CSV file in the export layout
+///
+/// Columns are matched by header name; unknown ones (such as timestamps) are
+/// ignored and only the name column is required. A row that cannot be read
+/// becomes an error indexed by its position among the data rows.
+pub fn read_sequences_csv<R: Read>(
+    reader: R,
+    delimiter: char,
+) -> Result<Vec<Result<SaveSequenceRequest, BatchImportError>>, String> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter_byte(delimiter)?)
+        .from_reader(reader);
+    let header = reader
+        .headers()
+        .map_err(|e| format!("Failed to read CSV header: {e}"))?
+        .clone();
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|field| field.trim().eq_ignore_ascii_case(name))
+    };
+    let columns = Columns {
+        name: column(NAME).ok_or("CSV file has no 'name' column")?,
+        description: column(DESCRIPTION),
+        tags: column(TAGS),
+        tags_json: column(TAGS_JSON),
+        unit: column(UNIT),
+        source: column(SOURCE),
+        pinned: column(PINNED),
+        data: column(DATA),
+        uncertainties: column(UNCERTAINTIES),
+    };
+
+    Ok(reader
+        .records()
+        .enumerate()
+        .map(|(index, record)| {
+            let record = record.map_err(|e| BatchImportError {
+                index,
+                sequence_name: String::new(),
+                error: format!("Failed to read CSV row: {e}"),
+            })?;
+            columns.parse(&record).map_err(|error| BatchImportError {
+                index,
+                sequence_name: record.get(columns.name).unwrap_or_default().to_owned(),
+                error,
+            })
+        })
+        .collect())
+}
+
+/// Positions of the known columns in a CSV header
+struct Columns {
+    name: usize,
+    description: Option<usize>,
+    tags: Option<usize>,
+    tags_json: Option<usize>,
+    unit: Option<usize>,
+    source: Option<usize>,
+    pinned: Option<usize>,
+    data: Option<usize>,
+    uncertainties: Option<usize>,
+}
+
+impl Columns {
+    fn parse(&self, record: &StringRecord) -> Result<SaveSequenceRequest, String> {
+        let field = |column: Option<usize>| column.and_then(|index| record.get(index));
+        let text = |column: Option<usize>| field(column).unwrap_or_default().to_owned();
+
+        let name = text(Some(self.name));
+        if name.trim().is_empty() {
+            return Err("Sequence name is empty".to_owned());
+        }
+        let tags = match (field(self.tags_json), field(self.tags)) {
+            (Some(json), _) if !json.is_empty() => {
+                from_str(json).map_err(|e| format!("Invalid tags_json field: {e}"))?
+            }
+            (_, Some(joined)) => split_tags(joined),
+            _ => Vec::new(),
+        };
+        let is_pinned = match field(self.pinned).map(str::trim) {
+            None | Some("") => false,
+            Some(value) if value == "1" || value.eq_ignore_ascii_case("true") => true,
+            Some(value) if value == "0" || value.eq_ignore_ascii_case("false") => false,
+            Some(value) => return Err(format!("Invalid is_pinned value: '{value}'")),
+        };
+        let data = field(self.data)
+            .map(|values| split_values(values, DATA))
+            .transpose()?
+            .unwrap_or_default();
+        let uncertainties = field(self.uncertainties)
+            .filter(|values| !values.is_empty())
+            .map(|values| split_values(values, UNCERTAINTIES))
+            .transpose()?;
+
+        Ok(SaveSequenceRequest {
+            id: None,
+            name,
+            description: text(self.description),
+            tags,
+            unit: text(self.unit),
+            source: text(self.source),
+            data,
+            uncertainties,
+            is_pinned,
+        })
+    }
+}
+
+/// Numbers joined by the list separator, in round-trip precision
+fn join_values(values: &[f64]) -> String {
+    values
+        .iter()
+        .map(f64::to_string)
+        .collect::<Vec<_>>()
+        .join(&LIST_SEPARATOR.to_string())
+}
+
+/// Numbers of a list field named `column`
+fn split_values(values: &str, column: &str) -> Result<Vec<f64>, String> {
+    if values.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    values
+        .split(LIST_SEPARATOR)
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .map_err(|_err| format!("Invalid number in {column}: '{value}'"))
+        })
+        .collect()
+}
+
+/// Tags joined by the list separator, escaping separators inside tags
+fn join_tags(tags: &[String]) -> String {
+    let mut joined = String::new();
+    for (index, tag) in tags.iter().enumerate() {
+        if index > 0 {
+            joined.push(LIST_SEPARATOR);
+        }
+        for c in tag.chars() {
+            if c == LIST_SEPARATOR || c == ESCAPE {
+                joined.push(ESCAPE);
+            }
+            joined.push(c);
+        }
+    }
+    joined
+}
+
+/// Inverse of [`join_tags`]
+fn split_tags(joined: &str) -> Vec<String> {
+    if joined.is_empty() {
+        return Vec::new();
+    }
+    let mut tags = Vec::new();
+    let mut current = String::new();
+    let mut chars = joined.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            ESCAPE => current.extend(chars.next()),
+            LIST_SEPARATOR => tags.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    tags.push(current);
+    tags
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use crate::data_library::database::DataLibraryDatabase;
+    use crate::data_library::models::{BatchImportRequest, ConflictPolicy, SearchRequest};
+    use std::env::temp_dir;
+    use std::fs::remove_file;
+
+    fn sample_request(index: usize) -> SaveSequenceRequest {
+        SaveSequenceRequest {
+            id: None,
+            name: format!("Temp\u{e9}rature \u{2116}{index} \u{2014} \u{6e2c}\u{5b9a}"),
+            description: format!("Run {index}, \"calibrated\"\nsecond line"),
+            tags: vec![
+                "\u{3b1}, \u{3b2}".to_owned(),
+                format!("batch;{}", index % 3),
+                r"C:\data".to_owned(),
+            ],
+            unit: "\u{b5}m/s\u{b2}".to_owned(),
+            source: format!("Sheet1, A1:A{index}"),
+            data: std::iter::successors(Some(-1e-12), |value| Some(value + 0.1))
+                .take(index)
+                .collect(),
+            // An empty uncertainty list is written like a missing one
+            uncertainties: (index.is_multiple_of(2) && index > 0).then(|| vec![0.01; index]),
+            is_pinned: index.is_multiple_of(4),
+        }
+    }
+
+    fn all_sequences(db: &DataLibraryDatabase) -> Vec<DataSequence> {
+        let search = SearchRequest {
+            page_size: Some(100),
+            ..SearchRequest::default()
+        };
+        let mut sequences = db.get_sequences_paginated(&search).unwrap().sequences;
+        sequences.sort_by(|a, b| a.name.cmp(&b.name));
+        sequences
+    }
+
+    fn csv_import(
+        file_path: &str,
+        delimiter: char,
+        on_conflict: ConflictPolicy,
+    ) -> BatchImportRequest {
+        BatchImportRequest {
+            sequences: Vec::new(),
+            csv_file_path: Some(file_path.to_owned()),
+            delimiter: Some(delimiter),
+            on_conflict,
+        }
+    }
+
+    #[test]
+    fn test_csv_round_trip_preserves_metadata() {
+        let source = DataLibraryDatabase::new(":memory:").unwrap();
+        let ids: Vec<String> = (0..50)
+            .map(|index| source.save_sequence(&sample_request(index)).unwrap())
+            .collect();
+
+        for (delimiter, join_tags) in [(',', true), (';', false)] {
+            let path = temp_dir().join(format!(
+                "anafis-library-test-{}-{join_tags}.csv",
+                std::process::id()
+            ));
+            let path = path.to_str().unwrap();
+            let options = CsvExportOptions {
+                delimiter,
+                join_tags,
+                ..CsvExportOptions::default()
+            };
+            source.export_to_csv(&ids, path, &options).unwrap();
+
+            let target = DataLibraryDatabase::new(":memory:").unwrap();
+            let response = target
+                .batch_import_sequences(csv_import(path, delimiter, ConflictPolicy::Fail))
+                .unwrap();
+            remove_file(path).unwrap();
+            assert_eq!(response.successful_imports, 50);
+            assert!(response.errors.is_empty());
+
+            for (original, imported) in all_sequences(&source).iter().zip(all_sequences(&target)) {
+                assert_eq!(imported.name, original.name);
+                assert_eq!(imported.description, original.description);
+                assert_eq!(imported.tags, original.tags);
+                assert_eq!(imported.unit, original.unit);
+                assert_eq!(imported.source, original.source);
+                assert_eq!(imported.is_pinned, original.is_pinned);
+                assert_eq!(imported.data, original.data);
+                assert_eq!(imported.uncertainties, original.uncertainties);
+            }
+        }
+    }
+
+    #[test]
+    fn test_csv_import_conflict_policies() {
+        let db = DataLibraryDatabase::new(":memory:").unwrap();
+        let id = db.save_sequence(&sample_request(3)).unwrap();
+        let path = temp_dir().join(format!(
+            "anafis-library-conflict-test-{}.csv",
+            std::process::id()
+        ));
+        let path = path.to_str().unwrap();
+        db.export_to_csv(&[id], path, &CsvExportOptions::default())
+            .unwrap();
+
+        let skipped = db
+            .batch_import_sequences(csv_import(path, ',', ConflictPolicy::Skip))
+            .unwrap();
+        assert_eq!(skipped.skipped_imports, 1);
+        let failed = db
+            .batch_import_sequences(csv_import(path, ',', ConflictPolicy::Fail))
+            .unwrap();
+        assert_eq!(failed.failed_imports, 1);
+        db.batch_import_sequences(csv_import(path, ',', ConflictPolicy::Rename))
+            .unwrap();
+        let names: Vec<String> = all_sequences(&db).into_iter().map(|s| s.name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Temp\u{e9}rature \u{2116}3 \u{2014} \u{6e2c}\u{5b9a}".to_owned(),
+                "Temp\u{e9}rature \u{2116}3 \u{2014} \u{6e2c}\u{5b9a} (2)".to_owned()
+            ]
+        );
+        db.batch_import_sequences(csv_import(path, ',', ConflictPolicy::Replace))
+            .unwrap();
+        assert_eq!(all_sequences(&db).len(), 2);
+        remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_joined_tags_escape_separators() {
+        let tags = vec!["a;b".to_owned(), r"c\d".to_owned(), "e, f".to_owned()];
+        let joined = join_tags(&tags);
+        assert_eq!(joined, r"a\;b;c\\d;e, f");
+        assert_eq!(split_tags(&joined), tags);
+    }
+}
@@ -4,10 +4,14 @@
 //! It provides CRUD operations for storing and retrieving data sequences with
 //! associated metadata and statistics.
 
+use super::csv_layout::{read_sequences_csv, write_sequences_csv};
+use super::derived::{Derivation, DerivedValues, evaluate_derived};
 use super::models::{
-    BatchImportError, BatchImportRequest, BatchImportResponse, DataSequence, SaveSequenceRequest,
-    SearchRequest, SequenceListResponse, SortBy, SortOrder, UpdateSequenceRequest,
+    BatchImportError, BatchImportRequest, BatchImportResponse, ConflictPolicy, CsvExportOptions,
+    DataSequence, SaveSequenceRequest, SearchFields, SearchRequest, SequenceListResponse,
+    SequenceSummary, SequenceVersion, SortBy, SortOrder, UpdateSequenceRequest,
 };
+use super::search::{SearchPattern, rank_sequences};
 use chrono::{DateTime, Utc};
 use rusqlite::types::Type;
 use rusqlite::{
@@ -15,15 +19,23 @@ use rusqlite::{
     params, params_from_iter,
 };
 use serde_json::{from_str, to_string};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 use std::fs::File;
-use std::io::Write as IoWrite;
 use std::sync::Mutex;
+use std::time::Duration;
 use uuid::Uuid;
 
 use crate::error::API_VERSION;
 
+/// How long a statement waits for a lock held by another connection
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Versions kept per sequence until the user changes the setting
+const DEFAULT_HISTORY_LIMIT: u32 = 10;
+/// Settings key of the number of versions kept per sequence
+const HISTORY_LIMIT_KEY: &str = "sequence_history_limit";
+
 pub struct DataLibraryDatabase {
     conn: Mutex<Connection>,
 }
@@ -237,6 +249,11 @@ impl DataLibraryDatabase {
     pub fn new(db_path: &str) -> SqliteResult<Self> {
         let conn = Connection::open(db_path)?;
 
+        // Write-ahead logging keeps readers unblocked during writes and the
+        // file consistent if the app stops mid-transaction
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+
         // Create sequences table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS sequences (
@@ -267,6 +284,58 @@ impl DataLibraryDatabase {
             [],
         )?;
 
+        // Earlier states of each sequence, newest with the highest version_id
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS sequence_versions (
+                version_id INTEGER PRIMARY KEY,
+                sequence_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT,
+                tags TEXT,
+                unit TEXT,
+                source TEXT,
+                data BLOB NOT NULL,
+                uncertainties BLOB,
+                is_pinned INTEGER DEFAULT 0,
+                created_at TEXT NOT NULL,
+                modified_at TEXT NOT NULL,
+                replaced_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Library settings, one value per key
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Formula of each derived sequence; `stale` marks a cached evaluation
+        // invalidated by a change to one of its inputs
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS derived_sequences (
+                sequence_id TEXT PRIMARY KEY,
+                formula TEXT NOT NULL,
+                propagate_uncertainty INTEGER NOT NULL DEFAULT 1,
+                stale INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+
+        // Sequence each variable of a derived sequence's formula stands for
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS derived_inputs (
+                derived_id TEXT NOT NULL,
+                variable TEXT NOT NULL,
+                input_id TEXT NOT NULL,
+                PRIMARY KEY (derived_id, variable)
+            )",
+            [],
+        )?;
+
         // Create indexes for better query performance
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_sequences_name ON sequences(name)",
@@ -284,14 +353,27 @@ impl DataLibraryDatabase {
             "CREATE INDEX IF NOT EXISTS idx_sequences_modified ON sequences(modified_at)",
             [],
         )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_sequence_versions_sequence ON sequence_versions(sequence_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_derived_inputs_input ON derived_inputs(input_id)",
+            [],
+        )?;
 
         Ok(Self {
             conn: Mutex::new(conn),
         })
     }
 
-    /// Save a new sequence to the database
+    /// Save a new sequence to the database, or overwrite the sequence
+    /// `request.id` after pushing its current state onto its history
     pub fn save_sequence(&self, request: &SaveSequenceRequest) -> SqliteResult<String> {
+        if let Some(id) = &request.id {
+            self.overwrite_sequence(id, request)?;
+            return Ok(id.clone());
+        }
         let id = Uuid::new_v4().to_string();
         let now = Utc::now();
 
@@ -394,7 +476,16 @@ impl DataLibraryDatabase {
         })
     }
 
+    /// Get a sequence; a derived sequence whose inputs changed since it was
+    /// last evaluated is re-evaluated first
     pub fn get_sequence(&self, id: &str) -> SqliteResult<Option<DataSequence>> {
+        if let Some(derivation) = self.stale_derivation(id)? {
+            self.refresh_derived(id, &derivation)?;
+        }
+        self.read_sequence(id)
+    }
+
+    fn read_sequence(&self, id: &str) -> SqliteResult<Option<DataSequence>> {
         self.conn
             .lock().expect("Database connection should not be poisoned")
             .prepare(
@@ -443,16 +534,26 @@ impl DataLibraryDatabase {
         let query = format!("UPDATE sequences SET {} WHERE id = ?", updates.join(", "));
 
         {
-            let conn = self
+            let mut conn = self
                 .conn
                 .lock()
                 .expect("Database connection should not be poisoned");
-            conn.execute(&query, params_from_iter(params.iter()))?;
+            // The version, the row and its search entry change together
+            let tx = conn.transaction()?;
+            // Pinning alone is not worth a version
+            if request.name.is_some()
+                || request.description.is_some()
+                || request.tags.is_some()
+                || request.unit.is_some()
+            {
+                Self::push_version(&tx, &request.id, &now.to_rfc3339())?;
+            }
+            tx.execute(&query, params_from_iter(params.iter()))?;
 
             // Update FTS table if name, description, or tags changed
             if request.name.is_some() || request.description.is_some() || request.tags.is_some() {
                 // Get current values for FTS update
-                let mut stmt = conn.prepare(
+                let mut stmt = tx.prepare(
                     "SELECT name, description, tags, source FROM sequences WHERE id = ?",
                 )?;
                 let mut rows = stmt.query(params![request.id])?;
@@ -466,12 +567,14 @@ impl DataLibraryDatabase {
                         SqliteError::FromSqlConversionFailure(2, Type::Text, Box::new(e))
                     })?;
 
-                    conn.execute(
+                    tx.execute(
                         "UPDATE sequences_fts SET name = ?1, description = ?2, tags = ?3, source = ?4 WHERE id = ?5",
                         params![name, description, tags.join(" "), source, request.id],
                     )?;
                 }
             }
+            tx.commit()?;
+            drop(conn);
         }
 
         Ok(())
@@ -485,10 +588,32 @@ impl DataLibraryDatabase {
             .expect("Database connection should not be poisoned");
         conn.execute("DELETE FROM sequences WHERE id = ?", params![id])?;
         conn.execute("DELETE FROM sequences_fts WHERE id = ?", params![id])?;
+        conn.execute(
+            "DELETE FROM sequence_versions WHERE sequence_id = ?",
+            params![id],
+        )?;
+        Self::invalidate_dependents(&conn, id)?;
+        Self::detach_derivation(&conn, id)?;
         drop(conn);
         Ok(())
     }
 
+    /// Sequences matching `query` in any of `fields`, most relevant first.
+    /// Matching runs in-process over every sequence, since values and
+    /// wildcards are beyond the FTS index.
+    pub fn search_sequences(
+        &self,
+        query: &str,
+        fields: SearchFields,
+    ) -> SqliteResult<Vec<SequenceSummary>> {
+        let sequences = self.query_sequences_with_limit(&SearchRequest::default(), None, None)?;
+        Ok(rank_sequences(
+            sequences,
+            &SearchPattern::new(query),
+            fields,
+        ))
+    }
+
     /// Get all unique tags
     pub fn get_all_tags(&self) -> SqliteResult<Vec<String>> {
         let mut all_tags = HashSet::new();
@@ -518,6 +643,7 @@ impl DataLibraryDatabase {
             .ok_or_else(|| SqliteError::QueryReturnedNoRows)?;
 
         let request = SaveSequenceRequest {
+            id: None,
             name: new_name.to_owned(),
             description: format!("{} (copy)", sequence.description),
             tags: sequence.tags,
@@ -531,11 +657,401 @@ impl DataLibraryDatabase {
         self.save_sequence(&request)
     }
 
-    /// Export sequences to CSV format
-    /// Format: Each sequence becomes a column pair (name, `name_uncertainty`)
-    /// First row: column headers
-    /// Subsequent rows: data values
-    pub fn export_to_csv(&self, sequence_ids: &[String], file_path: &str) -> SqliteResult<()> {
+    /// Earlier states of sequence `id`, most recently replaced first
+    pub fn get_sequence_history(&self, id: &str) -> SqliteResult<Vec<SequenceVersion>> {
+        Self::read_sequence_history(
+            &self
+                .conn
+                .lock()
+                .expect("Database connection should not be poisoned"),
+            id,
+        )
+    }
+
+    fn read_sequence_history(conn: &Connection, id: &str) -> SqliteResult<Vec<SequenceVersion>> {
+        conn.prepare(
+                "SELECT sequence_id, name, description, tags, unit, source, data, uncertainties, is_pinned, created_at, modified_at, replaced_at
+                 FROM sequence_versions
+                 WHERE sequence_id = ?1
+                 ORDER BY version_id DESC",
+            )?
+            .query_map(params![id], |row| {
+                let replaced_at_str: String = row.get(11)?;
+                let replaced_at = DateTime::parse_from_rfc3339(&replaced_at_str)
+                    .map_err(|e| SqliteError::FromSqlConversionFailure(11, Type::Text, Box::new(e)))?
+                    .with_timezone(&Utc);
+                Ok((replaced_at, Self::row_to_sequence(row)?))
+            })?
+            .enumerate()
+            .map(|(version_index, version)| {
+                version.map(|(replaced_at, sequence)| SequenceVersion {
+                    version_index,
+                    replaced_at,
+                    sequence,
+                })
+            })
+            .collect()
+    }
+
+    /// Make version `version_index` of sequence `id` current again; the
+    /// state it replaces goes onto the history, so a restore can be undone.
+    /// Returns `false` when there is no such version.
+    pub fn restore_sequence_version(&self, id: &str, version_index: usize) -> SqliteResult<bool> {
+        let mut conn = self
+            .conn
+            .lock()
+            .expect("Database connection should not be poisoned");
+        // The lookup shares the transaction, so a save in between cannot
+        // shift which version `version_index` names
+        let tx = conn.transaction()?;
+        let Some(version) = Self::read_sequence_history(&tx, id)?
+            .into_iter()
+            .nth(version_index)
+        else {
+            return Ok(false);
+        };
+        let sequence = version.sequence;
+        Self::write_overwrite(
+            &tx,
+            id,
+            &SaveSequenceRequest {
+                id: Some(id.to_owned()),
+                name: sequence.name,
+                description: sequence.description,
+                tags: sequence.tags,
+                unit: sequence.unit,
+                source: sequence.source,
+                data: sequence.data,
+                uncertainties: sequence.uncertainties,
+                is_pinned: sequence.is_pinned,
+            },
+        )?;
+        tx.commit()?;
+        drop(conn);
+        Ok(true)
+    }
+
+    /// Most versions kept per sequence
+    pub fn history_limit(&self) -> SqliteResult<u32> {
+        Self::read_history_limit(
+            &self
+                .conn
+                .lock()
+                .expect("Database connection should not be poisoned"),
+        )
+    }
+
+    /// Keep at most `limit` versions per sequence, dropping the oldest
+    /// versions beyond it right away
+    pub fn set_history_limit(&self, limit: u32) -> SqliteResult<()> {
+        let conn = self
+            .conn
+            .lock()
+            .expect("Database connection should not be poisoned");
+        conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![HISTORY_LIMIT_KEY, limit.to_string()],
+        )?;
+        let pruned = Self::prune_history(&conn, limit);
+        drop(conn);
+        pruned
+    }
+
+    /// Replace the content of sequence `id` with `request`, keeping its
+    /// creation time, after pushing its current state onto its history.
+    /// Sequences derived from it are invalidated, and a derived sequence
+    /// overwritten this way keeps its new values as plain data.
+    fn overwrite_sequence(&self, id: &str, request: &SaveSequenceRequest) -> SqliteResult<()> {
+        let mut conn = self
+            .conn
+            .lock()
+            .expect("Database connection should not be poisoned");
+        let tx = conn.transaction()?;
+        Self::write_overwrite(&tx, id, request)?;
+        tx.commit()?;
+        drop(conn);
+        Ok(())
+    }
+
+    /// Body of [`Self::overwrite_sequence`], run inside the caller's
+    /// transaction so the history, the sequence and its search entry change
+    /// together or not at all
+    fn write_overwrite(
+        conn: &Connection,
+        id: &str,
+        request: &SaveSequenceRequest,
+    ) -> SqliteResult<()> {
+        let now = Utc::now().to_rfc3339();
+        let data_json = to_string(&request.data)
+            .map_err(|e| SqliteError::ToSqlConversionFailure(Box::new(e)))?;
+        let uncertainties_json = request
+            .uncertainties
+            .as_ref()
+            .map(to_string)
+            .transpose()
+            .map_err(|e| SqliteError::ToSqlConversionFailure(Box::new(e)))?;
+        let tags_json = to_string(&request.tags)
+            .map_err(|e| SqliteError::ToSqlConversionFailure(Box::new(e)))?;
+
+        if !Self::push_version(conn, id, &now)? {
+            return Err(SqliteError::QueryReturnedNoRows);
+        }
+        conn.execute(
+            "UPDATE sequences SET name = ?1, description = ?2, tags = ?3, unit = ?4, source = ?5, data = ?6, uncertainties = ?7, is_pinned = ?8, modified_at = ?9
+             WHERE id = ?10",
+            params![
+                request.name,
+                request.description,
+                tags_json,
+                request.unit,
+                request.source,
+                data_json,
+                uncertainties_json,
+                i32::from(request.is_pinned),
+                now,
+                id,
+            ],
+        )?;
+        conn.execute(
+            "UPDATE sequences_fts SET name = ?1, description = ?2, tags = ?3, source = ?4 WHERE id = ?5",
+            params![
+                request.name,
+                request.description,
+                request.tags.join(" "),
+                request.source,
+                id,
+            ],
+        )?;
+        Self::invalidate_dependents(conn, id)?;
+        Self::detach_derivation(conn, id)?;
+        Ok(())
+    }
+
+    /// Copy the current state of sequence `id` into its history and drop
+    /// versions beyond the limit. Returns `false` when there is no such
+    /// sequence.
+    fn push_version(conn: &Connection, id: &str, replaced_at: &str) -> SqliteResult<bool> {
+        let copied = conn.execute(
+            "INSERT INTO sequence_versions (sequence_id, name, description, tags, unit, source, data, uncertainties, is_pinned, created_at, modified_at, replaced_at)
+             SELECT id, name, description, tags, unit, source, data, uncertainties, is_pinned, created_at, modified_at, ?2
+             FROM sequences
+             WHERE id = ?1",
+            params![id, replaced_at],
+        )?;
+        Self::prune_history(conn, Self::read_history_limit(conn)?)?;
+        Ok(copied > 0)
+    }
+
+    /// Delete all but the newest `limit` versions of every sequence
+    fn prune_history(conn: &Connection, limit: u32) -> SqliteResult<()> {
+        conn.execute(
+            "DELETE FROM sequence_versions WHERE version_id IN (
+                SELECT version_id FROM (
+                    SELECT version_id, ROW_NUMBER() OVER (
+                        PARTITION BY sequence_id ORDER BY version_id DESC
+                    ) AS position
+                    FROM sequence_versions
+                )
+                WHERE position > ?1
+            )",
+            params![limit],
+        )?;
+        Ok(())
+    }
+
+    fn read_history_limit(conn: &Connection) -> SqliteResult<u32> {
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?1",
+                params![HISTORY_LIMIT_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_HISTORY_LIMIT))
+    }
+
+    /// Create a sequence whose values are `formula` evaluated over the
+    /// sequences of `inputs`, keyed by formula variable. The formula and
+    /// inputs are stored with the evaluated values, which are recomputed
+    /// when an input changes.
+    pub fn create_derived_sequence(
+        &self,
+        name: &str,
+        formula: &str,
+        inputs: &HashMap<String, String>,
+        propagate_uncertainty: bool,
+    ) -> Result<String, String> {
+        let mut inputs: Vec<(String, String)> = inputs
+            .iter()
+            .map(|(variable, id)| (variable.to_lowercase(), id.clone()))
+            .collect();
+        inputs.sort();
+        let derivation = Derivation {
+            formula: formula.trim().to_owned(),
+            propagate_uncertainty,
+            inputs,
+        };
+        let values = self
+            .evaluate_derivation(&derivation)
+            .map_err(|e| e.to_string())?;
+        let id = self
+            .save_sequence(&SaveSequenceRequest {
+                id: None,
+                name: name.to_owned(),
+                description: String::new(),
+                tags: Vec::new(),
+                unit: String::new(),
+                source: format!("= {}", derivation.formula),
+                data: values.data,
+                uncertainties: values.uncertainties,
+                is_pinned: false,
+            })
+            .map_err(|e| e.to_string())?;
+
+        let conn = self
+            .conn
+            .lock()
+            .expect("Database connection should not be poisoned");
+        conn.execute(
+            "INSERT INTO derived_sequences (sequence_id, formula, propagate_uncertainty)
+             VALUES (?1, ?2, ?3)",
+            params![
+                id,
+                derivation.formula,
+                i32::from(derivation.propagate_uncertainty)
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+        for (variable, input_id) in &derivation.inputs {
+            conn.execute(
+                "INSERT INTO derived_inputs (derived_id, variable, input_id) VALUES (?1, ?2, ?3)",
+                params![id, variable, input_id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        drop(conn);
+        Ok(id)
+    }
+
+    /// Derivation of sequence `id` when its cached values are out of date
+    fn stale_derivation(&self, id: &str) -> SqliteResult<Option<Derivation>> {
+        let conn = self
+            .conn
+            .lock()
+            .expect("Database connection should not be poisoned");
+        let Some((formula, propagate_uncertainty)) = conn
+            .query_row(
+                "SELECT formula, propagate_uncertainty FROM derived_sequences
+                 WHERE sequence_id = ?1 AND stale = 1",
+                params![id],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)? != 0)),
+            )
+            .optional()?
+        else {
+            return Ok(None);
+        };
+        let inputs = conn
+            .prepare(
+                "SELECT variable, input_id FROM derived_inputs WHERE derived_id = ?1 ORDER BY variable",
+            )?
+            .query_map(params![id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<SqliteResult<Vec<(String, String)>>>()?;
+        drop(conn);
+        Ok(Some(Derivation {
+            formula,
+            propagate_uncertainty,
+            inputs,
+        }))
+    }
+
+    /// Load the inputs of `derivation`, refreshing derived inputs first, and
+    /// evaluate its formula
+    fn evaluate_derivation(&self, derivation: &Derivation) -> SqliteResult<DerivedValues> {
+        let mut inputs = Vec::with_capacity(derivation.inputs.len());
+        for (variable, input_id) in &derivation.inputs {
+            let sequence = self.get_sequence(input_id)?.ok_or_else(|| {
+                SqliteError::ToSqlConversionFailure(
+                    format!("Input sequence {input_id} for '{variable}' does not exist").into(),
+                )
+            })?;
+            inputs.push((variable.clone(), sequence));
+        }
+        evaluate_derived(
+            &derivation.formula,
+            &inputs,
+            derivation.propagate_uncertainty,
+        )
+        .map_err(|e| SqliteError::ToSqlConversionFailure(e.into()))
+    }
+
+    /// Re-evaluate derived sequence `id` and cache its values
+    fn refresh_derived(&self, id: &str, derivation: &Derivation) -> SqliteResult<()> {
+        let values = self.evaluate_derivation(derivation)?;
+        let data_json = to_string(&values.data)
+            .map_err(|e| SqliteError::ToSqlConversionFailure(Box::new(e)))?;
+        let uncertainties_json = values
+            .uncertainties
+            .as_ref()
+            .map(to_string)
+            .transpose()
+            .map_err(|e| SqliteError::ToSqlConversionFailure(Box::new(e)))?;
+        let conn = self
+            .conn
+            .lock()
+            .expect("Database connection should not be poisoned");
+        conn.execute(
+            "UPDATE sequences SET data = ?1, uncertainties = ?2, modified_at = ?3 WHERE id = ?4",
+            params![data_json, uncertainties_json, Utc::now().to_rfc3339(), id],
+        )?;
+        conn.execute(
+            "UPDATE derived_sequences SET stale = 0 WHERE sequence_id = ?1",
+            params![id],
+        )?;
+        drop(conn);
+        Ok(())
+    }
+
+    /// Mark every sequence derived, directly or through other derived
+    /// sequences, from sequence `id` as needing re-evaluation
+    fn invalidate_dependents(conn: &Connection, id: &str) -> SqliteResult<()> {
+        conn.execute(
+            "WITH RECURSIVE dependents(id) AS (
+                SELECT derived_id FROM derived_inputs WHERE input_id = ?1
+                UNION
+                SELECT derived_inputs.derived_id FROM derived_inputs
+                JOIN dependents ON derived_inputs.input_id = dependents.id
+            )
+            UPDATE derived_sequences SET stale = 1
+            WHERE sequence_id IN (SELECT id FROM dependents)",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    /// Forget the formula of sequence `id`, if it is derived
+    fn detach_derivation(conn: &Connection, id: &str) -> SqliteResult<()> {
+        conn.execute(
+            "DELETE FROM derived_sequences WHERE sequence_id = ?1",
+            params![id],
+        )?;
+        conn.execute(
+            "DELETE FROM derived_inputs WHERE derived_id = ?1",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    /// Export sequences to CSV, one row per sequence in the layout of
+    /// `options`, which `batch_import_sequences` reads back losslessly
+    pub fn export_to_csv(
+        &self,
+        sequence_ids: &[String],
+        file_path: &str,
+        options: &CsvExportOptions,
+    ) -> SqliteResult<()> {
         // Fetch all sequences
         let mut sequences = Vec::new();
         for id in sequence_ids {
@@ -548,93 +1064,343 @@ impl DataLibraryDatabase {
             return Err(SqliteError::QueryReturnedNoRows);
         }
 
-        // Find the maximum length
-        let max_len = sequences.iter().map(|s| s.data.len()).max().unwrap_or(0);
-
-        let mut file = File::create(file_path)
-            .map_err(|e| SqliteError::ToSqlConversionFailure(Box::new(e)))?;
-
-        // Write header row with embedded metadata
-        // Format: [name,unit,description,tags]
-        let mut header = Vec::new();
-        for seq in &sequences {
-            // Escape commas and quotes in metadata fields
-            let name = seq.name.replace(',', ";").replace('"', "'");
-            let unit = seq.unit.replace(',', ";").replace('"', "'");
-            let description = seq.description.replace(',', ";").replace('"', "'");
-            let tags = seq.tags.join("|").replace(',', ";").replace('"', "'");
-
-            // Data column with full metadata
-            header.push(format!("[{name},{unit},{description},{tags}]"));
-
-            // Uncertainty column if present
-            if seq.uncertainties.is_some() {
-                header.push(format!(
-                    "[{name}_uncertainty,{unit},Uncertainty values for {name},{tags}]"
-                ));
-            }
-        }
-        writeln!(file, "{}", header.join(","))
+        let file = File::create(file_path)
             .map_err(|e| SqliteError::ToSqlConversionFailure(Box::new(e)))?;
+        write_sequences_csv(&sequences, options, file)
+            .map_err(|e| SqliteError::ToSqlConversionFailure(e.into()))
+    }
 
-        // Write data rows
-        for i in 0..max_len {
-            let mut row = Vec::new();
-            for seq in &sequences {
-                if i < seq.data.len() {
-                    row.push(seq.data[i].to_string());
-                    if let Some(ref uncertainties) = seq.uncertainties {
-                        if i < uncertainties.len() {
-                            row.push(uncertainties[i].to_string());
-                        } else {
-                            row.push(String::new());
-                        }
-                    }
-                } else {
-                    row.push(String::new());
-                    if seq.uncertainties.is_some() {
-                        row.push(String::new());
-                    }
-                }
-            }
-            writeln!(file, "{}", row.join(","))
-                .map_err(|e| SqliteError::ToSqlConversionFailure(Box::new(e)))?;
+    /// Batch import multiple sequences, followed by those of the request's
+    /// CSV file. Returns results for each sequence, continuing on errors;
+    /// fails only when the CSV file cannot be read.
+    pub fn batch_import_sequences(
+        &self,
+        request: BatchImportRequest,
+    ) -> Result<BatchImportResponse, String> {
+        let mut entries: Vec<Result<SaveSequenceRequest, BatchImportError>> =
+            request.sequences.into_iter().map(Ok).collect();
+        if let Some(file_path) = &request.csv_file_path {
+            let file = File::open(file_path).map_err(|e| format!("Failed to open file: {e}"))?;
+            entries.extend(read_sequences_csv(file, request.delimiter.unwrap_or(','))?);
         }
 
-        Ok(())
-    }
-
-    /// Batch import multiple sequences
-    /// Returns results for each sequence, continuing on errors
-    pub fn batch_import_sequences(&self, request: BatchImportRequest) -> BatchImportResponse {
         let mut successful_imports = 0;
         let mut failed_imports = 0;
+        let mut skipped_imports = 0;
         let mut errors = Vec::new();
         let mut imported_ids = Vec::new();
 
-        for (index, sequence_request) in request.sequences.into_iter().enumerate() {
-            match self.save_sequence(&sequence_request) {
-                Ok(id) => {
+        for (index, entry) in entries.into_iter().enumerate() {
+            let result = entry.and_then(|sequence_request| {
+                let name = sequence_request.name.clone();
+                self.import_sequence(sequence_request, request.on_conflict)
+                    .map_err(|error| BatchImportError {
+                        index,
+                        sequence_name: name,
+                        error,
+                    })
+            });
+            match result {
+                Ok(Some(id)) => {
                     successful_imports += 1;
                     imported_ids.push(id);
                 }
+                Ok(None) => skipped_imports += 1,
                 Err(e) => {
                     failed_imports += 1;
-                    errors.push(BatchImportError {
-                        index,
-                        sequence_name: sequence_request.name,
-                        error: e.to_string(),
-                    });
+                    errors.push(BatchImportError { index, ..e });
                 }
             }
         }
 
-        BatchImportResponse {
+        Ok(BatchImportResponse {
             version: API_VERSION.to_owned(),
             successful_imports,
             failed_imports,
+            skipped_imports,
             errors,
             imported_ids,
+        })
+    }
+
+    /// Save an imported sequence, resolving a name conflict by `on_conflict`.
+    /// Returns the new id, or `None` when the sequence was skipped.
+    fn import_sequence(
+        &self,
+        mut request: SaveSequenceRequest,
+        on_conflict: ConflictPolicy,
+    ) -> Result<Option<String>, String> {
+        let existing = self
+            .sequence_ids_by_name(&request.name)
+            .map_err(|e| e.to_string())?;
+        if !existing.is_empty() {
+            match on_conflict {
+                ConflictPolicy::Rename => {
+                    request.name = self.free_name(&request.name).map_err(|e| e.to_string())?;
+                }
+                ConflictPolicy::Skip => return Ok(None),
+                ConflictPolicy::Replace => {
+                    for id in &existing {
+                        self.delete_sequence(id).map_err(|e| e.to_string())?;
+                    }
+                }
+                ConflictPolicy::Fail => {
+                    return Err(format!(
+                        "A sequence named '{}' already exists",
+                        request.name
+                    ));
+                }
+            }
         }
+        self.save_sequence(&request)
+            .map(Some)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Ids of the sequences named `name`
+    fn sequence_ids_by_name(&self, name: &str) -> SqliteResult<Vec<String>> {
+        self.conn
+            .lock()
+            .expect("Database connection should not be poisoned")
+            .prepare("SELECT id FROM sequences WHERE name = ?1")?
+            .query_map(params![name], |row| row.get(0))?
+            .collect()
+    }
+
+    /// First of "name (2)", "name (3)", ... not used by any sequence
+    fn free_name(&self, name: &str) -> SqliteResult<String> {
+        let mut suffix = 2_usize;
+        loop {
+            let candidate = format!("{name} ({suffix})");
+            if self.sequence_ids_by_name(&candidate)?.is_empty() {
+                return Ok(candidate);
+            }
+            suffix += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    fn request(id: Option<&str>, name: &str, data: Vec<f64>) -> SaveSequenceRequest {
+        SaveSequenceRequest {
+            id: id.map(str::to_owned),
+            name: name.to_owned(),
+            description: String::new(),
+            tags: vec!["run".to_owned()],
+            unit: "s".to_owned(),
+            source: String::new(),
+            data,
+            uncertainties: None,
+            is_pinned: false,
+        }
+    }
+
+    fn history_data(db: &DataLibraryDatabase, id: &str) -> Vec<Vec<f64>> {
+        db.get_sequence_history(id)
+            .unwrap()
+            .into_iter()
+            .map(|version| version.sequence.data)
+            .collect()
+    }
+
+    #[test]
+    fn test_file_database_uses_write_ahead_log() {
+        let path = std::env::temp_dir().join(format!("anafis_library_{}.db", Uuid::new_v4()));
+        let db = DataLibraryDatabase::new(path.to_str().unwrap()).unwrap();
+        db.save_sequence(&request(None, "Period", vec![1.0]))
+            .unwrap();
+        let mode: String = db
+            .conn
+            .lock()
+            .unwrap()
+            .pragma_query_value(None, "journal_mode", |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, "wal");
+        drop(db);
+        for suffix in ["", "-wal", "-shm"] {
+            let mut file = path.clone().into_os_string();
+            file.push(suffix);
+            std::fs::remove_file(file).ok();
+        }
+    }
+
+    #[test]
+    fn test_overwrites_keep_versions_and_restore() {
+        let db = DataLibraryDatabase::new(":memory:").unwrap();
+        let id = db
+            .save_sequence(&request(None, "Period", vec![1.0]))
+            .unwrap();
+        let created_at = db.get_sequence(&id).unwrap().unwrap().created_at;
+        for value in [2.0, 3.0] {
+            let saved = db
+                .save_sequence(&request(Some(&id), "Period", vec![value]))
+                .unwrap();
+            assert_eq!(saved, id);
+        }
+        let current = db.get_sequence(&id).unwrap().unwrap();
+        assert_eq!(current.data, [3.0]);
+        assert_eq!(current.created_at, created_at);
+        assert_eq!(history_data(&db, &id), [vec![2.0], vec![1.0]]);
+        let history = db.get_sequence_history(&id).unwrap();
+        assert_eq!(history[1].version_index, 1);
+        assert!(history[0].replaced_at >= history[1].replaced_at);
+
+        // Restoring the first save keeps the replaced state in the history
+        assert!(db.restore_sequence_version(&id, 1).unwrap());
+        assert_eq!(db.get_sequence(&id).unwrap().unwrap().data, [1.0]);
+        assert_eq!(history_data(&db, &id), [vec![3.0], vec![2.0], vec![1.0]]);
+        assert!(!db.restore_sequence_version(&id, 3).unwrap());
+
+        // Metadata edits are versioned, pinning is not
+        db.update_sequence(&UpdateSequenceRequest {
+            id: id.clone(),
+            name: Some("Pendulum period".to_owned()),
+            description: None,
+            tags: None,
+            unit: None,
+            is_pinned: None,
+        })
+        .unwrap();
+        db.update_sequence(&UpdateSequenceRequest {
+            id: id.clone(),
+            name: None,
+            description: None,
+            tags: None,
+            unit: None,
+            is_pinned: Some(true),
+        })
+        .unwrap();
+        let versions = db.get_sequence_history(&id).unwrap();
+        assert_eq!(versions.len(), 4);
+        assert_eq!(versions[0].sequence.name, "Period");
+
+        assert!(
+            db.save_sequence(&request(Some("missing"), "Period", vec![0.0]))
+                .is_err()
+        );
+        db.delete_sequence(&id).unwrap();
+        assert!(db.get_sequence_history(&id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_failed_overwrite_leaves_history_untouched() {
+        let db = DataLibraryDatabase::new(":memory:").unwrap();
+        let id = db
+            .save_sequence(&request(None, "Period", vec![1.0]))
+            .unwrap();
+        db.save_sequence(&request(Some(&id), "Period", vec![2.0]))
+            .unwrap();
+
+        // Without the search table the last statement of an overwrite fails
+        db.conn
+            .lock()
+            .unwrap()
+            .execute("DROP TABLE sequences_fts", [])
+            .unwrap();
+        assert!(
+            db.save_sequence(&request(Some(&id), "Period", vec![3.0]))
+                .is_err()
+        );
+        assert!(db.restore_sequence_version(&id, 0).is_err());
+        assert_eq!(db.get_sequence(&id).unwrap().unwrap().data, [2.0]);
+        assert_eq!(history_data(&db, &id), [vec![1.0]]);
+    }
+
+    #[test]
+    fn test_history_limit_drops_oldest_versions() {
+        let db = DataLibraryDatabase::new(":memory:").unwrap();
+        assert_eq!(db.history_limit().unwrap(), DEFAULT_HISTORY_LIMIT);
+        let id = db.save_sequence(&request(None, "Mass", vec![0.0])).unwrap();
+        let other = db
+            .save_sequence(&request(None, "Length", vec![0.0]))
+            .unwrap();
+        for value in 1..=12_u32 {
+            let data = vec![f64::from(value)];
+            db.save_sequence(&request(Some(&id), "Mass", data.clone()))
+                .unwrap();
+            db.save_sequence(&request(Some(&other), "Length", data))
+                .unwrap();
+        }
+        let kept = history_data(&db, &id);
+        assert_eq!(kept.len(), 10);
+        assert_eq!(kept[0], [11.0]);
+        assert_eq!(kept[9], [2.0]);
+
+        db.set_history_limit(3).unwrap();
+        assert_eq!(db.history_limit().unwrap(), 3);
+        assert_eq!(
+            history_data(&db, &other),
+            [vec![11.0], vec![10.0], vec![9.0]]
+        );
+        db.set_history_limit(0).unwrap();
+        db.save_sequence(&request(Some(&id), "Mass", vec![13.0]))
+            .unwrap();
+        assert!(db.get_sequence_history(&id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_derived_sequences_follow_their_inputs() {
+        let db = DataLibraryDatabase::new(":memory:").unwrap();
+        let distance = db
+            .save_sequence(&request(None, "Distance", vec![2.0, 6.0]))
+            .unwrap();
+        let time = db
+            .save_sequence(&request(None, "Time", vec![1.0, 2.0]))
+            .unwrap();
+        let inputs = HashMap::from([
+            ("X".to_owned(), distance.clone()),
+            ("t".to_owned(), time.clone()),
+        ]);
+        let speed = db
+            .create_derived_sequence("Speed", "x / t", &inputs, true)
+            .unwrap();
+        let doubled = db
+            .create_derived_sequence(
+                "Double speed",
+                "2 * v",
+                &HashMap::from([("v".to_owned(), speed.clone())]),
+                true,
+            )
+            .unwrap();
+        let derived = db.get_sequence(&speed).unwrap().unwrap();
+        assert_eq!(derived.data, [2.0, 3.0]);
+        assert_eq!(derived.source, "= x / t");
+        assert!(derived.uncertainties.is_none());
+
+        // A new input version reaches sequences derived through other
+        // derived sequences
+        let mut remeasured = request(Some(&distance), "Distance", vec![4.0, 8.0]);
+        remeasured.uncertainties = Some(vec![0.5, 1.0]);
+        db.save_sequence(&remeasured).unwrap();
+        let chained = db.get_sequence(&doubled).unwrap().unwrap();
+        assert_eq!(chained.data, [8.0, 8.0]);
+        assert_eq!(chained.uncertainties.unwrap(), [1.0, 1.0]);
+        assert_eq!(db.get_sequence(&speed).unwrap().unwrap().data, [4.0, 4.0]);
+
+        // Overwriting a derived sequence makes its values plain data
+        db.save_sequence(&request(Some(&speed), "Speed", vec![1.0]))
+            .unwrap();
+        db.save_sequence(&request(Some(&time), "Time", vec![4.0, 4.0]))
+            .unwrap();
+        assert_eq!(db.get_sequence(&speed).unwrap().unwrap().data, [1.0]);
+
+        db.delete_sequence(&time).unwrap();
+        let recomputed = db
+            .create_derived_sequence("Rate", "x / t", &inputs, false)
+            .unwrap_err();
+        assert!(recomputed.contains("does not exist"), "{recomputed}");
+        assert!(
+            db.create_derived_sequence(
+                "Bad",
+                "x +",
+                &HashMap::from([("x".to_owned(), distance)]),
+                true
+            )
+            .is_err()
+        );
     }
 }
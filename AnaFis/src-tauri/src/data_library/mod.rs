@@ -2,6 +2,9 @@
 // Provides persistent storage for data sequences using SQLite
 
 pub mod commands;
+pub mod csv_layout;
 pub mod database;
+pub mod derived;
 pub mod models;
+pub mod search;
 pub mod statistics;
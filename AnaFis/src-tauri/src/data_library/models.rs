@@ -1,6 +1,7 @@
 // Data Library Models
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::ops::BitOr;
 
 /// Represents a data sequence stored in the library
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,9 +31,13 @@ pub struct SequenceStatistics {
     pub has_uncertainties: bool,
 }
 
-/// Request to save a new sequence
+/// Request to save a new sequence, or to overwrite the sequence `id`
 #[derive(Debug, Clone, Deserialize)]
 pub struct SaveSequenceRequest {
+    /// Existing sequence to overwrite; its previous state is kept in its
+    /// version history
+    #[serde(default)]
+    pub id: Option<String>,
     pub name: String,
     pub description: String,
     pub tags: Vec<String>,
@@ -46,7 +51,73 @@ pub struct SaveSequenceRequest {
 /// Batch import request for multiple sequences
 #[derive(Debug, Clone, Deserialize)]
 pub struct BatchImportRequest {
+    #[serde(default)]
     pub sequences: Vec<SaveSequenceRequest>,
+    /// CSV file in the `export_sequences_csv` layout, imported after `sequences`
+    #[serde(default)]
+    pub csv_file_path: Option<String>,
+    /// Delimiter of the CSV file (default ',')
+    #[serde(default)]
+    pub delimiter: Option<char>,
+    #[serde(default)]
+    pub on_conflict: ConflictPolicy,
+}
+
+/// What to do when an imported sequence has the name of an existing one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Import under a free name such as "Voltage (2)"
+    #[default]
+    Rename,
+    /// Keep the existing sequence and drop the imported one
+    Skip,
+    /// Delete the existing sequence before importing
+    Replace,
+    /// Report the import as failed
+    Fail,
+}
+
+/// Optional metadata columns of a CSV export; the name is always written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataColumn {
+    Description,
+    Tags,
+    Unit,
+    Source,
+    Pinned,
+    CreatedAt,
+    ModifiedAt,
+}
+
+/// Layout of a CSV export: one row per sequence, one column per field
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CsvExportOptions {
+    pub columns: Vec<MetadataColumn>,
+    pub delimiter: char,
+    /// Tags as one semicolon-joined field instead of a JSON array
+    pub join_tags: bool,
+    /// Write the data and uncertainty values
+    pub include_values: bool,
+}
+
+impl Default for CsvExportOptions {
+    fn default() -> Self {
+        Self {
+            columns: vec![
+                MetadataColumn::Description,
+                MetadataColumn::Tags,
+                MetadataColumn::Unit,
+                MetadataColumn::Source,
+                MetadataColumn::Pinned,
+            ],
+            delimiter: ',',
+            join_tags: true,
+            include_values: true,
+        }
+    }
 }
 
 /// Batch import response with results for each sequence
@@ -55,6 +126,7 @@ pub struct BatchImportResponse {
     pub version: String,
     pub successful_imports: usize,
     pub failed_imports: usize,
+    pub skipped_imports: usize,
     pub errors: Vec<BatchImportError>,
     pub imported_ids: Vec<String>,
 }
@@ -67,6 +139,62 @@ pub struct BatchImportError {
     pub error: String,
 }
 
+/// Earlier state of a sequence, replaced by a save, update or restore
+#[derive(Debug, Clone, Serialize)]
+pub struct SequenceVersion {
+    /// Position in the history, 0 being the most recently replaced state
+    pub version_index: usize,
+    /// When this state was replaced
+    pub replaced_at: DateTime<Utc>,
+    pub sequence: DataSequence,
+}
+
+/// Element-wise comparison of a sequence with a re-measured or edited one
+#[derive(Debug, Clone, Serialize)]
+pub struct SequenceDiffResult {
+    pub first_id: String,
+    pub second_id: String,
+    /// `second[i] - first[i]` over the common prefix
+    pub differences: Vec<f64>,
+    /// Mean of the differences; `None` when there is no common prefix
+    pub mean_bias: Option<f64>,
+    pub rmse: Option<f64>,
+    pub max_abs_change: Option<f64>,
+    pub threshold: f64,
+    /// Indices whose absolute difference exceeds `threshold`
+    pub changed_indices: Vec<usize>,
+    /// Lengths of both sequences when they differ
+    pub length_mismatch: Option<LengthMismatch>,
+    pub metadata: MetadataDiff,
+}
+
+/// Lengths of two sequences of different size
+#[derive(Debug, Clone, Serialize)]
+pub struct LengthMismatch {
+    pub first_len: usize,
+    pub second_len: usize,
+}
+
+/// Metadata differences between two sequences
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MetadataDiff {
+    pub name: Option<FieldChange>,
+    pub description: Option<FieldChange>,
+    pub unit: Option<FieldChange>,
+    pub source: Option<FieldChange>,
+    /// Tags of the second sequence missing from the first
+    pub tags_added: Vec<String>,
+    /// Tags of the first sequence missing from the second
+    pub tags_removed: Vec<String>,
+}
+
+/// Value of a text field in the first and second sequence
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldChange {
+    pub before: String,
+    pub after: String,
+}
+
 /// Request to update an existing sequence
 #[derive(Debug, Clone, Deserialize)]
 pub struct UpdateSequenceRequest {
@@ -133,3 +261,64 @@ pub struct SequenceListResponse {
     pub has_next: bool,
     pub has_prev: bool,
 }
+
+/// Fields searched by `search_sequences`, as a bit set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SearchFields(pub u8);
+
+impl SearchFields {
+    pub const NONE: Self = Self(0);
+    pub const NAME: Self = Self(1);
+    pub const TAGS: Self = Self(1 << 1);
+    pub const DESCRIPTION: Self = Self(1 << 2);
+    /// Each data value, written as in the CSV export
+    pub const VALUES: Self = Self(1 << 3);
+    /// Where the sequence came from, e.g. "Sheet1, A1:A100"
+    pub const SOURCE: Self = Self(1 << 4);
+    pub const ALL: Self = Self(0b1_1111);
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 & Self::ALL.0 == 0
+    }
+}
+
+impl BitOr for SearchFields {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// How a search query matched a field, from weakest to strongest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    Substring,
+    Prefix,
+    Exact,
+}
+
+/// A sequence without its values, as listed by `search_sequences`
+#[derive(Debug, Clone, Serialize)]
+pub struct SequenceSummary {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub unit: String,
+    pub source: String,
+    pub length: usize,
+    pub has_uncertainties: bool,
+    pub is_pinned: bool,
+    pub modified_at: DateTime<Utc>,
+    /// Best match over the searched fields
+    pub match_kind: MatchKind,
+    /// Fields the query matched
+    pub matched_fields: SearchFields,
+}
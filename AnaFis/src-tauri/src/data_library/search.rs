@@ -0,0 +1,247 @@
+//! Free-text search over the sequences of the library
+//!
+//! Queries are case-insensitive and `*` matches any run of characters. Each
+//! searched field is matched exactly, as a prefix or as a substring, and a
+//! sequence ranks by its best match: exact before prefix before substring,
+//! with a match on the name ahead of an equally good match elsewhere.
+
+use super::models::{DataSequence, MatchKind, SearchFields, SequenceSummary};
+use std::cmp::Reverse;
+use std::iter::once;
+
+/// A search query compiled into the wildcard patterns of each match kind
+pub struct SearchPattern {
+    exact: Vec<char>,
+    prefix: Vec<char>,
+    substring: Vec<char>,
+}
+
+impl SearchPattern {
+    pub fn new(query: &str) -> Self {
+        let exact: Vec<char> = query.trim().to_lowercase().chars().collect();
+        let prefix = exact.iter().copied().chain(once('*')).collect();
+        let substring = once('*')
+            .chain(exact.iter().copied())
+            .chain(once('*'))
+            .collect();
+        Self {
+            exact,
+            prefix,
+            substring,
+        }
+    }
+
+    /// Strongest way the query matches `text`, if it does
+    pub fn match_kind(&self, text: &str) -> Option<MatchKind> {
+        let text: Vec<char> = text.to_lowercase().chars().collect();
+        if wildcard_match(&self.exact, &text) {
+            Some(MatchKind::Exact)
+        } else if wildcard_match(&self.prefix, &text) {
+            Some(MatchKind::Prefix)
+        } else if wildcard_match(&self.substring, &text) {
+            Some(MatchKind::Substring)
+        } else {
+            None
+        }
+    }
+}
+
+/// Summaries of the sequences matching `pattern` in any of `fields`, most
+/// relevant first; pinned sequences and then names break ties
+pub fn rank_sequences(
+    sequences: Vec<DataSequence>,
+    pattern: &SearchPattern,
+    fields: SearchFields,
+) -> Vec<SequenceSummary> {
+    let mut ranked: Vec<(bool, SequenceSummary)> = sequences
+        .into_iter()
+        .filter_map(|sequence| {
+            let name_match = fields
+                .contains(SearchFields::NAME)
+                .then(|| pattern.match_kind(&sequence.name))
+                .flatten();
+            let mut matched_fields = if name_match.is_some() {
+                SearchFields::NAME
+            } else {
+                SearchFields::NONE
+            };
+            let mut match_kind = name_match;
+            for (field, kind) in [
+                (SearchFields::TAGS, best_of(pattern, &sequence.tags)),
+                (
+                    SearchFields::DESCRIPTION,
+                    pattern.match_kind(&sequence.description),
+                ),
+                (
+                    SearchFields::VALUES,
+                    best_of(pattern, sequence.data.iter().map(f64::to_string)),
+                ),
+                (SearchFields::SOURCE, pattern.match_kind(&sequence.source)),
+            ] {
+                if let Some(kind) = kind.filter(|_| fields.contains(field)) {
+                    matched_fields = matched_fields | field;
+                    match_kind = match_kind.max(Some(kind));
+                }
+            }
+            let match_kind = match_kind?;
+            Some((
+                name_match == Some(match_kind),
+                summarize(sequence, match_kind, matched_fields),
+            ))
+        })
+        .collect();
+
+    ranked.sort_by_cached_key(|(name_wins, summary)| {
+        (
+            Reverse(summary.match_kind),
+            Reverse(*name_wins),
+            Reverse(summary.is_pinned),
+            summary.name.to_lowercase(),
+        )
+    });
+    ranked.into_iter().map(|(_, summary)| summary).collect()
+}
+
+/// Strongest match of the query over several values of one field
+fn best_of<T: AsRef<str>>(
+    pattern: &SearchPattern,
+    values: impl IntoIterator<Item = T>,
+) -> Option<MatchKind> {
+    values
+        .into_iter()
+        .filter_map(|value| pattern.match_kind(value.as_ref()))
+        .max()
+}
+
+fn summarize(
+    sequence: DataSequence,
+    match_kind: MatchKind,
+    matched_fields: SearchFields,
+) -> SequenceSummary {
+    SequenceSummary {
+        length: sequence.data.len(),
+        has_uncertainties: sequence.uncertainties.is_some(),
+        id: sequence.id,
+        name: sequence.name,
+        description: sequence.description,
+        tags: sequence.tags,
+        unit: sequence.unit,
+        source: sequence.source,
+        is_pinned: sequence.is_pinned,
+        modified_at: sequence.modified_at,
+        match_kind,
+        matched_fields,
+    }
+}
+
+/// Whether `pattern`, in which `*` matches any run of characters, matches
+/// all of `text`
+fn wildcard_match(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and of the text it has consumed up to
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if pattern.get(p) == Some(&'*') {
+            star = Some((p, t));
+            p += 1;
+        } else if pattern.get(p) == Some(&text[t]) {
+            p += 1;
+            t += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the last `*` swallow one more character and retry
+            star = Some((star_p, star_t + 1));
+            p = star_p + 1;
+            t = star_t + 1;
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use crate::data_library::database::DataLibraryDatabase;
+    use crate::data_library::models::SaveSequenceRequest;
+
+    fn save(db: &DataLibraryDatabase, name: &str, tags: &[&str], data: Vec<f64>) {
+        db.save_sequence(&SaveSequenceRequest {
+            id: None,
+            name: name.to_owned(),
+            description: format!("Measured {name}"),
+            tags: tags.iter().map(|&tag| tag.to_owned()).collect(),
+            unit: "V".to_owned(),
+            source: "Sheet1, A1:A3".to_owned(),
+            data,
+            uncertainties: None,
+            is_pinned: false,
+        })
+        .unwrap();
+    }
+
+    fn names(summaries: &[SequenceSummary]) -> Vec<&str> {
+        summaries
+            .iter()
+            .map(|summary| summary.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_wildcard_patterns() {
+        let pattern = SearchPattern::new("V*ge");
+        assert_eq!(pattern.match_kind("voltage"), Some(MatchKind::Exact));
+        assert_eq!(pattern.match_kind("Voltage drop"), Some(MatchKind::Prefix));
+        assert_eq!(
+            pattern.match_kind("Bias voltage"),
+            Some(MatchKind::Substring)
+        );
+        assert_eq!(pattern.match_kind("current"), None);
+        assert_eq!(
+            SearchPattern::new("*").match_kind(""),
+            Some(MatchKind::Exact)
+        );
+        assert_eq!(
+            SearchPattern::new("a*a*a").match_kind("aaa"),
+            Some(MatchKind::Exact)
+        );
+        assert_eq!(SearchPattern::new("a*a*a").match_kind("aa"), None);
+    }
+
+    #[test]
+    fn test_search_ranks_by_relevance() {
+        let db = DataLibraryDatabase::new(":memory:").unwrap();
+        save(&db, "Bias voltage", &[], vec![1.0]);
+        save(&db, "Voltage", &[], vec![2.0]);
+        save(&db, "Current", &["voltage"], vec![3.0]);
+        save(&db, "Voltage drop", &[], vec![4.25]);
+        save(&db, "Temperature", &["lab"], vec![20.5]);
+
+        let everywhere = db.search_sequences("VOLTAGE", SearchFields::ALL).unwrap();
+        assert_eq!(
+            names(&everywhere),
+            ["Voltage", "Current", "Voltage drop", "Bias voltage"]
+        );
+        assert_eq!(everywhere[1].matched_fields, SearchFields::TAGS);
+        assert_eq!(
+            everywhere[0].matched_fields,
+            SearchFields::NAME | SearchFields::DESCRIPTION
+        );
+        assert_eq!(everywhere[3].match_kind, MatchKind::Substring);
+
+        let by_name = db.search_sequences("voltage", SearchFields::NAME).unwrap();
+        assert_eq!(names(&by_name), ["Voltage", "Voltage drop", "Bias voltage"]);
+
+        let by_value = db.search_sequences("4.2*", SearchFields::VALUES).unwrap();
+        assert_eq!(names(&by_value), ["Voltage drop"]);
+        assert_eq!(by_value[0].match_kind, MatchKind::Exact);
+        assert_eq!(by_value[0].length, 1);
+
+        assert!(
+            db.search_sequences("lab", SearchFields::NAME)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
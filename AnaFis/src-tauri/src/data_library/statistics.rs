@@ -1,5 +1,7 @@
 // Statistical calculations for data sequences
-use super::models::{DataSequence, SequenceStatistics};
+use super::models::{
+    DataSequence, FieldChange, LengthMismatch, MetadataDiff, SequenceDiffResult, SequenceStatistics,
+};
 use std::cmp::Ordering;
 
 /// Calculate statistics for a data sequence
@@ -70,3 +72,134 @@ pub fn calculate_statistics(sequence: &DataSequence) -> SequenceStatistics {
         has_uncertainties: sequence.uncertainties.is_some(),
     }
 }
+
+/// Compare `second` with `first` element by element over their common
+/// prefix, along with their metadata
+pub fn diff_sequences(
+    first: &DataSequence,
+    second: &DataSequence,
+    threshold: f64,
+) -> SequenceDiffResult {
+    let differences: Vec<f64> = first
+        .data
+        .iter()
+        .zip(&second.data)
+        .map(|(before, after)| after - before)
+        .collect();
+
+    let (mean_bias, rmse, max_abs_change) = if differences.is_empty() {
+        (None, None, None)
+    } else {
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Count to f64 for difference statistics"
+        )]
+        let count = differences.len() as f64;
+        let sum_sq: f64 = differences.iter().map(|d| d * d).sum();
+        (
+            Some(differences.iter().sum::<f64>() / count),
+            Some((sum_sq / count).sqrt()),
+            Some(differences.iter().fold(0.0_f64, |a, d| a.max(d.abs()))),
+        )
+    };
+
+    let changed_indices = differences
+        .iter()
+        .enumerate()
+        .filter(|(_, d)| d.abs() > threshold)
+        .map(|(index, _)| index)
+        .collect();
+
+    let length_mismatch = (first.data.len() != second.data.len()).then_some(LengthMismatch {
+        first_len: first.data.len(),
+        second_len: second.data.len(),
+    });
+
+    SequenceDiffResult {
+        first_id: first.id.clone(),
+        second_id: second.id.clone(),
+        differences,
+        mean_bias,
+        rmse,
+        max_abs_change,
+        threshold,
+        changed_indices,
+        length_mismatch,
+        metadata: diff_metadata(first, second),
+    }
+}
+
+fn diff_metadata(first: &DataSequence, second: &DataSequence) -> MetadataDiff {
+    let change = |before: &str, after: &str| {
+        (before != after).then(|| FieldChange {
+            before: before.to_owned(),
+            after: after.to_owned(),
+        })
+    };
+    let missing = |from: &[String], other: &[String]| {
+        from.iter()
+            .filter(|tag| !other.contains(tag))
+            .cloned()
+            .collect()
+    };
+    MetadataDiff {
+        name: change(&first.name, &second.name),
+        description: change(&first.description, &second.description),
+        unit: change(&first.unit, &second.unit),
+        source: change(&first.source, &second.source),
+        tags_added: missing(&second.tags, &first.tags),
+        tags_removed: missing(&first.tags, &second.tags),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn sequence(id: &str, name: &str, tags: &[&str], data: Vec<f64>) -> DataSequence {
+        DataSequence {
+            id: id.to_owned(),
+            name: name.to_owned(),
+            description: String::new(),
+            tags: tags.iter().map(|&tag| tag.to_owned()).collect(),
+            unit: "V".to_owned(),
+            source: String::new(),
+            data,
+            uncertainties: None,
+            is_pinned: false,
+            created_at: Utc::now(),
+            modified_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_diff_on_common_prefix_with_metadata() {
+        let first = sequence("a", "Run 1", &["raw", "lab"], vec![1.0, 2.0, 3.0, 4.0]);
+        let second = sequence(
+            "b",
+            "Run 1 remeasured",
+            &["lab", "checked"],
+            vec![1.0, 2.5, 2.0],
+        );
+        let diff = diff_sequences(&first, &second, 0.6);
+
+        assert_eq!(diff.differences, [0.0, 0.5, -1.0]);
+        assert!((diff.mean_bias.unwrap() + 0.5 / 3.0).abs() < 1e-12);
+        assert!((diff.rmse.unwrap() - (1.25_f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert!((diff.max_abs_change.unwrap() - 1.0).abs() < 1e-12);
+        assert_eq!(diff.changed_indices, [2]);
+        let mismatch = diff.length_mismatch.unwrap();
+        assert_eq!((mismatch.first_len, mismatch.second_len), (4, 3));
+
+        let name = diff.metadata.name.unwrap();
+        assert_eq!(name.after, "Run 1 remeasured");
+        assert!(diff.metadata.unit.is_none());
+        assert_eq!(diff.metadata.tags_added, ["checked"]);
+        assert_eq!(diff.metadata.tags_removed, ["raw"]);
+
+        let empty = diff_sequences(&first, &sequence("c", "Run 1", &[], Vec::new()), 0.0);
+        assert!(empty.differences.is_empty() && empty.rmse.is_none());
+    }
+}
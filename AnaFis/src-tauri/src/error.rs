@@ -1,168 +1,335 @@
 // Standardized error handling for Tauri commands
 //
-// This module provides consistent error responses across all backend commands.
-// Error responses include error codes, messages, and optional details for better
-// frontend error handling and user experience.
+// Every command returns `CommandResult<T>`. Its error, `CommandError`, tells
+// the kind of failure apart (bad input, numerical breakdown, file system,
+// database, window) and carries a machine-readable code, so the frontend can
+// localize messages and react to specific failures. Errors reach the frontend
+// as `{ "code": "MATRIX_SINGULAR", "detail": "...", "field": null }`, with a
+// `path` key added for file system errors.
 
-use serde::{Deserialize, Serialize};
-use serde_json::to_string;
-use std::fmt::{Display, Formatter, Result as FmtResult};
+use serde::{Serialize, Serializer};
+use thiserror::Error;
 
 /// API version information
 pub const API_VERSION: &str = "1.0.0";
 
-/// Standardized error codes for different types of failures
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum ErrorCode {
+/// Machine-readable error codes sent to the frontend
+pub mod codes {
     // Generic errors
-    InternalError,
-    InvalidInput,
-    NotFound,
-    PermissionDenied,
-    Timeout,
+    /// Unexpected failure inside the backend
+    pub const INTERNAL_ERROR: &str = "INTERNAL_ERROR";
+    /// Input rejected without a more specific reason
+    pub const INVALID_INPUT: &str = "INVALID_INPUT";
+    /// Requested item does not exist
+    pub const NOT_FOUND: &str = "NOT_FOUND";
+    /// Computation stopped by `cancel_computation`
+    pub const CANCELLED: &str = "CANCELLED";
 
     // File system errors
-    FileNotFound,
-    FileAccessDenied,
-    FileCorrupted,
-    PathValidationFailed,
+    /// File does not exist
+    pub const FILE_NOT_FOUND: &str = "FILE_NOT_FOUND";
+    /// File exists but cannot be read or written
+    pub const FILE_ACCESS_DENIED: &str = "FILE_ACCESS_DENIED";
+    /// File content is damaged or cannot be decoded
+    pub const FILE_CORRUPTED: &str = "FILE_CORRUPTED";
 
     // Database errors
-    DatabaseError,
-    DatabaseConnectionFailed,
-    RecordNotFound,
-    DuplicateRecord,
+    /// Query or transaction failed
+    pub const DATABASE_ERROR: &str = "DATABASE_ERROR";
 
     // Conversion/calculation errors
-    ConversionFailed,
-    InvalidUnit,
-    IncompatibleUnits,
-    CalculationError,
+    /// Unit or value conversion failed
+    pub const CONVERSION_FAILED: &str = "CONVERSION_FAILED";
+    /// Numerical computation failed
+    pub const CALCULATION_ERROR: &str = "CALCULATION_ERROR";
+    /// Linear system or covariance matrix is singular
+    pub const MATRIX_SINGULAR: &str = "MATRIX_SINGULAR";
+    /// Too few observations for the requested analysis
+    pub const INSUFFICIENT_DATA: &str = "INSUFFICIENT_DATA";
+    /// Data has no spread, so scale parameters are undefined
+    pub const ZERO_VARIANCE: &str = "ZERO_VARIANCE";
+    /// Analysis is not available yet
+    pub const NOT_IMPLEMENTED: &str = "NOT_IMPLEMENTED";
+
+    // Formula errors
+    /// Formula uses symbols that were given no value
+    pub const UNKNOWN_SYMBOLS: &str = "UNKNOWN_SYMBOLS";
+    /// Formula cannot be compiled to an evaluator
+    pub const COMPILE_FAILED: &str = "COMPILE_FAILED";
 
     // Import/Export errors
-    ImportFailed,
-    ExportFailed,
-    UnsupportedFormat,
-    ParsingError,
+    /// Reading or parsing an imported file failed
+    pub const IMPORT_FAILED: &str = "IMPORT_FAILED";
+    /// Writing an exported file failed
+    pub const EXPORT_FAILED: &str = "EXPORT_FAILED";
+    /// File format is not supported
+    pub const UNSUPPORTED_FORMAT: &str = "UNSUPPORTED_FORMAT";
+    /// Text or formula cannot be parsed
+    pub const PARSING_ERROR: &str = "PARSING_ERROR";
 
     // Data validation errors
-    ValidationError,
-    MissingRequiredField,
-    InvalidDataType,
+    /// Input or option is invalid
+    pub const VALIDATION_ERROR: &str = "VALIDATION_ERROR";
 
     // Window errors
-    WindowError,
-}
-
-/// Standardized error response structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ErrorResponse {
-    /// API version
-    pub version: String,
-    /// Error code for programmatic handling
-    pub code: ErrorCode,
-    /// Human-readable error message
-    pub message: String,
-    /// Optional detailed information (for debugging)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub details: Option<String>,
-    /// Optional field name if error is related to a specific field
+    /// Window cannot be created, found or changed
+    pub const WINDOW_ERROR: &str = "WINDOW_ERROR";
+}
+
+/// Error returned by Tauri commands
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CommandError {
+    /// An input or option is invalid
+    #[error("{detail}")]
+    Validation {
+        /// Machine-readable code
+        code: &'static str,
+        /// Request field the error refers to
+        field: Option<String>,
+        /// Human-readable description
+        detail: String,
+    },
+    /// A computation failed numerically (singular system, divergence, ...)
+    #[error("{detail}")]
+    Numerical {
+        /// Machine-readable code
+        code: &'static str,
+        /// Human-readable description
+        detail: String,
+    },
+    /// Reading or writing a file failed
+    #[error("{detail}")]
+    Io {
+        /// Machine-readable code
+        code: &'static str,
+        /// File involved, when known
+        path: Option<String>,
+        /// Human-readable description
+        detail: String,
+    },
+    /// A data library database operation failed
+    #[error("{detail}")]
+    Database {
+        /// Machine-readable code
+        code: &'static str,
+        /// Human-readable description
+        detail: String,
+    },
+    /// A window cannot be created, found or changed
+    #[error("{detail}")]
+    Window {
+        /// Machine-readable code
+        code: &'static str,
+        /// Human-readable description
+        detail: String,
+    },
+    /// Unexpected failure inside the backend
+    #[error("{detail}")]
+    Internal {
+        /// Machine-readable code
+        code: &'static str,
+        /// Human-readable description
+        detail: String,
+    },
+}
+
+impl CommandError {
+    /// Machine-readable code of the error
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::Validation { code, .. }
+            | Self::Numerical { code, .. }
+            | Self::Io { code, .. }
+            | Self::Database { code, .. }
+            | Self::Window { code, .. }
+            | Self::Internal { code, .. } => code,
+        }
+    }
+
+    /// Human-readable description of the error
+    #[must_use]
+    pub fn detail(&self) -> &str {
+        match self {
+            Self::Validation { detail, .. }
+            | Self::Numerical { detail, .. }
+            | Self::Io { detail, .. }
+            | Self::Database { detail, .. }
+            | Self::Window { detail, .. }
+            | Self::Internal { detail, .. } => detail,
+        }
+    }
+
+    /// Request field a validation error refers to
+    #[must_use]
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            Self::Validation { field, .. } => field.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// File a file system error refers to
+    #[must_use]
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            Self::Io { path, .. } => path.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Prefix the detail with `context`, keeping code, field and path
+    #[must_use]
+    pub fn context(mut self, context: &str) -> Self {
+        let (Self::Validation { detail, .. }
+        | Self::Numerical { detail, .. }
+        | Self::Io { detail, .. }
+        | Self::Database { detail, .. }
+        | Self::Window { detail, .. }
+        | Self::Internal { detail, .. }) = &mut self;
+        *detail = format!("{context}: {detail}");
+        self
+    }
+}
+
+/// Shape of a `CommandError` on the wire
+#[derive(Serialize)]
+struct WireError<'err> {
+    code: &'static str,
+    detail: &'err str,
+    field: Option<&'err str>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub field: Option<String>,
+    path: Option<&'err str>,
 }
 
-impl Display for ErrorResponse {
-    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        write!(
-            f,
-            "{}: {}",
-            to_string(&self.code).unwrap_or_else(|_| "UNKNOWN_ERROR".to_owned()),
-            self.message
-        )
+impl Serialize for CommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        WireError {
+            code: self.code(),
+            detail: self.detail(),
+            field: self.field(),
+            path: self.path(),
+        }
+        .serialize(serializer)
     }
 }
 
 /// Type alias for command results using standardized errors
-pub type CommandResult<T> = Result<T, ErrorResponse>;
-
-pub fn file_not_found(path: impl Into<String>) -> ErrorResponse {
-    ErrorResponse {
-        version: API_VERSION.to_owned(),
-        code: ErrorCode::FileNotFound,
-        message: format!("File not found: {}", path.into()),
-        details: None,
-        field: None,
+pub type CommandResult<T> = Result<T, CommandError>;
+
+pub fn file_not_found(path: impl Into<String>) -> CommandError {
+    let path = path.into();
+    CommandError::Io {
+        code: codes::FILE_NOT_FOUND,
+        detail: format!("File not found: {path}"),
+        path: Some(path),
     }
 }
 
-pub fn validation_error(message: impl Into<String>, field: Option<String>) -> ErrorResponse {
-    ErrorResponse {
-        version: API_VERSION.to_owned(),
-        code: ErrorCode::ValidationError,
-        message: message.into(),
-        details: None,
+pub fn io_error(path: Option<String>, message: impl Into<String>) -> CommandError {
+    CommandError::Io {
+        code: codes::FILE_ACCESS_DENIED,
+        path,
+        detail: message.into(),
+    }
+}
+
+pub fn validation_error(message: impl Into<String>, field: Option<String>) -> CommandError {
+    CommandError::Validation {
+        code: codes::VALIDATION_ERROR,
         field,
+        detail: message.into(),
     }
 }
 
-pub fn internal_error(message: impl Into<String>) -> ErrorResponse {
-    ErrorResponse {
-        version: API_VERSION.to_owned(),
-        code: ErrorCode::InternalError,
-        message: message.into(),
-        details: None,
-        field: None,
+pub fn numerical_error(message: impl Into<String>) -> CommandError {
+    CommandError::Numerical {
+        code: codes::CALCULATION_ERROR,
+        detail: message.into(),
     }
 }
 
-pub fn database_error(message: impl Into<String>) -> ErrorResponse {
-    ErrorResponse {
-        version: API_VERSION.to_owned(),
-        code: ErrorCode::DatabaseError,
-        message: message.into(),
-        details: None,
-        field: None,
+pub fn internal_error(message: impl Into<String>) -> CommandError {
+    CommandError::Internal {
+        code: codes::INTERNAL_ERROR,
+        detail: message.into(),
     }
 }
 
-pub fn conversion_error(message: impl Into<String>) -> ErrorResponse {
-    ErrorResponse {
-        version: API_VERSION.to_owned(),
-        code: ErrorCode::ConversionFailed,
-        message: message.into(),
-        details: None,
-        field: None,
+pub fn database_error(message: impl Into<String>) -> CommandError {
+    CommandError::Database {
+        code: codes::DATABASE_ERROR,
+        detail: message.into(),
     }
 }
 
-pub fn import_error(message: impl Into<String>) -> ErrorResponse {
-    ErrorResponse {
-        version: API_VERSION.to_owned(),
-        code: ErrorCode::ImportFailed,
-        message: message.into(),
-        details: None,
+pub fn conversion_error(message: impl Into<String>) -> CommandError {
+    CommandError::Validation {
+        code: codes::CONVERSION_FAILED,
         field: None,
+        detail: message.into(),
     }
 }
 
-pub fn export_error(message: impl Into<String>) -> ErrorResponse {
-    ErrorResponse {
-        version: API_VERSION.to_owned(),
-        code: ErrorCode::ExportFailed,
-        message: message.into(),
-        details: None,
-        field: None,
+pub fn import_error(message: impl Into<String>) -> CommandError {
+    CommandError::Io {
+        code: codes::IMPORT_FAILED,
+        path: None,
+        detail: message.into(),
     }
 }
 
-pub fn window_error(message: impl Into<String>) -> ErrorResponse {
-    ErrorResponse {
-        version: API_VERSION.to_owned(),
-        code: ErrorCode::WindowError,
-        message: message.into(),
-        details: None,
-        field: None,
+pub fn export_error(message: impl Into<String>) -> CommandError {
+    CommandError::Io {
+        code: codes::EXPORT_FAILED,
+        path: None,
+        detail: message.into(),
+    }
+}
+
+pub fn window_error(message: impl Into<String>) -> CommandError {
+    CommandError::Window {
+        code: codes::WINDOW_ERROR,
+        detail: message.into(),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use serde_json::{json, to_value};
+
+    #[test]
+    fn test_errors_serialize_code_detail_and_field() {
+        let singular = CommandError::Numerical {
+            code: codes::MATRIX_SINGULAR,
+            detail: "Normal matrix is singular".to_owned(),
+        };
+        assert_eq!(
+            to_value(&singular).unwrap(),
+            json!({ "code": "MATRIX_SINGULAR", "detail": "Normal matrix is singular", "field": null })
+        );
+        assert_eq!(singular.to_string(), "Normal matrix is singular");
+
+        let invalid = validation_error("Alpha must be in (0, 1)", Some("alpha".to_owned()));
+        assert_eq!(
+            to_value(&invalid).unwrap(),
+            json!({ "code": "VALIDATION_ERROR", "detail": "Alpha must be in (0, 1)", "field": "alpha" })
+        );
+
+        assert_eq!(
+            to_value(file_not_found("/tmp/data.csv")).unwrap(),
+            json!({
+                "code": "FILE_NOT_FOUND",
+                "detail": "File not found: /tmp/data.csv",
+                "field": null,
+                "path": "/tmp/data.csv"
+            })
+        );
+
+        let sheet = export_error("File already exists").context("Sheet 'a'");
+        assert_eq!(sheet.code(), codes::EXPORT_FAILED);
+        assert_eq!(sheet.detail(), "Sheet 'a': File already exists");
     }
 }
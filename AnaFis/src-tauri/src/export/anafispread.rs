@@ -5,42 +5,116 @@
 // protection rules, resources, merged cells, and all Univer-specific data.
 //
 // This is the LOSSLESS native format for complete save/restore cycles.
-// Format: Magic bytes + Version + Compressed (gzip) JSON of full IWorkbookData
+// Format: Magic bytes + Version + Envelope header + Compressed JSON document
 //
-// File Structure:
+// File Structure (version 2):
 // - Bytes 0-7:   Magic number "ANAFIS\x01\x00" (identifies file type)
-// - Bytes 8-11:  Format version (u32, little-endian, currently 1)
-// - Bytes 12+:   Gzip-compressed JSON data
+// - Bytes 8-11:  Format version (u32, little-endian, currently 2)
+// - Bytes 12-15: Header length H (u32, little-endian)
+// - Bytes 16..16+H: JSON envelope header (versions, compression, SHA-256 checksums)
+// - Remaining:   Gzip- or zstd-compressed JSON document
+//
+// Version 1 files have no header: the gzip data follows the version directly.
 
+use crate::error::{CommandResult, export_error, io_error};
 use chrono::Utc;
 use flate2::Compression;
 use flate2::write::GzEncoder;
-use serde_json::{Value, json, to_writer};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json, to_vec};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
 use std::fs::File;
 use std::io::{BufWriter, Write};
 
 /// Magic number for .anafispread files: "ANAFIS" + version marker
-const MAGIC_NUMBER: &[u8; 8] = b"ANAFIS\x01\x00";
-const FORMAT_VERSION: u32 = 1;
+pub const MAGIC_NUMBER: &[u8; 8] = b"ANAFIS\x01\x00";
+/// Version written by this build; version 1 predates the envelope header
+pub const FORMAT_VERSION: u32 = 2;
+/// Payload bytes covered by each chunk checksum
+pub const CHECKSUM_CHUNK_SIZE: usize = 64 * 1024;
+/// zstd level balancing speed and size for interactive saves
+const ZSTD_LEVEL: i32 = 3;
+
+/// Compression of the .anafispread payload
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PayloadCompression {
+    /// gzip, readable by every `AnaFis` version
+    #[default]
+    Gzip,
+    /// zstd, faster and smaller for large workbooks
+    Zstd,
+}
+
+/// Envelope header stored between the version and the payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvelopeHeader {
+    /// Version of the document inside the payload
+    pub format_version: u32,
+    /// Version of the `AnaFis` build that wrote the file
+    pub app_version: String,
+    /// RFC 3339 time of writing
+    pub created_at: String,
+    /// Compression of the payload
+    pub compression: PayloadCompression,
+    /// Length of the compressed payload in bytes
+    pub payload_length: u64,
+    /// Hex SHA-256 of the compressed payload
+    pub checksum: String,
+    /// Hex SHA-256 of each `CHECKSUM_CHUNK_SIZE` chunk, to locate corruption
+    pub chunk_checksums: Vec<String>,
+}
 
 /// Export data to `AnaFis` Spreadsheet (.anafispread) format
 ///
 /// This format accepts the full `IWorkbookData` JSON snapshot from Univer's `workbook.save()`
-/// and stores it with compression for complete lossless save/restore.
+/// and stores it compressed (gzip unless `compression` asks for zstd) behind a
+/// checksummed envelope header.
 #[tauri::command]
 #[allow(
     clippy::needless_pass_by_value,
     reason = "Tauri commands require owned types for arguments"
 )]
-pub fn export_anafispread(data: Value, file_path: String) -> Result<(), String> {
-    // For .anafispread, we expect the IWorkbookData snapshot directly
-    let workbook_data = &data;
+pub fn export_anafispread(
+    data: Value,
+    file_path: String,
+    compression: Option<PayloadCompression>,
+) -> CommandResult<()> {
+    let bytes = encode_anafispread(&data, compression.unwrap_or_default()).map_err(export_error)?;
+
+    let file = File::create(&file_path).map_err(|e| {
+        io_error(
+            Some(file_path.clone()),
+            format!("Failed to create file: {e}"),
+        )
+    })?;
+    let mut writer = BufWriter::new(file);
+    writer.write_all(&bytes).map_err(|e| {
+        io_error(
+            Some(file_path.clone()),
+            format!("Failed to write AnaFis Spreadsheet file: {e}"),
+        )
+    })?;
+    writer.flush().map_err(|e| {
+        io_error(
+            Some(file_path.clone()),
+            format!("Failed to flush writer: {e}"),
+        )
+    })?;
 
-    // Create comprehensive export structure with metadata
-    let export_data = json!({
-        "version": "1.0",
+    Ok(())
+}
+
+/// Complete .anafispread file contents for a workbook snapshot
+pub fn encode_anafispread(
+    workbook_data: &Value,
+    compression: PayloadCompression,
+) -> Result<Vec<u8>, String> {
+    // The document keeps its descriptive metadata; versions and compression
+    // live in the envelope header.
+    let document = json!({
         "format": "anafis_spreadsheet",
-        "compressed": true,
         "metadata": {
             "created": Utc::now().to_rfc3339(),
             "creator": "AnaFis",
@@ -48,23 +122,51 @@ pub fn export_anafispread(data: Value, file_path: String) -> Result<(), String>
         },
         "workbook": workbook_data
     });
+    let json_bytes = to_vec(&document).map_err(|e| format!("Failed to serialize workbook: {e}"))?;
 
-    // Write to file with gzip compression (always compressed for .anafispread)
-    let mut file = File::create(&file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+    let payload = match compression {
+        PayloadCompression::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder
+                .write_all(&json_bytes)
+                .and_then(|()| encoder.finish())
+                .map_err(|e| format!("Failed to compress workbook: {e}"))?
+        }
+        PayloadCompression::Zstd => zstd::encode_all(json_bytes.as_slice(), ZSTD_LEVEL)
+            .map_err(|e| format!("Failed to compress workbook: {e}"))?,
+    };
 
-    // Write magic number to identify file type
-    file.write_all(MAGIC_NUMBER)
-        .map_err(|e| format!("Failed to write magic number: {e}"))?;
+    let header = EnvelopeHeader {
+        format_version: FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_owned(),
+        created_at: Utc::now().to_rfc3339(),
+        compression,
+        payload_length: payload.len() as u64,
+        checksum: sha256_hex(&payload),
+        chunk_checksums: payload
+            .chunks(CHECKSUM_CHUNK_SIZE)
+            .map(sha256_hex)
+            .collect(),
+    };
+    let header_bytes = to_vec(&header).map_err(|e| format!("Failed to serialize header: {e}"))?;
+    let header_length = u32::try_from(header_bytes.len())
+        .map_err(|e| format!("Envelope header is too large: {e}"))?;
 
-    // Write format version (u32, little-endian)
-    file.write_all(&FORMAT_VERSION.to_le_bytes())
-        .map_err(|e| format!("Failed to write version: {e}"))?;
-
-    // Now write the compressed JSON data
-    let encoder = GzEncoder::new(file, Compression::default());
-    let writer = BufWriter::new(encoder);
-    to_writer(writer, &export_data)
-        .map_err(|e| format!("Failed to write AnaFis Spreadsheet file: {e}"))?;
+    let mut bytes = Vec::with_capacity(16 + header_bytes.len() + payload.len());
+    bytes.extend_from_slice(MAGIC_NUMBER);
+    bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&header_length.to_le_bytes());
+    bytes.extend_from_slice(&header_bytes);
+    bytes.extend_from_slice(&payload);
+    Ok(bytes)
+}
 
-    Ok(())
+/// Lowercase hex SHA-256 digest
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            write!(hex, "{byte:02x}").expect("String writing never fails");
+            hex
+        })
 }
@@ -0,0 +1,309 @@
+// Apache Arrow IPC (Feather v2) export
+//
+// Writes one sheet as an Arrow IPC file, the format read by
+// `polars.read_ipc()` and `pandas.read_feather()`. Each column is typed from
+// its first non-empty cell: integers as Int64, other numbers as Float64,
+// booleans as Boolean and anything else as Utf8. A column whose later cells
+// do not fit that type is widened (Int64 to Float64 for fractional numbers,
+// otherwise Utf8). Empty cells are written as nulls.
+
+use super::{ExportConfig, cell_text, sheets_from_data};
+use crate::error::{CommandResult, export_error, validation_error};
+use arrow::array::{ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+use serde_json::Value;
+use std::fs::File;
+use std::io::BufWriter;
+use std::sync::Arc;
+
+/// Rows per record batch
+const BATCH_ROWS: usize = 64 * 1024;
+
+/// Arrow type of an exported column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnType {
+    Int64,
+    Float64,
+    Boolean,
+    Utf8,
+}
+
+impl ColumnType {
+    /// Type of a single cell; `None` for empty cells
+    fn of(cell: &Value) -> Option<Self> {
+        match cell {
+            Value::Null => None,
+            Value::String(text) if text.trim().is_empty() => None,
+            Value::Number(number) if number.is_i64() => Some(Self::Int64),
+            Value::Number(_) => Some(Self::Float64),
+            Value::Bool(_) => Some(Self::Boolean),
+            _ => Some(Self::Utf8),
+        }
+    }
+
+    /// Smallest type holding both `self` and a cell of type `other`
+    fn widen(self, other: Self) -> Self {
+        match (self, other) {
+            (current, next) if current == next => current,
+            (Self::Int64, Self::Float64) | (Self::Float64, Self::Int64) => Self::Float64,
+            _ => Self::Utf8,
+        }
+    }
+
+    const fn data_type(self) -> DataType {
+        match self {
+            Self::Int64 => DataType::Int64,
+            Self::Float64 => DataType::Float64,
+            Self::Boolean => DataType::Boolean,
+            Self::Utf8 => DataType::Utf8,
+        }
+    }
+}
+
+/// Export data to an Arrow IPC (Feather v2) file (2D array or a single sheet object)
+#[tauri::command]
+#[allow(
+    clippy::needless_pass_by_value,
+    reason = "Tauri commands require owned types for arguments"
+)]
+pub fn export_to_arrow_ipc(
+    data: Vec<Value>,
+    file_path: String,
+    config: ExportConfig,
+) -> CommandResult<()> {
+    let sheets =
+        sheets_from_data(&data).map_err(|e| validation_error(e, Some("data".to_owned())))?;
+    let [sheet] = sheets.as_slice() else {
+        return Err(validation_error(
+            "An Arrow IPC file holds a single table; export one sheet at a time",
+            Some("data".to_owned()),
+        ));
+    };
+    let (header, body) = if config.options.include_headers {
+        sheet
+            .rows
+            .split_first()
+            .map_or((None, sheet.rows.as_slice()), |(first, rest)| {
+                (Some(first.as_slice()), rest)
+            })
+    } else {
+        (None, sheet.rows.as_slice())
+    };
+    let width = sheet.rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    if width == 0 {
+        return Err(validation_error(
+            "No data to export",
+            Some("data".to_owned()),
+        ));
+    }
+
+    let types: Vec<ColumnType> = (0..width).map(|index| column_type(body, index)).collect();
+    let fields: Vec<Field> = column_names(header, width)
+        .into_iter()
+        .zip(&types)
+        .map(|(name, column_type)| Field::new(name, column_type.data_type(), true))
+        .collect();
+    let schema = Arc::new(Schema::new(fields));
+
+    let file = File::create(&file_path)
+        .map_err(|e| export_error(format!("Failed to create file: {e}")))?;
+    let mut writer = FileWriter::try_new(BufWriter::new(file), &schema)
+        .map_err(|e| export_error(format!("Failed to create Arrow IPC writer: {e}")))?;
+    for rows in body.chunks(BATCH_ROWS) {
+        let columns = types
+            .iter()
+            .enumerate()
+            .map(|(index, &column_type)| column_array(rows, index, column_type))
+            .collect();
+        let batch = RecordBatch::try_new(Arc::clone(&schema), columns)
+            .map_err(|e| export_error(format!("Failed to create RecordBatch: {e}")))?;
+        writer
+            .write(&batch)
+            .map_err(|e| export_error(format!("Failed to write RecordBatch: {e}")))?;
+    }
+    writer
+        .finish()
+        .map_err(|e| export_error(format!("Failed to finish Arrow IPC file: {e}")))?;
+
+    Ok(())
+}
+
+/// Type of column `index`: that of its first non-empty cell, widened to fit
+/// the cells after it; Utf8 for columns without values
+fn column_type(body: &[&Vec<Value>], index: usize) -> ColumnType {
+    body.iter()
+        .filter_map(|row| row.get(index).and_then(ColumnType::of))
+        .reduce(ColumnType::widen)
+        .unwrap_or(ColumnType::Utf8)
+}
+
+/// Unique column names, from the header row where it has text
+fn column_names(header: Option<&[Value]>, width: usize) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(width);
+    for index in 0..width {
+        let base = header
+            .and_then(|cells| cells.get(index))
+            .map(|cell| cell_text(cell).trim().to_owned())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| format!("column_{}", index + 1));
+        // pandas and polars reject duplicate column names
+        let mut name = base.clone();
+        let mut copy = 1;
+        while names.contains(&name) {
+            copy += 1;
+            name = format!("{base}_{copy}");
+        }
+        names.push(name);
+    }
+    names
+}
+
+/// Arrow array of column `index` over `rows`
+fn column_array(rows: &[&Vec<Value>], index: usize, column_type: ColumnType) -> ArrayRef {
+    let cells = rows
+        .iter()
+        .map(|row| row.get(index).filter(|cell| ColumnType::of(cell).is_some()));
+    match column_type {
+        ColumnType::Int64 => Arc::new(
+            cells
+                .map(|cell| cell.and_then(Value::as_i64))
+                .collect::<Int64Array>(),
+        ),
+        ColumnType::Float64 => Arc::new(
+            cells
+                .map(|cell| cell.and_then(Value::as_f64))
+                .collect::<Float64Array>(),
+        ),
+        ColumnType::Boolean => Arc::new(
+            cells
+                .map(|cell| cell.and_then(Value::as_bool))
+                .collect::<BooleanArray>(),
+        ),
+        ColumnType::Utf8 => Arc::new(
+            cells
+                .map(|cell| cell.map(cell_text))
+                .collect::<StringArray>(),
+        ),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use crate::export::{ExportFormat, ExportOptions};
+    use arrow::array::Array;
+    use arrow::ipc::reader::FileReader;
+    use serde_json::json;
+    use std::env::temp_dir;
+    use std::fs::remove_file;
+
+    fn config(include_headers: bool) -> ExportConfig {
+        ExportConfig {
+            range: "custom".to_owned(),
+            format: ExportFormat::ArrowIpc,
+            options: ExportOptions {
+                include_headers,
+                ..ExportOptions::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_column_types_from_first_value() {
+        let rows = [
+            json!([1, 1.5, "a", true, null, 2]),
+            json!([null, 2, "", false, " ", 2.5]),
+            json!([3, null, 4, null]),
+            json!([-7]),
+        ];
+        let body: Vec<&Vec<Value>> = rows.iter().map(|row| row.as_array().unwrap()).collect();
+        let types: Vec<ColumnType> = (0..6).map(|index| column_type(&body, index)).collect();
+        assert_eq!(
+            types,
+            [
+                ColumnType::Int64,
+                ColumnType::Float64,
+                ColumnType::Utf8,
+                ColumnType::Boolean,
+                ColumnType::Utf8,
+                ColumnType::Float64,
+            ]
+        );
+        assert_eq!(
+            ColumnType::Boolean.widen(ColumnType::Int64),
+            ColumnType::Utf8
+        );
+
+        let names = column_names(Some(&[json!("x"), json!(""), json!("x")]), 4);
+        assert_eq!(names, ["x", "column_2", "x_2", "column_4"]);
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let path = temp_dir().join(format!("anafis-arrow-test-{}.arrow", std::process::id()));
+        let data = vec![
+            json!(["n", "t (s)", "label", "ok"]),
+            json!([1, 0.5, "start", true]),
+            json!([2, 1, 3, null]),
+            json!([null, 1.5, "", false]),
+        ];
+        export_to_arrow_ipc(data, path.to_string_lossy().into_owned(), config(true)).unwrap();
+
+        let reader = FileReader::try_new(File::open(&path).unwrap(), None).unwrap();
+        let schema = reader.schema();
+        let fields: Vec<(&str, &DataType)> = schema
+            .fields()
+            .iter()
+            .map(|field| (field.name().as_str(), field.data_type()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("n", &DataType::Int64),
+                ("t (s)", &DataType::Float64),
+                ("label", &DataType::Utf8),
+                ("ok", &DataType::Boolean),
+            ]
+        );
+        let batches: Vec<RecordBatch> = reader.map(Result::unwrap).collect();
+        remove_file(&path).unwrap();
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 3);
+
+        let counts = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(counts.iter().collect::<Vec<_>>(), [Some(1), Some(2), None]);
+        let times = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(times.values().to_vec(), vec![0.5, 1.0, 1.5]);
+        let labels = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(
+            labels.iter().collect::<Vec<_>>(),
+            [Some("start"), Some("3"), None]
+        );
+        assert_eq!(batch.column(3).null_count(), 1);
+    }
+
+    #[test]
+    fn test_rejects_several_sheets_and_empty_data() {
+        let path = temp_dir().join(format!("anafis-arrow-test-{}-x.arrow", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        let sheets = vec![json!({ "rows": [[1]] }), json!({ "rows": [[2]] })];
+        assert!(export_to_arrow_ipc(sheets, path.clone(), config(false)).is_err());
+        assert!(export_to_arrow_ipc(vec![json!([])], path, config(false)).is_err());
+    }
+}
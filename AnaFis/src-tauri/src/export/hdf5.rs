@@ -0,0 +1,344 @@
+// HDF5 export
+//
+// Writes every sheet as datasets in the root group of a new HDF5 file. The
+// numeric columns of a sheet form one 2D float64 dataset (rows x columns)
+// named after the sheet, with empty cells stored as NaN. The remaining
+// columns form a 2D variable-length UTF-8 string dataset named
+// `<sheet>_text`. Each dataset carries `sheet_name`, `column_names` and
+// `exported_at` (RFC 3339) attributes.
+
+use super::{ExportConfig, Sheet, cell_text, is_numeric_column, numeric_text, sheets_from_data};
+use crate::error::{CommandResult, export_error, validation_error};
+use chrono::Utc;
+use hdf5::types::VarLenUnicode;
+use hdf5::{Dataset, File};
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Suffix of the dataset holding a sheet's non-numeric columns
+const TEXT_SUFFIX: &str = "_text";
+
+/// Export data to HDF5 format (2D array or sheet objects)
+#[tauri::command]
+#[allow(
+    clippy::needless_pass_by_value,
+    reason = "Tauri commands require owned types for arguments"
+)]
+pub fn export_to_hdf5(
+    data: Vec<Value>,
+    file_path: String,
+    config: ExportConfig,
+) -> CommandResult<()> {
+    let sheets =
+        sheets_from_data(&data).map_err(|e| validation_error(e, Some("data".to_owned())))?;
+    let names = dataset_names(&sheets);
+    let exported_at = Utc::now().to_rfc3339();
+
+    let file = File::create(&file_path)
+        .map_err(|e| export_error(format!("Failed to create HDF5 file: {e}")))?;
+    for (sheet, name) in sheets.iter().zip(&names) {
+        let table = SheetTable::new(&sheet.rows, config.options.include_headers);
+        let sheet_name = sheet.name.as_deref().unwrap_or(name);
+        write_sheet(&file, &table, name, sheet_name, &exported_at)
+            .map_err(|e| export_error(format!("Failed to write sheet '{sheet_name}': {e}")))?;
+    }
+    file.flush()
+        .map_err(|e| export_error(format!("Failed to flush HDF5 file: {e}")))?;
+
+    Ok(())
+}
+
+/// Rows of a sheet with its columns split into numeric and text columns
+struct SheetTable<'rows, 'data> {
+    /// Column names, from the header row where it has text
+    names: Vec<String>,
+    /// Data rows (the header row excluded)
+    body: &'rows [&'data Vec<Value>],
+    /// Indices of columns whose non-empty cells are all numbers
+    numeric: Vec<usize>,
+    /// Indices of all other columns
+    text: Vec<usize>,
+}
+
+impl<'rows, 'data> SheetTable<'rows, 'data> {
+    fn new(rows: &'rows [&'data Vec<Value>], include_headers: bool) -> Self {
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let (header, body) = if include_headers {
+            rows.split_first()
+                .map_or((None, rows), |(first, rest)| (Some(first), rest))
+        } else {
+            (None, rows)
+        };
+        let names = (0..width)
+            .map(|index| {
+                header
+                    .and_then(|cells| cells.get(index))
+                    .map(cell_text)
+                    .map(|text| text.trim().to_owned())
+                    .filter(|text| !text.is_empty())
+                    .unwrap_or_else(|| format!("column{}", index + 1))
+            })
+            .collect();
+        let (numeric, text) = (0..width).partition(|&index| is_numeric_column(body, index));
+
+        Self {
+            names,
+            body,
+            numeric,
+            text,
+        }
+    }
+
+    /// Names of the columns at `indices`
+    fn names_of(&self, indices: &[usize]) -> Vec<String> {
+        indices
+            .iter()
+            .map(|&index| self.names[index].clone())
+            .collect()
+    }
+
+    /// Numeric columns in row-major order; empty cells become NaN
+    fn numeric_values(&self) -> Vec<f64> {
+        self.body
+            .iter()
+            .flat_map(|row| {
+                self.numeric.iter().map(|&index| {
+                    row.get(index)
+                        .and_then(numeric_text)
+                        .and_then(|text| text.parse().ok())
+                        .unwrap_or(f64::NAN)
+                })
+            })
+            .collect()
+    }
+
+    /// Text columns in row-major order; missing cells become empty strings
+    fn text_values(&self) -> Vec<String> {
+        self.body
+            .iter()
+            .flat_map(|row| {
+                self.text
+                    .iter()
+                    .map(|&index| row.get(index).map(cell_text).unwrap_or_default())
+            })
+            .collect()
+    }
+}
+
+/// Unique dataset name for each sheet. HDF5 link names cannot contain '/'
+/// and '.' names the current group, so those are replaced; names are also
+/// kept clear of other sheets' `_text` datasets.
+fn dataset_names(sheets: &[Sheet<'_>]) -> Vec<String> {
+    let mut used: HashSet<String> = HashSet::new();
+    sheets
+        .iter()
+        .enumerate()
+        .map(|(index, sheet)| {
+            let base = sheet
+                .name
+                .as_deref()
+                .map(|name| name.trim().replace('/', "_"))
+                .filter(|name| !name.is_empty() && name != ".")
+                .unwrap_or_else(|| format!("Sheet {}", index + 1));
+            let mut name = base.clone();
+            let mut copy = 1;
+            while used.contains(&name) || used.contains(&format!("{name}{TEXT_SUFFIX}")) {
+                copy += 1;
+                name = format!("{base}_{copy}");
+            }
+            used.insert(format!("{name}{TEXT_SUFFIX}"));
+            used.insert(name.clone());
+            name
+        })
+        .collect()
+}
+
+/// Write the datasets of one sheet. Sheets without text columns, empty ones
+/// included, always get the numeric dataset so every sheet appears.
+fn write_sheet(
+    file: &File,
+    table: &SheetTable<'_, '_>,
+    name: &str,
+    sheet_name: &str,
+    exported_at: &str,
+) -> hdf5::Result<()> {
+    let rows = table.body.len();
+
+    if !table.numeric.is_empty() || table.text.is_empty() {
+        let dataset = file
+            .new_dataset::<f64>()
+            .shape((rows, table.numeric.len()))
+            .create(name)?;
+        dataset.write_raw(&table.numeric_values())?;
+        write_attributes(
+            &dataset,
+            sheet_name,
+            &table.names_of(&table.numeric),
+            exported_at,
+        )?;
+    }
+
+    if !table.text.is_empty() {
+        let values = table
+            .text_values()
+            .iter()
+            .map(|text| unicode(text))
+            .collect::<hdf5::Result<Vec<_>>>()?;
+        let dataset = file
+            .new_dataset::<VarLenUnicode>()
+            .shape((rows, table.text.len()))
+            .create(format!("{name}{TEXT_SUFFIX}").as_str())?;
+        dataset.write_raw(&values)?;
+        write_attributes(
+            &dataset,
+            sheet_name,
+            &table.names_of(&table.text),
+            exported_at,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Attach the sheet name, column names and export time to a dataset
+fn write_attributes(
+    dataset: &Dataset,
+    sheet_name: &str,
+    column_names: &[String],
+    exported_at: &str,
+) -> hdf5::Result<()> {
+    dataset
+        .new_attr::<VarLenUnicode>()
+        .shape(())
+        .create("sheet_name")?
+        .write_scalar(&unicode(sheet_name)?)?;
+
+    let names = column_names
+        .iter()
+        .map(|name| unicode(name))
+        .collect::<hdf5::Result<Vec<_>>>()?;
+    dataset
+        .new_attr::<VarLenUnicode>()
+        .shape(names.len())
+        .create("column_names")?
+        .write_raw(&names)?;
+
+    dataset
+        .new_attr::<VarLenUnicode>()
+        .shape(())
+        .create("exported_at")?
+        .write_scalar(&unicode(exported_at)?)
+}
+
+/// HDF5 variable-length string; NUL characters cannot be stored and are dropped
+fn unicode(text: &str) -> hdf5::Result<VarLenUnicode> {
+    text.replace('\0', "")
+        .parse()
+        .map_err(|e| hdf5::Error::from(format!("Invalid string '{text}': {e}")))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use crate::export::{ExportFormat, ExportOptions};
+    use serde_json::json;
+    use std::env::temp_dir;
+    use std::fs::remove_file;
+
+    fn sheet(name: Option<&str>) -> Sheet<'static> {
+        Sheet {
+            name: name.map(str::to_owned),
+            rows: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_columns_split_by_type() {
+        let rows = [
+            json!(["t (s)", "label", ""]).as_array().unwrap().clone(),
+            json!([0.5, "start", "1e3"]).as_array().unwrap().clone(),
+            json!([null, 2, " 4 "]).as_array().unwrap().clone(),
+            json!(["1.5"]).as_array().unwrap().clone(),
+        ];
+        let refs: Vec<&Vec<Value>> = rows.iter().collect();
+        let table = SheetTable::new(&refs, true);
+
+        assert_eq!(table.names, ["t (s)", "label", "column3"]);
+        assert_eq!(table.numeric, [0, 2]);
+        assert_eq!(table.text, [1]);
+        let values = table.numeric_values();
+        assert_eq!(values.len(), 6);
+        assert!(values[2].is_nan());
+        assert_eq!(values[..2].to_vec(), vec![0.5, 1000.0]);
+        assert_eq!(values[3..5].to_vec(), vec![4.0, 1.5]);
+        assert!(values[5].is_nan());
+        assert_eq!(table.text_values(), ["start", "2", ""]);
+    }
+
+    #[test]
+    fn test_dataset_names_are_unique_and_valid() {
+        let sheets = [
+            sheet(Some("Run 1/2")),
+            sheet(None),
+            sheet(Some("a")),
+            sheet(Some("a_text")),
+            sheet(Some("a")),
+            sheet(Some(".")),
+        ];
+        assert_eq!(
+            dataset_names(&sheets),
+            ["Run 1_2", "Sheet 2", "a", "a_text_2", "a_2", "Sheet 6"]
+        );
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let path = temp_dir().join(format!("anafis-hdf5-test-{}.h5", std::process::id()));
+        let data = vec![json!({
+            "name": "Decay",
+            "rows": [["t", "N", "note"], [0, 100, "start"], [1, 61.2, ""]],
+        })];
+        let config = ExportConfig {
+            range: "custom".to_owned(),
+            format: ExportFormat::Hdf5,
+            options: ExportOptions {
+                include_headers: true,
+                ..ExportOptions::default()
+            },
+        };
+        export_to_hdf5(data, path.to_string_lossy().into_owned(), config).unwrap();
+
+        let file = File::open(&path).unwrap();
+        let numeric = file.dataset("Decay").unwrap();
+        assert_eq!(numeric.shape(), [2, 2]);
+        assert_eq!(numeric.read_raw::<f64>().unwrap(), [0.0, 100.0, 1.0, 61.2]);
+        let columns = numeric
+            .attr("column_names")
+            .unwrap()
+            .read_raw::<VarLenUnicode>()
+            .unwrap();
+        assert_eq!(
+            columns
+                .iter()
+                .map(VarLenUnicode::as_str)
+                .collect::<Vec<_>>(),
+            ["t", "N"]
+        );
+        let sheet_name = numeric
+            .attr("sheet_name")
+            .unwrap()
+            .read_scalar::<VarLenUnicode>()
+            .unwrap();
+        assert_eq!(sheet_name.as_str(), "Decay");
+
+        let text = file.dataset("Decay_text").unwrap();
+        assert_eq!(text.shape(), [2, 1]);
+        let notes = text.read_raw::<VarLenUnicode>().unwrap();
+        assert_eq!(notes[0].as_str(), "start");
+        assert!(text.attr("exported_at").is_ok());
+
+        drop(file);
+        remove_file(&path).unwrap();
+    }
+}
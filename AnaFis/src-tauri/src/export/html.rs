@@ -1,15 +1,68 @@
-// HTML format export - simplified
+// HTML format export
 //
-// Exports data to HTML table format (2D array)
+// Exports data to HTML tables (2D array), or to one section per sheet when
+// given sheet objects `{ "name": ..., "rows": [[...]] }`. Standalone mode
+// wraps the tables in a complete HTML5 document with an embedded light or
+// dark theme. Output is written straight to the file, so very large tables
+// never build one giant String.
 
-use super::ExportConfig;
+use super::{
+    ExportConfig, ExportOptions, HtmlTheme, Sheet, cell_text, is_numeric_column, sheets_from_data,
+};
+use crate::error::{CommandResult, export_error, validation_error};
 use serde_json::Value;
-use std::fmt::Write;
 use std::fs::File;
-use std::io::{BufWriter, Write as IoWrite};
+use std::io::{self, BufWriter, Write};
 use tauri::command;
 
-/// Export data to HTML format (simplified - expects 2D array)
+/// Colour variables of the light theme
+const LIGHT_THEME: &str = "color-scheme: light; --bg: #ffffff; --fg: #1f2328; \
+--border: #d0d7de; --header: #f6f8fa; --stripe: #f6f8fa; --link: #0969da;";
+
+/// Colour variables of the dark theme
+const DARK_THEME: &str = "color-scheme: dark; --bg: #0d1117; --fg: #e6edf3; \
+--border: #30363d; --header: #161b22; --stripe: #161b22; --link: #4493f8;";
+
+/// Theme-independent styling of standalone documents
+const STYLE: &str = r#"body { margin: 20px; font-family: system-ui, -apple-system, "Segoe UI", Roboto, sans-serif; background: var(--bg); color: var(--fg); }
+a { color: var(--link); }
+nav ul { display: flex; flex-wrap: wrap; gap: 16px; padding: 0; list-style: none; }
+table { border-collapse: collapse; margin-bottom: 24px; }
+th, td { border: 1px solid var(--border); padding: 6px 10px; text-align: left; }
+th { background: var(--header); font-weight: 600; }
+.num { text-align: right; font-variant-numeric: tabular-nums; }
+table.zebra tbody tr:nth-child(even) { background: var(--stripe); }
+table.sticky thead th { position: sticky; top: 0; }
+table.sortable thead th { cursor: pointer; user-select: none; }
+th[aria-sort="ascending"]::after { content: " \25B2"; }
+th[aria-sort="descending"]::after { content: " \25BC"; }
+"#;
+
+/// Sorts a table body by the clicked header, numerically when both cells
+/// are numbers
+const SORT_SCRIPT: &str = r"document.querySelectorAll('table.sortable').forEach(function (table) {
+  var headers = table.querySelectorAll('thead th');
+  headers.forEach(function (header, column) {
+    header.addEventListener('click', function () {
+      var ascending = header.getAttribute('aria-sort') !== 'ascending';
+      headers.forEach(function (other) { other.removeAttribute('aria-sort'); });
+      header.setAttribute('aria-sort', ascending ? 'ascending' : 'descending');
+      var body = table.tBodies[0];
+      var rows = Array.prototype.slice.call(body.rows);
+      rows.sort(function (a, b) {
+        var x = a.cells[column] ? a.cells[column].textContent : '';
+        var y = b.cells[column] ? b.cells[column].textContent : '';
+        var nx = Number(x), ny = Number(y);
+        var order = x !== '' && y !== '' && !isNaN(nx) && !isNaN(ny) ? nx - ny : x.localeCompare(y);
+        return ascending ? order : -order;
+      });
+      rows.forEach(function (row) { body.appendChild(row); });
+    });
+  });
+});
+";
+
+/// Export data to HTML format (2D array or sheet objects)
 #[command]
 #[allow(
     clippy::needless_pass_by_value,
@@ -18,74 +71,130 @@ use tauri::command;
 pub fn export_to_html(
     data: Vec<Value>,
     file_path: String,
-    _config: ExportConfig,
-) -> Result<(), String> {
-    if data.is_empty() {
-        return Err("No data to export".to_owned());
-    }
+    config: ExportConfig,
+) -> CommandResult<()> {
+    let sheets =
+        sheets_from_data(&data).map_err(|e| validation_error(e, Some("data".to_owned())))?;
 
-    // Build HTML content
-    let mut html = String::new();
-
-    // HTML header
-    html.push_str("<!DOCTYPE html>\n");
-    html.push_str("<html>\n<head>\n");
-    html.push_str("<meta charset=\"utf-8\">\n");
-    html.push_str("<title>Exported Data</title>\n");
-
-    // Basic CSS styling
-    html.push_str("<style>\n");
-    html.push_str("body { font-family: Arial, sans-serif; margin: 20px; }\n");
-    html.push_str("table { border-collapse: collapse; width: 100%; }\n");
-    html.push_str("th, td { border: 1px solid #ddd; padding: 8px; text-align: left; }\n");
-    html.push_str("th { background-color: #f2f2f2; font-weight: bold; }\n");
-    html.push_str("tr:nth-child(even) { background-color: #f9f9f9; }\n");
-    html.push_str("tr:hover { background-color: #f5f5f5; }\n");
-    html.push_str("</style>\n");
-
-    html.push_str("</head>\n<body>\n");
-
-    // Table
-    html.push_str("<table>\n<tbody>\n");
-
-    // Process data rows - all rows are treated as data
-    for row in &data {
-        let Some(row_array) = row.as_array() else {
-            continue;
-        };
+    let file = File::create(&file_path)
+        .map_err(|e| export_error(format!("Failed to create file: {e}")))?;
+    let mut writer = BufWriter::new(file);
+    write_html(&mut writer, &sheets, &config.options)
+        .map_err(|e| export_error(format!("Failed to write HTML: {e}")))?;
+    writer
+        .flush()
+        .map_err(|e| export_error(format!("Failed to flush writer: {e}")))?;
 
-        html.push_str("<tr>\n");
+    Ok(())
+}
 
-        for cell in row_array {
-            let cell_content = match cell {
-                Value::String(s) => html_escape(s),
-                Value::Number(n) => n.to_string(),
-                Value::Bool(b) => b.to_string(),
-                Value::Null => String::new(),
-                _ => cell.to_string(),
-            };
+/// Write the tables, wrapped in a document when standalone
+fn write_html<W: Write>(
+    out: &mut W,
+    sheets: &[Sheet<'_>],
+    options: &ExportOptions,
+) -> io::Result<()> {
+    if options.html_standalone {
+        let theme = match options.html_theme {
+            HtmlTheme::Light => LIGHT_THEME,
+            HtmlTheme::Dark => DARK_THEME,
+        };
+        out.write_all(b"<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n")?;
+        out.write_all(b"<meta charset=\"utf-8\">\n")?;
+        out.write_all(
+            b"<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n",
+        )?;
+        out.write_all(b"<title>Exported Data</title>\n")?;
+        writeln!(out, "<style>\n:root {{ {theme} }}\n{STYLE}</style>")?;
+        out.write_all(b"</head>\n<body>\n")?;
+    }
 
-            writeln!(html, "<td>{cell_content}</td>").expect("String writing never fails");
+    if sheets.len() > 1 {
+        out.write_all(b"<nav>\n<ul>\n")?;
+        for (index, sheet) in sheets.iter().enumerate() {
+            let name = html_escape(sheet.name.as_deref().unwrap_or_default());
+            writeln!(out, "<li><a href=\"#sheet-{}\">{name}</a></li>", index + 1)?;
         }
+        out.write_all(b"</ul>\n</nav>\n")?;
+    }
 
-        html.push_str("</tr>\n");
+    for (index, sheet) in sheets.iter().enumerate() {
+        if let Some(name) = &sheet.name {
+            writeln!(out, "<section id=\"sheet-{}\">", index + 1)?;
+            writeln!(out, "<h2>{}</h2>", html_escape(name))?;
+        }
+        write_table(out, &sheet.rows, options)?;
+        if sheet.name.is_some() {
+            out.write_all(b"</section>\n")?;
+        }
     }
 
-    html.push_str("</tbody>\n</table>\n");
-    html.push_str("</body>\n</html>\n");
+    if options.html_sortable {
+        writeln!(out, "<script>\n{SORT_SCRIPT}</script>")?;
+    }
+    if options.html_standalone {
+        out.write_all(b"</body>\n</html>\n")?;
+    }
+    Ok(())
+}
 
-    // Write to file
-    let file = File::create(&file_path).map_err(|e| format!("Failed to create file: {e}"))?;
-    let mut writer = BufWriter::new(file);
+/// Write one table; numeric columns are right-aligned
+fn write_table<W: Write>(
+    out: &mut W,
+    rows: &[&Vec<Value>],
+    options: &ExportOptions,
+) -> io::Result<()> {
+    let num_cols = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let (header, body) = if options.include_headers {
+        rows.split_first()
+            .map_or((None, rows), |(first, rest)| (Some(*first), rest))
+    } else {
+        (None, rows)
+    };
+    let numeric: Vec<bool> = (0..num_cols)
+        .map(|index| is_numeric_column(body, index))
+        .collect();
 
-    writer
-        .write_all(html.as_bytes())
-        .map_err(|e| format!("Failed to write HTML: {e}"))?;
+    let classes: Vec<&str> = [
+        (options.html_zebra, "zebra"),
+        (options.html_sticky_header, "sticky"),
+        (options.html_sortable, "sortable"),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, class)| enabled.then_some(class))
+    .collect();
+    if classes.is_empty() {
+        out.write_all(b"<table>\n")?;
+    } else {
+        writeln!(out, "<table class=\"{}\">", classes.join(" "))?;
+    }
 
-    writer
-        .flush()
-        .map_err(|e| format!("Failed to flush writer: {e}"))?;
+    if let Some(header) = header {
+        out.write_all(b"<thead>\n<tr>")?;
+        write_cells(out, "th", header, &numeric)?;
+        out.write_all(b"</tr>\n</thead>\n")?;
+    }
+    out.write_all(b"<tbody>\n")?;
+    for row in body {
+        out.write_all(b"<tr>")?;
+        write_cells(out, "td", row, &numeric)?;
+        out.write_all(b"</tr>\n")?;
+    }
+    out.write_all(b"</tbody>\n</table>\n")
+}
 
+/// Write a row's cells, padding short rows to the table width
+fn write_cells<W: Write>(
+    out: &mut W,
+    tag: &str,
+    row: &[Value],
+    numeric: &[bool],
+) -> io::Result<()> {
+    for (index, &is_numeric) in numeric.iter().enumerate() {
+        let text = row.get(index).map(cell_text).unwrap_or_default();
+        let class = if is_numeric { " class=\"num\"" } else { "" };
+        write!(out, "<{tag}{class}>{}</{tag}>", html_escape(&text))?;
+    }
     Ok(())
 }
 
@@ -97,3 +206,72 @@ fn html_escape(text: &str) -> String {
         .replace('"', "&quot;")
         .replace('\'', "&#x27;")
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(data: &[Value], options: &ExportOptions) -> String {
+        let sheets = sheets_from_data(data).unwrap();
+        let mut out = Vec::new();
+        write_html(&mut out, &sheets, options).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_cells_are_escaped() {
+        let data = vec![json!(["<script>alert('x')</script>", "a & b", 1.5])];
+        let html = render(&data, &ExportOptions::default());
+        assert!(!html.contains("<script>"));
+        assert!(html.contains(
+            "<td>&lt;script&gt;alert(&#x27;x&#x27;)&lt;/script&gt;</td><td>a &amp; b</td>"
+        ));
+        assert!(html.contains("<td class=\"num\">1.5</td>"));
+        // Without standalone mode only the table is written.
+        assert!(html.starts_with("<table>\n"));
+    }
+
+    #[test]
+    fn test_standalone_document() {
+        let data = vec![json!(["Name", "Value"]), json!(["a", 2]), json!(["b"])];
+        let options = ExportOptions {
+            include_headers: true,
+            html_standalone: true,
+            html_theme: HtmlTheme::Dark,
+            html_zebra: true,
+            html_sortable: true,
+            ..ExportOptions::default()
+        };
+        let html = render(&data, &options);
+        assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n"));
+        assert!(html.contains("<meta charset=\"utf-8\">"));
+        assert!(html.contains(DARK_THEME));
+        assert!(html.ends_with("</script>\n</body>\n</html>\n"));
+        assert!(html.contains("<table class=\"zebra sortable\">"));
+        assert!(
+            html.contains("<thead>\n<tr><th>Name</th><th class=\"num\">Value</th></tr>\n</thead>")
+        );
+        // Short rows are padded to the table width.
+        assert!(html.contains("<tr><td>b</td><td class=\"num\"></td></tr>"));
+    }
+
+    #[test]
+    fn test_one_section_per_sheet() {
+        let data = vec![
+            json!({ "name": "Raw <data>", "rows": [[1, 2], [3, 4]] }),
+            json!({ "name": "Fit", "rows": [["a"]] }),
+            json!({ "rows": [] }),
+        ];
+        let html = render(&data, &ExportOptions::default());
+        assert_eq!(html.matches("<section").count(), 3);
+        assert_eq!(html.matches("<table>").count(), 3);
+        assert!(html.contains("<li><a href=\"#sheet-1\">Raw &lt;data&gt;</a></li>"));
+        assert!(html.contains("<section id=\"sheet-2\">\n<h2>Fit</h2>"));
+        assert!(html.contains("<h2>Sheet 3</h2>"));
+
+        assert!(sheets_from_data(&[json!({ "name": "Empty" })]).is_err());
+        assert!(sheets_from_data(&[]).is_err());
+    }
+}
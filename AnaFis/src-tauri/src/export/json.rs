@@ -0,0 +1,475 @@
+// JSON format export
+//
+// Exports data (2D array) as a JSON array of records, or as JSON Lines with
+// one record per line. With headers the records are objects keyed by column
+// name, otherwise arrays. Type inference turns numeric and boolean strings
+// into JSON numbers and booleans, column by column; an optional schema lists
+// the inferred column types. Output can be gzip-compressed.
+
+use super::{ExportConfig, ExportOptions, JsonFormat, cell_text};
+use crate::error::{CommandResult, export_error, validation_error};
+use chrono::{NaiveDate, NaiveDateTime};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use serde_json::{Map, Number, Value, json, to_writer, to_writer_pretty};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use tracing::warn;
+
+/// Share of non-empty cells that must parse as numbers for a column to be
+/// numeric; the remaining cells are written as null
+const NUMERIC_COLUMN_SHARE: f64 = 0.95;
+
+/// Largest magnitude below which every integer is exact in an `f64`
+const MAX_EXACT_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// A cell classified for type inference
+#[derive(Debug, Clone, PartialEq)]
+enum TypedCell {
+    Empty,
+    Number(Number),
+    Bool(bool),
+    /// Date or date-time, already in ISO 8601 form
+    Date(String),
+    Text,
+}
+
+/// Inferred type of a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnType {
+    Integer,
+    Number,
+    Boolean,
+    Date,
+    String,
+    Null,
+}
+
+impl ColumnType {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Integer => "integer",
+            Self::Number => "number",
+            Self::Boolean => "boolean",
+            Self::Date => "date",
+            Self::String => "string",
+            Self::Null => "null",
+        }
+    }
+}
+
+/// Records ready to write, with their column schema and coercion warnings
+struct JsonDocument {
+    columns: Vec<(String, ColumnType)>,
+    records: Vec<Value>,
+    warnings: Vec<String>,
+}
+
+/// Export data to JSON or JSON Lines format (expects 2D array)
+#[tauri::command]
+#[allow(
+    clippy::needless_pass_by_value,
+    reason = "Tauri commands require owned types for arguments"
+)]
+pub fn export_to_json(
+    data: Vec<Value>,
+    file_path: String,
+    config: ExportConfig,
+) -> CommandResult<()> {
+    let options = &config.options;
+    let document =
+        build_document(&data, options).map_err(|e| validation_error(e, Some("data".to_owned())))?;
+    for warning in &document.warnings {
+        warn!(file = %file_path, "{warning}");
+    }
+
+    let file = File::create(&file_path)
+        .map_err(|e| export_error(format!("Failed to create file: {e}")))?;
+    let writer = BufWriter::new(file);
+    let written = if options.compress {
+        let mut encoder = GzEncoder::new(writer, Compression::default());
+        write_document(&mut encoder, &document, options)
+            .and_then(|()| encoder.finish())
+            .and_then(|mut inner| inner.flush())
+    } else {
+        let mut writer = writer;
+        write_document(&mut writer, &document, options).and_then(|()| writer.flush())
+    };
+    written.map_err(|e| export_error(format!("Failed to write JSON: {e}")))
+}
+
+/// Classify every cell, settle column types and build the records
+fn build_document(data: &[Value], options: &ExportOptions) -> Result<JsonDocument, String> {
+    let rows: Vec<&Vec<Value>> = data.iter().filter_map(Value::as_array).collect();
+    if rows.is_empty() {
+        return Err("No data to export".to_owned());
+    }
+    let num_cols = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let (header, body) = if options.include_headers {
+        rows.split_first()
+            .map_or((None, rows.as_slice()), |(first, rest)| {
+                (Some(*first), rest)
+            })
+    } else {
+        (None, rows.as_slice())
+    };
+
+    let names: Vec<String> = (0..num_cols)
+        .map(|index| {
+            header
+                .and_then(|header| header.get(index))
+                .map(cell_text)
+                .filter(|name| !name.trim().is_empty())
+                .unwrap_or_else(|| format!("column_{}", index + 1))
+        })
+        .collect();
+
+    let date_format = options.date_format.as_deref();
+    let mut columns = Vec::with_capacity(num_cols);
+    let mut cells: Vec<Vec<Value>> = body.iter().map(|_| Vec::with_capacity(num_cols)).collect();
+    let mut warnings = Vec::new();
+    for (index, name) in names.into_iter().enumerate() {
+        let classified: Vec<TypedCell> = body
+            .iter()
+            .map(|row| {
+                classify(
+                    row.get(index).unwrap_or(&Value::Null),
+                    options.infer_types,
+                    date_format,
+                )
+            })
+            .collect();
+        let column_type = column_type(&classified, options.infer_types);
+        for (row_index, (row, typed)) in body.iter().zip(classified).enumerate() {
+            let original = row.get(index).unwrap_or(&Value::Null);
+            let value = match (column_type, typed) {
+                (_, TypedCell::Empty) => Value::Null,
+                (ColumnType::Integer | ColumnType::Number, TypedCell::Number(number)) => {
+                    Value::Number(number)
+                }
+                (ColumnType::Integer | ColumnType::Number, _) => {
+                    warnings.push(format!(
+                        "Column '{name}', row {}: '{}' is not a number and was written as null",
+                        row_index + 1,
+                        cell_text(original)
+                    ));
+                    Value::Null
+                }
+                (ColumnType::Boolean, TypedCell::Bool(flag)) => Value::Bool(flag),
+                (ColumnType::Date, TypedCell::Date(iso_date)) => Value::String(iso_date),
+                _ => original.clone(),
+            };
+            cells[row_index].push(value);
+        }
+        columns.push((name, column_type));
+    }
+
+    let records = cells
+        .into_iter()
+        .map(|values| {
+            if header.is_some() {
+                Value::Object(
+                    columns
+                        .iter()
+                        .map(|(name, _)| name.clone())
+                        .zip(values)
+                        .collect::<Map<String, Value>>(),
+                )
+            } else {
+                Value::Array(values)
+            }
+        })
+        .collect();
+
+    Ok(JsonDocument {
+        columns,
+        records,
+        warnings,
+    })
+}
+
+/// Classify a cell; strings are only parsed when inferring types, and only
+/// matched as dates when a date format is given
+fn classify(cell: &Value, infer_types: bool, date_format: Option<&str>) -> TypedCell {
+    match cell {
+        Value::Null => TypedCell::Empty,
+        Value::Number(number) => TypedCell::Number(number.clone()),
+        Value::Bool(flag) => TypedCell::Bool(*flag),
+        Value::String(text) => {
+            let trimmed = text.trim();
+            if trimmed.is_empty() {
+                TypedCell::Empty
+            } else if !infer_types {
+                TypedCell::Text
+            } else if let Some(date) = date_format.and_then(|format| parse_date(trimmed, format)) {
+                TypedCell::Date(date)
+            } else if let Some(number) = parse_number(trimmed) {
+                TypedCell::Number(number)
+            } else if trimmed.eq_ignore_ascii_case("true") {
+                TypedCell::Bool(true)
+            } else if trimmed.eq_ignore_ascii_case("false") {
+                TypedCell::Bool(false)
+            } else {
+                TypedCell::Text
+            }
+        }
+        _ => TypedCell::Text,
+    }
+}
+
+/// A finite number written in decimal or scientific notation. Integers with
+/// leading zeros such as `007` are identifiers and stay text, as do `NaN` and
+/// infinities, which JSON cannot represent.
+fn parse_number(text: &str) -> Option<Number> {
+    let digits = text.trim_start_matches(['+', '-']);
+    if !digits.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    let mut chars = digits.chars();
+    if chars.next() == Some('0') && chars.next().is_some_and(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let value = text.parse::<f64>().ok().filter(|value| value.is_finite())?;
+    if value.fract() == 0.0 && value.abs() < MAX_EXACT_INTEGER {
+        #[allow(
+            clippy::cast_possible_truncation,
+            reason = "Integral value below 2^53 converts to i64 exactly"
+        )]
+        return Some(Number::from(value as i64));
+    }
+    Number::from_f64(value)
+}
+
+/// A date or date-time in `format`, rewritten as ISO 8601
+fn parse_date(text: &str, format: &str) -> Option<String> {
+    NaiveDateTime::parse_from_str(text, format)
+        .map(|date_time| date_time.format("%Y-%m-%dT%H:%M:%S").to_string())
+        .or_else(|_| NaiveDate::parse_from_str(text, format).map(|date| date.to_string()))
+        .ok()
+}
+
+/// Type of a column from its cells: numeric once enough cells are numbers
+/// (all of them without type inference), boolean or date only when every
+/// non-empty cell is one, string otherwise
+fn column_type(cells: &[TypedCell], infer_types: bool) -> ColumnType {
+    let filled = cells
+        .iter()
+        .filter(|cell| **cell != TypedCell::Empty)
+        .count();
+    if filled == 0 {
+        return ColumnType::Null;
+    }
+    let numbers: Vec<&Number> = cells
+        .iter()
+        .filter_map(|cell| match cell {
+            TypedCell::Number(number) => Some(number),
+            _ => None,
+        })
+        .collect();
+    #[allow(clippy::cast_precision_loss, reason = "Cell counts casting to f64")]
+    let share = numbers.len() as f64 / filled as f64;
+    let threshold = if infer_types {
+        NUMERIC_COLUMN_SHARE
+    } else {
+        1.0
+    };
+    if share >= threshold {
+        return if numbers
+            .iter()
+            .all(|number| number.is_i64() || number.is_u64())
+        {
+            ColumnType::Integer
+        } else {
+            ColumnType::Number
+        };
+    }
+    let all = |matches: fn(&TypedCell) -> bool| {
+        cells
+            .iter()
+            .all(|cell| *cell == TypedCell::Empty || matches(cell))
+    };
+    if all(|cell| matches!(cell, TypedCell::Bool(_))) {
+        ColumnType::Boolean
+    } else if all(|cell| matches!(cell, TypedCell::Date(_))) {
+        ColumnType::Date
+    } else {
+        ColumnType::String
+    }
+}
+
+/// Write the records as one JSON value, or as JSON Lines. The schema and
+/// warnings, when present, go in a wrapping object or on the first line.
+fn write_document<W: Write>(
+    out: &mut W,
+    document: &JsonDocument,
+    options: &ExportOptions,
+) -> io::Result<()> {
+    let schema = options.json_schema.then(|| {
+        let columns: Vec<Value> = document
+            .columns
+            .iter()
+            .map(|(name, column_type)| json!({ "name": name, "type": column_type.name() }))
+            .collect();
+        json!({ "columns": columns })
+    });
+    let mut header = Map::new();
+    if let Some(schema) = schema {
+        header.insert("schema".to_owned(), schema);
+    }
+    if !document.warnings.is_empty() {
+        header.insert("warnings".to_owned(), json!(document.warnings));
+    }
+
+    match options.json_format {
+        JsonFormat::Json if header.is_empty() => to_writer_pretty(&mut *out, &document.records)?,
+        JsonFormat::Json => {
+            header.insert("records".to_owned(), json!(document.records));
+            to_writer_pretty(&mut *out, &header)?;
+        }
+        JsonFormat::Jsonl => {
+            if !header.is_empty() {
+                to_writer(&mut *out, &header)?;
+                out.write_all(b"\n")?;
+            }
+            for record in &document.records {
+                to_writer(&mut *out, record)?;
+                out.write_all(b"\n")?;
+            }
+            return Ok(());
+        }
+    }
+    out.write_all(b"\n")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use crate::export::ExportFormat;
+    use flate2::read::GzDecoder;
+    use std::env::temp_dir;
+    use std::fs::{read, remove_file};
+    use std::io::Read;
+
+    fn options() -> ExportOptions {
+        ExportOptions {
+            include_headers: true,
+            infer_types: true,
+            ..ExportOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_type_inference_edge_cases() {
+        let data = vec![
+            json!(["id", "value", "flag", "note", "day"]),
+            json!(["007", "1e5", "TRUE", "NaN", "2024-03-01"]),
+            json!(["012", "-0.25", "false", "1.5", "2024-03-02"]),
+        ];
+        let document = build_document(&data, &options()).unwrap();
+        let types: Vec<&str> = document
+            .columns
+            .iter()
+            .map(|(_, column_type)| column_type.name())
+            .collect();
+        assert_eq!(types, ["string", "number", "boolean", "string", "string"]);
+        assert_eq!(
+            document.records[0],
+            json!({ "id": "007", "value": 100_000, "flag": true, "note": "NaN", "day": "2024-03-01" })
+        );
+        assert!(document.warnings.is_empty());
+
+        // Dates are only recognized with an explicit format.
+        let with_dates = ExportOptions {
+            date_format: Some("%Y-%m-%d".to_owned()),
+            ..options()
+        };
+        let dated = build_document(&data, &with_dates).unwrap();
+        assert_eq!(dated.columns[4].1, ColumnType::Date);
+        assert_eq!(dated.records[1]["day"], json!("2024-03-02"));
+
+        let raw = ExportOptions {
+            infer_types: false,
+            ..options()
+        };
+        let untyped = build_document(&data, &raw).unwrap();
+        assert_eq!(untyped.records[0]["value"], json!("1e5"));
+    }
+
+    #[test]
+    fn test_numeric_column_coerces_stragglers_to_null() {
+        let mut data = vec![json!(["reading"])];
+        data.extend((0..19).map(|index| json!([index.to_string()])));
+        data.push(json!(["n/a"]));
+        let document = build_document(&data, &options()).unwrap();
+        assert_eq!(document.columns[0].1, ColumnType::Integer);
+        assert_eq!(document.records[19], json!({ "reading": null }));
+        assert_eq!(document.warnings.len(), 1);
+        assert!(document.warnings[0].contains("'n/a'"));
+
+        // One more text cell drops the column below the threshold.
+        data.push(json!(["missing"]));
+        let textual = build_document(&data, &options()).unwrap();
+        assert_eq!(textual.columns[0].1, ColumnType::String);
+        assert_eq!(textual.records[0], json!({ "reading": "0" }));
+    }
+
+    #[test]
+    fn test_jsonl_one_record_per_line() {
+        let data = vec![json!(["x", "y"]), json!([1, 2]), json!([3, 4]), json!([5])];
+        let jsonl = ExportOptions {
+            json_format: JsonFormat::Jsonl,
+            ..options()
+        };
+        let mut out = Vec::new();
+        write_document(&mut out, &build_document(&data, &jsonl).unwrap(), &jsonl).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count(), 3);
+        assert_eq!(text.lines().last().unwrap(), r#"{"x":5,"y":null}"#);
+
+        let with_schema = ExportOptions {
+            json_schema: true,
+            ..jsonl
+        };
+        let mut with_header = Vec::new();
+        let document = build_document(&data, &with_schema).unwrap();
+        write_document(&mut with_header, &document, &with_schema).unwrap();
+        let first: Value =
+            serde_json::from_slice(with_header.split(|&b| b == b'\n').next().unwrap()).unwrap();
+        assert_eq!(
+            first["schema"]["columns"][1],
+            json!({ "name": "y", "type": "integer" })
+        );
+    }
+
+    #[test]
+    fn test_compressed_output_round_trip() {
+        let path = temp_dir().join(format!("anafis-json-test-{}.json.gz", std::process::id()));
+        let data = vec![
+            json!(["name", "mass"]),
+            json!(["a", "1.5"]),
+            json!(["b", 2]),
+        ];
+        let config = ExportConfig {
+            range: "custom".to_owned(),
+            format: ExportFormat::Json,
+            options: ExportOptions {
+                compress: true,
+                ..options()
+            },
+        };
+        export_to_json(data, path.to_string_lossy().into_owned(), config).unwrap();
+
+        let compressed = read(&path).unwrap();
+        remove_file(&path).unwrap();
+        let mut text = String::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut text)
+            .unwrap();
+        let records: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(
+            records,
+            json!([{ "name": "a", "mass": 1.5 }, { "name": "b", "mass": 2 }])
+        );
+    }
+}
@@ -3,6 +3,7 @@
 // Exports data to Markdown table format (2D array)
 
 use super::ExportConfig;
+use crate::error::{CommandResult, export_error, validation_error};
 use serde_json::Value;
 use std::fs::File;
 use std::io::{BufWriter, Write};
@@ -17,13 +18,17 @@ pub fn export_to_markdown(
     data: Vec<Value>,
     file_path: String,
     _config: ExportConfig,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     if data.is_empty() {
-        return Err("No data to export".to_owned());
+        return Err(validation_error(
+            "No data to export",
+            Some("data".to_owned()),
+        ));
     }
 
     // Create file with buffered writer
-    let file = File::create(&file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+    let file = File::create(&file_path)
+        .map_err(|e| export_error(format!("Failed to create file: {e}")))?;
     let mut writer = BufWriter::new(file);
 
     // Process data rows - all rows are treated as data
@@ -50,12 +55,13 @@ pub fn export_to_markdown(
 
         // Write the row
         let row_str = formatted_cells.join(" | ");
-        writeln!(writer, "| {row_str} |").map_err(|e| format!("Failed to write row: {e}"))?;
+        writeln!(writer, "| {row_str} |")
+            .map_err(|e| export_error(format!("Failed to write row: {e}")))?;
     }
 
     writer
         .flush()
-        .map_err(|e| format!("Failed to flush writer: {e}"))?;
+        .map_err(|e| export_error(format!("Failed to flush writer: {e}")))?;
 
     Ok(())
 }
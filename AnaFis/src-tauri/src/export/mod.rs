@@ -2,35 +2,53 @@
 //
 // Architecture:
 // - PRIMARY (Lossless): anafispread (native format - full IWorkbookData snapshots)
-// - INTERCHANGE (Text): csv, tsv, txt (for external application interaction)
-// - COLUMNAR: parquet (efficient binary columnar format)
+// - INTERCHANGE (Text): csv, tsv, txt, json (for external application interaction)
+// - COLUMNAR: parquet (efficient binary columnar format), arrow IPC (Feather v2)
+// - SCIENTIFIC: hdf5 (numeric arrays with metadata attributes)
+// - INTERCHANGE (Markup): xml (element or attribute cells)
 // - READ-ONLY: html, markdown, tex (document/report generation)
 //
 // Submodules:
 // - text: CSV, TSV, TXT exports
+// - json: JSON and JSON Lines record exports
 // - html: HTML table exports
+// - xml: XML exports with configurable element names
 // - markdown: Markdown table exports
 // - tex: LaTeX table exports
 // - parquet: Apache Parquet exports
+// - arrow_ipc: Apache Arrow IPC (Feather v2) exports
+// - hdf5: HDF5 exports, one dataset per sheet (`hdf5` feature)
 // - anafispread: Custom AnaFis spreadsheet format
+// - multi_file: One file per sheet in a directory, in any per-sheet format
 
 pub mod anafispread;
+pub mod arrow_ipc;
+#[cfg(feature = "hdf5")]
+pub mod hdf5;
 pub mod html;
+pub mod json;
 pub mod markdown;
+pub mod multi_file;
 pub mod parquet;
 pub mod tex;
 pub mod text;
+pub mod xml;
 
-use crate::error::{CommandResult, export_error, validation_error};
+use crate::error::{CommandResult, validation_error};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use self::anafispread::export_anafispread;
+use self::arrow_ipc::export_to_arrow_ipc;
+#[cfg(feature = "hdf5")]
+use self::hdf5::export_to_hdf5;
 use self::html::export_to_html;
+use self::json::export_to_json;
 use self::markdown::export_to_markdown;
 use self::parquet::export_to_parquet;
 use self::tex::export_to_latex;
 use self::text::export_to_text;
+use self::xml::export_to_xml;
 
 /// Export format types supported by the application
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,21 +60,30 @@ pub enum ExportFormat {
     Tsv,
     /// Custom delimiter text
     Txt,
+    /// JSON records or JSON Lines
+    Json,
     /// Apache Parquet
     Parquet,
+    /// Apache Arrow IPC file (Feather v2)
+    #[serde(rename = "arrow")]
+    ArrowIpc,
+    /// HDF5 datasets
+    Hdf5,
     /// LaTeX table
     Tex,
     /// HTML table
     Html,
     /// Markdown table
     Markdown,
+    /// XML document
+    Xml,
     /// `AnaFis` spreadsheet format
     #[serde(rename = "anafispread")]
     AnaFisSpread,
 }
 
 /// Export configuration passed from frontend
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportConfig {
     /// Range mode used (for logging/debugging): 'sheet', 'all', or 'custom'
@@ -67,8 +94,45 @@ pub struct ExportConfig {
     pub options: ExportOptions,
 }
 
+/// Rule style of exported LaTeX tables
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LatexStyle {
+    /// `tabular` without rules (`\hline` under the header row)
+    #[default]
+    Plain,
+    /// `\toprule`, `\midrule` and `\bottomrule` from the booktabs package
+    Booktabs,
+}
+
+/// Colour theme of standalone HTML documents
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HtmlTheme {
+    /// Dark text on a white page
+    #[default]
+    Light,
+    /// Light text on a dark page
+    Dark,
+}
+
+/// Layout of exported JSON
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonFormat {
+    /// One JSON array of records
+    #[default]
+    Json,
+    /// JSON Lines, one record per line
+    Jsonl,
+}
+
 /// Options for configuring exports
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[allow(
+    clippy::struct_excessive_bools,
+    reason = "Independent format switches set by the export dialog"
+)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportOptions {
     /// Include header row
@@ -76,13 +140,106 @@ pub struct ExportOptions {
     pub include_headers: bool,
     /// Delimiter character (default: ',')
     pub delimiter: Option<String>,
+    /// LaTeX table rules (default: plain)
+    #[serde(default)]
+    pub latex_style: LatexStyle,
+    /// Align numeric LaTeX columns on the decimal point with siunitx `S` columns
+    #[serde(default)]
+    pub latex_siunitx: bool,
+    /// `(value, uncertainty)` column pairs merged into one LaTeX column
+    #[serde(default)]
+    pub uncertainty_column_pairs: Vec<(usize, usize)>,
+    /// Write a complete HTML document with embedded CSS instead of bare tables
+    #[serde(default)]
+    pub html_standalone: bool,
+    /// Colour theme of standalone HTML documents (default: light)
+    #[serde(default)]
+    pub html_theme: HtmlTheme,
+    /// Shade every other HTML table row
+    #[serde(default)]
+    pub html_zebra: bool,
+    /// Keep the HTML header row visible while scrolling
+    #[serde(default)]
+    pub html_sticky_header: bool,
+    /// Embed a script that sorts HTML tables by the clicked header
+    #[serde(default)]
+    pub html_sortable: bool,
+    /// Parse numeric and boolean strings into JSON numbers and booleans
+    #[serde(default)]
+    pub infer_types: bool,
+    /// Emit a schema of column names and inferred types with JSON exports
+    #[serde(default)]
+    pub json_schema: bool,
+    /// JSON array or JSON Lines (default: array)
+    #[serde(default)]
+    pub json_format: JsonFormat,
+    /// Gzip-compress the output
+    #[serde(default)]
+    pub compress: bool,
+    /// chrono format of date strings to convert to ISO 8601 during type
+    /// inference; dates are left untouched without it
+    #[serde(default)]
+    pub date_format: Option<String>,
+    /// Name of the XML root element; blank for `data`
+    #[serde(default)]
+    pub xml_root_element: String,
+    /// Name of the XML element written for each row; blank for `row`
+    #[serde(default)]
+    pub xml_row_element: String,
+    /// Write XML cells as attributes of the row element instead of child elements
+    #[serde(default)]
+    pub xml_cell_as_attribute: bool,
+    /// Replace existing files when exporting one file per sheet
+    #[serde(default)]
+    pub overwrite: bool,
 }
 
 /// Frontend config structure (simplified)
 #[derive(Debug, Serialize, Deserialize, Default)]
+#[allow(
+    clippy::struct_excessive_bools,
+    reason = "Independent format switches set by the export dialog"
+)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportConfigFrontend {
     pub delimiter: Option<String>,
+    /// Treat the first row as a header row
+    #[serde(default)]
+    pub include_headers: bool,
+    #[serde(default)]
+    pub latex_style: LatexStyle,
+    #[serde(default)]
+    pub latex_siunitx: bool,
+    #[serde(default)]
+    pub uncertainty_column_pairs: Vec<(usize, usize)>,
+    #[serde(default)]
+    pub html_standalone: bool,
+    #[serde(default)]
+    pub html_theme: HtmlTheme,
+    #[serde(default)]
+    pub html_zebra: bool,
+    #[serde(default)]
+    pub html_sticky_header: bool,
+    #[serde(default)]
+    pub html_sortable: bool,
+    #[serde(default)]
+    pub infer_types: bool,
+    #[serde(default)]
+    pub json_schema: bool,
+    #[serde(default)]
+    pub json_format: JsonFormat,
+    #[serde(default)]
+    pub compress: bool,
+    #[serde(default)]
+    pub date_format: Option<String>,
+    #[serde(default)]
+    pub xml_root_element: String,
+    #[serde(default)]
+    pub xml_row_element: String,
+    #[serde(default)]
+    pub xml_cell_as_attribute: bool,
+    #[serde(default)]
+    pub overwrite: bool,
 }
 
 /// Main export dispatcher function that routes to the appropriate format handler
@@ -97,24 +254,56 @@ pub async fn export_data(
         range: "custom".to_owned(),
         format,
         options: ExportOptions {
-            include_headers: false, // Default value
+            include_headers: config.include_headers,
             delimiter: config.delimiter,
+            latex_style: config.latex_style,
+            latex_siunitx: config.latex_siunitx,
+            uncertainty_column_pairs: config.uncertainty_column_pairs,
+            html_standalone: config.html_standalone,
+            html_theme: config.html_theme,
+            html_zebra: config.html_zebra,
+            html_sticky_header: config.html_sticky_header,
+            html_sortable: config.html_sortable,
+            infer_types: config.infer_types,
+            json_schema: config.json_schema,
+            json_format: config.json_format,
+            compress: config.compress,
+            date_format: config.date_format,
+            xml_root_element: config.xml_root_element,
+            xml_row_element: config.xml_row_element,
+            xml_cell_as_attribute: config.xml_cell_as_attribute,
+            overwrite: config.overwrite,
         },
     };
 
+    export_with_config(data, file_path, export_config)
+}
+
+/// Route data to the export function of `export_config.format`
+fn export_with_config(
+    data: Vec<Value>,
+    file_path: String,
+    export_config: ExportConfig,
+) -> CommandResult<()> {
     match export_config.format {
         ExportFormat::Csv | ExportFormat::Tsv | ExportFormat::Txt => {
             export_to_text(data, file_path, export_config)
-                .map_err(|e| export_error(format!("Text export failed: {e}")))
         }
-        ExportFormat::Parquet => export_to_parquet(data, file_path, export_config)
-            .map_err(|e| export_error(format!("Parquet export failed: {e}"))),
-        ExportFormat::Html => export_to_html(data, file_path, export_config)
-            .map_err(|e| export_error(format!("HTML export failed: {e}"))),
-        ExportFormat::Markdown => export_to_markdown(data, file_path, export_config)
-            .map_err(|e| export_error(format!("Markdown export failed: {e}"))),
-        ExportFormat::Tex => export_to_latex(data, file_path, export_config)
-            .map_err(|e| export_error(format!("LaTeX export failed: {e}"))),
+        ExportFormat::Json => export_to_json(data, file_path, export_config),
+        ExportFormat::Parquet => export_to_parquet(data, file_path, export_config),
+        ExportFormat::ArrowIpc => export_to_arrow_ipc(data, file_path, export_config),
+        #[cfg(feature = "hdf5")]
+        ExportFormat::Hdf5 => export_to_hdf5(data, file_path, export_config),
+        #[cfg(not(feature = "hdf5"))]
+        ExportFormat::Hdf5 => Err(crate::error::CommandError::Validation {
+            code: crate::error::codes::UNSUPPORTED_FORMAT,
+            field: Some("format".to_owned()),
+            detail: "HDF5 export is not available in this build".to_owned(),
+        }),
+        ExportFormat::Html => export_to_html(data, file_path, export_config),
+        ExportFormat::Markdown => export_to_markdown(data, file_path, export_config),
+        ExportFormat::Xml => export_to_xml(data, file_path, export_config),
+        ExportFormat::Tex => export_to_latex(data, file_path, export_config),
         ExportFormat::AnaFisSpread => {
             // For anafispread, we need to pass the data directly (not as array)
             let workbook_data = if data.len() == 1 {
@@ -125,8 +314,86 @@ pub async fn export_data(
                     Some("data".to_owned()),
                 ));
             };
-            export_anafispread(workbook_data, file_path)
-                .map_err(|e| export_error(format!("AnaFis spread export failed: {e}")))
+            export_anafispread(workbook_data, file_path, None)
+        }
+    }
+}
+
+/// Rows of one exported sheet
+struct Sheet<'data> {
+    /// Sheet name; plain 2D array input has none
+    name: Option<String>,
+    rows: Vec<&'data Vec<Value>>,
+}
+
+/// Split the input into sheets: objects with a `rows` array are sheets,
+/// anything else is a single unnamed sheet of rows
+fn sheets_from_data(data: &[Value]) -> Result<Vec<Sheet<'_>>, String> {
+    if data.is_empty() {
+        return Err("No data to export".to_owned());
+    }
+    if !data.iter().all(Value::is_object) {
+        return Ok(vec![Sheet {
+            name: None,
+            rows: data.iter().filter_map(Value::as_array).collect(),
+        }]);
+    }
+
+    data.iter()
+        .enumerate()
+        .map(|(index, sheet)| {
+            let rows = sheet
+                .get("rows")
+                .and_then(Value::as_array)
+                .ok_or_else(|| format!("Sheet {} has no rows array", index + 1))?;
+            let name = sheet
+                .get("name")
+                .and_then(Value::as_str)
+                .map_or_else(|| format!("Sheet {}", index + 1), str::to_owned);
+            Ok(Sheet {
+                name: Some(name),
+                rows: rows.iter().filter_map(Value::as_array).collect(),
+            })
+        })
+        .collect()
+}
+
+/// Plain text of a cell
+fn cell_text(cell: &Value) -> String {
+    match cell {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => String::new(),
+        _ => cell.to_string(),
+    }
+}
+
+/// Text of a cell holding a finite number, as a number or numeric string
+fn numeric_text(cell: &Value) -> Option<String> {
+    match cell {
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => {
+            let trimmed = s.trim();
+            trimmed
+                .parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite())
+                .map(|_| trimmed.to_owned())
+        }
+        _ => None,
+    }
+}
+
+/// Whether every non-empty cell of column `index` is a number
+fn is_numeric_column(body: &[&Vec<Value>], index: usize) -> bool {
+    let mut any_number = false;
+    for cell in body.iter().filter_map(|row| row.get(index)) {
+        if numeric_text(cell).is_some() {
+            any_number = true;
+        } else if !cell_text(cell).trim().is_empty() {
+            return false;
         }
     }
+    any_number
 }
@@ -0,0 +1,255 @@
+// Multi-file export
+//
+// Writes every sheet of a workbook to its own file in a directory, named
+// `{sheet_name}.{extension}`. Each sheet's rows go through the regular
+// per-format export function, so any format that exports a 2D array works.
+// Sheet names are turned into portable file names, and existing files are
+// only replaced when the `overwrite` option is set.
+
+use super::{ExportConfig, ExportFormat, JsonFormat, export_with_config, sheets_from_data};
+use crate::error::{CommandResult, export_error, validation_error};
+use crate::import::validate_and_canonicalize_path;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::create_dir_all;
+use std::path::PathBuf;
+
+/// A file written by a multi-file export
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedSheetFile {
+    /// Sheet the file was written from
+    pub sheet_name: String,
+    /// Canonical path of the file
+    pub path: String,
+    /// Data rows written (the header row excluded)
+    pub rows: usize,
+}
+
+/// Export each sheet to its own file in `directory_path`, creating the
+/// directory if needed
+#[tauri::command]
+#[allow(
+    clippy::needless_pass_by_value,
+    reason = "Tauri commands require owned types for arguments"
+)]
+pub fn export_multi_file(
+    data: Vec<Value>,
+    directory_path: String,
+    config: ExportConfig,
+) -> CommandResult<Vec<ExportedSheetFile>> {
+    if matches!(config.format, ExportFormat::AnaFisSpread) {
+        return Err(validation_error(
+            "AnaFis spreadsheets hold the whole workbook and cannot be split per sheet",
+            Some("format".to_owned()),
+        ));
+    }
+    let sheets =
+        sheets_from_data(&data).map_err(|e| validation_error(e, Some("data".to_owned())))?;
+
+    create_dir_all(&directory_path)
+        .map_err(|e| export_error(format!("Failed to create directory: {e}")))?;
+    let directory = validate_and_canonicalize_path(&directory_path)
+        .map_err(|e| validation_error(e, Some("directoryPath".to_owned())))?;
+    if !directory.is_dir() {
+        return Err(validation_error(
+            format!("Not a directory: {directory_path}"),
+            Some("directoryPath".to_owned()),
+        ));
+    }
+
+    let extension = file_extension(&config);
+    let mut stems: Vec<String> = Vec::with_capacity(sheets.len());
+    let mut targets: Vec<(String, PathBuf)> = Vec::with_capacity(sheets.len());
+    for (index, sheet) in sheets.iter().enumerate() {
+        let sheet_name = sheet
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("Sheet {}", index + 1));
+        let stem = unique_stem(&file_stem(&sheet_name, index), &stems);
+        let path = directory.join(format!("{stem}.{extension}"));
+        // Check every target before writing anything
+        if !config.options.overwrite && path.exists() {
+            return Err(validation_error(
+                format!(
+                    "{} already exists; enable overwrite to replace it",
+                    path.display()
+                ),
+                Some("overwrite".to_owned()),
+            ));
+        }
+        stems.push(stem);
+        targets.push((sheet_name, path));
+    }
+
+    sheets
+        .iter()
+        .zip(targets)
+        .map(|(sheet, (sheet_name, path))| {
+            let rows: Vec<Value> = sheet
+                .rows
+                .iter()
+                .map(|row| Value::Array((*row).clone()))
+                .collect();
+            let data_rows = if config.options.include_headers {
+                rows.len().saturating_sub(1)
+            } else {
+                rows.len()
+            };
+            let file_path = path.to_string_lossy().into_owned();
+            export_with_config(rows, file_path.clone(), config.clone())
+                .map_err(|e| e.context(&format!("Sheet '{sheet_name}'")))?;
+            Ok(ExportedSheetFile {
+                sheet_name,
+                path: file_path,
+                rows: data_rows,
+            })
+        })
+        .collect()
+}
+
+/// Extension of the files written for the configured format
+const fn file_extension(config: &ExportConfig) -> &'static str {
+    match config.format {
+        ExportFormat::Csv => "csv",
+        ExportFormat::Tsv => "tsv",
+        ExportFormat::Txt => "txt",
+        ExportFormat::Json => match (config.options.json_format, config.options.compress) {
+            (JsonFormat::Json, false) => "json",
+            (JsonFormat::Json, true) => "json.gz",
+            (JsonFormat::Jsonl, false) => "jsonl",
+            (JsonFormat::Jsonl, true) => "jsonl.gz",
+        },
+        ExportFormat::Parquet => "parquet",
+        ExportFormat::ArrowIpc => "arrow",
+        ExportFormat::Hdf5 => "h5",
+        ExportFormat::Tex => "tex",
+        ExportFormat::Html => "html",
+        ExportFormat::Markdown => "md",
+        ExportFormat::Xml => "xml",
+        ExportFormat::AnaFisSpread => "anafispread",
+    }
+}
+
+/// File name stem for a sheet: characters not allowed in file names on
+/// Windows, macOS or Linux become '_', and Windows device names get a
+/// leading '_'
+fn file_stem(sheet_name: &str, index: usize) -> String {
+    let replaced: String = sheet_name
+        .chars()
+        .map(|character| {
+            if character.is_control() || r#"<>:"/\|?*"#.contains(character) {
+                '_'
+            } else {
+                character
+            }
+        })
+        .collect();
+    // Windows drops trailing dots and spaces
+    let stem = replaced.trim().trim_end_matches('.').to_owned();
+    if stem.is_empty() {
+        return format!("Sheet {}", index + 1);
+    }
+    let device = stem.split('.').next().unwrap_or_default().to_uppercase();
+    let is_device = matches!(device.as_str(), "CON" | "PRN" | "AUX" | "NUL")
+        || ((device.starts_with("COM") || device.starts_with("LPT"))
+            && device.len() == 4
+            && device.ends_with(|character: char| character.is_ascii_digit()));
+    if is_device { format!("_{stem}") } else { stem }
+}
+
+/// `stem`, or `stem_2`, `stem_3`, ... when an earlier sheet has it; compared
+/// case-insensitively for case-insensitive file systems
+fn unique_stem(stem: &str, taken: &[String]) -> String {
+    let is_taken = |candidate: &str| {
+        taken
+            .iter()
+            .any(|other| other.to_lowercase() == candidate.to_lowercase())
+    };
+    let mut name = stem.to_owned();
+    let mut copy = 1;
+    while is_taken(&name) {
+        copy += 1;
+        name = format!("{stem}_{copy}");
+    }
+    name
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use crate::export::ExportOptions;
+    use serde_json::json;
+    use std::env::temp_dir;
+    use std::fs::{read_to_string, remove_dir_all};
+
+    fn csv_config(overwrite: bool) -> ExportConfig {
+        ExportConfig {
+            range: "custom".to_owned(),
+            format: ExportFormat::Csv,
+            options: ExportOptions {
+                include_headers: true,
+                overwrite,
+                ..ExportOptions::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_one_file_per_sheet() {
+        let directory = temp_dir().join(format!("anafis-multi-file-{}", std::process::id()));
+        let directory_path = directory.join("nested").to_string_lossy().into_owned();
+        let data = vec![
+            json!({ "name": "Run 1", "rows": [["t", "x"], [0, 1.5], [1, 2.5]] }),
+            json!({ "name": "a/b", "rows": [["v"]] }),
+            json!({ "name": "RUN 1", "rows": [] }),
+        ];
+
+        let files =
+            export_multi_file(data.clone(), directory_path.clone(), csv_config(false)).unwrap();
+        let names: Vec<String> = files
+            .iter()
+            .map(|file| {
+                PathBuf::from(&file.path)
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        assert_eq!(names, ["Run 1.csv", "a_b.csv", "RUN 1_2.csv"]);
+        assert_eq!(
+            files.iter().map(|file| file.rows).collect::<Vec<_>>(),
+            [2, 0, 0]
+        );
+        assert_eq!(files[1].sheet_name, "a/b");
+        assert_eq!(
+            read_to_string(&files[0].path).unwrap(),
+            "t,x\r\n0,1.5\r\n1,2.5\r\n"
+        );
+
+        // Existing files are kept unless overwrite is set
+        let error =
+            export_multi_file(data.clone(), directory_path.clone(), csv_config(false)).unwrap_err();
+        assert!(
+            error.detail().contains("already exists"),
+            "{}",
+            error.detail()
+        );
+        assert!(export_multi_file(data, directory_path, csv_config(true)).is_ok());
+
+        remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_file_stems_are_portable() {
+        assert_eq!(file_stem("x: y?", 0), "x_ y_");
+        assert_eq!(file_stem(" data. ", 0), "data");
+        assert_eq!(file_stem("...", 4), "Sheet 5");
+        assert_eq!(file_stem("con", 0), "_con");
+        assert_eq!(file_stem("COM1.backup", 0), "_COM1.backup");
+        assert_eq!(file_stem("Console", 0), "Console");
+        assert_eq!(unique_stem("a", &["A".to_owned(), "a_2".to_owned()]), "a_3");
+    }
+}
@@ -3,6 +3,7 @@
 // Exports data to Apache Parquet columnar format (2D array)
 
 use super::ExportConfig;
+use crate::error::{CommandResult, export_error, validation_error};
 use arrow::array::{ArrayRef, StringArray};
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
@@ -22,7 +23,7 @@ pub fn export_to_parquet(
     data: Vec<Value>,
     file_path: String,
     _config: ExportConfig,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     // Determine the maximum number of columns
     let max_cols = data
         .iter()
@@ -32,7 +33,10 @@ pub fn export_to_parquet(
         .unwrap_or(0);
 
     if max_cols == 0 {
-        return Err("No data to export".to_owned());
+        return Err(validation_error(
+            "No data to export",
+            Some("data".to_owned()),
+        ));
     }
 
     let num_rows = data.len();
@@ -78,22 +82,23 @@ pub fn export_to_parquet(
 
     // Create RecordBatch
     let batch = RecordBatch::try_new(Arc::clone(&schema), columns)
-        .map_err(|e| format!("Failed to create RecordBatch: {e}"))?;
+        .map_err(|e| export_error(format!("Failed to create RecordBatch: {e}")))?;
 
     // Write to Parquet file
-    let file = File::create(&file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+    let file = File::create(&file_path)
+        .map_err(|e| export_error(format!("Failed to create file: {e}")))?;
 
     let props = WriterProperties::builder().build();
     let mut writer = ArrowWriter::try_new(file, schema, Some(props))
-        .map_err(|e| format!("Failed to create Parquet writer: {e}"))?;
+        .map_err(|e| export_error(format!("Failed to create Parquet writer: {e}")))?;
 
     writer
         .write(&batch)
-        .map_err(|e| format!("Failed to write RecordBatch: {e}"))?;
+        .map_err(|e| export_error(format!("Failed to write RecordBatch: {e}")))?;
 
     writer
         .close()
-        .map_err(|e| format!("Failed to close Parquet writer: {e}"))?;
+        .map_err(|e| export_error(format!("Failed to close Parquet writer: {e}")))?;
 
     Ok(())
 }
@@ -1,13 +1,27 @@
-// LaTeX format export - simplified
+// LaTeX format export
 //
-// Exports data to LaTeX table format (2D array)
+// Exports data to a LaTeX table (2D array). Tables can use booktabs rules,
+// siunitx `S` columns for decimal alignment, and merged value/uncertainty
+// columns written in the compact `12.34(5)` notation.
 
-use super::ExportConfig;
+use super::{ExportConfig, ExportOptions, LatexStyle, cell_text, is_numeric_column, numeric_text};
+use crate::error::{CommandResult, export_error, validation_error};
 use serde_json::Value;
 use std::fs::File;
 use std::io::{BufWriter, Write};
 
-/// Export data to LaTeX format (simplified - expects 2D array)
+/// How an output column is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnKind {
+    /// Left-aligned text
+    Text,
+    /// siunitx `S` column of plain numbers
+    Numeric,
+    /// Value column merged with its uncertainty column
+    Uncertainty(usize),
+}
+
+/// Export data to LaTeX format (expects 2D array)
 #[tauri::command]
 #[allow(
     clippy::needless_pass_by_value,
@@ -16,80 +30,376 @@ use std::io::{BufWriter, Write};
 pub fn export_to_latex(
     data: Vec<Value>,
     file_path: String,
-    _config: ExportConfig,
-) -> Result<(), String> {
-    if data.is_empty() {
-        return Err("No data to export".to_owned());
-    }
+    config: ExportConfig,
+) -> CommandResult<()> {
+    let latex = render_latex(&data, &config.options)
+        .map_err(|e| validation_error(e, Some("data".to_owned())))?;
 
     // Create file with buffered writer
-    let file = File::create(&file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+    let file = File::create(&file_path)
+        .map_err(|e| export_error(format!("Failed to create file: {e}")))?;
     let mut writer = BufWriter::new(file);
+    writer
+        .write_all(latex.as_bytes())
+        .map_err(|e| export_error(format!("Failed to write LaTeX: {e}")))?;
+    writer
+        .flush()
+        .map_err(|e| export_error(format!("Failed to flush writer: {e}")))?;
 
-    // LaTeX table environment
-    writeln!(writer, "\\begin{{table}}[h]").map_err(|e| format!("Failed to write LaTeX: {e}"))?;
-    writeln!(writer, "\\centering").map_err(|e| format!("Failed to write LaTeX: {e}"))?;
+    Ok(())
+}
+
+/// Render rows of cells as a LaTeX `table` environment
+fn render_latex(data: &[Value], options: &ExportOptions) -> Result<String, String> {
+    if data.is_empty() {
+        return Err("No data to export".to_owned());
+    }
+    let rows: Vec<&Vec<Value>> = data.iter().filter_map(Value::as_array).collect();
 
     // Determine number of columns
-    let num_cols = data
-        .iter()
-        .filter_map(|row| row.as_array().map(Vec::len))
-        .max()
-        .unwrap_or(0);
+    let num_cols = rows.iter().map(|row| row.len()).max().unwrap_or(0);
     if num_cols == 0 {
         return Err("No columns found in data".to_owned());
     }
+    validate_pairs(&options.uncertainty_column_pairs, num_cols)?;
 
-    // Default column alignment (left-aligned)
-    let column_alignment = "l".repeat(num_cols);
+    let (header, body) = if options.include_headers {
+        rows.split_first()
+            .map_or((None, rows.as_slice()), |(first, rest)| {
+                (Some(*first), rest)
+            })
+    } else {
+        (None, rows.as_slice())
+    };
+    let columns = column_layout(body, num_cols, options);
+    let booktabs = options.latex_style == LatexStyle::Booktabs;
 
-    // Tabular environment
-    writeln!(writer, "\\begin{{tabular}}{{{column_alignment}}}")
-        .map_err(|e| format!("Failed to write LaTeX: {e}"))?;
+    let packages: Vec<&str> = [(booktabs, "booktabs"), (options.latex_siunitx, "siunitx")]
+        .into_iter()
+        .filter_map(|(needed, package)| needed.then_some(package))
+        .collect();
+    let mut lines = Vec::new();
+    if !packages.is_empty() {
+        lines.push("% Required packages:".to_owned());
+        for package in packages {
+            lines.push(format!("% \\usepackage{{{package}}}"));
+        }
+    }
 
-    // Process data rows
-    for row in &data {
-        let Some(row_array) = row.as_array() else {
-            continue;
-        };
+    // LaTeX table environment
+    lines.push("\\begin{table}[h]".to_owned());
+    lines.push("\\centering".to_owned());
+    let column_spec: String = columns
+        .iter()
+        .map(|(_, kind)| match kind {
+            ColumnKind::Numeric => 'S',
+            ColumnKind::Uncertainty(_) if options.latex_siunitx => 'c',
+            _ => 'l',
+        })
+        .collect();
+    lines.push(format!("\\begin{{tabular}}{{{column_spec}}}"));
+    if booktabs {
+        lines.push("\\toprule".to_owned());
+    }
+
+    if let Some(header) = header {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|&(index, kind)| {
+                let text = header.get(index).map(cell_text).unwrap_or_default();
+                let escaped = latex_escape(&text);
+                // S columns would try to parse the header as a number.
+                if kind == ColumnKind::Numeric {
+                    format!("{{{escaped}}}")
+                } else {
+                    escaped
+                }
+            })
+            .collect();
+        lines.push(format!("{} \\\\", cells.join(" & ")));
+        lines.push(if booktabs { "\\midrule" } else { "\\hline" }.to_owned());
+    }
 
-        // Format row cells
-        let formatted_cells: Vec<String> = row_array
+    // Process data rows
+    for row in body {
+        let cells: Vec<String> = columns
             .iter()
-            .map(|cell| match cell {
-                Value::String(s) => latex_escape(s),
-                Value::Number(n) => n.to_string(),
-                Value::Bool(b) => b.to_string(),
-                Value::Null => String::new(),
-                _ => cell.to_string(),
+            .map(|&(index, kind)| {
+                let cell = row.get(index).unwrap_or(&Value::Null);
+                match kind {
+                    ColumnKind::Uncertainty(uncertainty_index) => format_measurement(
+                        cell,
+                        row.get(uncertainty_index).unwrap_or(&Value::Null),
+                        options.latex_siunitx,
+                    ),
+                    ColumnKind::Numeric => numeric_text(cell).unwrap_or_default(),
+                    ColumnKind::Text => format_cell(cell, options.latex_siunitx),
+                }
             })
             .collect();
+        lines.push(format!("{} \\\\", cells.join(" & ")));
+    }
 
-        // Write the row
-        let row_str = formatted_cells.join(" & ");
-        writeln!(writer, "{row_str} \\\\")
-            .map_err(|e| format!("Failed to write LaTeX row: {e}"))?;
+    if booktabs {
+        lines.push("\\bottomrule".to_owned());
     }
-    writeln!(writer, "\\end{{tabular}}").map_err(|e| format!("Failed to write LaTeX: {e}"))?;
-    writeln!(writer, "\\end{{table}}").map_err(|e| format!("Failed to write LaTeX: {e}"))?;
+    lines.push("\\end{tabular}".to_owned());
+    lines.push("\\end{table}".to_owned());
 
-    writer
-        .flush()
-        .map_err(|e| format!("Failed to flush writer: {e}"))?;
+    let mut latex = lines.join("\n");
+    latex.push('\n');
+    Ok(latex)
+}
 
+/// Check that pairs refer to existing, distinct columns used at most once
+fn validate_pairs(pairs: &[(usize, usize)], num_cols: usize) -> Result<(), String> {
+    let mut used = vec![false; num_cols];
+    for &(value, uncertainty) in pairs {
+        if value >= num_cols || uncertainty >= num_cols {
+            return Err(format!(
+                "Uncertainty pair ({value}, {uncertainty}) is outside the {num_cols} exported columns"
+            ));
+        }
+        if value == uncertainty || used[value] || used[uncertainty] {
+            return Err(format!(
+                "Uncertainty pair ({value}, {uncertainty}) reuses a column"
+            ));
+        }
+        used[value] = true;
+        used[uncertainty] = true;
+    }
     Ok(())
 }
 
+/// Source column and layout of each output column; uncertainty columns are
+/// folded into their value column
+fn column_layout(
+    body: &[&Vec<Value>],
+    num_cols: usize,
+    options: &ExportOptions,
+) -> Vec<(usize, ColumnKind)> {
+    (0..num_cols)
+        .filter(|&index| {
+            !options
+                .uncertainty_column_pairs
+                .iter()
+                .any(|&(_, uncertainty)| uncertainty == index)
+        })
+        .map(|index| {
+            let pair = options
+                .uncertainty_column_pairs
+                .iter()
+                .find(|&&(value, _)| value == index);
+            let kind = if let Some(&(_, uncertainty)) = pair {
+                ColumnKind::Uncertainty(uncertainty)
+            } else if options.latex_siunitx && is_numeric_column(body, index) {
+                ColumnKind::Numeric
+            } else {
+                ColumnKind::Text
+            };
+            (index, kind)
+        })
+        .collect()
+}
+
+/// A text-column cell: numbers go through `\num` (siunitx) or math mode
+/// (scientific notation without siunitx), everything else is escaped
+fn format_cell(cell: &Value, siunitx: bool) -> String {
+    match numeric_text(cell) {
+        Some(number) if siunitx => format!("\\num{{{number}}}"),
+        Some(number) => scientific_to_math(&number),
+        None => latex_escape(&cell_text(cell)),
+    }
+}
+
+/// `1.2e-3` as `$1.2 \times 10^{-3}$`; other numbers are returned unchanged
+fn scientific_to_math(number: &str) -> String {
+    number
+        .split_once(['e', 'E'])
+        .and_then(|(mantissa, exponent)| {
+            exponent
+                .parse::<i32>()
+                .ok()
+                .map(|exponent| format!("${mantissa} \\times 10^{{{exponent}}}$"))
+        })
+        .unwrap_or_else(|| number.to_owned())
+}
+
+/// A value with its uncertainty, rounded so the uncertainty keeps one
+/// significant digit (two when it starts with 1), e.g. `\num{12.34(5)}` or
+/// `$12.34 \pm 0.05$` without siunitx
+fn format_measurement(value_cell: &Value, uncertainty_cell: &Value, siunitx: bool) -> String {
+    let value = numeric_text(value_cell).and_then(|text| text.parse::<f64>().ok());
+    let uncertainty = numeric_text(uncertainty_cell)
+        .and_then(|text| text.parse::<f64>().ok())
+        .map(f64::abs)
+        .filter(|uncertainty| *uncertainty > 0.0);
+    let (Some(value), Some(uncertainty)) = (value, uncertainty) else {
+        return format_cell(value_cell, siunitx);
+    };
+
+    let decimals = uncertainty_decimals(uncertainty);
+    let precision = decimals.unsigned_abs() as usize;
+    let value_text = format!("{value:.precision$}");
+    if siunitx {
+        let digits = (uncertainty * 10_f64.powi(decimals)).round();
+        format!("\\num{{{value_text}({digits})}}")
+    } else {
+        format!("${value_text} \\pm {uncertainty:.precision$}$")
+    }
+}
+
+/// Decimal places that keep one significant digit of a positive finite
+/// `uncertainty` (two when it starts with 1), never fewer than zero
+#[allow(
+    clippy::cast_possible_truncation,
+    reason = "Decimal places of a finite uncertainty, clamped to a small range"
+)]
+pub fn uncertainty_decimals(uncertainty: f64) -> i32 {
+    let leading_digit = uncertainty / 10_f64.powf(uncertainty.log10().floor());
+    let significant = if leading_digit < 2.0 { 2.0 } else { 1.0 };
+    (significant - 1.0 - uncertainty.log10().floor()).clamp(0.0, 15.0) as i32
+}
+
 /// Escape LaTeX special characters
 fn latex_escape(text: &str) -> String {
-    text.replace('\\', "\\textbackslash{}")
-        .replace('&', "\\&")
-        .replace('%', "\\%")
-        .replace('$', "\\$")
-        .replace('#', "\\#")
-        .replace('_', "\\_")
-        .replace('{', "\\{")
-        .replace('}', "\\}")
-        .replace('~', "\\textasciitilde{}")
-        .replace('^', "\\textasciicircum{}")
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\n' | '\r' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn table() -> Vec<Value> {
+        vec![
+            json!(["Sample", "Length (m)", "Mass", "u(Mass)"]),
+            json!(["A_1", 12.5, 12.3412, 0.052]),
+            json!(["B", 1.2e-10, "1.5", "0.13"]),
+        ]
+    }
+
+    fn options(style: LatexStyle, siunitx: bool) -> ExportOptions {
+        ExportOptions {
+            include_headers: true,
+            delimiter: None,
+            latex_style: style,
+            latex_siunitx: siunitx,
+            uncertainty_column_pairs: vec![(2, 3)],
+            ..ExportOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_plain_table() {
+        assert_eq!(
+            render_latex(&table(), &options(LatexStyle::Plain, false)).unwrap(),
+            "\\begin{table}[h]
+\\centering
+\\begin{tabular}{lll}
+Sample & Length (m) & Mass \\\\
+\\hline
+A\\_1 & 12.5 & $12.34 \\pm 0.05$ \\\\
+B & $1.2 \\times 10^{-10}$ & $1.50 \\pm 0.13$ \\\\
+\\end{tabular}
+\\end{table}
+"
+        );
+    }
+
+    #[test]
+    fn test_booktabs_table() {
+        assert_eq!(
+            render_latex(&table(), &options(LatexStyle::Booktabs, false)).unwrap(),
+            "% Required packages:
+% \\usepackage{booktabs}
+\\begin{table}[h]
+\\centering
+\\begin{tabular}{lll}
+\\toprule
+Sample & Length (m) & Mass \\\\
+\\midrule
+A\\_1 & 12.5 & $12.34 \\pm 0.05$ \\\\
+B & $1.2 \\times 10^{-10}$ & $1.50 \\pm 0.13$ \\\\
+\\bottomrule
+\\end{tabular}
+\\end{table}
+"
+        );
+    }
+
+    #[test]
+    fn test_plain_siunitx_table() {
+        assert_eq!(
+            render_latex(&table(), &options(LatexStyle::Plain, true)).unwrap(),
+            "% Required packages:
+% \\usepackage{siunitx}
+\\begin{table}[h]
+\\centering
+\\begin{tabular}{lSc}
+Sample & {Length (m)} & Mass \\\\
+\\hline
+A\\_1 & 12.5 & \\num{12.34(5)} \\\\
+B & 1.2e-10 & \\num{1.50(13)} \\\\
+\\end{tabular}
+\\end{table}
+"
+        );
+    }
+
+    #[test]
+    fn test_booktabs_siunitx_table() {
+        let mut no_headers = options(LatexStyle::Booktabs, true);
+        no_headers.include_headers = false;
+        no_headers.uncertainty_column_pairs.clear();
+        assert_eq!(
+            render_latex(&table()[1..], &no_headers).unwrap(),
+            "% Required packages:
+% \\usepackage{booktabs}
+% \\usepackage{siunitx}
+\\begin{table}[h]
+\\centering
+\\begin{tabular}{lSSS}
+\\toprule
+A\\_1 & 12.5 & 12.3412 & 0.052 \\\\
+B & 1.2e-10 & 1.5 & 0.13 \\\\
+\\bottomrule
+\\end{tabular}
+\\end{table}
+"
+        );
+    }
+
+    #[test]
+    fn test_text_cells_are_escaped() {
+        let data = vec![json!([
+            "100% & {more} #1_a^2 ~ $x$ \\end{table}",
+            "line\nbreak"
+        ])];
+        let latex = render_latex(&data, &ExportOptions::default()).unwrap();
+        assert!(latex.contains(
+            "100\\% \\& \\{more\\} \\#1\\_a\\textasciicircum{}2 \\textasciitilde{} \\$x\\$ \\textbackslash{}end\\{table\\} & line break \\\\"
+        ));
+
+        let mut bad_pairs = options(LatexStyle::Plain, true);
+        bad_pairs.uncertainty_column_pairs = vec![(0, 7)];
+        assert!(render_latex(&table(), &bad_pairs).is_err());
+        bad_pairs.uncertainty_column_pairs = vec![(0, 1), (1, 2)];
+        assert!(render_latex(&table(), &bad_pairs).is_err());
+    }
 }
@@ -3,6 +3,7 @@
 // Exports data to delimited text formats (2D array)
 
 use super::{ExportConfig, ExportFormat};
+use crate::error::{CommandResult, export_error, validation_error};
 use serde_json::{Value, to_string};
 use std::fs::File;
 use std::io::{BufWriter, Write};
@@ -17,20 +18,26 @@ pub fn export_to_text(
     data: Vec<Value>,
     file_path: String,
     config: ExportConfig,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     // Determine delimiter based on format
     let delimiter = match config.format {
         ExportFormat::Csv => config.options.delimiter.as_deref().unwrap_or(","),
         ExportFormat::Tsv => "\t",
         ExportFormat::Txt => config.options.delimiter.as_deref().unwrap_or("|"),
-        _ => return Err("Invalid format for text export".to_owned()),
+        _ => {
+            return Err(validation_error(
+                "Invalid format for text export",
+                Some("format".to_owned()),
+            ));
+        }
     };
 
     let quote_char = '"';
     let line_ending = "\r\n";
 
     // Create file with buffered writer for performance
-    let file = File::create(&file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+    let file = File::create(&file_path)
+        .map_err(|e| export_error(format!("Failed to create file: {e}")))?;
     let mut writer = BufWriter::new(file);
 
     // Export data rows - each element should be an array
@@ -46,12 +53,12 @@ pub fn export_to_text(
             .collect();
 
         write!(writer, "{}{}", formatted_row.join(delimiter), line_ending)
-            .map_err(|e| format!("Failed to write row: {e}"))?;
+            .map_err(|e| export_error(format!("Failed to write row: {e}")))?;
     }
 
     writer
         .flush()
-        .map_err(|e| format!("Failed to flush writer: {e}"))?;
+        .map_err(|e| export_error(format!("Failed to flush writer: {e}")))?;
 
     Ok(())
 }
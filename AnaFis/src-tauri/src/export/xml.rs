@@ -0,0 +1,306 @@
+// XML format export
+//
+// Exports data (2D array) as an XML 1.0 document: a root element holding one
+// element per row. Cells become child elements of the row, or attributes of
+// it, named after the header row (`column1`, `column2`, ... without one).
+// Sheet objects `{ "name": ..., "rows": [[...]] }` produce one
+// `<sheet name="...">` element per sheet. Header names are turned into valid
+// XML names, and characters XML 1.0 does not allow are dropped.
+
+use super::{ExportConfig, ExportOptions, Sheet, cell_text, sheets_from_data};
+use crate::error::{CommandResult, export_error, validation_error};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+/// Root element name when none is configured
+const DEFAULT_ROOT_ELEMENT: &str = "data";
+
+/// Row element name when none is configured
+const DEFAULT_ROW_ELEMENT: &str = "row";
+
+/// Export data to XML format (2D array or sheet objects)
+#[tauri::command]
+#[allow(
+    clippy::needless_pass_by_value,
+    reason = "Tauri commands require owned types for arguments"
+)]
+pub fn export_to_xml(
+    data: Vec<Value>,
+    file_path: String,
+    config: ExportConfig,
+) -> CommandResult<()> {
+    let options = &config.options;
+    let sheets =
+        sheets_from_data(&data).map_err(|e| validation_error(e, Some("data".to_owned())))?;
+    let root = element_name(&options.xml_root_element, DEFAULT_ROOT_ELEMENT)
+        .map_err(|e| validation_error(e, Some("xmlRootElement".to_owned())))?;
+    let row = element_name(&options.xml_row_element, DEFAULT_ROW_ELEMENT)
+        .map_err(|e| validation_error(e, Some("xmlRowElement".to_owned())))?;
+
+    let file = File::create(&file_path)
+        .map_err(|e| export_error(format!("Failed to create file: {e}")))?;
+    let mut writer = BufWriter::new(file);
+    write_xml(&mut writer, &sheets, root, row, options)
+        .map_err(|e| export_error(format!("Failed to write XML: {e}")))?;
+    writer
+        .flush()
+        .map_err(|e| export_error(format!("Failed to flush writer: {e}")))?;
+
+    Ok(())
+}
+
+/// Configured element name, or `default` when blank
+fn element_name<'name>(
+    configured: &'name str,
+    default: &'static str,
+) -> Result<&'name str, String> {
+    let trimmed = configured.trim();
+    if trimmed.is_empty() {
+        return Ok(default);
+    }
+    if xml_name(trimmed).as_deref() == Some(trimmed) {
+        Ok(trimmed)
+    } else {
+        Err(format!("'{trimmed}' is not a valid XML element name"))
+    }
+}
+
+/// Write the document: declaration, root element and one element per row
+fn write_xml<W: Write>(
+    out: &mut W,
+    sheets: &[Sheet<'_>],
+    root: &str,
+    row_element: &str,
+    options: &ExportOptions,
+) -> io::Result<()> {
+    out.write_all(b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n")?;
+    writeln!(out, "<{root}>")?;
+    for sheet in sheets {
+        if let Some(name) = &sheet.name {
+            writeln!(out, "  <sheet name=\"{}\">", xml_escape(name))?;
+            write_rows(out, &sheet.rows, row_element, "    ", options)?;
+            out.write_all(b"  </sheet>\n")?;
+        } else {
+            write_rows(out, &sheet.rows, row_element, "  ", options)?;
+        }
+    }
+    writeln!(out, "</{root}>")
+}
+
+/// Write a sheet's rows, padding short rows to the sheet width
+fn write_rows<W: Write>(
+    out: &mut W,
+    rows: &[&Vec<Value>],
+    row_element: &str,
+    indent: &str,
+    options: &ExportOptions,
+) -> io::Result<()> {
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let (header, body) = if options.include_headers {
+        rows.split_first()
+            .map_or((None, rows), |(first, rest)| (Some(first.as_slice()), rest))
+    } else {
+        (None, rows)
+    };
+    let columns = column_names(header, width);
+
+    for row in body {
+        let cells = columns
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (name, row.get(index).map(cell_text).unwrap_or_default()));
+        if options.xml_cell_as_attribute {
+            write!(out, "{indent}<{row_element}")?;
+            for (name, text) in cells {
+                write!(out, " {name}=\"{}\"", xml_escape(&text))?;
+            }
+            out.write_all(b"/>\n")?;
+        } else {
+            writeln!(out, "{indent}<{row_element}>")?;
+            for (name, text) in cells {
+                if text.is_empty() {
+                    writeln!(out, "{indent}  <{name}/>")?;
+                } else {
+                    writeln!(out, "{indent}  <{name}>{}</{name}>", xml_escape(&text))?;
+                }
+            }
+            writeln!(out, "{indent}</{row_element}>")?;
+        }
+    }
+    Ok(())
+}
+
+/// Unique XML names of the columns, from the header row where it has text
+fn column_names(header: Option<&[Value]>, width: usize) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(width);
+    for index in 0..width {
+        let base = header
+            .and_then(|cells| cells.get(index))
+            .and_then(|cell| xml_name(&cell_text(cell)))
+            .unwrap_or_else(|| format!("column{}", index + 1));
+        // Attributes must be unique, so repeated headers get a suffix
+        let mut name = base.clone();
+        let mut copy = 1;
+        while names.contains(&name) {
+            copy += 1;
+            name = format!("{base}_{copy}");
+        }
+        names.push(name);
+    }
+    names
+}
+
+/// `text` as an XML name: invalid characters become '_' and names that do
+/// not start with a letter or '_' get a leading '_'; `None` when blank
+fn xml_name(text: &str) -> Option<String> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let mut name: String = trimmed
+        .chars()
+        .map(|character| {
+            if is_name_start_char(character)
+                || character.is_ascii_digit()
+                || matches!(character, '-' | '.')
+            {
+                character
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !name.starts_with(is_name_start_char) {
+        name.insert(0, '_');
+    }
+    Some(name)
+}
+
+/// Letters and '_'; non-ASCII letters below U+00C0 are not XML name characters
+fn is_name_start_char(character: char) -> bool {
+    character == '_'
+        || character.is_ascii_alphabetic()
+        || (character >= '\u{C0}' && character.is_alphabetic())
+}
+
+/// Escape XML special characters and drop characters XML 1.0 does not allow.
+/// Tabs and line breaks are written as character references so attribute
+/// values keep them.
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' => escaped.push_str("&#9;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\u{0}'..='\u{1F}' | '\u{FFFE}' | '\u{FFFF}' => {}
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(data: &[Value], options: &ExportOptions) -> String {
+        let sheets = sheets_from_data(data).unwrap();
+        let root = element_name(&options.xml_root_element, DEFAULT_ROOT_ELEMENT).unwrap();
+        let row = element_name(&options.xml_row_element, DEFAULT_ROW_ELEMENT).unwrap();
+        let mut out = Vec::new();
+        write_xml(&mut out, &sheets, root, row, options).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_cells_as_child_elements() {
+        let data = vec![
+            json!(["Time (s)", "2theta", "", "x"]),
+            json!([0.5, "a < b & c", null, "1"]),
+            json!([1]),
+        ];
+        let options = ExportOptions {
+            include_headers: true,
+            xml_root_element: "measurements".to_owned(),
+            xml_row_element: " point ".to_owned(),
+            ..ExportOptions::default()
+        };
+        let xml = render(&data, &options);
+        assert_eq!(
+            xml,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <measurements>\n\
+             \x20 <point>\n\
+             \x20   <Time__s_>0.5</Time__s_>\n\
+             \x20   <_2theta>a &lt; b &amp; c</_2theta>\n\
+             \x20   <column3/>\n\
+             \x20   <x>1</x>\n\
+             \x20 </point>\n\
+             \x20 <point>\n\
+             \x20   <Time__s_>1</Time__s_>\n\
+             \x20   <_2theta/>\n\
+             \x20   <column3/>\n\
+             \x20   <x/>\n\
+             \x20 </point>\n\
+             </measurements>\n"
+        );
+    }
+
+    #[test]
+    fn test_cells_as_attributes() {
+        let data = vec![
+            json!(["name", "name", "note"]),
+            json!(["\"quoted\"", "it's", "line\nbreak\u{1}"]),
+        ];
+        let options = ExportOptions {
+            include_headers: true,
+            xml_cell_as_attribute: true,
+            ..ExportOptions::default()
+        };
+        let xml = render(&data, &options);
+        assert!(xml.contains(
+            "  <row name=\"&quot;quoted&quot;\" name_2=\"it&apos;s\" note=\"line&#10;break\"/>\n"
+        ));
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<data>\n"));
+
+        // Without headers the columns are numbered.
+        let plain = render(
+            &[json!([1, 2])],
+            &ExportOptions {
+                xml_cell_as_attribute: true,
+                ..ExportOptions::default()
+            },
+        );
+        assert!(plain.contains("<row column1=\"1\" column2=\"2\"/>"));
+    }
+
+    #[test]
+    fn test_one_element_per_sheet() {
+        let data = vec![
+            json!({ "name": "Raw \"run\" 1", "rows": [["v"], [1]] }),
+            json!({ "rows": [] }),
+        ];
+        let options = ExportOptions {
+            include_headers: true,
+            ..ExportOptions::default()
+        };
+        let xml = render(&data, &options);
+        assert!(xml.contains(
+            "<data>\n  <sheet name=\"Raw &quot;run&quot; 1\">\n    <row>\n      <v>1</v>\n    </row>\n  </sheet>\n"
+        ));
+        assert!(xml.contains("  <sheet name=\"Sheet 2\">\n  </sheet>\n</data>\n"));
+
+        assert!(element_name("1row", DEFAULT_ROW_ELEMENT).is_err());
+        assert!(element_name("my row", DEFAULT_ROW_ELEMENT).is_err());
+        assert_eq!(element_name("", DEFAULT_ROW_ELEMENT).unwrap(), "row");
+    }
+}
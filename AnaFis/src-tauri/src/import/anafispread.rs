@@ -6,19 +6,36 @@
 // File Structure:
 // - Bytes 0-7:   Magic number "ANAFIS\x01\x00" (identifies file type)
 // - Bytes 8-11:  Format version (u32, little-endian)
-// - Bytes 12+:   Gzip-compressed JSON data
+// - Version 1:   Gzip-compressed JSON data from byte 12
+// - Version 2+:  Header length (u32), JSON envelope header, then the payload
+//
+// Version 2 payloads are verified against the SHA-256 checksums of the header
+// before decompression. Documents older than the current version are brought
+// up to date one version at a time through `MIGRATIONS`.
 
+use crate::error::{
+    CommandError, CommandResult, codes, file_not_found, io_error, validation_error,
+};
+use crate::export::anafispread::{
+    CHECKSUM_CHUNK_SIZE, EnvelopeHeader, FORMAT_VERSION, MAGIC_NUMBER, PayloadCompression,
+    sha256_hex,
+};
 use flate2::read::GzDecoder;
-use serde_json::{Value, from_reader};
-use std::fs::File;
-use std::io::{BufReader, Read};
+use serde_json::{Value, from_reader, from_slice};
+use std::fs::{metadata, read};
+use std::io::ErrorKind;
 
 // Maximum file size: 100MB (reasonable limit for spreadsheet files)
 const MAX_FILE_SIZE: u64 = 100 * 1024 * 1024;
 
-/// Magic number for .anafispread files: "ANAFIS" + version marker
-const MAGIC_NUMBER: &[u8; 8] = b"ANAFIS\x01\x00";
-const SUPPORTED_VERSION: u32 = 1;
+/// Bytes before the envelope header: magic number and format version
+const PREAMBLE_LENGTH: usize = 12;
+
+/// Upgrades a document of version n (index n - 1) to version n + 1
+type Migration = fn(Value) -> Result<Value, String>;
+
+/// Migration registry; `MIGRATIONS.len() + 1` must equal `FORMAT_VERSION`
+const MIGRATIONS: [Migration; 1] = [migrate_v1_to_v2];
 
 /// Import data from `AnaFis` Spreadsheet (.anafispread) format
 ///
@@ -28,58 +45,57 @@ const SUPPORTED_VERSION: u32 = 1;
     clippy::needless_pass_by_value,
     reason = "Tauri commands require owned types for arguments"
 )]
-pub fn import_anafis_spread(file_path: String) -> Result<Value, String> {
-    // Open the file
-    let mut file = File::open(&file_path).map_err(|e| format!("Failed to open file: {e}"))?;
-
+pub fn import_anafis_spread(file_path: String) -> CommandResult<Value> {
     // Check file size for security
-    let metadata = file
-        .metadata()
-        .map_err(|e| format!("Failed to read file metadata: {e}"))?;
+    let metadata = metadata(&file_path).map_err(|e| {
+        if e.kind() == ErrorKind::NotFound {
+            file_not_found(file_path.clone())
+        } else {
+            io_error(
+                Some(file_path.clone()),
+                format!("Failed to read file metadata: {e}"),
+            )
+        }
+    })?;
 
     if metadata.len() > MAX_FILE_SIZE {
         #[allow(
             clippy::integer_division,
             reason = "Integer division is acceptable for approximate MB display"
         )]
-        return Err(format!(
-            "File too large: {} MB (maximum: {} MB)",
-            metadata.len() / (1024 * 1024),
-            MAX_FILE_SIZE / (1024 * 1024)
+        return Err(validation_error(
+            format!(
+                "File too large: {} MB (maximum: {} MB)",
+                metadata.len() / (1024 * 1024),
+                MAX_FILE_SIZE / (1024 * 1024)
+            ),
+            Some("file_path".to_owned()),
         ));
     }
 
-    // Read and verify magic number
-    let mut magic_buf = [0_u8; 8];
-    file.read_exact(&mut magic_buf)
-        .map_err(|e| format!("Failed to read file header: {e}"))?;
-
-    if &magic_buf != MAGIC_NUMBER {
-        return Err(
-            "Invalid file format: Not an AnaFis Spreadsheet file (magic number mismatch)"
-                .to_owned(),
-        );
-    }
-
-    // Read and verify format version
-    let mut version_buf = [0_u8; 4];
-    file.read_exact(&mut version_buf)
-        .map_err(|e| format!("Failed to read version: {e}"))?;
-
-    let version = u32::from_le_bytes(version_buf);
-    if version != SUPPORTED_VERSION {
-        return Err(format!(
-            "Unsupported file version: {version} (expected: {SUPPORTED_VERSION})"
-        ));
-    }
+    let bytes = read(&file_path)
+        .map_err(|e| io_error(Some(file_path.clone()), format!("Failed to read file: {e}")))?;
 
-    // Decompress the remaining data (all .anafispread files are gzip compressed)
-    let decoder = GzDecoder::new(file);
-    let reader = BufReader::new(decoder);
+    decode_anafispread(&bytes).map_err(|detail| CommandError::Io {
+        code: codes::FILE_CORRUPTED,
+        path: Some(file_path),
+        detail,
+    })
+}
 
-    // Parse JSON
-    let data: Value =
-        from_reader(reader).map_err(|e| format!("Failed to parse AnaFis Spreadsheet file: {e}"))?;
+/// Workbook snapshot of a complete .anafispread file, migrated to the current version
+pub fn decode_anafispread(bytes: &[u8]) -> Result<Value, String> {
+    let version = format_version(bytes)?;
+    let document = match version {
+        1 => decompress(&bytes[PREAMBLE_LENGTH..], PayloadCompression::Gzip)?,
+        2..=FORMAT_VERSION => decode_envelope(bytes)?,
+        _ => {
+            return Err(format!(
+                "Unsupported file version: {version} (newest supported: {FORMAT_VERSION})"
+            ));
+        }
+    };
+    let data = migrate(document, version)?;
 
     // Validate format in JSON metadata
     if let Some(format) = data.get("format").and_then(|f| f.as_str()) {
@@ -98,3 +114,191 @@ pub fn import_anafis_spread(file_path: String) -> Result<Value, String> {
     // Return the full IWorkbookData snapshot
     Ok(workbook.clone())
 }
+
+/// Format version from the first bytes of a file, after checking the magic number
+pub fn format_version(bytes: &[u8]) -> Result<u32, String> {
+    let Some((magic, rest)) = bytes.split_first_chunk::<8>() else {
+        return Err("Failed to read file header: file is too short".to_owned());
+    };
+    if magic != MAGIC_NUMBER {
+        return Err(
+            "Invalid file format: Not an AnaFis Spreadsheet file (magic number mismatch)"
+                .to_owned(),
+        );
+    }
+    let Some((version, _)) = rest.split_first_chunk::<4>() else {
+        return Err("Failed to read version: file is too short".to_owned());
+    };
+    Ok(u32::from_le_bytes(*version))
+}
+
+/// Read the envelope header, verify the payload checksums and decompress it
+fn decode_envelope(bytes: &[u8]) -> Result<Value, String> {
+    let header_start = PREAMBLE_LENGTH + 4;
+    let header_length = bytes
+        .get(PREAMBLE_LENGTH..header_start)
+        .and_then(|length| length.try_into().ok())
+        .map(u32::from_le_bytes)
+        .ok_or("Failed to read envelope header length: file is too short")?;
+    let payload_start = header_start + header_length as usize;
+    let header: EnvelopeHeader = bytes
+        .get(header_start..payload_start)
+        .ok_or_else(|| {
+            format!(
+                "File truncated inside the envelope header at byte {}",
+                bytes.len()
+            )
+        })
+        .and_then(|header| {
+            from_slice(header).map_err(|e| format!("Invalid envelope header: {e}"))
+        })?;
+
+    let payload = &bytes[payload_start..];
+    let expected_length = usize::try_from(header.payload_length)
+        .map_err(|e| format!("Invalid payload length in envelope header: {e}"))?;
+    if payload.len() != expected_length {
+        return Err(format!(
+            "Payload length mismatch: expected {expected_length} bytes from byte {payload_start}, found {} (file ends at byte {})",
+            payload.len(),
+            bytes.len()
+        ));
+    }
+    verify_payload(payload, payload_start, &header)?;
+    decompress(payload, header.compression)
+}
+
+/// Compare the payload with the header checksums, locating the first corrupted chunk
+fn verify_payload(payload: &[u8], offset: usize, header: &EnvelopeHeader) -> Result<(), String> {
+    if payload.chunks(CHECKSUM_CHUNK_SIZE).count() == header.chunk_checksums.len() {
+        for (index, (chunk, expected)) in payload
+            .chunks(CHECKSUM_CHUNK_SIZE)
+            .zip(&header.chunk_checksums)
+            .enumerate()
+        {
+            if sha256_hex(chunk) != *expected {
+                let start = offset + index * CHECKSUM_CHUNK_SIZE;
+                return Err(format!(
+                    "Checksum mismatch: file is corrupted between bytes {start} and {}",
+                    start + chunk.len()
+                ));
+            }
+        }
+    }
+    if sha256_hex(payload) != header.checksum {
+        return Err(format!(
+            "Checksum mismatch: payload starting at byte {offset} is corrupted"
+        ));
+    }
+    Ok(())
+}
+
+/// Decompress and parse a JSON document
+fn decompress(payload: &[u8], compression: PayloadCompression) -> Result<Value, String> {
+    let parsed = match compression {
+        PayloadCompression::Gzip => from_reader(GzDecoder::new(payload)),
+        PayloadCompression::Zstd => {
+            let decoder = zstd::Decoder::new(payload)
+                .map_err(|e| format!("Failed to decompress AnaFis Spreadsheet file: {e}"))?;
+            from_reader(decoder)
+        }
+    };
+    parsed.map_err(|e| format!("Failed to parse AnaFis Spreadsheet file: {e}"))
+}
+
+/// Apply the migrations from `version` up to `FORMAT_VERSION`
+fn migrate(document: Value, version: u32) -> Result<Value, String> {
+    let first = version.saturating_sub(1) as usize;
+    MIGRATIONS
+        .get(first..)
+        .unwrap_or_default()
+        .iter()
+        .try_fold(document, |document, migration| migration(document))
+}
+
+/// Version 2 moved the version and compression flags into the envelope header
+fn migrate_v1_to_v2(mut document: Value) -> Result<Value, String> {
+    let object = document
+        .as_object_mut()
+        .ok_or("Version 1 document is not a JSON object")?;
+    object.remove("version");
+    object.remove("compressed");
+    Ok(document)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use crate::export::anafispread::encode_anafispread;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use serde_json::json;
+    use std::io::Write;
+
+    fn workbook() -> Value {
+        json!({ "id": "wb-1", "sheets": { "s1": { "cellData": { "0": { "0": { "v": 1.5 } } } } } })
+    }
+
+    #[test]
+    fn test_legacy_file_loads() {
+        let legacy = json!({
+            "version": "1.0",
+            "format": "anafis_spreadsheet",
+            "compressed": true,
+            "metadata": { "creator": "AnaFis" },
+            "workbook": workbook()
+        });
+        let mut bytes = MAGIC_NUMBER.to_vec();
+        bytes.extend_from_slice(&1_u32.to_le_bytes());
+        let mut encoder = GzEncoder::new(bytes, Compression::default());
+        encoder.write_all(legacy.to_string().as_bytes()).unwrap();
+        let file = encoder.finish().unwrap();
+
+        assert_eq!(format_version(&file).unwrap(), 1);
+        assert_eq!(decode_anafispread(&file).unwrap(), workbook());
+        assert_eq!(
+            migrate(legacy, 1).unwrap(),
+            json!({
+                "format": "anafis_spreadsheet",
+                "metadata": { "creator": "AnaFis" },
+                "workbook": workbook()
+            })
+        );
+    }
+
+    #[test]
+    fn test_current_round_trip() {
+        for compression in [PayloadCompression::Gzip, PayloadCompression::Zstd] {
+            let file = encode_anafispread(&workbook(), compression).unwrap();
+            assert_eq!(format_version(&file).unwrap(), FORMAT_VERSION);
+            assert_eq!(decode_anafispread(&file).unwrap(), workbook());
+        }
+        assert_eq!(MIGRATIONS.len() + 1, FORMAT_VERSION as usize);
+    }
+
+    #[test]
+    fn test_corrupted_file_reports_offset() {
+        let file = encode_anafispread(&workbook(), PayloadCompression::Zstd).unwrap();
+        let mut corrupted = file.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xFF;
+        let error = decode_anafispread(&corrupted).unwrap_err();
+        assert!(error.starts_with("Checksum mismatch"), "{error}");
+        assert!(error.contains(&format!("and {}", file.len())), "{error}");
+
+        let truncated = decode_anafispread(&file[..last]).unwrap_err();
+        assert!(
+            truncated.contains(&format!("file ends at byte {last}")),
+            "{truncated}"
+        );
+
+        let mut future = file;
+        future[8..12].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        assert!(
+            decode_anafispread(&future)
+                .unwrap_err()
+                .contains("Unsupported")
+        );
+        assert!(decode_anafispread(b"NOTANAFIS\0\0\0").is_err());
+    }
+}
@@ -0,0 +1,573 @@
+// Column type inference for imported value grids
+//
+// Classifies each column of a grid returned by the importers as integer,
+// float, boolean, date or text from the share of its non-blank cells that
+// parse as each type. Blank cells never count against a type.
+//
+// Numbers may use thousands separators. The decimal separator is chosen per
+// column, since "1,234" is a thousand in a comma-delimited file and a
+// decimal in a semicolon-delimited one: the separator under which more cells
+// parse wins, a point winning ties. Dates try every configured format and
+// report the other formats that parse the same cells, such as dd/mm/yyyy
+// against mm/dd/yyyy when no day exceeds 12.
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Date formats tried when none are given, in order of preference
+const DEFAULT_DATE_FORMATS: [&str; 7] = [
+    "%Y-%m-%d",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S",
+    "%d/%m/%Y",
+    "%m/%d/%Y",
+    "%d.%m.%Y",
+    "%Y/%m/%d",
+];
+
+/// Share of non-blank cells a type must parse to be inferred when no
+/// minimum is given
+const DEFAULT_MIN_SUCCESS_RATE: f64 = 0.9;
+
+/// Type inferred for a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ColumnType {
+    /// No non-blank cells
+    Empty,
+    /// Whole numbers written without a fraction or exponent
+    Integer,
+    /// Numbers with a fraction or exponent
+    Float,
+    /// true/false or yes/no
+    Boolean,
+    /// Dates in one of the configured formats
+    Date,
+    /// No cell parses as another type
+    Text,
+    /// No type parses enough of the cells
+    Mixed,
+}
+
+impl ColumnType {
+    /// Lowercase name used in suggestions
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Empty => "empty",
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Boolean => "boolean",
+            Self::Date => "date",
+            Self::Text => "text",
+            Self::Mixed => "mixed",
+        }
+    }
+}
+
+/// Options of column type inference
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ColumnTypeOptions {
+    /// Whether the first row holds column names
+    pub has_header: bool,
+    /// chrono format strings tried for dates, in order of preference
+    pub date_formats: Vec<String>,
+    /// Decimal separator of numbers ('.' or ','); detected per column if unset
+    pub decimal_separator: Option<char>,
+    /// Share of non-blank cells a type must parse, in (0, 1]
+    pub min_success_rate: f64,
+}
+
+impl Default for ColumnTypeOptions {
+    fn default() -> Self {
+        Self {
+            has_header: false,
+            date_formats: DEFAULT_DATE_FORMATS.map(str::to_owned).to_vec(),
+            decimal_separator: None,
+            min_success_rate: DEFAULT_MIN_SUCCESS_RATE,
+        }
+    }
+}
+
+/// Inferred type of one column
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnTypeInference {
+    /// Column index in the grid
+    pub column: usize,
+    /// Header of the column, if the grid has one
+    pub name: Option<String>,
+    /// Inferred type
+    pub inferred_type: ColumnType,
+    /// Format of date columns
+    pub date_format: Option<String>,
+    /// Other formats parsing as many cells as `date_format`
+    pub ambiguous_date_formats: Vec<String>,
+    /// Decimal separator of numeric columns
+    pub decimal_separator: Option<char>,
+    /// Number of non-blank cells
+    pub non_blank: usize,
+    /// Share of non-blank cells parsing as the best matching type
+    pub success_rate: f64,
+    /// Grid rows whose non-blank cell does not parse as the best matching type
+    pub non_conforming_rows: Vec<usize>,
+    /// Conversions worth applying before using the column
+    pub suggestions: Vec<String>,
+}
+
+/// What one non-blank cell parses as
+struct CellParse {
+    boolean: bool,
+    /// Whether the cell is written as an integer, if it parses as a number
+    /// under '.' and ',' decimals
+    point: Option<bool>,
+    comma: Option<bool>,
+    /// Parses under each date format
+    dates: Vec<bool>,
+}
+
+impl CellParse {
+    /// Whether the cell parses as a number under `decimal`, and as an integer
+    const fn number(&self, decimal: char) -> Option<bool> {
+        if decimal == ',' {
+            self.comma
+        } else {
+            self.point
+        }
+    }
+}
+
+/// Infer the type of every column of `rows`
+///
+/// # Errors
+/// Returns an error if no date format is given, a decimal separator other
+/// than '.' or ',' is given, or the minimum success rate is outside (0, 1].
+pub fn infer_column_types(
+    rows: &[Vec<Value>],
+    options: &ColumnTypeOptions,
+) -> Result<Vec<ColumnTypeInference>, String> {
+    if options.date_formats.is_empty() {
+        return Err("At least one date format is required".to_owned());
+    }
+    if options
+        .decimal_separator
+        .is_some_and(|separator| !matches!(separator, '.' | ','))
+    {
+        return Err("Decimal separator must be '.' or ','".to_owned());
+    }
+    if !(options.min_success_rate > 0.0 && options.min_success_rate <= 1.0) {
+        return Err(format!(
+            "Minimum success rate must be in (0, 1], got {}",
+            options.min_success_rate
+        ));
+    }
+
+    let first_row = usize::from(options.has_header);
+    let column_count = rows.iter().map(Vec::len).max().unwrap_or(0);
+    Ok((0..column_count)
+        .map(|column| {
+            let name = rows
+                .first()
+                .filter(|_| options.has_header)
+                .and_then(|header| header.get(column))
+                .map(cell_text);
+            let cells: Vec<(usize, CellParse)> = rows
+                .iter()
+                .enumerate()
+                .skip(first_row)
+                .filter_map(|(row, values)| {
+                    let parse = parse_cell(values.get(column)?, &options.date_formats)?;
+                    Some((row, parse))
+                })
+                .collect();
+            infer_column(column, name, &cells, options)
+        })
+        .collect())
+}
+
+/// Inferred type of one column from its parsed non-blank cells
+fn infer_column(
+    column: usize,
+    name: Option<String>,
+    cells: &[(usize, CellParse)],
+    options: &ColumnTypeOptions,
+) -> ColumnTypeInference {
+    let count = |matches: &dyn Fn(&CellParse) -> bool| {
+        cells.iter().filter(|(_, parse)| matches(parse)).count()
+    };
+
+    let point_count = count(&|parse| parse.point.is_some());
+    let comma_count = count(&|parse| parse.comma.is_some());
+    let decimal_separator = options
+        .decimal_separator
+        .unwrap_or(if comma_count > point_count { ',' } else { '.' });
+    let number = |parse: &CellParse| parse.number(decimal_separator);
+    let number_count = count(&|parse| number(parse).is_some());
+    let integer_count = count(&|parse| number(parse) == Some(true));
+
+    let date_counts: Vec<usize> = (0..options.date_formats.len())
+        .map(|format| count(&|parse| parse.dates[format]))
+        .collect();
+    let date_count = date_counts.iter().copied().max().unwrap_or(0);
+    // First format among those parsing the most cells
+    let date_format = date_counts
+        .iter()
+        .position(|&format_count| format_count == date_count)
+        .unwrap_or(0);
+
+    // Ties go to the earlier type
+    let numeric_type = if integer_count == number_count {
+        ColumnType::Integer
+    } else {
+        ColumnType::Float
+    };
+    let candidates = [
+        (ColumnType::Boolean, count(&|parse| parse.boolean)),
+        (numeric_type, number_count),
+        (ColumnType::Date, date_count),
+    ];
+    let (best_type, best_count) = candidates
+        .iter()
+        .copied()
+        .reduce(|best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+        .unwrap_or((ColumnType::Text, 0));
+
+    let matches_best = |parse: &CellParse| match best_type {
+        ColumnType::Boolean => parse.boolean,
+        ColumnType::Date => parse.dates[date_format],
+        _ => number(parse).is_some(),
+    };
+    #[allow(clippy::cast_precision_loss, reason = "Cell counts casting to f64")]
+    let success_rate = if cells.is_empty() || best_count == 0 {
+        1.0
+    } else {
+        best_count as f64 / cells.len() as f64
+    };
+    let inferred_type = if cells.is_empty() {
+        ColumnType::Empty
+    } else if best_count == 0 {
+        ColumnType::Text
+    } else if success_rate < options.min_success_rate {
+        ColumnType::Mixed
+    } else {
+        best_type
+    };
+    let non_conforming_rows: Vec<usize> = if best_count == 0 {
+        Vec::new()
+    } else {
+        cells
+            .iter()
+            .filter(|(_, parse)| !matches_best(parse))
+            .map(|&(row, _)| row)
+            .collect()
+    };
+
+    let is_date = inferred_type == ColumnType::Date;
+    let ambiguous_date_formats: Vec<String> = if is_date {
+        date_counts
+            .iter()
+            .enumerate()
+            .filter(|&(format, &format_count)| format != date_format && format_count == date_count)
+            .map(|(format, _)| options.date_formats[format].clone())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let mut inference = ColumnTypeInference {
+        column,
+        name,
+        inferred_type,
+        date_format: is_date.then(|| options.date_formats[date_format].clone()),
+        ambiguous_date_formats,
+        decimal_separator: matches!(inferred_type, ColumnType::Integer | ColumnType::Float)
+            .then_some(decimal_separator),
+        non_blank: cells.len(),
+        success_rate,
+        non_conforming_rows,
+        suggestions: Vec::new(),
+    };
+    inference.suggestions = suggestions(&inference, best_type);
+    inference
+}
+
+/// Conversions worth applying to an inferred column; `best_type` is the type
+/// parsing most cells of mixed columns
+fn suggestions(inference: &ColumnTypeInference, best_type: ColumnType) -> Vec<String> {
+    let percent = (inference.success_rate * 100.0).floor();
+    let mut suggestions = Vec::new();
+    match inference.inferred_type {
+        ColumnType::Mixed => suggestions.push(format!(
+            "Only {percent}% parse as {}; keep the column as text",
+            best_type.label()
+        )),
+        ColumnType::Integer | ColumnType::Float | ColumnType::Boolean | ColumnType::Date
+            if !inference.non_conforming_rows.is_empty() =>
+        {
+            suggestions.push(format!(
+                "{percent}% parse as {}; coerce {} cells to null",
+                inference.inferred_type.label(),
+                inference.non_conforming_rows.len()
+            ));
+        }
+        _ => {}
+    }
+    if !inference.ambiguous_date_formats.is_empty() {
+        suggestions.push(format!(
+            "Dates also parse as {}; confirm the day and month order",
+            inference.ambiguous_date_formats.join(", ")
+        ));
+    }
+    suggestions
+}
+
+/// Text of a header cell
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// What a cell parses as, or `None` for blank cells
+fn parse_cell(value: &Value, date_formats: &[String]) -> Option<CellParse> {
+    let no_dates = || vec![false; date_formats.len()];
+    match value {
+        Value::Null => None,
+        Value::Bool(_) => Some(CellParse {
+            boolean: true,
+            point: None,
+            comma: None,
+            dates: no_dates(),
+        }),
+        Value::Number(number) => {
+            let integer = number.as_f64().is_some_and(|float| float.fract() == 0.0);
+            Some(CellParse {
+                boolean: false,
+                point: Some(integer),
+                comma: Some(integer),
+                dates: no_dates(),
+            })
+        }
+        Value::String(text) => {
+            let text = text.trim();
+            if text.is_empty() {
+                return None;
+            }
+            Some(CellParse {
+                boolean: ["true", "false", "yes", "no"]
+                    .iter()
+                    .any(|word| text.eq_ignore_ascii_case(word)),
+                point: parse_number(text, '.').map(|(_, integer)| integer),
+                comma: parse_number(text, ',').map(|(_, integer)| integer),
+                dates: date_formats
+                    .iter()
+                    .map(|format| {
+                        NaiveDateTime::parse_from_str(text, format).is_ok()
+                            || NaiveDate::parse_from_str(text, format).is_ok()
+                    })
+                    .collect(),
+            })
+        }
+        Value::Array(_) | Value::Object(_) => Some(CellParse {
+            boolean: false,
+            point: None,
+            comma: None,
+            dates: no_dates(),
+        }),
+    }
+}
+
+/// Value of a number written with `decimal` as decimal separator and
+/// optional thousands separators ("1,234.5", "1.234,5", "1 234,5"), and
+/// whether it is written as an integer
+fn parse_number(text: &str, decimal: char) -> Option<(f64, bool)> {
+    let (sign, unsigned) = text.strip_prefix('-').map_or_else(
+        || ("", text.strip_prefix('+').unwrap_or(text)),
+        |rest| ("-", rest),
+    );
+    let (body, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((body, exponent)) => (body, Some(exponent.parse::<i32>().ok()?)),
+        None => (unsigned, None),
+    };
+    let (whole, fraction) = match body.split_once(decimal) {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (body, None),
+    };
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_none_or(str::is_empty)) || !fraction.is_none_or(is_digits) {
+        return None;
+    }
+
+    let group_separators: &[char] = if decimal == ',' {
+        &['.', ' ', '\u{a0}']
+    } else {
+        &[',', ' ', '\u{a0}']
+    };
+    let digits = if let Some(separator) = whole.chars().find(|c| group_separators.contains(c)) {
+        // Thousands groups: 1 to 3 leading digits, then groups of exactly 3
+        let mut groups = whole.split(separator);
+        let leading = groups.next().unwrap_or_default();
+        let rest: Vec<&str> = groups.collect();
+        if exponent.is_some()
+            || !(1..=3).contains(&leading.len())
+            || !is_digits(leading)
+            || rest
+                .iter()
+                .any(|group| group.len() != 3 || !is_digits(group))
+        {
+            return None;
+        }
+        let mut digits = leading.to_owned();
+        digits.extend(rest);
+        digits
+    } else if is_digits(whole) {
+        whole.to_owned()
+    } else {
+        return None;
+    };
+
+    let normalized = format!(
+        "{sign}{}.{}e{}",
+        if digits.is_empty() { "0" } else { &digits },
+        fraction.filter(|part| !part.is_empty()).unwrap_or("0"),
+        exponent.unwrap_or(0)
+    );
+    let value = normalized.parse::<f64>().ok()?;
+    Some((value, fraction.is_none() && exponent.is_none()))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn column(values: &[Value]) -> ColumnTypeInference {
+        let rows: Vec<Vec<Value>> = values.iter().map(|value| vec![value.clone()]).collect();
+        infer_column_types(&rows, &ColumnTypeOptions::default())
+            .unwrap()
+            .remove(0)
+    }
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_number("1,234.5", '.'), Some((1234.5, false)));
+        assert_eq!(parse_number("1.234,5", ','), Some((1234.5, false)));
+        assert_eq!(parse_number("-12 345", '.'), Some((-12345.0, true)));
+        assert_eq!(parse_number("1,234", ','), Some((1.234, false)));
+        assert_eq!(parse_number("2.5e-3", '.'), Some((0.0025, false)));
+        assert_eq!(parse_number(".5", '.'), Some((0.5, false)));
+        assert_eq!(parse_number("12,34", '.'), None);
+        assert_eq!(parse_number("1,234e3", '.'), None);
+        assert_eq!(parse_number("inf", '.'), None);
+        assert_eq!(parse_number("-", '.'), None);
+    }
+
+    #[test]
+    fn test_ambiguous_dates_are_flagged() {
+        let ambiguous = column(&[json!("01/02/2024"), json!("03/04/2024"), json!(null)]);
+        assert_eq!(ambiguous.inferred_type, ColumnType::Date);
+        assert_eq!(ambiguous.date_format.as_deref(), Some("%d/%m/%Y"));
+        assert_eq!(ambiguous.ambiguous_date_formats, vec!["%m/%d/%Y"]);
+        assert_eq!(ambiguous.suggestions.len(), 1);
+
+        let day_first = column(&[json!("01/02/2024"), json!("25/12/2024")]);
+        assert_eq!(day_first.date_format.as_deref(), Some("%d/%m/%Y"));
+        assert!(day_first.ambiguous_date_formats.is_empty());
+
+        let iso = column(&[
+            json!("2024-01-02T10:30:00"),
+            json!("2024-01-03T08:00:00.250"),
+        ]);
+        assert_eq!(iso.inferred_type, ColumnType::Date);
+        assert_eq!(iso.date_format.as_deref(), Some("%Y-%m-%dT%H:%M:%S%.f"));
+        assert!(iso.ambiguous_date_formats.is_empty());
+    }
+
+    #[test]
+    fn test_thousands_separators() {
+        let values: Vec<Value> = ["1,234", "12,345.5", "999", "1,000,000"]
+            .iter()
+            .map(|text| json!(text))
+            .chain([json!(42.0)])
+            .collect();
+        let result = column(&values);
+        assert_eq!(result.inferred_type, ColumnType::Float);
+        assert_eq!(result.decimal_separator, Some('.'));
+        assert!((result.success_rate - 1.0).abs() < f64::EPSILON);
+
+        // Semicolon-delimited exports write decimal commas
+        let decimal_comma = column(&[json!("1,5"), json!("2,25"), json!("1.234,5")]);
+        assert_eq!(decimal_comma.inferred_type, ColumnType::Float);
+        assert_eq!(decimal_comma.decimal_separator, Some(','));
+
+        let integers = column(&[json!("1,234"), json!("12,345,678"), json!("7")]);
+        assert_eq!(integers.inferred_type, ColumnType::Integer);
+    }
+
+    #[test]
+    fn test_yes_no_column_is_boolean() {
+        let result = column(&[
+            json!("Yes"),
+            json!("no"),
+            json!(" "),
+            json!("YES"),
+            json!(true),
+        ]);
+        assert_eq!(result.inferred_type, ColumnType::Boolean);
+        assert_eq!(result.non_blank, 4);
+        assert!(result.non_conforming_rows.is_empty());
+    }
+
+    #[test]
+    fn test_coercion_suggestion_and_mixed_columns() {
+        let mut values: Vec<Value> = (0..32).map(|index| json!(f64::from(index) + 0.5)).collect();
+        values.push(json!("n/a"));
+        let result = column(&values);
+        assert_eq!(result.inferred_type, ColumnType::Float);
+        assert_eq!(result.non_conforming_rows, vec![32]);
+        assert_eq!(
+            result.suggestions,
+            vec!["96% parse as float; coerce 1 cells to null"]
+        );
+
+        let mixed = column(&[json!(1.0), json!("a"), json!("b"), json!("yes")]);
+        assert_eq!(mixed.inferred_type, ColumnType::Mixed);
+        assert_eq!(
+            column(&[json!("a"), json!("b")]).inferred_type,
+            ColumnType::Text
+        );
+        assert_eq!(column(&[json!(null)]).inferred_type, ColumnType::Empty);
+    }
+
+    #[test]
+    fn test_header_and_options() {
+        let rows = vec![
+            vec![json!("Time"), json!("Voltage")],
+            vec![json!(1.0), json!("3,5")],
+            vec![json!(2.0), json!("4,0")],
+        ];
+        let options = ColumnTypeOptions {
+            has_header: true,
+            ..ColumnTypeOptions::default()
+        };
+        let result = infer_column_types(&rows, &options).unwrap();
+        assert_eq!(result[0].name.as_deref(), Some("Time"));
+        assert_eq!(result[0].inferred_type, ColumnType::Integer);
+        assert_eq!(result[1].decimal_separator, Some(','));
+
+        let invalid = ColumnTypeOptions {
+            decimal_separator: Some(';'),
+            ..ColumnTypeOptions::default()
+        };
+        assert!(infer_column_types(&rows, &invalid).is_err());
+    }
+}
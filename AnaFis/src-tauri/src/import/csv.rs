@@ -8,37 +8,89 @@
 // - Various encodings (UTF-8, Latin1, etc.)
 // - First row as header option
 // - Skip rows option
+// - Streaming import in chunks of rows, and line counting without decoding
 
-use super::ImportResponse;
+use super::{CsvChunk, CsvStats, CsvStreamSummary, ImportResponse};
 use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
 use serde_json::{Number, Value};
 use std::collections::HashMap;
-use std::fs::File;
+use std::fs::{File, read};
 use std::io::{BufRead, BufReader};
+use std::mem::take;
+use std::path::Path;
 use std::str::from_utf8;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt};
 
-/// Detect file encoding by reading the first few bytes
+/// Block size used when counting lines
+const STATS_BLOCK_BYTES: usize = 64 * 1024;
+
+/// Delimiters tried by `detect_delimiter`, in order of preference on ties
+const DELIMITER_CANDIDATES: [char; 5] = [',', '\t', '|', ';', ' '];
+
+/// Detect file encoding by reading the first line
 fn detect_encoding(file_path: &str) -> Result<&'static Encoding, String> {
     let file = File::open(file_path).map_err(|e| format!("Failed to open file: {e}"))?;
 
     let mut reader = BufReader::new(file);
-    let mut buffer = vec![0_u8; 1024];
-    let bytes_read = reader
+    let mut buffer = Vec::with_capacity(1024);
+    reader
         .read_until(b'\n', &mut buffer)
         .map_err(|e| format!("Failed to read file: {e}"))?;
 
-    // Try to detect encoding based on BOM or content
-    if bytes_read >= 3 && buffer[0] == 0xEF && buffer[1] == 0xBB && buffer[2] == 0xBF {
-        return Ok(UTF_8); // UTF-8 with BOM
-    }
+    Ok(sniff_encoding(&buffer))
+}
 
-    // Check if it's valid UTF-8
-    if from_utf8(&buffer[..bytes_read]).is_ok() {
-        return Ok(UTF_8);
+/// Guess the encoding of a file from its first line
+fn sniff_encoding(first_line: &[u8]) -> &'static Encoding {
+    // UTF-8 with BOM, or valid UTF-8 content
+    if first_line.starts_with(&[0xEF, 0xBB, 0xBF]) || from_utf8(first_line).is_ok() {
+        return UTF_8;
     }
 
     // Default to Windows-1252 (common for legacy files)
-    Ok(WINDOWS_1252)
+    WINDOWS_1252
+}
+
+/// Split one line into fields, honouring double-quoted fields and `""` escapes
+fn split_fields(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current_field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => {
+                if in_quotes {
+                    // Check for escaped quote
+                    if chars.peek() == Some(&'"') {
+                        current_field.push('"');
+                        chars.next();
+                    } else {
+                        in_quotes = false;
+                    }
+                } else {
+                    in_quotes = true;
+                }
+            }
+            c if c == delimiter && !in_quotes => {
+                fields.push(take(&mut current_field));
+            }
+            c => {
+                current_field.push(c);
+            }
+        }
+    }
+    fields.push(current_field);
+    fields
+}
+
+/// JSON value of a field: a number when it parses as one, otherwise the text
+fn field_value(field: &str) -> Value {
+    field.parse::<f64>().map_or_else(
+        |_| Value::String(field.to_owned()),
+        |num| Value::Number(Number::from_f64(num).unwrap_or_else(|| Number::from(0))),
+    )
 }
 
 /// Parse a CSV file with custom delimiter
@@ -49,35 +101,46 @@ pub fn parse_delimited_file(
     first_row_as_header: bool,
     encoding_name: Option<&str>,
 ) -> Result<ImportResponse, String> {
-    // Determine encoding
-    let encoding = if let Some(enc_name) = encoding_name {
-        match enc_name.to_lowercase().as_str() {
-            "utf-8" | "utf8" => UTF_8,
-            "latin1" | "iso-8859-1" | "windows-1252" | "cp1252" => WINDOWS_1252,
-            _ => detect_encoding(file_path)?,
-        }
-    } else {
-        detect_encoding(file_path)?
-    };
+    let bytes = read(file_path).map_err(|e| format!("Failed to read file: {e}"))?;
+    import_csv_from_bytes(
+        &bytes,
+        delimiter,
+        skip_rows,
+        first_row_as_header,
+        encoding_name,
+    )
+}
 
-    // Read file with detected encoding
-    let file = File::open(file_path).map_err(|e| format!("Failed to open file: {e}"))?;
+/// Parse delimited text held in memory, such as a downloaded file
+///
+/// # Errors
+/// Returns an error if the text cannot be decoded or no rows remain after
+/// skipping
+pub fn import_csv_from_bytes(
+    bytes: &[u8],
+    delimiter: char,
+    skip_rows: usize,
+    first_row_as_header: bool,
+    encoding_name: Option<&str>,
+) -> Result<ImportResponse, String> {
+    // Determine encoding, guessing it from the first line unless named
+    let first_line = bytes
+        .split_inclusive(|&byte| byte == b'\n')
+        .next()
+        .unwrap_or_default();
+    let encoding = match encoding_name.map(str::to_lowercase).as_deref() {
+        Some("utf-8" | "utf8") => UTF_8,
+        Some("latin1" | "iso-8859-1" | "windows-1252" | "cp1252") => WINDOWS_1252,
+        _ => sniff_encoding(first_line),
+    };
 
-    let mut reader = BufReader::new(file);
     let mut lines = Vec::new();
-    let mut buffer = Vec::new();
-
-    while reader
-        .read_until(b'\n', &mut buffer)
-        .map_err(|e| format!("Failed to read file: {e}"))?
-        > 0
-    {
-        let (decoded, _, had_errors) = encoding.decode(&buffer);
+    for line in bytes.split_inclusive(|&byte| byte == b'\n') {
+        let (decoded, _, had_errors) = encoding.decode(line);
         if had_errors {
             return Err("Encoding error: file contains invalid characters".to_owned());
         }
         lines.push(decoded.into_owned());
-        buffer.clear();
     }
 
     // Skip rows if requested
@@ -97,38 +160,7 @@ pub fn parse_delimited_file(
             continue;
         }
 
-        // Simple CSV parsing (handles quoted fields)
-        let mut fields = Vec::new();
-        let mut current_field = String::new();
-        let mut in_quotes = false;
-        let mut chars = line.chars().peekable();
-
-        while let Some(ch) = chars.next() {
-            match ch {
-                '"' => {
-                    if in_quotes {
-                        // Check for escaped quote
-                        if chars.peek() == Some(&'"') {
-                            current_field.push('"');
-                            chars.next();
-                        } else {
-                            in_quotes = false;
-                        }
-                    } else {
-                        in_quotes = true;
-                    }
-                }
-                c if c == delimiter && !in_quotes => {
-                    fields.push(current_field.clone());
-                    current_field.clear();
-                }
-                c => {
-                    current_field.push(c);
-                }
-            }
-        }
-        fields.push(current_field);
-
+        let fields = split_fields(line, delimiter);
         max_columns = max_columns.max(fields.len());
         rows.push(fields);
     }
@@ -139,17 +171,7 @@ pub fn parse_delimited_file(
     // Convert to JSON values
     let mut sheet_data: Vec<Vec<Value>> = Vec::new();
     for row in rows.iter().skip(data_start) {
-        let mut json_row = Vec::new();
-        for field in row {
-            // Try to parse as number
-            if let Ok(num) = field.parse::<f64>() {
-                json_row.push(Value::Number(
-                    Number::from_f64(num).unwrap_or_else(|| Number::from(0)),
-                ));
-            } else {
-                json_row.push(Value::String(field.clone()));
-            }
-        }
+        let mut json_row: Vec<Value> = row.iter().map(|field| field_value(field)).collect();
         // Pad row to max_columns
         while json_row.len() < max_columns {
             json_row.push(Value::Null);
@@ -204,3 +226,312 @@ pub fn import_txt(
         encoding,
     )
 }
+
+/// Detect the delimiter of a text file from its first `n_sample_lines`
+/// non-blank lines
+///
+/// Each candidate (comma, tab, pipe, semicolon, space) splits the sampled
+/// lines into fields; the one giving the most consistent column count (the
+/// lowest coefficient of variation) wins, ties going to more columns.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or no candidate splits the
+/// sampled lines into more than one column
+pub fn detect_delimiter(file_path: &str, n_sample_lines: usize) -> Result<char, String> {
+    let encoding = detect_encoding(file_path)?;
+    let file = File::open(file_path).map_err(|e| format!("Failed to open file: {e}"))?;
+
+    let mut reader = BufReader::new(file);
+    let mut lines = Vec::with_capacity(n_sample_lines);
+    let mut buffer = Vec::new();
+    while lines.len() < n_sample_lines
+        && reader
+            .read_until(b'\n', &mut buffer)
+            .map_err(|e| format!("Failed to read file: {e}"))?
+            > 0
+    {
+        let (decoded, _, _) = encoding.decode(&buffer);
+        let line = decoded.trim_end_matches(&['\r', '\n'][..]);
+        if !line.trim().is_empty() {
+            lines.push(line.to_owned());
+        }
+        buffer.clear();
+    }
+
+    delimiter_from_lines(&lines)
+        .ok_or_else(|| "Could not detect a delimiter in the sampled lines".to_owned())
+}
+
+/// Candidate delimiter with the most consistent column count over `lines`
+fn delimiter_from_lines(lines: &[String]) -> Option<char> {
+    #[allow(clippy::cast_precision_loss, reason = "Line counts casting to f64")]
+    let line_count = lines.len() as f64;
+    DELIMITER_CANDIDATES
+        .iter()
+        .filter_map(|&candidate| {
+            #[allow(clippy::cast_precision_loss, reason = "Column counts casting to f64")]
+            let counts: Vec<f64> = lines
+                .iter()
+                .map(|line| split_fields(line, candidate).len() as f64)
+                .collect();
+            let mean = counts.iter().sum::<f64>() / line_count;
+            if mean <= 1.0 {
+                return None;
+            }
+            let variance = counts
+                .iter()
+                .map(|count| (count - mean).powi(2))
+                .sum::<f64>()
+                / line_count;
+            Some((candidate, variance.sqrt() / mean, mean))
+        })
+        // min_by keeps the first of equal elements, so candidate order breaks
+        // the remaining ties
+        .min_by(|a, b| a.1.total_cmp(&b.1).then(b.2.total_cmp(&a.2)))
+        .map(|(candidate, _, _)| candidate)
+}
+
+/// Stream a delimited file `chunk_size` rows at a time, never holding more
+/// than one chunk in memory
+///
+/// Blank lines are dropped and rows are not padded, since the widest row is
+/// only known at the end (see [`CsvStreamSummary::max_columns`]). The encoding
+/// is guessed from the first line. Reading stops at the first error returned
+/// by `on_chunk`.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or decoded, no rows remain
+/// after skipping, or `on_chunk` fails
+pub async fn import_csv_streaming<F>(
+    file_path: &Path,
+    skip_rows: usize,
+    delimiter: char,
+    chunk_size: usize,
+    mut on_chunk: F,
+) -> Result<CsvStreamSummary, String>
+where
+    F: FnMut(CsvChunk) -> Result<(), String>,
+{
+    if chunk_size == 0 {
+        return Err("Chunk size must be at least 1".to_owned());
+    }
+
+    let file = tokio::fs::File::open(file_path)
+        .await
+        .map_err(|e| format!("Failed to open file: {e}"))?;
+    let mut reader = tokio::io::BufReader::new(file);
+    let mut buffer = Vec::new();
+    let mut encoding = None;
+    let mut lines_read = 0;
+    let mut rows = Vec::with_capacity(chunk_size);
+    let mut summary = CsvStreamSummary::default();
+
+    let mut emit = |chunk_rows: Vec<Vec<Value>>, totals: &mut CsvStreamSummary| {
+        let row_count = chunk_rows.len();
+        on_chunk(CsvChunk {
+            index: totals.chunk_count,
+            first_row: totals.row_count,
+            rows: chunk_rows,
+        })?;
+        totals.chunk_count += 1;
+        totals.row_count += row_count;
+        Ok::<(), String>(())
+    };
+
+    while reader
+        .read_until(b'\n', &mut buffer)
+        .await
+        .map_err(|e| format!("Failed to read file: {e}"))?
+        > 0
+    {
+        let encoding = *encoding.get_or_insert_with(|| sniff_encoding(&buffer));
+        lines_read += 1;
+        if lines_read > skip_rows {
+            let (decoded, _, had_errors) = encoding.decode(&buffer);
+            if had_errors {
+                return Err(format!(
+                    "Encoding error: line {lines_read} contains invalid characters"
+                ));
+            }
+            let line = decoded.trim_end_matches(&['\r', '\n'][..]);
+            if !line.is_empty() {
+                let row: Vec<Value> = split_fields(line, delimiter)
+                    .iter()
+                    .map(|field| field_value(field))
+                    .collect();
+                summary.max_columns = summary.max_columns.max(row.len());
+                rows.push(row);
+                if rows.len() == chunk_size {
+                    emit(take(&mut rows), &mut summary)?;
+                }
+            }
+        }
+        buffer.clear();
+    }
+    if !rows.is_empty() {
+        emit(rows, &mut summary)?;
+    }
+
+    if summary.row_count == 0 {
+        return Err("File is empty or all rows were skipped".to_owned());
+    }
+    Ok(summary)
+}
+
+/// Count the non-blank lines of a delimited file and the fields of its first
+/// non-blank line, reading raw blocks without decoding or storing the rows
+///
+/// # Errors
+/// Returns an error if the file cannot be opened or read
+pub async fn csv_stats(file_path: &Path, delimiter: char) -> Result<CsvStats, String> {
+    let mut file = tokio::fs::File::open(file_path)
+        .await
+        .map_err(|e| format!("Failed to open file: {e}"))?;
+    let size = file
+        .metadata()
+        .await
+        .map_err(|e| format!("Failed to read file metadata: {e}"))?
+        .len();
+
+    let mut block = vec![0_u8; STATS_BLOCK_BYTES];
+    let mut line_count = 0;
+    let mut line_has_content = false;
+    // Bytes of the first non-blank line, kept until it ends
+    let mut first_line: Option<Vec<u8>> = None;
+    let mut current_line = Vec::new();
+
+    loop {
+        let bytes_read = file
+            .read(&mut block)
+            .await
+            .map_err(|e| format!("Failed to read file: {e}"))?;
+        if bytes_read == 0 {
+            break;
+        }
+        for &byte in &block[..bytes_read] {
+            if byte == b'\n' {
+                if line_has_content {
+                    line_count += 1;
+                    if first_line.is_none() {
+                        first_line = Some(take(&mut current_line));
+                    }
+                }
+                line_has_content = false;
+                current_line.clear();
+                continue;
+            }
+            line_has_content |= !matches!(byte, b' ' | b'\t' | b'\r');
+            if first_line.is_none() {
+                current_line.push(byte);
+            }
+        }
+    }
+    if line_has_content {
+        line_count += 1;
+        if first_line.is_none() {
+            first_line = Some(current_line);
+        }
+    }
+
+    let column_count = first_line.map_or(0, |bytes| {
+        let (decoded, _, _) = sniff_encoding(&bytes).decode(&bytes);
+        split_fields(decoded.trim_end_matches('\r'), delimiter).len()
+    });
+    Ok(CsvStats {
+        size,
+        line_count,
+        column_count,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+    use std::fs::{remove_file, write};
+    use std::path::PathBuf;
+
+    fn temp_csv(name: &str, contents: &str) -> PathBuf {
+        let path = temp_dir().join(format!("anafis-csv-{name}-{}.csv", std::process::id()));
+        write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_import_from_bytes_matches_file_import() {
+        // "Température" in Windows-1252, which is not valid UTF-8
+        let bytes = b"t;Temp\xe9rature\r\n0;1.5\r\n\r\n1;2\n";
+        let response = import_csv_from_bytes(bytes, ';', 0, false, None).unwrap();
+        let rows = &response.sheets["Sheet1"];
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0][1], Value::String("Temp\u{e9}rature".to_owned()));
+        assert_eq!(rows[1][1], Value::from(1.5));
+
+        let skipped = import_csv_from_bytes(bytes, ';', 1, false, Some("latin1")).unwrap();
+        assert_eq!(skipped.sheets["Sheet1"].len(), 2);
+        assert!(import_csv_from_bytes(b"", ',', 0, false, None).is_err());
+    }
+
+    #[test]
+    fn test_streaming_import_yields_chunks() {
+        let path = temp_csv("stream", "# header line\nx,y\n1,2\n\n3,\"a,b\"\n5,6,7\n8,9");
+        let mut chunks = Vec::new();
+        let summary =
+            tauri::async_runtime::block_on(import_csv_streaming(&path, 1, ',', 2, |chunk| {
+                chunks.push(chunk);
+                Ok(())
+            }))
+            .unwrap();
+        remove_file(&path).unwrap();
+
+        assert_eq!(
+            (summary.chunk_count, summary.row_count, summary.max_columns),
+            (3, 5, 3)
+        );
+        let sizes: Vec<(usize, usize, usize)> = chunks
+            .iter()
+            .map(|chunk| (chunk.index, chunk.first_row, chunk.rows.len()))
+            .collect();
+        assert_eq!(sizes, vec![(0, 0, 2), (1, 2, 2), (2, 4, 1)]);
+        assert_eq!(chunks[0].rows[0], vec![Value::from("x"), Value::from("y")]);
+        assert_eq!(chunks[1].rows[0][1], Value::from("a,b"));
+        assert_eq!(chunks[1].rows[1].len(), 3);
+    }
+
+    #[test]
+    fn test_streaming_import_stops_on_callback_error() {
+        let path = temp_csv("abort", "1\n2\n3\n");
+        let mut calls = 0;
+        let result = tauri::async_runtime::block_on(import_csv_streaming(&path, 0, ',', 1, |_| {
+            calls += 1;
+            Err("receiver closed".to_owned())
+        }));
+        remove_file(&path).unwrap();
+        assert_eq!(result.unwrap_err(), "receiver closed");
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_csv_stats_counts_lines() {
+        let path = temp_csv("stats", "\n  \na;b;\"c;d\"\r\n1;2;3\r\n\r\n4;5;6");
+        let stats = tauri::async_runtime::block_on(csv_stats(&path, ';')).unwrap();
+        remove_file(&path).unwrap();
+        assert_eq!(stats.line_count, 3);
+        assert_eq!(stats.column_count, 3);
+        assert_eq!(stats.size, 29);
+    }
+
+    #[test]
+    fn test_detect_delimiter_prefers_consistent_columns() {
+        let path = temp_csv("detect", "a;b;c\n1,5;2;3\n\n4;5,5;6\n");
+        let detected = detect_delimiter(&path.to_string_lossy(), 10).unwrap();
+        remove_file(&path).unwrap();
+        assert_eq!(detected, ';');
+
+        let lines = |text: &str| text.lines().map(str::to_owned).collect::<Vec<_>>();
+        assert_eq!(delimiter_from_lines(&lines("x\ty z\n1\t2 3")), Some('\t'));
+        assert_eq!(delimiter_from_lines(&lines("\"a,b\"|c\n1|2")), Some('|'));
+        assert_eq!(delimiter_from_lines(&lines("abc\ndef")), None);
+    }
+}
@@ -0,0 +1,123 @@
+// Excel import handler
+//
+// Imports .xlsx workbooks and converts every sheet to Univer-compatible workbook data.
+// Formula cells carry their last computed value as stored in the file, and date
+// cells are converted to ISO 8601 strings.
+
+use super::ImportResponse;
+use calamine::{Data, ExcelDateTime, Reader, Xlsx, open_workbook};
+use chrono::NaiveTime;
+use serde_json::{Number, Value};
+use std::collections::HashMap;
+
+/// Import all sheets of an Excel workbook
+///
+/// # Errors
+/// Returns an error if the file is not a readable .xlsx workbook
+pub fn import_xlsx(file_path: &str) -> Result<ImportResponse, String> {
+    let mut workbook: Xlsx<_> =
+        open_workbook(file_path).map_err(|e| format!("Failed to open workbook: {e}"))?;
+
+    let mut sheets = HashMap::new();
+    for sheet_name in workbook.sheet_names() {
+        let range = workbook
+            .worksheet_range(&sheet_name)
+            .map_err(|e| format!("Failed to read sheet '{sheet_name}': {e}"))?;
+
+        // The range starts at the first used cell; pad so cells keep their
+        // positions in the sheet
+        let (first_row, first_column) = range.start().unwrap_or((0, 0));
+        let leading_blanks = vec![Value::Null; first_column as usize];
+        let mut sheet_data: Vec<Vec<Value>> = vec![Vec::new(); first_row as usize];
+        for row in range.rows() {
+            let mut json_row = leading_blanks.clone();
+            json_row.extend(row.iter().map(convert_cell_to_json));
+            sheet_data.push(json_row);
+        }
+
+        sheets.insert(sheet_name, sheet_data);
+    }
+
+    Ok(ImportResponse { sheets })
+}
+
+/// Convert an Excel cell to a JSON value
+fn convert_cell_to_json(cell: &Data) -> Value {
+    match cell {
+        Data::Empty => Value::Null,
+        Data::Bool(b) => Value::Bool(*b),
+        Data::Int(i) => Value::Number((*i).into()),
+        Data::Float(f) => Number::from_f64(*f).map_or(Value::Null, Value::Number),
+        Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => Value::String(s.clone()),
+        Data::DateTime(dt) => convert_datetime_to_json(dt),
+        Data::Error(e) => Value::String(e.to_string()),
+    }
+}
+
+/// Format an Excel date or duration as ISO 8601 - dates without a time of
+/// day become plain dates
+fn convert_datetime_to_json(datetime: &ExcelDateTime) -> Value {
+    let iso = if datetime.is_duration() {
+        datetime.as_duration().map(|duration| duration.to_string())
+    } else {
+        datetime.as_datetime().map(|value| {
+            if value.time() == NaiveTime::MIN {
+                value.date().format("%Y-%m-%d").to_string()
+            } else {
+                value.format("%Y-%m-%dT%H:%M:%S%.f").to_string()
+            }
+        })
+    };
+
+    // Out-of-range serials keep their raw value
+    iso.map_or_else(
+        || Number::from_f64(datetime.as_f64()).map_or(Value::Null, Value::Number),
+        Value::String,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calamine::{CellErrorType, ExcelDateTimeType};
+
+    fn excel_datetime(serial: f64) -> Data {
+        Data::DateTime(ExcelDateTime::new(
+            serial,
+            ExcelDateTimeType::DateTime,
+            false,
+        ))
+    }
+
+    #[test]
+    fn test_convert_cell_values() {
+        assert_eq!(convert_cell_to_json(&Data::Empty), Value::Null);
+        assert_eq!(convert_cell_to_json(&Data::Bool(true)), Value::Bool(true));
+        assert_eq!(convert_cell_to_json(&Data::Int(-3)), Value::from(-3));
+        assert_eq!(convert_cell_to_json(&Data::Float(2.5)), Value::from(2.5));
+        assert_eq!(convert_cell_to_json(&Data::Float(f64::NAN)), Value::Null);
+        assert_eq!(
+            convert_cell_to_json(&Data::String("x".to_owned())),
+            Value::from("x")
+        );
+        assert_eq!(
+            convert_cell_to_json(&Data::Error(CellErrorType::Div0)),
+            Value::from("#DIV/0!")
+        );
+    }
+
+    #[test]
+    fn test_convert_dates_to_iso_8601() {
+        // 45306 is 2024-01-15 in the 1900 date system
+        assert_eq!(
+            convert_cell_to_json(&excel_datetime(45306.0)),
+            Value::from("2024-01-15")
+        );
+        assert_eq!(
+            convert_cell_to_json(&excel_datetime(45306.75)),
+            Value::from("2024-01-15T18:00:00")
+        );
+        let duration = Data::DateTime(ExcelDateTime::new(0.5, ExcelDateTimeType::TimeDelta, false));
+        assert_eq!(convert_cell_to_json(&duration), Value::from("PT43200S"));
+    }
+}
@@ -0,0 +1,787 @@
+// Formula audit of IWorkbookData snapshots
+//
+// Extracts the A1 and Sheet!A1 references of every formula in a workbook
+// snapshot without evaluating anything, builds the cell dependency graph and
+// reports circular references, references to missing sheets, out-of-range
+// or empty cells, volatile functions and the deepest dependency chain.
+//
+// Cells that only carry a shared formula id (`si`) repeat the formula of
+// another cell at an offset and are not audited.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+/// Rows of a sheet without a `rowCount`, the Excel grid limit
+const MAX_ROWS: u32 = 1_048_576;
+
+/// Columns of a sheet without a `columnCount`, the Excel grid limit
+const MAX_COLUMNS: u32 = 16_384;
+
+/// Functions that recalculate on every edit, whatever their arguments
+const VOLATILE_FUNCTIONS: [&str; 6] = ["NOW", "TODAY", "RAND", "RANDBETWEEN", "OFFSET", "INDIRECT"];
+
+/// Kind of problem found in a formula
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FormulaIssueKind {
+    /// Formulas that depend on themselves through their references
+    CircularReference,
+    /// Reference to a sheet that is not in the workbook
+    MissingSheet,
+    /// Reference beyond the rows or columns of its sheet
+    OutOfRange,
+    /// Reference to a cell without value or formula
+    EmptyReference,
+    /// Formula calling a volatile function, with the formulas depending on it
+    VolatileChain,
+}
+
+/// One problem found in the workbook formulas
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormulaIssue {
+    /// What is wrong
+    pub kind: FormulaIssueKind,
+    /// Cells involved as `Sheet!A1`, the formula cell first
+    pub cells: Vec<String>,
+    /// Description naming the cells
+    pub message: String,
+}
+
+/// Formula audit of a workbook, issues grouped by severity
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormulaAuditReport {
+    /// Circular references and references to missing sheets
+    pub errors: Vec<FormulaIssue>,
+    /// References beyond the sheet bounds
+    pub warnings: Vec<FormulaIssue>,
+    /// References to empty cells and volatile chains
+    pub info: Vec<FormulaIssue>,
+    /// Number of cells holding a formula
+    pub formula_count: usize,
+    /// Formulas in the longest acyclic dependency chain
+    pub max_chain_depth: usize,
+    /// Cells of that chain, from the formula without formula precedents
+    pub longest_chain: Vec<String>,
+}
+
+impl FormulaAuditReport {
+    fn push(&mut self, issue: FormulaIssue) {
+        match issue.kind {
+            FormulaIssueKind::CircularReference | FormulaIssueKind::MissingSheet => {
+                self.errors.push(issue);
+            }
+            FormulaIssueKind::OutOfRange => self.warnings.push(issue),
+            FormulaIssueKind::EmptyReference | FormulaIssueKind::VolatileChain => {
+                self.info.push(issue);
+            }
+        }
+    }
+}
+
+/// Sheet index, row and column of a cell, all from 0
+type CellKey = (usize, u32, u32);
+
+/// Name, bounds and filled cells of one sheet
+struct SheetCells {
+    name: String,
+    row_count: u32,
+    column_count: u32,
+    filled: BTreeSet<(u32, u32)>,
+    formulas: Vec<(u32, u32, String)>,
+}
+
+/// Reference found in a formula; single cells have `start == end`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CellReference {
+    sheet: Option<String>,
+    start: (u32, u32),
+    end: (u32, u32),
+}
+
+impl CellReference {
+    /// Reference with `start` the top-left and `end` the bottom-right corner
+    fn new(sheet: Option<String>, first: (u32, u32), second: (u32, u32)) -> Self {
+        Self {
+            sheet,
+            start: (first.0.min(second.0), first.1.min(second.1)),
+            end: (first.0.max(second.0), first.1.max(second.1)),
+        }
+    }
+}
+
+/// References and called functions of a formula string
+#[derive(Debug, Default, PartialEq, Eq)]
+struct FormulaTokens {
+    references: Vec<CellReference>,
+    functions: Vec<String>,
+}
+
+/// Audit the formulas of an `IWorkbookData` snapshot
+///
+/// # Errors
+/// Returns an error if the snapshot has no `sheets` object.
+pub fn audit_formulas(workbook: &Value) -> Result<FormulaAuditReport, String> {
+    let sheets = workbook
+        .get("sheets")
+        .and_then(Value::as_object)
+        .ok_or("Workbook snapshot has no sheets")?;
+    let sheets = sheet_cells(workbook, sheets);
+    let mut report = FormulaAuditReport::default();
+    let graph = formula_graph(&sheets, &mut report);
+    let name = |node: usize| {
+        let (sheet, row, column) = graph.cells[node];
+        cell_name(&sheets[sheet].name, row, column)
+    };
+    report.formula_count = graph.cells.len();
+
+    let mut cyclic = vec![false; graph.cells.len()];
+    for mut members in strongly_connected(&graph.precedents) {
+        let is_cycle = members.len() > 1 || graph.precedents[members[0]].contains(&members[0]);
+        if !is_cycle {
+            continue;
+        }
+        members.sort_unstable();
+        for &member in &members {
+            cyclic[member] = true;
+        }
+        let cycle: Vec<String> = members.into_iter().map(name).collect();
+        report.push(FormulaIssue {
+            kind: FormulaIssueKind::CircularReference,
+            message: format!("Circular reference through {}", cycle.join(", ")),
+            cells: cycle,
+        });
+    }
+
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); graph.cells.len()];
+    for (node, precedents) in graph.precedents.iter().enumerate() {
+        for &precedent in precedents {
+            dependents[precedent].push(node);
+        }
+    }
+
+    for (source, function) in &graph.volatile {
+        let source_cell = name(*source);
+        let mut chain = vec![source_cell.clone()];
+        chain.extend(downstream(*source, &dependents).into_iter().map(name));
+        report.push(FormulaIssue {
+            kind: FormulaIssueKind::VolatileChain,
+            message: format!(
+                "{source_cell} calls {function}; {} dependent formulas recalculate on every edit",
+                chain.len() - 1
+            ),
+            cells: chain,
+        });
+    }
+
+    let chain = longest_chain(&graph.precedents, &dependents, &cyclic);
+    report.max_chain_depth = chain.len();
+    report.longest_chain = chain.into_iter().map(name).collect();
+    Ok(report)
+}
+
+/// Formula cells with their formula precedents and volatile calls
+struct FormulaGraph {
+    /// Formula cells in sheet, row and column order
+    cells: Vec<CellKey>,
+    /// Formula cells referenced by each formula cell
+    precedents: Vec<Vec<usize>>,
+    /// Formula cells calling a volatile function, with the function
+    volatile: Vec<(usize, String)>,
+}
+
+/// Dependency graph of the formulas, reporting references to missing sheets
+/// and to out-of-range or empty cells along the way
+fn formula_graph(sheets: &[SheetCells], report: &mut FormulaAuditReport) -> FormulaGraph {
+    let sheet_index: HashMap<String, usize> = sheets
+        .iter()
+        .enumerate()
+        .map(|(index, sheet)| (sheet.name.to_lowercase(), index))
+        .collect();
+    let cells: Vec<CellKey> = sheets
+        .iter()
+        .enumerate()
+        .flat_map(|(index, sheet)| {
+            sheet
+                .formulas
+                .iter()
+                .map(move |&(row, column, _)| (index, row, column))
+        })
+        .collect();
+    let node_of: HashMap<CellKey, usize> = cells
+        .iter()
+        .enumerate()
+        .map(|(node, &cell)| (cell, node))
+        .collect();
+    let mut precedents: Vec<Vec<usize>> = vec![Vec::new(); cells.len()];
+    let mut volatile: Vec<(usize, String)> = Vec::new();
+
+    let formulas = sheets.iter().flat_map(|sheet| &sheet.formulas);
+    for (node, (&(sheet, formula_row, formula_column), (_, _, formula))) in
+        cells.iter().zip(formulas).enumerate()
+    {
+        let tokens = formula_tokens(formula);
+        if let Some(function) = tokens
+            .functions
+            .iter()
+            .find(|function| VOLATILE_FUNCTIONS.contains(&function.as_str()))
+        {
+            volatile.push((node, function.clone()));
+        }
+
+        let formula_cell = cell_name(&sheets[sheet].name, formula_row, formula_column);
+        let mut reported = HashSet::new();
+        for reference in tokens.references {
+            let target = if let Some(target_name) = &reference.sheet {
+                let Some(&target) = sheet_index.get(&target_name.to_lowercase()) else {
+                    if reported.insert((FormulaIssueKind::MissingSheet, target_name.clone())) {
+                        report.push(FormulaIssue {
+                            kind: FormulaIssueKind::MissingSheet,
+                            cells: vec![formula_cell.clone()],
+                            message: format!(
+                                "{formula_cell} refers to missing sheet '{target_name}'"
+                            ),
+                        });
+                    }
+                    continue;
+                };
+                target
+            } else {
+                sheet
+            };
+            let target_sheet = &sheets[target];
+            let referenced = range_name(&target_sheet.name, reference.start, reference.end);
+
+            if reference.end.0 >= target_sheet.row_count
+                || reference.end.1 >= target_sheet.column_count
+            {
+                if reported.insert((FormulaIssueKind::OutOfRange, referenced.clone())) {
+                    report.push(FormulaIssue {
+                        kind: FormulaIssueKind::OutOfRange,
+                        cells: vec![formula_cell.clone(), referenced.clone()],
+                        message: format!(
+                            "{formula_cell} refers to {referenced}, beyond the {} x {} sheet '{}'",
+                            target_sheet.row_count, target_sheet.column_count, target_sheet.name
+                        ),
+                    });
+                }
+                continue;
+            }
+
+            if reference.start == reference.end
+                && !target_sheet.filled.contains(&reference.start)
+                && reported.insert((FormulaIssueKind::EmptyReference, referenced.clone()))
+            {
+                report.push(FormulaIssue {
+                    kind: FormulaIssueKind::EmptyReference,
+                    cells: vec![formula_cell.clone(), referenced.clone()],
+                    message: format!("{formula_cell} refers to empty cell {referenced}"),
+                });
+            }
+
+            // Formula cells inside the referenced range
+            let rows = reference.start.0..=reference.end.0;
+            let columns = reference.start.1..=reference.end.1;
+            precedents[node].extend(
+                target_sheet
+                    .formulas
+                    .iter()
+                    .filter(|(row, column, _)| rows.contains(row) && columns.contains(column))
+                    .map(|&(row, column, _)| node_of[&(target, row, column)]),
+            );
+        }
+        precedents[node].sort_unstable();
+        precedents[node].dedup();
+    }
+
+    FormulaGraph {
+        cells,
+        precedents,
+        volatile,
+    }
+}
+
+/// Nodes reachable from `source` through `dependents`, in node order
+fn downstream(source: usize, dependents: &[Vec<usize>]) -> Vec<usize> {
+    let mut reached = vec![false; dependents.len()];
+    reached[source] = true;
+    let mut queue = VecDeque::from([source]);
+    while let Some(node) = queue.pop_front() {
+        for &dependent in &dependents[node] {
+            if !reached[dependent] {
+                reached[dependent] = true;
+                queue.push_back(dependent);
+            }
+        }
+    }
+    (0..dependents.len())
+        .filter(|&node| reached[node] && node != source)
+        .collect()
+}
+
+/// Sheets in `sheetOrder`, followed by any sheet it does not list
+fn sheet_cells(workbook: &Value, sheets: &Map<String, Value>) -> Vec<SheetCells> {
+    let mut order: Vec<&str> = workbook
+        .get("sheetOrder")
+        .and_then(Value::as_array)
+        .map(|ids| {
+            ids.iter()
+                .filter_map(Value::as_str)
+                .filter(|id| sheets.contains_key(*id))
+                .collect()
+        })
+        .unwrap_or_default();
+    for id in sheets.keys() {
+        if !order.contains(&id.as_str()) {
+            order.push(id);
+        }
+    }
+
+    order
+        .into_iter()
+        .map(|id| {
+            let sheet = &sheets[id];
+            let bound = |key: &str, limit: u32| {
+                sheet
+                    .get(key)
+                    .and_then(Value::as_u64)
+                    .and_then(|count| u32::try_from(count).ok())
+                    .unwrap_or(limit)
+            };
+            let mut cells = SheetCells {
+                name: sheet
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .unwrap_or(id)
+                    .to_owned(),
+                row_count: bound("rowCount", MAX_ROWS),
+                column_count: bound("columnCount", MAX_COLUMNS),
+                filled: BTreeSet::new(),
+                formulas: Vec::new(),
+            };
+            let rows = sheet.get("cellData").and_then(Value::as_object);
+            for (row, columns) in rows.into_iter().flatten() {
+                let Ok(row) = row.parse::<u32>() else {
+                    continue;
+                };
+                let Some(columns) = columns.as_object() else {
+                    continue;
+                };
+                for (column, cell) in columns {
+                    let Ok(column) = column.parse::<u32>() else {
+                        continue;
+                    };
+                    let formula = cell
+                        .get("f")
+                        .and_then(Value::as_str)
+                        .filter(|formula| !formula.trim().is_empty());
+                    let has_value = cell
+                        .get("v")
+                        .is_some_and(|value| !value.is_null() && value.as_str() != Some(""));
+                    if has_value || formula.is_some() || cell.get("si").is_some() {
+                        cells.filled.insert((row, column));
+                    }
+                    if let Some(formula) = formula {
+                        cells.formulas.push((row, column, formula.to_owned()));
+                    }
+                }
+            }
+            cells
+                .formulas
+                .sort_unstable_by_key(|&(row, column, _)| (row, column));
+            cells
+        })
+        .collect()
+}
+
+/// References and function names of a formula, skipping string literals
+fn formula_tokens(formula: &str) -> FormulaTokens {
+    let chars: Vec<char> = formula.chars().collect();
+    let is_word = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '$');
+    let mut tokens = FormulaTokens::default();
+    let mut position = 0;
+
+    while position < chars.len() {
+        let current = chars[position];
+        if current == '"' {
+            position = skip_quoted(&chars, position, '"');
+            continue;
+        }
+
+        let (sheet, word_start) = if current == '\'' {
+            let end = skip_quoted(&chars, position, '\'');
+            if chars.get(end) != Some(&'!') {
+                position = end;
+                continue;
+            }
+            let quoted: String = chars[position + 1..end - 1].iter().collect();
+            (Some(quoted.replace("''", "'")), end + 1)
+        } else if is_word(current) && (position == 0 || !is_word(chars[position - 1])) {
+            (None, position)
+        } else {
+            position += 1;
+            continue;
+        };
+
+        let mut word_end = word_start;
+        while word_end < chars.len() && is_word(chars[word_end]) {
+            word_end += 1;
+        }
+        let word: String = chars[word_start..word_end].iter().collect();
+        position = word_end;
+
+        match chars.get(word_end) {
+            Some('!') if sheet.is_none() => {
+                // Unquoted sheet name; the reference follows the '!'
+                let mut cell_end = word_end + 1;
+                while cell_end < chars.len() && is_word(chars[cell_end]) {
+                    cell_end += 1;
+                }
+                let cell: String = chars[word_end + 1..cell_end].iter().collect();
+                position = cell_end;
+                if let Some(start) = parse_cell(&cell) {
+                    let (end, after) = range_end(&chars, cell_end, start);
+                    position = after;
+                    tokens
+                        .references
+                        .push(CellReference::new(Some(word), start, end));
+                }
+            }
+            Some('(') if sheet.is_none() => tokens.functions.push(word.to_uppercase()),
+            _ => {
+                if let Some(start) = parse_cell(&word) {
+                    let (end, after) = range_end(&chars, word_end, start);
+                    position = after;
+                    tokens
+                        .references
+                        .push(CellReference::new(sheet, start, end));
+                }
+            }
+        }
+    }
+    tokens
+}
+
+/// Position after the literal opened by the `quote` at `start`; doubled
+/// quotes are escapes
+fn skip_quoted(chars: &[char], start: usize, quote: char) -> usize {
+    let mut position = start + 1;
+    while position < chars.len() {
+        if chars[position] == quote {
+            if chars.get(position + 1) == Some(&quote) {
+                position += 2;
+                continue;
+            }
+            return position + 1;
+        }
+        position += 1;
+    }
+    position
+}
+
+/// End cell of a range continuing at `position` with ":B2", and the position
+/// after it; single cells end where they start
+fn range_end(chars: &[char], position: usize, start: (u32, u32)) -> ((u32, u32), usize) {
+    if chars.get(position) != Some(&':') {
+        return (start, position);
+    }
+    let mut end = position + 1;
+    while end < chars.len() && (chars[end].is_ascii_alphanumeric() || chars[end] == '$') {
+        end += 1;
+    }
+    let cell: String = chars[position + 1..end].iter().collect();
+    parse_cell(&cell).map_or((start, position), |cell_end| (cell_end, end))
+}
+
+/// Row and column, from 0, of an A1-style cell with optional `$` anchors
+fn parse_cell(cell: &str) -> Option<(u32, u32)> {
+    let cell = cell.strip_prefix('$').unwrap_or(cell);
+    let letters = cell.chars().take_while(char::is_ascii_alphabetic).count();
+    if !(1..=3).contains(&letters) {
+        return None;
+    }
+    let (column, rest) = cell.split_at(letters);
+    let digits = rest.strip_prefix('$').unwrap_or(rest);
+    if digits.is_empty() || digits.len() > 7 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let row = digits.parse::<u32>().ok()?.checked_sub(1)?;
+    let column = column.chars().fold(0, |index, letter| {
+        index * 26 + u32::from(letter.to_ascii_uppercase()) - u32::from('A') + 1
+    });
+    Some((row, column - 1))
+}
+
+/// Column letters of a column index from 0
+#[allow(
+    clippy::integer_division,
+    reason = "Bijective base-26 digits of the column"
+)]
+fn column_letters(column: u32) -> String {
+    let mut letters = Vec::new();
+    let mut remaining = column + 1;
+    while remaining > 0 {
+        let letter = (remaining - 1) % 26;
+        letters.push(char::from_u32(u32::from('A') + letter).unwrap_or('?'));
+        remaining = (remaining - 1) / 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// `Sheet!A1` name of a cell
+fn cell_name(sheet: &str, row: u32, column: u32) -> String {
+    format!("{sheet}!{}{}", column_letters(column), row + 1)
+}
+
+/// `Sheet!A1` or `Sheet!A1:B2` name of a reference
+fn range_name(sheet: &str, start: (u32, u32), end: (u32, u32)) -> String {
+    let first = cell_name(sheet, start.0, start.1);
+    if start == end {
+        first
+    } else {
+        format!("{first}:{}{}", column_letters(end.1), end.0 + 1)
+    }
+}
+
+/// Strongly connected components of the graph with `edges[node]` as the
+/// successors of each node (iterative Tarjan, safe for long chains)
+fn strongly_connected(edges: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut index: Vec<Option<usize>> = vec![None; edges.len()];
+    let mut low = vec![0; edges.len()];
+    let mut on_stack = vec![false; edges.len()];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut next_index = 0;
+
+    for root in 0..edges.len() {
+        if index[root].is_some() {
+            continue;
+        }
+        index[root] = Some(next_index);
+        low[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        let mut work = vec![(root, 0)];
+
+        while let Some(&(node, edge)) = work.last() {
+            if let Some(&target) = edges[node].get(edge) {
+                if let Some(frame) = work.last_mut() {
+                    frame.1 += 1;
+                }
+                match index[target] {
+                    None => {
+                        index[target] = Some(next_index);
+                        low[target] = next_index;
+                        next_index += 1;
+                        stack.push(target);
+                        on_stack[target] = true;
+                        work.push((target, 0));
+                    }
+                    Some(target_index) if on_stack[target] => {
+                        low[node] = low[node].min(target_index);
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                low[parent] = low[parent].min(low[node]);
+            }
+            if index[node] == Some(low[node]) {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+/// Longest chain of formulas outside cycles, from the formula without
+/// formula precedents to the deepest dependent
+fn longest_chain(
+    precedents: &[Vec<usize>],
+    dependents: &[Vec<usize>],
+    cyclic: &[bool],
+) -> Vec<usize> {
+    let mut remaining: Vec<usize> = precedents
+        .iter()
+        .map(|node_precedents| node_precedents.iter().filter(|&&p| !cyclic[p]).count())
+        .collect();
+    let mut depth = vec![0_usize; precedents.len()];
+    let mut via: Vec<Option<usize>> = vec![None; precedents.len()];
+    let mut queue: VecDeque<usize> = (0..precedents.len())
+        .filter(|&node| !cyclic[node] && remaining[node] == 0)
+        .collect();
+    for &node in &queue {
+        depth[node] = 1;
+    }
+
+    while let Some(node) = queue.pop_front() {
+        for &dependent in &dependents[node] {
+            if cyclic[dependent] {
+                continue;
+            }
+            if depth[node] + 1 > depth[dependent] {
+                depth[dependent] = depth[node] + 1;
+                via[dependent] = Some(node);
+            }
+            remaining[dependent] -= 1;
+            if remaining[dependent] == 0 {
+                queue.push_back(dependent);
+            }
+        }
+    }
+
+    let deepest = (0..depth.len())
+        .filter(|&node| depth[node] > 0)
+        .max_by_key(|&node| {
+            // Earliest cell among equally deep chains
+            (depth[node], std::cmp::Reverse(node))
+        });
+    let mut chain: Vec<usize> = Vec::new();
+    let mut current = deepest;
+    while let Some(node) = current {
+        chain.push(node);
+        current = via[node];
+    }
+    chain.reverse();
+    chain
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn workbook(cells: &Value) -> Value {
+        json!({
+            "id": "wb-1",
+            "sheetOrder": ["s1", "s2"],
+            "sheets": {
+                "s1": { "name": "Data", "rowCount": 100, "columnCount": 10, "cellData": cells },
+                "s2": { "name": "Results", "cellData": { "0": { "0": { "v": 2.0 } } } }
+            }
+        })
+    }
+
+    #[test]
+    fn test_formula_tokens() {
+        let tokens =
+            formula_tokens("=SUM($A$1:B3) + 'My Sheet'!C2*Results!D4 + LOG10(E5) & \"F6\"");
+        assert_eq!(tokens.functions, vec!["SUM", "LOG10"]);
+        assert_eq!(
+            tokens.references,
+            vec![
+                CellReference {
+                    sheet: None,
+                    start: (0, 0),
+                    end: (2, 1)
+                },
+                CellReference {
+                    sheet: Some("My Sheet".to_owned()),
+                    start: (1, 2),
+                    end: (1, 2)
+                },
+                CellReference {
+                    sheet: Some("Results".to_owned()),
+                    start: (3, 3),
+                    end: (3, 3)
+                },
+                CellReference {
+                    sheet: None,
+                    start: (4, 4),
+                    end: (4, 4)
+                },
+            ]
+        );
+        assert!(formula_tokens("=1.5E3*2").references.is_empty());
+        assert_eq!(column_letters(27), "AB");
+        assert_eq!(parse_cell("AB12"), Some((11, 27)));
+    }
+
+    #[test]
+    fn test_three_cell_cycle() {
+        let cells = json!({
+            "0": {
+                "0": { "f": "=C1+1" },
+                "1": { "f": "=A1*2" },
+                "2": { "f": "=B1-3" },
+                "3": { "f": "=A1" }
+            }
+        });
+        let report = audit_formulas(&workbook(&cells)).unwrap();
+        assert_eq!(report.formula_count, 4);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].kind, FormulaIssueKind::CircularReference);
+        assert_eq!(
+            report.errors[0].cells,
+            vec!["Data!A1", "Data!B1", "Data!C1"]
+        );
+        // D1 depends on the cycle but is not part of it
+        assert_eq!(report.max_chain_depth, 1);
+        assert_eq!(report.longest_chain, vec!["Data!D1"]);
+    }
+
+    #[test]
+    fn test_dangling_cross_sheet_reference() {
+        let cells = json!({
+            "0": {
+                "0": { "v": 1.0 },
+                "1": { "f": "=Summary!A1+Results!A1+A1" },
+                "2": { "f": "=B1+A200+'Results'!B9" }
+            }
+        });
+        let report = audit_formulas(&workbook(&cells)).unwrap();
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].kind, FormulaIssueKind::MissingSheet);
+        assert_eq!(report.errors[0].cells, vec!["Data!B1"]);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].cells, vec!["Data!C1", "Data!A200"]);
+        assert_eq!(report.info.len(), 1);
+        assert_eq!(report.info[0].kind, FormulaIssueKind::EmptyReference);
+        assert_eq!(report.info[0].cells, vec!["Data!C1", "Results!B9"]);
+        assert_eq!(report.longest_chain, vec!["Data!B1", "Data!C1"]);
+    }
+
+    #[test]
+    fn test_volatile_chain() {
+        let cells = json!({
+            "0": { "0": { "f": "=NOW()" }, "1": { "f": "=A1+1" } },
+            "1": { "0": { "f": "=SUM(A1:B1)" } }
+        });
+        let report = audit_formulas(&workbook(&cells)).unwrap();
+        assert!(report.errors.is_empty() && report.warnings.is_empty());
+        assert_eq!(report.info.len(), 1);
+        assert_eq!(report.info[0].kind, FormulaIssueKind::VolatileChain);
+        assert_eq!(report.info[0].cells, vec!["Data!A1", "Data!B1", "Data!A2"]);
+        assert_eq!(report.max_chain_depth, 3);
+    }
+
+    #[test]
+    fn test_clean_workbook() {
+        let cells = json!({
+            "0": { "0": { "v": 1.0 }, "1": { "v": 2.0 } },
+            "1": { "0": { "f": "=A1+B1*Results!A1" }, "1": { "f": "=\"A9\" & A2" } }
+        });
+        let report = audit_formulas(&workbook(&cells)).unwrap();
+        assert!(report.errors.is_empty());
+        assert!(report.warnings.is_empty());
+        assert!(report.info.is_empty());
+        assert_eq!(report.formula_count, 2);
+        assert_eq!(report.longest_chain, vec!["Data!A2", "Data!B2"]);
+        assert!(audit_formulas(&json!({ "id": "wb-1" })).is_err());
+    }
+}
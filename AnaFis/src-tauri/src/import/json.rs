@@ -0,0 +1,200 @@
+// JSON import handler
+//
+// Imports JSON files and converts them to Univer-compatible workbook data.
+// Supports:
+// - Arrays of objects (records from APIs and document databases): the union of
+//   all keys becomes the header row, in order of first appearance, and missing
+//   keys are left blank
+// - Nested objects, flattened into dot-separated columns (`address.city`)
+// - 2D arrays, imported row by row
+// Arrays inside cells are kept as their JSON text.
+
+use super::ImportResponse;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::BufReader;
+
+/// Import a JSON file holding an array of objects or a 2D array
+///
+/// # Errors
+/// Returns an error if the file cannot be read, is not valid JSON, or its
+/// root is not an array of objects or an array of arrays
+pub fn import_json(file_path: &str) -> Result<ImportResponse, String> {
+    let file = File::open(file_path).map_err(|e| format!("Failed to open file: {e}"))?;
+    let root: Value = serde_json::from_reader(BufReader::new(file))
+        .map_err(|e| format!("Failed to parse JSON: {e}"))?;
+
+    rows_response(&root)
+}
+
+/// Import a JSON document held in memory, such as a downloaded file
+///
+/// # Errors
+/// Returns an error if the bytes are not valid JSON or the root is not an
+/// array of objects or an array of arrays
+pub fn import_json_from_bytes(bytes: &[u8]) -> Result<ImportResponse, String> {
+    let root: Value =
+        serde_json::from_slice(bytes).map_err(|e| format!("Failed to parse JSON: {e}"))?;
+
+    rows_response(&root)
+}
+
+/// Single-sheet response holding the rows of a JSON document
+fn rows_response(root: &Value) -> Result<ImportResponse, String> {
+    let sheet_data = convert_json_to_rows(root)?;
+
+    let mut sheets = HashMap::new();
+    sheets.insert("Sheet1".to_owned(), sheet_data);
+
+    Ok(ImportResponse { sheets })
+}
+
+/// Convert a JSON document to sheet rows
+fn convert_json_to_rows(root: &Value) -> Result<Vec<Vec<Value>>, String> {
+    let Value::Array(items) = root else {
+        return Err("JSON root must be an array of objects or an array of arrays".to_owned());
+    };
+
+    if items.iter().all(Value::is_object) {
+        Ok(object_rows(items))
+    } else if items.iter().all(Value::is_array) {
+        Ok(items
+            .iter()
+            .filter_map(Value::as_array)
+            .map(|row| row.iter().map(cell_value).collect())
+            .collect())
+    } else {
+        Err("JSON array must contain only objects or only arrays".to_owned())
+    }
+}
+
+/// Header row of the union of flattened keys, then one row per object
+fn object_rows(items: &[Value]) -> Vec<Vec<Value>> {
+    let records: Vec<Map<String, Value>> = items
+        .iter()
+        .filter_map(Value::as_object)
+        .map(|object| {
+            let mut record = Map::new();
+            flatten_object(object, "", &mut record);
+            record
+        })
+        .collect();
+
+    // Union of keys in order of first appearance
+    let mut header: Vec<String> = Vec::new();
+    let mut seen = HashSet::new();
+    for key in records.iter().flat_map(Map::keys) {
+        if seen.insert(key.as_str()) {
+            header.push(key.clone());
+        }
+    }
+
+    let mut sheet_data = Vec::with_capacity(records.len() + 1);
+    sheet_data.push(header.iter().cloned().map(Value::String).collect());
+    for record in &records {
+        sheet_data.push(
+            header
+                .iter()
+                .map(|key| record.get(key).cloned().unwrap_or(Value::Null))
+                .collect(),
+        );
+    }
+    sheet_data
+}
+
+/// Flatten nested objects into `record`, joining keys with dots
+fn flatten_object(object: &Map<String, Value>, prefix: &str, record: &mut Map<String, Value>) {
+    for (key, value) in object {
+        let name = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            Value::Object(nested) if !nested.is_empty() => flatten_object(nested, &name, record),
+            _ => {
+                record.insert(name, cell_value(value));
+            }
+        }
+    }
+}
+
+/// Scalar cell value; arrays and objects are kept as their JSON text
+fn cell_value(value: &Value) -> Value {
+    match value {
+        Value::Array(_) | Value::Object(_) => Value::String(value.to_string()),
+        scalar => scalar.clone(),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::env::temp_dir;
+    use std::fs::{remove_file, write};
+
+    #[test]
+    fn test_object_array_unions_and_flattens_keys() {
+        let root = json!([
+            { "id": 1, "address": { "city": "Lisbon", "zip": "1000" } },
+            { "id": 2, "name": "B", "tags": ["x", "y"] },
+            { "address": { "city": "Porto" } }
+        ]);
+        let rows = convert_json_to_rows(&root).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                vec![
+                    json!("id"),
+                    json!("address.city"),
+                    json!("address.zip"),
+                    json!("name"),
+                    json!("tags")
+                ],
+                vec![
+                    json!(1),
+                    json!("Lisbon"),
+                    json!("1000"),
+                    Value::Null,
+                    Value::Null
+                ],
+                vec![
+                    json!(2),
+                    Value::Null,
+                    Value::Null,
+                    json!("B"),
+                    json!("[\"x\",\"y\"]")
+                ],
+                vec![
+                    Value::Null,
+                    json!("Porto"),
+                    Value::Null,
+                    Value::Null,
+                    Value::Null
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_2d_array_file() {
+        let path = temp_dir().join(format!("anafis-json-2d-{}.json", std::process::id()));
+        write(&path, r#"[["x", "y"], [1, 2.5], [3, null]]"#).unwrap();
+        let response = import_json(&path.to_string_lossy()).unwrap();
+        remove_file(&path).unwrap();
+
+        assert_eq!(
+            response.sheets["Sheet1"],
+            vec![
+                vec![json!("x"), json!("y")],
+                vec![json!(1), json!(2.5)],
+                vec![json!(3), Value::Null],
+            ]
+        );
+        assert!(convert_json_to_rows(&json!({ "a": 1 })).is_err());
+        assert!(convert_json_to_rows(&json!([{ "a": 1 }, [1]])).is_err());
+    }
+}
@@ -0,0 +1,755 @@
+// Import merge handler
+//
+// Merges one sheet of an imported file into an existing sheet of the open
+// workbook. Source columns are matched to the target header by name, after
+// renaming through the column mapping, and merged in one of three modes:
+// - append_rows: source rows are added below the target rows
+// - new_columns: source columns are added to the right of the target
+//   columns, row by row
+// - update_by_key: source rows overwrite the target row with the same key,
+//   or are added when no target row has that key
+// Rows that cannot be merged - empty keys, keys of another type than the
+// target key column, keys repeated in the source - are reported as conflicts
+// instead of aborting the merge.
+
+use super::ImportResponse;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+/// How imported rows are merged into the target sheet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeMode {
+    /// Add the source rows below the target rows
+    AppendRows,
+    /// Add the source columns to the right of the target columns
+    NewColumns,
+    /// Update the target rows whose key matches a source row, add the others
+    UpdateByKey,
+}
+
+/// What happens to source columns that are not in the target header
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnmappedColumns {
+    /// Leave them out of the merge
+    #[default]
+    Drop,
+    /// Add them as new columns at the end of the target header
+    Append,
+}
+
+/// Merge options sent from frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeOptions {
+    /// How the imported rows are merged
+    pub merge_mode: MergeMode,
+    /// Imported sheet to merge; may be omitted when the file has one sheet
+    /// or a sheet named like the target
+    #[serde(default)]
+    pub source_sheet: Option<String>,
+    /// Source header -> target header renames; other source columns are
+    /// matched by their own name
+    #[serde(default)]
+    pub column_mapping: HashMap<String, String>,
+    /// Target columns identifying a row, required by `update_by_key`
+    #[serde(default)]
+    pub key_columns: Vec<String>,
+    /// Source columns missing from the target header, for `append_rows` and
+    /// `update_by_key`
+    #[serde(default)]
+    pub unmapped_columns: UnmappedColumns,
+    /// Return only the changes instead of the whole merged sheet
+    #[serde(default)]
+    pub delta_only: bool,
+}
+
+/// Reason a source row or column was left out of the merge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    /// A key cell of the source row is empty
+    MissingKey,
+    /// A key cell has another type than the target key column
+    TypeMismatch,
+    /// The key was already used by an earlier source row
+    DuplicateKey,
+    /// The column name is already taken in the target header
+    DuplicateColumn,
+}
+
+/// Source row or column that could not be merged
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeConflict {
+    /// Reason of the conflict
+    pub kind: ConflictKind,
+    /// Row of the imported sheet, the header being row 0; `None` for column
+    /// conflicts
+    pub source_row: Option<usize>,
+    /// Target column involved
+    pub column: Option<String>,
+    /// Human-readable explanation
+    pub detail: String,
+}
+
+/// Cell of an existing target row changed by the merge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CellUpdate {
+    /// Row of the merged sheet, the header being row 0
+    pub row: usize,
+    /// Column of the merged sheet
+    pub column: usize,
+    /// Value before the merge
+    pub previous: Value,
+    /// Value after the merge
+    pub value: Value,
+}
+
+/// Summary of the changes a merge makes to the target sheet
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergePlan {
+    /// Rows added below the target rows
+    pub rows_added: usize,
+    /// Cells of existing rows that changed
+    pub cells_updated: usize,
+    /// Columns added to the target header
+    pub columns_added: Vec<String>,
+    /// Source columns left out of the merge
+    pub columns_dropped: Vec<String>,
+    /// Target columns the source does not provide
+    pub columns_missing: Vec<String>,
+    /// Changed cells of existing rows
+    pub updates: Vec<CellUpdate>,
+    /// Rows and columns that could not be merged
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Merge plan and merged data returned to the frontend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeResult {
+    /// Sheet of the workbook the data was merged into
+    pub target_sheet: String,
+    /// Imported sheet that was merged
+    pub source_sheet: String,
+    /// Changes made by the merge
+    pub plan: MergePlan,
+    /// Header of the merged sheet
+    pub header: Vec<Value>,
+    /// Whole merged sheet, header included; `None` when only the delta was
+    /// requested
+    pub merged: Option<Vec<Vec<Value>>>,
+    /// Rows added below the target rows, in the merged column layout
+    pub added_rows: Vec<Vec<Value>>,
+}
+
+/// Type of a key cell; keys only match keys of the same type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum KeyKind {
+    Number,
+    Text,
+    Boolean,
+}
+
+impl KeyKind {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Number => "number",
+            Self::Text => "text",
+            Self::Boolean => "boolean",
+        }
+    }
+}
+
+/// Normalized key cell - numbers compare by value, text trimmed
+type KeyCell = (KeyKind, String);
+
+/// Take the imported sheet to merge out of an import response
+///
+/// Without an explicit sheet name, the only sheet of the file is used, or
+/// else the sheet named like the target.
+///
+/// # Errors
+/// Returns an error if the requested sheet does not exist or no sheet can be
+/// chosen
+pub fn take_source_sheet(
+    mut response: ImportResponse,
+    requested: Option<&str>,
+    target_sheet: &str,
+) -> Result<(String, Vec<Vec<Value>>), String> {
+    if let Some(name) = requested {
+        return response
+            .sheets
+            .remove_entry(name)
+            .ok_or_else(|| format!("Sheet '{name}' not found in the imported file"));
+    }
+    if response.sheets.len() == 1 {
+        return response
+            .sheets
+            .into_iter()
+            .next()
+            .ok_or_else(|| "The imported file has no sheets".to_owned());
+    }
+    if let Some(sheet) = response.sheets.remove_entry(target_sheet) {
+        return Ok(sheet);
+    }
+    if response.sheets.is_empty() {
+        return Err("The imported file has no sheets".to_owned());
+    }
+
+    let mut names: Vec<&str> = response.sheets.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    Err(format!(
+        "The imported file has {} sheets ({}); choose the one to merge",
+        names.len(),
+        names.join(", ")
+    ))
+}
+
+/// Merge the rows of an imported sheet into a target sheet
+///
+/// Both sheets start with a header row. Empty source cells never overwrite
+/// target cells.
+///
+/// # Errors
+/// Returns an error if either sheet has no header row, or if the key columns
+/// of an `update_by_key` merge are missing from either header
+pub fn merge_sheets(
+    target_sheet: String,
+    target: &[Vec<Value>],
+    source_sheet: String,
+    source: &[Vec<Value>],
+    options: &MergeOptions,
+) -> Result<MergeResult, String> {
+    let (target_header, target_body) = target
+        .split_first()
+        .ok_or_else(|| format!("Target sheet '{target_sheet}' has no header row"))?;
+    let (source_header, source_body) = source
+        .split_first()
+        .ok_or_else(|| format!("Imported sheet '{source_sheet}' has no header row"))?;
+
+    let mut plan = MergePlan::default();
+    let mut header: Vec<String> = target_header.iter().map(header_name).collect();
+    let target_width = header.len();
+    let placement = place_columns(source_header, &mut header, options, &mut plan);
+
+    if options.merge_mode != MergeMode::NewColumns {
+        plan.columns_missing = (0..target_width)
+            .filter(|column| !placement.contains(&Some(*column)))
+            .filter_map(|column| header.get(column).cloned())
+            .collect();
+    }
+
+    // Ragged target rows are padded to the merged width
+    let width = header.len();
+    let mut rows: Vec<Vec<Value>> = target_body
+        .iter()
+        .map(|row| {
+            let mut padded = row.clone();
+            padded.resize(width, Value::Null);
+            padded
+        })
+        .collect();
+
+    match options.merge_mode {
+        MergeMode::AppendRows => {
+            rows.extend(
+                source_body
+                    .iter()
+                    .map(|row| placed_row(row, &placement, width)),
+            );
+        }
+        MergeMode::NewColumns => {
+            for (index, source_row) in source_body.iter().enumerate() {
+                if index >= rows.len() {
+                    rows.push(vec![Value::Null; width]);
+                }
+                let existing = index < target_body.len();
+                for (column, value) in placed_cells(source_row, &placement) {
+                    if let Some(cell) = rows.get_mut(index).and_then(|row| row.get_mut(column)) {
+                        if existing {
+                            plan.updates.push(CellUpdate {
+                                row: index + 1,
+                                column,
+                                previous: cell.clone(),
+                                value: value.clone(),
+                            });
+                        }
+                        *cell = value.clone();
+                    }
+                }
+            }
+        }
+        MergeMode::UpdateByKey => {
+            update_by_key(
+                &mut rows,
+                &header,
+                source_body,
+                &placement,
+                options,
+                &mut plan,
+            )?;
+        }
+    }
+
+    plan.cells_updated = plan.updates.len();
+    let added_rows = rows.split_off(target_body.len().min(rows.len()));
+    plan.rows_added = added_rows.len();
+
+    let header: Vec<Value> = header.into_iter().map(Value::String).collect();
+    let merged = (!options.delta_only).then(|| {
+        let mut merged = Vec::with_capacity(rows.len() + added_rows.len() + 1);
+        merged.push(header.clone());
+        merged.extend(rows);
+        merged.extend(added_rows.iter().cloned());
+        merged
+    });
+
+    Ok(MergeResult {
+        target_sheet,
+        source_sheet,
+        plan,
+        header,
+        merged,
+        added_rows,
+    })
+}
+
+/// Target column of each source column, adding new columns to `header` as
+/// the mode and options require
+fn place_columns(
+    source_header: &[Value],
+    header: &mut Vec<String>,
+    options: &MergeOptions,
+    plan: &mut MergePlan,
+) -> Vec<Option<usize>> {
+    let mut placement: Vec<Option<usize>> = Vec::with_capacity(source_header.len());
+    for cell in source_header {
+        let source_name = header_name(cell);
+        let name = options
+            .column_mapping
+            .get(&source_name)
+            .cloned()
+            .unwrap_or(source_name);
+        let existing = header.iter().position(|column| *column == name);
+
+        let column = match (options.merge_mode, existing) {
+            (MergeMode::NewColumns, Some(_)) => {
+                plan.conflicts.push(MergeConflict {
+                    kind: ConflictKind::DuplicateColumn,
+                    source_row: None,
+                    detail: format!("Column '{name}' is already in the target header"),
+                    column: Some(name),
+                });
+                None
+            }
+            (_, Some(column)) if placement.contains(&Some(column)) => {
+                plan.conflicts.push(MergeConflict {
+                    kind: ConflictKind::DuplicateColumn,
+                    source_row: None,
+                    detail: format!("Several source columns map to column '{name}'"),
+                    column: Some(name),
+                });
+                None
+            }
+            (_, Some(column)) => Some(column),
+            (MergeMode::AppendRows | MergeMode::UpdateByKey, None)
+                if options.unmapped_columns == UnmappedColumns::Drop =>
+            {
+                plan.columns_dropped.push(name);
+                None
+            }
+            (_, None) => {
+                plan.columns_added.push(name.clone());
+                header.push(name);
+                Some(header.len() - 1)
+            }
+        };
+        placement.push(column);
+    }
+    placement
+}
+
+/// Update the rows matching the key of a source row and add the others
+fn update_by_key(
+    rows: &mut Vec<Vec<Value>>,
+    header: &[String],
+    source_body: &[Vec<Value>],
+    placement: &[Option<usize>],
+    options: &MergeOptions,
+    plan: &mut MergePlan,
+) -> Result<(), String> {
+    let keys = resolve_keys(rows, header, placement, options, plan)?;
+
+    let mut index: HashMap<Vec<KeyCell>, usize> = HashMap::new();
+    for (row_index, row) in rows.iter().enumerate() {
+        let key: Option<Vec<KeyCell>> = keys
+            .iter()
+            .map(|(column, _, _)| row.get(*column).and_then(key_cell))
+            .collect();
+        if let Some(key) = key {
+            index.entry(key).or_insert(row_index);
+        }
+    }
+
+    let width = header.len();
+    let mut seen: HashMap<Vec<KeyCell>, usize> = HashMap::new();
+    'rows: for (offset, source_row) in source_body.iter().enumerate() {
+        let source_index = offset + 1;
+        let mut key = Vec::with_capacity(keys.len());
+        for (target_column, source_column, kind) in &keys {
+            let column = Some(header[*target_column].clone());
+            let cell = source_row.get(*source_column).unwrap_or(&Value::Null);
+            let Some(key_part) = key_cell(cell) else {
+                plan.conflicts.push(MergeConflict {
+                    kind: ConflictKind::MissingKey,
+                    source_row: Some(source_index),
+                    column,
+                    detail: "Key cell is empty".to_owned(),
+                });
+                continue 'rows;
+            };
+            if let Some(expected) = kind
+                && *expected != key_part.0
+            {
+                plan.conflicts.push(MergeConflict {
+                    kind: ConflictKind::TypeMismatch,
+                    source_row: Some(source_index),
+                    column,
+                    detail: format!(
+                        "Expected a {} key, found {} '{}'",
+                        expected.name(),
+                        key_part.0.name(),
+                        key_part.1
+                    ),
+                });
+                continue 'rows;
+            }
+            key.push(key_part);
+        }
+
+        match seen.entry(key.clone()) {
+            Entry::Occupied(first) => {
+                plan.conflicts.push(MergeConflict {
+                    kind: ConflictKind::DuplicateKey,
+                    source_row: Some(source_index),
+                    column: None,
+                    detail: format!("Key already used by source row {}", first.get()),
+                });
+                continue 'rows;
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(source_index);
+            }
+        }
+
+        let Some(&row_index) = index.get(&key) else {
+            rows.push(placed_row(source_row, placement, width));
+            continue;
+        };
+        let Some(row) = rows.get_mut(row_index) else {
+            continue;
+        };
+        for (column, value) in placed_cells(source_row, placement) {
+            if let Some(cell) = row.get_mut(column)
+                && !same_value(cell, value)
+            {
+                plan.updates.push(CellUpdate {
+                    row: row_index + 1,
+                    column,
+                    previous: cell.clone(),
+                    value: value.clone(),
+                });
+                *cell = value.clone();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Target column, source column and expected type of each key column
+fn resolve_keys(
+    rows: &[Vec<Value>],
+    header: &[String],
+    placement: &[Option<usize>],
+    options: &MergeOptions,
+    plan: &MergePlan,
+) -> Result<Vec<(usize, usize, Option<KeyKind>)>, String> {
+    if options.key_columns.is_empty() {
+        return Err("update_by_key merges need at least one key column".to_owned());
+    }
+
+    let mut keys = Vec::with_capacity(options.key_columns.len());
+    for name in &options.key_columns {
+        let target_column = header
+            .iter()
+            .position(|column| column == name)
+            .filter(|column| !plan.columns_added.contains(&header[*column]))
+            .ok_or_else(|| format!("Key column '{name}' is not in the target header"))?;
+        let source_column = placement
+            .iter()
+            .position(|placed| *placed == Some(target_column))
+            .ok_or_else(|| {
+                format!("Key column '{name}' is not in the imported sheet; map a column to it")
+            })?;
+        let kind = rows
+            .iter()
+            .find_map(|row| row.get(target_column).and_then(key_cell))
+            .map(|(kind, _)| kind);
+        keys.push((target_column, source_column, kind));
+    }
+
+    Ok(keys)
+}
+
+/// Non-empty cells of a source row with their target column
+fn placed_cells<'row>(
+    row: &'row [Value],
+    placement: &'row [Option<usize>],
+) -> impl Iterator<Item = (usize, &'row Value)> {
+    row.iter()
+        .zip(placement)
+        .filter_map(|(value, column)| column.map(|column| (column, value)))
+        .filter(|(_, value)| !value.is_null())
+}
+
+/// New row of the merged sheet holding the placed cells of a source row
+fn placed_row(row: &[Value], placement: &[Option<usize>], width: usize) -> Vec<Value> {
+    let mut placed = vec![Value::Null; width];
+    for (column, value) in placed_cells(row, placement) {
+        if let Some(cell) = placed.get_mut(column) {
+            *cell = value.clone();
+        }
+    }
+    placed
+}
+
+/// Column name of a header cell
+fn header_name(cell: &Value) -> String {
+    match cell {
+        Value::String(name) => name.trim().to_owned(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Normalized key of a cell, `None` for empty cells
+fn key_cell(value: &Value) -> Option<KeyCell> {
+    match value {
+        Value::Null => None,
+        Value::Bool(flag) => Some((KeyKind::Boolean, flag.to_string())),
+        // 3 and 3.0 are the same key
+        Value::Number(number) => Some((
+            KeyKind::Number,
+            number
+                .as_f64()
+                .map_or_else(|| number.to_string(), |x| x.to_string()),
+        )),
+        Value::String(text) => {
+            let text = text.trim();
+            (!text.is_empty()).then(|| (KeyKind::Text, text.to_owned()))
+        }
+        other => Some((KeyKind::Text, other.to_string())),
+    }
+}
+
+/// Whether two cells hold the same value, comparing numbers by value
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => x.to_bits() == y.to_bits(),
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn options(merge_mode: MergeMode) -> MergeOptions {
+        MergeOptions {
+            merge_mode,
+            source_sheet: None,
+            column_mapping: HashMap::new(),
+            key_columns: Vec::new(),
+            unmapped_columns: UnmappedColumns::Drop,
+            delta_only: false,
+        }
+    }
+
+    fn merge(target: &[Vec<Value>], source: &[Vec<Value>], options: &MergeOptions) -> MergeResult {
+        merge_sheets(
+            "Target".to_owned(),
+            target,
+            "Sheet1".to_owned(),
+            source,
+            options,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_append_rows_with_fewer_columns_than_target() {
+        let target = vec![
+            vec![json!("time"), json!("voltage"), json!("current")],
+            vec![json!(0), json!(1.5), json!(0.1)],
+        ];
+        let source = vec![
+            vec![json!("V"), json!("time"), json!("note")],
+            vec![json!(1.7), json!(1), json!("warm")],
+            vec![json!(1.9), json!(2), Value::Null],
+        ];
+        let mut opts = options(MergeMode::AppendRows);
+        opts.column_mapping
+            .insert("V".to_owned(), "voltage".to_owned());
+        let result = merge(&target, &source, &opts);
+
+        assert_eq!(result.plan.rows_added, 2);
+        assert_eq!(result.plan.cells_updated, 0);
+        assert_eq!(result.plan.columns_missing, vec!["current"]);
+        assert_eq!(result.plan.columns_dropped, vec!["note"]);
+        assert_eq!(
+            result.merged.unwrap(),
+            vec![
+                vec![json!("time"), json!("voltage"), json!("current")],
+                vec![json!(0), json!(1.5), json!(0.1)],
+                vec![json!(1), json!(1.7), Value::Null],
+                vec![json!(2), json!(1.9), Value::Null],
+            ]
+        );
+
+        // Appended extra columns widen the existing rows
+        opts.unmapped_columns = UnmappedColumns::Append;
+        opts.delta_only = true;
+        let delta = merge(&target, &source, &opts);
+        assert_eq!(delta.plan.columns_added, vec!["note"]);
+        assert!(delta.merged.is_none());
+        assert_eq!(
+            delta.added_rows[0],
+            vec![json!(1), json!(1.7), Value::Null, json!("warm")]
+        );
+    }
+
+    #[test]
+    fn test_update_by_key_with_duplicate_source_keys() {
+        let target = vec![
+            vec![json!("id"), json!("value")],
+            vec![json!(1), json!(10)],
+            vec![json!(2), json!(20)],
+        ];
+        let source = vec![
+            vec![json!("id"), json!("value")],
+            vec![json!(2.0), json!(25)],
+            vec![json!(3), json!(30)],
+            vec![json!(2), json!(99)],
+            vec![json!(1), Value::Null],
+        ];
+        let mut opts = options(MergeMode::UpdateByKey);
+        opts.key_columns = vec!["id".to_owned()];
+        let result = merge(&target, &source, &opts);
+
+        assert_eq!(result.plan.rows_added, 1);
+        assert_eq!(result.plan.cells_updated, 1);
+        assert_eq!(
+            result.plan.updates,
+            vec![CellUpdate {
+                row: 2,
+                column: 1,
+                previous: json!(20),
+                value: json!(25),
+            }]
+        );
+        assert_eq!(result.plan.conflicts.len(), 1);
+        assert_eq!(result.plan.conflicts[0].kind, ConflictKind::DuplicateKey);
+        assert_eq!(result.plan.conflicts[0].source_row, Some(3));
+        assert_eq!(
+            result.merged.unwrap(),
+            vec![
+                vec![json!("id"), json!("value")],
+                vec![json!(1), json!(10)],
+                vec![json!(2), json!(25)],
+                vec![json!(3), json!(30)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_conflict_report_structure() {
+        let target = vec![vec![json!("id"), json!("value")], vec![json!(1), json!(10)]];
+        let source = vec![
+            vec![json!("id"), json!("value"), json!("value")],
+            vec![json!("one"), json!(11), json!(12)],
+            vec![Value::Null, json!(13), json!(14)],
+            vec![json!(1), json!(15), json!(16)],
+        ];
+        let mut opts = options(MergeMode::UpdateByKey);
+        opts.key_columns = vec!["id".to_owned()];
+        let result = merge(&target, &source, &opts);
+
+        assert_eq!(
+            serde_json::to_value(&result.plan.conflicts).unwrap(),
+            json!([
+                {
+                    "kind": "duplicate_column",
+                    "sourceRow": null,
+                    "column": "value",
+                    "detail": "Several source columns map to column 'value'"
+                },
+                {
+                    "kind": "type_mismatch",
+                    "sourceRow": 1,
+                    "column": "id",
+                    "detail": "Expected a number key, found text 'one'"
+                },
+                {
+                    "kind": "missing_key",
+                    "sourceRow": 2,
+                    "column": "id",
+                    "detail": "Key cell is empty"
+                }
+            ])
+        );
+        // Conflicting rows are skipped, the others still merge
+        assert_eq!(result.plan.updates[0].value, json!(15));
+        assert_eq!(result.plan.rows_added, 0);
+
+        opts.key_columns = vec!["missing".to_owned()];
+        assert!(merge_sheets("T".to_owned(), &target, "S".to_owned(), &source, &opts).is_err());
+    }
+
+    #[test]
+    fn test_new_columns_and_source_sheet_choice() {
+        let target = vec![vec![json!("x")], vec![json!(1)]];
+        let source = vec![
+            vec![json!("x"), json!("y")],
+            vec![json!(5), json!(2)],
+            vec![json!(6), json!(3)],
+        ];
+        let result = merge(&target, &source, &options(MergeMode::NewColumns));
+        assert_eq!(result.plan.columns_added, vec!["y"]);
+        assert_eq!(result.plan.conflicts[0].kind, ConflictKind::DuplicateColumn);
+        assert_eq!(result.plan.rows_added, 1);
+        assert_eq!(result.added_rows, vec![vec![Value::Null, json!(3)]]);
+
+        let mut sheets = HashMap::new();
+        sheets.insert("Runs".to_owned(), source.clone());
+        sheets.insert("Target".to_owned(), target.clone());
+        let response = ImportResponse { sheets };
+        let (name, rows) = take_source_sheet(response, None, "Target").unwrap();
+        assert_eq!(name, "Target");
+        assert_eq!(rows, target);
+
+        let mut ambiguous = HashMap::new();
+        ambiguous.insert("A".to_owned(), source.clone());
+        ambiguous.insert("B".to_owned(), source);
+        let ambiguous = ImportResponse { sheets: ambiguous };
+        assert!(take_source_sheet(ambiguous, None, "Target").is_err());
+    }
+}
@@ -4,12 +4,18 @@
 //!
 //! ## Supported Formats
 //! - **LOSSLESS**: anafispread (native format - full `IWorkbookData` snapshots)
-//! - **TEXT INTERCHANGE**: csv, tsv, txt (for external application data)
+//! - **TEXT INTERCHANGE**: csv, tsv, txt, json (for external application data)
 //! - **COLUMNAR**: parquet (efficient binary columnar format)
+//! - **SPREADSHEET**: xlsx (Excel workbooks, all sheets)
+//! - **DATABASE**: sqlite (result of a read-only SELECT query)
+//! - **REMOTE**: csv, tsv, txt, json and parquet downloaded from an HTTP(S) URL
+//!
+//! Formulas of .anafispread snapshots can also be audited for broken dependency chains,
+//! and imported sheets can be merged into an existing sheet of the open workbook.
 //!
 //! The module handles parsing and converting various file formats to Univer-compatible workbook data.
 
-use crate::error::{CommandResult, file_not_found, import_error, validation_error};
+use crate::error::{CommandError, CommandResult, codes, import_error, io_error, validation_error};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
@@ -18,16 +24,32 @@ use std::path::{Path, PathBuf};
 use tokio::fs::metadata;
 use tokio::task::spawn_blocking;
 
-use self::anafispread::import_anafis_spread;
-use self::csv::{import_csv, import_tsv, import_txt};
+use self::anafispread::{format_version, import_anafis_spread};
+use self::column_types::{ColumnTypeInference, ColumnTypeOptions};
+use self::csv::{
+    csv_stats, detect_delimiter, import_csv, import_csv_streaming, import_tsv, import_txt,
+};
+use self::excel::import_xlsx;
+use self::formula_audit::{FormulaAuditReport, audit_formulas};
+use self::json::import_json;
+use self::merge::{MergeOptions, MergeResult, merge_sheets, take_source_sheet};
 use self::parquet::import_parquet;
+use self::sqlite::{import_sqlite, sqlite_tables};
 
+use tauri::ipc::Channel;
 use tokio::fs::File;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+
+/// Text files above this size get their metadata from a raw line count
+/// instead of a full decode
+const LARGE_TEXT_FILE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Lines sampled when detecting the delimiter of a text file
+const DELIMITER_SAMPLE_LINES: usize = 50;
 
 /// Validate and canonicalize a file path to prevent directory traversal
 /// Returns the canonicalized path if valid, or an error if invalid
-fn validate_and_canonicalize_path(file_path: &str) -> Result<PathBuf, String> {
+pub fn validate_and_canonicalize_path(file_path: &str) -> Result<PathBuf, String> {
     // Canonicalize the path to resolve any .. or . components
     let path = Path::new(file_path);
     let canonical_path = path
@@ -44,8 +66,15 @@ fn validate_and_canonicalize_path(file_path: &str) -> Result<PathBuf, String> {
 
 // Submodules for specific format parsers
 pub mod anafispread;
+pub mod column_types;
 pub mod csv;
+pub mod excel;
+pub mod formula_audit;
+pub mod json;
+pub mod merge;
 pub mod parquet;
+pub mod sqlite;
+pub mod url;
 
 /// Import options sent from frontend (simplified structure)
 #[derive(Debug, Serialize, Deserialize)]
@@ -58,6 +87,12 @@ pub struct ImportOptionsFrontend {
     pub delimiter: String,
     #[serde(default)]
     pub encoding: String,
+    /// SELECT query run on sqlite databases
+    #[serde(default)]
+    pub query: String,
+    /// Largest download accepted by URL imports, in bytes
+    #[serde(default)]
+    pub max_download_bytes: Option<u64>,
 }
 /// File metadata extracted from import files
 #[derive(Debug, Serialize, Deserialize)]
@@ -70,6 +105,10 @@ pub struct FileMetadata {
     pub column_count: Option<usize>,
     pub has_formulas: Option<bool>,
     pub has_formatting: Option<bool>,
+    /// Format version of native .anafispread files
+    pub format_version: Option<u32>,
+    /// Delimiter detected in text files (csv, tsv, txt)
+    pub detected_delimiter: Option<char>,
 }
 
 /// Frontend-compatible import response structure
@@ -79,6 +118,42 @@ pub struct ImportResponse {
     pub sheets: HashMap<String, Vec<Vec<Value>>>,
 }
 
+/// One chunk of rows sent by the streaming CSV import
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvChunk {
+    /// Position of this chunk in the stream, from 0
+    pub index: usize,
+    /// Index of the first row of this chunk among all imported rows
+    pub first_row: usize,
+    /// Parsed rows, not padded to a common width
+    pub rows: Vec<Vec<Value>>,
+}
+
+/// Totals of a completed streaming CSV import
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvStreamSummary {
+    /// Number of chunks sent
+    pub chunk_count: usize,
+    /// Number of rows sent
+    pub row_count: usize,
+    /// Widest row, for padding on the receiving side
+    pub max_columns: usize,
+}
+
+/// Size and line count of a delimited text file
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvStats {
+    /// File size in bytes
+    pub size: u64,
+    /// Number of non-blank lines
+    pub line_count: usize,
+    /// Number of fields in the first non-blank line
+    pub column_count: usize,
+}
+
 /// Main import command - called from frontend
 #[tauri::command]
 pub async fn import_spreadsheet_file(
@@ -90,35 +165,170 @@ pub async fn import_spreadsheet_file(
         .map_err(|e| validation_error(e, Some("file_path".to_owned())))?;
 
     // All parsers use blocking std::fs I/O; move them off the async executor
+    spawn_blocking(move || import_by_format(&canonical_path.to_string_lossy(), &options))
+        .await
+        .map_err(|e| import_error(format!("Import task panicked: {e}")))?
+}
+
+/// Import a file and merge one of its sheets into an existing sheet
+///
+/// `target_rows` holds the current content of the target sheet, header row
+/// first. The result lists the rows added, cells updated and conflicting rows,
+/// with the whole merged sheet unless only the delta was requested.
+#[tauri::command]
+pub async fn import_and_merge(
+    file_path: String,
+    options: ImportOptionsFrontend,
+    target_sheet: String,
+    target_rows: Vec<Vec<Value>>,
+    merge: MergeOptions,
+) -> CommandResult<MergeResult> {
+    let canonical_path = validate_and_canonicalize_path(&file_path)
+        .map_err(|e| validation_error(e, Some("file_path".to_owned())))?;
+
     spawn_blocking(move || {
-        let path_str = canonical_path.to_string_lossy();
-        match options.format.as_str() {
-            "csv" => import_csv(&path_str, options.skip_rows, false, Some(&options.encoding))
-                .map_err(|e| import_error(format!("CSV import failed: {e}"))),
-            "tsv" => import_tsv(&path_str, options.skip_rows, false, Some(&options.encoding))
-                .map_err(|e| import_error(format!("TSV import failed: {e}"))),
-            "txt" => import_txt(
-                &path_str,
-                &options.delimiter,
+        let response = import_by_format(&canonical_path.to_string_lossy(), &options)?;
+        let (source_sheet, source_rows) =
+            take_source_sheet(response, merge.source_sheet.as_deref(), &target_sheet)
+                .map_err(|e| validation_error(e, Some("source_sheet".to_owned())))?;
+        merge_sheets(
+            target_sheet,
+            &target_rows,
+            source_sheet,
+            &source_rows,
+            &merge,
+        )
+        .map_err(|e| validation_error(e, Some("merge".to_owned())))
+    })
+    .await
+    .map_err(|e| import_error(format!("Import task panicked: {e}")))?
+}
+
+/// Parse a file with the parser of the requested format
+fn import_by_format(
+    path_str: &str,
+    options: &ImportOptionsFrontend,
+) -> CommandResult<ImportResponse> {
+    match options.format.as_str() {
+        "csv" => import_csv(path_str, options.skip_rows, false, Some(&options.encoding))
+            .map_err(|e| import_error(format!("CSV import failed: {e}"))),
+        "tsv" => import_tsv(path_str, options.skip_rows, false, Some(&options.encoding))
+            .map_err(|e| import_error(format!("TSV import failed: {e}"))),
+        "txt" => {
+            // Without an explicit delimiter, detect it from the first lines
+            let delimiter = if options.delimiter.is_empty() {
+                detect_delimiter(path_str, DELIMITER_SAMPLE_LINES)
+                    .map_err(|e| import_error(format!("TXT import failed: {e}")))?
+                    .to_string()
+            } else {
+                options.delimiter.clone()
+            };
+            import_txt(
+                path_str,
+                &delimiter,
                 options.skip_rows,
                 false,
                 Some(&options.encoding),
             )
-            .map_err(|e| import_error(format!("TXT import failed: {e}"))),
-            "anafispread" => Err(import_error(
-                "Use import_anafis_spread_direct for .anafispread files".to_owned(),
-            )),
-            "parquet" => import_parquet(&path_str)
-                .map_err(|e| import_error(format!("Parquet import failed: {e}"))),
-            _ => Err(validation_error(
-                format!("Unsupported format: {}", options.format),
-                Some("format".to_owned()),
-            )),
+            .map_err(|e| import_error(format!("TXT import failed: {e}")))
+        }
+        "anafispread" => Err(import_error(
+            "Use import_anafis_spread_direct for .anafispread files".to_owned(),
+        )),
+        "parquet" => import_parquet(path_str)
+            .map_err(|e| import_error(format!("Parquet import failed: {e}"))),
+        "json" => {
+            import_json(path_str).map_err(|e| import_error(format!("JSON import failed: {e}")))
+        }
+        "sqlite" => {
+            if options.query.trim().is_empty() {
+                return Err(validation_error(
+                    "A SELECT query is required to import from a SQLite database",
+                    Some("query".to_owned()),
+                ));
+            }
+            import_sqlite(path_str, &options.query)
+                .map_err(|e| import_error(format!("SQLite import failed: {e}")))
+        }
+        "xlsx" => {
+            import_xlsx(path_str).map_err(|e| import_error(format!("Excel import failed: {e}")))
         }
+        _ => Err(CommandError::Validation {
+            code: codes::UNSUPPORTED_FORMAT,
+            field: Some("format".to_owned()),
+            detail: format!("Unsupported format: {}", options.format),
+        }),
+    }
+}
+
+/// Streaming import of a delimited text file - rows are sent to `on_chunk`
+/// `chunk_size` at a time, so files larger than memory can be loaded
+/// incrementally
+#[tauri::command]
+pub async fn import_csv_stream(
+    file_path: String,
+    skip_rows: usize,
+    delimiter: String,
+    chunk_size: usize,
+    on_chunk: Channel<CsvChunk>,
+) -> CommandResult<CsvStreamSummary> {
+    let canonical_path = validate_and_canonicalize_path(&file_path)
+        .map_err(|e| validation_error(e, Some("file_path".to_owned())))?;
+    let delimiter = delimiter.chars().next().ok_or_else(|| {
+        validation_error(
+            "Delimiter must be at least one character",
+            Some("delimiter".to_owned()),
+        )
+    })?;
+
+    import_csv_streaming(&canonical_path, skip_rows, delimiter, chunk_size, |chunk| {
+        on_chunk
+            .send(chunk)
+            .map_err(|e| format!("Failed to send rows to the frontend: {e}"))
+    })
+    .await
+    .map_err(|e| import_error(format!("Streaming import failed: {e}")))
+}
+
+/// Line and column counts of a text file, read without loading it - use
+/// instead of `get_file_metadata` dimensions for very large files
+/// For TXT files, pass delimiter parameter; if None, uses default "|"
+#[tauri::command]
+pub async fn import_csv_stats(
+    file_path: String,
+    delimiter: Option<String>,
+) -> CommandResult<CsvStats> {
+    let canonical_path = validate_and_canonicalize_path(&file_path)
+        .map_err(|e| validation_error(e, Some("file_path".to_owned())))?;
+    let extension = canonical_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    csv_stats(
+        &canonical_path,
+        text_delimiter(&extension, delimiter.as_deref()),
+    )
+    .await
+    .map_err(|e| import_error(format!("Failed to count lines: {e}")))
+}
+
+/// Tables and views of a `SQLite` database, for choosing what to import
+#[tauri::command]
+pub async fn list_sqlite_tables(file_path: String) -> CommandResult<Vec<String>> {
+    let canonical_path = validate_and_canonicalize_path(&file_path)
+        .map_err(|e| validation_error(e, Some("file_path".to_owned())))?;
+
+    let path_str = canonical_path.to_string_lossy().to_string();
+    spawn_blocking(move || {
+        sqlite_tables(&path_str)
+            .map_err(|e| import_error(format!("Failed to list SQLite tables: {e}")))
     })
     .await
     .map_err(|e| import_error(format!("Import task panicked: {e}")))?
 }
+
 /// Direct import command for .anafispread format
 /// Returns raw `IWorkbookData` without conversion for lossless snapshot loading
 #[tauri::command]
@@ -130,8 +340,38 @@ pub async fn import_anafis_spread_direct(file_path: String) -> CommandResult<Val
     let path_str = canonical_path.to_string_lossy().to_string();
     // anafispread uses blocking std::fs + GzDecoder; move off the async executor
     spawn_blocking(move || {
-        import_anafis_spread(path_str)
-            .map_err(|e| import_error(format!("AnaFis spread import failed: {e}")))
+        import_anafis_spread(path_str).map_err(|e| e.context("AnaFis spread import failed"))
+    })
+    .await
+    .map_err(|e| import_error(format!("Import task panicked: {e}")))?
+}
+
+/// Infer the type of each column of an imported value grid - integer, float,
+/// boolean, date (with its format), text or mixed - with the parse success
+/// rate, the rows that do not conform and suggested conversions
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn infer_column_types(
+    rows: Vec<Vec<Value>>,
+    options: Option<ColumnTypeOptions>,
+) -> CommandResult<Vec<ColumnTypeInference>> {
+    column_types::infer_column_types(&rows, &options.unwrap_or_default())
+        .map_err(|e| validation_error(e, Some("options".to_owned())))
+}
+
+/// Formula audit of an .anafispread file - circular references, references
+/// to missing sheets or to out-of-range and empty cells, volatile chains and
+/// the deepest dependency chain
+#[tauri::command]
+pub async fn audit_workbook_formulas(file_path: String) -> CommandResult<FormulaAuditReport> {
+    let canonical_path = validate_and_canonicalize_path(&file_path)
+        .map_err(|e| validation_error(e, Some("file_path".to_owned())))?;
+
+    let path_str = canonical_path.to_string_lossy().to_string();
+    spawn_blocking(move || {
+        let workbook =
+            import_anafis_spread(path_str).map_err(|e| e.context("AnaFis spread import failed"))?;
+        audit_formulas(&workbook).map_err(|e| import_error(format!("Formula audit failed: {e}")))
     })
     .await
     .map_err(|e| import_error(format!("Import task panicked: {e}")))?
@@ -149,9 +389,12 @@ pub async fn get_file_metadata(
         .map_err(|e| validation_error(e, Some("file_path".to_owned())))?;
 
     // Get file size (use tokio::fs to avoid blocking the async executor)
-    let metadata = metadata(&canonical_path)
-        .await
-        .map_err(|e| file_not_found(format!("Failed to read file metadata: {e}")))?;
+    let metadata = metadata(&canonical_path).await.map_err(|e| {
+        io_error(
+            Some(file_path.clone()),
+            format!("Failed to read file metadata: {e}"),
+        )
+    })?;
     let size = metadata.len();
 
     // Get extension
@@ -161,15 +404,44 @@ pub async fn get_file_metadata(
         .unwrap_or("")
         .to_lowercase();
 
+    // Sample the first lines of text files for their delimiter
+    let detected_delimiter = if matches!(extension.as_str(), "csv" | "tsv" | "txt") {
+        let path_str = canonical_path.to_string_lossy().to_string();
+        spawn_blocking(move || detect_delimiter(&path_str, DELIMITER_SAMPLE_LINES).ok())
+            .await
+            .ok()
+            .flatten()
+    } else {
+        None
+    };
+    // TXT files without an explicit delimiter are measured with the detected one
+    let delimiter = delimiter.filter(|d| !d.is_empty()).or_else(|| {
+        detected_delimiter
+            .filter(|_| extension == "txt")
+            .map(String::from)
+    });
+
     // Try to get row/column count for supported formats
     let (row_count, column_count) = match extension.as_str() {
         "csv" | "tsv" | "txt" => {
             // For text-based formats, read all lines to determine dimensions
             // For TXT, use the provided delimiter or default to "|"
-            match get_text_file_dimensions(&canonical_path, &extension, delimiter.as_deref()).await
-            {
-                Ok((rows, cols)) => (Some(rows), Some(cols)),
-                Err(_) => (None, None), // Silently fail - metadata is optional
+            // Large files only get a raw line count and the first row's width
+            if size > LARGE_TEXT_FILE_BYTES {
+                let delimiter = text_delimiter(&extension, delimiter.as_deref());
+                // Metadata is optional, so a failed count leaves it unknown
+                csv_stats(&canonical_path, delimiter)
+                    .await
+                    .map_or((None, None), |stats| {
+                        (Some(stats.line_count), Some(stats.column_count))
+                    })
+            } else {
+                match get_text_file_dimensions(&canonical_path, &extension, delimiter.as_deref())
+                    .await
+                {
+                    Ok((rows, cols)) => (Some(rows), Some(cols)),
+                    Err(_) => (None, None), // Silently fail - metadata is optional
+                }
             }
         }
         "parquet" => {
@@ -183,6 +455,13 @@ pub async fn get_file_metadata(
         _ => (None, None),
     };
 
+    // The version sits in the fixed preamble, so only those bytes are read
+    let format_version = if extension == "anafispread" {
+        read_anafispread_version(&canonical_path).await.ok()
+    } else {
+        None
+    };
+
     Ok(FileMetadata {
         path: file_path,
         size,
@@ -191,9 +470,33 @@ pub async fn get_file_metadata(
         column_count,
         has_formulas: None,
         has_formatting: None,
+        format_version,
+        detected_delimiter,
     })
 }
 
+/// Format version of an .anafispread file, read from its first 12 bytes
+async fn read_anafispread_version(file_path: &Path) -> Result<u32, String> {
+    let mut preamble = [0_u8; 12];
+    File::open(file_path)
+        .await
+        .map_err(|e| format!("Failed to open file: {e}"))?
+        .read_exact(&mut preamble)
+        .await
+        .map_err(|e| format!("Failed to read file header: {e}"))?;
+    format_version(&preamble)
+}
+
+/// Delimiter of a text file from its extension; TXT uses the provided
+/// delimiter or "|"
+fn text_delimiter(extension: &str, txt_delimiter: Option<&str>) -> char {
+    match extension {
+        "tsv" => '\t',
+        "txt" => txt_delimiter.and_then(|d| d.chars().next()).unwrap_or('|'),
+        _ => ',',
+    }
+}
+
 /// Helper function to get dimensions of text-based files (CSV, TSV, TXT)
 /// For TXT files, delimiter parameter can be provided; if None, uses default "|"
 async fn get_text_file_dimensions(
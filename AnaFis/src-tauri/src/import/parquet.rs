@@ -4,7 +4,8 @@
 // Parquet is a columnar binary format commonly used for data science and analytics.
 
 use super::ImportResponse;
-use parquet::file::reader::{FileReader, SerializedFileReader};
+use bytes::Bytes;
+use parquet::file::reader::{ChunkReader, FileReader, SerializedFileReader};
 use parquet::record::{Field, Row};
 use serde_json::{Number, Value};
 use std::collections::HashMap;
@@ -18,6 +19,25 @@ pub fn import_parquet(file_path: &str) -> Result<ImportResponse, String> {
     let reader =
         SerializedFileReader::new(file).map_err(|e| format!("Failed to read parquet file: {e}"))?;
 
+    read_rows(&reader)
+}
+
+/// Import a Parquet file held in memory, such as a downloaded file
+///
+/// # Errors
+/// Returns an error if the bytes are not a valid Parquet file or a row cannot
+/// be read
+pub fn import_parquet_from_bytes(bytes: Vec<u8>) -> Result<ImportResponse, String> {
+    let reader = SerializedFileReader::new(Bytes::from(bytes))
+        .map_err(|e| format!("Failed to read parquet file: {e}"))?;
+
+    read_rows(&reader)
+}
+
+/// Header row of column names followed by every row of `reader`
+fn read_rows<R: ChunkReader + 'static>(
+    reader: &SerializedFileReader<R>,
+) -> Result<ImportResponse, String> {
     // Get row iterator
     let row_iter = reader
         .get_row_iter(None)
@@ -0,0 +1,211 @@
+// SQLite import handler
+//
+// Imports the result of a user-supplied SELECT query on a SQLite database and
+// converts it to Univer-compatible workbook data, with the column names as the
+// first row. The database is opened read-only, and queries containing
+// data-modifying keywords are rejected before they reach SQLite.
+
+use super::ImportResponse;
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags};
+use serde_json::{Number, Value};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Keywords that modify a database or its schema. `REPLACE` is left out
+/// because it is also a string function; `REPLACE` and `INSERT OR REPLACE`
+/// statements are refused by the other checks.
+const FORBIDDEN_KEYWORDS: [&str; 9] = [
+    "DROP", "CREATE", "INSERT", "UPDATE", "DELETE", "ALTER", "ATTACH", "DETACH", "PRAGMA",
+];
+
+/// Open a `SQLite` database without write access
+fn open_read_only(file_path: &str) -> Result<Connection, String> {
+    Connection::open_with_flags(
+        file_path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| format!("Failed to open database: {e}"))
+}
+
+/// Reject queries that are not a single read-only SELECT
+///
+/// Words inside quotes are ignored, so string literals and quoted identifiers
+/// may contain the forbidden keywords
+fn validate_query(query: &str) -> Result<(), String> {
+    // Blank out quoted text before splitting into words
+    let mut unquoted = String::with_capacity(query.len());
+    let mut quote = None;
+    for ch in query.chars() {
+        match quote {
+            Some(open) => {
+                if ch == open {
+                    quote = None;
+                }
+                unquoted.push(' ');
+            }
+            None if matches!(ch, '\'' | '"' | '`') => {
+                quote = Some(ch);
+                unquoted.push(' ');
+            }
+            None => unquoted.push(ch),
+        }
+    }
+    let words: Vec<String> = unquoted
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_uppercase)
+        .collect();
+
+    if !matches!(words.first().map(String::as_str), Some("SELECT" | "WITH")) {
+        return Err("Only SELECT queries can be imported".to_owned());
+    }
+    if let Some(keyword) = words
+        .iter()
+        .find(|word| FORBIDDEN_KEYWORDS.contains(&word.as_str()))
+    {
+        return Err(format!(
+            "Query contains the data-modifying keyword {keyword}"
+        ));
+    }
+    Ok(())
+}
+
+/// Import the result of a SELECT query on a `SQLite` database
+///
+/// # Errors
+/// Returns an error if the query is not a single read-only SELECT, the
+/// database cannot be opened, or the query fails
+pub fn import_sqlite(file_path: &str, query: &str) -> Result<ImportResponse, String> {
+    validate_query(query)?;
+    let conn = open_read_only(file_path)?;
+
+    let mut statement = conn
+        .prepare(query)
+        .map_err(|e| format!("Invalid query: {e}"))?;
+    if !statement.readonly() {
+        return Err("Only read-only queries can be imported".to_owned());
+    }
+
+    let column_count = statement.column_count();
+    let mut sheet_data: Vec<Vec<Value>> = vec![
+        statement
+            .column_names()
+            .into_iter()
+            .map(|name| Value::String(name.to_owned()))
+            .collect(),
+    ];
+
+    let mut rows = statement
+        .query([])
+        .map_err(|e| format!("Query failed: {e}"))?;
+    while let Some(row) = rows
+        .next()
+        .map_err(|e| format!("Failed to read row: {e}"))?
+    {
+        let json_row = (0..column_count)
+            .map(|index| {
+                row.get_ref(index)
+                    .map(convert_sqlite_value_to_json)
+                    .map_err(|e| format!("Failed to read column {index}: {e}"))
+            })
+            .collect::<Result<Vec<Value>, String>>()?;
+        sheet_data.push(json_row);
+    }
+
+    let mut sheets = HashMap::new();
+    sheets.insert("Sheet1".to_owned(), sheet_data);
+
+    Ok(ImportResponse { sheets })
+}
+
+/// Names of the tables and views of a `SQLite` database, sorted
+///
+/// # Errors
+/// Returns an error if the database cannot be opened or read
+pub fn sqlite_tables(file_path: &str) -> Result<Vec<String>, String> {
+    let conn = open_read_only(file_path)?;
+    let mut statement = conn
+        .prepare(
+            "SELECT name FROM sqlite_master \
+             WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        )
+        .map_err(|e| format!("Failed to read schema: {e}"))?;
+    statement
+        .query_map([], |row| row.get(0))
+        .map_err(|e| format!("Failed to read schema: {e}"))?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| format!("Failed to read table name: {e}"))
+}
+
+/// Convert a `SQLite` value to JSON; blobs become hex strings
+fn convert_sqlite_value_to_json(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::Number(i.into()),
+        ValueRef::Real(f) => Number::from_f64(f).map_or(Value::Null, Value::Number),
+        ValueRef::Text(text) => Value::String(String::from_utf8_lossy(text).into_owned()),
+        ValueRef::Blob(bytes) => {
+            let mut hex_str = String::with_capacity(2 + bytes.len() * 2);
+            hex_str.push_str("0x");
+            for byte in bytes {
+                write!(hex_str, "{byte:02x}").expect("Writing to String should not fail");
+            }
+            Value::String(hex_str)
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::env::temp_dir;
+    use std::fs::remove_file;
+
+    #[test]
+    fn test_import_select_with_header_row() {
+        let path = temp_dir().join(format!("anafis-sqlite-{}.db", std::process::id()));
+        let path_str = path.to_string_lossy().to_string();
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE samples (id INTEGER, label TEXT, value REAL, raw BLOB);
+                 INSERT INTO samples VALUES (1, 'a', 1.5, x'0aff'), (2, NULL, NULL, NULL);
+                 CREATE VIEW labelled AS SELECT * FROM samples WHERE label IS NOT NULL;",
+            )
+            .unwrap();
+        }
+
+        let response = import_sqlite(
+            &path_str,
+            "SELECT id, label, value, raw FROM samples ORDER BY id",
+        )
+        .unwrap();
+        let tables = sqlite_tables(&path_str).unwrap();
+        remove_file(&path).unwrap();
+
+        assert_eq!(
+            response.sheets["Sheet1"],
+            vec![
+                vec![json!("id"), json!("label"), json!("value"), json!("raw")],
+                vec![json!(1), json!("a"), json!(1.5), json!("0x0aff")],
+                vec![json!(2), Value::Null, Value::Null, Value::Null],
+            ]
+        );
+        assert_eq!(tables, ["labelled", "samples"]);
+    }
+
+    #[test]
+    fn test_query_guard_rejects_mutations() {
+        assert!(validate_query("select * from t where note = 'drop me'").is_ok());
+        assert!(validate_query("WITH x AS (SELECT 1) SELECT * FROM x").is_ok());
+        assert!(validate_query("SELECT updated_at FROM t").is_ok());
+        assert!(validate_query("SELECT replace(label, 'a', 'b') FROM t").is_ok());
+        assert!(validate_query("REPLACE INTO t VALUES (1)").is_err());
+        assert!(validate_query("DELETE FROM t").is_err());
+        assert!(validate_query("SELECT 1; DROP TABLE t").is_err());
+        assert!(validate_query("PRAGMA table_info(t)").is_err());
+    }
+}
@@ -0,0 +1,335 @@
+// URL import handler
+//
+// Downloads a dataset over HTTP or HTTPS and hands the body to the CSV, TSV,
+// TXT, JSON or Parquet parser. The parser is chosen from the Content-Type
+// header, then from the extension of the final URL path, then from the
+// requested format.
+//
+// Only remote hosts are fetched: `localhost`, loopback and unspecified
+// addresses are refused, including as redirect targets. Host names are checked
+// again after DNS resolution, so a name pointing at this machine is refused
+// too. Downloads larger than the configured maximum are aborted while
+// streaming.
+
+use super::csv::import_csv_from_bytes;
+use super::json::import_json_from_bytes;
+use super::parquet::import_parquet_from_bytes;
+use super::{ImportOptionsFrontend, ImportResponse};
+use crate::error::{CommandError, CommandResult, import_error, validation_error};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::redirect::Policy;
+use reqwest::{Client, Url};
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use tokio::net::lookup_host;
+use tokio::task::spawn_blocking;
+
+/// Largest download accepted when the options give no maximum: 50MB
+const DEFAULT_MAX_DOWNLOAD_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Time allowed for the whole request, body included
+const REQUEST_TIMEOUT: Duration = Duration::from_mins(1);
+
+/// Redirects followed before giving up
+const MAX_REDIRECTS: usize = 10;
+
+/// User-Agent header sent with every request
+const USER_AGENT: &str = concat!("AnaFis/", env!("CARGO_PKG_VERSION"));
+
+/// Import a dataset from an HTTP or HTTPS URL
+#[tauri::command]
+pub async fn import_from_url(
+    url: String,
+    options: ImportOptionsFrontend,
+) -> CommandResult<ImportResponse> {
+    let url = Url::parse(url.trim())
+        .map_err(|e| validation_error(format!("Invalid URL: {e}"), Some("url".to_owned())))?;
+    check_remote_url(&url).map_err(|e| validation_error(e, Some("url".to_owned())))?;
+    let max_bytes = options
+        .max_download_bytes
+        .unwrap_or(DEFAULT_MAX_DOWNLOAD_BYTES);
+
+    let client = Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(USER_AGENT)
+        .dns_resolver(RemoteOnlyResolver(SystemResolver))
+        .redirect(Policy::custom(|attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                return attempt.error("Too many redirects");
+            }
+            match check_remote_url(attempt.url()) {
+                Ok(()) => attempt.follow(),
+                Err(e) => attempt.error(e),
+            }
+        }))
+        .build()
+        .map_err(|e| import_error(format!("Failed to create HTTP client: {e}")))?;
+
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| import_error(format!("Download failed: {e}")))?;
+    if let Some(length) = response.content_length()
+        && length > max_bytes
+    {
+        return Err(too_large(max_bytes));
+    }
+
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned);
+    let format =
+        body_format(content_type.as_deref(), response.url(), &options.format).ok_or_else(|| {
+            validation_error(
+                format!(
+                    "Cannot tell the format of the download (Content-Type: {})",
+                    content_type.as_deref().unwrap_or("none")
+                ),
+                Some("format".to_owned()),
+            )
+        })?;
+
+    // Content-Length may be missing or wrong, so the limit is enforced while reading
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| import_error(format!("Download failed: {e}")))?
+    {
+        if u64::try_from(body.len() + chunk.len()).unwrap_or(u64::MAX) > max_bytes {
+            return Err(too_large(max_bytes));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    // The parsers are synchronous; move them off the async executor
+    spawn_blocking(move || match format {
+        "csv" | "tsv" | "txt" => {
+            let delimiter = match format {
+                "csv" => ',',
+                "tsv" => '\t',
+                _ => options.delimiter.chars().next().ok_or_else(|| {
+                    validation_error(
+                        "A delimiter is required to import TXT data",
+                        Some("delimiter".to_owned()),
+                    )
+                })?,
+            };
+            import_csv_from_bytes(
+                &body,
+                delimiter,
+                options.skip_rows,
+                false,
+                Some(&options.encoding),
+            )
+            .map_err(|e| import_error(format!("Delimited text import failed: {e}")))
+        }
+        "json" => import_json_from_bytes(&body)
+            .map_err(|e| import_error(format!("JSON import failed: {e}"))),
+        _ => import_parquet_from_bytes(body)
+            .map_err(|e| import_error(format!("Parquet import failed: {e}"))),
+    })
+    .await
+    .map_err(|e| import_error(format!("Import task panicked: {e}")))?
+}
+
+/// Error for downloads above `max_bytes`
+#[allow(
+    clippy::integer_division,
+    reason = "Integer division is acceptable for approximate MB display"
+)]
+fn too_large(max_bytes: u64) -> CommandError {
+    import_error(format!(
+        "Download too large (maximum: {} MB)",
+        max_bytes / (1024 * 1024)
+    ))
+}
+
+/// Check that `url` is HTTP or HTTPS on a host other than this machine
+fn check_remote_url(url: &Url) -> Result<(), String> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!(
+            "Only HTTP and HTTPS URLs can be imported, got '{}'",
+            url.scheme()
+        ));
+    }
+    let host = url
+        .host_str()
+        .ok_or("URL has no host")?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_lowercase();
+    let is_local = host == "localhost"
+        || host.ends_with(".localhost")
+        || host.parse::<IpAddr>().is_ok_and(is_local_address);
+    if is_local {
+        return Err(format!("Importing from local host '{host}' is not allowed"));
+    }
+    Ok(())
+}
+
+/// Whether `address` belongs to this machine
+const fn is_local_address(address: IpAddr) -> bool {
+    let address = address.to_canonical();
+    address.is_loopback() || address.is_unspecified()
+}
+
+/// Resolver backed by the operating system's name lookup
+struct SystemResolver;
+
+impl Resolve for SystemResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_owned();
+        Box::pin(async move {
+            let addresses: Addrs = Box::new(lookup_host((host, 0)).await?);
+            Ok(addresses)
+        })
+    }
+}
+
+/// Resolver that refuses host names with any address on this machine
+///
+/// The host string check cannot see where a name points; this one runs on
+/// every lookup, redirects included.
+struct RemoteOnlyResolver<R>(R);
+
+impl<R: Resolve> Resolve for RemoteOnlyResolver<R> {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_owned();
+        let lookup = self.0.resolve(name);
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = lookup.await?.collect();
+            if addresses
+                .iter()
+                .any(|address| is_local_address(address.ip()))
+            {
+                return Err(format!(
+                    "Importing from '{host}' is not allowed: it resolves to this machine"
+                )
+                .into());
+            }
+            let addresses: Addrs = Box::new(addresses.into_iter());
+            Ok(addresses)
+        })
+    }
+}
+
+/// Parser for a download: from its media type, else the extension of the URL
+/// path, else the requested format
+fn body_format(content_type: Option<&str>, url: &Url, requested: &str) -> Option<&'static str> {
+    let media_type = content_type
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_lowercase())
+        .unwrap_or_default();
+    let from_media_type = match media_type.as_str() {
+        "text/csv" | "application/csv" | "text/comma-separated-values" => Some("csv"),
+        "text/tab-separated-values" => Some("tsv"),
+        "application/json" | "text/json" => Some("json"),
+        "application/vnd.apache.parquet" | "application/x-parquet" | "application/parquet" => {
+            Some("parquet")
+        }
+        other if other.ends_with("+json") => Some("json"),
+        _ => None,
+    };
+    let extension = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, extension)| extension.to_lowercase());
+    let known = |format: &str| {
+        ["csv", "tsv", "txt", "json", "parquet"]
+            .into_iter()
+            .find(|candidate| format.eq_ignore_ascii_case(candidate))
+    };
+
+    from_media_type
+        .or_else(|| extension.as_deref().and_then(known))
+        .or_else(|| known(requested))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+    use std::str::FromStr;
+    use tauri::async_runtime::block_on;
+
+    /// Resolver answering every name with one fixed address
+    struct FixedResolver(IpAddr);
+
+    impl Resolve for FixedResolver {
+        fn resolve(&self, _name: Name) -> Resolving {
+            let addresses: Addrs = Box::new(std::iter::once(SocketAddr::new(self.0, 0)));
+            Box::pin(async move { Ok(addresses) })
+        }
+    }
+
+    fn url(text: &str) -> Url {
+        Url::parse(text).unwrap()
+    }
+
+    #[test]
+    fn test_only_remote_http_urls_are_allowed() {
+        assert!(check_remote_url(&url("https://www.itl.nist.gov/div898/strd/data.csv")).is_ok());
+        assert!(check_remote_url(&url("http://203.0.113.7/data.json")).is_ok());
+
+        for refused in [
+            "ftp://example.org/data.csv",
+            "file:///etc/passwd",
+            "http://localhost:8080/data.csv",
+            "http://api.localhost/data.csv",
+            "http://127.0.0.1/data.csv",
+            "http://127.10.0.1/data.csv",
+            "http://0.0.0.0/data.csv",
+            "http://[::1]/data.csv",
+            "http://[::ffff:127.0.0.1]/data.csv",
+        ] {
+            assert!(check_remote_url(&url(refused)).is_err(), "{refused}");
+        }
+    }
+
+    #[test]
+    fn test_names_resolving_to_this_machine_are_refused() {
+        let resolve = |address: IpAddr| {
+            block_on(
+                RemoteOnlyResolver(FixedResolver(address))
+                    .resolve(Name::from_str("data.example.org").unwrap()),
+            )
+        };
+
+        let error = resolve(IpAddr::V4(Ipv4Addr::LOCALHOST)).err().unwrap();
+        assert!(error.to_string().contains("data.example.org"));
+        assert!(resolve("::1".parse().unwrap()).is_err());
+        assert!(resolve(IpAddr::V4(Ipv4Addr::UNSPECIFIED)).is_err());
+
+        let remote: Vec<SocketAddr> = resolve("203.0.113.7".parse().unwrap()).unwrap().collect();
+        assert_eq!(remote, vec!["203.0.113.7:0".parse().unwrap()]);
+    }
+
+    #[test]
+    fn test_format_from_content_type_extension_and_request() {
+        let data = url("https://example.org/files/data.parquet?download=1");
+        assert_eq!(
+            body_format(Some("text/csv; charset=utf-8"), &data, "json"),
+            Some("csv")
+        );
+        assert_eq!(
+            body_format(Some("application/vnd.api+json"), &data, ""),
+            Some("json")
+        );
+        // Generic media types fall back to the extension, then the request
+        assert_eq!(
+            body_format(Some("application/octet-stream"), &data, "csv"),
+            Some("parquet")
+        );
+        let plain = url("https://example.org/export");
+        assert_eq!(body_format(Some("text/plain"), &plain, "TSV"), Some("tsv"));
+        assert_eq!(body_format(None, &plain, "xlsx"), None);
+    }
+}
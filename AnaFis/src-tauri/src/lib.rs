@@ -9,10 +9,8 @@ mod unit_conversion;
 mod utils;
 mod windows;
 
-use std::env::args;
 mod startup;
 
-use std::sync::Mutex;
 use std::thread::{sleep, spawn};
 use std::time::Duration;
 
@@ -23,16 +21,32 @@ use tauri_plugin_dialog::init;
 use crate::data_library::commands as data_commands;
 use crate::export::anafispread::export_anafispread;
 use crate::export::export_data;
-use crate::import::{get_file_metadata, import_anafis_spread_direct, import_spreadsheet_file};
+use crate::export::multi_file::export_multi_file;
+use crate::import::url::import_from_url;
+use crate::import::{
+    audit_workbook_formulas, get_file_metadata, import_anafis_spread_direct, import_and_merge,
+    import_csv_stats, import_csv_stream, import_spreadsheet_file, infer_column_types,
+    list_sqlite_tables,
+};
 use crate::scientific::curve_fitting::commands as curve_commands;
+use crate::scientific::distributions::commands as distribution_commands;
 use crate::scientific::math_functions as math_commands;
+use crate::scientific::preprocessing::commands as preprocessing_commands;
+use crate::scientific::statistics::commands as statistics_commands;
+use crate::scientific::time_series::commands as time_series_commands;
 use crate::scientific::uncertainty_propagation::calculator as uncertainty_calc;
 use crate::scientific::uncertainty_propagation::{
     convert_confidence_to_sigma, convert_sigma_to_confidence, generate_uncertainty_formulas,
+    generate_uncertainty_formulas_mc, generate_uncertainty_formulas_second_order,
 };
 use crate::unit_conversion::commands as unit_commands;
+use crate::utils::cancellation;
 use crate::utils::file_operations as file_ops;
-use crate::utils::{init_logging, log_info};
+use crate::utils::logging as logging_commands;
+use crate::utils::python_runtime;
+use crate::utils::recovery;
+use crate::utils::tasks as task_commands;
+use crate::utils::{init_logging, log_error, log_info, log_warn};
 use crate::windows::secondary_windows as window_commands;
 use crate::windows::window_manager as manager_commands;
 use dotenv::dotenv;
@@ -54,16 +68,61 @@ use dotenv::dotenv;
 )]
 pub fn run() {
     Builder::default()
+        // Must come first: a second launch hands its files to this instance and exits
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            startup::focus_main_window(app);
+            startup::open_files(app, startup::forwarded_arguments(argv, &cwd));
+        }))
         .invoke_handler(generate_handler![
             // Scientific Computation Commands
             curve_commands::fit_custom_odr,
+            curve_commands::calibration_analysis,
             curve_commands::evaluate_model_curve,
             curve_commands::evaluate_model_grid,
+            curve_commands::evaluate_formula,
+            curve_commands::get_model_cache_stats,
+            curve_commands::analyze_formula_dimensions,
             uncertainty_calc::calculate_uncertainty,
             uncertainty_calc::generate_latex,
             generate_uncertainty_formulas,
+            generate_uncertainty_formulas_mc,
+            generate_uncertainty_formulas_second_order,
             convert_confidence_to_sigma,
             convert_sigma_to_confidence,
+            // Time Series Commands
+            time_series_commands::holt_winters_forecast,
+            time_series_commands::dynamic_time_warping,
+            time_series_commands::evaluate_forecast_accuracy,
+            time_series_commands::prophet_forecast,
+            time_series_commands::prophet_detect_anomalies,
+            time_series_commands::resample_series,
+            time_series_commands::compute_periodogram,
+            time_series_commands::detect_changepoints,
+            time_series_commands::detect_series_anomalies,
+            time_series_commands::score_series_anomalies,
+            // Preprocessing Commands
+            preprocessing_commands::preprocess_column,
+            preprocessing_commands::preprocess_columns,
+            preprocessing_commands::winsorize_data,
+            // Distribution Fitting Commands
+            distribution_commands::fit_distributions,
+            distribution_commands::fit_gaussian_mixture_model,
+            // Statistical Test Commands
+            statistics_commands::adf_test,
+            statistics_commands::test_normality,
+            statistics_commands::kaplan_meier,
+            statistics_commands::log_rank_test,
+            statistics_commands::grouped_statistics,
+            statistics_commands::run_factorial_anova,
+            statistics_commands::crosstab_and_test,
+            statistics_commands::analyze_columns,
+            statistics_commands::ransac_regression,
+            statistics_commands::huber_regression,
+            statistics_commands::irls_regression,
+            statistics_commands::combine_measurements,
+            statistics_commands::detect_outliers,
+            statistics_commands::generate_outlier_report,
+            statistics_commands::prepare_selection,
             // Math Function Commands (pre-compiled symb_anafis evaluators)
             // Only functions NOT natively supported by Univer
             math_commands::math_asec,
@@ -86,8 +145,11 @@ pub fn run() {
             math_commands::math_sinc,
             math_commands::math_lambertw,
             math_commands::math_cbrt,
-            // Unit Conversion Commands (12 commands)
+            // Unit Conversion Commands (19 commands)
             unit_commands::convert_value,
+            unit_commands::convert_column,
+            unit_commands::batch_convert_values,
+            unit_commands::batch_convert_uncertainties,
             unit_commands::get_conversion_preview,
             unit_commands::check_unit_compatibility,
             unit_commands::get_available_units,
@@ -96,10 +158,16 @@ pub fn run() {
             unit_commands::parse_unit_formula,
             unit_commands::analyze_dimensional_compatibility,
             unit_commands::get_unit_dimensional_formula,
+            unit_commands::verify_formula_dimensions,
             unit_commands::validate_unit_string,
             unit_commands::get_supported_categories,
-            // Window Management Commands (9 commands)
+            unit_commands::register_custom_unit,
+            unit_commands::unregister_custom_unit,
+            unit_commands::get_physical_constant,
+            unit_commands::get_all_physical_constants,
+            // Window Management Commands (12 commands)
             window_commands::open_latex_preview_window,
+            window_commands::toggle_tool_window,
             window_commands::open_uncertainty_calculator_window,
             window_commands::close_uncertainty_calculator_window,
             window_commands::resize_uncertainty_calculator_window,
@@ -108,58 +176,80 @@ pub fn run() {
             window_commands::open_data_library_window,
             window_commands::close_data_library_window,
             manager_commands::set_window_size,
-            // Data Library Commands (12 commands)
+            manager_commands::get_window_layout,
+            manager_commands::reset_window_layout,
+            // Data Library Commands (18 commands)
             data_commands::save_sequence,
             data_commands::get_sequences,
+            data_commands::search_sequences,
             data_commands::get_sequence,
             data_commands::update_sequence,
             data_commands::delete_sequence,
+            data_commands::get_sequence_history,
+            data_commands::restore_sequence_version,
+            data_commands::get_sequence_history_limit,
+            data_commands::set_sequence_history_limit,
             data_commands::get_sequence_stats,
+            data_commands::diff_sequences,
             data_commands::pin_sequence,
             data_commands::duplicate_sequence,
+            data_commands::create_derived_sequence,
             data_commands::get_all_tags,
             data_commands::export_sequences_csv,
             data_commands::batch_import_sequences,
-            // Export Commands (2 commands - dispatcher + snapshot)
+            // Export Commands (3 commands - dispatcher + per-sheet files + snapshot)
             export_data,
+            export_multi_file,
             export_anafispread,
-            // Import Commands (3 commands)
+            // Import Commands (10 commands)
             import_spreadsheet_file,
+            import_and_merge,
             import_anafis_spread_direct,
+            audit_workbook_formulas,
             get_file_metadata,
+            import_csv_stream,
+            import_csv_stats,
+            list_sqlite_tables,
+            infer_column_types,
+            import_from_url,
+            // Crash Recovery Commands (4 commands)
+            recovery::save_recovery_snapshot,
+            recovery::check_recovery_snapshots,
+            recovery::load_recovery_snapshot,
+            recovery::discard_recovery_snapshots,
             // Utility Commands (File Operations)
             file_ops::save_png_file,
             file_ops::save_image_from_data_url,
             file_ops::save_svg_file,
+            file_ops::save_images_batch,
+            file_ops::save_pdf_from_svg,
+            file_ops::save_pdf_from_svgs,
             file_ops::save_binary_file,
             file_ops::read_file_text,
             file_ops::check_ffmpeg_available,
             file_ops::transcode_webm_to_mp4,
-            startup::get_startup_file,
+            python_runtime::get_python_status,
+            logging_commands::get_recent_logs,
+            logging_commands::get_log_file_path,
+            task_commands::get_running_tasks,
+            cancellation::cancel_computation,
+            startup::frontend_ready,
         ])
         .plugin(init())
         .setup(|app| {
             // Load environment variables from .env file
             dotenv().ok();
 
-            // Initialize logging
-            if let Err(e) = init_logging() {
+            // Initialize logging to a rotating file in the app log dir
+            if let Err(e) = init_logging(app.path().app_log_dir().ok().as_deref()) {
                 eprintln!("Failed to initialize logging: {e}");
             }
 
-            // Check for file association open (when app is launched with a file)
-            let args: Vec<String> = args().collect();
-            let mut pending_file = None;
-            for arg in args.into_iter().skip(1) {
-                let lower_arg = arg.to_lowercase();
-                if lower_arg.ends_with(".anafispread") {
-                    let normalized_path = arg.replace('\\', "/");
-                    log_info(&format!("Opening file from association: {normalized_path}"));
-                    pending_file = Some(normalized_path);
-                    break;
-                }
-            }
-            app.manage(startup::StartupFileState(Mutex::new(pending_file)));
+            let arguments = startup::command_line_arguments();
+            app.manage(startup::FileOpenQueue::default());
+
+            // Files opened through file association are queued until `frontend_ready`
+            startup::open_files(app.handle(), arguments);
 
             // Initialize Data Library
             match data_commands::init_data_library(app.handle()) {
@@ -168,12 +258,45 @@ pub fn run() {
                     log_info("Data Library initialized successfully");
                 }
                 Err(e) => {
-                    log_info(&format!("WARNING: Failed to initialize Data Library: {e}"));
+                    log_error(&format!("Failed to initialize Data Library: {e}"));
                 }
             }
 
+            // Register the user's custom units
+            match unit_commands::load_custom_units(app.handle()) {
+                Ok(count) if count > 0 => log_info(&format!("Loaded {count} custom units")),
+                Ok(_) => {}
+                Err(e) => log_error(&format!("Failed to load custom units: {e}")),
+            }
+
             log_info(&format!("Dev mode: {}", cfg!(debug_assertions)));
 
+            // Flags of fits that can be cancelled by computation id
+            app.manage(cancellation::CancellationRegistry::default());
+
+            // Probe for Python off the main thread; the result is cached for the frontend
+            app.manage(python_runtime::PythonRuntimeState::default());
+            let probe_handle = app.handle().clone();
+            spawn(move || {
+                let state = probe_handle.state::<python_runtime::PythonRuntimeState>();
+                let status = state.status();
+                if status.found {
+                    log_info(&format!(
+                        "Python found at {} (version {})",
+                        status.path.as_deref().unwrap_or("?"),
+                        status.version.as_deref().unwrap_or("unknown")
+                    ));
+                } else {
+                    log_warn("Python not found on PATH");
+                }
+            });
+
+            // Autosave snapshots for crash recovery
+            app.manage(recovery::RecoveryStore::load(app.handle()));
+
+            // Restore saved window sizes and positions
+            app.manage(manager_commands::WindowLayoutStore::load(app.handle()));
+
             // Listen for main window events
             let app_handle = app.handle().clone();
             if let Some(main_window) = app.get_webview_window("main") {
@@ -181,6 +304,10 @@ pub fn run() {
                 // before the frontend stylesheet and React tree are ready.
                 drop(main_window.set_background_color(Some(Color(10, 10, 10, 255))));
 
+                // Reapply the saved geometry and maximized state while hidden.
+                manager_commands::restore_window_layout(app.handle(), &main_window);
+                manager_commands::track_window_layout(app.handle(), &main_window);
+
                 // Keep hidden until frontend emits a ready event.
                 drop(main_window.hide());
                 let main_window_for_ready = main_window.clone();
@@ -204,14 +331,10 @@ pub fn run() {
                 main_window.on_window_event(move |event| {
                     if matches!(event, WindowEvent::Destroyed) {
                         // Main window is being destroyed, close all child windows
-                        if let Some(w) = app_handle.get_webview_window("uncertainty-calculator") {
-                            drop(w.close());
-                        }
-                        if let Some(w) = app_handle.get_webview_window("settings") {
-                            drop(w.close());
-                        }
-                        if let Some(w) = app_handle.get_webview_window("latex-preview") {
-                            drop(w.close());
+                        for label in window_commands::child_window_labels() {
+                            if let Some(w) = app_handle.get_webview_window(label) {
+                                drop(w.close());
+                            }
                         }
                     }
                 });
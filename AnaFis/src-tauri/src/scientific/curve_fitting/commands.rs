@@ -1,25 +1,85 @@
+use super::logic::calibration::run_calibration;
+use super::logic::dimensions::analyze_dimensions;
 use super::logic::engine::{
-    evaluate_model_expr_batch, get_or_compile_model, normalize_identifiers,
+    ModelCacheStats, evaluate_model_expr_batch, get_or_compile_model, model_cache_stats,
+    normalize_identifiers, validate_identifier, validate_symbol_sets,
 };
-use super::run_fit_request;
+use super::logic::run_fit_request_with_progress;
 use super::types::{
-    CurveEvaluationRequest, CurveEvaluationResponse, GridEvaluationRequest, GridEvaluationResponse,
+    CalibrationRequest, CalibrationResponse, CurveEvaluationRequest, CurveEvaluationResponse,
+    FormulaDimensionRequest, FormulaDimensionResponse, FormulaEvaluationRequest,
+    FormulaEvaluationResponse, FormulaGrid, GridEvaluationRequest, GridEvaluationResponse,
     OdrError, OdrFitRequest, OdrFitResponse, OdrResult,
 };
+use crate::error::{CommandError, CommandResult, internal_error};
+use crate::utils::cancellation::CancellationRegistry;
+use crate::utils::progress::ProgressReporter;
+use crate::utils::tasks::run_heavy;
+use std::collections::HashSet;
 use std::slice::from_ref;
-use tauri;
+use symb_anafis::parse;
+use tauri::{self, AppHandle, Manager};
 
 const MAX_GRID_RESOLUTION: usize = 2_000;
 
 /// Perform a custom ODR fit
 ///
+/// When the request carries a `computationId`, `computation-progress` events
+/// with that id report the solver iterations and chi-squared while it runs,
+/// and `cancel_computation` with that id stops it early: the response then
+/// holds the estimate reached, with `success: false` and termination reason
+/// `cancelled`.
+///
 /// # Errors
 /// Returns an error if the data preparation fails, the model cannot be compiled,
-/// or the ODR solver fails to converge.
+/// or the ODR solver fails to converge. Malformed requests are rejected while
+/// the arguments are deserialized, with the path of the offending field.
 #[tauri::command]
-#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
-pub fn fit_custom_odr(request: OdrFitRequest) -> Result<OdrFitResponse, String> {
-    run_fit_request(&request).map_err(|error| error.to_string())
+pub async fn fit_custom_odr(
+    app: AppHandle,
+    request: OdrFitRequest,
+) -> CommandResult<OdrFitResponse> {
+    let registration = request
+        .computation_id
+        .as_deref()
+        .map(|id| app.state::<CancellationRegistry>().register(id));
+    run_heavy("fit_custom_odr", move || {
+        let progress = request.computation_id.clone().map(|id| {
+            let reporter = ProgressReporter::for_app(app, id);
+            match &registration {
+                Some(registration) => reporter.with_cancellation(registration.flag()),
+                None => reporter,
+            }
+        });
+        let fitted =
+            run_fit_request_with_progress(&request, progress.as_ref()).map_err(CommandError::from);
+        drop(registration);
+        fitted
+    })
+    .await
+    .map_err(internal_error)?
+}
+
+/// Fit a calibration line or quadratic to standards and read the
+/// concentration of each sample off it by inverse prediction.
+///
+/// Sample uncertainties combine the fit covariance with the signal
+/// uncertainty, samples outside the calibrated range are flagged, and the
+/// detection and quantification limits are `3.3 * s / slope` and
+/// `10 * s / slope` with `s` from the blanks or the intercept.
+///
+/// # Errors
+/// Returns an error if the standards, samples or blanks are invalid, the fit
+/// fails, or the calibration slope is zero.
+#[tauri::command]
+pub async fn calibration_analysis(
+    request: CalibrationRequest,
+) -> CommandResult<CalibrationResponse> {
+    run_heavy("calibration_analysis", move || {
+        run_calibration(&request).map_err(CommandError::from)
+    })
+    .await
+    .map_err(internal_error)?
 }
 
 /// Evaluate a model on a 2D grid
@@ -31,8 +91,8 @@ pub fn fit_custom_odr(request: OdrFitRequest) -> Result<OdrFitResponse, String>
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
 pub fn evaluate_model_grid(
     request: GridEvaluationRequest,
-) -> Result<GridEvaluationResponse, String> {
-    evaluate_model_grid_inner(&request).map_err(|error| error.to_string())
+) -> CommandResult<GridEvaluationResponse> {
+    evaluate_model_grid_inner(&request).map_err(CommandError::from)
 }
 
 /// Evaluate a model on a 1D curve.
@@ -44,8 +104,184 @@ pub fn evaluate_model_grid(
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
 pub fn evaluate_model_curve(
     request: CurveEvaluationRequest,
-) -> Result<CurveEvaluationResponse, String> {
-    evaluate_model_curve_inner(&request).map_err(|error| error.to_string())
+) -> CommandResult<CurveEvaluationResponse> {
+    evaluate_model_curve_inner(&request).map_err(CommandError::from)
+}
+
+/// Evaluate a user formula along one swept variable, with every other symbol
+/// held fixed.
+///
+/// Points where the formula is not finite are listed by index instead of
+/// failing the call.
+///
+/// # Errors
+/// Returns an error if the formula cannot be parsed or compiled, uses symbols
+/// without a value (all listed in the message), or the grid is invalid.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn evaluate_formula(
+    request: FormulaEvaluationRequest,
+) -> CommandResult<FormulaEvaluationResponse> {
+    evaluate_formula_inner(&request).map_err(CommandError::from)
+}
+
+/// Infer the dimension each parameter of a model formula must have, given the
+/// units of its variables, and list where the formula is dimensionally
+/// inconsistent (e.g. `exp(t)` with `t` in seconds).
+///
+/// # Errors
+/// Returns an error if the formula cannot be parsed, uses symbols that are
+/// neither variables nor parameters, or a unit is not understood.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn analyze_formula_dimensions(
+    request: FormulaDimensionRequest,
+) -> CommandResult<FormulaDimensionResponse> {
+    analyze_dimensions(&request).map_err(CommandError::from)
+}
+
+/// Size and hit/miss counters of the compiled model cache.
+///
+/// # Errors
+/// Returns an error if the cache lock is poisoned.
+#[tauri::command]
+pub fn get_model_cache_stats() -> CommandResult<ModelCacheStats> {
+    model_cache_stats().map_err(CommandError::from)
+}
+
+fn evaluate_formula_inner(
+    request: &FormulaEvaluationRequest,
+) -> OdrResult<FormulaEvaluationResponse> {
+    let sweep_names = normalize_identifiers(from_ref(&request.sweep_variable), "sweep variable")?;
+    let mut fixed: Vec<(String, f64)> = Vec::with_capacity(request.fixed_values.len());
+    for (name, &value) in &request.fixed_values {
+        validate_identifier(name.trim(), "fixed symbol")?;
+        if !value.is_finite() {
+            return Err(OdrError::Validation(format!(
+                "Fixed symbol '{name}' must have a finite value"
+            )));
+        }
+        fixed.push((name.trim().to_lowercase(), value));
+    }
+    fixed.sort_by(|left, right| left.0.cmp(&right.0));
+    if let Some(pair) = fixed.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+        return Err(OdrError::Validation(format!(
+            "Duplicate fixed symbol names are not allowed (case-insensitive collision on '{}')",
+            pair[0].0
+        )));
+    }
+
+    let formula = request.formula.trim().to_lowercase();
+    let known: HashSet<String> = sweep_names
+        .iter()
+        .chain(fixed.iter().map(|(name, _)| name))
+        .cloned()
+        .collect();
+    let used = parse(&formula, &known, &HashSet::new(), None)
+        .map_err(|error| OdrError::Parse(error.to_string()))?
+        .variables();
+    let mut unknown: Vec<String> = used
+        .iter()
+        .filter(|name| !known.contains(*name) && !matches!(name.as_str(), "pi" | "e"))
+        .cloned()
+        .collect();
+    if !unknown.is_empty() {
+        unknown.sort();
+        return Err(OdrError::UnknownSymbols(unknown));
+    }
+
+    // Only symbols the formula uses become parameters, so the cache key does
+    // not depend on unrelated entries of `fixed_values`.
+    fixed.retain(|(name, _)| used.contains(name));
+    let parameter_names: Vec<String> = fixed.iter().map(|(name, _)| name.clone()).collect();
+    validate_symbol_sets(&sweep_names, &parameter_names)?;
+
+    let sweep = formula_grid_points(&request.grid)?;
+    let compiled_model = get_or_compile_model(&formula, "y", &sweep_names, &parameter_names, true)?;
+    let evaluator = compiled_model.model_evaluator.as_ref().ok_or_else(|| {
+        OdrError::Compile("formula evaluator missing from compiled model".to_owned())
+    })?;
+
+    let parameter_columns: Vec<Vec<f64>> = fixed
+        .iter()
+        .map(|&(_, value)| vec![value; sweep.len()])
+        .collect();
+    let mut columns: Vec<&[f64]> = Vec::with_capacity(1 + parameter_columns.len());
+    columns.push(&sweep);
+    columns.extend(parameter_columns.iter().map(Vec::as_slice));
+
+    let mut values = vec![0.0; sweep.len()];
+    evaluator
+        .eval_batch(&columns, &mut values, None)
+        .map_err(|error| OdrError::Numerical(format!("formula evaluation failed: {error:?}")))?;
+    let non_finite_indices = values
+        .iter()
+        .enumerate()
+        .filter(|(_, value)| !value.is_finite())
+        .map(|(index, _)| index)
+        .collect();
+
+    Ok(FormulaEvaluationResponse {
+        x: sweep,
+        y: values,
+        non_finite_indices,
+    })
+}
+
+/// Sweep values of a formula grid, validated to be finite.
+fn formula_grid_points(grid: &FormulaGrid) -> OdrResult<Vec<f64>> {
+    match *grid {
+        FormulaGrid::Range { start, stop, n } => {
+            if !start.is_finite() || !stop.is_finite() {
+                return Err(OdrError::Validation(
+                    "Sweep range must contain finite values".to_owned(),
+                ));
+            }
+            if n < 2 {
+                return Err(OdrError::Validation(
+                    "Sweep range needs at least 2 points".to_owned(),
+                ));
+            }
+            if n > MAX_GRID_RESOLUTION {
+                return Err(OdrError::Validation(format!(
+                    "Sweep resolution too high: max supported is {MAX_GRID_RESOLUTION}"
+                )));
+            }
+            #[allow(
+                clippy::cast_precision_loss,
+                reason = "Precision loss in resolution cast is acceptable for visualization"
+            )]
+            let step = (stop - start) / (n - 1) as f64;
+            Ok((0..n)
+                .map(|i| {
+                    if i == n - 1 {
+                        stop
+                    } else {
+                        #[allow(
+                            clippy::cast_precision_loss,
+                            reason = "Precision loss in index cast is acceptable for visualization"
+                        )]
+                        {
+                            (i as f64).mul_add(step, start)
+                        }
+                    }
+                })
+                .collect())
+        }
+        FormulaGrid::Points(ref points) => {
+            if points.is_empty() {
+                return Err(OdrError::Validation(
+                    "Sweep needs at least one point".to_owned(),
+                ));
+            }
+            if points.iter().any(|value| !value.is_finite()) {
+                return Err(OdrError::Validation(
+                    "Sweep points must be finite".to_owned(),
+                ));
+            }
+            Ok(points.clone())
+        }
+    }
 }
 
 fn evaluate_model_curve_inner(
@@ -92,6 +328,7 @@ fn evaluate_model_curve_inner(
         "y", // dummy dependent name since curve evals the raw function
         &normalized_independent_names,
         &normalized_parameter_names,
+        false,
     )?;
 
     let point_count = request.resolution;
@@ -187,6 +424,7 @@ fn evaluate_model_grid_inner(request: &GridEvaluationRequest) -> OdrResult<GridE
         "z", // dummy dependent name since grid just evals the raw function
         &normalized_independent_names,
         &normalized_parameter_names,
+        false,
     )?;
 
     let res = request.resolution;
@@ -0,0 +1,417 @@
+//! Bootstrap of fitted ODR parameters.
+//!
+//! Resampled data sets are refitted in parallel from the fitted parameters
+//! against the same compiled models. Only the parameter vector of each refit is
+//! kept; percentile intervals and the sample covariance of those vectors
+//! describe the parameter distribution without the local linearisation behind
+//! the covariance-based uncertainties.
+
+use nalgebra::DMatrix;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_distr::StandardNormal;
+use rand_pcg::Pcg64;
+use rayon::prelude::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::engine::{
+    CompiledModel, EvaluationState, MAX_BOOTSTRAP_RESAMPLES, MIN_BOOTSTRAP_RESAMPLES,
+    PointCovariances, PreparedData, solve_odr,
+};
+use super::multi_start::SolverSettings;
+use crate::scientific::curve_fitting::types::{
+    BootstrapConfig, BootstrapMethod, BootstrapSummary, OdrError, OdrResult,
+};
+use crate::scientific::primitives::descriptive::{mean, quantile_sorted, sorted};
+use crate::utils::progress::ProgressReporter;
+
+/// Outcome of refitting one resample.
+enum Refit {
+    /// Not attempted because the computation was cancelled.
+    Skipped,
+    /// The solver failed or stopped without converging.
+    Failed,
+    /// Converged parameters.
+    Converged(Vec<f64>),
+}
+
+/// Fit quantities the resamples are drawn around.
+struct Baseline<'data> {
+    data: &'data PreparedData,
+    /// Observed values with each dependent variable replaced by its fitted values.
+    centers: Vec<Vec<f64>>,
+    /// Variable index of each layer's dependent variable.
+    dependent_indices: Vec<usize>,
+    /// Dependent residuals divided by their standard uncertainty: [`layer_idx`][point_idx].
+    weighted_residuals: Vec<Vec<f64>>,
+    /// Lower Cholesky factor of each point covariance, or one shared factor.
+    factors: Vec<DMatrix<f64>>,
+}
+
+/// Validates a bootstrap configuration.
+///
+/// # Errors
+/// Returns `OdrError::Validation` for an out-of-range resample count.
+pub fn validate_bootstrap(config: &BootstrapConfig) -> OdrResult<()> {
+    if !(MIN_BOOTSTRAP_RESAMPLES..=MAX_BOOTSTRAP_RESAMPLES).contains(&config.n_resamples) {
+        return Err(OdrError::Validation(format!(
+            "bootstrap.nResamples must be between {MIN_BOOTSTRAP_RESAMPLES} and {MAX_BOOTSTRAP_RESAMPLES}, got {}",
+            config.n_resamples
+        )));
+    }
+    Ok(())
+}
+
+/// Refits `config.n_resamples` resampled data sets from `parameters` and
+/// summarises the converged refits.
+///
+/// Residual resampling adds the dependent residuals, scaled by each point's
+/// uncertainty and drawn with replacement, to the fitted values. Pairs
+/// resampling draws whole points with replacement. Parametric resampling
+/// draws every point from its covariance around the fitted dependent values
+/// and the observed independent values. Once the computation is cancelled,
+/// the remaining resamples are skipped.
+///
+/// # Errors
+/// Returns `OdrError::Validation` if a layer's dependent variable is missing
+/// from the data.
+#[allow(
+    clippy::too_many_arguments,
+    reason = "The fit result and solver settings are threaded separately from the request"
+)]
+pub fn run_bootstrap(
+    models: &[Arc<CompiledModel>],
+    data: &PreparedData,
+    parameters: &[f64],
+    state: &EvaluationState,
+    global_parameter_names: &[String],
+    config: &BootstrapConfig,
+    settings: SolverSettings,
+    confidence_level: f64,
+    progress: Option<&ProgressReporter>,
+) -> OdrResult<BootstrapSummary> {
+    let baseline = Baseline::new(models, data, state, config.method)?;
+    let mut seeder = Pcg64::seed_from_u64(config.seed);
+    let seeds: Vec<u64> = (0..config.n_resamples).map(|_| seeder.next_u64()).collect();
+
+    if let Some(progress) = progress {
+        progress.restart();
+    }
+    let finished = AtomicUsize::new(0);
+    let refits: Vec<Refit> = seeds
+        .into_par_iter()
+        .map(|seed| {
+            if progress.is_some_and(ProgressReporter::is_cancelled) {
+                return Refit::Skipped;
+            }
+            let mut rng = Pcg64::seed_from_u64(seed);
+            let resampled = match config.method {
+                BootstrapMethod::Residual => baseline.residual_resample(&mut rng),
+                BootstrapMethod::Pairs => baseline.pairs_resample(&mut rng),
+                BootstrapMethod::Parametric => baseline.parametric_resample(&mut rng),
+            };
+            let solved = solve_odr(
+                models,
+                &resampled,
+                parameters.to_vec(),
+                global_parameter_names,
+                settings.max_iterations,
+                settings.tolerance,
+                settings.initial_damping,
+                None,
+            );
+            if let Some(progress) = progress {
+                let done = finished.fetch_add(1, Ordering::Relaxed) + 1;
+                progress.report(done, config.n_resamples, None);
+            }
+            match solved {
+                Ok((refitted, _, _, reason))
+                    if reason.is_converged() && refitted.iter().all(|value| value.is_finite()) =>
+                {
+                    Refit::Converged(refitted)
+                }
+                _ => Refit::Failed,
+            }
+        })
+        .collect();
+    if let Some(progress) = progress {
+        progress.finish(finished.load(Ordering::Relaxed), config.n_resamples, None);
+    }
+
+    let failed_resamples = refits
+        .iter()
+        .filter(|refit| matches!(refit, Refit::Failed))
+        .count();
+    let converged: Vec<Vec<f64>> = refits
+        .into_iter()
+        .filter_map(|refit| match refit {
+            Refit::Converged(refitted) => Some(refitted),
+            Refit::Skipped | Refit::Failed => None,
+        })
+        .collect();
+
+    let columns: Vec<Vec<f64>> = (0..parameters.len())
+        .map(|param| converged.iter().map(|refitted| refitted[param]).collect())
+        .collect();
+    let tail = (1.0 - confidence_level) / 2.0;
+    let (parameter_ci_lower, parameter_ci_upper) = columns
+        .iter()
+        .map(|column| {
+            let ordered = sorted(column);
+            (
+                quantile_sorted(&ordered, tail).unwrap_or(f64::NAN),
+                quantile_sorted(&ordered, 1.0 - tail).unwrap_or(f64::NAN),
+            )
+        })
+        .unzip();
+    let parameter_covariance = sample_covariance(&columns);
+    let parameter_uncertainties = parameter_covariance
+        .iter()
+        .enumerate()
+        .map(|(param, row)| row[param].sqrt())
+        .collect();
+
+    Ok(BootstrapSummary {
+        method: config.method,
+        n_resamples: config.n_resamples,
+        converged_resamples: converged.len(),
+        failed_resamples,
+        confidence_level,
+        parameter_ci_lower,
+        parameter_ci_upper,
+        parameter_uncertainties,
+        parameter_covariance,
+    })
+}
+
+/// Note summarising a bootstrap for the response message.
+#[must_use]
+pub fn bootstrap_note(summary: &BootstrapSummary) -> String {
+    let method = match summary.method {
+        BootstrapMethod::Residual => "residual",
+        BootstrapMethod::Pairs => "pairs",
+        BootstrapMethod::Parametric => "parametric",
+    };
+    let skipped = summary.n_resamples - summary.converged_resamples - summary.failed_resamples;
+    let mut note = format!(
+        "Bootstrap: {} of {} {method} resamples converged",
+        summary.converged_resamples, summary.n_resamples
+    );
+    if skipped > 0 {
+        note = format!("{note}, {skipped} skipped after cancellation");
+    }
+    if summary.converged_resamples < 2 {
+        note.push_str("; too few to estimate intervals");
+    }
+    note
+}
+
+impl<'data> Baseline<'data> {
+    fn new(
+        models: &[Arc<CompiledModel>],
+        data: &'data PreparedData,
+        state: &EvaluationState,
+        method: BootstrapMethod,
+    ) -> OdrResult<Self> {
+        let dependent_indices = models
+            .iter()
+            .map(|model| {
+                data.variable_names
+                    .iter()
+                    .position(|name| name == &model.dependent_name)
+                    .ok_or_else(|| {
+                        OdrError::Validation(format!(
+                            "Dependent variable {} not found in data",
+                            model.dependent_name
+                        ))
+                    })
+            })
+            .collect::<OdrResult<Vec<usize>>>()?;
+
+        let mut centers = data.variable_values.clone();
+        for (layer, &variable) in dependent_indices.iter().enumerate() {
+            centers[variable].clone_from(&state.layer_fitted_values[layer]);
+        }
+        let weighted_residuals = dependent_indices
+            .iter()
+            .zip(&state.layer_residuals)
+            .map(|(&variable, residuals)| {
+                residuals
+                    .iter()
+                    .enumerate()
+                    .map(|(point, residual)| {
+                        residual / data.point_covariances[point][variable][variable].sqrt()
+                    })
+                    .collect()
+            })
+            .collect();
+        let factors = if method == BootstrapMethod::Parametric {
+            match &data.point_covariances {
+                PointCovariances::PerPoint(covariances) => covariances
+                    .iter()
+                    .map(|matrix| lower_factor(matrix))
+                    .collect(),
+                PointCovariances::Shared(covariance) => vec![lower_factor(covariance)],
+            }
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            data,
+            centers,
+            dependent_indices,
+            weighted_residuals,
+            factors,
+        })
+    }
+
+    fn residual_resample(&self, rng: &mut Pcg64) -> PreparedData {
+        let count = self.data.point_count;
+        let mut values = self.centers.clone();
+        for (&variable, weighted) in self.dependent_indices.iter().zip(&self.weighted_residuals) {
+            for (point, value) in values[variable].iter_mut().enumerate() {
+                let sigma = self.data.point_covariances[point][variable][variable].sqrt();
+                *value = sigma.mul_add(weighted[draw_index(rng, count)], *value);
+            }
+        }
+        with_values(
+            self.data,
+            values,
+            copy_covariances(&self.data.point_covariances),
+        )
+    }
+
+    fn pairs_resample(&self, rng: &mut Pcg64) -> PreparedData {
+        let count = self.data.point_count;
+        let points: Vec<usize> = (0..count).map(|_| draw_index(rng, count)).collect();
+        let values = self
+            .data
+            .variable_values
+            .iter()
+            .map(|column| points.iter().map(|&point| column[point]).collect())
+            .collect();
+        let covariances = match &self.data.point_covariances {
+            PointCovariances::PerPoint(covariances) => PointCovariances::PerPoint(
+                points
+                    .iter()
+                    .map(|&point| covariances[point].clone())
+                    .collect(),
+            ),
+            PointCovariances::Shared(covariance) => PointCovariances::Shared(covariance.clone()),
+        };
+        with_values(self.data, values, covariances)
+    }
+
+    fn parametric_resample(&self, rng: &mut Pcg64) -> PreparedData {
+        let dim = self.centers.len();
+        let mut values = self.centers.clone();
+        let mut normals = vec![0.0_f64; dim];
+        for point in 0..self.data.point_count {
+            let factor = self.factors.get(point).unwrap_or(&self.factors[0]);
+            for normal in &mut normals {
+                *normal = rng.sample(StandardNormal);
+            }
+            for (row, column) in values.iter_mut().enumerate() {
+                let noise: f64 = (0..=row).map(|col| factor[(row, col)] * normals[col]).sum();
+                column[point] += noise;
+            }
+        }
+        with_values(
+            self.data,
+            values,
+            copy_covariances(&self.data.point_covariances),
+        )
+    }
+}
+
+/// `data` with its values and covariances replaced, keeping the metadata.
+fn with_values(
+    data: &PreparedData,
+    variable_values: Vec<Vec<f64>>,
+    point_covariances: PointCovariances,
+) -> PreparedData {
+    PreparedData {
+        variable_names: data.variable_names.clone(),
+        variable_values,
+        point_covariances,
+        point_count: data.point_count,
+        had_uncertainty_clamp: data.had_uncertainty_clamp,
+        had_low_count_poisson: data.had_low_count_poisson,
+        inferred_type_a_dof_count: data.inferred_type_a_dof_count,
+        variable_uncertainty_dofs: data.variable_uncertainty_dofs.clone(),
+        welch_satterthwaite_dof: data.welch_satterthwaite_dof,
+        had_zero_count_poisson: data.had_zero_count_poisson,
+        clamped_variance_count: data.clamped_variance_count,
+    }
+}
+
+fn copy_covariances(covariances: &PointCovariances) -> PointCovariances {
+    match covariances {
+        PointCovariances::PerPoint(matrices) => PointCovariances::PerPoint(matrices.clone()),
+        PointCovariances::Shared(matrix) => PointCovariances::Shared(matrix.clone()),
+    }
+}
+
+/// Lower Cholesky factor of `covariance`, or the diagonal standard
+/// uncertainties when it is not positive definite.
+fn lower_factor(covariance: &[Vec<f64>]) -> DMatrix<f64> {
+    let dim = covariance.len();
+    let matrix = DMatrix::from_fn(dim, dim, |row, col| covariance[row][col]);
+    matrix.clone().cholesky().map_or_else(
+        || {
+            DMatrix::from_fn(dim, dim, |row, col| {
+                if row == col {
+                    matrix[(row, col)].max(0.0).sqrt()
+                } else {
+                    0.0
+                }
+            })
+        },
+        |cholesky| cholesky.l(),
+    )
+}
+
+fn draw_index(rng: &mut Pcg64, count: usize) -> usize {
+    #[allow(
+        clippy::cast_possible_truncation,
+        reason = "Remainder is below the point count, which fits in usize"
+    )]
+    let index = (rng.next_u64() % count as u64) as usize;
+    index
+}
+
+/// Sample covariance (n - 1 denominator) between the columns, NaN with fewer
+/// than two samples.
+fn sample_covariance(columns: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let means: Vec<f64> = columns
+        .iter()
+        .map(|column| mean(column).unwrap_or(f64::NAN))
+        .collect();
+    let samples = columns.first().map_or(0, Vec::len);
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Resample counts are bounded by MAX_BOOTSTRAP_RESAMPLES"
+    )]
+    let denominator = samples.saturating_sub(1) as f64;
+    columns
+        .iter()
+        .zip(&means)
+        .map(|(left, left_mean)| {
+            columns
+                .iter()
+                .zip(&means)
+                .map(|(right, right_mean)| {
+                    if samples < 2 {
+                        return f64::NAN;
+                    }
+                    left.iter()
+                        .zip(right)
+                        .map(|(a, b)| (a - left_mean) * (b - right_mean))
+                        .sum::<f64>()
+                        / denominator
+                })
+                .collect()
+        })
+        .collect()
+}
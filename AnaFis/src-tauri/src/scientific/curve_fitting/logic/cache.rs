@@ -1,6 +1,7 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::{Arc, LazyLock, Mutex};
 
+use serde::Serialize;
 use symb_anafis::{CompiledEvaluator, Expr, Symbol, gradient, parse, symb};
 
 use super::constants::MODEL_CACHE_MAX_ENTRIES;
@@ -31,6 +32,12 @@ pub struct CompiledModel {
     pub independent_parameter_mixed_hessian_exprs: Vec<Expr>,
     /// Expressions for second derivatives with respect to parameters (for batch eval).
     pub parameter_hessian_exprs: Vec<Expr>,
+    /// Whether derivatives come from finite differences instead of the symbolic
+    /// expressions above (which are then left empty).
+    pub numerical_gradients: bool,
+    /// Compiled evaluator for the model itself, used for per-point finite differences
+    /// when `numerical_gradients` is set.
+    pub model_evaluator: Option<CompiledEvaluator>,
 }
 
 /// LRU cache for compiled models to avoid redundant recompilation.
@@ -40,6 +47,24 @@ pub struct ModelCache {
     pub entries: HashMap<String, Arc<CompiledModel>>,
     /// Order of access to implement LRU eviction.
     pub access_order: VecDeque<String>,
+    /// Lookups served from the cache.
+    pub hits: u64,
+    /// Lookups that had to compile the model.
+    pub misses: u64,
+}
+
+/// Snapshot of the model cache usage counters.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCacheStats {
+    /// Compiled models currently cached.
+    pub entries: usize,
+    /// Maximum number of cached models.
+    pub capacity: usize,
+    /// Lookups served from the cache since startup.
+    pub hits: u64,
+    /// Lookups that had to compile the model since startup.
+    pub misses: u64,
 }
 
 /// Global singleton for model caching.
@@ -88,8 +113,27 @@ impl ModelCache {
     }
 }
 
+/// Current size and hit/miss counters of the global model cache.
+///
+/// # Errors
+/// Returns `OdrError::CachePoisoned` if the cache lock is poisoned.
+pub fn model_cache_stats() -> OdrResult<ModelCacheStats> {
+    let cache = MODEL_CACHE.lock().map_err(|_err| OdrError::CachePoisoned)?;
+    let stats = ModelCacheStats {
+        entries: cache.entries.len(),
+        capacity: MODEL_CACHE_MAX_ENTRIES,
+        hits: cache.hits,
+        misses: cache.misses,
+    };
+    drop(cache);
+    Ok(stats)
+}
+
 /// Retrieves a compiled model from cache or compiles it if not found.
 ///
+/// With `numerical_gradients`, symbolic differentiation is skipped entirely and
+/// only the model expression is kept.
+///
 /// # Errors
 /// Returns `OdrError` if model compilation fails or cache is poisoned.
 pub fn get_or_compile_model(
@@ -97,6 +141,7 @@ pub fn get_or_compile_model(
     dependent_name: &str,
     independent_names: &[String],
     parameter_names: &[String],
+    numerical_gradients: bool,
 ) -> OdrResult<Arc<CompiledModel>> {
     let normalized_dependent = dependent_name.trim().to_lowercase();
     let normalized_independent: Vec<String> = independent_names
@@ -113,13 +158,16 @@ pub fn get_or_compile_model(
         &normalized_dependent,
         &normalized_independent,
         &normalized_parameters,
+        numerical_gradients,
     );
 
     {
         let mut cache = MODEL_CACHE.lock().map_err(|_err| OdrError::CachePoisoned)?;
         if let Some(model) = cache.get(&key) {
+            cache.hits += 1;
             return Ok(model);
         }
+        cache.misses += 1;
     }
 
     let compiled = Arc::new(compile_model_inner(
@@ -127,6 +175,7 @@ pub fn get_or_compile_model(
         &normalized_dependent,
         &normalized_independent,
         &normalized_parameters,
+        numerical_gradients,
     )?);
 
     let mut cache = MODEL_CACHE.lock().map_err(|_err| OdrError::CachePoisoned)?;
@@ -146,6 +195,7 @@ fn build_model_cache_key(
     dependent_name: &str,
     independent_names: &[String],
     parameter_names: &[String],
+    numerical_gradients: bool,
 ) -> String {
     fn append_part(key: &mut String, value: &str) {
         let trimmed = value.trim();
@@ -173,6 +223,7 @@ fn build_model_cache_key(
     for name in parameter_names {
         append_part(&mut key, name);
     }
+    key.push_str(if numerical_gradients { "|fd" } else { "|sym" });
 
     key
 }
@@ -187,6 +238,7 @@ fn compile_model_inner(
     dependent_name: &str,
     independent_names: &[String],
     parameter_names: &[String],
+    numerical_gradients: bool,
 ) -> OdrResult<CompiledModel> {
     let formula = model_formula.trim().to_lowercase();
     if formula.is_empty() {
@@ -209,6 +261,26 @@ fn compile_model_inner(
     evaluator_order.extend(independent_names.iter().map(String::as_str));
     evaluator_order.extend(parameter_names.iter().map(String::as_str));
 
+    if numerical_gradients {
+        let model_evaluator = CompiledEvaluator::compile(&expr, &evaluator_order, None)
+            .map_err(|error| OdrError::Compile(format!("model evaluator: {error:?}")))?;
+        return Ok(CompiledModel {
+            formula,
+            dependent_name: dependent_name.to_owned(),
+            parameter_names: parameter_names.to_vec(),
+            independent_names: independent_names.to_vec(),
+            model_expr: expr,
+            parameter_gradient_exprs: Vec::new(),
+            independent_gradient_exprs: Vec::new(),
+            independent_hessian_evaluators: Vec::new(),
+            independent_hessian_exprs: Vec::new(),
+            independent_parameter_mixed_hessian_exprs: Vec::new(),
+            parameter_hessian_exprs: Vec::new(),
+            numerical_gradients,
+            model_evaluator: Some(model_evaluator),
+        });
+    }
+
     let parameter_symbols: Vec<Symbol> = parameter_names.iter().map(|name| symb(name)).collect();
     let parameter_symbol_refs: Vec<&Symbol> = parameter_symbols.iter().collect();
     let parameter_gradients = gradient(&expr, &parameter_symbol_refs)
@@ -291,5 +363,7 @@ fn compile_model_inner(
         independent_hessian_exprs,
         independent_parameter_mixed_hessian_exprs,
         parameter_hessian_exprs,
+        numerical_gradients,
+        model_evaluator: None,
     })
 }
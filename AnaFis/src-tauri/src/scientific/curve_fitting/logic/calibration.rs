@@ -0,0 +1,305 @@
+//! Calibration curves for analytical measurements.
+//!
+//! Standards of known concentration are fitted with a straight line or a
+//! quadratic through the regular ODR request, so uncertainties in both the
+//! concentrations and the signals are honoured. A sample signal `y0` is then
+//! converted back to a concentration `x0` by solving `f(x0) = y0`. Linearising
+//! around the solution, `u(x0)^2 = (u(y0)^2 + g' V g) / f'(x0)^2` where
+//! `g = (1, x0, x0^2)` and `V` is the parameter covariance; for a straight
+//! line this is the textbook inverse-prediction formula. Detection and
+//! quantification limits follow the ICH convention `3.3 * s / slope` and
+//! `10 * s / slope`, with `s` from blank replicates or the intercept.
+
+use super::orchestrator::run_fit_request;
+use crate::scientific::curve_fitting::types::{
+    CalibrationModel, CalibrationRequest, CalibrationResponse, CalibrationSample,
+    DetectionNoiseSource, InversePrediction, ModelLayer, OdrError, OdrFitRequest, OdrFitResponse,
+    OdrResult, VariableInput,
+};
+use crate::scientific::primitives::descriptive::sample_variance;
+
+/// Multiple of the noise over the slope giving the limit of detection.
+const LOD_FACTOR: f64 = 3.3;
+/// Multiple of the noise over the slope giving the limit of quantification.
+const LOQ_FACTOR: f64 = 10.0;
+
+impl CalibrationModel {
+    const fn formula(self) -> &'static str {
+        match self {
+            Self::Linear => "a + b*x",
+            Self::Quadratic => "a + b*x + c*x^2",
+        }
+    }
+
+    const fn parameter_count(self) -> usize {
+        match self {
+            Self::Linear => 2,
+            Self::Quadratic => 3,
+        }
+    }
+}
+
+/// Fit the calibration standards and convert every sample signal to a
+/// concentration with its uncertainty.
+///
+/// # Errors
+/// Returns `OdrError` if the standards, samples or blanks are invalid, the
+/// fit fails, or the slope at zero concentration vanishes.
+pub fn run_calibration(request: &CalibrationRequest) -> OdrResult<CalibrationResponse> {
+    let point_count = request.concentrations.len();
+    if request.signals.len() != point_count {
+        return Err(OdrError::Validation(format!(
+            "Calibration needs one signal per standard: got {point_count} concentrations and {} signals",
+            request.signals.len()
+        )));
+    }
+    let parameter_count = request.model.parameter_count();
+    if point_count <= parameter_count {
+        return Err(OdrError::Validation(format!(
+            "A {} calibration needs at least {} standards, got {point_count}",
+            request.model.formula(),
+            parameter_count + 1
+        )));
+    }
+    if request
+        .concentrations
+        .iter()
+        .any(|value| !value.is_finite())
+    {
+        return Err(OdrError::Validation(
+            "Standard concentrations must be finite".to_owned(),
+        ));
+    }
+
+    let fit = fit_standards(request, parameter_count)?;
+    if !fit.success {
+        return Err(OdrError::Numerical(format!(
+            "Calibration fit did not converge: {}",
+            fit.termination_reason
+        )));
+    }
+
+    let coefficients = &fit.parameter_values;
+    let (intercept, slope) = (coefficients[0], coefficients[1]);
+    if slope == 0.0 {
+        return Err(OdrError::Numerical(
+            "Calibration slope at zero concentration is zero; detection limits are undefined"
+                .to_owned(),
+        ));
+    }
+    let calibrated_range = request
+        .concentrations
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), &x| {
+            (low.min(x), high.max(x))
+        });
+
+    let samples = request
+        .samples
+        .iter()
+        .enumerate()
+        .map(|(index, sample)| inverse_prediction(index, sample, &fit, calibrated_range))
+        .collect::<OdrResult<Vec<_>>>()?;
+
+    let (detection_noise, detection_noise_source) = match &request.blank_signals {
+        Some(blanks) => (blank_noise(blanks)?, DetectionNoiseSource::Blank),
+        None => (
+            fit.parameter_uncertainties[0],
+            DetectionNoiseSource::Intercept,
+        ),
+    };
+
+    Ok(CalibrationResponse {
+        model: request.model,
+        intercept,
+        slope,
+        curvature: coefficients.get(2).copied(),
+        calibrated_range,
+        samples,
+        detection_noise,
+        detection_noise_source,
+        limit_of_detection: LOD_FACTOR * detection_noise / slope.abs(),
+        limit_of_quantification: LOQ_FACTOR * detection_noise / slope.abs(),
+        fit,
+    })
+}
+
+/// Fit the standards with the calibration polynomial.
+fn fit_standards(
+    request: &CalibrationRequest,
+    parameter_count: usize,
+) -> OdrResult<OdrFitResponse> {
+    run_fit_request(&OdrFitRequest {
+        layers: vec![ModelLayer {
+            formula: request.model.formula().to_owned(),
+            dependent_variable: "y".to_owned(),
+            independent_variables: vec!["x".to_owned()],
+        }],
+        independent_variables: vec![VariableInput {
+            name: "x".to_owned(),
+            values: request.concentrations.clone(),
+            uncertainties: request.concentration_uncertainties.clone(),
+            uncertainty_type: None,
+            uncertainty_degrees_of_freedom: None,
+        }],
+        dependent_variables: vec![VariableInput {
+            name: "y".to_owned(),
+            values: request.signals.clone(),
+            // Equal weights when the signals carry no uncertainties; the
+            // covariance is scaled by the scatter of the standards either way
+            uncertainties: Some(
+                request
+                    .signal_uncertainties
+                    .clone()
+                    .unwrap_or_else(|| vec![1.0; request.signals.len()]),
+            ),
+            uncertainty_type: None,
+            uncertainty_degrees_of_freedom: None,
+        }],
+        parameter_names: ["a", "b", "c"][..parameter_count]
+            .iter()
+            .map(|&name| name.to_owned())
+            .collect(),
+        initial_guess: None,
+        max_iterations: None,
+        tolerance: None,
+        initial_damping: None,
+        point_correlations: None,
+        use_poisson_weighting: None,
+        confidence_level: request.confidence_level,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
+    })
+}
+
+/// Concentration of one sample and its uncertainty from the linearised
+/// inverse of the calibration polynomial.
+fn inverse_prediction(
+    index: usize,
+    sample: &CalibrationSample,
+    fit: &OdrFitResponse,
+    calibrated_range: (f64, f64),
+) -> OdrResult<InversePrediction> {
+    let coefficients = &fit.parameter_values;
+    let signal_uncertainty = sample_uncertainty(index, sample, fit.residual_standard_error)?;
+    let concentration = solve_concentration(coefficients, sample.signal, calibrated_range);
+    let uncertainty = concentration.and_then(|x0| {
+        let sensitivity = polynomial_derivative(coefficients, x0);
+        (sensitivity != 0.0).then(|| {
+            let gradient: Vec<f64> = (0..coefficients.len())
+                .scan(1.0, |power, _| {
+                    let current = *power;
+                    *power *= x0;
+                    Some(current)
+                })
+                .collect();
+            let fit_variance = quadratic_form(&fit.parameter_covariance, &gradient);
+            signal_uncertainty
+                .mul_add(signal_uncertainty, fit_variance)
+                .sqrt()
+                / sensitivity.abs()
+        })
+    });
+    Ok(InversePrediction {
+        label: sample.label.clone(),
+        signal: sample.signal,
+        signal_uncertainty,
+        concentration,
+        uncertainty,
+        expanded_uncertainty: uncertainty.map(|value| value * fit.coverage_factor),
+        extrapolated: concentration
+            .is_some_and(|x0| x0 < calibrated_range.0 || x0 > calibrated_range.1),
+    })
+}
+
+/// Standard uncertainty of a sample signal, defaulting to one reading with
+/// the scatter of the standards.
+fn sample_uncertainty(
+    index: usize,
+    sample: &CalibrationSample,
+    residual_standard_error: f64,
+) -> OdrResult<f64> {
+    if !sample.signal.is_finite() {
+        return Err(OdrError::Validation(format!(
+            "samples[{index}].signal must be finite"
+        )));
+    }
+    match sample.uncertainty {
+        Some(uncertainty) if uncertainty.is_finite() && uncertainty >= 0.0 => Ok(uncertainty),
+        Some(uncertainty) => Err(OdrError::Validation(format!(
+            "samples[{index}].uncertainty must be finite and non-negative, got {uncertainty}"
+        ))),
+        None => Ok(residual_standard_error),
+    }
+}
+
+/// Standard deviation of the blank replicates.
+fn blank_noise(blanks: &[f64]) -> OdrResult<f64> {
+    if blanks.len() < 2 {
+        return Err(OdrError::Validation(format!(
+            "Blank noise needs at least 2 blank signals, got {}",
+            blanks.len()
+        )));
+    }
+    if blanks.iter().any(|value| !value.is_finite()) {
+        return Err(OdrError::Validation(
+            "Blank signals must be finite".to_owned(),
+        ));
+    }
+    Ok(sample_variance(blanks).unwrap_or(0.0).sqrt())
+}
+
+/// Concentration at which the calibration polynomial gives `signal`. Of two
+/// roots of a quadratic the one nearer the calibrated range is taken, then
+/// the one nearer its centre.
+fn solve_concentration(coefficients: &[f64], signal: f64, range: (f64, f64)) -> Option<f64> {
+    let (offset, slope) = (coefficients[0] - signal, coefficients[1]);
+    let curvature = coefficients.get(2).copied().unwrap_or(0.0);
+    if curvature == 0.0 {
+        return (slope != 0.0).then(|| -offset / slope);
+    }
+    let discriminant = slope.mul_add(slope, -4.0 * curvature * offset);
+    if discriminant < 0.0 {
+        return None;
+    }
+    // Numerically stable pair of roots; `q` vanishes only for a double root
+    // at zero
+    let q = -0.5 * (slope + discriminant.sqrt().copysign(slope));
+    let roots = if q == 0.0 {
+        [0.0; 2]
+    } else {
+        [q / curvature, offset / q]
+    };
+    let centre = 0.5 * (range.0 + range.1);
+    let outside = |x: f64| (range.0 - x).max(x - range.1).max(0.0);
+    roots.into_iter().min_by(|left, right| {
+        outside(*left)
+            .total_cmp(&outside(*right))
+            .then((left - centre).abs().total_cmp(&(right - centre).abs()))
+    })
+}
+
+/// Derivative of the calibration polynomial at `x`.
+fn polynomial_derivative(coefficients: &[f64], x: f64) -> f64 {
+    let curvature = coefficients.get(2).copied().unwrap_or(0.0);
+    (2.0 * curvature).mul_add(x, coefficients[1])
+}
+
+/// `g' V g` for a covariance matrix `V`.
+fn quadratic_form(covariance: &[Vec<f64>], gradient: &[f64]) -> f64 {
+    gradient
+        .iter()
+        .zip(covariance)
+        .map(|(left, row)| {
+            left * row
+                .iter()
+                .zip(gradient)
+                .map(|(entry, right)| entry * right)
+                .sum::<f64>()
+        })
+        .sum()
+}
@@ -31,3 +31,19 @@ pub const INNER_CORRECTION_DAMPING: f64 = 1e-6;
 /// are treated as having no measurable uncertainty and are excluded from latent
 /// variable corrections.
 pub const CORRECTION_VARIANCE_THRESHOLD: f64 = MIN_VARIANCE * 2.0;
+/// Relative step for central first differences (about `cbrt(f64::EPSILON)`).
+pub const FD_FIRST_DERIVATIVE_STEP: f64 = 6e-6;
+/// Relative step for central second differences (about `f64::EPSILON^(1/4)`).
+pub const FD_SECOND_DERIVATIVE_STEP: f64 = 1.2e-4;
+/// Maximum relative discrepancy between symbolic and finite-difference derivatives
+/// accepted by gradient verification.
+pub const GRADIENT_CHECK_TOLERANCE: f64 = 1e-4;
+/// Floor of the discrepancy scale, relative to `1 + |f|`, so derivatives that vanish
+/// are compared against finite-difference rounding noise rather than zero.
+pub const GRADIENT_CHECK_FLOOR: f64 = 1e-5;
+/// Maximum number of starting points accepted by a multi-start fit.
+pub const MAX_MULTI_STARTS: usize = 256;
+/// Fewest resamples accepted by a bootstrap of the fit.
+pub const MIN_BOOTSTRAP_RESAMPLES: usize = 10;
+/// Maximum number of resamples accepted by a bootstrap of the fit.
+pub const MAX_BOOTSTRAP_RESAMPLES: usize = 10_000;
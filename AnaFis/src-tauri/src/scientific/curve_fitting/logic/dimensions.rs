@@ -0,0 +1,690 @@
+//! Dimensional analysis of model formulas against the units of their variables.
+//!
+//! Every parameter gets an unknown dimension, and the structure of the formula
+//! turns into linear constraints on those unknowns: added terms share one
+//! dimension, function arguments and symbolic exponents are dimensionless, and
+//! the whole formula has the dimension of the dependent variable. Constraints
+//! are kept in reduced row echelon form as they arrive, so one that contradicts
+//! the earlier ones is reported where it occurs. Units are parsed by the same
+//! converter that backs `get_unit_dimensional_formula`.
+
+use std::collections::HashSet;
+use std::iter::once;
+use std::sync::Arc;
+
+use symb_anafis::visitor::ExprView;
+use symb_anafis::{Expr, parse};
+
+use super::sanitization::{normalize_identifiers, validate_identifier, validate_symbol_sets};
+use crate::scientific::curve_fitting::types::{
+    DimensionConflict, FormulaDimension, FormulaDimensionRequest, FormulaDimensionResponse,
+    OdrError, OdrResult, ParameterDimension, ParameterDimensionStatus,
+};
+use crate::unit_conversion::commands::format_dimension;
+use crate::unit_conversion::core::{Dimension, UNIT_CONVERTER};
+
+/// Number of SI base dimensions (M, L, T, I, Θ, N, J).
+const BASE_DIMENSIONS: usize = 7;
+
+/// Exponents closer than this are treated as equal.
+const EXPONENT_TOLERANCE: f64 = 1e-9;
+
+/// Dimension of a subexpression: fixed exponents plus multiples of the unknown
+/// parameter dimensions.
+///
+/// `known` holds the SI exponents followed by the exponent of each distinct
+/// variable unit, which is what lets parameter units be written as "V/s".
+#[derive(Debug, Clone)]
+struct LinearDimension {
+    unknowns: Vec<f64>,
+    known: Vec<f64>,
+}
+
+impl LinearDimension {
+    fn zero(parameter_count: usize, known_len: usize) -> Self {
+        Self {
+            unknowns: vec![0.0; parameter_count],
+            known: vec![0.0; known_len],
+        }
+    }
+
+    /// `self + factor * other`
+    fn plus(mut self, other: &Self, factor: f64) -> Self {
+        add_scaled(&mut self.unknowns, &other.unknowns, factor);
+        add_scaled(&mut self.known, &other.known, factor);
+        self
+    }
+
+    fn scaled(mut self, factor: f64) -> Self {
+        for value in self.unknowns.iter_mut().chain(self.known.iter_mut()) {
+            *value *= factor;
+        }
+        self
+    }
+
+    fn has_unknowns(&self) -> bool {
+        self.unknowns.iter().any(|value| is_nonzero(*value))
+    }
+}
+
+/// A constraint `unknowns · D + known = 0`, normalized so the pivot
+/// parameter has coefficient 1 and no other row mentions it.
+#[derive(Debug)]
+struct Row {
+    pivot: usize,
+    unknowns: Vec<f64>,
+    known: Vec<f64>,
+}
+
+/// Constraints on the parameter dimensions, in reduced row echelon form.
+#[derive(Debug, Default)]
+struct ConstraintSystem {
+    rows: Vec<Row>,
+    /// Set when SI-compatible but different units meet (e.g. m and cm), so
+    /// parameter units cannot be written with the variable units.
+    mixed_units: bool,
+}
+
+impl ConstraintSystem {
+    /// Require `dimension` to be zero; false when that contradicts the
+    /// constraints already present, in which case it is not added.
+    fn require_zero(&mut self, dimension: &LinearDimension) -> bool {
+        let mut unknowns = dimension.unknowns.clone();
+        let mut known = dimension.known.clone();
+        for row in &self.rows {
+            let factor = unknowns[row.pivot];
+            if is_nonzero(factor) {
+                add_scaled(&mut unknowns, &row.unknowns, -factor);
+                add_scaled(&mut known, &row.known, -factor);
+            }
+        }
+
+        let Some(pivot) = unknowns.iter().position(|value| is_nonzero(*value)) else {
+            let (si, units) = known.split_at(BASE_DIMENSIONS);
+            if si.iter().any(|value| is_nonzero(*value)) {
+                return false;
+            }
+            if units.iter().any(|value| is_nonzero(*value)) {
+                self.mixed_units = true;
+            }
+            return true;
+        };
+
+        let scale = unknowns[pivot];
+        for value in unknowns.iter_mut().chain(known.iter_mut()) {
+            *value /= scale;
+        }
+        for row in &mut self.rows {
+            let factor = row.unknowns[pivot];
+            if is_nonzero(factor) {
+                add_scaled(&mut row.unknowns, &unknowns, -factor);
+                add_scaled(&mut row.known, &known, -factor);
+            }
+        }
+        self.rows.push(Row {
+            pivot,
+            unknowns,
+            known,
+        });
+        true
+    }
+}
+
+/// Walks a formula, collecting constraints on the parameter dimensions.
+struct Analyzer<'names> {
+    parameters: &'names [String],
+    /// Variable names with their known dimension vectors.
+    variables: Vec<(String, Vec<f64>)>,
+    known_len: usize,
+    system: ConstraintSystem,
+    inconsistencies: Vec<DimensionConflict>,
+    /// Whether each parameter occurs in the formula.
+    seen: Vec<bool>,
+    /// Outermost dimensionless context each parameter occurs in.
+    contexts: Vec<Option<String>>,
+    /// Dimensionless contexts enclosing the node being walked.
+    enclosing: Vec<String>,
+}
+
+impl<'names> Analyzer<'names> {
+    fn new(
+        parameters: &'names [String],
+        variables: Vec<(String, Vec<f64>)>,
+        known_len: usize,
+    ) -> Self {
+        Self {
+            parameters,
+            variables,
+            known_len,
+            system: ConstraintSystem::default(),
+            inconsistencies: Vec::new(),
+            seen: vec![false; parameters.len()],
+            contexts: vec![None; parameters.len()],
+            enclosing: Vec::new(),
+        }
+    }
+
+    fn zero(&self) -> LinearDimension {
+        LinearDimension::zero(self.parameters.len(), self.known_len)
+    }
+
+    /// Require `dimension` to vanish, recording `describe()` against `term`
+    /// if it cannot.
+    fn require_zero(
+        &mut self,
+        dimension: &LinearDimension,
+        term: &dyn ToString,
+        describe: impl FnOnce() -> String,
+    ) {
+        if !self.system.require_zero(dimension) {
+            self.inconsistencies.push(DimensionConflict {
+                term: term.to_string(),
+                message: describe(),
+            });
+        }
+    }
+
+    fn walk(&mut self, expr: &Expr) -> OdrResult<LinearDimension> {
+        match expr.view() {
+            ExprView::Number(_) => Ok(self.zero()),
+            ExprView::Symbol(name) => Ok(self.symbol(&name)),
+            ExprView::Function { name, args } => self.function(name, args),
+            ExprView::Sum(terms) => self.sum(&terms),
+            ExprView::Product(factors) => {
+                let mut dimension = self.zero();
+                for factor in factors.iter() {
+                    dimension = dimension.plus(&self.walk(factor)?, 1.0);
+                }
+                Ok(dimension)
+            }
+            ExprView::Div(numerator, denominator) => {
+                let numerator = self.walk(numerator)?;
+                Ok(numerator.plus(&self.walk(denominator)?, -1.0))
+            }
+            ExprView::Pow(base, exponent) => self.power(base, exponent),
+            ExprView::Derivative { .. } => Err(OdrError::Validation(
+                "Derivatives are not supported in model formulas".to_owned(),
+            )),
+        }
+    }
+
+    fn symbol(&mut self, name: &str) -> LinearDimension {
+        let mut dimension = self.zero();
+        if let Some(index) = self
+            .parameters
+            .iter()
+            .position(|parameter| parameter == name)
+        {
+            self.seen[index] = true;
+            if self.contexts[index].is_none() {
+                self.contexts[index] = self.enclosing.first().cloned();
+            }
+            dimension.unknowns[index] = 1.0;
+        } else if let Some((_, known)) =
+            self.variables.iter().find(|(variable, _)| variable == name)
+        {
+            dimension.known.clone_from(known);
+        }
+        // Anything else is `pi` or `e`; other symbols are rejected before the walk.
+        dimension
+    }
+
+    fn function(&mut self, name: &str, args: &[Arc<Expr>]) -> OdrResult<LinearDimension> {
+        match (name, args) {
+            ("sqrt", [arg]) => return Ok(self.walk(arg)?.scaled(0.5)),
+            ("cbrt", [arg]) => return Ok(self.walk(arg)?.scaled(1.0 / 3.0)),
+            ("abs", [arg]) => return self.walk(arg),
+            _ => {}
+        }
+
+        let arguments: Vec<String> = args.iter().map(ToString::to_string).collect();
+        let call = format!("{name}({})", arguments.join(", "));
+        self.enclosing.push(format!("the argument of {call}"));
+        for (arg, text) in args.iter().zip(&arguments) {
+            let dimension = self.walk(arg)?;
+            self.require_zero(&dimension, text, || {
+                if dimension.has_unknowns() {
+                    format!("{call} needs a dimensionless argument, which contradicts the rest of the formula")
+                } else {
+                    format!(
+                        "{call} needs a dimensionless argument, but {text} has dimension {}",
+                        dimension_text(&dimension.known)
+                    )
+                }
+            });
+        }
+        self.enclosing.pop();
+        Ok(self.zero())
+    }
+
+    fn sum(&mut self, terms: &[Arc<Expr>]) -> OdrResult<LinearDimension> {
+        let Some((first, rest)) = terms.split_first() else {
+            return Ok(self.zero());
+        };
+        let first_dimension = self.walk(first)?;
+        for term in rest {
+            let difference = self.walk(term)?.plus(&first_dimension, -1.0);
+            self.require_zero(&difference, term, || {
+                if difference.has_unknowns() || first_dimension.has_unknowns() {
+                    format!("{first} and {term} are added but cannot have the same dimension")
+                } else {
+                    let term_dimension = first_dimension.clone().plus(&difference, 1.0);
+                    format!(
+                        "{first} ({}) and {term} ({}) are added but have different dimensions",
+                        dimension_text(&first_dimension.known),
+                        dimension_text(&term_dimension.known)
+                    )
+                }
+            });
+        }
+        Ok(first_dimension)
+    }
+
+    fn power(&mut self, base: &Expr, exponent: &Expr) -> OdrResult<LinearDimension> {
+        if let Some(power) = constant_value(exponent) {
+            return Ok(self.walk(base)?.scaled(power));
+        }
+
+        // A symbolic exponent only makes sense on a dimensionless base
+        let call = format!("({base})^({exponent})");
+        for (part, label) in [(exponent, "exponent"), (base, "base")] {
+            self.enclosing.push(format!("the {label} of {call}"));
+            let dimension = self.walk(part)?;
+            self.require_zero(&dimension, part, || {
+                if dimension.has_unknowns() {
+                    format!("The {label} of {call} must be dimensionless, which contradicts the rest of the formula")
+                } else {
+                    format!(
+                        "The {label} of {call} must be dimensionless, but {part} has dimension {}",
+                        dimension_text(&dimension.known)
+                    )
+                }
+            });
+            self.enclosing.pop();
+        }
+        Ok(self.zero())
+    }
+
+    /// Report of parameter `index` from the final constraints.
+    fn parameter(&self, index: usize, dependent: &str, units: &[String]) -> ParameterDimension {
+        let indeterminate = |reasoning: String| ParameterDimension {
+            name: self.parameters[index].clone(),
+            status: ParameterDimensionStatus::Indeterminate,
+            dimensional_formula: None,
+            unit: None,
+            reasoning,
+        };
+
+        let Some(row) = self.system.rows.iter().find(|row| row.pivot == index) else {
+            // A free unknown: constrained only through other parameters' rows
+            let coupled: Vec<&str> = self
+                .system
+                .rows
+                .iter()
+                .filter(|row| {
+                    row.unknowns
+                        .get(index)
+                        .is_some_and(|value| is_nonzero(*value))
+                })
+                .map(|row| self.parameters[row.pivot].as_str())
+                .collect();
+            return indeterminate(if !coupled.is_empty() {
+                combination_reasoning(&coupled)
+            } else if self.seen[index] {
+                "Nothing in the formula constrains its dimension".to_owned()
+            } else {
+                "Does not appear in the formula".to_owned()
+            });
+        };
+        let coupled: Vec<&str> = row
+            .unknowns
+            .iter()
+            .enumerate()
+            .filter(|&(other, coefficient)| other != index && is_nonzero(*coefficient))
+            .map(|(other, _)| self.parameters[other].as_str())
+            .collect();
+        if !coupled.is_empty() {
+            return indeterminate(combination_reasoning(&coupled));
+        }
+
+        let exponents: Vec<f64> = row.known.iter().map(|value| -value).collect();
+        let (si, unit_exponents) = exponents.split_at(BASE_DIMENSIONS);
+        let Some(dimension) = integer_dimension(si) else {
+            return indeterminate(format!(
+                "The formula would give it a non-integer dimension {}",
+                dimension_text(&exponents)
+            ));
+        };
+        let (status, reasoning) = match (
+            si.iter().all(|value| !is_nonzero(*value)),
+            &self.contexts[index],
+        ) {
+            (true, Some(context)) => (
+                ParameterDimensionStatus::DimensionlessRequired,
+                format!("Appears in {context}, which must be dimensionless"),
+            ),
+            (true, None) => (
+                ParameterDimensionStatus::DimensionlessRequired,
+                "The formula fixes it as dimensionless".to_owned(),
+            ),
+            (false, Some(context)) => (
+                ParameterDimensionStatus::Inferred,
+                format!("Fixed by {context} being dimensionless"),
+            ),
+            (false, None) => (
+                ParameterDimensionStatus::Inferred,
+                format!("Fixed by matching the formula to the dimension of '{dependent}'"),
+            ),
+        };
+        ParameterDimension {
+            name: self.parameters[index].clone(),
+            status,
+            dimensional_formula: Some(format_dimension(&dimension)),
+            unit: if self.system.mixed_units {
+                None
+            } else {
+                unit_text(unit_exponents, units)
+            },
+            reasoning,
+        }
+    }
+}
+
+/// Reasoning for a parameter whose dimension is only fixed jointly with `coupled`
+fn combination_reasoning(coupled: &[&str]) -> String {
+    format!(
+        "Only its combination with {} is fixed; how the dimension splits between them is arbitrary",
+        coupled.join(", ")
+    )
+}
+
+/// Infer the dimension of each parameter of a formula from the units of its
+/// variables, and list where the formula cannot be dimensionally consistent.
+pub fn analyze_dimensions(
+    request: &FormulaDimensionRequest,
+) -> OdrResult<FormulaDimensionResponse> {
+    let parameters = normalize_identifiers(&request.parameter_names, "parameter")?;
+    let independent_names: Vec<String> = request
+        .independent_variables
+        .iter()
+        .map(|variable| variable.name.clone())
+        .collect();
+    let independent = normalize_identifiers(&independent_names, "independent variable")?;
+    validate_symbol_sets(&independent, &parameters)?;
+    let dependent = request.dependent_variable.name.trim().to_lowercase();
+    validate_identifier(&dependent, "dependent variable")?;
+
+    // Distinct variable units are the basis parameter units are written in
+    let mut units: Vec<String> = Vec::new();
+    for variable in once(&request.dependent_variable).chain(&request.independent_variables) {
+        let unit = variable.unit.trim();
+        if !unit.is_empty() && !units.iter().any(|known| known == unit) {
+            units.push(unit.to_owned());
+        }
+    }
+    let known_len = BASE_DIMENSIONS + units.len();
+    let unit_vector = |unit: &str| -> OdrResult<Vec<f64>> {
+        let mut vector = vec![0.0; known_len];
+        let unit = unit.trim();
+        if let Some(position) = units.iter().position(|known| known == unit) {
+            vector[..BASE_DIMENSIONS].copy_from_slice(&si_exponents(&parse_unit_dimension(unit)?));
+            vector[BASE_DIMENSIONS + position] = 1.0;
+        }
+        Ok(vector)
+    };
+    let dependent_vector = unit_vector(&request.dependent_variable.unit)?;
+    let variables = independent
+        .iter()
+        .zip(&request.independent_variables)
+        .map(|(name, variable)| Ok((name.clone(), unit_vector(&variable.unit)?)))
+        .collect::<OdrResult<Vec<_>>>()?;
+
+    let known: HashSet<String> = independent.iter().chain(&parameters).cloned().collect();
+    let expr = parse_formula(&request.formula, &known)?;
+
+    let mut analyzer = Analyzer::new(&parameters, variables, known_len);
+    let model = analyzer.walk(&expr)?;
+    let target = LinearDimension {
+        unknowns: vec![0.0; parameters.len()],
+        known: dependent_vector,
+    };
+    let mismatch = model.clone().plus(&target, -1.0);
+    analyzer.require_zero(&mismatch, &expr, || {
+        let target_text = dimension_text(&target.known);
+        if model.has_unknowns() {
+            format!("The formula cannot have the dimension of '{dependent}' ({target_text})")
+        } else {
+            format!(
+                "The formula has dimension {} but '{dependent}' has dimension {target_text}",
+                dimension_text(&model.known)
+            )
+        }
+    });
+
+    let parameters = (0..parameters.len())
+        .map(|index| analyzer.parameter(index, &dependent, &units))
+        .collect();
+    Ok(FormulaDimensionResponse {
+        parameters,
+        is_consistent: analyzer.inconsistencies.is_empty(),
+        inconsistencies: analyzer
+            .inconsistencies
+            .into_iter()
+            .map(|conflict| conflict.message)
+            .collect(),
+    })
+}
+
+/// Dimension of a formula whose variables all have known dimensions, checked
+/// against `expected` when one is given.
+///
+/// The formula has no parameters, so every inconsistency is a conflict
+/// between known dimensions and names the term it arises in.
+///
+/// # Errors
+/// Returns an error if a variable name is invalid, the formula does not
+/// parse, or it uses a symbol that is not one of the variables.
+pub fn verify_dimensions(
+    formula: &str,
+    variables: &[(String, Dimension)],
+    expected: Option<&Dimension>,
+) -> OdrResult<FormulaDimension> {
+    let names: Vec<String> = variables.iter().map(|(name, _)| name.clone()).collect();
+    let names = if names.is_empty() {
+        names
+    } else {
+        normalize_identifiers(&names, "variable")?
+    };
+    let known: HashSet<String> = names.iter().cloned().collect();
+    let expr = parse_formula(formula, &known)?;
+
+    let variables = names
+        .into_iter()
+        .zip(variables)
+        .map(|(name, (_, dimension))| (name, si_exponents(dimension).to_vec()))
+        .collect();
+    let mut analyzer = Analyzer::new(&[], variables, BASE_DIMENSIONS);
+    let output = analyzer.walk(&expr)?;
+    // Terms that disagree leave the formula without a dimension of its own
+    let dimension = if analyzer.inconsistencies.is_empty() {
+        integer_dimension(&output.known)
+    } else {
+        None
+    };
+
+    if let Some(expected) = expected {
+        let target = LinearDimension {
+            unknowns: Vec::new(),
+            known: si_exponents(expected).to_vec(),
+        };
+        analyzer.require_zero(&output.clone().plus(&target, -1.0), &expr, || {
+            format!(
+                "The formula has dimension {} but {} is expected",
+                dimension_text(&output.known),
+                format_dimension(expected)
+            )
+        });
+    }
+
+    Ok(FormulaDimension {
+        dimension,
+        conflicts: analyzer.inconsistencies,
+    })
+}
+
+/// Parse a formula, rejecting symbols outside `known` other than `pi` and `e`.
+fn parse_formula(formula: &str, known: &HashSet<String>) -> OdrResult<Expr> {
+    let formula = formula.trim().to_lowercase();
+    let expr = parse(&formula, known, &HashSet::new(), None)
+        .map_err(|error| OdrError::Parse(error.to_string()))?;
+    let mut unknown: Vec<String> = expr
+        .variables()
+        .into_iter()
+        .filter(|name| !known.contains(name) && !matches!(name.as_str(), "pi" | "e"))
+        .collect();
+    if !unknown.is_empty() {
+        unknown.sort();
+        return Err(OdrError::UnknownSymbols(unknown));
+    }
+    Ok(expr)
+}
+
+/// Parse a unit string with the shared unit converter.
+fn parse_unit_dimension(unit: &str) -> OdrResult<Dimension> {
+    let converter = UNIT_CONVERTER
+        .lock()
+        .map_err(|error| OdrError::Validation(format!("Failed to lock unit converter: {error}")))?;
+    converter
+        .parse_unit(unit)
+        .map(|parsed| parsed.dimension)
+        .map_err(|error| OdrError::Validation(format!("Unit '{unit}': {error}")))
+}
+
+/// Value of an exponent built only from numbers.
+fn constant_value(expr: &Expr) -> Option<f64> {
+    match expr.view() {
+        ExprView::Number(value) => Some(value),
+        ExprView::Sum(terms) => terms.iter().map(|term| constant_value(term)).sum(),
+        ExprView::Product(factors) => factors
+            .iter()
+            .map(|factor| constant_value(factor))
+            .product(),
+        ExprView::Div(numerator, denominator) => {
+            Some(constant_value(numerator)? / constant_value(denominator)?)
+        }
+        _ => None,
+    }
+}
+
+fn si_exponents(dimension: &Dimension) -> [f64; BASE_DIMENSIONS] {
+    [
+        dimension.mass,
+        dimension.length,
+        dimension.time,
+        dimension.current,
+        dimension.temperature,
+        dimension.amount,
+        dimension.luminosity,
+    ]
+    .map(f64::from)
+}
+
+/// SI exponents as a `Dimension`, if they are all integers.
+#[allow(
+    clippy::cast_possible_truncation,
+    reason = "Exponents are rounded and checked to be integers first"
+)]
+fn integer_dimension(si: &[f64]) -> Option<Dimension> {
+    let mut exponents = [0_i32; BASE_DIMENSIONS];
+    for (exponent, value) in exponents.iter_mut().zip(si) {
+        if is_nonzero(value - value.round()) {
+            return None;
+        }
+        *exponent = value.round() as i32;
+    }
+    let [mass, length, time, current, temperature, amount, luminosity] = exponents;
+    Some(Dimension {
+        mass,
+        length,
+        time,
+        current,
+        temperature,
+        amount,
+        luminosity,
+    })
+}
+
+/// Dimensional formula of the SI part of `known`, e.g. `[L·T^-1]`.
+fn dimension_text(known: &[f64]) -> String {
+    let si = &known[..BASE_DIMENSIONS];
+    integer_dimension(si).map_or_else(
+        || {
+            let exponents: Vec<String> = ["M", "L", "T", "I", "\u{398}", "N", "J"]
+                .iter()
+                .zip(si)
+                .filter(|&(_, value)| is_nonzero(*value))
+                .map(|(symbol, value)| format!("{symbol}^{}", round_exponent(*value)))
+                .collect();
+            format!("[{}]", exponents.join("\u{b7}"))
+        },
+        |dimension| format_dimension(&dimension),
+    )
+}
+
+/// Unit written with the variable units, e.g. `V/s`; `None` for non-integer
+/// exponents.
+fn unit_text(exponents: &[f64], units: &[String]) -> Option<String> {
+    let mut numerator = Vec::new();
+    let mut denominator = Vec::new();
+    for (&value, unit) in exponents.iter().zip(units) {
+        if is_nonzero(value - value.round()) {
+            return None;
+        }
+        if !is_nonzero(value) {
+            continue;
+        }
+        let base = if unit.chars().all(char::is_alphanumeric) {
+            unit.clone()
+        } else {
+            format!("({unit})")
+        };
+        let power = value.abs().round();
+        let factor = if is_nonzero(power - 1.0) {
+            format!("{base}^{power}")
+        } else {
+            base
+        };
+        if value > 0.0 {
+            numerator.push(factor);
+        } else {
+            denominator.push(factor);
+        }
+    }
+
+    let top = if numerator.is_empty() {
+        "1".to_owned()
+    } else {
+        numerator.join("\u{b7}")
+    };
+    Some(match denominator.as_slice() {
+        [] => top,
+        [single] => format!("{top}/{single}"),
+        _ => format!("{top}/({})", denominator.join("\u{b7}")),
+    })
+}
+
+/// Exponent rounded for display, e.g. `0.5` or `0.333`.
+fn round_exponent(value: f64) -> f64 {
+    (value * 1000.0).round() / 1000.0
+}
+
+fn is_nonzero(value: f64) -> bool {
+    value.abs() > EXPONENT_TOLERANCE
+}
+
+/// `target += factor * source`
+fn add_scaled(target: &mut [f64], source: &[f64], factor: f64) {
+    for (value, &addend) in target.iter_mut().zip(source) {
+        *value = factor.mul_add(addend, *value);
+    }
+}
@@ -1,8 +1,103 @@
 use std::iter::repeat_n;
 use std::mem::take;
+use std::ops::Range;
 use symb_anafis::{Expr, eval_f64};
 
-use super::{BatchEvaluationResult, OdrError, OdrResult};
+use super::{
+    BatchEvaluationResult, CompiledModel, FD_FIRST_DERIVATIVE_STEP, FD_SECOND_DERIVATIVE_STEP,
+    HessianBatchResult, OdrError, OdrResult,
+};
+
+/// Evaluates a layer's model values and first derivatives, using the symbolic
+/// gradients or central finite differences depending on how the model was compiled.
+///
+/// # Errors
+/// Returns `OdrError::Numerical` if evaluation fails or produces non-finite values.
+pub fn evaluate_layer_batch(
+    model: &CompiledModel,
+    columns: &[&[f64]],
+    layer_idx: usize,
+) -> OdrResult<BatchEvaluationResult> {
+    if model.numerical_gradients {
+        evaluate_model_and_gradients_numerical(
+            &model.model_expr,
+            &model.independent_names,
+            &model.parameter_names,
+            columns,
+            layer_idx,
+        )
+    } else {
+        evaluate_model_and_gradients_batch(
+            &model.model_expr,
+            &model.independent_gradient_exprs,
+            &model.parameter_gradient_exprs,
+            &model.independent_names,
+            &model.parameter_names,
+            columns,
+            layer_idx,
+        )
+    }
+}
+
+/// Evaluates a layer's second derivatives, using the symbolic Hessian expressions
+/// or central finite differences depending on how the model was compiled.
+///
+/// # Errors
+/// Returns `OdrError::Numerical` if evaluation fails or produces non-finite values.
+pub fn evaluate_layer_hessians_batch(
+    model: &CompiledModel,
+    columns: &[&[f64]],
+    layer_idx: usize,
+) -> OdrResult<HessianBatchResult> {
+    if model.numerical_gradients {
+        let independent_count = model.independent_names.len();
+        let variable_count = columns.len();
+        let full = evaluate_hessian_numerical(
+            &model.model_expr,
+            &variable_order(&model.independent_names, &model.parameter_names),
+            columns,
+            variable_count,
+            &format!("layer {layer_idx} finite-difference Hessian"),
+        )?;
+        let block = |rows: Range<usize>, cols: Range<usize>| -> Vec<Vec<f64>> {
+            rows.flat_map(|row| cols.clone().map(move |col| (row, col)))
+                .map(|(row, col)| full[row][col].clone())
+                .collect()
+        };
+        return Ok(HessianBatchResult {
+            independent: block(0..independent_count, 0..independent_count),
+            mixed: block(0..independent_count, independent_count..variable_count),
+            parameter: block(
+                independent_count..variable_count,
+                independent_count..variable_count,
+            ),
+        });
+    }
+
+    Ok(HessianBatchResult {
+        independent: evaluate_hessian_exprs_batch(
+            &model.independent_hessian_exprs,
+            &model.independent_names,
+            &model.parameter_names,
+            columns,
+            &format!("layer {layer_idx} independent Hessian"),
+        )?,
+        mixed: evaluate_hessian_exprs_batch(
+            &model.independent_parameter_mixed_hessian_exprs,
+            &model.independent_names,
+            &model.parameter_names,
+            columns,
+            &format!("layer {layer_idx} mixed Hessian"),
+        )?,
+        parameter: evaluate_hessian_exprs_batch(
+            &model.parameter_hessian_exprs,
+            &model.independent_names,
+            &model.parameter_names,
+            columns,
+            &format!("layer {layer_idx} parameter Hessian"),
+        )?,
+    })
+}
 
 /// Evaluates the model and all its gradients in a single batched call using `eval_f64`.
 ///
@@ -36,14 +131,7 @@ pub fn evaluate_model_and_gradients_batch(
     }
 
     // Build variable names for each expression (all use the same variable order)
-    let mut all_var_names: Vec<&str> =
-        Vec::with_capacity(independent_names.len() + parameter_names.len());
-    for name in independent_names {
-        all_var_names.push(name.as_str());
-    }
-    for name in parameter_names {
-        all_var_names.push(name.as_str());
-    }
+    let all_var_names = variable_order(independent_names, parameter_names);
 
     // Each expression uses the same variable order
     let var_names: Vec<&[&str]> = repeat_n(&all_var_names[..], total_exprs).collect();
@@ -119,17 +207,22 @@ pub fn evaluate_model_expr_batch(
     columns: &[&[f64]],
     evaluator_label: &str,
 ) -> OdrResult<Vec<f64>> {
-    let exprs: Vec<&Expr> = vec![model_expr];
+    evaluate_model_expr_batch_ordered(
+        model_expr,
+        &variable_order(independent_names, parameter_names),
+        columns,
+        evaluator_label,
+    )
+}
 
-    let mut all_var_names: Vec<&str> =
-        Vec::with_capacity(independent_names.len() + parameter_names.len());
-    for name in independent_names {
-        all_var_names.push(name.as_str());
-    }
-    for name in parameter_names {
-        all_var_names.push(name.as_str());
-    }
-    let var_names: Vec<&[&str]> = vec![&all_var_names[..]];
+fn evaluate_model_expr_batch_ordered(
+    model_expr: &Expr,
+    variable_order: &[&str],
+    columns: &[&[f64]],
+    evaluator_label: &str,
+) -> OdrResult<Vec<f64>> {
+    let exprs: Vec<&Expr> = vec![model_expr];
+    let var_names: Vec<&[&str]> = vec![variable_order];
 
     let data: Vec<&[&[f64]]> = vec![columns];
 
@@ -215,14 +308,7 @@ pub fn evaluate_hessian_exprs_batch(
         return Ok(Vec::new());
     }
 
-    let mut all_var_names: Vec<&str> =
-        Vec::with_capacity(independent_names.len() + parameter_names.len());
-    for name in independent_names {
-        all_var_names.push(name.as_str());
-    }
-    for name in parameter_names {
-        all_var_names.push(name.as_str());
-    }
+    let all_var_names = variable_order(independent_names, parameter_names);
 
     let exprs: Vec<&Expr> = hessian_exprs.iter().collect();
     let var_names: Vec<&[&str]> = repeat_n(&all_var_names[..], hessian_exprs.len()).collect();
@@ -252,3 +338,201 @@ pub fn evaluate_hessian_exprs_batch(
 
     Ok(validated)
 }
+
+/// Evaluates the model and its first derivatives by central differences.
+///
+/// All perturbed evaluations go through a single `eval_f64` call, so this mode
+/// shares the batching of the symbolic path.
+///
+/// # Errors
+/// Returns `OdrError::Numerical` if evaluation fails or produces non-finite values.
+pub fn evaluate_model_and_gradients_numerical(
+    model_expr: &Expr,
+    independent_names: &[String],
+    parameter_names: &[String],
+    columns: &[&[f64]],
+    layer_idx: usize,
+) -> OdrResult<BatchEvaluationResult> {
+    let steps = difference_steps(columns, FD_FIRST_DERIVATIVE_STEP);
+    // Stencil layout: base point, then (+h, -h) for each column.
+    let mut stencils = vec![Vec::new()];
+    for column in 0..columns.len() {
+        stencils.push(vec![(column, 1.0)]);
+        stencils.push(vec![(column, -1.0)]);
+    }
+    let mut outputs = evaluate_stencils(
+        model_expr,
+        &variable_order(independent_names, parameter_names),
+        columns,
+        &steps,
+        &stencils,
+        &format!("layer {layer_idx} finite-difference gradient"),
+    )?;
+
+    let mut independent_derivatives: Vec<Vec<f64>> = (0..columns.len())
+        .map(|column| {
+            outputs[1 + 2 * column]
+                .iter()
+                .zip(&outputs[2 + 2 * column])
+                .zip(&steps[column])
+                .map(|((plus, minus), step)| (plus - minus) / (2.0 * step))
+                .collect()
+        })
+        .collect();
+    let parameter_derivatives = independent_derivatives.split_off(independent_names.len());
+
+    Ok(BatchEvaluationResult {
+        fitted_values: take(&mut outputs[0]),
+        independent_derivatives,
+        parameter_derivatives,
+    })
+}
+
+/// Hessian of the model with respect to the first `differentiated` columns by
+/// central differences, as `result[row][col][point_idx]`.
+///
+/// # Errors
+/// Returns `OdrError::Numerical` if evaluation fails or produces non-finite values.
+pub fn evaluate_hessian_numerical(
+    model_expr: &Expr,
+    variable_order: &[&str],
+    columns: &[&[f64]],
+    differentiated: usize,
+    label: &str,
+) -> OdrResult<Vec<Vec<Vec<f64>>>> {
+    let steps = difference_steps(columns, FD_SECOND_DERIVATIVE_STEP);
+    // Stencil layout: base point, (+h, -h) per column, then (++, +-, -+, --)
+    // per column pair.
+    let mut stencils = vec![Vec::new()];
+    for column in 0..differentiated {
+        stencils.push(vec![(column, 1.0)]);
+        stencils.push(vec![(column, -1.0)]);
+    }
+    for row in 0..differentiated {
+        for col in (row + 1)..differentiated {
+            for (row_sign, col_sign) in [(1.0, 1.0), (1.0, -1.0), (-1.0, 1.0), (-1.0, -1.0)] {
+                stencils.push(vec![(row, row_sign), (col, col_sign)]);
+            }
+        }
+    }
+    let outputs = evaluate_stencils(
+        model_expr,
+        variable_order,
+        columns,
+        &steps,
+        &stencils,
+        label,
+    )?;
+
+    let base = &outputs[0];
+    let mut hessian = vec![vec![Vec::new(); differentiated]; differentiated];
+    for column in 0..differentiated {
+        hessian[column][column] = base
+            .iter()
+            .zip(&outputs[1 + 2 * column])
+            .zip(&outputs[2 + 2 * column])
+            .zip(&steps[column])
+            .map(|(((center, plus), minus), step)| {
+                (-2.0_f64).mul_add(*center, plus + minus) / (step * step)
+            })
+            .collect();
+    }
+    let mut corner_offset = 1 + 2 * differentiated;
+    for row in 0..differentiated {
+        for col in (row + 1)..differentiated {
+            let corners = &outputs[corner_offset..corner_offset + 4];
+            let values: Vec<f64> = (0..base.len())
+                .map(|point| {
+                    (corners[0][point] - corners[1][point] - corners[2][point] + corners[3][point])
+                        / (4.0 * steps[row][point] * steps[col][point])
+                })
+                .collect();
+            hessian[col][row].clone_from(&values);
+            hessian[row][col] = values;
+            corner_offset += 4;
+        }
+    }
+
+    Ok(hessian)
+}
+
+/// Variable order shared by every evaluator: independents, then parameters.
+#[must_use]
+pub fn variable_order<'names>(
+    independent_names: &'names [String],
+    parameter_names: &'names [String],
+) -> Vec<&'names str> {
+    independent_names
+        .iter()
+        .chain(parameter_names)
+        .map(String::as_str)
+        .collect()
+}
+
+/// Per-point central-difference steps `relative_step * max(|v|, 1)`, rounded so
+/// that `v + h` is exactly representable.
+fn difference_steps(columns: &[&[f64]], relative_step: f64) -> Vec<Vec<f64>> {
+    columns
+        .iter()
+        .map(|column| {
+            column
+                .iter()
+                .map(|&value| relative_step.mul_add(value.abs().max(1.0), value) - value)
+                .collect()
+        })
+        .collect()
+}
+
+/// Evaluates the model on shifted copies of `columns` in one `eval_f64` call.
+///
+/// Each stencil lists `(column, multiple)` shifts: column `c` at point `i` moves
+/// by `multiple * steps[c][i]`. The shifted copies are stacked into one long batch
+/// so the model is compiled once per call. Returns one output row per stencil.
+fn evaluate_stencils(
+    model_expr: &Expr,
+    variable_order: &[&str],
+    columns: &[&[f64]],
+    steps: &[Vec<f64>],
+    stencils: &[Vec<(usize, f64)>],
+    label: &str,
+) -> OdrResult<Vec<Vec<f64>>> {
+    if columns.len() != variable_order.len() {
+        return Err(OdrError::Numerical(format!(
+            "{label} received {} columns for {} variables",
+            columns.len(),
+            variable_order.len()
+        )));
+    }
+    let expected_points = expected_point_count(columns, label)?;
+
+    // stacked[column][stencil_idx * expected_points + point_idx]
+    let stacked: Vec<Vec<f64>> = columns
+        .iter()
+        .enumerate()
+        .map(|(column_idx, column)| {
+            let mut values = Vec::with_capacity(stencils.len() * expected_points);
+            for stencil in stencils {
+                let multiple = stencil
+                    .iter()
+                    .find(|(shifted, _)| *shifted == column_idx)
+                    .map_or(0.0, |&(_, multiple)| multiple);
+                values.extend(
+                    column
+                        .iter()
+                        .zip(&steps[column_idx])
+                        .map(|(value, step)| multiple.mul_add(*step, *value)),
+                );
+            }
+            values
+        })
+        .collect();
+    let stacked_refs: Vec<&[f64]> = stacked.iter().map(Vec::as_slice).collect();
+
+    let output =
+        evaluate_model_expr_batch_ordered(model_expr, variable_order, &stacked_refs, label)?;
+    Ok(output
+        .chunks(expected_points.max(1))
+        .take(stencils.len())
+        .map(<[f64]>::to_vec)
+        .collect())
+}
@@ -55,10 +55,14 @@ pub fn prepare_data(request: &OdrFitRequest) -> OdrResult<PreparedData> {
     let mut had_zero_count_poisson = false;
     let mut clamped_variance_count = 0_usize;
 
-    let mut process_variable = |var: &VariableInput, is_dependent: bool| -> OdrResult<()> {
+    // `field` is the request path of the variable, e.g. `independent_variables[1]`
+    let mut process_variable = |field: &str,
+                                var: &VariableInput,
+                                is_dependent: bool|
+     -> OdrResult<()> {
         if var.values.len() != point_count {
             return Err(OdrError::Validation(format!(
-                "Variable '{}' length mismatch: expected {}, got {}",
+                "{field}.values ('{}'): expected {} values, got {}",
                 var.name,
                 point_count,
                 var.values.len()
@@ -66,21 +70,26 @@ pub fn prepare_data(request: &OdrFitRequest) -> OdrResult<PreparedData> {
         }
 
         let name = var.name.trim().to_lowercase();
-        validate_identifier(&name, "variable")?;
+        validate_identifier(&name, "variable")
+            .map_err(|error| OdrError::Validation(format!("{field}.name: {error}")))?;
 
         if variable_names.contains(&name) {
             return Err(OdrError::Validation(format!(
-                "Duplicate expected variable name mapping: {name}"
+                "{field}.name ('{}'): duplicate variable name '{name}'",
+                var.name
             )));
         }
 
         variable_names.push(name);
-        variable_values.push(sanitize_values(&var.values, &var.name)?);
+        variable_values.push(sanitize_values(
+            &var.values,
+            &format!("{field}.values ('{}')", var.name),
+        )?);
 
         let resolved_dof = match (var.uncertainty_type, var.uncertainty_degrees_of_freedom) {
             (_, Some(dof)) if !dof.is_finite() || dof <= 0.0 => {
                 return Err(OdrError::Validation(format!(
-                    "Uncertainty degrees of freedom for '{}' must be finite and > 0",
+                    "{field}.uncertainty_degrees_of_freedom ('{}'): must be finite and > 0, got {dof}",
                     var.name
                 )));
             }
@@ -101,13 +110,16 @@ pub fn prepare_data(request: &OdrFitRequest) -> OdrResult<PreparedData> {
         if let Some(uncertainties) = &var.uncertainties {
             if uncertainties.len() != point_count {
                 return Err(OdrError::Validation(format!(
-                    "Uncertainty length mismatch for '{}': expected {}, got {}",
+                    "{field}.uncertainties ('{}'): expected {} values, got {}",
                     var.name,
                     point_count,
                     uncertainties.len()
                 )));
             }
-            let (sigma, clamped_count) = sanitize_uncertainties(uncertainties, &var.name)?;
+            let (sigma, clamped_count) = sanitize_uncertainties(
+                uncertainties,
+                &format!("{field}.uncertainties ('{}')", var.name),
+            )?;
             if clamped_count > 0 {
                 had_uncertainty_clamp = true;
                 clamped_variance_count += clamped_count;
@@ -118,7 +130,7 @@ pub fn prepare_data(request: &OdrFitRequest) -> OdrResult<PreparedData> {
             for (idx, val) in var.values.iter().enumerate() {
                 if *val < 0.0 {
                     return Err(OdrError::Validation(format!(
-                        "Poisson weighting requires non-negative counts for '{}' at index {idx}",
+                        "{field}.values[{idx}] ('{}'): Poisson weighting requires non-negative counts, got {val}",
                         var.name
                     )));
                 }
@@ -143,11 +155,11 @@ pub fn prepare_data(request: &OdrFitRequest) -> OdrResult<PreparedData> {
         Ok(())
     };
 
-    for var in &request.independent_variables {
-        process_variable(var, false)?;
+    for (index, var) in request.independent_variables.iter().enumerate() {
+        process_variable(&format!("independent_variables[{index}]"), var, false)?;
     }
-    for var in &request.dependent_variables {
-        process_variable(var, true)?;
+    for (index, var) in request.dependent_variables.iter().enumerate() {
+        process_variable(&format!("dependent_variables[{index}]"), var, true)?;
     }
 
     let point_covariances = build_point_covariances(
@@ -177,7 +189,7 @@ pub fn sanitize_values(values: &[f64], label: &str) -> OdrResult<Vec<f64>> {
     for (idx, value) in values.iter().enumerate() {
         if !value.is_finite() {
             return Err(OdrError::Validation(format!(
-                "Non-finite value in {label} at index {idx}"
+                "{label} at index {idx}: non-finite value {value}"
             )));
         }
         sanitized.push(*value);
@@ -194,12 +206,12 @@ pub fn sanitize_uncertainties(values: &[f64], label: &str) -> OdrResult<(Vec<f64
     for (idx, &val) in values.iter().enumerate() {
         if !val.is_finite() {
             return Err(OdrError::Validation(format!(
-                "Non-finite uncertainty found in variable '{label}' at index {idx}"
+                "{label} at index {idx}: non-finite uncertainty {val}"
             )));
         }
         if val < 0.0 {
             return Err(OdrError::Validation(format!(
-                "Negative uncertainty found in variable '{label}' at index {idx}"
+                "{label} at index {idx}: negative uncertainty {val}"
             )));
         }
         if val < sigma_min {
@@ -12,7 +12,7 @@ use super::{
     CORRECTION_VARIANCE_THRESHOLD, CompiledModel, EvaluationState, INNER_CORRECTION_DAMPING,
     MIN_VARIANCE, OdrError, OdrResult, ParameterSource, PreparedData,
     compute_second_derivative_corrections_numerical, dependent_curvature_coefficient,
-    evaluate_hessian_exprs_batch, evaluate_model_and_gradients_batch, extract_joint_covariance,
+    evaluate_layer_batch, evaluate_layer_hessians_batch, extract_joint_covariance,
     invert_small_psd, solve_inner_corrections_multi_point, solve_linear_system_matrix,
     sqrt_psd_matrix,
 };
@@ -185,39 +185,12 @@ pub fn evaluate_model(
 
         let column_refs: Vec<&[f64]> = columns.iter().map(|c| &c[..]).collect();
 
-        layer_batch_results.push(evaluate_model_and_gradients_batch(
-            &model.model_expr,
-            &model.independent_gradient_exprs,
-            &model.parameter_gradient_exprs,
-            &model.independent_names,
-            &model.parameter_names,
-            &column_refs,
-            layer_idx,
-        )?);
-
-        layer_independent_hessian_results.push(evaluate_hessian_exprs_batch(
-            &model.independent_hessian_exprs,
-            &model.independent_names,
-            &model.parameter_names,
-            &column_refs,
-            &format!("layer {layer_idx} independent Hessian"),
-        )?);
-
-        layer_mixed_hessian_results.push(evaluate_hessian_exprs_batch(
-            &model.independent_parameter_mixed_hessian_exprs,
-            &model.independent_names,
-            &model.parameter_names,
-            &column_refs,
-            &format!("layer {layer_idx} mixed Hessian"),
-        )?);
-
-        layer_parameter_hessian_results.push(evaluate_hessian_exprs_batch(
-            &model.parameter_hessian_exprs,
-            &model.independent_names,
-            &model.parameter_names,
-            &column_refs,
-            &format!("layer {layer_idx} parameter Hessian"),
-        )?);
+        layer_batch_results.push(evaluate_layer_batch(model, &column_refs, layer_idx)?);
+
+        let hessians = evaluate_layer_hessians_batch(model, &column_refs, layer_idx)?;
+        layer_independent_hessian_results.push(hessians.independent);
+        layer_mixed_hessian_results.push(hessians.mixed);
+        layer_parameter_hessian_results.push(hessians.parameter);
     }
 
     for point in 0..point_count {
@@ -0,0 +1,151 @@
+//! Verification of symbolic first derivatives against central finite differences.
+//!
+//! Run once at the initial guess when a request asks for it, so a wrong or
+//! mis-simplified derivative is reported per symbol before it can silently
+//! steer the optimizer.
+
+use super::{
+    CompiledModel, GRADIENT_CHECK_FLOOR, GRADIENT_CHECK_TOLERANCE, OdrError, OdrResult,
+    PreparedData, evaluate_model_and_gradients_batch, evaluate_model_and_gradients_numerical,
+};
+use symb_anafis::Expr;
+
+use std::sync::Arc;
+
+/// Checks the symbolic derivatives of every layer at the observed independent values
+/// and the given global parameters.
+///
+/// # Errors
+/// Returns `OdrError::Validation` with a per-symbol report if any derivative disagrees
+/// with its finite-difference estimate, or `OdrError::Numerical` if evaluation fails.
+pub fn verify_model_gradients(
+    models: &[Arc<CompiledModel>],
+    data: &PreparedData,
+    global_parameters: &[f64],
+    global_parameter_names: &[String],
+) -> OdrResult<()> {
+    let mut report = Vec::new();
+    for (layer_idx, model) in models.iter().enumerate() {
+        let mut columns: Vec<&[f64]> =
+            Vec::with_capacity(model.independent_names.len() + model.parameter_names.len());
+        for name in &model.independent_names {
+            let idx = data
+                .variable_names
+                .iter()
+                .position(|n| n == name)
+                .ok_or_else(|| {
+                    OdrError::Validation(format!("Independent variable {name} not found in data"))
+                })?;
+            columns.push(&data.variable_values[idx]);
+        }
+        let mut parameter_columns = Vec::with_capacity(model.parameter_names.len());
+        for local_name in &model.parameter_names {
+            let global_idx = global_parameter_names
+                .iter()
+                .position(|name| name == local_name)
+                .ok_or_else(|| {
+                    OdrError::Validation(format!(
+                        "Parameter {local_name} not found in global parameters"
+                    ))
+                })?;
+            parameter_columns.push(vec![global_parameters[global_idx]; data.point_count]);
+        }
+        columns.extend(parameter_columns.iter().map(Vec::as_slice));
+
+        report.extend(layer_gradient_discrepancies(
+            &model.model_expr,
+            &model.independent_gradient_exprs,
+            &model.parameter_gradient_exprs,
+            &model.independent_names,
+            &model.parameter_names,
+            &columns,
+            layer_idx,
+        )?);
+    }
+
+    if report.is_empty() {
+        Ok(())
+    } else {
+        Err(OdrError::Validation(format!(
+            "Analytic gradient verification failed at the initial guess (relative tolerance {GRADIENT_CHECK_TOLERANCE:e}): {}",
+            report.join("; ")
+        )))
+    }
+}
+
+/// Compares one layer's derivative expressions with central differences of
+/// `model_expr` and describes every symbol whose worst relative discrepancy over
+/// the points exceeds `GRADIENT_CHECK_TOLERANCE`.
+///
+/// # Errors
+/// Returns `OdrError::Numerical` if evaluation fails or produces non-finite values.
+#[allow(
+    clippy::too_many_arguments,
+    reason = "Mirrors the batch evaluator signature"
+)]
+pub fn layer_gradient_discrepancies(
+    model_expr: &Expr,
+    independent_gradient_exprs: &[Expr],
+    parameter_gradient_exprs: &[Expr],
+    independent_names: &[String],
+    parameter_names: &[String],
+    columns: &[&[f64]],
+    layer_idx: usize,
+) -> OdrResult<Vec<String>> {
+    let analytic = evaluate_model_and_gradients_batch(
+        model_expr,
+        independent_gradient_exprs,
+        parameter_gradient_exprs,
+        independent_names,
+        parameter_names,
+        columns,
+        layer_idx,
+    )?;
+    let numerical = evaluate_model_and_gradients_numerical(
+        model_expr,
+        independent_names,
+        parameter_names,
+        columns,
+        layer_idx,
+    )?;
+
+    let symbols = independent_names.iter().chain(parameter_names);
+    let analytic_derivatives = analytic
+        .independent_derivatives
+        .iter()
+        .chain(&analytic.parameter_derivatives);
+    let numerical_derivatives = numerical
+        .independent_derivatives
+        .iter()
+        .chain(&numerical.parameter_derivatives);
+
+    let mut report = Vec::new();
+    for ((symbol, exact), approximate) in
+        symbols.zip(analytic_derivatives).zip(numerical_derivatives)
+    {
+        let mut worst = (0.0_f64, 0_usize);
+        for (point, ((a, n), f)) in exact
+            .iter()
+            .zip(approximate)
+            .zip(&analytic.fitted_values)
+            .enumerate()
+        {
+            let scale = a
+                .abs()
+                .max(n.abs())
+                .max(GRADIENT_CHECK_FLOOR * (1.0 + f.abs()));
+            let discrepancy = (a - n).abs() / scale;
+            if discrepancy > worst.0 {
+                worst = (discrepancy, point);
+            }
+        }
+        let (discrepancy, point) = worst;
+        if discrepancy > GRADIENT_CHECK_TOLERANCE {
+            report.push(format!(
+                "layer {layer_idx} d/d{symbol}: relative discrepancy {discrepancy:.3e} at point {point} (analytic {:.6e}, finite difference {:.6e})",
+                exact[point], approximate[point]
+            ));
+        }
+    }
+    Ok(report)
+}
@@ -10,13 +10,14 @@
 //! the theoretical rigor of the profiled ODR approach.
 
 use super::{
-    CORRECTION_VARIANCE_THRESHOLD, CompiledModel, INNER_CORRECTION_DAMPING,
-    INNER_CORRECTION_MAX_ITERS, INNER_CORRECTION_TOLERANCE, OdrError, OdrResult, PreparedData,
-    dependent_curvature_coefficient, evaluate_model_and_gradients_batch, extract_joint_covariance,
-    invert_small_psd, solve_linear_system,
+    CORRECTION_VARIANCE_THRESHOLD, CompiledModel, FD_SECOND_DERIVATIVE_STEP,
+    INNER_CORRECTION_DAMPING, INNER_CORRECTION_MAX_ITERS, INNER_CORRECTION_TOLERANCE, OdrError,
+    OdrResult, PreparedData, dependent_curvature_coefficient, evaluate_layer_batch,
+    extract_joint_covariance, invert_small_psd, solve_linear_system,
 };
 use nalgebra::{DMatrix, DVector};
 use std::sync::Arc;
+use symb_anafis::CompiledEvaluator;
 
 #[derive(Copy, Clone)]
 /// Source of parameters for a batch solve.
@@ -150,15 +151,7 @@ pub fn solve_inner_corrections_multi_point(
             }
 
             let column_refs: Vec<&[f64]> = columns.iter().map(|c| &c[..]).collect();
-            let batch_res = evaluate_model_and_gradients_batch(
-                &model.model_expr,
-                &model.independent_gradient_exprs,
-                &model.parameter_gradient_exprs,
-                &model.independent_names,
-                &model.parameter_names,
-                &column_refs,
-                layer_idx,
-            )?;
+            let batch_res = evaluate_layer_batch(model, &column_refs, layer_idx)?;
             layer_batch_eval_results.push(Some(batch_res));
         }
 
@@ -320,6 +313,10 @@ pub fn evaluate_model_hessian_wrt_independents(
         return Ok(hessian);
     }
 
+    if let Some(evaluator) = &model.model_evaluator {
+        return finite_difference_independent_hessian(evaluator, args, independent_count);
+    }
+
     for row in 0..independent_count {
         for col in 0..independent_count {
             let idx = row * independent_count + col;
@@ -345,3 +342,50 @@ pub fn evaluate_model_hessian_wrt_independents(
 
     Ok(hessian)
 }
+
+/// Central-difference Hessian of the compiled model with respect to the first
+/// `independent_count` arguments at a single point.
+fn finite_difference_independent_hessian(
+    evaluator: &CompiledEvaluator,
+    args: &[f64],
+    independent_count: usize,
+) -> OdrResult<DMatrix<f64>> {
+    let steps: Vec<f64> = args[..independent_count]
+        .iter()
+        .map(|&value| FD_SECOND_DERIVATIVE_STEP.mul_add(value.abs().max(1.0), value) - value)
+        .collect();
+    let mut shifted = args.to_vec();
+    let mut evaluate_shifted = |shifts: &[(usize, f64)]| -> OdrResult<f64> {
+        shifted.copy_from_slice(args);
+        for &(idx, multiple) in shifts {
+            shifted[idx] = multiple.mul_add(steps[idx], shifted[idx]);
+        }
+        let value = evaluator.evaluate(&shifted);
+        if value.is_finite() {
+            Ok(value)
+        } else {
+            Err(OdrError::Numerical(
+                "Non-finite value while evaluating finite-difference independent-variable Hessian"
+                    .to_owned(),
+            ))
+        }
+    };
+
+    let center = evaluate_shifted(&[])?;
+    let mut hessian = DMatrix::<f64>::zeros(independent_count, independent_count);
+    for row in 0..independent_count {
+        let plus = evaluate_shifted(&[(row, 1.0)])?;
+        let minus = evaluate_shifted(&[(row, -1.0)])?;
+        hessian[(row, row)] = (-2.0_f64).mul_add(center, plus + minus) / (steps[row] * steps[row]);
+        for col in (row + 1)..independent_count {
+            let corners = evaluate_shifted(&[(row, 1.0), (col, 1.0)])?
+                - evaluate_shifted(&[(row, 1.0), (col, -1.0)])?
+                - evaluate_shifted(&[(row, -1.0), (col, 1.0)])?
+                + evaluate_shifted(&[(row, -1.0), (col, -1.0)])?;
+            let value = corners / (4.0 * steps[row] * steps[col]);
+            hessian[(row, col)] = value;
+            hessian[(col, row)] = value;
+        }
+    }
+    Ok(hessian)
+}
@@ -0,0 +1,83 @@
+//! Analytic fast path for models that are linear in their parameters.
+//!
+//! When every second derivative of every layer with respect to the parameters
+//! vanishes identically, the weighted residuals are affine in the parameters
+//! and one Gauss-Newton step from any starting point lands exactly on the
+//! weighted least-squares solution. With uncertainties only on the dependent
+//! variables that step is the whole fit; with independent-variable
+//! uncertainties the latent corrections make the problem nonlinear again, so
+//! the step only seeds the Levenberg-Marquardt loop.
+
+use std::sync::Arc;
+
+use symb_anafis::Expr;
+
+use super::{
+    CORRECTION_VARIANCE_THRESHOLD, CompiledModel, EvaluationState, OdrResult, PointCovariances,
+    PreparedData, build_normal_equations, diagnose_matrix, evaluate_model, solve_linear_system,
+};
+
+/// Whether every layer is linear in the parameters, i.e. all symbolic second
+/// derivatives with respect to the parameters simplify to zero.
+///
+/// Models compiled for numerical gradients carry no symbolic derivatives and
+/// are never treated as linear.
+#[must_use]
+pub fn is_linear_in_parameters(models: &[Arc<CompiledModel>]) -> bool {
+    models.iter().all(|model| {
+        !model.numerical_gradients && model.parameter_hessian_exprs.iter().all(Expr::is_zero_num)
+    })
+}
+
+/// Whether any independent variable of any layer carries an uncertainty above
+/// `CORRECTION_VARIANCE_THRESHOLD` at some point.
+#[must_use]
+pub fn has_independent_uncertainty(models: &[Arc<CompiledModel>], data: &PreparedData) -> bool {
+    let independent_indices: Vec<usize> = models
+        .iter()
+        .flat_map(|model| &model.independent_names)
+        .filter_map(|name| data.variable_names.iter().position(|known| known == name))
+        .collect();
+    let distinct_points = match data.point_covariances {
+        PointCovariances::Shared(_) => 1,
+        PointCovariances::PerPoint(_) => data.point_count,
+    };
+    (0..distinct_points).any(|point| {
+        independent_indices.iter().any(|&index| {
+            data.point_covariances[point][index][index] > CORRECTION_VARIANCE_THRESHOLD
+        })
+    })
+}
+
+/// Weighted least-squares parameters of a parameter-linear model: a single
+/// undamped Gauss-Newton step from `start`, solved through the SVD.
+///
+/// Returns `None` when the normal matrix has rank zero or the step is not
+/// finite, leaving the iterative solver to report the failure.
+///
+/// # Errors
+/// Returns `OdrError` if the model cannot be evaluated at `start` or at the
+/// solution.
+pub fn solve_linear_parameters(
+    models: &[Arc<CompiledModel>],
+    data: &PreparedData,
+    start: &[f64],
+    global_parameter_names: &[String],
+) -> OdrResult<Option<(Vec<f64>, EvaluationState)>> {
+    let state = evaluate_model(models, data, start, global_parameter_names)?;
+    let (normal_matrix, gradient_vector) = build_normal_equations(&state);
+    if diagnose_matrix(&normal_matrix).effective_rank == 0 {
+        return Ok(None);
+    }
+    let delta = solve_linear_system(normal_matrix, &-gradient_vector)?;
+    let parameters: Vec<f64> = start
+        .iter()
+        .zip(delta.iter())
+        .map(|(value, step)| value + step)
+        .collect();
+    if parameters.iter().any(|value| !value.is_finite()) {
+        return Ok(None);
+    }
+    let solved = evaluate_model(models, data, &parameters, global_parameter_names)?;
+    Ok(Some((parameters, solved)))
+}
@@ -3,13 +3,16 @@ pub mod curvature;
 pub mod data_prep;
 pub mod diagnostics;
 pub mod evaluation;
+pub mod gradient_check;
 pub mod inference;
 pub mod inner_solve;
 pub mod linear_algebra;
+pub mod linear_solve;
 pub mod solver;
 pub mod state;
 pub use batch_eval::{
-    evaluate_hessian_exprs_batch, evaluate_model_and_gradients_batch, evaluate_model_expr_batch,
+    evaluate_layer_batch, evaluate_layer_hessians_batch, evaluate_model_and_gradients_batch,
+    evaluate_model_and_gradients_numerical, evaluate_model_expr_batch,
 };
 pub use curvature::{
     compute_second_derivative_corrections_numerical, dependent_curvature_coefficient,
@@ -18,17 +21,22 @@ pub use curvature::{
 pub use data_prep::{is_positive_semidefinite, prepare_data};
 pub use diagnostics::{build_normal_equations, diagnose_matrix};
 pub use evaluation::evaluate_model;
+pub use gradient_check::verify_model_gradients;
 pub use inference::{ParameterInference, compute_parameter_inference};
 pub use inner_solve::{ParameterSource, solve_inner_corrections_multi_point};
 pub use linear_algebra::{
     invert_small_psd, solve_linear_system, solve_linear_system_matrix, sqrt_psd_matrix,
 };
+pub use linear_solve::{
+    has_independent_uncertainty, is_linear_in_parameters, solve_linear_parameters,
+};
 pub use solver::solve_odr;
 pub use state::{
-    BatchEvaluationResult, EvaluationState, OdrTerminationReason, PointCovariances, PreparedData,
+    BatchEvaluationResult, EvaluationState, HessianBatchResult, OdrTerminationReason,
+    PointCovariances, PreparedData,
 };
 
-pub use super::cache::{CompiledModel, get_or_compile_model};
+pub use super::cache::{CompiledModel, ModelCacheStats, get_or_compile_model, model_cache_stats};
 pub use super::constants::*;
 pub use super::sanitization::{normalize_identifiers, validate_identifier, validate_symbol_sets};
 pub use super::{OdrError, OdrFitRequest, OdrResult, UncertaintyType, VariableInput};
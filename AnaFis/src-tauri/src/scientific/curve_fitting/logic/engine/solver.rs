@@ -11,19 +11,35 @@
 use nalgebra::DVector;
 use std::sync::Arc;
 
+use crate::utils::progress::ProgressReporter;
+
 use super::{
-    CompiledModel, EvaluationState, MAX_DAMPING, MIN_DAMPING, OdrResult, OdrTerminationReason,
-    PreparedData, build_normal_equations, diagnose_matrix, evaluate_model, solve_linear_system,
+    CompiledModel, EvaluationState, MAX_DAMPING, MIN_DAMPING, OdrError, OdrResult,
+    OdrTerminationReason, PreparedData, build_normal_equations, diagnose_matrix, evaluate_model,
+    solve_linear_system,
 };
 
+/// Iterations between two checks of the cancellation flag.
+const CANCEL_CHECK_INTERVAL: usize = 10;
+
 /// Solves the Orthogonal Distance Regression (ODR) problem using Levenberg-Marquardt across all layers simultaneously.
 ///
+/// With a `progress` reporter, the completed iterations and the current
+/// chi-squared are reported at the start of every iteration, and the loop
+/// stops every `CANCEL_CHECK_INTERVAL` iterations if the computation was
+/// cancelled.
+///
 /// # Errors
-/// Returns `OdrError` if numerical convergence fails or fitting error occurs.
+/// Returns `OdrError::Cancelled` with the current parameters if cancelled,
+/// or `OdrError` if numerical convergence fails or fitting error occurs.
 #[allow(
     clippy::too_many_lines,
     reason = "ODR loop keeps all acceptance/rejection and termination logic explicit for numerical safety"
 )]
+#[allow(
+    clippy::too_many_arguments,
+    reason = "Solver settings are scalar knobs shared with the multi-start driver"
+)]
 pub fn solve_odr(
     models: &[Arc<CompiledModel>],
     data: &PreparedData,
@@ -32,6 +48,7 @@ pub fn solve_odr(
     max_iterations: usize,
     tolerance: f64,
     initial_damping: f64,
+    progress: Option<&ProgressReporter>,
 ) -> OdrResult<(Vec<f64>, EvaluationState, usize, OdrTerminationReason)> {
     let mut damping = initial_damping;
     let mut nu = 2.0;
@@ -45,6 +62,15 @@ pub fn solve_odr(
     let mut trial_parameters = vec![0.0_f64; parameter_count];
 
     for iteration in 0..max_iterations {
+        if let Some(progress) = progress {
+            if iteration % CANCEL_CHECK_INTERVAL == 0 && progress.is_cancelled() {
+                return Err(OdrError::Cancelled {
+                    parameters,
+                    iterations: iteration,
+                });
+            }
+            progress.report(iteration, max_iterations, Some(current.chi_squared));
+        }
         iterations = iteration + 1;
 
         let (normal_matrix, gradient_vector) = build_normal_equations(&current);
@@ -27,6 +27,21 @@ pub enum OdrTerminationReason {
     DampingSaturated,
     /// Stopped after exhausting `max_iterations`.
     MaxIterations,
+    /// Solved in one step because the model is linear in the parameters.
+    LinearSolution,
+    /// Stopped because the computation was cancelled.
+    Cancelled,
+}
+
+impl OdrTerminationReason {
+    /// Whether the loop stopped on a convergence criterion.
+    #[must_use]
+    pub const fn is_converged(self) -> bool {
+        matches!(
+            self,
+            Self::ScaledGradient | Self::ScaledStep | Self::Improvement | Self::LinearSolution
+        )
+    }
 }
 
 /// Data prepared and validated for the ODR solver.
@@ -124,3 +139,14 @@ pub struct BatchEvaluationResult {
     /// Derivatives with respect to parameters: [`param_idx`][point_idx].
     pub parameter_derivatives: Vec<Vec<f64>>,
 }
+
+/// Result of batch evaluation of the model's second derivatives.
+#[derive(Debug, Clone)]
+pub struct HessianBatchResult {
+    /// Independent-variable Hessian, row-major: [`row * n_x + col`][point_idx].
+    pub independent: Vec<Vec<f64>>,
+    /// Mixed independent-parameter Hessian, row-major: [`x_idx * n_p + p_idx`][point_idx].
+    pub mixed: Vec<Vec<f64>>,
+    /// Parameter Hessian, row-major: [`row * n_p + col`][point_idx].
+    pub parameter: Vec<Vec<f64>>,
+}
@@ -87,6 +87,11 @@ pub fn build_warnings(context: &WarningContext<'_>) -> Vec<String> {
                 .to_owned(),
         );
     }
+    if termination_reason == OdrTerminationReason::Cancelled {
+        warnings.push(
+            "Fit cancelled before convergence; reporting the estimate at cancellation".to_owned(),
+        );
+    }
     if termination_reason == OdrTerminationReason::DampingSaturated {
         warnings.push(
             "Damping saturated before convergence; solution may be weakly constrained".to_owned(),
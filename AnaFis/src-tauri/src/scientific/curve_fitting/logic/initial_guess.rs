@@ -0,0 +1,494 @@
+//! Data-driven starting values for common model shapes.
+//!
+//! When a request carries no initial guess, a single-layer, single-variable
+//! formula is reduced to a structural template (parameters become `P`, the
+//! independent variable `X`) and matched against known shapes. Each shape
+//! derives its parameters from the data; anything else falls back to 1.0.
+
+use std::f64::consts::{FRAC_PI_2, LN_2, TAU};
+use std::sync::Arc;
+
+use super::engine::{CompiledModel, PreparedData};
+use crate::scientific::time_series::SpectralEngine;
+
+/// Derives parameter values, in order of first appearance in the template,
+/// from data sorted by the independent variable.
+type Heuristic = fn(&[f64], &[f64]) -> Option<Vec<f64>>;
+
+/// Recognised shapes: canonical template, heuristic description, heuristic.
+const SHAPES: &[(&str, &str, Heuristic)] = &[
+    ("P*X+P", "linear least squares", line_slope_first),
+    ("P+P*X", "linear least squares", line_intercept_first),
+    (
+        "P*exp(P*X)",
+        "log-linear regression (exponential)",
+        exponential,
+    ),
+    (
+        "P*exp(-P*X)",
+        "log-linear regression (exponential)",
+        exponential_decay,
+    ),
+    (
+        "P*exp(-((X-P)/P)^2)",
+        "peak height, position and width (Gaussian)",
+        gaussian,
+    ),
+    (
+        "P*exp(-((X-P)/P)^2)+P",
+        "peak height, position and width (Gaussian)",
+        gaussian_with_baseline,
+    ),
+    (
+        "P*exp(-P*X^2)",
+        "peak height and width (Gaussian)",
+        centred_gaussian,
+    ),
+    (
+        "P*exp(-P*X^2)+P",
+        "peak height and width (Gaussian)",
+        centred_gaussian_with_baseline,
+    ),
+    ("P*sin(P*X+P)", "FFT dominant frequency (sinusoid)", sine),
+    (
+        "P*sin(P*X+P)+P",
+        "FFT dominant frequency (sinusoid)",
+        sine_with_offset,
+    ),
+    ("P*cos(P*X+P)", "FFT dominant frequency (sinusoid)", cosine),
+    (
+        "P*cos(P*X+P)+P",
+        "FFT dominant frequency (sinusoid)",
+        cosine_with_offset,
+    ),
+    (
+        "P/(1+exp(-P*(X-P)))",
+        "plateau and midpoint detection (logistic)",
+        logistic,
+    ),
+];
+
+/// Starting parameter values and the heuristic that produced them.
+#[derive(Debug, Clone)]
+pub struct InitialGuess {
+    /// Values in global parameter order.
+    pub values: Vec<f64>,
+    /// Description of the heuristic, or `None` for the all-ones default.
+    pub heuristic: Option<&'static str>,
+}
+
+/// Builds a starting point for a request without an explicit initial guess.
+#[must_use]
+pub fn build_initial_guess(
+    models: &[Arc<CompiledModel>],
+    prepared: &PreparedData,
+    parameter_names: &[String],
+) -> InitialGuess {
+    shape_guess(models, prepared, parameter_names).map_or_else(
+        || InitialGuess {
+            values: vec![1.0; parameter_names.len()],
+            heuristic: None,
+        },
+        |(values, heuristic)| InitialGuess {
+            values,
+            heuristic: Some(heuristic),
+        },
+    )
+}
+
+fn shape_guess(
+    models: &[Arc<CompiledModel>],
+    prepared: &PreparedData,
+    parameter_names: &[String],
+) -> Option<(Vec<f64>, &'static str)> {
+    let [model] = models else {
+        return None;
+    };
+    let [independent] = model.independent_names.as_slice() else {
+        return None;
+    };
+    let (template, appearance) = canonical_template(&model.formula, independent, parameter_names)?;
+    let &(_, description, heuristic) = SHAPES.iter().find(|(shape, _, _)| *shape == template)?;
+
+    let column = |name: &str| {
+        prepared
+            .variable_names
+            .iter()
+            .position(|candidate| candidate == name)
+            .map(|idx| &prepared.variable_values[idx])
+    };
+    let mut points: Vec<(f64, f64)> = column(independent)?
+        .iter()
+        .copied()
+        .zip(column(&model.dependent_name)?.iter().copied())
+        .collect();
+    points.sort_by(|left, right| left.0.total_cmp(&right.0));
+    let (x, y): (Vec<f64>, Vec<f64>) = points.into_iter().unzip();
+
+    let derived = heuristic(&x, &y)?;
+    if derived.len() != appearance.len() || derived.iter().any(|value| !value.is_finite()) {
+        return None;
+    }
+    let values = parameter_names
+        .iter()
+        .map(|name| {
+            appearance
+                .iter()
+                .position(|seen| seen == name)
+                .map(|idx| derived[idx])
+        })
+        .collect::<Option<Vec<f64>>>()?;
+    Some((values, description))
+}
+
+/// Reduces a formula to its structural template, returning the template and
+/// the parameters in order of appearance. Returns `None` if a parameter is
+/// used more than once or not at all.
+fn canonical_template(
+    formula: &str,
+    independent: &str,
+    parameter_names: &[String],
+) -> Option<(String, Vec<String>)> {
+    let normalized = formula.replace("**", "^");
+    let mut template = String::with_capacity(normalized.len());
+    let mut appearance: Vec<String> = Vec::with_capacity(parameter_names.len());
+    let mut chars = normalized.chars().peekable();
+    while let Some(current) = chars.next() {
+        if current.is_whitespace() {
+            continue;
+        }
+        if current.is_ascii_alphabetic() || current == '_' {
+            let mut identifier = String::from(current);
+            while let Some(&next) = chars.peek() {
+                if next.is_ascii_alphanumeric() || next == '_' {
+                    identifier.push(next);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            if identifier == independent {
+                template.push('X');
+            } else if parameter_names.contains(&identifier) {
+                if appearance.contains(&identifier) {
+                    return None;
+                }
+                appearance.push(identifier);
+                template.push('P');
+            } else {
+                template.push_str(&identifier);
+            }
+        } else {
+            template.push(current);
+        }
+    }
+    (appearance.len() == parameter_names.len()).then_some((template, appearance))
+}
+
+/// Ordinary least-squares line `(slope, intercept)`.
+fn linear_fit(x: &[f64], y: &[f64]) -> Option<(f64, f64)> {
+    if x.len() < 2 {
+        return None;
+    }
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for means"
+    )]
+    let count = x.len() as f64;
+    let x_mean = x.iter().sum::<f64>() / count;
+    let y_mean = y.iter().sum::<f64>() / count;
+    let (covariance, variance) = x.iter().zip(y).fold((0.0, 0.0), |(cov, var), (xi, yi)| {
+        let dx = xi - x_mean;
+        (dx.mul_add(yi - y_mean, cov), dx.mul_add(dx, var))
+    });
+    if variance <= 0.0 {
+        return None;
+    }
+    let slope = covariance / variance;
+    Some((slope, slope.mul_add(-x_mean, y_mean)))
+}
+
+fn line_slope_first(x: &[f64], y: &[f64]) -> Option<Vec<f64>> {
+    linear_fit(x, y).map(|(slope, intercept)| vec![slope, intercept])
+}
+
+fn line_intercept_first(x: &[f64], y: &[f64]) -> Option<Vec<f64>> {
+    linear_fit(x, y).map(|(slope, intercept)| vec![intercept, slope])
+}
+
+/// `(amplitude, rate)` of `a*exp(b*x)` from a line through `ln|y|`.
+fn log_linear(x: &[f64], y: &[f64]) -> Option<(f64, f64)> {
+    let sign = y.first()?.signum();
+    if y.iter().any(|value| value * sign <= 0.0) {
+        return None;
+    }
+    let logs: Vec<f64> = y.iter().map(|value| value.abs().ln()).collect();
+    let (slope, intercept) = linear_fit(x, &logs)?;
+    Some((sign * intercept.exp(), slope))
+}
+
+fn exponential(x: &[f64], y: &[f64]) -> Option<Vec<f64>> {
+    log_linear(x, y).map(|(amplitude, rate)| vec![amplitude, rate])
+}
+
+fn exponential_decay(x: &[f64], y: &[f64]) -> Option<Vec<f64>> {
+    log_linear(x, y).map(|(amplitude, rate)| vec![amplitude, -rate])
+}
+
+/// Baseline estimated from the two ends of the sorted data.
+fn end_baseline(y: &[f64]) -> Option<f64> {
+    Some(f64::midpoint(*y.first()?, *y.last()?))
+}
+
+/// `(height, position, half width at half maximum)` of the largest excursion
+/// from `baseline`.
+fn peak(x: &[f64], y: &[f64], baseline: f64) -> Option<(f64, f64, f64)> {
+    let (peak_idx, _) = y
+        .iter()
+        .map(|value| (value - baseline).abs())
+        .enumerate()
+        .max_by(|left, right| left.1.total_cmp(&right.1))?;
+    let height = y[peak_idx] - baseline;
+    if height == 0.0 {
+        return None;
+    }
+    let half = height.abs() / 2.0;
+    let excursion = |idx: usize| (y[idx] - baseline).abs();
+    let crossing = |inside: usize, outside: usize| {
+        let fraction = (excursion(inside) - half) / (excursion(inside) - excursion(outside));
+        fraction.mul_add(x[outside] - x[inside], x[inside])
+    };
+
+    let left = (0..peak_idx)
+        .rev()
+        .find(|&idx| excursion(idx) < half)
+        .map(|idx| crossing(idx + 1, idx));
+    let right = (peak_idx + 1..y.len())
+        .find(|&idx| excursion(idx) < half)
+        .map(|idx| crossing(idx - 1, idx));
+    let span = x[x.len() - 1] - x[0];
+    let half_width = match (left, right) {
+        (Some(low), Some(high)) => (high - low) / 2.0,
+        (Some(low), None) => x[peak_idx] - low,
+        (None, Some(high)) => high - x[peak_idx],
+        (None, None) => span / 4.0,
+    };
+    (half_width > 0.0).then_some((height, x[peak_idx], half_width))
+}
+
+fn gaussian(x: &[f64], y: &[f64]) -> Option<Vec<f64>> {
+    let (height, position, half_width) = peak(x, y, 0.0)?;
+    Some(vec![height, position, half_width / LN_2.sqrt()])
+}
+
+fn gaussian_with_baseline(x: &[f64], y: &[f64]) -> Option<Vec<f64>> {
+    let baseline = end_baseline(y)?;
+    let (height, position, half_width) = peak(x, y, baseline)?;
+    Some(vec![height, position, half_width / LN_2.sqrt(), baseline])
+}
+
+fn centred_gaussian(x: &[f64], y: &[f64]) -> Option<Vec<f64>> {
+    let (height, _, half_width) = peak(x, y, 0.0)?;
+    Some(vec![height, LN_2 / (half_width * half_width)])
+}
+
+fn centred_gaussian_with_baseline(x: &[f64], y: &[f64]) -> Option<Vec<f64>> {
+    let baseline = end_baseline(y)?;
+    let (height, _, half_width) = peak(x, y, baseline)?;
+    Some(vec![height, LN_2 / (half_width * half_width), baseline])
+}
+
+/// `[amplitude, angular frequency, phase, offset]` of `a*sin(b*x + c) + d`.
+///
+/// The frequency is the FFT peak of the data resampled onto an even grid;
+/// amplitude and phase then follow from a linear fit at that frequency.
+fn sinusoid(x: &[f64], y: &[f64], with_offset: bool) -> Option<[f64; 4]> {
+    let count = x.len();
+    if count < 4 {
+        return None;
+    }
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for mean and grid spacing"
+    )]
+    let (offset, spacing) = (
+        if with_offset {
+            y.iter().sum::<f64>() / count as f64
+        } else {
+            0.0
+        },
+        (x[count - 1] - x[0]) / (count - 1) as f64,
+    );
+    if spacing <= 0.0 {
+        return None;
+    }
+
+    let mut cursor = 0;
+    let grid: Vec<f64> = (0..count)
+        .map(|step| {
+            #[allow(
+                clippy::cast_precision_loss,
+                reason = "Grid index casting to f64 for position"
+            )]
+            let position = (step as f64).mul_add(spacing, x[0]);
+            while cursor + 2 < count && x[cursor + 1] < position {
+                cursor += 1;
+            }
+            let width = x[cursor + 1] - x[cursor];
+            let fraction = if width > 0.0 {
+                ((position - x[cursor]) / width).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            fraction.mul_add(y[cursor + 1] - y[cursor], y[cursor]) - offset
+        })
+        .collect();
+    let angular = TAU * SpectralEngine::dominant_frequency(&grid, spacing)?;
+
+    // Normal equations for y - d ≈ A sin(bx) + B cos(bx).
+    let (mut ss, mut sc, mut cc, mut ys, mut yc) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for (xi, yi) in x.iter().zip(y) {
+        let (sin, cos) = (angular * xi).sin_cos();
+        let centred = yi - offset;
+        ss = sin.mul_add(sin, ss);
+        sc = sin.mul_add(cos, sc);
+        cc = cos.mul_add(cos, cc);
+        ys = centred.mul_add(sin, ys);
+        yc = centred.mul_add(cos, yc);
+    }
+    let determinant = ss.mul_add(cc, -(sc * sc));
+    if determinant.abs() <= f64::EPSILON * ss * cc {
+        return None;
+    }
+    let sin_weight = ys.mul_add(cc, -(yc * sc)) / determinant;
+    let cos_weight = yc.mul_add(ss, -(ys * sc)) / determinant;
+    Some([
+        sin_weight.hypot(cos_weight),
+        angular,
+        cos_weight.atan2(sin_weight),
+        offset,
+    ])
+}
+
+fn sine(x: &[f64], y: &[f64]) -> Option<Vec<f64>> {
+    sinusoid(x, y, false).map(|[amplitude, angular, phase, _]| vec![amplitude, angular, phase])
+}
+
+fn sine_with_offset(x: &[f64], y: &[f64]) -> Option<Vec<f64>> {
+    sinusoid(x, y, true).map(|parameters| parameters.to_vec())
+}
+
+fn cosine(x: &[f64], y: &[f64]) -> Option<Vec<f64>> {
+    sinusoid(x, y, false)
+        .map(|[amplitude, angular, phase, _]| vec![amplitude, angular, phase - FRAC_PI_2])
+}
+
+fn cosine_with_offset(x: &[f64], y: &[f64]) -> Option<Vec<f64>> {
+    sinusoid(x, y, true).map(|[amplitude, angular, phase, offset]| {
+        vec![amplitude, angular, phase - FRAC_PI_2, offset]
+    })
+}
+
+/// `[capacity, rate, midpoint]` of `a / (1 + exp(-b (x - c)))`.
+///
+/// The capacity is the plateau (largest excursion), the midpoint the first
+/// crossing of half the plateau, and the rate follows from the slope of the
+/// central 20-80 % rise, which equals `a b / 4` at the midpoint.
+fn logistic(x: &[f64], y: &[f64]) -> Option<Vec<f64>> {
+    let capacity = *y
+        .iter()
+        .max_by(|left, right| left.abs().total_cmp(&right.abs()))?;
+    if capacity == 0.0 {
+        return None;
+    }
+    let fraction = |value: f64| value / capacity;
+    let midpoint = x
+        .windows(2)
+        .zip(y.windows(2))
+        .find(|(_, pair)| (fraction(pair[0]) - 0.5) * (fraction(pair[1]) - 0.5) <= 0.0)
+        .map(|(xs, pair)| {
+            let denominator = pair[1] - pair[0];
+            if denominator == 0.0 {
+                xs[0]
+            } else {
+                ((capacity / 2.0 - pair[0]) / denominator).mul_add(xs[1] - xs[0], xs[0])
+            }
+        })?;
+
+    let (central_x, central_y): (Vec<f64>, Vec<f64>) = x
+        .iter()
+        .zip(y)
+        .filter(|(_, value)| (0.2..=0.8).contains(&fraction(**value)))
+        .map(|(xi, yi)| (*xi, *yi))
+        .unzip();
+    let rising = y[y.len() - 1] / capacity > y[0] / capacity;
+    let rate = match linear_fit(&central_x, &central_y) {
+        Some((slope, _)) if slope != 0.0 => 4.0 * slope / capacity,
+        _ => {
+            let span = x[x.len() - 1] - x[0];
+            let magnitude = if span > 0.0 { 8.0 / span } else { 1.0 };
+            if rising { magnitude } else { -magnitude }
+        }
+    };
+    Some(vec![capacity, rate, midpoint])
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|name| (*name).to_owned()).collect()
+    }
+
+    #[test]
+    fn test_canonical_template() {
+        let (template, appearance) = canonical_template(
+            "amp*exp(-((t - mu)/w)^2) + base",
+            "t",
+            &names(&["mu", "amp", "w", "base"]),
+        )
+        .unwrap();
+        assert_eq!(template, "P*exp(-((X-P)/P)^2)+P");
+        assert_eq!(appearance, names(&["amp", "mu", "w", "base"]));
+        assert!(canonical_template("a*exp(a*x)", "x", &names(&["a"])).is_none());
+        assert!(canonical_template("a*x", "x", &names(&["a", "b"])).is_none());
+    }
+
+    #[test]
+    fn test_shape_heuristics() {
+        let x: Vec<f64> = (0..200).map(|i| f64::from(i) * 0.05).collect();
+
+        let decaying: Vec<f64> = x.iter().map(|&xi| 2.5 * (-0.4 * xi).exp()).collect();
+        let decay = exponential_decay(&x, &decaying).unwrap();
+        assert!((decay[0] - 2.5).abs() < 1e-9 && (decay[1] - 0.4).abs() < 1e-9);
+
+        let peaked: Vec<f64> = x
+            .iter()
+            .map(|&xi| 3.0_f64.mul_add((-((xi - 6.0) / 0.8).powi(2)).exp(), 0.5))
+            .collect();
+        let peak = gaussian_with_baseline(&x, &peaked).unwrap();
+        assert!((peak[0] - 3.0).abs() < 0.05);
+        assert!((peak[1] - 6.0).abs() < 0.05);
+        assert!((peak[2] - 0.8).abs() < 0.05);
+        assert!((peak[3] - 0.5).abs() < 0.05);
+
+        let oscillating: Vec<f64> = x
+            .iter()
+            .map(|&xi| 2.0_f64.mul_add(3.0_f64.mul_add(xi, 0.5).sin(), 1.0))
+            .collect();
+        let wave = sine_with_offset(&x, &oscillating).unwrap();
+        assert!((wave[1] - 3.0).abs() < 0.15, "{wave:?}");
+        assert!((wave[3] - 1.0).abs() < 0.1);
+
+        let saturating: Vec<f64> = x
+            .iter()
+            .map(|&xi| 4.0 / (1.0 + (-1.5 * (xi - 5.0)).exp()))
+            .collect();
+        let curve = logistic(&x, &saturating).unwrap();
+        assert!((curve[0] - 4.0).abs() < 0.05);
+        assert!((curve[1] - 1.5).abs() < 0.3);
+        assert!((curve[2] - 5.0).abs() < 0.05);
+    }
+}
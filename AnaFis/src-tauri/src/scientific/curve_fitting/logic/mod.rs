@@ -1,13 +1,18 @@
+pub mod bootstrap;
 pub mod cache;
+pub mod calibration;
 pub mod constants;
+pub mod dimensions;
 pub mod dof_logic;
 /// Core numerical ODR engine modules.
 pub mod engine;
 pub mod fit_metrics;
 pub mod fit_notes;
+pub mod initial_guess;
+pub mod multi_start;
 pub mod orchestrator;
 pub mod response_builder;
 pub mod sanitization;
-pub use orchestrator::run_fit_request;
+pub use orchestrator::{run_fit_request, run_fit_request_with_progress};
 
 pub use super::types::*;
@@ -0,0 +1,280 @@
+//! Multi-start driver for ODR fits on rugged chi-squared surfaces.
+//!
+//! Starting vectors are drawn from a seeded Latin hypercube over user ranges and
+//! solved in parallel against the same compiled models. Only the best
+//! `EvaluationState` survives the reduction; every start is reported in a summary.
+
+use rand::{RngCore, SeedableRng};
+use rand_pcg::Pcg64;
+use rayon::prelude::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::engine::{
+    CompiledModel, EvaluationState, MAX_MULTI_STARTS, OdrTerminationReason, PreparedData, solve_odr,
+};
+use crate::scientific::curve_fitting::types::{
+    MultiStartConfig, OdrError, OdrResult, StartSummary,
+};
+use crate::utils::progress::ProgressReporter;
+
+/// Solver settings shared by every start.
+#[derive(Debug, Clone, Copy)]
+pub struct SolverSettings {
+    /// Iteration cap per start.
+    pub max_iterations: usize,
+    /// Convergence tolerance.
+    pub tolerance: f64,
+    /// Initial Levenberg-Marquardt damping.
+    pub initial_damping: f64,
+}
+
+/// Best fit of a multi-start run.
+pub struct MultiStartOutcome {
+    /// Optimized parameters of the lowest-chi-squared start.
+    pub parameters: Vec<f64>,
+    /// Final evaluation state of that start.
+    pub state: EvaluationState,
+    /// Iterations performed by that start.
+    pub iterations: usize,
+    /// Why that start stopped.
+    pub termination_reason: OdrTerminationReason,
+    /// Outcome of every start, in sampling order.
+    pub summaries: Vec<StartSummary>,
+}
+
+type Solved = (Vec<f64>, EvaluationState, usize, OdrTerminationReason);
+
+/// Validates a multi-start configuration against the parameter count.
+///
+/// # Errors
+/// Returns `OdrError::Validation` for an out-of-range start count or malformed ranges.
+pub fn validate_multi_start(config: &MultiStartConfig, parameter_count: usize) -> OdrResult<()> {
+    if config.n_starts == 0 || config.n_starts > MAX_MULTI_STARTS {
+        return Err(OdrError::Validation(format!(
+            "multiStart.nStarts must be between 1 and {MAX_MULTI_STARTS}, got {}",
+            config.n_starts
+        )));
+    }
+    if config.parameter_ranges.len() != parameter_count {
+        return Err(OdrError::Validation(format!(
+            "multiStart.parameterRanges length mismatch: expected {parameter_count}, got {}",
+            config.parameter_ranges.len()
+        )));
+    }
+    for (idx, &(low, high)) in config.parameter_ranges.iter().enumerate() {
+        if !low.is_finite() || !high.is_finite() || low > high {
+            return Err(OdrError::Validation(format!(
+                "multiStart.parameterRanges[{idx}] must be finite with min <= max"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Draws `count` points from a Latin hypercube over `ranges`: each axis is cut into
+/// `count` equal strata, each stratum is used exactly once, and strata are paired
+/// across axes by independent shuffles.
+#[must_use]
+pub fn latin_hypercube(ranges: &[(f64, f64)], count: usize, seed: u64) -> Vec<Vec<f64>> {
+    let mut rng = Pcg64::seed_from_u64(seed);
+    let mut samples = vec![Vec::with_capacity(ranges.len()); count];
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Start counts are bounded by MAX_MULTI_STARTS"
+    )]
+    let strata = count as f64;
+    for &(low, high) in ranges {
+        let mut order: Vec<usize> = (0..count).collect();
+        for idx in (1..count).rev() {
+            #[allow(
+                clippy::cast_possible_truncation,
+                reason = "Remainder is below idx + 1, which fits in usize"
+            )]
+            let swap = (rng.next_u64() % (idx as u64 + 1)) as usize;
+            order.swap(idx, swap);
+        }
+        for (sample, stratum) in samples.iter_mut().zip(order) {
+            #[allow(
+                clippy::cast_precision_loss,
+                reason = "Stratum index and 53-bit mantissa draw are exact in f64"
+            )]
+            let position = (stratum as f64 + unit_interval(&mut rng)) / strata;
+            sample.push((high - low).mul_add(position, low));
+        }
+    }
+    samples
+}
+
+/// Runs `solve_odr` from every sampled start in parallel and keeps the start with
+/// the lowest profiled chi-squared. Starts that fail numerically are recorded in
+/// the summary without aborting the run. With a `progress` reporter, each
+/// finished start is reported as one iteration out of the start count, with
+/// its chi-squared. Once the computation is cancelled, the starts not begun
+/// yet are skipped and the best finished start is returned as cancelled.
+///
+/// # Errors
+/// Returns `OdrError::Numerical` if every start fails, or
+/// `OdrError::Cancelled` if cancelled before any start finished.
+pub fn run_multi_start(
+    models: &[Arc<CompiledModel>],
+    data: &PreparedData,
+    global_parameter_names: &[String],
+    config: &MultiStartConfig,
+    settings: SolverSettings,
+    progress: Option<&ProgressReporter>,
+) -> OdrResult<MultiStartOutcome> {
+    let starts = latin_hypercube(&config.parameter_ranges, config.n_starts, config.seed);
+    let finished = AtomicUsize::new(0);
+
+    let (best, mut indexed_summaries) = starts
+        .into_par_iter()
+        .enumerate()
+        .map(|(index, start)| {
+            if progress.is_some_and(ProgressReporter::is_cancelled) {
+                let summary = StartSummary {
+                    initial_parameters: start,
+                    chi_squared: None,
+                    iterations: 0,
+                    converged: false,
+                    error: Some("Cancelled before this start".to_owned()),
+                };
+                return (None, vec![(index, summary)]);
+            }
+            let solved = solve_odr(
+                models,
+                data,
+                start.clone(),
+                global_parameter_names,
+                settings.max_iterations,
+                settings.tolerance,
+                settings.initial_damping,
+                None,
+            );
+            if let Some(progress) = progress {
+                let done = finished.fetch_add(1, Ordering::Relaxed) + 1;
+                let chi_squared = solved.as_ref().ok().map(|s| s.1.chi_squared);
+                progress.report(done, config.n_starts, chi_squared);
+            }
+            let summary = match &solved {
+                Ok((_, state, iterations, reason)) => StartSummary {
+                    initial_parameters: start,
+                    chi_squared: Some(state.chi_squared),
+                    iterations: *iterations,
+                    converged: reason.is_converged(),
+                    error: None,
+                },
+                Err(error) => StartSummary {
+                    initial_parameters: start,
+                    chi_squared: None,
+                    iterations: 0,
+                    converged: false,
+                    error: Some(error.to_string()),
+                },
+            };
+            (
+                solved.ok().filter(|s| s.1.chi_squared.is_finite()),
+                vec![(index, summary)],
+            )
+        })
+        .reduce(
+            || (None, Vec::new()),
+            |(left_best, mut left_summaries), (right_best, right_summaries)| {
+                left_summaries.extend(right_summaries);
+                (better(left_best, right_best), left_summaries)
+            },
+        );
+
+    indexed_summaries.sort_by_key(|(index, _)| *index);
+    let summaries: Vec<StartSummary> = indexed_summaries
+        .into_iter()
+        .map(|(_, summary)| summary)
+        .collect();
+
+    if progress.is_some_and(ProgressReporter::is_cancelled) {
+        let Some((parameters, state, iterations, _)) = best else {
+            return Err(OdrError::Cancelled {
+                parameters: Vec::new(),
+                iterations: 0,
+            });
+        };
+        return Ok(MultiStartOutcome {
+            parameters,
+            state,
+            iterations,
+            termination_reason: OdrTerminationReason::Cancelled,
+            summaries,
+        });
+    }
+
+    let (parameters, state, iterations, termination_reason) = best.ok_or_else(|| {
+        let first_error = summaries
+            .iter()
+            .find_map(|summary| summary.error.clone())
+            .unwrap_or_else(|| "non-finite chi-squared".to_owned());
+        OdrError::Numerical(format!(
+            "all {} multi-start runs failed; first error: {first_error}",
+            summaries.len()
+        ))
+    })?;
+
+    Ok(MultiStartOutcome {
+        parameters,
+        state,
+        iterations,
+        termination_reason,
+        summaries,
+    })
+}
+
+fn better(left: Option<Solved>, right: Option<Solved>) -> Option<Solved> {
+    match (left, right) {
+        (Some(l), Some(r)) => Some(if r.1.chi_squared < l.1.chi_squared {
+            r
+        } else {
+            l
+        }),
+        (l, r) => l.or(r),
+    }
+}
+
+fn unit_interval(rng: &mut Pcg64) -> f64 {
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "53 random bits are exactly representable in f64"
+    )]
+    let mantissa = (rng.next_u64() >> 11) as f64;
+    mantissa * f64::EPSILON / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latin_hypercube_strata() {
+        let ranges = [(0.0, 1.0), (-5.0, 5.0)];
+        let samples = latin_hypercube(&ranges, 8, 7);
+        assert_eq!(samples, latin_hypercube(&ranges, 8, 7));
+        assert_ne!(samples, latin_hypercube(&ranges, 8, 8));
+
+        for (axis, &(low, high)) in ranges.iter().enumerate() {
+            let mut strata: Vec<usize> = samples
+                .iter()
+                .map(|sample| {
+                    let position = (sample[axis] - low) / (high - low);
+                    assert!((0.0..1.0).contains(&position));
+                    #[allow(
+                        clippy::cast_possible_truncation,
+                        clippy::cast_sign_loss,
+                        reason = "Position is in [0, 1)"
+                    )]
+                    let stratum = (position * 8.0) as usize;
+                    stratum
+                })
+                .collect();
+            strata.sort_unstable();
+            assert_eq!(strata, (0..8).collect::<Vec<_>>());
+        }
+    }
+}
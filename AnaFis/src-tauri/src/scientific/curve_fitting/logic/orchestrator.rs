@@ -1,9 +1,20 @@
+use std::sync::Arc;
+
+use super::bootstrap::{bootstrap_note, run_bootstrap, validate_bootstrap};
 use super::engine::{
-    DEFAULT_DAMPING, DEFAULT_MAX_ITERATIONS, DEFAULT_TOLERANCE, get_or_compile_model,
-    normalize_identifiers, prepare_data, solve_odr, validate_identifier, validate_symbol_sets,
+    CompiledModel, DEFAULT_DAMPING, DEFAULT_MAX_ITERATIONS, DEFAULT_TOLERANCE, EvaluationState,
+    OdrTerminationReason, PreparedData, evaluate_model, get_or_compile_model,
+    has_independent_uncertainty, is_linear_in_parameters, normalize_identifiers, prepare_data,
+    solve_linear_parameters, solve_odr, validate_identifier, validate_symbol_sets,
+    verify_model_gradients,
 };
+use super::initial_guess::build_initial_guess;
+use super::multi_start::{SolverSettings, run_multi_start, validate_multi_start};
 use super::response_builder::build_response;
-use crate::scientific::curve_fitting::types::{OdrError, OdrFitRequest, OdrFitResponse, OdrResult};
+use crate::scientific::curve_fitting::types::{
+    OdrError, OdrFitRequest, OdrFitResponse, OdrResult, StartSummary,
+};
+use crate::utils::progress::ProgressReporter;
 
 /// Orchestrates the execution of a curve-fitting request.
 ///
@@ -12,8 +23,31 @@ use crate::scientific::curve_fitting::types::{OdrError, OdrFitRequest, OdrFitRes
 /// # Errors
 /// Returns `OdrError` if validation fails or the solver encounters a numerical issue.
 pub fn run_fit_request(request: &OdrFitRequest) -> OdrResult<OdrFitResponse> {
+    run_fit_request_with_progress(request, None)
+}
+
+/// Runs a curve-fitting request like [`run_fit_request`], reporting the
+/// solver iterations to `progress` and the final state once solved.
+///
+/// # Errors
+/// Returns `OdrError` if validation fails or the solver encounters a numerical issue.
+#[allow(
+    clippy::too_many_lines,
+    reason = "Validation, compilation and solver dispatch are kept in one sequential pass"
+)]
+pub fn run_fit_request_with_progress(
+    request: &OdrFitRequest,
+    progress: Option<&ProgressReporter>,
+) -> OdrResult<OdrFitResponse> {
     // Future extension point: route by solver mode (profiled vs. simultaneous augmented-state)
     // once a full ODRPACK-style backend is introduced.
+    if request.verify_gradients && request.numerical_gradients {
+        return Err(OdrError::Validation(
+            "verifyGradients checks symbolic derivatives and cannot be combined with numericalGradients"
+                .to_owned(),
+        ));
+    }
+
     let prepared = prepare_data(request)?;
     let normalized_parameter_names = normalize_identifiers(&request.parameter_names, "parameter")?;
 
@@ -32,30 +66,32 @@ pub fn run_fit_request(request: &OdrFitRequest) -> OdrResult<OdrFitResponse> {
             &normalized_dependent,
             &normalized_independent,
             &normalized_parameter_names,
+            request.numerical_gradients,
         )?;
         compiled_models.push(compiled);
     }
 
-    let parameter_count = normalized_parameter_names.len();
-    let initial_guess = if let Some(initial) = &request.initial_guess {
-        if initial.len() != parameter_count {
-            return Err(OdrError::Validation(format!(
-                "Initial guess length mismatch: expected {}, got {}",
-                parameter_count,
-                initial.len()
-            )));
-        }
-        for (idx, value) in initial.iter().enumerate() {
-            if !value.is_finite() {
-                return Err(OdrError::Validation(format!(
-                    "Initial guess contains non-finite value at {idx}"
-                )));
-            }
-        }
-        initial.clone()
-    } else {
-        vec![1.0; parameter_count]
-    };
+    if let Some(config) = &request.multi_start {
+        validate_multi_start(config, normalized_parameter_names.len())?;
+    }
+    if let Some(config) = &request.bootstrap {
+        validate_bootstrap(config)?;
+    }
+    let (initial_guess, mut guess_heuristic) = resolve_initial_guess(
+        request,
+        &compiled_models,
+        &prepared,
+        &normalized_parameter_names,
+    )?;
+
+    if request.verify_gradients {
+        verify_model_gradients(
+            &compiled_models,
+            &prepared,
+            &initial_guess,
+            &normalized_parameter_names,
+        )?;
+    }
 
     let max_iterations = request
         .max_iterations
@@ -66,20 +102,70 @@ pub fn run_fit_request(request: &OdrFitRequest) -> OdrResult<OdrFitResponse> {
         .confidence_level
         .unwrap_or(0.95)
         .clamp(0.5, 0.999_999);
-    let tolerance = request.tolerance.unwrap_or(DEFAULT_TOLERANCE);
-    let initial_damping = request.initial_damping.unwrap_or(DEFAULT_DAMPING);
 
-    let (params, final_state, iterations, termination_reason) = solve_odr(
-        &compiled_models,
-        &prepared,
-        initial_guess,
-        &normalized_parameter_names,
+    let settings = SolverSettings {
         max_iterations,
-        tolerance,
-        initial_damping,
-    )?;
+        tolerance: request.tolerance.unwrap_or(DEFAULT_TOLERANCE),
+        initial_damping: request.initial_damping.unwrap_or(DEFAULT_DAMPING),
+    };
+
+    let (params, final_state, iterations, termination_reason, starts_summary) =
+        if let Some(config) = &request.multi_start {
+            let outcome = run_multi_start(
+                &compiled_models,
+                &prepared,
+                &normalized_parameter_names,
+                config,
+                settings,
+                progress,
+            )?;
+            (
+                outcome.parameters,
+                outcome.state,
+                outcome.iterations,
+                outcome.termination_reason,
+                Some(outcome.summaries),
+            )
+        } else {
+            let (params, final_state, iterations, termination_reason) = solve_single_start(
+                request,
+                &compiled_models,
+                &prepared,
+                initial_guess,
+                &normalized_parameter_names,
+                settings,
+                &mut guess_heuristic,
+                progress,
+            )?;
+            (params, final_state, iterations, termination_reason, None)
+        };
+    if let Some(progress) = progress {
+        let (done, limit) = request
+            .multi_start
+            .as_ref()
+            .map_or((iterations, max_iterations), |config| {
+                (config.n_starts, config.n_starts)
+            });
+        progress.finish(done, limit, Some(final_state.chi_squared));
+    }
+    let bootstrap = match &request.bootstrap {
+        Some(config) if termination_reason != OdrTerminationReason::Cancelled => {
+            Some(run_bootstrap(
+                &compiled_models,
+                &prepared,
+                &params,
+                &final_state,
+                &normalized_parameter_names,
+                config,
+                settings,
+                confidence_level,
+                progress,
+            )?)
+        }
+        _ => None,
+    };
 
-    Ok(build_response(
+    let mut response = build_response(
         &compiled_models,
         &prepared,
         params,
@@ -87,5 +173,146 @@ pub fn run_fit_request(request: &OdrFitRequest) -> OdrResult<OdrFitResponse> {
         iterations,
         termination_reason,
         confidence_level,
-    ))
+    );
+    let mut notes = leading_notes(
+        request,
+        guess_heuristic,
+        starts_summary.as_deref(),
+        termination_reason,
+    );
+    notes.extend(bootstrap.as_ref().map(bootstrap_note));
+    notes.extend(response.message.take());
+    response.message = Some(notes.join(" | "));
+    response.starts_summary = starts_summary;
+    response.bootstrap = bootstrap;
+
+    Ok(response)
+}
+
+/// Runs the solver from one start. Parameter-linear models take the analytic
+/// fast path when allowed: solved outright with uncertainties only on the
+/// dependent variables, otherwise used as the starting point in place of the
+/// heuristic guess (an explicit guess is kept). A cancelled solve is
+/// reported at the parameters it stopped on.
+#[allow(
+    clippy::too_many_arguments,
+    reason = "Start state and solver settings are threaded separately from the request"
+)]
+fn solve_single_start(
+    request: &OdrFitRequest,
+    models: &[Arc<CompiledModel>],
+    prepared: &PreparedData,
+    initial_guess: Vec<f64>,
+    parameter_names: &[String],
+    settings: SolverSettings,
+    guess_heuristic: &mut Option<&'static str>,
+    progress: Option<&ProgressReporter>,
+) -> OdrResult<(Vec<f64>, EvaluationState, usize, OdrTerminationReason)> {
+    let analytic = if request.allow_fast_path && is_linear_in_parameters(models) {
+        solve_linear_parameters(models, prepared, &initial_guess, parameter_names)?
+    } else {
+        None
+    };
+    let start = match analytic {
+        Some((params, final_state)) if !has_independent_uncertainty(models, prepared) => {
+            *guess_heuristic = None;
+            return Ok((params, final_state, 1, OdrTerminationReason::LinearSolution));
+        }
+        Some((params, _)) if request.initial_guess.is_none() => {
+            *guess_heuristic = Some("weighted linear least squares");
+            params
+        }
+        _ => initial_guess,
+    };
+    match solve_odr(
+        models,
+        prepared,
+        start,
+        parameter_names,
+        settings.max_iterations,
+        settings.tolerance,
+        settings.initial_damping,
+        progress,
+    ) {
+        Err(OdrError::Cancelled {
+            parameters,
+            iterations,
+        }) => {
+            let state = evaluate_model(models, prepared, &parameters, parameter_names)?;
+            Ok((
+                parameters,
+                state,
+                iterations,
+                OdrTerminationReason::Cancelled,
+            ))
+        }
+        solved => solved,
+    }
+}
+
+/// Returns the explicit initial guess after validation, or a heuristic guess with
+/// its description when the request carries none.
+fn resolve_initial_guess(
+    request: &OdrFitRequest,
+    models: &[Arc<CompiledModel>],
+    prepared: &PreparedData,
+    parameter_names: &[String],
+) -> OdrResult<(Vec<f64>, Option<&'static str>)> {
+    let Some(initial) = &request.initial_guess else {
+        let guess = build_initial_guess(models, prepared, parameter_names);
+        return Ok((guess.values, guess.heuristic));
+    };
+    if initial.len() != parameter_names.len() {
+        return Err(OdrError::Validation(format!(
+            "Initial guess length mismatch: expected {}, got {}",
+            parameter_names.len(),
+            initial.len()
+        )));
+    }
+    for (idx, value) in initial.iter().enumerate() {
+        if !value.is_finite() {
+            return Err(OdrError::Validation(format!(
+                "Initial guess contains non-finite value at {idx}"
+            )));
+        }
+    }
+    Ok((initial.clone(), None))
+}
+
+/// Notes that precede the solver warnings in the response message: gradient
+/// mode, then the multi-start summary, the initial-guess heuristic or the
+/// linear fast path.
+fn leading_notes(
+    request: &OdrFitRequest,
+    guess_heuristic: Option<&'static str>,
+    starts_summary: Option<&[StartSummary]>,
+    termination_reason: OdrTerminationReason,
+) -> Vec<String> {
+    let gradient_note = if request.numerical_gradients {
+        "Gradients: central finite differences"
+    } else if request.verify_gradients {
+        "Gradients: symbolic, verified against finite differences at the initial guess"
+    } else {
+        "Gradients: symbolic"
+    };
+    let mut notes = vec![gradient_note.to_owned()];
+    if let Some(summaries) = starts_summary {
+        let converged = summaries.iter().filter(|start| start.converged).count();
+        let failed = summaries
+            .iter()
+            .filter(|start| start.error.is_some())
+            .count();
+        notes.push(format!(
+            "Multi-start: best of {} starts ({converged} converged, {failed} failed)",
+            summaries.len()
+        ));
+    } else if let Some(heuristic) = guess_heuristic {
+        notes.push(format!("Initial guess: {heuristic}"));
+    } else if termination_reason == OdrTerminationReason::LinearSolution {
+        notes.push(
+            "Solver: model is linear in the parameters, solved directly by weighted least squares"
+                .to_owned(),
+        );
+    }
+    notes
 }
@@ -167,12 +167,7 @@ pub fn build_response(
         .expect("models should have at least one layer for response building");
 
     OdrFitResponse {
-        success: matches!(
-            termination_reason,
-            OdrTerminationReason::ScaledGradient
-                | OdrTerminationReason::ScaledStep
-                | OdrTerminationReason::Improvement
-        ),
+        success: termination_reason.is_converged(),
         termination_reason: termination_reason_label(termination_reason).to_owned(),
         message: if warnings.is_empty() {
             None
@@ -210,6 +205,8 @@ pub fn build_response(
         welch_satterthwaite_dof: ws_dof,
         coverage_degrees_of_freedom: coverage_dof,
         assumptions,
+        starts_summary: None,
+        bootstrap: None,
     }
 }
 
@@ -222,5 +219,7 @@ const fn termination_reason_label(reason: OdrTerminationReason) -> &'static str
         OdrTerminationReason::Singular => "singular",
         OdrTerminationReason::DampingSaturated => "dampingSaturated",
         OdrTerminationReason::MaxIterations => "maxIterations",
+        OdrTerminationReason::LinearSolution => "linearSolution",
+        OdrTerminationReason::Cancelled => "cancelled",
     }
 }
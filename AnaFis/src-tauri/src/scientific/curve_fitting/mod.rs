@@ -6,10 +6,18 @@ mod logic;
 mod tests;
 mod types;
 
+pub use logic::dimensions::verify_dimensions;
 pub use logic::run_fit_request;
 
-pub use commands::{evaluate_model_curve, evaluate_model_grid, fit_custom_odr};
+pub use commands::{
+    analyze_formula_dimensions, calibration_analysis, evaluate_formula, evaluate_model_curve,
+    evaluate_model_grid, fit_custom_odr, get_model_cache_stats,
+};
 pub use types::{
-    CurveEvaluationRequest, CurveEvaluationResponse, GridEvaluationRequest, GridEvaluationResponse,
-    ModelLayer, OdrError, OdrFitRequest, OdrFitResponse, OdrResult, VariableInput,
+    CalibrationModel, CalibrationRequest, CalibrationResponse, CalibrationSample,
+    CurveEvaluationRequest, CurveEvaluationResponse, DimensionConflict, FormulaDimension,
+    FormulaDimensionRequest, FormulaDimensionResponse, FormulaEvaluationRequest,
+    FormulaEvaluationResponse, FormulaGrid, GridEvaluationRequest, GridEvaluationResponse,
+    InversePrediction, ModelLayer, OdrError, OdrFitRequest, OdrFitResponse, OdrResult,
+    VariableInput,
 };
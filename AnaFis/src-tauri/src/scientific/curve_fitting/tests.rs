@@ -6,12 +6,46 @@
     clippy::shadow_unrelated,
     reason = "Test code uses unwrap/panic/print for diagnostics and sequential shadowing for state progression"
 )]
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde_json::{Value, json};
+use symb_anafis::parse;
+use tauri::async_runtime::block_on;
+
+use crate::error::{CommandError, CommandResult, codes};
 use crate::scientific::curve_fitting::commands::{
-    evaluate_model_curve, evaluate_model_grid, fit_custom_odr,
+    self, analyze_formula_dimensions, evaluate_formula, evaluate_model_curve, evaluate_model_grid,
+};
+use crate::scientific::curve_fitting::logic::dimensions::verify_dimensions;
+use crate::scientific::curve_fitting::logic::engine::gradient_check::layer_gradient_discrepancies;
+use crate::scientific::curve_fitting::logic::engine::{
+    get_or_compile_model, is_linear_in_parameters, model_cache_stats,
 };
+use crate::scientific::curve_fitting::logic::{run_fit_request, run_fit_request_with_progress};
 use crate::scientific::curve_fitting::types::{
-    CurveEvaluationRequest, GridEvaluationRequest, ModelLayer, OdrFitRequest, VariableInput,
+    BootstrapConfig, BootstrapMethod, CalibrationModel, CalibrationRequest, CalibrationResponse,
+    CalibrationSample, CurveEvaluationRequest, DetectionNoiseSource, FormulaDimensionRequest,
+    FormulaEvaluationRequest, FormulaGrid, GridEvaluationRequest, ModelLayer, MultiStartConfig,
+    OdrFitRequest, OdrFitResponse, ParameterDimensionStatus, VariableInput, VariableUnit,
 };
+use crate::unit_conversion::core::Dimension;
+use crate::utils::progress::ProgressReporter;
+
+/// Fit as the `fit_custom_odr` command does, without progress events.
+#[allow(
+    clippy::needless_pass_by_value,
+    reason = "Takes the request by value like the command"
+)]
+fn fit_custom_odr(request: OdrFitRequest) -> CommandResult<OdrFitResponse> {
+    run_fit_request(&request).map_err(CommandError::from)
+}
+
+/// Run the `calibration_analysis` command to completion.
+fn calibration_analysis(request: CalibrationRequest) -> CommandResult<CalibrationResponse> {
+    block_on(commands::calibration_analysis(request))
+}
 
 fn repeat_corr(point_count: usize, matrix: &[Vec<f64>]) -> Vec<Vec<Vec<f64>>> {
     (0..point_count).map(|_| matrix.to_vec()).collect()
@@ -50,6 +84,12 @@ fn test_fit_custom_odr_linear_model_no_correlation() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let result = fit_custom_odr(request).unwrap();
@@ -90,6 +130,12 @@ fn test_fit_custom_odr_identifier_normalization_case_insensitive() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let result = fit_custom_odr(request).unwrap();
@@ -136,6 +182,12 @@ fn test_fit_custom_odr_handles_ill_conditioned_parameter_scaling() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let result = fit_custom_odr(request).unwrap();
@@ -206,6 +258,12 @@ fn test_fit_custom_odr_with_independent_correlations() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let result = fit_custom_odr(request).unwrap();
@@ -250,6 +308,12 @@ fn test_fit_custom_odr_with_cross_xy_correlation() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let result = fit_custom_odr(request).unwrap();
@@ -292,6 +356,12 @@ fn test_fit_custom_odr_zero_uncertainty_clamp() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let result = fit_custom_odr(request).unwrap();
@@ -340,21 +410,26 @@ fn test_fit_custom_odr_invalid_correlation_shape() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let err = fit_custom_odr(request).unwrap_err();
-    assert!(err.contains("invalid shape"));
+    assert!(err.detail().contains("invalid shape"));
 }
 
-#[test]
-fn test_fit_custom_odr_nonlinear_gaussian_like() {
+fn gaussian_like_request() -> OdrFitRequest {
     let x: Vec<f64> = (-40..=40).map(|i| f64::from(i) * 0.05).collect();
     let y: Vec<f64> = x
         .iter()
         .map(|&xi| 2.0_f64.mul_add((-0.7 * xi * xi).exp(), 0.5))
         .collect();
 
-    let request = OdrFitRequest {
+    OdrFitRequest {
         layers: vec![ModelLayer {
             formula: "a*exp(-b*x^2)+c".to_owned(),
             dependent_variable: "y".to_owned(),
@@ -382,13 +457,226 @@ fn test_fit_custom_odr_nonlinear_gaussian_like() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
-    };
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
+    }
+}
+
+#[test]
+fn test_fit_custom_odr_nonlinear_gaussian_like() {
+    let result = fit_custom_odr(gaussian_like_request()).unwrap();
+    assert!(result.success);
+    assert!((result.parameter_values[0] - 2.0).abs() < 1e-3);
+    assert!((result.parameter_values[1] - 0.7).abs() < 1e-3);
+    assert!((result.parameter_values[2] - 0.5).abs() < 1e-3);
+    assert!(
+        result
+            .message
+            .unwrap_or_default()
+            .starts_with("Gradients: symbolic")
+    );
+}
+
+#[test]
+fn test_fit_reports_progress_when_requested() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink_events = Arc::clone(&events);
+    let reporter = ProgressReporter::new("gaussian".to_owned(), move |progress| {
+        sink_events.lock().unwrap().push(progress.clone());
+    });
+    let result = run_fit_request_with_progress(&gaussian_like_request(), Some(&reporter)).unwrap();
+
+    let events = events.lock().unwrap().clone();
+    let last = events.last().unwrap();
+    assert_eq!(last.id, "gaussian");
+    assert_eq!(last.iteration, result.iterations);
+    assert_eq!(last.max_iterations, 600);
+    assert!(
+        (last.chi_squared.unwrap() - result.chi_squared).abs()
+            <= 1e-9 * result.chi_squared.max(1.0)
+    );
+    assert!(
+        events
+            .windows(2)
+            .all(|pair| pair[0].iteration <= pair[1].iteration)
+    );
+    assert!(events.iter().all(|event| event.max_iterations == 600));
+}
+
+#[test]
+fn test_cancelled_fit_reports_estimate_without_success() {
+    let cancelled = Arc::new(AtomicBool::new(true));
+    let reporter = ProgressReporter::new("gaussian".to_owned(), |_| {})
+        .with_cancellation(Arc::clone(&cancelled));
+    let result = run_fit_request_with_progress(&gaussian_like_request(), Some(&reporter)).unwrap();
+    assert!(!result.success);
+    assert_eq!(result.termination_reason, "cancelled");
+    assert_eq!(result.iterations, 0);
+    assert!(result.message.unwrap_or_default().contains("cancelled"));
+
+    cancelled.store(false, Ordering::Relaxed);
+    let result = run_fit_request_with_progress(&gaussian_like_request(), Some(&reporter)).unwrap();
+    assert!(result.success);
+}
+
+#[test]
+fn test_bootstrap_width_interval_is_asymmetric_for_sparse_gaussian() {
+    let mut request = gaussian_like_request();
+    for variable in request
+        .independent_variables
+        .iter_mut()
+        .chain(&mut request.dependent_variables)
+    {
+        variable.values = variable.values.iter().step_by(8).copied().collect();
+    }
+    request.independent_variables[0].uncertainties = None;
+    request.dependent_variables[0].uncertainties = Some(vec![0.25; 11]);
+    request.bootstrap = Some(BootstrapConfig {
+        n_resamples: 400,
+        method: BootstrapMethod::Parametric,
+        seed: 3,
+    });
+
+    let result = fit_custom_odr(request).unwrap();
+    assert!(result.success);
+    let bootstrap = result.bootstrap.unwrap();
+    assert_eq!(bootstrap.n_resamples, 400);
+    assert!(bootstrap.converged_resamples >= 380);
+    assert_eq!(
+        bootstrap.converged_resamples + bootstrap.failed_resamples,
+        400
+    );
+    let width = result.parameter_values[1];
+    let below = width - bootstrap.parameter_ci_lower[1];
+    let above = bootstrap.parameter_ci_upper[1] - width;
+    assert!(below > 0.0 && above > 0.0);
+    assert!(above > 1.2 * below);
+    assert!(result.message.unwrap_or_default().contains("Bootstrap:"));
+}
+
+#[test]
+fn test_bootstrap_linear_fit_matches_analytic_uncertainties() {
+    let mut request = gaussian_like_request();
+    request.layers[0].formula = "a*x + c".to_owned();
+    request.parameter_names = vec!["a".to_owned(), "c".to_owned()];
+    request.initial_guess = None;
+    request.independent_variables[0].uncertainties = None;
+
+    for method in [
+        BootstrapMethod::Parametric,
+        BootstrapMethod::Residual,
+        BootstrapMethod::Pairs,
+    ] {
+        request.bootstrap = Some(BootstrapConfig {
+            n_resamples: 500,
+            method,
+            seed: 11,
+        });
+        let result = fit_custom_odr(request.clone()).unwrap();
+        let bootstrap = result.bootstrap.unwrap();
+        assert_eq!(bootstrap.converged_resamples, 500);
+        for (param, value) in result.parameter_values.iter().enumerate() {
+            assert!(bootstrap.parameter_ci_lower[param] < *value);
+            assert!(bootstrap.parameter_ci_upper[param] > *value);
+        }
+        if method == BootstrapMethod::Parametric {
+            for (spread, analytic) in bootstrap
+                .parameter_uncertainties
+                .iter()
+                .zip(&result.parameter_uncertainties_raw)
+            {
+                assert!((spread / analytic - 1.0).abs() < 0.1);
+            }
+        }
+    }
+
+    request.bootstrap = Some(BootstrapConfig {
+        n_resamples: 5,
+        method: BootstrapMethod::Pairs,
+        seed: 0,
+    });
+    assert!(fit_custom_odr(request).is_err());
+}
+
+#[test]
+fn test_fit_custom_odr_numerical_gradients_gaussian_like() {
+    let mut request = gaussian_like_request();
+    request.numerical_gradients = true;
 
     let result = fit_custom_odr(request).unwrap();
     assert!(result.success);
     assert!((result.parameter_values[0] - 2.0).abs() < 1e-3);
     assert!((result.parameter_values[1] - 0.7).abs() < 1e-3);
     assert!((result.parameter_values[2] - 0.5).abs() < 1e-3);
+    assert!(
+        result
+            .message
+            .unwrap_or_default()
+            .contains("central finite differences")
+    );
+}
+
+#[test]
+fn test_fit_custom_odr_verified_gradients() {
+    let mut request = gaussian_like_request();
+    request.verify_gradients = true;
+
+    let result = fit_custom_odr(request).unwrap();
+    assert!(result.success);
+    assert!(result.message.unwrap_or_default().contains("verified"));
+
+    let mut request = gaussian_like_request();
+    request.verify_gradients = true;
+    request.numerical_gradients = true;
+    assert!(fit_custom_odr(request).is_err());
+}
+
+#[test]
+fn test_gradient_verification_catches_broken_derivative() {
+    let independent_names = vec!["x".to_owned()];
+    let parameter_names = vec!["a".to_owned(), "b".to_owned()];
+    let known: HashSet<String> = ["x", "a", "b"].iter().map(|s| (*s).to_owned()).collect();
+    let parse_expr = |text: &str| parse(text, &known, &HashSet::new(), None).unwrap();
+
+    let model = parse_expr("a*exp(-b*x^2)");
+    let independent_gradient = vec![parse_expr("-2*a*b*x*exp(-b*x^2)")];
+    let correct = vec![parse_expr("exp(-b*x^2)"), parse_expr("-a*x^2*exp(-b*x^2)")];
+    // Sign error in d/db.
+    let broken = vec![parse_expr("exp(-b*x^2)"), parse_expr("a*x^2*exp(-b*x^2)")];
+
+    let x: Vec<f64> = (-10..=10).map(|i| f64::from(i) * 0.2).collect();
+    let a = vec![2.0; x.len()];
+    let b = vec![0.7; x.len()];
+    let columns: Vec<&[f64]> = vec![&x, &a, &b];
+
+    let clean = layer_gradient_discrepancies(
+        &model,
+        &independent_gradient,
+        &correct,
+        &independent_names,
+        &parameter_names,
+        &columns,
+        0,
+    )
+    .unwrap();
+    assert!(clean.is_empty(), "{clean:?}");
+
+    let report = layer_gradient_discrepancies(
+        &model,
+        &independent_gradient,
+        &broken,
+        &independent_names,
+        &parameter_names,
+        &columns,
+        0,
+    )
+    .unwrap();
+    assert_eq!(report.len(), 1, "{report:?}");
+    assert!(report[0].contains("d/db"));
 }
 
 #[test]
@@ -464,6 +752,12 @@ fn test_fit_custom_odr_multivariable_full_covariance() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let result = fit_custom_odr(request).unwrap();
@@ -535,10 +829,20 @@ fn test_fit_custom_odr_rejects_non_psd_correlation_matrix() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let err = fit_custom_odr(request).unwrap_err();
-    assert!(err.to_lowercase().contains("positive semidefinite"));
+    assert!(
+        err.detail()
+            .to_lowercase()
+            .contains("positive semidefinite")
+    );
 }
 
 #[test]
@@ -554,7 +858,7 @@ fn test_evaluate_model_grid_rejects_too_high_resolution() {
     };
 
     let err = evaluate_model_grid(request).unwrap_err();
-    assert!(err.to_lowercase().contains("resolution too high"));
+    assert!(err.detail().to_lowercase().contains("resolution too high"));
 }
 
 #[test]
@@ -569,7 +873,80 @@ fn test_evaluate_model_curve_rejects_too_high_resolution() {
     };
 
     let err = evaluate_model_curve(request).unwrap_err();
-    assert!(err.to_lowercase().contains("resolution too high"));
+    assert!(err.detail().to_lowercase().contains("resolution too high"));
+}
+
+fn formula_request(
+    formula: &str,
+    fixed_values: &[(&str, f64)],
+    grid: FormulaGrid,
+) -> FormulaEvaluationRequest {
+    FormulaEvaluationRequest {
+        formula: formula.to_owned(),
+        fixed_values: fixed_values
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), value))
+            .collect(),
+        sweep_variable: "x".to_owned(),
+        grid,
+    }
+}
+
+#[test]
+fn test_evaluate_formula_reuses_model_cache() {
+    // Formula text unique to this test, so no other test compiles it first.
+    let request = || {
+        formula_request(
+            "Amp*sin(Omega*x) + 0.375",
+            &[("amp", 2.0), ("omega", 3.0), ("unused", 1.0)],
+            FormulaGrid::Points(vec![0.0, 0.5, 1.0]),
+        )
+    };
+
+    let before = model_cache_stats().unwrap();
+    let uncached = evaluate_formula(request()).unwrap();
+    let after_first = model_cache_stats().unwrap();
+    assert!(after_first.misses > before.misses);
+    for (x, y) in uncached.x.iter().zip(&uncached.y) {
+        assert!((2.0_f64.mul_add((3.0 * x).sin(), 0.375) - y).abs() < 1e-12);
+    }
+    assert!(uncached.non_finite_indices.is_empty());
+
+    let cached = evaluate_formula(request()).unwrap();
+    let after_second = model_cache_stats().unwrap();
+    assert!(after_second.hits > after_first.hits);
+    assert_eq!(cached.y, uncached.y);
+    assert!(after_second.entries <= after_second.capacity);
+}
+
+#[test]
+fn test_evaluate_formula_reports_non_finite_points() {
+    let response = evaluate_formula(formula_request(
+        "1/x",
+        &[],
+        FormulaGrid::Range {
+            start: -1.0,
+            stop: 1.0,
+            n: 5,
+        },
+    ))
+    .unwrap();
+    assert_eq!(response.x, vec![-1.0, -0.5, 0.0, 0.5, 1.0]);
+    assert_eq!(response.non_finite_indices, vec![2]);
+    assert!((response.y[1] + 2.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_evaluate_formula_lists_unknown_symbols() {
+    let err = evaluate_formula(formula_request(
+        "a*x^2 + b*x + c + pi",
+        &[("a", 1.0)],
+        FormulaGrid::Points(vec![1.0]),
+    ))
+    .unwrap_err();
+    assert_eq!(err.code(), codes::UNKNOWN_SYMBOLS);
+    assert!(err.detail().contains("b, c"), "{err}");
+    assert!(!err.detail().contains("pi"));
 }
 
 #[test]
@@ -622,6 +999,12 @@ fn test_fit_custom_odr_two_layers_shared_parameter() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let result = fit_custom_odr(request).unwrap();
@@ -664,6 +1047,12 @@ fn test_fit_custom_odr_poisson_weighting() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let result = fit_custom_odr(request).unwrap();
@@ -705,6 +1094,12 @@ fn test_fit_custom_odr_poisson_weighting_accepts_fractional_rebinned_counts() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let result = fit_custom_odr(request).unwrap();
@@ -745,6 +1140,12 @@ fn test_fit_custom_odr_preserves_negative_off_diagonal_covariance() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let result = fit_custom_odr(request).unwrap();
@@ -786,6 +1187,12 @@ fn test_fit_custom_odr_rank_deficient_sets_infinite_uncertainties() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let result = fit_custom_odr(request).unwrap();
@@ -838,6 +1245,12 @@ fn test_fit_custom_odr_reports_r_squared_odr_caveat() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let result = fit_custom_odr(request).unwrap();
@@ -882,6 +1295,12 @@ fn test_fit_custom_odr_reports_coverage_factor_and_expanded_uncertainty() {
         initial_damping: None,
         tolerance: None,
         confidence_level: Some(0.95),
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let result = fit_custom_odr(request).unwrap();
@@ -938,9 +1357,715 @@ fn test_fit_custom_odr_reports_rank_and_condition_number() {
         initial_damping: None,
         tolerance: None,
         confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
     };
 
     let result = fit_custom_odr(request).unwrap();
     assert!(result.effective_rank <= result.parameter_values.len());
     assert!(result.condition_number.is_finite() || result.condition_number.is_infinite());
 }
+
+fn shape_request(
+    formula: &str,
+    parameters: &[&str],
+    model: impl Fn(f64) -> f64,
+    initial_guess: Option<Vec<f64>>,
+) -> OdrFitRequest {
+    let x: Vec<f64> = (0..60).map(|i| f64::from(i) * 0.1).collect();
+    let y: Vec<f64> = x.iter().map(|&xi| model(xi)).collect();
+    let count = x.len();
+
+    OdrFitRequest {
+        layers: vec![ModelLayer {
+            formula: formula.to_owned(),
+            dependent_variable: "y".to_owned(),
+            independent_variables: vec!["x".to_owned()],
+        }],
+        independent_variables: vec![VariableInput {
+            name: "x".to_owned(),
+            values: x,
+            uncertainties: Some(vec![0.01; count]),
+            uncertainty_type: None,
+            uncertainty_degrees_of_freedom: None,
+        }],
+        dependent_variables: vec![VariableInput {
+            name: "y".to_owned(),
+            values: y,
+            uncertainties: Some(vec![0.02; count]),
+            uncertainty_type: None,
+            uncertainty_degrees_of_freedom: None,
+        }],
+        use_poisson_weighting: None,
+        parameter_names: parameters.iter().map(|name| (*name).to_owned()).collect(),
+        initial_guess,
+        max_iterations: Some(60),
+        point_correlations: None,
+        initial_damping: None,
+        tolerance: None,
+        confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path: true,
+        computation_id: None,
+        bootstrap: None,
+    }
+}
+
+fn recovers(parameter_values: &[f64], expected: &[f64], tolerance: f64) -> bool {
+    parameter_values
+        .iter()
+        .zip(expected)
+        .all(|(value, target)| (value - target).abs() < tolerance)
+}
+
+#[test]
+fn test_auto_initial_guess_gaussian_peak() {
+    let formula = "a*exp(-((x-b)/c)^2)";
+    let peak = |x: f64| 3.0 * (-((x - 5.0) / 0.5).powi(2)).exp();
+    let expected = [3.0, 5.0, 0.5];
+
+    // All-ones start with a tight iteration budget: it either fails numerically,
+    // stalls at the cap or settles elsewhere.
+    let from_ones = fit_custom_odr(OdrFitRequest {
+        max_iterations: Some(20),
+        ..shape_request(formula, &["a", "b", "c"], peak, Some(vec![1.0; 3]))
+    });
+    assert!(from_ones.map_or(true, |result| {
+        !result.success || !recovers(&result.parameter_values, &expected, 1e-3)
+    }));
+
+    let result = fit_custom_odr(shape_request(formula, &["a", "b", "c"], peak, None)).unwrap();
+    assert!(result.success);
+    assert!(
+        recovers(&result.parameter_values, &expected, 1e-3),
+        "{:?}",
+        result.parameter_values
+    );
+    assert!(
+        result
+            .message
+            .unwrap()
+            .contains("Initial guess: peak height, position and width (Gaussian)")
+    );
+}
+
+#[test]
+fn test_auto_initial_guess_sine_wave() {
+    let formula = "a*sin(b*x+c)+d";
+    let wave = |x: f64| 2.0_f64.mul_add(3.0_f64.mul_add(x, 0.5).sin(), 1.0);
+    let expected = [2.0, 3.0, 0.5, 1.0];
+
+    let from_ones = fit_custom_odr(OdrFitRequest {
+        max_iterations: Some(20),
+        ..shape_request(formula, &["a", "b", "c", "d"], wave, Some(vec![1.0; 4]))
+    });
+    assert!(from_ones.map_or(true, |result| {
+        !result.success || !recovers(&result.parameter_values, &expected, 1e-3)
+    }));
+
+    let result = fit_custom_odr(shape_request(formula, &["a", "b", "c", "d"], wave, None)).unwrap();
+    assert!(result.success);
+    assert!(
+        recovers(&result.parameter_values, &expected, 1e-3),
+        "{:?}",
+        result.parameter_values
+    );
+    assert!(
+        result
+            .message
+            .unwrap()
+            .contains("Initial guess: FFT dominant frequency (sinusoid)")
+    );
+}
+
+#[test]
+fn test_multi_start_double_gaussian_finds_global_minimum() {
+    let double_peak = |x: f64| {
+        2.0_f64.mul_add(
+            (-((x - 2.0) / 0.5).powi(2)).exp(),
+            1.2 * (-((x - 4.2) / 0.7).powi(2)).exp(),
+        )
+    };
+    let request = |multi_start: Option<MultiStartConfig>| {
+        let mut request = OdrFitRequest {
+            max_iterations: Some(60),
+            multi_start,
+            ..shape_request(
+                "a1*exp(-((x-b1)/c1)^2)+a2*exp(-((x-b2)/c2)^2)",
+                &["a1", "b1", "c1", "a2", "b2", "c2"],
+                double_peak,
+                None,
+            )
+        };
+        request.independent_variables[0].uncertainties = None;
+        request
+    };
+    let expected = [2.0, 2.0, 0.5, 1.2, 4.2, 0.7];
+
+    // The all-ones default start either fails or collapses both peaks onto one.
+    let single = fit_custom_odr(request(None));
+    assert!(single.as_ref().map_or(true, |result| {
+        !recovers(&result.parameter_values, &expected, 1e-2)
+    }));
+    let single_chi_squared = single.map_or(f64::INFINITY, |result| result.chi_squared);
+
+    let result = fit_custom_odr(request(Some(MultiStartConfig {
+        n_starts: 8,
+        parameter_ranges: vec![
+            (0.5, 3.0),
+            (0.0, 3.0),
+            (0.2, 1.5),
+            (0.5, 3.0),
+            (3.0, 6.0),
+            (0.2, 1.5),
+        ],
+        seed: 11,
+    })))
+    .unwrap();
+
+    assert!(result.success);
+    assert!(
+        recovers(&result.parameter_values, &expected, 1e-3),
+        "{:?}",
+        result.parameter_values
+    );
+    assert!(result.chi_squared < 1e-6 * single_chi_squared);
+    let summary = result.starts_summary.unwrap();
+    assert_eq!(summary.len(), 8);
+    let best = summary
+        .iter()
+        .filter_map(|start| start.chi_squared)
+        .fold(f64::INFINITY, f64::min);
+    assert!((best - result.chi_squared).abs() <= 1e-9 * (1.0 + best));
+    assert!(
+        result
+            .message
+            .unwrap()
+            .contains("Multi-start: best of 8 starts")
+    );
+
+    let invalid = fit_custom_odr(request(Some(MultiStartConfig {
+        n_starts: 4,
+        parameter_ranges: vec![(0.0, 1.0)],
+        seed: 0,
+    })));
+    assert!(invalid.is_err());
+}
+
+fn plane_request(x_uncertainty: Option<f64>, allow_fast_path: bool) -> OdrFitRequest {
+    let x1: Vec<f64> = (0..30).map(|i| f64::from(i) * 0.3).collect();
+    let x2: Vec<f64> = (0..30).map(|i| (f64::from(i) * 0.7).cos()).collect();
+    let y: Vec<f64> = x1
+        .iter()
+        .zip(&x2)
+        .zip(0_i32..)
+        .map(|((&a, &b), i)| {
+            let noise = 0.05 * (f64::from(i) * 1.3).sin();
+            1.5_f64.mul_add(a, (-2.0_f64).mul_add(b, 0.5)) + noise
+        })
+        .collect();
+    let sigma_y: Vec<f64> = (0..30)
+        .map(|i| 0.01_f64.mul_add(f64::from(i % 3), 0.05))
+        .collect();
+    let count = y.len();
+    let independent = |name: &str, values: Vec<f64>| VariableInput {
+        name: name.to_owned(),
+        values,
+        uncertainties: x_uncertainty.map(|sigma| vec![sigma; count]),
+        uncertainty_type: None,
+        uncertainty_degrees_of_freedom: None,
+    };
+
+    OdrFitRequest {
+        layers: vec![ModelLayer {
+            formula: "a*x1 + b*x2 + c".to_owned(),
+            dependent_variable: "y".to_owned(),
+            independent_variables: vec!["x1".to_owned(), "x2".to_owned()],
+        }],
+        independent_variables: vec![independent("x1", x1), independent("x2", x2)],
+        dependent_variables: vec![VariableInput {
+            name: "y".to_owned(),
+            values: y,
+            uncertainties: Some(sigma_y),
+            uncertainty_type: None,
+            uncertainty_degrees_of_freedom: None,
+        }],
+        use_poisson_weighting: None,
+        parameter_names: vec!["a".to_owned(), "b".to_owned(), "c".to_owned()],
+        initial_guess: None,
+        max_iterations: Some(200),
+        point_correlations: None,
+        initial_damping: None,
+        tolerance: None,
+        confidence_level: None,
+        verify_gradients: false,
+        numerical_gradients: false,
+        multi_start: None,
+        allow_fast_path,
+        computation_id: None,
+        bootstrap: None,
+    }
+}
+
+#[test]
+fn test_linear_fast_path_matches_iterative_fit() {
+    let fast = fit_custom_odr(plane_request(None, true)).unwrap();
+    let iterative = fit_custom_odr(plane_request(None, false)).unwrap();
+
+    assert!(fast.success);
+    assert_eq!(fast.termination_reason, "linearSolution");
+    assert_eq!(fast.iterations, 1);
+    assert!(iterative.success);
+    assert_ne!(iterative.termination_reason, "linearSolution");
+    assert!(iterative.iterations > 1);
+
+    for (fast_value, iterative_value) in fast
+        .parameter_values
+        .iter()
+        .zip(&iterative.parameter_values)
+    {
+        assert!(
+            (fast_value - iterative_value).abs() < 1e-7,
+            "{:?} vs {:?}",
+            fast.parameter_values,
+            iterative.parameter_values
+        );
+    }
+    for (fast_row, iterative_row) in fast
+        .parameter_covariance_raw
+        .iter()
+        .zip(&iterative.parameter_covariance_raw)
+    {
+        for (fast_value, iterative_value) in fast_row.iter().zip(iterative_row) {
+            assert!((fast_value - iterative_value).abs() <= 1e-9 * fast_value.abs().max(1e-12));
+        }
+    }
+    assert!(fast.chi_squared <= iterative.chi_squared + 1e-9);
+}
+
+#[test]
+fn test_linear_fast_path_seeds_iterative_fit_with_x_uncertainty() {
+    let seeded = fit_custom_odr(plane_request(Some(0.01), true)).unwrap();
+    let unseeded = fit_custom_odr(plane_request(Some(0.01), false)).unwrap();
+
+    assert!(seeded.success);
+    assert_ne!(seeded.termination_reason, "linearSolution");
+    assert!(
+        seeded
+            .message
+            .unwrap()
+            .contains("Initial guess: weighted linear least squares")
+    );
+    assert!(unseeded.success);
+    for (seeded_value, unseeded_value) in seeded
+        .parameter_values
+        .iter()
+        .zip(&unseeded.parameter_values)
+    {
+        assert!((seeded_value - unseeded_value).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_parameter_linearity_detection() {
+    let compile = |formula: &str| {
+        get_or_compile_model(
+            formula,
+            "y",
+            &["x".to_owned()],
+            &["a".to_owned(), "b".to_owned()],
+            false,
+        )
+        .unwrap()
+    };
+    assert!(is_linear_in_parameters(&[compile("a*x^2 + b*sin(x)")]));
+    assert!(!is_linear_in_parameters(&[compile("a*exp(b*x)")]));
+    assert!(!is_linear_in_parameters(&[compile("a*b*x")]));
+}
+
+/// Payload shaped like the one the fitting tab sends to `fit_custom_odr`
+fn linear_payload() -> Value {
+    json!({
+        "layers": [{
+            "formula": "a*x + b",
+            "dependentVariable": "y",
+            "independentVariables": ["x"]
+        }],
+        "independentVariables": [{
+            "name": "x",
+            "values": [0.0, 1.0, 2.0, 3.0, 4.0],
+            "uncertainties": [0.1, 0.1, 0.1, 0.1, 0.1]
+        }],
+        "dependentVariables": [{
+            "name": "y",
+            "values": [1.0, 3.0, 5.0, 7.0, 9.0],
+            "uncertainties": [0.2, 0.2, 0.2, 0.2, 0.2],
+            "uncertaintyType": "typeA"
+        }],
+        "parameterNames": ["a", "b"],
+        "initialGuess": [1.0, 0.0]
+    })
+}
+
+fn parse_error(payload: Value) -> String {
+    serde_json::from_value::<OdrFitRequest>(payload)
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn test_fit_custom_odr_accepts_frontend_payload() {
+    let request: OdrFitRequest = serde_json::from_value(linear_payload()).unwrap();
+    assert!(request.allow_fast_path);
+
+    let result = fit_custom_odr(request).unwrap();
+    assert!(result.success);
+    assert!((result.parameter_values[0] - 2.0).abs() < 1e-6);
+    assert!((result.parameter_values[1] - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_fit_request_deserialization_errors_name_the_field() {
+    let mut payload = linear_payload();
+    payload["independentVariables"][0]["values"] = json!("0 1 2 3 4");
+    let err = parse_error(payload);
+    assert!(
+        err.starts_with("independent_variables[0].values: invalid type: string"),
+        "{err}"
+    );
+
+    let mut payload = linear_payload();
+    payload["dependentVariables"][0]["uncertainties"][2] = json!("0.2");
+    let err = parse_error(payload);
+    assert!(
+        err.starts_with("dependent_variables[0].uncertainties[2]:"),
+        "{err}"
+    );
+
+    let mut payload = linear_payload();
+    payload["dependentVariables"][0]["uncertaintyType"] = json!("typeC");
+    let err = parse_error(payload);
+    assert!(
+        err.starts_with("dependent_variables[0].uncertainty_type: unknown variant"),
+        "{err}"
+    );
+
+    let mut payload = linear_payload();
+    payload["layers"][0]
+        .as_object_mut()
+        .unwrap()
+        .remove("dependentVariable");
+    let err = parse_error(payload);
+    assert!(err.starts_with("layers[0]: missing field"), "{err}");
+}
+
+#[test]
+fn test_fit_custom_odr_validation_errors_name_the_variable() {
+    let mut payload = linear_payload();
+    payload["independentVariables"][0]["values"] = json!([0.0, 1.0, 2.0, 3.0]);
+    let request: OdrFitRequest = serde_json::from_value(payload).unwrap();
+    let err = fit_custom_odr(request).unwrap_err();
+    assert_eq!(err.code(), codes::VALIDATION_ERROR);
+    assert_eq!(
+        err.detail(),
+        "independent_variables[0].values ('x'): expected 5 values, got 4"
+    );
+
+    let mut payload = linear_payload();
+    payload["dependentVariables"][0]["uncertainties"][3] = json!(-0.2);
+    let request: OdrFitRequest = serde_json::from_value(payload).unwrap();
+    let err = fit_custom_odr(request).unwrap_err();
+    assert_eq!(
+        err.detail(),
+        "dependent_variables[0].uncertainties ('y') at index 3: negative uncertainty -0.2"
+    );
+
+    let mut request: OdrFitRequest = serde_json::from_value(linear_payload()).unwrap();
+    request.independent_variables[0].values[1] = f64::NAN;
+    let err = fit_custom_odr(request).unwrap_err();
+    assert_eq!(
+        err.detail(),
+        "independent_variables[0].values ('x') at index 1: non-finite value NaN"
+    );
+}
+
+fn dimension_request(
+    formula: &str,
+    variables: &[(&str, &str)],
+    unit_of_y: &str,
+    parameters: &[&str],
+) -> FormulaDimensionRequest {
+    FormulaDimensionRequest {
+        formula: formula.to_owned(),
+        dependent_variable: VariableUnit {
+            name: "y".to_owned(),
+            unit: unit_of_y.to_owned(),
+        },
+        independent_variables: variables
+            .iter()
+            .map(|&(name, unit)| VariableUnit {
+                name: name.to_owned(),
+                unit: unit.to_owned(),
+            })
+            .collect(),
+        parameter_names: parameters.iter().map(|&name| name.to_owned()).collect(),
+    }
+}
+
+#[test]
+fn test_formula_dimensions_linear_model() {
+    let report = analyze_formula_dimensions(dimension_request(
+        "a*x + b",
+        &[("x", "s")],
+        "V",
+        &["a", "b"],
+    ))
+    .unwrap();
+    assert!(report.is_consistent, "{:?}", report.inconsistencies);
+    let slope = &report.parameters[0];
+    assert_eq!(slope.status, ParameterDimensionStatus::Inferred);
+    assert_eq!(slope.unit.as_deref(), Some("V/s"));
+    assert_eq!(
+        slope.dimensional_formula.as_deref(),
+        Some("[M\u{b7}L^2\u{b7}T^-4\u{b7}I^-1]")
+    );
+    assert_eq!(report.parameters[1].unit.as_deref(), Some("V"));
+
+    // Only the product of a and b is fixed
+    let report =
+        analyze_formula_dimensions(dimension_request("a*b*x", &[("x", "s")], "V", &["a", "b"]))
+            .unwrap();
+    assert!(report.is_consistent);
+    for parameter in &report.parameters {
+        assert_eq!(parameter.status, ParameterDimensionStatus::Indeterminate);
+        assert!(
+            parameter.reasoning.contains("combination"),
+            "{}",
+            parameter.reasoning
+        );
+    }
+}
+
+#[test]
+fn test_formula_dimensions_gaussian_peak() {
+    let request = dimension_request(
+        "a*exp(-(x - c)^2/(2*w^2)) + k*sin(f*x)",
+        &[("x", "m")],
+        "V",
+        &["a", "c", "w", "k", "f"],
+    );
+    let report = analyze_formula_dimensions(request).unwrap();
+    assert!(report.is_consistent, "{:?}", report.inconsistencies);
+    let units: Vec<Option<&str>> = report
+        .parameters
+        .iter()
+        .map(|parameter| parameter.unit.as_deref())
+        .collect();
+    assert_eq!(
+        units,
+        [Some("V"), Some("m"), Some("m"), Some("V"), Some("1/m")]
+    );
+    assert_eq!(
+        report.parameters[1].status,
+        ParameterDimensionStatus::Inferred
+    );
+    assert!(report.parameters[1].reasoning.contains("exp("));
+
+    let report =
+        analyze_formula_dimensions(dimension_request("exp(-b*x)", &[("x", "")], "", &["b"]))
+            .unwrap();
+    assert_eq!(
+        report.parameters[0].status,
+        ParameterDimensionStatus::DimensionlessRequired
+    );
+}
+
+#[test]
+fn test_formula_dimensions_reports_exponential_of_seconds() {
+    let request = dimension_request("a*exp(x_seconds)", &[("x_seconds", "s")], "V", &["a"]);
+    let report = analyze_formula_dimensions(request).unwrap();
+    assert!(!report.is_consistent);
+    assert_eq!(report.inconsistencies.len(), 1);
+    let issue = &report.inconsistencies[0];
+    assert!(issue.contains("exp(x_seconds)"), "{issue}");
+    assert!(issue.contains("[T]"), "{issue}");
+    // The rest of the formula is still analyzed
+    assert_eq!(report.parameters[0].unit.as_deref(), Some("V"));
+
+    let err =
+        analyze_formula_dimensions(dimension_request("a*x", &[("x", "parsec^q")], "V", &["a"]))
+            .unwrap_err();
+    assert!(err.detail().contains("parsec^q"), "{err}");
+}
+
+#[test]
+fn test_verify_dimensions_of_kinetic_energy() {
+    let dimension = |formula: &str| Dimension::parse(formula).unwrap();
+    let variables = [
+        ("m".to_owned(), dimension("[M]")),
+        ("v".to_owned(), dimension("[L/T]")),
+        ("t".to_owned(), dimension("[T]")),
+    ];
+    let energy = dimension("[M L^2 / T^2]");
+    let report = verify_dimensions("m*v^2/2", &variables, Some(&energy)).unwrap();
+    assert!(report.conflicts.is_empty(), "{:?}", report.conflicts);
+    assert_eq!(report.dimension, Some(energy.clone()));
+
+    // The wrong expected dimension is a conflict on the whole formula
+    let report = verify_dimensions("m*v", &variables, Some(&energy)).unwrap();
+    assert_eq!(report.dimension, Some(dimension("[M L / T]")));
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].term, "m*v");
+
+    // Mismatched terms are named and leave the output undetermined
+    let report = verify_dimensions("m*v^2 + v*t + sin(t)", &variables, None).unwrap();
+    assert_eq!(report.dimension, None);
+    let terms: Vec<&str> = report
+        .conflicts
+        .iter()
+        .map(|conflict| conflict.term.as_str())
+        .collect();
+    assert_eq!(terms.len(), 3, "{:?}", report.conflicts);
+    assert!(terms.contains(&"t"), "{terms:?}");
+    assert!(
+        report
+            .conflicts
+            .iter()
+            .any(|conflict| conflict.message.contains("sin(t)")),
+        "{:?}",
+        report.conflicts
+    );
+
+    let err = verify_dimensions("m*x", &variables, None).unwrap_err();
+    assert!(err.to_string().contains('x'), "{err}");
+}
+
+fn calibration_request(noise_scale: f64, samples: &[(f64, Option<f64>)]) -> CalibrationRequest {
+    let concentrations: Vec<f64> = vec![0.0, 1.0, 2.0, 4.0, 6.0, 8.0];
+    let noise = [0.03, -0.05, 0.02, 0.04, -0.06, 0.02];
+    let signals = concentrations
+        .iter()
+        .zip(noise)
+        .map(|(&x, e)| noise_scale.mul_add(e, x.mul_add(2.0, 0.5)))
+        .collect();
+    CalibrationRequest {
+        concentrations,
+        concentration_uncertainties: None,
+        signals,
+        signal_uncertainties: None,
+        model: CalibrationModel::Linear,
+        samples: samples
+            .iter()
+            .map(|&(signal, uncertainty)| CalibrationSample {
+                label: None,
+                signal,
+                uncertainty,
+            })
+            .collect(),
+        blank_signals: None,
+        confidence_level: None,
+    }
+}
+
+#[test]
+fn test_calibration_inverse_prediction_matches_analytic_line() {
+    let request = calibration_request(1.0, &[(9.0, None), (9.0, Some(0.1)), (30.0, None)]);
+    let result = calibration_analysis(request.clone()).unwrap();
+
+    // Ordinary least squares by hand
+    let x = &request.concentrations;
+    let y = &request.signals;
+    #[allow(clippy::cast_precision_loss, reason = "Small point count")]
+    let n = x.len() as f64;
+    let x_mean = x.iter().sum::<f64>() / n;
+    let y_mean = y.iter().sum::<f64>() / n;
+    let sxx: f64 = x.iter().map(|xi| (xi - x_mean).powi(2)).sum();
+    let sxy: f64 = x
+        .iter()
+        .zip(y)
+        .map(|(xi, yi)| (xi - x_mean) * (yi - y_mean))
+        .sum();
+    let slope = sxy / sxx;
+    let intercept = slope.mul_add(-x_mean, y_mean);
+    let rss: f64 = x
+        .iter()
+        .zip(y)
+        .map(|(xi, yi)| (yi - slope.mul_add(*xi, intercept)).powi(2))
+        .sum();
+    let s = (rss / (n - 2.0)).sqrt();
+    let close = |actual: f64, expected: f64| (actual - expected).abs() <= 1e-8 * expected.abs();
+    assert!(close(result.slope, slope) && close(result.intercept, intercept));
+
+    // s_x0 = (s / b) sqrt(1/m + 1/n + (y0 - mean y)^2 / (b^2 Sxx)) with m = 1
+    let x0 = (9.0 - intercept) / slope;
+    let leverage = 1.0 / n + (9.0 - y_mean).powi(2) / (slope * slope * sxx);
+    let single = &result.samples[0];
+    assert!(close(single.concentration.unwrap(), x0));
+    assert!(close(single.signal_uncertainty, s));
+    assert!(close(
+        single.uncertainty.unwrap(),
+        s / slope * (1.0 + leverage).sqrt()
+    ));
+    assert!(!single.extrapolated);
+    let measured = &result.samples[1];
+    assert!(close(
+        measured.uncertainty.unwrap(),
+        0.1_f64.mul_add(0.1, s * s * leverage).sqrt() / slope
+    ));
+    assert!(close(
+        measured.expanded_uncertainty.unwrap(),
+        measured.uncertainty.unwrap() * result.fit.coverage_factor
+    ));
+    assert!(result.samples[2].extrapolated);
+    assert_eq!(result.calibrated_range, (0.0, 8.0));
+
+    // A quadratic takes the root inside the calibrated range, not the one
+    // near -23.6
+    let mut quadratic = calibration_request(1.0, &[(9.0, None)]);
+    quadratic.model = CalibrationModel::Quadratic;
+    for (signal, &x) in quadratic.signals.iter_mut().zip(&quadratic.concentrations) {
+        *signal += 0.1 * x * x;
+    }
+    let result = calibration_analysis(quadratic).unwrap();
+    let concentration = result.samples[0].concentration.unwrap();
+    assert!((concentration - 3.6).abs() < 0.05, "{concentration}");
+    assert!(result.curvature.is_some());
+}
+
+#[test]
+fn test_calibration_detection_limits_scale_with_intercept_noise() {
+    let quiet = calibration_analysis(calibration_request(1.0, &[])).unwrap();
+    let noisy = calibration_analysis(calibration_request(3.0, &[])).unwrap();
+    assert_eq!(
+        quiet.detection_noise_source,
+        DetectionNoiseSource::Intercept
+    );
+    assert!((quiet.detection_noise - quiet.fit.parameter_uncertainties[0]).abs() < 1e-15);
+    // Tripling the scatter triples the intercept standard error
+    let ratio = noisy.detection_noise / quiet.detection_noise;
+    assert!((ratio - 3.0).abs() < 1e-9, "{ratio}");
+    let lod_ratio = noisy.limit_of_detection / quiet.limit_of_detection;
+    assert!((lod_ratio - 3.0 * quiet.slope / noisy.slope).abs() < 1e-9);
+    assert!((quiet.limit_of_quantification / quiet.limit_of_detection - 10.0 / 3.3).abs() < 1e-12);
+    assert!((quiet.limit_of_detection - 3.3 * quiet.detection_noise / quiet.slope).abs() < 1e-12);
+
+    let mut with_blanks = calibration_request(1.0, &[]);
+    with_blanks.blank_signals = Some(vec![0.48, 0.52, 0.50, 0.54, 0.46]);
+    let blanks = calibration_analysis(with_blanks.clone()).unwrap();
+    assert_eq!(blanks.detection_noise_source, DetectionNoiseSource::Blank);
+    assert!((blanks.detection_noise - 0.1_f64.sqrt() / 10.0).abs() < 1e-12);
+
+    with_blanks.blank_signals = Some(vec![0.5]);
+    assert!(calibration_analysis(with_blanks).is_err());
+    let mut short = calibration_request(1.0, &[]);
+    short.model = CalibrationModel::Quadratic;
+    short.concentrations.truncate(3);
+    short.signals.truncate(3);
+    assert!(calibration_analysis(short).is_err());
+}
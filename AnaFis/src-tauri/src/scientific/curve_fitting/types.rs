@@ -1,6 +1,17 @@
-use serde::{Deserialize, Serialize};
+#![allow(
+    clippy::same_name_method,
+    reason = "OdrFitRequest derives an inherent deserialize (remote = \"Self\") that its Deserialize impl wraps"
+)]
+use std::collections::HashMap;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_path_to_error::{Deserializer as TrackedDeserializer, Path, Segment, Track};
 use thiserror::Error;
 
+use crate::error::{CommandError, codes, internal_error, numerical_error, validation_error};
+use crate::unit_conversion::core::Dimension;
+
 /// Uncertainty evaluation mode for an input variable (GUM Type A / Type B).
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -46,8 +57,11 @@ pub struct ModelLayer {
 /// Note: This solver uses a nested/profiled strategy where per-point latent x-corrections
 /// are solved in an inner loop; the outer LM uses the profiled gradient via implicit
 /// differentiation and a Gauss-Newton approximation for reduced Hessian curvature.
+///
+/// Deserialization errors are prefixed with the path of the offending field,
+/// e.g. `independent_variables[1].values[3]: invalid type: string "x", expected f64`.
 #[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", remote = "Self")]
 pub struct OdrFitRequest {
     /// The layers forming the system to be fitted.
     pub layers: Vec<ModelLayer>,
@@ -71,6 +85,154 @@ pub struct OdrFitRequest {
     pub use_poisson_weighting: Option<bool>,
     /// Optional confidence level for expanded uncertainties (default 0.95).
     pub confidence_level: Option<f64>,
+    /// If true, checks the symbolic derivatives against central finite differences
+    /// at the initial guess and aborts with a per-symbol report on disagreement.
+    #[serde(default)]
+    pub verify_gradients: bool,
+    /// If true, skips symbolic differentiation and uses central finite differences
+    /// for all model derivatives.
+    #[serde(default)]
+    pub numerical_gradients: bool,
+    /// Optional multi-start configuration; when present the initial guess is replaced
+    /// by sampled starting points and the best converged fit is returned.
+    #[serde(default)]
+    pub multi_start: Option<MultiStartConfig>,
+    /// If true (default), models linear in all parameters are solved directly
+    /// by weighted least squares when only dependent variables carry
+    /// uncertainties, and seed the iterative solver otherwise. Set to false to
+    /// force the iterative path.
+    #[serde(default = "default_allow_fast_path")]
+    pub allow_fast_path: bool,
+    /// Optional id of this fit; when set, `computation-progress` events carrying
+    /// it report the iterations and chi-squared while the solver runs.
+    #[serde(default)]
+    pub computation_id: Option<String>,
+    /// Optional bootstrap of the fitted parameters, run after the main fit.
+    #[serde(default)]
+    pub bootstrap: Option<BootstrapConfig>,
+}
+
+const fn default_allow_fast_path() -> bool {
+    true
+}
+
+impl<'de> Deserialize<'de> for OdrFitRequest {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut track = Track::new();
+        Self::deserialize(TrackedDeserializer::new(deserializer, &mut track)).map_err(|error| {
+            let path = field_path(&track.path());
+            if path.is_empty() {
+                error
+            } else {
+                D::Error::custom(format!("{path}: {error}"))
+            }
+        })
+    }
+}
+
+/// Request field path with snake case keys, e.g. `dependent_variables[0].uncertainties[2]`
+fn field_path(tracked: &Path) -> String {
+    let mut path = String::new();
+    for segment in tracked {
+        match segment {
+            Segment::Seq { index } => {
+                path.push('[');
+                path.push_str(&index.to_string());
+                path.push(']');
+            }
+            Segment::Map { key } | Segment::Enum { variant: key } => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                for character in key.chars() {
+                    if character.is_ascii_uppercase() {
+                        path.push('_');
+                    }
+                    path.push(character.to_ascii_lowercase());
+                }
+            }
+            Segment::Unknown => path.push_str(".?"),
+        }
+    }
+    path
+}
+
+/// Configuration for a multi-start ODR fit over a rugged chi-squared surface.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiStartConfig {
+    /// Number of starting points to sample.
+    pub n_starts: usize,
+    /// Sampling range `(min, max)` for each parameter, in `parameter_names` order.
+    pub parameter_ranges: Vec<(f64, f64)>,
+    /// Seed for the Latin hypercube sampler (default 0).
+    #[serde(default)]
+    pub seed: u64,
+}
+
+/// How the data are resampled for a bootstrap of the fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BootstrapMethod {
+    /// Fitted values plus weighted residuals drawn with replacement.
+    Residual,
+    /// Data points drawn with replacement.
+    Pairs,
+    /// Every point redrawn from its covariance around the fit.
+    Parametric,
+}
+
+/// Configuration for a bootstrap of the fitted parameters.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootstrapConfig {
+    /// Number of resampled data sets to refit.
+    pub n_resamples: usize,
+    /// How the data are resampled.
+    pub method: BootstrapMethod,
+    /// Seed for the resampling (default 0).
+    #[serde(default)]
+    pub seed: u64,
+}
+
+/// Parameter distribution estimated by refitting resampled data.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootstrapSummary {
+    /// How the data were resampled.
+    pub method: BootstrapMethod,
+    /// Number of resamples requested.
+    pub n_resamples: usize,
+    /// Resamples whose refit converged; the statistics below use only these.
+    pub converged_resamples: usize,
+    /// Resamples whose refit failed or did not converge.
+    pub failed_resamples: usize,
+    /// Coverage of the percentile intervals (the fit confidence level).
+    pub confidence_level: f64,
+    /// Lower percentile bound of each parameter.
+    pub parameter_ci_lower: Vec<f64>,
+    /// Upper percentile bound of each parameter.
+    pub parameter_ci_upper: Vec<f64>,
+    /// Standard deviation of each parameter across resamples.
+    pub parameter_uncertainties: Vec<f64>,
+    /// Sample covariance of the parameters across resamples.
+    pub parameter_covariance: Vec<Vec<f64>>,
+}
+
+/// Outcome of a single start in a multi-start ODR fit.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartSummary {
+    /// The sampled starting parameter values.
+    pub initial_parameters: Vec<f64>,
+    /// Final profiled chi-squared, or `None` if the start failed numerically.
+    pub chi_squared: Option<f64>,
+    /// Number of iterations performed.
+    pub iterations: usize,
+    /// Whether the optimizer met a convergence criterion.
+    pub converged: bool,
+    /// Error message if the start failed.
+    pub error: Option<String>,
 }
 
 /// Response containing the results of a profiled ODR fit.
@@ -150,6 +312,10 @@ pub struct OdrFitResponse {
     pub coverage_degrees_of_freedom: Option<f64>,
     /// Assumptions used for uncertainty interpretation (NIST GUM context).
     pub assumptions: Vec<String>,
+    /// Per-start outcomes when the fit used multiple starting points.
+    pub starts_summary: Option<Vec<StartSummary>>,
+    /// Bootstrap parameter distribution when requested.
+    pub bootstrap: Option<BootstrapSummary>,
 }
 
 /// Request structure for evaluating a model on a 2D grid.
@@ -212,6 +378,254 @@ pub struct CurveEvaluationResponse {
     pub y: Vec<f64>,
 }
 
+/// Sweep points of a formula evaluation.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum FormulaGrid {
+    /// `n` evenly spaced points from `start` to `stop`, both included.
+    Range {
+        /// First sweep value.
+        start: f64,
+        /// Last sweep value.
+        stop: f64,
+        /// Number of points.
+        n: usize,
+    },
+    /// Explicit sweep values.
+    Points(Vec<f64>),
+}
+
+/// Request for evaluating a formula along one swept variable.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormulaEvaluationRequest {
+    /// The formula to evaluate, e.g. `a*sin(omega*x)`.
+    pub formula: String,
+    /// Values of every other symbol in the formula.
+    #[serde(default)]
+    pub fixed_values: HashMap<String, f64>,
+    /// Name of the swept variable.
+    pub sweep_variable: String,
+    /// Values of the swept variable.
+    pub grid: FormulaGrid,
+}
+
+/// Formula values along the sweep.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormulaEvaluationResponse {
+    /// Sweep values.
+    pub x: Vec<f64>,
+    /// Formula values; non-finite entries serialize as `null`.
+    pub y: Vec<f64>,
+    /// Indices of the sweep points where the formula is not finite.
+    pub non_finite_indices: Vec<usize>,
+}
+
+/// A model variable and the unit its values are given in.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariableUnit {
+    /// Variable name as used in the formula.
+    pub name: String,
+    /// Unit string understood by the unit converter (e.g. "V", "m/s^2");
+    /// empty for a dimensionless variable.
+    #[serde(default)]
+    pub unit: String,
+}
+
+/// Request for the dimensional analysis of a model formula.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormulaDimensionRequest {
+    /// Model formula (e.g. "a*x + b").
+    pub formula: String,
+    /// Dependent variable the formula models, with its unit.
+    pub dependent_variable: VariableUnit,
+    /// Independent variables used by the formula, with their units.
+    pub independent_variables: Vec<VariableUnit>,
+    /// Names of the fitted parameters.
+    pub parameter_names: Vec<String>,
+}
+
+/// How far the formula pins down the dimension of a parameter.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ParameterDimensionStatus {
+    /// The formula fixes a unique dimension.
+    Inferred,
+    /// The formula requires the parameter to be dimensionless.
+    DimensionlessRequired,
+    /// The dimension cannot be inferred uniquely.
+    Indeterminate,
+}
+
+/// Inferred dimension of one parameter.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParameterDimension {
+    /// Parameter name (normalized to lowercase).
+    pub name: String,
+    /// How far the dimension is determined.
+    pub status: ParameterDimensionStatus,
+    /// SI dimensional formula (e.g. "[M·L^2·T^-4·I^-1]"), when determined.
+    pub dimensional_formula: Option<String>,
+    /// Unit written with the variable units (e.g. "V/s"), when determined and
+    /// the variables use consistent units.
+    pub unit: Option<String>,
+    /// Why the formula gives this result.
+    pub reasoning: String,
+}
+
+/// Parameter dimensions of a model formula and its dimensional inconsistencies.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormulaDimensionResponse {
+    /// One entry per parameter, in request order.
+    pub parameters: Vec<ParameterDimension>,
+    /// Places where the formula cannot be dimensionally consistent.
+    pub inconsistencies: Vec<String>,
+    /// True when no inconsistency was found.
+    pub is_consistent: bool,
+}
+
+/// A place where a formula cannot be dimensionally consistent.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DimensionConflict {
+    /// Subexpression the conflict arises in (e.g. "x + t").
+    pub term: String,
+    /// What the conflict is, with the dimensions involved.
+    pub message: String,
+}
+
+/// Dimension of a formula whose variables all have known dimensions.
+#[derive(Debug, Clone)]
+pub struct FormulaDimension {
+    /// Dimension of the whole formula; `None` when its terms disagree or the
+    /// exponents are not integers.
+    pub dimension: Option<Dimension>,
+    /// Places where the formula is inconsistent, including a mismatch with
+    /// the expected dimension.
+    pub conflicts: Vec<DimensionConflict>,
+}
+
+/// Polynomial relating signal to concentration in a calibration.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CalibrationModel {
+    /// `signal = a + b*x`.
+    #[default]
+    Linear,
+    /// `signal = a + b*x + c*x^2`.
+    Quadratic,
+}
+
+/// Signal of a sample whose concentration is to be read off the calibration.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationSample {
+    /// Optional name of the sample.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Measured signal.
+    pub signal: f64,
+    /// Standard uncertainty of the signal; defaults to the residual standard
+    /// error of the calibration, i.e. a single reading.
+    #[serde(default)]
+    pub uncertainty: Option<f64>,
+}
+
+/// Request for a calibration curve with inverse prediction of samples.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationRequest {
+    /// Concentrations of the standards.
+    pub concentrations: Vec<f64>,
+    /// Optional standard uncertainties of the concentrations.
+    #[serde(default)]
+    pub concentration_uncertainties: Option<Vec<f64>>,
+    /// Signals measured for the standards.
+    pub signals: Vec<f64>,
+    /// Optional standard uncertainties of the signals.
+    #[serde(default)]
+    pub signal_uncertainties: Option<Vec<f64>>,
+    /// Calibration polynomial (default linear).
+    #[serde(default)]
+    pub model: CalibrationModel,
+    /// Samples to convert to concentrations.
+    #[serde(default)]
+    pub samples: Vec<CalibrationSample>,
+    /// Optional replicate signals of a blank; when at least two are given
+    /// their standard deviation replaces the intercept standard error in the
+    /// detection limits.
+    #[serde(default)]
+    pub blank_signals: Option<Vec<f64>>,
+    /// Optional confidence level for expanded uncertainties (default 0.95).
+    #[serde(default)]
+    pub confidence_level: Option<f64>,
+}
+
+/// Source of the signal noise behind the detection limits.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DetectionNoiseSource {
+    /// Standard deviation of the blank replicates.
+    Blank,
+    /// Standard error of the fitted intercept.
+    Intercept,
+}
+
+/// Concentration of one sample obtained by inverse prediction.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InversePrediction {
+    /// Name of the sample, if given.
+    pub label: Option<String>,
+    /// Measured signal.
+    pub signal: f64,
+    /// Standard uncertainty used for the signal.
+    pub signal_uncertainty: f64,
+    /// Predicted concentration; `None` when no concentration gives the
+    /// signal.
+    pub concentration: Option<f64>,
+    /// Standard uncertainty of the concentration from the fit covariance and
+    /// the signal uncertainty; `None` where the curve is flat.
+    pub uncertainty: Option<f64>,
+    /// Uncertainty expanded by the fit's coverage factor.
+    pub expanded_uncertainty: Option<f64>,
+    /// Whether the concentration lies outside the range of the standards.
+    pub extrapolated: bool,
+}
+
+/// Calibration curve, sample concentrations and detection limits.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationResponse {
+    /// Calibration polynomial used.
+    pub model: CalibrationModel,
+    /// Signal of a zero concentration.
+    pub intercept: f64,
+    /// Sensitivity at zero concentration.
+    pub slope: f64,
+    /// Quadratic coefficient of a quadratic calibration.
+    pub curvature: Option<f64>,
+    /// Lowest and highest concentration of the standards.
+    pub calibrated_range: (f64, f64),
+    /// Concentration of each sample, in request order.
+    pub samples: Vec<InversePrediction>,
+    /// Signal standard deviation the detection limits are based on.
+    pub detection_noise: f64,
+    /// Where the detection noise comes from.
+    pub detection_noise_source: DetectionNoiseSource,
+    /// Limit of detection, `3.3 * s / slope`.
+    pub limit_of_detection: f64,
+    /// Limit of quantification, `10 * s / slope`.
+    pub limit_of_quantification: f64,
+    /// The underlying fit with its diagnostics.
+    pub fit: OdrFitResponse,
+}
+
 /// Errors that can occur during ODR fitting.
 #[derive(Debug, Error)]
 pub enum OdrError {
@@ -227,10 +641,51 @@ pub enum OdrError {
     /// Numerical failure during ODR solver (e.g., non-invertible matrix).
     #[error("Numerical failure: {0}")]
     Numerical(String),
+    /// Formula refers to symbols that were given no value.
+    #[error("Formula uses undefined symbols: {}", .0.join(", "))]
+    UnknownSymbols(Vec<String>),
     /// Internal model cache lock poisoned.
     #[error("Internal model cache lock poisoned")]
     CachePoisoned,
+    /// The computation was cancelled by its computation id.
+    #[error("Fit cancelled after {iterations} iterations")]
+    Cancelled {
+        /// Parameters reached when the solver stopped.
+        parameters: Vec<f64>,
+        /// Iterations completed before stopping.
+        iterations: usize,
+    },
 }
 
 /// Result type for ODR operations.
 pub type OdrResult<T> = Result<T, OdrError>;
+
+impl From<OdrError> for CommandError {
+    fn from(error: OdrError) -> Self {
+        let detail = error.to_string();
+        match error {
+            OdrError::Validation(_) => validation_error(detail, None),
+            OdrError::Parse(_) => Self::Validation {
+                code: codes::PARSING_ERROR,
+                field: Some("formula".to_owned()),
+                detail,
+            },
+            OdrError::Compile(_) => Self::Validation {
+                code: codes::COMPILE_FAILED,
+                field: Some("formula".to_owned()),
+                detail,
+            },
+            OdrError::UnknownSymbols(_) => Self::Validation {
+                code: codes::UNKNOWN_SYMBOLS,
+                field: Some("formula".to_owned()),
+                detail,
+            },
+            OdrError::Numerical(_) => numerical_error(detail),
+            OdrError::CachePoisoned => internal_error(detail),
+            OdrError::Cancelled { .. } => Self::Internal {
+                code: codes::CANCELLED,
+                detail,
+            },
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use std::cmp::Ordering;
+
+use super::fitting::{FittedDistribution, fit_mle, log_likelihood, standard_errors};
+use super::goodness_of_fit::{goodness_of_fit, qq_plot};
+use super::mixture::fit_gaussian_mixture;
+use super::types::{
+    DistributionError, DistributionFamily, DistributionFitRequest, DistributionFitResponse,
+    DistributionFitResult, ExcludedDistribution, FittedParameter, GmmFitRequest, GmmResult,
+};
+use crate::error::{CommandError, CommandResult};
+use crate::scientific::primitives::descriptive::sorted;
+
+/// Minimum sample size for distribution fitting.
+const MIN_SAMPLE_SIZE: usize = 5;
+/// Default EM iteration cap for Gaussian mixtures.
+const GMM_DEFAULT_MAX_ITERATIONS: usize = 500;
+/// Default relative log-likelihood tolerance for Gaussian mixtures.
+const GMM_DEFAULT_TOLERANCE: f64 = 1e-8;
+
+/// Fit candidate distributions to a data column and rank them by AIC.
+///
+/// # Errors
+/// Returns an error if the sample is too small, contains non-finite values,
+/// or is constant.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn fit_distributions(
+    request: DistributionFitRequest,
+) -> CommandResult<DistributionFitResponse> {
+    fit_distributions_inner(&request).map_err(CommandError::from)
+}
+
+/// Fit a Gaussian mixture model to a data column by expectation-maximization.
+///
+/// # Errors
+/// Returns an error for invalid options, too few observations, or non-finite
+/// or constant data.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn fit_gaussian_mixture_model(request: GmmFitRequest) -> CommandResult<GmmResult> {
+    fit_gaussian_mixture(
+        &request.data,
+        request.n_components,
+        request.max_iterations.unwrap_or(GMM_DEFAULT_MAX_ITERATIONS),
+        request.tolerance.unwrap_or(GMM_DEFAULT_TOLERANCE),
+        request.seed,
+    )
+    .map_err(CommandError::from)
+}
+
+/// Domain restriction of a family, if the data violate it.
+fn domain_violation(family: DistributionFamily, minimum: f64, maximum: f64) -> Option<String> {
+    match family {
+        DistributionFamily::Normal | DistributionFamily::Cauchy => None,
+        DistributionFamily::Exponential => (minimum < 0.0)
+            .then(|| format!("Exponential requires non-negative data (minimum is {minimum})")),
+        DistributionFamily::Beta => (minimum <= 0.0 || maximum >= 1.0).then(|| {
+            format!("Beta requires data strictly inside (0, 1) (range is [{minimum}, {maximum}])")
+        }),
+        DistributionFamily::LogNormal | DistributionFamily::Gamma | DistributionFamily::Weibull => {
+            (minimum <= 0.0).then(|| {
+                format!(
+                    "{} requires strictly positive data (minimum is {minimum})",
+                    family.label()
+                )
+            })
+        }
+    }
+}
+
+/// Distribution fitting engine.
+pub struct DistributionFitter;
+
+impl DistributionFitter {
+    /// Fit each of `families` by maximum likelihood and return the successful
+    /// fits sorted by AIC, best first. Families whose domain excludes the data
+    /// or whose likelihood cannot be maximized are left out.
+    ///
+    /// # Errors
+    /// Returns an error if the sample is too small, contains non-finite values,
+    /// or is constant.
+    pub fn fit_all(
+        data: &[f64],
+        families: &[DistributionFamily],
+    ) -> Result<Vec<DistributionFitResult>, String> {
+        let request = DistributionFitRequest {
+            data: data.to_vec(),
+            families: Some(families.to_vec()),
+        };
+        fit_distributions_inner(&request)
+            .map(|response| response.fits)
+            .map_err(|error| error.to_string())
+    }
+
+    /// Fit an `n_components` Gaussian mixture by expectation-maximization,
+    /// starting from seeded K-means means, equal weights and the sample
+    /// standard deviation for every component.
+    ///
+    /// # Errors
+    /// Returns an error for invalid options, too few observations, or
+    /// non-finite or constant data.
+    pub fn fit_gmm(
+        data: &[f64],
+        n_components: usize,
+        max_iter: usize,
+        tol: f64,
+        seed: u64,
+    ) -> Result<GmmResult, String> {
+        fit_gaussian_mixture(data, n_components, max_iter, tol, seed)
+            .map_err(|error| error.to_string())
+    }
+}
+
+pub fn fit_distributions_inner(
+    request: &DistributionFitRequest,
+) -> Result<DistributionFitResponse, DistributionError> {
+    let data = &request.data;
+    if data.len() < MIN_SAMPLE_SIZE {
+        return Err(DistributionError::InsufficientData {
+            required: MIN_SAMPLE_SIZE,
+            actual: data.len(),
+        });
+    }
+    if data.iter().any(|value| !value.is_finite()) {
+        return Err(DistributionError::NonFinite);
+    }
+    let sorted_data = sorted(data);
+    let minimum = sorted_data[0];
+    let maximum = sorted_data[sorted_data.len() - 1];
+    if (maximum - minimum).abs() <= f64::EPSILON * minimum.abs() {
+        return Err(DistributionError::ZeroVariance);
+    }
+
+    let families = request
+        .families
+        .clone()
+        .unwrap_or_else(|| DistributionFamily::ALL.to_vec());
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for information criteria"
+    )]
+    let n = data.len() as f64;
+
+    let mut fits = Vec::new();
+    let mut excluded = Vec::new();
+    for family in families {
+        if let Some(reason) = domain_violation(family, minimum, maximum) {
+            excluded.push(ExcludedDistribution { family, reason });
+            continue;
+        }
+        let parameters = match fit_mle(family, data) {
+            Ok(parameters) => parameters,
+            Err(error) => {
+                excluded.push(ExcludedDistribution {
+                    family,
+                    reason: error.to_string(),
+                });
+                continue;
+            }
+        };
+        let Some(distribution) = FittedDistribution::from_parameters(family, &parameters) else {
+            excluded.push(ExcludedDistribution {
+                family,
+                reason: format!("{} fit produced invalid parameters", family.label()),
+            });
+            continue;
+        };
+
+        let log_likelihood = log_likelihood(family, &parameters, data);
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Parameter count casting to f64 for information criteria"
+        )]
+        let parameter_count = parameters.len() as f64;
+        let errors = standard_errors(family, &parameters, data);
+
+        fits.push(DistributionFitResult {
+            family,
+            rank: 0,
+            parameters: family
+                .parameter_names()
+                .iter()
+                .zip(parameters.iter().zip(errors))
+                .map(|(name, (&value, standard_error))| FittedParameter {
+                    name: (*name).to_owned(),
+                    value,
+                    standard_error,
+                })
+                .collect(),
+            log_likelihood,
+            aic: 2.0_f64.mul_add(parameter_count, -2.0 * log_likelihood),
+            bic: parameter_count.mul_add(n.ln(), -2.0 * log_likelihood),
+            delta_aic: 0.0,
+            goodness_of_fit: goodness_of_fit(&sorted_data, &distribution),
+            qq_plot: qq_plot(&sorted_data, &distribution),
+        });
+    }
+
+    fits.sort_by(|a, b| a.aic.partial_cmp(&b.aic).unwrap_or(Ordering::Equal));
+    let best_aic = fits.first().map_or(0.0, |fit| fit.aic);
+    for (index, fit) in fits.iter_mut().enumerate() {
+        fit.rank = index + 1;
+        fit.delta_aic = fit.aic - best_aic;
+    }
+
+    Ok(DistributionFitResponse {
+        sample_size: data.len(),
+        fits,
+        excluded,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random sample drawn by inverse-CDF sampling.
+    fn sample(family: DistributionFamily, parameters: &[f64], count: usize) -> Vec<f64> {
+        let distribution = FittedDistribution::from_parameters(family, parameters).unwrap();
+        let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+        (0..count)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                #[allow(
+                    clippy::cast_precision_loss,
+                    reason = "Top 53 bits form an exact uniform mantissa"
+                )]
+                let uniform = ((state >> 11) as f64 + 0.5) / (1_u64 << 53) as f64;
+                distribution.quantile(uniform)
+            })
+            .collect()
+    }
+
+    fn fit(data: Vec<f64>) -> DistributionFitResponse {
+        fit_distributions_inner(&DistributionFitRequest {
+            data,
+            families: None,
+        })
+        .unwrap()
+    }
+
+    fn parameter(result: &DistributionFitResult, name: &str) -> f64 {
+        result
+            .parameters
+            .iter()
+            .find(|p| p.name == name)
+            .unwrap()
+            .value
+    }
+
+    #[test]
+    fn test_gamma_sample_recovers_parameters_and_ranks_first() {
+        let response = fit(sample(DistributionFamily::Gamma, &[3.0, 2.0], 5000));
+        let best = &response.fits[0];
+        assert_eq!(best.family, DistributionFamily::Gamma);
+        assert!((parameter(best, "shape") - 3.0).abs() < 0.2);
+        assert!((parameter(best, "scale") - 2.0).abs() < 0.15);
+        assert!(best.parameters.iter().all(|p| p.standard_error.is_some()));
+        assert_eq!(best.qq_plot.theoretical.len(), 512);
+    }
+
+    #[test]
+    fn test_weibull_sample_recovers_parameters_and_ranks_first() {
+        let response = fit(sample(DistributionFamily::Weibull, &[0.8, 5.0], 5000));
+        let best = &response.fits[0];
+        assert_eq!(best.family, DistributionFamily::Weibull);
+        assert!((parameter(best, "shape") - 0.8).abs() < 0.05);
+        assert!((parameter(best, "scale") - 5.0).abs() < 0.3);
+    }
+
+    #[test]
+    fn test_lognormal_sample_ranks_first() {
+        let response = fit(sample(DistributionFamily::LogNormal, &[1.0, 0.9], 5000));
+        let best = &response.fits[0];
+        assert_eq!(best.family, DistributionFamily::LogNormal);
+        assert!((parameter(best, "mu") - 1.0).abs() < 0.05);
+        assert!((parameter(best, "sigma") - 0.9).abs() < 0.05);
+        assert!(best.goodness_of_fit.ks_p_value > 0.01);
+    }
+
+    #[test]
+    fn test_fit_all_recovers_beta_and_sorts_by_aic() {
+        let data = sample(DistributionFamily::Beta, &[2.0, 5.0], 5000);
+        let fits = DistributionFitter::fit_all(&data, &DistributionFamily::ALL).unwrap();
+        let best = &fits[0];
+        assert_eq!(best.family, DistributionFamily::Beta);
+        assert!((parameter(best, "alpha") - 2.0).abs() < 0.15);
+        assert!((parameter(best, "beta") - 5.0).abs() < 0.4);
+        assert!(best.parameters.iter().all(|p| p.standard_error.is_some()));
+        assert!(fits.windows(2).all(|pair| pair[0].aic <= pair[1].aic));
+    }
+
+    #[test]
+    fn test_fit_all_prefers_cauchy_for_heavy_tails() {
+        let data = sample(DistributionFamily::Cauchy, &[3.0, 0.5], 5000);
+        let fits = DistributionFitter::fit_all(
+            &data,
+            &[DistributionFamily::Normal, DistributionFamily::Cauchy],
+        )
+        .unwrap();
+        let best = &fits[0];
+        assert_eq!(best.family, DistributionFamily::Cauchy);
+        assert!((parameter(best, "location") - 3.0).abs() < 0.05);
+        assert!((parameter(best, "scale") - 0.5).abs() < 0.05);
+        assert!(best.goodness_of_fit.ks_statistic < fits[1].goodness_of_fit.ks_statistic);
+    }
+
+    #[test]
+    fn test_negative_data_excludes_positive_families() {
+        let response = fit(sample(DistributionFamily::Normal, &[-1.0, 2.0], 2000));
+        assert_eq!(response.fits.len(), 2);
+        assert_eq!(response.fits[0].family, DistributionFamily::Normal);
+        assert_eq!(response.fits[1].family, DistributionFamily::Cauchy);
+        assert!((parameter(&response.fits[0], "mu") + 1.0).abs() < 0.15);
+        assert_eq!(response.excluded.len(), 5);
+    }
+
+    #[test]
+    fn test_rejects_small_or_constant_samples() {
+        let request = DistributionFitRequest {
+            data: vec![1.0, 2.0],
+            families: None,
+        };
+        assert!(fit_distributions_inner(&request).is_err());
+        let constant = DistributionFitRequest {
+            data: vec![4.0; 10],
+            families: None,
+        };
+        assert!(matches!(
+            fit_distributions_inner(&constant),
+            Err(DistributionError::ZeroVariance)
+        ));
+    }
+}
@@ -0,0 +1,113 @@
+//! Goodness-of-fit statistics and Q-Q plot data for fitted distributions.
+use super::fitting::FittedDistribution;
+use super::types::{GoodnessOfFit, QqPlotData};
+
+/// Maximum number of points returned for a Q-Q plot.
+pub const MAX_QQ_POINTS: usize = 512;
+/// Probability clamp keeping `ln(F)` and `ln(1 - F)` finite in the AD sum.
+const CDF_CLAMP: f64 = 1e-300;
+
+/// Kolmogorov-Smirnov and Anderson-Darling statistics for sorted data.
+#[must_use]
+pub fn goodness_of_fit(sorted_data: &[f64], distribution: &FittedDistribution) -> GoodnessOfFit {
+    let n = sorted_data.len();
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for EDF statistics"
+    )]
+    let n_f = n as f64;
+
+    let cdf_values: Vec<f64> = sorted_data.iter().map(|&x| distribution.cdf(x)).collect();
+
+    let mut ks_statistic: f64 = 0.0;
+    for (index, &cdf) in cdf_values.iter().enumerate() {
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Order statistic index casting to f64 for the EDF"
+        )]
+        let rank = index as f64;
+        ks_statistic = ks_statistic
+            .max((rank + 1.0) / n_f - cdf)
+            .max(cdf - rank / n_f);
+    }
+
+    let mut ad_sum = 0.0;
+    for (index, &cdf) in cdf_values.iter().enumerate() {
+        let lower = cdf.clamp(CDF_CLAMP, 1.0);
+        let upper = (1.0 - cdf_values[n - 1 - index]).clamp(CDF_CLAMP, 1.0);
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Order statistic index casting to f64 for AD weights"
+        )]
+        let weight = 2.0_f64.mul_add(index as f64, 1.0);
+        ad_sum = weight.mul_add(lower.ln() + upper.ln(), ad_sum);
+    }
+    let anderson_darling = -n_f - ad_sum / n_f;
+
+    GoodnessOfFit {
+        ks_statistic,
+        ks_p_value: kolmogorov_p_value(ks_statistic, n_f),
+        anderson_darling,
+    }
+}
+
+/// Asymptotic Kolmogorov p-value with Stephens' small-sample correction.
+fn kolmogorov_p_value(statistic: f64, n: f64) -> f64 {
+    let root_n = n.sqrt();
+    let lambda = (0.12 + root_n + 0.11 / root_n) * statistic;
+    if lambda < 1e-3 {
+        return 1.0;
+    }
+    let mut sum = 0.0;
+    let mut sign = 1.0;
+    for term in 1..=100 {
+        let k = f64::from(term);
+        let contribution = sign * (-2.0 * k * k * lambda * lambda).exp();
+        sum += contribution;
+        if contribution.abs() < 1e-12 {
+            break;
+        }
+        sign = -sign;
+    }
+    (2.0 * sum).clamp(0.0, 1.0)
+}
+
+/// Q-Q plot pairs at plotting positions `(i + 0.5) / n`, thinned to at most
+/// [`MAX_QQ_POINTS`] evenly spaced order statistics.
+#[must_use]
+pub fn qq_plot(sorted_data: &[f64], distribution: &FittedDistribution) -> QqPlotData {
+    let n = sorted_data.len();
+    let count = n.min(MAX_QQ_POINTS);
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for plotting positions"
+    )]
+    let n_f = n as f64;
+
+    let mut theoretical = Vec::with_capacity(count);
+    let mut empirical = Vec::with_capacity(count);
+    for point in 0..count {
+        let index = if count == n {
+            point
+        } else {
+            #[allow(
+                clippy::integer_division,
+                reason = "Evenly spaced integer order-statistic indices"
+            )]
+            let thinned = point * (n - 1) / (count - 1).max(1);
+            thinned
+        };
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Order statistic index casting to f64 for plotting positions"
+        )]
+        let probability = (index as f64 + 0.5) / n_f;
+        theoretical.push(distribution.quantile(probability));
+        empirical.push(sorted_data[index]);
+    }
+
+    QqPlotData {
+        theoretical,
+        empirical,
+    }
+}
@@ -0,0 +1,305 @@
+//! One-dimensional Gaussian mixture models fitted by expectation-maximization.
+//!
+//! Means start from K-means (Lloyd) on randomly chosen data points, weights from
+//! equal shares and every sigma from the sample standard deviation. The E-step
+//! works in log space (log-sum-exp) so far-out observations do not underflow, and
+//! variances are floored relative to the sample variance so a component cannot
+//! collapse onto a single point.
+use rand::{RngCore, SeedableRng};
+use rand_pcg::Pcg64;
+use std::f64::consts::TAU;
+
+use super::types::{DistributionError, GmmComponent, GmmResult};
+
+/// Component variance floor relative to the sample variance.
+const VARIANCE_FLOOR_FRACTION: f64 = 1e-6;
+/// Iteration cap for the K-means initialization.
+const KMEANS_MAX_ITERATIONS: usize = 100;
+/// Minimum observations per component.
+const OBSERVATIONS_PER_COMPONENT: usize = 3;
+/// Label used in numerical error messages.
+const FAMILY_LABEL: &str = "Gaussian mixture";
+
+/// Fit an `n_components` Gaussian mixture to `data` by EM.
+///
+/// # Errors
+/// Returns an error for invalid options, too few observations, non-finite or
+/// constant data, or a non-finite likelihood.
+pub fn fit_gaussian_mixture(
+    data: &[f64],
+    n_components: usize,
+    max_iterations: usize,
+    tolerance: f64,
+    seed: u64,
+) -> Result<GmmResult, DistributionError> {
+    if n_components == 0 {
+        return Err(DistributionError::InvalidArgument(
+            "At least one mixture component is required".to_owned(),
+        ));
+    }
+    if max_iterations == 0 || !(tolerance.is_finite() && tolerance > 0.0) {
+        return Err(DistributionError::InvalidArgument(
+            "maxIterations must be positive and tolerance finite and positive".to_owned(),
+        ));
+    }
+    let required = OBSERVATIONS_PER_COMPONENT * n_components;
+    if data.len() < required {
+        return Err(DistributionError::InsufficientData {
+            required,
+            actual: data.len(),
+        });
+    }
+    if data.iter().any(|value| !value.is_finite()) {
+        return Err(DistributionError::NonFinite);
+    }
+
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for moments"
+    )]
+    let n = data.len() as f64;
+    let mean = data.iter().sum::<f64>() / n;
+    let sample_variance = data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    if sample_variance <= 0.0 {
+        return Err(DistributionError::ZeroVariance);
+    }
+    let variance_floor = VARIANCE_FLOOR_FRACTION * sample_variance;
+
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Component count casting to f64 for equal initial weights"
+    )]
+    let mut weights = vec![1.0 / n_components as f64; n_components];
+    let mut means = kmeans_centers(data, n_components, seed);
+    let mut variances = vec![sample_variance; n_components];
+    let mut responsibilities = vec![vec![0.0; n_components]; data.len()];
+
+    let mut log_likelihood = f64::NEG_INFINITY;
+    let mut iterations = 0;
+    let mut converged = false;
+    loop {
+        let previous = log_likelihood;
+        log_likelihood = expectation(data, &weights, &means, &variances, &mut responsibilities);
+        if !log_likelihood.is_finite() {
+            return Err(DistributionError::Numerical {
+                family: FAMILY_LABEL,
+                message: format!("non-finite log-likelihood after {iterations} iterations"),
+            });
+        }
+        if (log_likelihood - previous).abs() <= tolerance * (1.0 + log_likelihood.abs()) {
+            converged = true;
+            break;
+        }
+        if iterations == max_iterations {
+            break;
+        }
+        maximization(
+            data,
+            &responsibilities,
+            variance_floor,
+            &mut weights,
+            &mut means,
+            &mut variances,
+        );
+        iterations += 1;
+    }
+
+    let mut order: Vec<usize> = (0..n_components).collect();
+    order.sort_by(|&a, &b| means[a].total_cmp(&means[b]));
+    let components: Vec<GmmComponent> = order
+        .iter()
+        .map(|&j| GmmComponent {
+            weight: weights[j],
+            mean: means[j],
+            sigma: variances[j].sqrt(),
+        })
+        .collect();
+    let responsibilities = responsibilities
+        .iter()
+        .map(|row| order.iter().map(|&j| row[j]).collect())
+        .collect();
+
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Parameter count casting to f64 for information criteria"
+    )]
+    let parameter_count = (3 * n_components - 1) as f64;
+    Ok(GmmResult {
+        weights: components
+            .iter()
+            .map(|component| component.weight)
+            .collect(),
+        components,
+        log_likelihood,
+        aic: 2.0_f64.mul_add(parameter_count, -2.0 * log_likelihood),
+        bic: parameter_count.mul_add(n.ln(), -2.0 * log_likelihood),
+        responsibilities,
+        iterations,
+        converged,
+    })
+}
+
+/// E-step: fills `responsibilities` and returns the log-likelihood.
+fn expectation(
+    data: &[f64],
+    weights: &[f64],
+    means: &[f64],
+    variances: &[f64],
+    responsibilities: &mut [Vec<f64>],
+) -> f64 {
+    let mut total = 0.0;
+    for (&x, row) in data.iter().zip(responsibilities.iter_mut()) {
+        for (slot, ((weight, mean), variance)) in
+            row.iter_mut().zip(weights.iter().zip(means).zip(variances))
+        {
+            let log_density = (-0.5_f64).mul_add((TAU * variance).ln(), weight.ln());
+            *slot = (-0.5 * (x - mean).powi(2)).mul_add(variance.recip(), log_density);
+        }
+        let peak = row.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let log_sum = peak
+            + row
+                .iter()
+                .map(|log_term| (log_term - peak).exp())
+                .sum::<f64>()
+                .ln();
+        for slot in row.iter_mut() {
+            *slot = (*slot - log_sum).exp();
+        }
+        total += log_sum;
+    }
+    total
+}
+
+/// M-step: re-estimates weights, means and floored variances.
+fn maximization(
+    data: &[f64],
+    responsibilities: &[Vec<f64>],
+    variance_floor: f64,
+    weights: &mut [f64],
+    means: &mut [f64],
+    variances: &mut [f64],
+) {
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for mixing weights"
+    )]
+    let n = data.len() as f64;
+    for j in 0..weights.len() {
+        let mass = responsibilities
+            .iter()
+            .map(|row| row[j])
+            .sum::<f64>()
+            .max(f64::MIN_POSITIVE);
+        let mean = data
+            .iter()
+            .zip(responsibilities)
+            .map(|(x, row)| row[j] * x)
+            .sum::<f64>()
+            / mass;
+        let variance = data
+            .iter()
+            .zip(responsibilities)
+            .map(|(x, row)| row[j] * (x - mean).powi(2))
+            .sum::<f64>()
+            / mass;
+        weights[j] = mass / n;
+        means[j] = mean;
+        variances[j] = variance.max(variance_floor);
+    }
+}
+
+/// Lloyd's K-means from `count` distinct randomly chosen observations.
+fn kmeans_centers(data: &[f64], count: usize, seed: u64) -> Vec<f64> {
+    let mut rng = Pcg64::seed_from_u64(seed);
+    let mut indices: Vec<usize> = (0..data.len()).collect();
+    for slot in 0..count {
+        let remaining = (data.len() - slot) as u64;
+        #[allow(
+            clippy::cast_possible_truncation,
+            reason = "Offset is below the remaining index count"
+        )]
+        let offset = (rng.next_u64() % remaining) as usize;
+        indices.swap(slot, slot + offset);
+    }
+    let mut centers: Vec<f64> = indices[..count].iter().map(|&idx| data[idx]).collect();
+
+    let mut assignments = vec![usize::MAX; data.len()];
+    for _ in 0..KMEANS_MAX_ITERATIONS {
+        let mut changed = false;
+        for (&x, assignment) in data.iter().zip(assignments.iter_mut()) {
+            let nearest = (0..count)
+                .min_by(|&a, &b| (x - centers[a]).abs().total_cmp(&(x - centers[b]).abs()))
+                .unwrap_or(0);
+            if *assignment != nearest {
+                *assignment = nearest;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+        for (j, center) in centers.iter_mut().enumerate() {
+            let (sum, members) = data
+                .iter()
+                .zip(&assignments)
+                .filter(|&(_, &assignment)| assignment == j)
+                .fold((0.0, 0_u32), |(sum, members), (x, _)| {
+                    (sum + x, members + 1)
+                });
+            // An empty cluster keeps its previous center.
+            if members > 0 {
+                *center = sum / f64::from(members);
+            }
+        }
+    }
+    centers
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use statrs::distribution::{ContinuousCDF, Normal};
+
+    /// Stratified quantiles of `N(mean, sigma)`: an exactly shaped sample.
+    fn normal_sample(mean: f64, sigma: f64, count: u32) -> Vec<f64> {
+        let normal = Normal::new(mean, sigma).unwrap();
+        (0..count)
+            .map(|i| normal.inverse_cdf((f64::from(i) + 0.5) / f64::from(count)))
+            .collect()
+    }
+
+    #[test]
+    fn test_bimodal_sample_recovers_components() {
+        let mut data = normal_sample(-2.0, 0.5, 600);
+        data.extend(normal_sample(3.0, 1.0, 400));
+
+        let fit = fit_gaussian_mixture(&data, 2, 500, 1e-10, 7).unwrap();
+        assert!(fit.converged);
+        let [low, high] = [&fit.components[0], &fit.components[1]];
+        assert!((low.weight - 0.6).abs() < 0.01 && (high.weight - 0.4).abs() < 0.01);
+        assert!((low.mean + 2.0).abs() < 0.02 && (high.mean - 3.0).abs() < 0.05);
+        assert!((low.sigma - 0.5).abs() < 0.02 && (high.sigma - 1.0).abs() < 0.05);
+        assert_eq!(fit.weights.len(), 2);
+        assert!(
+            fit.responsibilities
+                .iter()
+                .all(|row| (row.iter().sum::<f64>() - 1.0).abs() < 1e-12)
+        );
+        assert!(fit.responsibilities[0][0] > 0.99 && fit.responsibilities[999][1] > 0.99);
+
+        let single = fit_gaussian_mixture(&data, 1, 500, 1e-10, 7).unwrap();
+        assert!(fit.bic < single.bic && fit.aic < single.aic);
+    }
+
+    #[test]
+    fn test_rejects_invalid_options() {
+        let data = normal_sample(0.0, 1.0, 20);
+        assert!(fit_gaussian_mixture(&data, 0, 100, 1e-8, 0).is_err());
+        assert!(fit_gaussian_mixture(&data, 2, 0, 1e-8, 0).is_err());
+        assert!(matches!(
+            fit_gaussian_mixture(&data, 10, 100, 1e-8, 0),
+            Err(DistributionError::InsufficientData { required: 30, .. })
+        ));
+    }
+}
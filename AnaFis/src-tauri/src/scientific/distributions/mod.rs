@@ -0,0 +1,16 @@
+//! Distribution fitting: maximum likelihood estimation, model ranking and
+//! goodness-of-fit diagnostics for common continuous families, plus Gaussian
+//! mixture models fitted by expectation-maximization.
+pub(crate) mod commands;
+pub mod fitting;
+pub mod goodness_of_fit;
+pub mod mixture;
+mod types;
+
+pub use commands::{DistributionFitter, fit_distributions, fit_gaussian_mixture_model};
+pub use fitting::FittedDistribution;
+pub use types::{
+    DistributionError, DistributionFamily, DistributionFitRequest, DistributionFitResponse,
+    DistributionFitResult, ExcludedDistribution, FittedParameter, GmmComponent, GmmFitRequest,
+    GmmResult, GoodnessOfFit, QqPlotData,
+};
@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::error::{CommandError, codes, numerical_error, validation_error};
+
+/// Continuous distribution families supported by the fitter.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum DistributionFamily {
+    /// Normal (Gaussian) with mean `mu` and standard deviation `sigma`.
+    Normal,
+    /// Log-normal; `mu` and `sigma` are the mean and sd of `ln(x)`.
+    LogNormal,
+    /// Exponential with `rate`.
+    Exponential,
+    /// Gamma with `shape` and `scale`.
+    Gamma,
+    /// Two-parameter Weibull with `shape` and `scale`.
+    Weibull,
+    /// Beta on `(0, 1)` with shape parameters `alpha` and `beta`.
+    Beta,
+    /// Cauchy with `location` and `scale`.
+    Cauchy,
+}
+
+impl DistributionFamily {
+    /// All families, in the order they are tried.
+    pub const ALL: [Self; 7] = [
+        Self::Normal,
+        Self::LogNormal,
+        Self::Exponential,
+        Self::Gamma,
+        Self::Weibull,
+        Self::Beta,
+        Self::Cauchy,
+    ];
+
+    /// Display name used in notes and errors.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Normal => "Normal",
+            Self::LogNormal => "Log-normal",
+            Self::Exponential => "Exponential",
+            Self::Gamma => "Gamma",
+            Self::Weibull => "Weibull",
+            Self::Beta => "Beta",
+            Self::Cauchy => "Cauchy",
+        }
+    }
+
+    /// Parameter names, in the order used by parameter vectors.
+    #[must_use]
+    pub const fn parameter_names(self) -> &'static [&'static str] {
+        match self {
+            Self::Normal | Self::LogNormal => &["mu", "sigma"],
+            Self::Exponential => &["rate"],
+            Self::Gamma | Self::Weibull => &["shape", "scale"],
+            Self::Beta => &["alpha", "beta"],
+            Self::Cauchy => &["location", "scale"],
+        }
+    }
+}
+
+/// A fitted parameter and its asymptotic standard error.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FittedParameter {
+    /// Parameter name.
+    pub name: String,
+    /// Maximum likelihood estimate.
+    pub value: f64,
+    /// Standard error from the inverse observed information matrix, when it is
+    /// positive definite.
+    pub standard_error: Option<f64>,
+}
+
+/// Goodness-of-fit statistics against the fitted distribution.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoodnessOfFit {
+    /// Kolmogorov-Smirnov statistic `D = sup |F_n(x) - F(x)|`.
+    pub ks_statistic: f64,
+    /// Asymptotic KS p-value. Conservative because the parameters were
+    /// estimated from the same data.
+    pub ks_p_value: f64,
+    /// Anderson-Darling statistic `A^2`.
+    pub anderson_darling: f64,
+}
+
+/// Theoretical vs. empirical quantiles for a Q-Q plot.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QqPlotData {
+    /// Quantiles of the fitted distribution at the plotting positions.
+    pub theoretical: Vec<f64>,
+    /// Matching order statistics of the data.
+    pub empirical: Vec<f64>,
+}
+
+/// One candidate distribution fitted to the data.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DistributionFitResult {
+    /// Distribution family.
+    pub family: DistributionFamily,
+    /// 1-based rank by AIC (1 = best).
+    pub rank: usize,
+    /// Fitted parameters.
+    pub parameters: Vec<FittedParameter>,
+    /// Maximized log-likelihood.
+    pub log_likelihood: f64,
+    /// Akaike information criterion.
+    pub aic: f64,
+    /// Bayesian information criterion.
+    pub bic: f64,
+    /// AIC difference to the best candidate.
+    pub delta_aic: f64,
+    /// Goodness-of-fit statistics.
+    pub goodness_of_fit: GoodnessOfFit,
+    /// Q-Q plot data.
+    pub qq_plot: QqPlotData,
+}
+
+/// A family that was not fitted, and why.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExcludedDistribution {
+    /// Distribution family.
+    pub family: DistributionFamily,
+    /// Reason it was skipped.
+    pub reason: String,
+}
+
+/// Request payload for `fit_distributions`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DistributionFitRequest {
+    /// Sample values.
+    pub data: Vec<f64>,
+    /// Optional subset of families to try (default: all).
+    #[serde(default)]
+    pub families: Option<Vec<DistributionFamily>>,
+}
+
+/// Ranked distribution fits for a data column.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DistributionFitResponse {
+    /// Number of observations used.
+    pub sample_size: usize,
+    /// Successful fits, best first.
+    pub fits: Vec<DistributionFitResult>,
+    /// Families skipped because of the data domain or a numerical failure.
+    pub excluded: Vec<ExcludedDistribution>,
+}
+
+/// Request payload for `fit_gaussian_mixture`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GmmFitRequest {
+    /// Sample values.
+    pub data: Vec<f64>,
+    /// Number of mixture components.
+    pub n_components: usize,
+    /// Maximum EM iterations (default 500).
+    #[serde(default)]
+    pub max_iterations: Option<usize>,
+    /// Convergence tolerance on the log-likelihood change, relative to
+    /// `1 + |log-likelihood|` (default 1e-8).
+    #[serde(default)]
+    pub tolerance: Option<f64>,
+    /// Seed for the K-means initialization (default 0).
+    #[serde(default)]
+    pub seed: u64,
+}
+
+/// One Gaussian component of a fitted mixture.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GmmComponent {
+    /// Mixing weight.
+    pub weight: f64,
+    /// Component mean.
+    pub mean: f64,
+    /// Component standard deviation.
+    pub sigma: f64,
+}
+
+/// Gaussian mixture fitted by expectation-maximization.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GmmResult {
+    /// Components, sorted by mean.
+    pub components: Vec<GmmComponent>,
+    /// Mixing weights in component order, for plotting the mixture PDF.
+    pub weights: Vec<f64>,
+    /// Log-likelihood at convergence.
+    pub log_likelihood: f64,
+    /// Akaike information criterion.
+    pub aic: f64,
+    /// Bayesian information criterion.
+    pub bic: f64,
+    /// Posterior component probabilities: `[observation][component]`.
+    pub responsibilities: Vec<Vec<f64>>,
+    /// EM iterations performed.
+    pub iterations: usize,
+    /// Whether the log-likelihood change fell below the tolerance.
+    pub converged: bool,
+}
+
+/// Errors raised by distribution fitting.
+#[derive(Debug, Error)]
+pub enum DistributionError {
+    /// Not enough observations for maximum likelihood.
+    #[error("At least {required} observations are required, got {actual}")]
+    InsufficientData {
+        /// Minimum sample size.
+        required: usize,
+        /// Provided sample size.
+        actual: usize,
+    },
+    /// Input contains NaN or infinite values.
+    #[error("Data contains non-finite values")]
+    NonFinite,
+    /// A fitting option is out of range.
+    #[error("{0}")]
+    InvalidArgument(String),
+    /// Data has no spread, so scale parameters are undefined.
+    #[error("Data is constant; distribution parameters are not identifiable")]
+    ZeroVariance,
+    /// Numerical failure while maximizing the likelihood.
+    #[error("{family} fit failed: {message}")]
+    Numerical {
+        /// Family being fitted.
+        family: &'static str,
+        /// Failure description.
+        message: String,
+    },
+}
+
+impl From<DistributionError> for CommandError {
+    fn from(error: DistributionError) -> Self {
+        let detail = error.to_string();
+        match error {
+            DistributionError::InsufficientData { .. } => Self::Validation {
+                code: codes::INSUFFICIENT_DATA,
+                field: Some("data".to_owned()),
+                detail,
+            },
+            DistributionError::NonFinite => Self::Validation {
+                code: codes::INVALID_INPUT,
+                field: Some("data".to_owned()),
+                detail,
+            },
+            DistributionError::InvalidArgument(_) => validation_error(detail, None),
+            DistributionError::ZeroVariance => Self::Numerical {
+                code: codes::ZERO_VARIANCE,
+                detail,
+            },
+            DistributionError::Numerical { .. } => numerical_error(detail),
+        }
+    }
+}
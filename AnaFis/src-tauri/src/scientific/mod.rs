@@ -1,4 +1,9 @@
-//! Scientific computation module containing curve fitting, uncertainty propagation, and math function tools.
+//! Scientific computation module containing curve fitting, distribution fitting, statistical tests, uncertainty propagation, time-series, preprocessing and math function tools.
 pub mod curve_fitting;
+pub mod distributions;
 pub mod math_functions;
+pub mod preprocessing;
+pub mod primitives;
+pub mod statistics;
+pub mod time_series;
 pub mod uncertainty_propagation;
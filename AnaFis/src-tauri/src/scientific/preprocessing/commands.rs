@@ -0,0 +1,225 @@
+use super::imputation::{DataImputationEngine, ImputedColumn, validate_aligned};
+use super::transforms::fit_transform;
+use super::types::{
+    ImputationStrategy, PreprocessColumnRequest, PreprocessColumnResponse,
+    PreprocessColumnsRequest, PreprocessingChangeLog, PreprocessingError, TransformationSpec,
+    WinsorizeRequest, WinsorizeResponse,
+};
+use crate::error::{CommandError, CommandResult};
+
+/// Impute and optionally transform a single column.
+///
+/// # Errors
+/// Returns an error if the column is entirely missing, still contains gaps
+/// when a transformation is requested without imputation, or the
+/// transformation cannot be fitted.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn preprocess_column(
+    request: PreprocessColumnRequest,
+) -> CommandResult<PreprocessColumnResponse> {
+    preprocess_column_inner(&request).map_err(CommandError::from)
+}
+
+/// Impute and optionally transform several aligned columns.
+///
+/// # Errors
+/// Returns an error if columns are misaligned, k-NN has too few complete rows,
+/// or any per-column step fails.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn preprocess_columns(
+    request: PreprocessColumnsRequest,
+) -> CommandResult<Vec<PreprocessColumnResponse>> {
+    preprocess_columns_inner(&request).map_err(CommandError::from)
+}
+
+/// Clamp a column to percentile bounds.
+///
+/// # Errors
+/// Returns an error for invalid percentiles or a column without finite values.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn winsorize_data(request: WinsorizeRequest) -> CommandResult<WinsorizeResponse> {
+    let data: Vec<f64> = request
+        .values
+        .iter()
+        .map(|value| value.unwrap_or(f64::NAN))
+        .collect();
+    let (values, report) =
+        DataImputationEngine::winsorize(&data, request.lower_percentile, request.upper_percentile)
+            .map_err(CommandError::from)?;
+    Ok(WinsorizeResponse {
+        values: values
+            .into_iter()
+            .map(|value| value.is_finite().then_some(value))
+            .collect(),
+        report,
+    })
+}
+
+pub fn preprocess_column_inner(
+    request: &PreprocessColumnRequest,
+) -> Result<PreprocessColumnResponse, PreprocessingError> {
+    let imputed = match request.imputation {
+        Some(strategy) => Some(DataImputationEngine::impute_column(
+            &request.values,
+            strategy,
+            0,
+        )?),
+        None => None,
+    };
+    finish_column(
+        &request.values,
+        imputed,
+        request.imputation,
+        request.transformation,
+        0,
+    )
+}
+
+pub fn preprocess_columns_inner(
+    request: &PreprocessColumnsRequest,
+) -> Result<Vec<PreprocessColumnResponse>, PreprocessingError> {
+    validate_aligned(&request.columns)?;
+
+    let imputed: Vec<Option<ImputedColumn>> = match request.imputation {
+        Some(ImputationStrategy::Knn { k }) => {
+            DataImputationEngine::impute_knn(&request.columns, k)?
+                .into_iter()
+                .map(Some)
+                .collect()
+        }
+        Some(strategy) => request
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                DataImputationEngine::impute_column(column, strategy, index).map(Some)
+            })
+            .collect::<Result<_, _>>()?,
+        None => vec![None; request.columns.len()],
+    };
+
+    request
+        .columns
+        .iter()
+        .zip(imputed)
+        .enumerate()
+        .map(|(index, (column, imputed_column))| {
+            finish_column(
+                column,
+                imputed_column,
+                request.imputation,
+                request.transformation,
+                index,
+            )
+        })
+        .collect()
+}
+
+fn finish_column(
+    original: &[Option<f64>],
+    imputed: Option<ImputedColumn>,
+    strategy: Option<ImputationStrategy>,
+    transformation: Option<TransformationSpec>,
+    column_index: usize,
+) -> Result<PreprocessColumnResponse, PreprocessingError> {
+    let (values, imputed_indices, mut notes) = if let Some(column) = imputed {
+        (column.values, column.imputed_indices, column.notes)
+    } else {
+        let values: Vec<f64> = original
+            .iter()
+            .map(|value| value.unwrap_or(f64::NAN))
+            .collect();
+        (values, Vec::new(), Vec::new())
+    };
+    let imputed_values = imputed_indices.iter().map(|&idx| values[idx]).collect();
+
+    let (values, parameters) = if let Some(spec) = transformation {
+        if values.iter().any(|value| !value.is_finite()) {
+            return Err(PreprocessingError::Validation(format!(
+                "Column {column_index} still has missing values; choose an imputation strategy before transforming"
+            )));
+        }
+        let (transformed, parameters, note) = fit_transform(&values, spec)?;
+        notes.extend(note);
+        (transformed, Some(parameters))
+    } else {
+        (values, None)
+    };
+
+    Ok(PreprocessColumnResponse {
+        values,
+        change_log: PreprocessingChangeLog {
+            imputation: strategy,
+            imputed_indices,
+            imputed_values,
+            transformation: parameters,
+            notes,
+        },
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use crate::scientific::preprocessing::transforms::inverse;
+    use crate::scientific::preprocessing::types::TransformationKind;
+
+    #[test]
+    fn test_impute_transform_inverse_round_trip() {
+        let request = PreprocessColumnRequest {
+            values: vec![Some(-2.0), None, Some(4.0), Some(9.0), None, Some(0.5)],
+            imputation: Some(ImputationStrategy::LinearInterpolation),
+            transformation: Some(TransformationSpec {
+                kind: TransformationKind::Log,
+                auto_offset: true,
+            }),
+        };
+        let response = preprocess_column_inner(&request).unwrap();
+        let log = &response.change_log;
+        assert_eq!(log.imputed_indices, vec![1, 4]);
+        let parameters = log.transformation.unwrap();
+        assert!((parameters.offset - 3.0).abs() < 1e-12);
+
+        let restored = inverse(&response.values, &parameters);
+        let expected = [-2.0, 1.0, 4.0, 9.0, 4.75, 0.5];
+        for (value, target) in restored.iter().zip(expected) {
+            assert!((value - target).abs() < 1e-9, "{value} vs {target}");
+        }
+    }
+
+    #[test]
+    fn test_multi_column_knn_then_standardize() {
+        let request = PreprocessColumnsRequest {
+            columns: vec![
+                vec![Some(1.0), Some(2.0), Some(3.0), Some(4.0)],
+                vec![Some(10.0), Some(20.0), None, Some(40.0)],
+            ],
+            imputation: Some(ImputationStrategy::Knn { k: 2 }),
+            transformation: Some(TransformationSpec {
+                kind: TransformationKind::Standardize,
+                auto_offset: false,
+            }),
+        };
+        let responses = preprocess_columns_inner(&request).unwrap();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[1].change_log.imputed_indices, vec![2]);
+        assert!((responses[1].change_log.imputed_values[0] - 30.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_transform_without_imputation_rejects_gaps() {
+        let request = PreprocessColumnRequest {
+            values: vec![Some(1.0), None],
+            imputation: None,
+            transformation: Some(TransformationSpec {
+                kind: TransformationKind::Sqrt,
+                auto_offset: false,
+            }),
+        };
+        assert!(preprocess_column_inner(&request).is_err());
+    }
+}
@@ -0,0 +1,890 @@
+//! Missing-value imputation for spreadsheet columns.
+//!
+//! A value is treated as missing when it is `None` or non-finite. Single-column
+//! strategies only look at the column itself; k-NN uses the other columns of the
+//! same row to locate similar complete rows.
+//!
+//! Winsorization is the robust alternative to dropping outliers: values beyond
+//! chosen percentiles are pulled in to those percentiles. The Box-Cox power
+//! transform, with lambda estimated by maximum likelihood, brings skewed
+//! positive data closer to normal.
+use std::cmp::Ordering;
+
+use super::transforms::{box_cox, box_cox_log_likelihood};
+use super::types::{
+    BoxCoxResult, ImputationStrategy, PolynomialFeatures, PreprocessingError,
+    QuantileTransformTarget, QuantileTransformer, WinsorizeReport,
+};
+use crate::scientific::primitives::ScalarMinimization;
+use crate::scientific::primitives::descriptive::{
+    mean, median, quantile_sorted, sample_variance, sorted,
+};
+use crate::scientific::statistics::HypothesisTestingEngine;
+
+/// Bound on the magnitude of an estimated Box-Cox lambda.
+const BOX_COX_LAMBDA_LIMIT: f64 = 5.0;
+/// Relative tolerance of the Box-Cox lambda search.
+const BOX_COX_TOLERANCE: f64 = 1e-8;
+/// Iteration limit of the Box-Cox lambda search.
+const BOX_COX_MAX_ITERATIONS: usize = 200;
+
+/// Column after imputation, with the positions that were filled.
+#[derive(Debug, Clone)]
+pub struct ImputedColumn {
+    /// Fully populated column.
+    pub values: Vec<f64>,
+    /// Indices that were missing and have been filled.
+    pub imputed_indices: Vec<usize>,
+    /// Notes about edge handling.
+    pub notes: Vec<String>,
+}
+
+/// Missing-value imputation strategies.
+pub struct DataImputationEngine;
+
+fn observed(value: Option<f64>) -> Option<f64> {
+    value.filter(|v| v.is_finite())
+}
+
+impl DataImputationEngine {
+    /// Impute a single column with a column-local strategy.
+    ///
+    /// `column_index` is only used in error messages.
+    ///
+    /// # Errors
+    /// Returns an error if the column has no finite values, or if k-NN is
+    /// requested (it needs the other columns of the row).
+    pub fn impute_column(
+        values: &[Option<f64>],
+        strategy: ImputationStrategy,
+        column_index: usize,
+    ) -> Result<ImputedColumn, PreprocessingError> {
+        let present: Vec<f64> = values.iter().filter_map(|v| observed(*v)).collect();
+        if present.is_empty() {
+            return Err(PreprocessingError::AllMissing(column_index));
+        }
+        let imputed_indices: Vec<usize> = values
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, v)| observed(*v).is_none().then_some(idx))
+            .collect();
+        let mut notes = Vec::new();
+
+        let filled = match strategy {
+            ImputationStrategy::Mean => {
+                let fill = mean(&present).unwrap_or(0.0);
+                fill_constant(values, fill)
+            }
+            ImputationStrategy::Median => {
+                let fill = median(&present).unwrap_or(0.0);
+                fill_constant(values, fill)
+            }
+            ImputationStrategy::Mode => fill_constant(values, mode(&present)),
+            ImputationStrategy::LinearInterpolation => {
+                let (filled, edge_filled) = interpolate_linear(values);
+                if edge_filled {
+                    notes.push(
+                        "Leading/trailing gaps filled with the nearest observed value".to_owned(),
+                    );
+                }
+                filled
+            }
+            ImputationStrategy::Locf => {
+                let (filled, backfilled) = carry_forward(values);
+                if backfilled > 0 {
+                    notes.push(format!(
+                        "{backfilled} leading value(s) back-filled from the first observation"
+                    ));
+                }
+                filled
+            }
+            ImputationStrategy::Knn { .. } => {
+                return Err(PreprocessingError::Validation(
+                    "k-NN imputation requires multiple columns; use preprocess_columns".to_owned(),
+                ));
+            }
+        };
+
+        Ok(ImputedColumn {
+            values: filled,
+            imputed_indices,
+            notes,
+        })
+    }
+
+    /// Impute every column of a row-aligned table using k-nearest neighbours.
+    ///
+    /// Distances are Euclidean over the columns observed in the incomplete row,
+    /// each standardized by its standard deviation over the complete rows, so
+    /// columns on different scales contribute comparably.
+    ///
+    /// # Errors
+    /// Returns an error if `k` is zero, if columns have different lengths, if a
+    /// column is entirely missing, or if fewer than `k` complete rows exist.
+    pub fn impute_knn(
+        columns: &[Vec<Option<f64>>],
+        k: usize,
+    ) -> Result<Vec<ImputedColumn>, PreprocessingError> {
+        if k == 0 {
+            return Err(PreprocessingError::Validation(
+                "k-NN imputation needs k >= 1".to_owned(),
+            ));
+        }
+        let row_count = validate_aligned(columns)?;
+        for (column_index, column) in columns.iter().enumerate() {
+            if column.iter().all(|v| observed(*v).is_none()) {
+                return Err(PreprocessingError::AllMissing(column_index));
+            }
+        }
+
+        let complete_rows: Vec<usize> = (0..row_count)
+            .filter(|&row| columns.iter().all(|column| observed(column[row]).is_some()))
+            .collect();
+        if complete_rows.len() < k {
+            return Err(PreprocessingError::InsufficientCompleteRows {
+                required: k,
+                available: complete_rows.len(),
+            });
+        }
+
+        let scales: Vec<f64> = columns
+            .iter()
+            .map(|column| {
+                let complete: Vec<f64> = complete_rows
+                    .iter()
+                    .filter_map(|&row| observed(column[row]))
+                    .collect();
+                sample_variance(&complete)
+                    .map(f64::sqrt)
+                    .filter(|sd| *sd > 0.0)
+                    .unwrap_or(1.0)
+            })
+            .collect();
+
+        let mut results: Vec<ImputedColumn> = columns
+            .iter()
+            .map(|column| ImputedColumn {
+                values: column.iter().map(|v| observed(*v).unwrap_or(0.0)).collect(),
+                imputed_indices: Vec::new(),
+                notes: Vec::new(),
+            })
+            .collect();
+
+        for row in 0..row_count {
+            let missing: Vec<usize> = (0..columns.len())
+                .filter(|&col| observed(columns[col][row]).is_none())
+                .collect();
+            if missing.is_empty() {
+                continue;
+            }
+
+            let mut distances: Vec<(f64, usize)> = complete_rows
+                .iter()
+                .map(|&candidate| {
+                    let distance = (0..columns.len())
+                        .filter_map(|col| {
+                            let here = observed(columns[col][row])?;
+                            let there = observed(columns[col][candidate])?;
+                            Some(((here - there) / scales[col]).powi(2))
+                        })
+                        .sum::<f64>();
+                    (distance, candidate)
+                })
+                .collect();
+            distances.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+            let neighbours = &distances[..k];
+
+            if missing.len() == columns.len() {
+                results[missing[0]].notes.push(format!(
+                    "Row {row} has no observed values; neighbours chosen arbitrarily"
+                ));
+            }
+
+            for &col in &missing {
+                let neighbour_values: Vec<f64> = neighbours
+                    .iter()
+                    .filter_map(|&(_, candidate)| observed(columns[col][candidate]))
+                    .collect();
+                results[col].values[row] = mean(&neighbour_values).unwrap_or(0.0);
+                results[col].imputed_indices.push(row);
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Clamp values outside the `lower_percentile` and `upper_percentile`
+    /// empirical percentiles (0-100, type 7 interpolation) to those bounds.
+    ///
+    /// Non-finite values are left in place and ignored by the percentiles.
+    ///
+    /// # Errors
+    /// Returns an error unless `0 <= lower_percentile < upper_percentile <= 100`,
+    /// or if `data` has no finite values.
+    pub fn winsorize(
+        data: &[f64],
+        lower_percentile: f64,
+        upper_percentile: f64,
+    ) -> Result<(Vec<f64>, WinsorizeReport), PreprocessingError> {
+        if !(0.0..=100.0).contains(&lower_percentile)
+            || !(0.0..=100.0).contains(&upper_percentile)
+            || lower_percentile >= upper_percentile
+        {
+            return Err(PreprocessingError::Validation(format!(
+                "Percentiles must satisfy 0 <= lower < upper <= 100, got {lower_percentile} and {upper_percentile}"
+            )));
+        }
+        let present: Vec<f64> = data.iter().copied().filter(|v| v.is_finite()).collect();
+        if present.is_empty() {
+            return Err(PreprocessingError::Validation(
+                "Winsorization needs at least one finite value".to_owned(),
+            ));
+        }
+
+        let present = sorted(&present);
+        let lower_bound = quantile_sorted(&present, lower_percentile / 100.0).unwrap_or(present[0]);
+        let upper_bound = quantile_sorted(&present, upper_percentile / 100.0)
+            .unwrap_or(present[present.len() - 1]);
+        let mut lower_clamped = 0;
+        let mut upper_clamped = 0;
+        let values = data
+            .iter()
+            .map(|&value| {
+                if !value.is_finite() {
+                    value
+                } else if value < lower_bound {
+                    lower_clamped += 1;
+                    lower_bound
+                } else if value > upper_bound {
+                    upper_clamped += 1;
+                    upper_bound
+                } else {
+                    value
+                }
+            })
+            .collect();
+
+        Ok((
+            values,
+            WinsorizeReport {
+                lower_bound,
+                upper_bound,
+                lower_clamped,
+                upper_clamped,
+            },
+        ))
+    }
+
+    /// Box-Cox transform strictly positive `data` with `lambda`, or with the
+    /// maximum-likelihood lambda in `[-5, 5]` (Brent's method) when `None`.
+    ///
+    /// # Errors
+    /// Returns an error for fewer than two values, non-finite or non-positive
+    /// values, constant data, or a non-finite `lambda`.
+    pub fn box_cox_transform(
+        data: &[f64],
+        lambda: Option<f64>,
+    ) -> Result<BoxCoxResult, PreprocessingError> {
+        if data.len() < 2 {
+            return Err(PreprocessingError::Validation(
+                "Box-Cox transformation needs at least two values".to_owned(),
+            ));
+        }
+        if data.iter().any(|value| !value.is_finite()) {
+            return Err(PreprocessingError::Validation(
+                "Box-Cox transformation needs finite values".to_owned(),
+            ));
+        }
+        let minimum = data.iter().copied().fold(f64::INFINITY, f64::min);
+        if minimum <= 0.0 {
+            return Err(PreprocessingError::OutOfDomain {
+                kind: "Box-Cox",
+                requirement: "strictly positive",
+                minimum,
+            });
+        }
+        if data.iter().all(|value| value.total_cmp(&minimum).is_eq()) {
+            return Err(PreprocessingError::ZeroSpread("Box-Cox transformation"));
+        }
+
+        let (lambda, lambda_estimated) = match lambda {
+            Some(lambda) if !lambda.is_finite() => {
+                return Err(PreprocessingError::Validation(format!(
+                    "Box-Cox lambda must be finite, got {lambda}"
+                )));
+            }
+            Some(lambda) => (lambda, false),
+            None => {
+                let optimum = ScalarMinimization::brent(
+                    |candidate| -box_cox_log_likelihood(data, candidate),
+                    -BOX_COX_LAMBDA_LIMIT,
+                    BOX_COX_LAMBDA_LIMIT,
+                    BOX_COX_TOLERANCE,
+                    BOX_COX_MAX_ITERATIONS,
+                );
+                (optimum.x, true)
+            }
+        };
+
+        let values: Vec<f64> = data.iter().map(|&value| box_cox(value, lambda)).collect();
+        let normality_p_value = HypothesisTestingEngine::anderson_darling_normality(&values)
+            .ok()
+            .map(|test| test.p_value);
+        Ok(BoxCoxResult {
+            log_likelihood: box_cox_log_likelihood(data, lambda),
+            values,
+            lambda,
+            lambda_estimated,
+            is_log: lambda == 0.0,
+            normality_p_value,
+        })
+    }
+
+    /// Fit a quantile transformation of `data` onto `target` from
+    /// `n_quantiles` evenly spaced empirical quantiles (type 7 interpolation),
+    /// capped at the number of finite values.
+    ///
+    /// Non-finite values are ignored by the fit.
+    ///
+    /// # Errors
+    /// Returns an error if `n_quantiles < 2` or `data` has fewer than two
+    /// finite values.
+    pub fn fit_quantile_transform(
+        data: &[f64],
+        target: QuantileTransformTarget,
+        n_quantiles: usize,
+    ) -> Result<QuantileTransformer, PreprocessingError> {
+        if n_quantiles < 2 {
+            return Err(PreprocessingError::Validation(format!(
+                "Quantile transformation needs at least two quantiles, got {n_quantiles}"
+            )));
+        }
+        let present: Vec<f64> = data.iter().copied().filter(|v| v.is_finite()).collect();
+        if present.len() < 2 {
+            return Err(PreprocessingError::Validation(
+                "Quantile transformation needs at least two finite values".to_owned(),
+            ));
+        }
+
+        let present = sorted(&present);
+        let count = n_quantiles.min(present.len());
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Quantile index casting to f64 for its probability"
+        )]
+        let references: Vec<f64> = (0..count)
+            .map(|index| index as f64 / (count - 1) as f64)
+            .collect();
+        let quantiles = references
+            .iter()
+            .map(|&probability| quantile_sorted(&present, probability).unwrap_or(present[0]))
+            .collect();
+        Ok(QuantileTransformer {
+            target,
+            quantiles,
+            references,
+        })
+    }
+
+    /// Map `data` onto `target` through its own empirical quantiles; see
+    /// [`DataImputationEngine::fit_quantile_transform`] and
+    /// [`QuantileTransformer::transform`].
+    ///
+    /// # Errors
+    /// Returns an error if `n_quantiles < 2` or `data` has fewer than two
+    /// finite values.
+    pub fn quantile_transform(
+        data: &[f64],
+        target: QuantileTransformTarget,
+        n_quantiles: usize,
+    ) -> Result<Vec<f64>, PreprocessingError> {
+        let transformer = Self::fit_quantile_transform(data, target, n_quantiles)?;
+        Ok(data
+            .iter()
+            .map(|&value| transformer.transform(value))
+            .collect())
+    }
+
+    /// Expand `data`, given as rows of observations, into every monomial of
+    /// the inputs with total degree `1..=degree`, ordered by degree and then
+    /// lexicographically (`x1, x2, x1^2, x1*x2, x2^2, ...`).
+    ///
+    /// `include_bias` prepends a constant column named `1`; `interaction_only`
+    /// keeps only products of distinct inputs (no `xi^k` with `k > 1`).
+    ///
+    /// # Errors
+    /// Returns an error for a zero degree, no rows or inputs, or ragged or
+    /// non-finite rows.
+    pub fn polynomial_features(
+        data: &[Vec<f64>],
+        degree: usize,
+        include_bias: bool,
+        interaction_only: bool,
+    ) -> Result<PolynomialFeatures, PreprocessingError> {
+        if degree == 0 {
+            return Err(PreprocessingError::Validation(
+                "Polynomial degree must be at least 1".to_owned(),
+            ));
+        }
+        let inputs = data.first().map_or(0, Vec::len);
+        if inputs == 0 {
+            return Err(PreprocessingError::Validation(
+                "Polynomial features need at least one row and one input".to_owned(),
+            ));
+        }
+        for (index, row) in data.iter().enumerate() {
+            if row.len() != inputs {
+                return Err(PreprocessingError::Validation(format!(
+                    "Row {index} has {} values, expected {inputs}",
+                    row.len()
+                )));
+            }
+            if row.iter().any(|value| !value.is_finite()) {
+                return Err(PreprocessingError::Validation(format!(
+                    "Row {index} contains non-finite values"
+                )));
+            }
+        }
+
+        let mut terms: Vec<Vec<usize>> = Vec::new();
+        if include_bias {
+            terms.push(Vec::new());
+        }
+        for term_degree in 1..=degree {
+            let mut term = Vec::with_capacity(term_degree);
+            monomials(
+                inputs,
+                term_degree,
+                interaction_only,
+                0,
+                &mut term,
+                &mut terms,
+            );
+        }
+
+        let matrix = data
+            .iter()
+            .map(|row| {
+                terms
+                    .iter()
+                    .map(|term| term.iter().map(|&input| row[input]).product())
+                    .collect()
+            })
+            .collect();
+        let feature_names = terms.iter().map(|term| monomial_name(term)).collect();
+        Ok(PolynomialFeatures {
+            matrix,
+            feature_names,
+        })
+    }
+}
+
+/// Ensure every column has the same length and return it.
+///
+/// # Errors
+/// Returns an error if no columns are given or lengths differ.
+pub fn validate_aligned(columns: &[Vec<Option<f64>>]) -> Result<usize, PreprocessingError> {
+    let Some(first) = columns.first() else {
+        return Err(PreprocessingError::Validation(
+            "At least one column is required".to_owned(),
+        ));
+    };
+    let row_count = first.len();
+    if let Some((index, column)) = columns
+        .iter()
+        .enumerate()
+        .find(|(_, column)| column.len() != row_count)
+    {
+        return Err(PreprocessingError::Validation(format!(
+            "Column {index} has {} rows, expected {row_count}",
+            column.len()
+        )));
+    }
+    Ok(row_count)
+}
+
+fn fill_constant(values: &[Option<f64>], fill: f64) -> Vec<f64> {
+    values
+        .iter()
+        .map(|value| observed(*value).unwrap_or(fill))
+        .collect()
+}
+
+/// Most frequent value; ties resolve to the smallest value.
+fn mode(present: &[f64]) -> f64 {
+    let mut sorted_values = present.to_vec();
+    sorted_values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+    let mut best_value = sorted_values[0];
+    let mut best_count = 0;
+    let mut run_start = 0;
+    for idx in 1..=sorted_values.len() {
+        let run_ended = idx == sorted_values.len()
+            || sorted_values[idx].total_cmp(&sorted_values[run_start]) != Ordering::Equal;
+        if run_ended {
+            let count = idx - run_start;
+            if count > best_count {
+                best_count = count;
+                best_value = sorted_values[run_start];
+            }
+            run_start = idx;
+        }
+    }
+    best_value
+}
+
+/// Linear interpolation over index; edges take the nearest observed value.
+fn interpolate_linear(values: &[Option<f64>]) -> (Vec<f64>, bool) {
+    let anchors: Vec<(usize, f64)> = values
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, v)| observed(*v).map(|value| (idx, value)))
+        .collect();
+    let mut edge_filled = false;
+    let mut next_anchor = 0;
+
+    let filled = (0..values.len())
+        .map(|idx| {
+            while next_anchor < anchors.len() && anchors[next_anchor].0 < idx {
+                next_anchor += 1;
+            }
+            if let Some(&(anchor_idx, value)) = anchors.get(next_anchor)
+                && anchor_idx == idx
+            {
+                return value;
+            }
+            match (
+                next_anchor.checked_sub(1).map(|prev| anchors[prev]),
+                anchors.get(next_anchor),
+            ) {
+                (Some((left_idx, left)), Some(&(right_idx, right))) => {
+                    #[allow(
+                        clippy::cast_precision_loss,
+                        reason = "Row index casting to f64 for interpolation weight"
+                    )]
+                    let weight = (idx - left_idx) as f64 / (right_idx - left_idx) as f64;
+                    weight.mul_add(right - left, left)
+                }
+                (Some((_, edge)), None) | (None, Some(&(_, edge))) => {
+                    edge_filled = true;
+                    edge
+                }
+                (None, None) => 0.0,
+            }
+        })
+        .collect();
+
+    (filled, edge_filled)
+}
+
+/// Last observation carried forward; leading gaps are back-filled.
+fn carry_forward(values: &[Option<f64>]) -> (Vec<f64>, usize) {
+    let first = values.iter().find_map(|v| observed(*v)).unwrap_or(0.0);
+    let mut last: Option<f64> = None;
+    let mut backfilled = 0;
+    let filled = values
+        .iter()
+        .map(|value| {
+            if let Some(present) = observed(*value) {
+                last = Some(present);
+                present
+            } else if let Some(previous) = last {
+                previous
+            } else {
+                backfilled += 1;
+                first
+            }
+        })
+        .collect();
+    (filled, backfilled)
+}
+
+/// Append every non-decreasing (strictly increasing if `distinct`) sequence
+/// of `remaining` more input indices, starting at `first`, to `terms`.
+fn monomials(
+    inputs: usize,
+    remaining: usize,
+    distinct: bool,
+    first: usize,
+    term: &mut Vec<usize>,
+    terms: &mut Vec<Vec<usize>>,
+) {
+    if remaining == 0 {
+        terms.push(term.clone());
+        return;
+    }
+    for input in first..inputs {
+        term.push(input);
+        monomials(
+            inputs,
+            remaining - 1,
+            distinct,
+            if distinct { input + 1 } else { input },
+            term,
+            terms,
+        );
+        term.pop();
+    }
+}
+
+/// Name of the monomial over sorted input indices, e.g. `x1^2*x3`; `1` for
+/// the empty product.
+fn monomial_name(term: &[usize]) -> String {
+    if term.is_empty() {
+        return "1".to_owned();
+    }
+    let mut factors: Vec<String> = Vec::new();
+    let mut start = 0;
+    while start < term.len() {
+        let power = term[start..]
+            .iter()
+            .take_while(|&&input| input == term[start])
+            .count();
+        factors.push(if power == 1 {
+            format!("x{}", term[start] + 1)
+        } else {
+            format!("x{}^{power}", term[start] + 1)
+        });
+        start += power;
+    }
+    factors.join("*")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use statrs::distribution::{ContinuousCDF, Normal};
+
+    fn column() -> Vec<Option<f64>> {
+        vec![
+            Some(1.0),
+            None,
+            Some(3.0),
+            Some(3.0),
+            Some(f64::NAN),
+            Some(10.0),
+        ]
+    }
+
+    #[test]
+    fn test_mean_imputation() {
+        let result =
+            DataImputationEngine::impute_column(&column(), ImputationStrategy::Mean, 0).unwrap();
+        assert_eq!(result.imputed_indices, vec![1, 4]);
+        assert!((result.values[1] - 4.25).abs() < 1e-12);
+        assert!((result.values[4] - 4.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_median_imputation() {
+        let result =
+            DataImputationEngine::impute_column(&column(), ImputationStrategy::Median, 0).unwrap();
+        assert!((result.values[1] - 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_mode_imputation() {
+        let result =
+            DataImputationEngine::impute_column(&column(), ImputationStrategy::Mode, 0).unwrap();
+        assert!((result.values[4] - 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_linear_interpolation_with_edges() {
+        let values = vec![None, Some(2.0), None, None, Some(8.0), None];
+        let result = DataImputationEngine::impute_column(
+            &values,
+            ImputationStrategy::LinearInterpolation,
+            0,
+        )
+        .unwrap();
+        assert_eq!(result.values, vec![2.0, 2.0, 4.0, 6.0, 8.0, 8.0]);
+        assert_eq!(result.notes.len(), 1);
+    }
+
+    #[test]
+    fn test_locf_imputation() {
+        let values = vec![None, Some(5.0), None, Some(7.0), None];
+        let result =
+            DataImputationEngine::impute_column(&values, ImputationStrategy::Locf, 0).unwrap();
+        assert_eq!(result.values, vec![5.0, 5.0, 5.0, 7.0, 7.0]);
+        assert_eq!(result.imputed_indices, vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn test_knn_imputation_uses_similar_rows() {
+        let x = vec![
+            Some(1.0),
+            Some(2.0),
+            Some(3.0),
+            Some(10.0),
+            Some(11.0),
+            Some(2.1),
+        ];
+        let y = vec![
+            Some(1.0),
+            Some(2.0),
+            Some(3.0),
+            Some(100.0),
+            Some(110.0),
+            None,
+        ];
+        let result = DataImputationEngine::impute_knn(&[x, y], 2).unwrap();
+        assert_eq!(result[1].imputed_indices, vec![5]);
+        assert!((result[1].values[5] - 2.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_rejects_all_missing_and_sparse_knn() {
+        let empty = vec![None, Some(f64::NAN)];
+        assert!(matches!(
+            DataImputationEngine::impute_column(&empty, ImputationStrategy::Mean, 3),
+            Err(PreprocessingError::AllMissing(3))
+        ));
+
+        let x = vec![Some(1.0), None, Some(3.0)];
+        let y = vec![Some(1.0), Some(2.0), None];
+        assert!(matches!(
+            DataImputationEngine::impute_knn(&[x, y], 2),
+            Err(PreprocessingError::InsufficientCompleteRows {
+                required: 2,
+                available: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn test_winsorize_clamps_to_percentiles() {
+        let data = [1.0, 2.0, 3.0, 4.0, f64::NAN, 5.0, 6.0, 7.0, 8.0, 9.0, 100.0];
+        let (values, report) = DataImputationEngine::winsorize(&data, 10.0, 90.0).unwrap();
+        assert!((report.lower_bound - 1.9).abs() < 1e-12);
+        assert!((report.upper_bound - 18.1).abs() < 1e-12);
+        assert_eq!((report.lower_clamped, report.upper_clamped), (1, 1));
+        assert!((values[0] - 1.9).abs() < 1e-12);
+        assert!((values[10] - 18.1).abs() < 1e-12);
+        assert!(values[4].is_nan());
+        assert!((values[5] - 5.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_winsorize_rejects_invalid_percentiles() {
+        let data = [1.0, 2.0, 3.0];
+        assert!(DataImputationEngine::winsorize(&data, 50.0, 50.0).is_err());
+        assert!(DataImputationEngine::winsorize(&data, -1.0, 90.0).is_err());
+        assert!(DataImputationEngine::winsorize(&data, 10.0, 101.0).is_err());
+        assert!(DataImputationEngine::winsorize(&[f64::NAN], 10.0, 90.0).is_err());
+    }
+
+    #[test]
+    fn test_box_cox_estimates_log_for_lognormal_data() {
+        let standard_normal = Normal::new(0.0, 1.0).unwrap();
+        let data: Vec<f64> = (1..=40)
+            .map(|rank| {
+                standard_normal
+                    .inverse_cdf((f64::from(rank) - 0.5) / 40.0)
+                    .mul_add(0.8, 1.0)
+                    .exp()
+            })
+            .collect();
+        let result = DataImputationEngine::box_cox_transform(&data, None).unwrap();
+        assert!(result.lambda_estimated);
+        assert!(result.lambda.abs() < 0.1, "lambda = {}", result.lambda);
+        assert!(result.normality_p_value.unwrap() > 0.5);
+        for (value, original) in result.values.iter().zip(&data) {
+            assert!((result.back_transform(*value) - original).abs() < 1e-9 * original);
+        }
+    }
+
+    #[test]
+    fn test_box_cox_fixed_lambda_and_domain() {
+        let result = DataImputationEngine::box_cox_transform(&[1.0, 2.0, 4.0], Some(1.0)).unwrap();
+        for (value, expected) in result.values.iter().zip([0.0, 1.0, 3.0]) {
+            assert!((value - expected).abs() < 1e-12);
+        }
+        assert!(!result.lambda_estimated && !result.is_log);
+        assert!(result.normality_p_value.is_none());
+        assert!(matches!(
+            DataImputationEngine::box_cox_transform(&[0.0, 1.0], None),
+            Err(PreprocessingError::OutOfDomain { .. })
+        ));
+        assert!(DataImputationEngine::box_cox_transform(&[2.0, 2.0], None).is_err());
+    }
+
+    #[test]
+    fn test_quantile_transform_uniform_averages_ties() {
+        let data = [3.0, 1.0, 2.0, 2.0, 5.0, f64::NAN];
+        let values =
+            DataImputationEngine::quantile_transform(&data, QuantileTransformTarget::Uniform, 100)
+                .unwrap();
+        // Quantiles at 0, 1/4, 1/2, 3/4, 1 are 1, 2, 2, 3, 5.
+        for (value, expected) in values.iter().zip([0.75, 0.0, 0.375, 0.375, 1.0]) {
+            assert!((value - expected).abs() < 1e-12);
+        }
+        assert!(values[5].is_nan());
+    }
+
+    #[test]
+    fn test_quantile_transform_normal_clips_future_values() {
+        let data: Vec<f64> = (1..=21).map(|value| f64::from(value).powi(3)).collect();
+        let values =
+            DataImputationEngine::quantile_transform(&data, QuantileTransformTarget::Normal, 11)
+                .unwrap();
+        assert!(values[10].abs() < 1e-12);
+        assert!((values[0] + values[20]).abs() < 1e-9);
+        assert!(values[20] > 5.0 && values[20].is_finite());
+
+        let transformer = DataImputationEngine::fit_quantile_transform(
+            &data,
+            QuantileTransformTarget::Uniform,
+            11,
+        )
+        .unwrap();
+        assert!(transformer.transform(-100.0).abs() < f64::EPSILON);
+        assert!((transformer.transform(1e6) - 1.0).abs() < f64::EPSILON);
+        assert!(
+            DataImputationEngine::quantile_transform(&data, QuantileTransformTarget::Uniform, 1)
+                .is_err()
+        );
+        assert!(
+            DataImputationEngine::quantile_transform(&[1.0], QuantileTransformTarget::Normal, 10)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_polynomial_features_full_degree_two() {
+        let data = vec![vec![2.0, 3.0], vec![-1.0, 0.5]];
+        let features = DataImputationEngine::polynomial_features(&data, 2, true, false).unwrap();
+        assert_eq!(
+            features.feature_names,
+            ["1", "x1", "x2", "x1^2", "x1*x2", "x2^2"]
+        );
+        assert_eq!(features.matrix[0], vec![1.0, 2.0, 3.0, 4.0, 6.0, 9.0]);
+        assert_eq!(features.matrix[1], vec![1.0, -1.0, 0.5, 1.0, -0.5, 0.25]);
+
+        let cubic = DataImputationEngine::polynomial_features(&data[..1], 3, false, false).unwrap();
+        assert_eq!(cubic.feature_names.len(), 9);
+        assert_eq!(cubic.feature_names[6], "x1^2*x2");
+        assert!((cubic.matrix[0][6] - 12.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_polynomial_features_interaction_only() {
+        let data = vec![vec![2.0, 3.0, 5.0]];
+        let features = DataImputationEngine::polynomial_features(&data, 3, false, true).unwrap();
+        assert_eq!(
+            features.feature_names,
+            ["x1", "x2", "x3", "x1*x2", "x1*x3", "x2*x3", "x1*x2*x3"]
+        );
+        assert_eq!(
+            features.matrix[0],
+            vec![2.0, 3.0, 5.0, 6.0, 10.0, 15.0, 30.0]
+        );
+        assert!(DataImputationEngine::polynomial_features(&data, 0, true, false).is_err());
+        assert!(
+            DataImputationEngine::polynomial_features(&[vec![1.0], vec![]], 2, true, false)
+                .is_err()
+        );
+    }
+}
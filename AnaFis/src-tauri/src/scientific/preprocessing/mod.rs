@@ -0,0 +1,15 @@
+//! Column preprocessing: missing-value imputation, winsorization, invertible
+//! transformations, quantile transformations and polynomial feature expansion.
+pub(crate) mod commands;
+pub mod imputation;
+pub mod transforms;
+mod types;
+
+pub use commands::{preprocess_column, preprocess_columns, winsorize_data};
+pub use imputation::{DataImputationEngine, ImputedColumn};
+pub use types::{
+    BoxCoxResult, ImputationStrategy, PolynomialFeatures, PreprocessColumnRequest,
+    PreprocessColumnResponse, PreprocessColumnsRequest, PreprocessingChangeLog, PreprocessingError,
+    QuantileTransformTarget, QuantileTransformer, TransformationKind, TransformationParameters,
+    TransformationSpec, WinsorizeReport, WinsorizeRequest, WinsorizeResponse,
+};
@@ -0,0 +1,276 @@
+//! Variance-stabilizing and scaling transformations with invertible parameters,
+//! including the Box-Cox power family and quantile transformations.
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use super::types::{
+    BoxCoxResult, PreprocessingError, QuantileTransformTarget, QuantileTransformer,
+    TransformationKind, TransformationParameters, TransformationSpec,
+};
+use crate::scientific::primitives::descriptive::{mean, quantile_sorted, sample_variance, sorted};
+
+/// Fit the transformation to `values` and apply it.
+///
+/// # Errors
+/// Returns an error if log/sqrt is requested on out-of-domain data without
+/// `auto_offset`, or if a scaling transform is applied to a constant column.
+pub fn fit_transform(
+    values: &[f64],
+    spec: TransformationSpec,
+) -> Result<(Vec<f64>, TransformationParameters, Option<String>), PreprocessingError> {
+    let minimum = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let mut note = None;
+
+    let parameters = match spec.kind {
+        TransformationKind::Log => {
+            let offset = if minimum > 0.0 {
+                0.0
+            } else if spec.auto_offset {
+                let offset = 1.0 - minimum;
+                note = Some(format!(
+                    "Added offset {offset} before log so the minimum maps to 0"
+                ));
+                offset
+            } else {
+                return Err(PreprocessingError::OutOfDomain {
+                    kind: "Log",
+                    requirement: "strictly positive",
+                    minimum,
+                });
+            };
+            identity_scaled(TransformationKind::Log, offset)
+        }
+        TransformationKind::Sqrt => {
+            let offset = if minimum >= 0.0 {
+                0.0
+            } else if spec.auto_offset {
+                note = Some(format!(
+                    "Added offset {} before sqrt so the minimum maps to 0",
+                    -minimum
+                ));
+                -minimum
+            } else {
+                return Err(PreprocessingError::OutOfDomain {
+                    kind: "Sqrt",
+                    requirement: "non-negative",
+                    minimum,
+                });
+            };
+            identity_scaled(TransformationKind::Sqrt, offset)
+        }
+        TransformationKind::Standardize => {
+            let center = mean(values).unwrap_or(0.0);
+            let scale = sample_variance(values).map_or(0.0, f64::sqrt);
+            if scale <= 0.0 || !scale.is_finite() {
+                return Err(PreprocessingError::ZeroSpread("standardization"));
+            }
+            TransformationParameters {
+                kind: TransformationKind::Standardize,
+                offset: 0.0,
+                center,
+                scale,
+            }
+        }
+        TransformationKind::RobustScale => {
+            let ordered = sorted(values);
+            let center = quantile_sorted(&ordered, 0.5).unwrap_or(0.0);
+            let scale = quantile_sorted(&ordered, 0.75).unwrap_or(0.0)
+                - quantile_sorted(&ordered, 0.25).unwrap_or(0.0);
+            if scale <= 0.0 || !scale.is_finite() {
+                return Err(PreprocessingError::ZeroSpread("robust scaling"));
+            }
+            TransformationParameters {
+                kind: TransformationKind::RobustScale,
+                offset: 0.0,
+                center,
+                scale,
+            }
+        }
+    };
+
+    Ok((apply(values, &parameters), parameters, note))
+}
+
+const fn identity_scaled(kind: TransformationKind, offset: f64) -> TransformationParameters {
+    TransformationParameters {
+        kind,
+        offset,
+        center: 0.0,
+        scale: 1.0,
+    }
+}
+
+/// Apply previously fitted transformation parameters.
+#[must_use]
+pub fn apply(values: &[f64], parameters: &TransformationParameters) -> Vec<f64> {
+    values
+        .iter()
+        .map(|&value| {
+            let shifted = value + parameters.offset;
+            let mapped = match parameters.kind {
+                TransformationKind::Log => shifted.ln(),
+                TransformationKind::Sqrt => shifted.sqrt(),
+                TransformationKind::Standardize | TransformationKind::RobustScale => shifted,
+            };
+            (mapped - parameters.center) / parameters.scale
+        })
+        .collect()
+}
+
+/// Map transformed values back to the original scale.
+#[must_use]
+pub fn inverse(values: &[f64], parameters: &TransformationParameters) -> Vec<f64> {
+    values
+        .iter()
+        .map(|&value| {
+            let unscaled = value.mul_add(parameters.scale, parameters.center);
+            let unmapped = match parameters.kind {
+                TransformationKind::Log => unscaled.exp(),
+                TransformationKind::Sqrt => unscaled * unscaled,
+                TransformationKind::Standardize | TransformationKind::RobustScale => unscaled,
+            };
+            unmapped - parameters.offset
+        })
+        .collect()
+}
+
+impl BoxCoxResult {
+    /// Map a transformed value back to the original scale.
+    #[must_use]
+    pub fn back_transform(&self, value: f64) -> f64 {
+        box_cox_inverse(value, self.lambda)
+    }
+}
+
+/// Box-Cox transform `(x^lambda - 1) / lambda`, or `ln(x)` for `lambda = 0`.
+#[must_use]
+pub fn box_cox(value: f64, lambda: f64) -> f64 {
+    if lambda == 0.0 {
+        value.ln()
+    } else {
+        (lambda * value.ln()).exp_m1() / lambda
+    }
+}
+
+/// Inverse Box-Cox transform; NaN where `lambda * y + 1 <= 0` has no preimage.
+#[must_use]
+pub fn box_cox_inverse(value: f64, lambda: f64) -> f64 {
+    if lambda == 0.0 {
+        value.exp()
+    } else if lambda.mul_add(value, 1.0) <= 0.0 {
+        f64::NAN
+    } else {
+        ((lambda * value).ln_1p() / lambda).exp()
+    }
+}
+
+/// Concentrated Box-Cox log-likelihood `-(n/2) ln(sigma^2) + (lambda - 1) sum(ln x)`
+/// of strictly positive `values`, with `sigma^2` the biased variance of the
+/// transformed values.
+#[must_use]
+pub fn box_cox_log_likelihood(values: &[f64], lambda: f64) -> f64 {
+    let transformed: Vec<f64> = values.iter().map(|&value| box_cox(value, lambda)).collect();
+    let center = mean(&transformed).unwrap_or(0.0);
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for the likelihood"
+    )]
+    let n = values.len() as f64;
+    let variance = transformed
+        .iter()
+        .map(|value| (value - center).powi(2))
+        .sum::<f64>()
+        / n;
+    let log_sum: f64 = values.iter().map(|value| value.ln()).sum();
+    (lambda - 1.0).mul_add(log_sum, -0.5 * n * variance.ln())
+}
+
+/// Distance of the probit argument from 0 and 1, keeping normal-target
+/// values finite (about 5.2 standard deviations).
+const PROBIT_BOUND: f64 = 1e-7;
+
+impl QuantileTransformer {
+    /// Map `value` onto the target distribution.
+    ///
+    /// Values outside the training range are clipped to the extreme
+    /// quantiles; a value equal to several tied quantiles takes the average of
+    /// their probabilities. Non-finite values are returned unchanged.
+    #[must_use]
+    pub fn transform(&self, value: f64) -> f64 {
+        if !value.is_finite() {
+            return value;
+        }
+        let last = self.quantiles.len() - 1;
+        let clipped = value.clamp(self.quantiles[0], self.quantiles[last]);
+        let first_tied = self
+            .quantiles
+            .partition_point(|&quantile| quantile < clipped);
+        let past_tied = self
+            .quantiles
+            .partition_point(|&quantile| quantile <= clipped);
+        let probability = if first_tied < past_tied {
+            f64::midpoint(self.references[first_tied], self.references[past_tied - 1])
+        } else {
+            let (left, right) = (past_tied - 1, first_tied);
+            let weight =
+                (clipped - self.quantiles[left]) / (self.quantiles[right] - self.quantiles[left]);
+            weight.mul_add(
+                self.references[right] - self.references[left],
+                self.references[left],
+            )
+        };
+        match self.target {
+            QuantileTransformTarget::Uniform => probability,
+            QuantileTransformTarget::Normal => Normal::new(0.0, 1.0).map_or(f64::NAN, |normal| {
+                normal.inverse_cdf(probability.clamp(PROBIT_BOUND, 1.0 - PROBIT_BOUND))
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    const fn spec(kind: TransformationKind, auto_offset: bool) -> TransformationSpec {
+        TransformationSpec { kind, auto_offset }
+    }
+
+    #[test]
+    fn test_log_requires_positive_or_offset() {
+        let data = [0.0, 1.0, 3.0];
+        assert!(fit_transform(&data, spec(TransformationKind::Log, false)).is_err());
+        let (values, params, note) =
+            fit_transform(&data, spec(TransformationKind::Log, true)).unwrap();
+        assert!((params.offset - 1.0).abs() < 1e-12);
+        assert!(values[0].abs() < 1e-12);
+        assert!(note.is_some());
+    }
+
+    #[test]
+    fn test_standardize_and_robust_scale() {
+        let data = [2.0, 4.0, 6.0, 8.0, 100.0];
+        let (z, params, _) =
+            fit_transform(&data, spec(TransformationKind::Standardize, false)).unwrap();
+        assert!(mean(&z).unwrap().abs() < 1e-12);
+        assert!((sample_variance(&z).unwrap() - 1.0).abs() < 1e-12);
+        assert!((params.center - 24.0).abs() < 1e-12);
+
+        let (robust, robust_params, _) =
+            fit_transform(&data, spec(TransformationKind::RobustScale, false)).unwrap();
+        assert!((robust_params.center - 6.0).abs() < 1e-12);
+        assert!((robust_params.scale - 4.0).abs() < 1e-12);
+        assert!(robust[2].abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sqrt_inverse_and_constant_column() {
+        let data = [-1.0, 0.0, 3.0];
+        let (values, params, _) =
+            fit_transform(&data, spec(TransformationKind::Sqrt, true)).unwrap();
+        for (restored, original) in inverse(&values, &params).iter().zip(data) {
+            assert!((restored - original).abs() < 1e-12);
+        }
+        assert!(fit_transform(&[5.0, 5.0], spec(TransformationKind::Standardize, false)).is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::error::{CommandError, codes, validation_error};
+
+/// Strategy used to fill missing (null or non-finite) values.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "method")]
+pub enum ImputationStrategy {
+    /// Replace missing values with the column mean.
+    Mean,
+    /// Replace missing values with the column median.
+    Median,
+    /// Replace missing values with the most frequent observed value.
+    Mode,
+    /// Interpolate linearly between the nearest observed neighbours.
+    LinearInterpolation,
+    /// Carry the last observed value forward.
+    Locf,
+    /// Average the column value of the `k` nearest complete rows (multi-column only).
+    Knn {
+        /// Number of neighbours to average.
+        k: usize,
+    },
+}
+
+/// Transformation family applied after imputation.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TransformationKind {
+    /// Natural logarithm, `ln(x + offset)`.
+    Log,
+    /// Square root, `sqrt(x + offset)`.
+    Sqrt,
+    /// Z-score, `(x - mean) / sd`.
+    Standardize,
+    /// Robust scaling, `(x - median) / IQR`.
+    RobustScale,
+}
+
+/// Requested transformation for a column.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformationSpec {
+    /// Transformation family.
+    pub kind: TransformationKind,
+    /// For log/sqrt: shift the data into the valid domain instead of failing.
+    #[serde(default)]
+    pub auto_offset: bool,
+}
+
+/// Fitted transformation parameters, sufficient to invert the transform.
+///
+/// The forward map is `(g(x + offset) - center) / scale`, where `g` is `ln`,
+/// `sqrt`, or the identity depending on `kind`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformationParameters {
+    /// Transformation family.
+    pub kind: TransformationKind,
+    /// Additive shift applied before `g` (non-zero only for auto-offset log/sqrt).
+    pub offset: f64,
+    /// Location subtracted after `g` (mean or median for scaling transforms).
+    pub center: f64,
+    /// Divisor applied after centering (sd or IQR for scaling transforms).
+    pub scale: f64,
+}
+
+/// Record of what preprocessing did to a column.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreprocessingChangeLog {
+    /// Imputation strategy that was applied, if any.
+    pub imputation: Option<ImputationStrategy>,
+    /// Indices of the values that were imputed.
+    pub imputed_indices: Vec<usize>,
+    /// Values written at `imputed_indices`, before any transformation.
+    pub imputed_values: Vec<f64>,
+    /// Fitted transformation parameters, if a transformation was applied.
+    pub transformation: Option<TransformationParameters>,
+    /// Human-readable notes (edge handling, offsets, etc.).
+    pub notes: Vec<String>,
+}
+
+/// Processed column and its change log.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreprocessColumnResponse {
+    /// Processed values, same length as the input column.
+    pub values: Vec<f64>,
+    /// Change log for this column.
+    pub change_log: PreprocessingChangeLog,
+}
+
+/// Request for preprocessing a single column.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreprocessColumnRequest {
+    /// Column values; `null` marks a missing value.
+    pub values: Vec<Option<f64>>,
+    /// Optional imputation strategy (k-NN is not available for a single column).
+    #[serde(default)]
+    pub imputation: Option<ImputationStrategy>,
+    /// Optional transformation applied after imputation.
+    #[serde(default)]
+    pub transformation: Option<TransformationSpec>,
+}
+
+/// Request for preprocessing several aligned columns.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreprocessColumnsRequest {
+    /// Columns of equal length; `null` marks a missing value.
+    pub columns: Vec<Vec<Option<f64>>>,
+    /// Optional imputation strategy applied to every column.
+    #[serde(default)]
+    pub imputation: Option<ImputationStrategy>,
+    /// Optional transformation applied to every column after imputation.
+    #[serde(default)]
+    pub transformation: Option<TransformationSpec>,
+}
+
+/// Box-Cox transformed data with the parameters needed to invert it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoxCoxResult {
+    /// Transformed values.
+    pub values: Vec<f64>,
+    /// Transformation parameter, estimated or as given.
+    pub lambda: f64,
+    /// Whether `lambda` was estimated by maximum likelihood.
+    pub lambda_estimated: bool,
+    /// Whether the transform is the `lambda = 0` logarithm, inverted by `exp`.
+    pub is_log: bool,
+    /// Concentrated log-likelihood at `lambda`.
+    pub log_likelihood: f64,
+    /// Anderson-Darling normality p-value of the transformed values, when
+    /// there are enough of them.
+    pub normality_p_value: Option<f64>,
+}
+
+/// Bounds and clamp counts of a winsorization.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WinsorizeReport {
+    /// Value at the lower percentile; smaller values were raised to it.
+    pub lower_bound: f64,
+    /// Value at the upper percentile; larger values were lowered to it.
+    pub upper_bound: f64,
+    /// Number of values raised to the lower bound.
+    pub lower_clamped: usize,
+    /// Number of values lowered to the upper bound.
+    pub upper_clamped: usize,
+}
+
+/// Request for winsorizing a column.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WinsorizeRequest {
+    /// Column values; `null` marks a missing value, which is kept as is.
+    pub values: Vec<Option<f64>>,
+    /// Lower percentile in `[0, 100)`.
+    pub lower_percentile: f64,
+    /// Upper percentile in `(lower_percentile, 100]`.
+    pub upper_percentile: f64,
+}
+
+/// Winsorized column and its report.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WinsorizeResponse {
+    /// Winsorized values, `null` where the input was missing.
+    pub values: Vec<Option<f64>>,
+    /// Bounds and clamp counts.
+    pub report: WinsorizeReport,
+}
+
+/// Distribution that a quantile transformation maps onto.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum QuantileTransformTarget {
+    /// Uniform on `[0, 1]`.
+    Uniform,
+    /// Standard normal, through the probit of the uniform value.
+    Normal,
+}
+
+/// Fitted quantile transformation: empirical quantiles of the training data
+/// paired with their cumulative probabilities.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuantileTransformer {
+    /// Target distribution.
+    pub target: QuantileTransformTarget,
+    /// Non-decreasing training quantiles (type 7 interpolation).
+    pub quantiles: Vec<f64>,
+    /// Evenly spaced probabilities in `[0, 1]` matching `quantiles`.
+    pub references: Vec<f64>,
+}
+
+/// Polynomial design matrix with a name for each feature column.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolynomialFeatures {
+    /// Rows of observations, one value per feature.
+    pub matrix: Vec<Vec<f64>>,
+    /// Feature names such as `1`, `x1`, `x1^2` or `x1*x2`, with inputs
+    /// numbered from 1.
+    pub feature_names: Vec<String>,
+}
+
+/// Errors raised by column preprocessing.
+#[derive(Debug, Error)]
+pub enum PreprocessingError {
+    /// Column has no observed values to impute from.
+    #[error("Column {0} contains no finite values")]
+    AllMissing(usize),
+    /// Log/sqrt requested on data outside the domain without auto-offset.
+    #[error(
+        "{kind} transform requires {requirement} data (minimum is {minimum}); enable auto offset to shift the data"
+    )]
+    OutOfDomain {
+        /// Transformation name.
+        kind: &'static str,
+        /// Domain requirement description.
+        requirement: &'static str,
+        /// Smallest value in the column.
+        minimum: f64,
+    },
+    /// k-NN imputation does not have enough complete rows.
+    #[error("k-NN imputation needs at least {required} complete rows, found {available}")]
+    InsufficientCompleteRows {
+        /// Requested neighbour count.
+        required: usize,
+        /// Complete rows available.
+        available: usize,
+    },
+    /// Scaling transform cannot be applied to a constant column.
+    #[error("Column has zero spread; cannot apply {0}")]
+    ZeroSpread(&'static str),
+    /// Generic input validation failure.
+    #[error("{0}")]
+    Validation(String),
+}
+
+impl From<PreprocessingError> for CommandError {
+    fn from(error: PreprocessingError) -> Self {
+        let detail = error.to_string();
+        match error {
+            PreprocessingError::AllMissing(_)
+            | PreprocessingError::InsufficientCompleteRows { .. } => Self::Validation {
+                code: codes::INSUFFICIENT_DATA,
+                field: None,
+                detail,
+            },
+            PreprocessingError::ZeroSpread(_) => Self::Numerical {
+                code: codes::ZERO_VARIANCE,
+                detail,
+            },
+            PreprocessingError::OutOfDomain { .. } | PreprocessingError::Validation(_) => {
+                validation_error(detail, None)
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Descriptive statistics over finite `f64` slices.
+use std::cmp::Ordering;
+
+/// Median absolute deviation of a standard normal variable.
+const NORMAL_MAD: f64 = 0.674_489_750_196_081_7;
+
+/// Arithmetic mean, or `None` for an empty slice.
+#[must_use]
+pub fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for mean calculation"
+    )]
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Unbiased sample variance (n - 1 denominator), or `None` with fewer than two values.
+#[must_use]
+pub fn sample_variance(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let center = mean(values)?;
+    let sum_sq: f64 = values.iter().map(|value| (value - center).powi(2)).sum();
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for variance calculation"
+    )]
+    Some(sum_sq / (values.len() - 1) as f64)
+}
+
+/// Copy and sort values ascending (NaN-tolerant total order).
+#[must_use]
+pub fn sorted(values: &[f64]) -> Vec<f64> {
+    let mut copy = values.to_vec();
+    copy.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    copy
+}
+
+/// Quantile of already-sorted data using linear interpolation between order
+/// statistics (Hyndman & Fan type 7, the spreadsheet `PERCENTILE.INC` rule).
+#[must_use]
+pub fn quantile_sorted(sorted_values: &[f64], probability: f64) -> Option<f64> {
+    let count = sorted_values.len();
+    if count == 0 || !(0.0..=1.0).contains(&probability) {
+        return None;
+    }
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for quantile position"
+    )]
+    let position = probability * (count - 1) as f64;
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "Position is non-negative and bounded by the slice length"
+    )]
+    let lower_index = position.floor() as usize;
+    let upper_index = (lower_index + 1).min(count - 1);
+    let fraction = position - position.floor();
+    Some(fraction.mul_add(
+        sorted_values[upper_index] - sorted_values[lower_index],
+        sorted_values[lower_index],
+    ))
+}
+
+/// Median of unsorted data.
+#[must_use]
+pub fn median(values: &[f64]) -> Option<f64> {
+    quantile_sorted(&sorted(values), 0.5)
+}
+
+/// Median absolute deviation of `values` from their median, or `None` for an
+/// empty slice.
+#[must_use]
+pub fn median_absolute_deviation(values: &[f64]) -> Option<f64> {
+    let center = median(values)?;
+    let deviations: Vec<f64> = values.iter().map(|value| (value - center).abs()).collect();
+    median(&deviations)
+}
+
+/// Modified z-score `0.6745 (value - center) / mad` of Iglewicz and Hoaglin;
+/// infinite for any deviation when `mad` is zero.
+#[must_use]
+pub fn modified_z_score(value: f64, center: f64, mad: f64) -> f64 {
+    let deviation = value - center;
+    if mad > 0.0 {
+        NORMAL_MAD * deviation / mad
+    } else if deviation == 0.0 {
+        0.0
+    } else {
+        f64::INFINITY.copysign(deviation)
+    }
+}
+
+/// Modified z-scores of `values` about their own median and median absolute
+/// deviation, or `None` for an empty slice.
+#[must_use]
+pub fn modified_z_scores(values: &[f64]) -> Option<Vec<f64>> {
+    let center = median(values)?;
+    let mad = median_absolute_deviation(values)?;
+    Some(
+        values
+            .iter()
+            .map(|&value| modified_z_score(value, center, mad))
+            .collect(),
+    )
+}
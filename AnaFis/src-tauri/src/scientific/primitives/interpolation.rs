@@ -0,0 +1,127 @@
+//! One-dimensional interpolation on strictly increasing abscissae.
+//!
+//! Piecewise-linear, previous-value, nearest-neighbour and natural cubic
+//! spline evaluation. Points outside the abscissa range are evaluated on the
+//! outermost interval (linear/cubic extrapolation); callers that need a
+//! different policy check the range themselves.
+
+/// Interpolation routines over sorted, duplicate-free abscissae.
+pub struct Interpolation;
+
+impl Interpolation {
+    /// Index `i` of the interval `[xs[i], xs[i + 1]]` containing `x`, clamped
+    /// to the first and last interval. Requires `xs.len() >= 2`.
+    #[must_use]
+    pub fn interval(xs: &[f64], x: f64) -> usize {
+        xs.partition_point(|&knot| knot <= x)
+            .saturating_sub(1)
+            .min(xs.len().saturating_sub(2))
+    }
+
+    /// Left index and weight of the right neighbour for linear interpolation:
+    /// the value is `(1 - w) * ys[i] + w * ys[i + 1]`.
+    #[must_use]
+    pub fn linear_weights(xs: &[f64], x: f64) -> (usize, f64) {
+        let i = Self::interval(xs, x);
+        (i, (x - xs[i]) / (xs[i + 1] - xs[i]))
+    }
+
+    /// Piecewise-linear interpolation.
+    #[must_use]
+    pub fn linear(xs: &[f64], ys: &[f64], x: f64) -> f64 {
+        let (i, weight) = Self::linear_weights(xs, x);
+        weight.mul_add(ys[i + 1] - ys[i], ys[i])
+    }
+
+    /// Index of the last knot at or before `x` (the first knot if `x` precedes it).
+    #[must_use]
+    pub fn previous_index(xs: &[f64], x: f64) -> usize {
+        xs.partition_point(|&knot| knot <= x).saturating_sub(1)
+    }
+
+    /// Index of the knot closest to `x`; ties go to the earlier knot.
+    #[must_use]
+    pub fn nearest_index(xs: &[f64], x: f64) -> usize {
+        let after = xs.partition_point(|&knot| knot < x);
+        if after == 0 {
+            return 0;
+        }
+        if after == xs.len() {
+            return xs.len() - 1;
+        }
+        if x - xs[after - 1] <= xs[after] - x {
+            after - 1
+        } else {
+            after
+        }
+    }
+
+    /// Second derivatives of the natural cubic spline through `(xs, ys)`.
+    ///
+    /// Solves the tridiagonal system with the Thomas algorithm; both end
+    /// curvatures are zero. Requires `xs.len() >= 2`.
+    #[must_use]
+    pub fn natural_spline_second_derivatives(xs: &[f64], ys: &[f64]) -> Vec<f64> {
+        let n = xs.len();
+        let mut second = vec![0.0; n];
+        if n < 3 {
+            return second;
+        }
+        // Forward sweep: `diagonal` and `rhs` hold the eliminated system.
+        let mut diagonal = vec![0.0; n];
+        let mut rhs = vec![0.0; n];
+        for i in 1..n - 1 {
+            let h_left = xs[i] - xs[i - 1];
+            let h_right = xs[i + 1] - xs[i];
+            let slope_change = (ys[i + 1] - ys[i]) / h_right - (ys[i] - ys[i - 1]) / h_left;
+            diagonal[i] = 2.0 * (h_left + h_right);
+            rhs[i] = 6.0 * slope_change;
+            if i > 1 {
+                let factor = h_left / diagonal[i - 1];
+                diagonal[i] = (-factor).mul_add(h_left, diagonal[i]);
+                rhs[i] = (-factor).mul_add(rhs[i - 1], rhs[i]);
+            }
+        }
+        for i in (1..n - 1).rev() {
+            let h_right = xs[i + 1] - xs[i];
+            second[i] = h_right.mul_add(-second[i + 1], rhs[i]) / diagonal[i];
+        }
+        second
+    }
+
+    /// Evaluate the cubic spline with the given second derivatives at `x`.
+    #[must_use]
+    pub fn cubic(xs: &[f64], ys: &[f64], second_derivatives: &[f64], x: f64) -> f64 {
+        let i = Self::interval(xs, x);
+        let width = xs[i + 1] - xs[i];
+        let left = (xs[i + 1] - x) / width;
+        let right = (x - xs[i]) / width;
+        let curvature = (left.powi(3) - left).mul_add(
+            second_derivatives[i],
+            (right.powi(3) - right) * second_derivatives[i + 1],
+        );
+        (curvature * width * width).mul_add(1.0 / 6.0, left.mul_add(ys[i], right * ys[i + 1]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_natural_spline_reproduces_knots_and_lines() {
+        let xs = [0.0, 1.0, 2.5, 4.0];
+        let ys = [1.0, -2.0, 0.5, 3.0];
+        let second = Interpolation::natural_spline_second_derivatives(&xs, &ys);
+        assert!(second[0].abs() < 1e-15 && second[3].abs() < 1e-15);
+        for (x, y) in xs.iter().zip(ys) {
+            assert!((Interpolation::cubic(&xs, &ys, &second, *x) - y).abs() < 1e-12);
+        }
+        // A straight line is its own spline.
+        let line: Vec<f64> = xs.iter().map(|x| 2.0_f64.mul_add(*x, 1.0)).collect();
+        let flat = Interpolation::natural_spline_second_derivatives(&xs, &line);
+        assert!((Interpolation::cubic(&xs, &line, &flat, 1.7) - 4.4).abs() < 1e-12);
+        assert_eq!(Interpolation::nearest_index(&xs, 1.75), 1);
+        assert_eq!(Interpolation::previous_index(&xs, 2.4), 1);
+    }
+}
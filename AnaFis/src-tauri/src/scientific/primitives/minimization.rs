@@ -0,0 +1,163 @@
+//! Scalar minimization.
+//!
+//! Brent's method: parabolic interpolation through the three best points so
+//! far, falling back to a golden-section step whenever the parabola leaves the
+//! bracket or fails to shrink the step. Converges superlinearly on smooth
+//! functions and never worse than golden-section search.
+
+/// Golden-section fraction `(3 - sqrt(5)) / 2`.
+const GOLDEN_FRACTION: f64 = 0.381_966_011_250_105_1;
+/// Absolute tolerance floor for iterates near zero.
+const ABSOLUTE_TOLERANCE: f64 = 1e-10;
+
+/// Minimum found by a scalar minimizer.
+#[derive(Debug, Clone, Copy)]
+pub struct ScalarMinimum {
+    /// Location of the minimum.
+    pub x: f64,
+    /// Function value at `x`.
+    pub value: f64,
+    /// Whether the bracket shrank below the tolerance.
+    pub converged: bool,
+}
+
+/// Scalar minimization routines.
+pub struct ScalarMinimization;
+
+impl ScalarMinimization {
+    /// Minimize `function` over `[lower, upper]` by Brent's method.
+    ///
+    /// Converges when the bracket around the iterate is within `tolerance`
+    /// (relative to the magnitude of the iterate). The end points themselves
+    /// are never evaluated, so a minimum on the boundary is approached to
+    /// within the tolerance.
+    #[must_use]
+    pub fn brent<F>(
+        function: F,
+        lower: f64,
+        upper: f64,
+        tolerance: f64,
+        max_iterations: usize,
+    ) -> ScalarMinimum
+    where
+        F: Fn(f64) -> f64,
+    {
+        let mut low = lower.min(upper);
+        let mut high = lower.max(upper);
+        let mut best = GOLDEN_FRACTION.mul_add(high - low, low);
+        let (mut second, mut third) = (best, best);
+        let mut f_best = function(best);
+        let (mut f_second, mut f_third) = (f_best, f_best);
+        let mut step: f64 = 0.0;
+        let mut previous_step: f64 = 0.0;
+
+        for _ in 0..max_iterations {
+            let middle = f64::midpoint(low, high);
+            let tol = tolerance.mul_add(best.abs(), ABSOLUTE_TOLERANCE);
+            if (best - middle).abs() <= 0.5_f64.mul_add(-(high - low), 2.0 * tol) {
+                return ScalarMinimum {
+                    x: best,
+                    value: f_best,
+                    converged: true,
+                };
+            }
+
+            let mut parabolic = false;
+            if previous_step.abs() > tol {
+                let cross = (best - second) * (f_best - f_third);
+                let denominator = (best - third) * (f_best - f_second);
+                let mut numerator = (best - third).mul_add(denominator, -(best - second) * cross);
+                let mut denominator = 2.0 * (denominator - cross);
+                if denominator > 0.0 {
+                    numerator = -numerator;
+                } else {
+                    denominator = -denominator;
+                }
+                let step_before_last = previous_step;
+                previous_step = step;
+                if numerator.abs() < (0.5 * denominator * step_before_last).abs()
+                    && numerator > denominator * (low - best)
+                    && numerator < denominator * (high - best)
+                {
+                    step = numerator / denominator;
+                    let trial = best + step;
+                    if trial - low < 2.0 * tol || high - trial < 2.0 * tol {
+                        step = tol.copysign(middle - best);
+                    }
+                    parabolic = true;
+                }
+            }
+            if !parabolic {
+                previous_step = if best < middle {
+                    high - best
+                } else {
+                    low - best
+                };
+                step = GOLDEN_FRACTION * previous_step;
+            }
+
+            let trial = if step.abs() >= tol {
+                best + step
+            } else {
+                best + tol.copysign(step)
+            };
+            let f_trial = function(trial);
+            if f_trial <= f_best {
+                if trial < best {
+                    high = best;
+                } else {
+                    low = best;
+                }
+                (third, f_third) = (second, f_second);
+                (second, f_second) = (best, f_best);
+                (best, f_best) = (trial, f_trial);
+            } else {
+                if trial < best {
+                    low = trial;
+                } else {
+                    high = trial;
+                }
+                if f_trial <= f_second || second.total_cmp(&best).is_eq() {
+                    (third, f_third) = (second, f_second);
+                    (second, f_second) = (trial, f_trial);
+                } else if f_trial <= f_third
+                    || third.total_cmp(&best).is_eq()
+                    || third.total_cmp(&second).is_eq()
+                {
+                    (third, f_third) = (trial, f_trial);
+                }
+            }
+        }
+
+        ScalarMinimum {
+            x: best,
+            value: f_best,
+            converged: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brent_finds_interior_minimum() {
+        let minimum = ScalarMinimization::brent(
+            |x| (x - 1.5).mul_add(x - 1.5, x.cos()),
+            -5.0,
+            5.0,
+            1e-10,
+            200,
+        );
+        assert!(minimum.converged);
+        // Stationary point of (x - 1.5)^2 + cos(x): 2 (x - 1.5) = sin(x).
+        assert!(2.0_f64.mul_add(minimum.x - 1.5, -minimum.x.sin()).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_brent_approaches_boundary_minimum() {
+        let minimum = ScalarMinimization::brent(|x| x, -5.0, 5.0, 1e-10, 200);
+        assert!((minimum.x + 5.0).abs() < 1e-6);
+    }
+}
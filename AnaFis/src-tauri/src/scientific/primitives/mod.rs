@@ -0,0 +1,11 @@
+//! Shared numerical building blocks used by the higher-level scientific modules.
+pub mod descriptive;
+pub mod interpolation;
+pub mod minimization;
+pub mod optimization;
+pub mod root_finding;
+
+pub use interpolation::Interpolation;
+pub use minimization::{ScalarMinimization, ScalarMinimum};
+pub use optimization::{BoundedLeastSquaresResult, minimize_bounded_least_squares};
+pub use root_finding::RootFinding;
@@ -0,0 +1,116 @@
+//! Scalar root finding.
+//!
+//! Safeguarded Newton iteration: Newton steps are taken while they stay inside
+//! the current sign-change bracket, otherwise the step falls back to bisection.
+//! This keeps Newton's quadratic convergence near the root without risking
+//! divergence on the flat tails typical of likelihood equations.
+
+/// Scalar root-finding routines.
+pub struct RootFinding;
+
+impl RootFinding {
+    /// Find a root of `function` inside `[lower, upper]`.
+    ///
+    /// `derivative` is used for Newton steps; the bracket must contain a sign
+    /// change. Converges when the bracket or step shrinks below `tolerance`
+    /// (relative to the magnitude of the iterate).
+    ///
+    /// # Errors
+    /// Returns an error if the bracket does not contain a sign change, the
+    /// function is non-finite at the end points, or the iteration limit is hit.
+    pub fn newton_bisection<F, D>(
+        function: F,
+        derivative: D,
+        lower: f64,
+        upper: f64,
+        tolerance: f64,
+        max_iterations: usize,
+    ) -> Result<f64, String>
+    where
+        F: Fn(f64) -> f64,
+        D: Fn(f64) -> f64,
+    {
+        let mut low = lower.min(upper);
+        let mut high = lower.max(upper);
+        let f_low = function(low);
+        let f_high = function(high);
+        if !f_low.is_finite() || !f_high.is_finite() {
+            return Err(format!(
+                "Function is not finite at the bracket end points [{low}, {high}]"
+            ));
+        }
+        if f_low == 0.0 {
+            return Ok(low);
+        }
+        if f_high == 0.0 {
+            return Ok(high);
+        }
+        if f_low.signum() == f_high.signum() {
+            return Err(format!("No sign change in bracket [{low}, {high}]"));
+        }
+        let low_is_negative = f_low < 0.0;
+
+        let mut x = f64::midpoint(low, high);
+        for _ in 0..max_iterations {
+            let value = function(x);
+            if value == 0.0 {
+                return Ok(x);
+            }
+            if (value < 0.0) == low_is_negative {
+                low = x;
+            } else {
+                high = x;
+            }
+
+            let slope = derivative(x);
+            let newton = x - value / slope;
+            let next = if slope.is_finite() && slope != 0.0 && newton > low && newton < high {
+                newton
+            } else {
+                f64::midpoint(low, high)
+            };
+
+            let scale = next.abs().max(1.0);
+            if (next - x).abs() <= tolerance * scale || (high - low) <= tolerance * scale {
+                return Ok(next);
+            }
+            x = next;
+        }
+
+        Err(format!(
+            "Root finding did not converge within {max_iterations} iterations"
+        ))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_newton_bisection_finds_sqrt_two() {
+        let root = RootFinding::newton_bisection(
+            |x| x.mul_add(x, -2.0),
+            |x| 2.0 * x,
+            0.0,
+            2.0,
+            1e-14,
+            100,
+        )
+        .unwrap();
+        assert!((root - 2.0_f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_bad_derivative_falls_back_to_bisection() {
+        let root =
+            RootFinding::newton_bisection(|x| x.powi(3) - 1.0, |_| 0.0, 0.0, 3.0, 1e-12, 200)
+                .unwrap();
+        assert!((root - 1.0).abs() < 1e-9);
+        assert!(
+            RootFinding::newton_bisection(|x| x.mul_add(x, 1.0), |x| 2.0 * x, -1.0, 1.0, 1e-12, 50)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,633 @@
+//! One-way and factorial analysis of variance.
+//!
+//! The total sum of squares about the grand mean splits into a between-group
+//! part, from the group means, and a within-group part, from the deviations
+//! inside each group. Their mean-square ratio follows an F distribution with
+//! `(k - 1, N - k)` degrees of freedom when all group means are equal.
+//!
+//! Factorial designs fit every main effect and interaction as a linear model
+//! with sum-to-zero coded factors. Each term gets its Type II sum of squares:
+//! the drop in residual sum of squares when the term is added to the model of
+//! all terms that do not contain it. On balanced data this equals the
+//! classical decomposition, and on unbalanced data it tests each term after
+//! the others at the same or lower order. The fits go through the SVD, so
+//! empty factor-level combinations only cost the confounded degrees of
+//! freedom.
+use std::collections::HashMap;
+
+use nalgebra::{DMatrix, DVector};
+use statrs::distribution::{ContinuousCDF, FisherSnedecor};
+
+use super::error::StatisticsError;
+use super::types::{
+    AnovaCell, AnovaRow, FactorLevels, FactorialAnovaResult, OneWayAnovaResult, TermCellMeans,
+};
+use crate::scientific::primitives::descriptive::{mean, sample_variance};
+
+/// Most factors accepted by the factorial ANOVA, whose terms number `2^k - 1`.
+const MAX_FACTORS: usize = 8;
+
+/// Analysis-of-variance tests.
+pub struct AnovaTesting;
+
+impl AnovaTesting {
+    /// One-way ANOVA of the equal-means hypothesis across `groups`.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::Validation`] for fewer than two groups, an
+    /// empty group or non-finite values, [`StatisticsError::InsufficientData`]
+    /// without within-group degrees of freedom, and
+    /// [`StatisticsError::Numerical`] when every group is constant.
+    pub fn one_way_anova(groups: &[Vec<f64>]) -> Result<OneWayAnovaResult, StatisticsError> {
+        if groups.len() < 2 {
+            return Err(StatisticsError::validation(
+                "groups",
+                format!("ANOVA needs at least two groups, got {}", groups.len()),
+            ));
+        }
+        if groups.iter().any(Vec::is_empty) {
+            return Err(StatisticsError::validation(
+                "groups",
+                "Every ANOVA group needs at least one value",
+            ));
+        }
+        if groups.iter().flatten().any(|value| !value.is_finite()) {
+            return Err(StatisticsError::validation(
+                "groups",
+                "ANOVA values must be finite",
+            ));
+        }
+        let total_count: usize = groups.iter().map(Vec::len).sum();
+        if total_count <= groups.len() {
+            return Err(StatisticsError::InsufficientData {
+                needed: groups.len() + 1,
+                got: total_count,
+            });
+        }
+
+        let all: Vec<f64> = groups.iter().flatten().copied().collect();
+        let grand_mean = mean(&all).unwrap_or(0.0);
+        let (mut between, mut within) = (0.0, 0.0);
+        for group in groups {
+            let group_mean = mean(group).unwrap_or(0.0);
+            #[allow(clippy::cast_precision_loss, reason = "Group size casting to f64")]
+            let size = group.len() as f64;
+            between += size * (group_mean - grand_mean).powi(2);
+            within += group
+                .iter()
+                .map(|value| (value - group_mean).powi(2))
+                .sum::<f64>();
+        }
+        if within <= 0.0 {
+            return Err(StatisticsError::Numerical {
+                context: "Every group is constant; the F statistic is undefined".to_owned(),
+            });
+        }
+
+        let (df_between, df_within) = (groups.len() - 1, total_count - groups.len());
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Degrees of freedom casting to f64"
+        )]
+        let (df_between_f, df_within_f) = (df_between as f64, df_within as f64);
+        let (mean_between, mean_within) = (between / df_between_f, within / df_within_f);
+        let f_statistic = mean_between / mean_within;
+        let p_value = FisherSnedecor::new(df_between_f, df_within_f)
+            .map_err(|error| error.to_string())?
+            .sf(f_statistic);
+
+        let row = |source: &str, degrees_of_freedom, sum_of_squares, mean_square, test| {
+            let (row_f_statistic, row_p_value) = test;
+            AnovaRow {
+                source: source.to_owned(),
+                degrees_of_freedom,
+                sum_of_squares,
+                mean_square,
+                f_statistic: row_f_statistic,
+                p_value: row_p_value,
+            }
+        };
+        Ok(OneWayAnovaResult {
+            table: vec![
+                row(
+                    "Between groups",
+                    df_between,
+                    between,
+                    Some(mean_between),
+                    (Some(f_statistic), Some(p_value)),
+                ),
+                row(
+                    "Within groups",
+                    df_within,
+                    within,
+                    Some(mean_within),
+                    (None, None),
+                ),
+                row(
+                    "Total",
+                    total_count - 1,
+                    between + within,
+                    None,
+                    (None, None),
+                ),
+            ],
+            f_statistic,
+            p_value,
+        })
+    }
+
+    /// Factorial ANOVA of `response` on the categorical `factors`, one column
+    /// per entry of `factor_names`, with Type II sums of squares for every
+    /// main effect and interaction.
+    ///
+    /// Rows with a non-finite response or a blank level are skipped. The
+    /// result carries the count, mean and standard error of every cell of
+    /// every term for interaction plots, and lists the combinations of all
+    /// factors without observations.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::Validation`] for no factors, more than
+    /// eight, columns of different lengths or a factor with fewer than two
+    /// levels, [`StatisticsError::InsufficientData`] without residual degrees
+    /// of freedom, and [`StatisticsError::Numerical`] when the model fits
+    /// exactly.
+    #[allow(
+        clippy::too_many_lines,
+        reason = "Validation, term fits and cell summaries read best in sequence"
+    )]
+    pub fn factorial_anova(
+        response: &[f64],
+        factors: &[Vec<String>],
+        factor_names: &[String],
+    ) -> Result<FactorialAnovaResult, StatisticsError> {
+        if factors.is_empty() || factors.len() > MAX_FACTORS {
+            return Err(StatisticsError::validation(
+                "factors",
+                format!(
+                    "Factorial ANOVA needs between one and {MAX_FACTORS} factors, got {}",
+                    factors.len()
+                ),
+            ));
+        }
+        if factor_names.len() != factors.len() {
+            return Err(StatisticsError::validation(
+                "factor_names",
+                format!(
+                    "Expected one name per factor ({} factors, {} names)",
+                    factors.len(),
+                    factor_names.len()
+                ),
+            ));
+        }
+        if factors.iter().any(|factor| factor.len() != response.len()) {
+            return Err(StatisticsError::validation(
+                "factors",
+                "Every factor column needs one level per response value",
+            ));
+        }
+
+        let kept: Vec<usize> = (0..response.len())
+            .filter(|&row| {
+                response[row].is_finite()
+                    && factors.iter().all(|factor| !factor[row].trim().is_empty())
+            })
+            .collect();
+        let y = DVector::from_iterator(kept.len(), kept.iter().map(|&row| response[row]));
+        let mut levels: Vec<Vec<String>> = Vec::with_capacity(factors.len());
+        let mut codes: Vec<Vec<usize>> = Vec::with_capacity(factors.len());
+        for (factor, name) in factors.iter().zip(factor_names) {
+            let mut positions: HashMap<&str, usize> = HashMap::new();
+            let mut names = Vec::new();
+            let factor_codes = kept
+                .iter()
+                .map(|&row| {
+                    let level = factor[row].as_str();
+                    *positions.entry(level).or_insert_with(|| {
+                        names.push(level.to_owned());
+                        names.len() - 1
+                    })
+                })
+                .collect();
+            if names.len() < 2 {
+                return Err(StatisticsError::validation(
+                    "factors",
+                    format!("Factor '{name}' needs at least two levels"),
+                ));
+            }
+            levels.push(names);
+            codes.push(factor_codes);
+        }
+
+        // Terms as bit masks over the factors, main effects first
+        let mut terms: Vec<usize> = (1..1_usize << factors.len()).collect();
+        terms.sort_by_key(|term| (term.count_ones(), *term));
+        let level_counts: Vec<usize> = levels.iter().map(Vec::len).collect();
+        let fit = |included: &[usize]| fit_terms(&y, &codes, &level_counts, included);
+
+        let (residual_ss, full_rank) = fit(&terms)?;
+        let residual_df = kept.len() - full_rank;
+        if residual_df == 0 {
+            return Err(StatisticsError::InsufficientData {
+                needed: full_rank + 1,
+                got: kept.len(),
+            });
+        }
+        if residual_ss <= 0.0 {
+            return Err(StatisticsError::Numerical {
+                context: "The model fits every observation exactly; the F statistics are undefined"
+                    .to_owned(),
+            });
+        }
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Degrees of freedom casting to f64"
+        )]
+        let residual_mean_square = residual_ss / residual_df as f64;
+
+        let mut table = Vec::with_capacity(terms.len() + 2);
+        let mut partial_eta_squared = Vec::with_capacity(terms.len());
+        for &term in &terms {
+            let mut included: Vec<usize> = terms
+                .iter()
+                .copied()
+                .filter(|&other| other & term != term)
+                .collect();
+            let (base_ss, base_rank) = fit(&included)?;
+            included.push(term);
+            let (term_ss, term_rank) = fit(&included)?;
+            let sum_of_squares = (base_ss - term_ss).max(0.0);
+            let degrees_of_freedom = term_rank - base_rank;
+
+            let (mean_square, f_statistic, p_value) = if degrees_of_freedom == 0 {
+                (None, None, None)
+            } else {
+                #[allow(
+                    clippy::cast_precision_loss,
+                    reason = "Degrees of freedom casting to f64"
+                )]
+                let (df, df_residual) = (degrees_of_freedom as f64, residual_df as f64);
+                let mean_square = sum_of_squares / df;
+                let f_statistic = mean_square / residual_mean_square;
+                let p_value = FisherSnedecor::new(df, df_residual)
+                    .map_err(|error| error.to_string())?
+                    .sf(f_statistic);
+                (Some(mean_square), Some(f_statistic), Some(p_value))
+            };
+            table.push(AnovaRow {
+                source: term_name(term, factor_names),
+                degrees_of_freedom,
+                sum_of_squares,
+                mean_square,
+                f_statistic,
+                p_value,
+            });
+            partial_eta_squared.push(sum_of_squares / (sum_of_squares + residual_ss));
+        }
+        let grand_mean = y.mean();
+        table.push(AnovaRow {
+            source: "Residuals".to_owned(),
+            degrees_of_freedom: residual_df,
+            sum_of_squares: residual_ss,
+            mean_square: Some(residual_mean_square),
+            f_statistic: None,
+            p_value: None,
+        });
+        table.push(AnovaRow {
+            source: "Total".to_owned(),
+            degrees_of_freedom: kept.len() - 1,
+            sum_of_squares: y.iter().map(|value| (value - grand_mean).powi(2)).sum(),
+            mean_square: None,
+            f_statistic: None,
+            p_value: None,
+        });
+
+        let cell_means: Vec<TermCellMeans> = terms
+            .iter()
+            .map(|&term| term_cells(term, &y, &codes, &levels, factor_names))
+            .collect();
+        // The last term crosses every factor
+        let full_cells = &cell_means[cell_means.len() - 1].cells;
+        let missing_cells: Vec<Vec<String>> = full_cells
+            .iter()
+            .filter(|cell| cell.count == 0)
+            .map(|cell| cell.levels.clone())
+            .collect();
+        let balanced = full_cells
+            .iter()
+            .all(|cell| cell.count == full_cells[0].count);
+        let mut warnings = Vec::new();
+        if !missing_cells.is_empty() {
+            warnings.push(format!(
+                "{} of {} factor-level combinations have no observations; terms involving them lose degrees of freedom and their cell means are undefined",
+                missing_cells.len(),
+                full_cells.len()
+            ));
+        }
+
+        Ok(FactorialAnovaResult {
+            table,
+            partial_eta_squared,
+            factors: factor_names
+                .iter()
+                .zip(levels)
+                .map(|(name, levels)| FactorLevels {
+                    name: name.clone(),
+                    levels,
+                })
+                .collect(),
+            cell_means,
+            missing_cells,
+            balanced,
+            observations: kept.len(),
+            skipped_rows: response.len() - kept.len(),
+            warnings,
+        })
+    }
+}
+
+/// Name of a term: its factor names joined by `:`.
+fn term_name(term: usize, factor_names: &[String]) -> String {
+    factor_names
+        .iter()
+        .enumerate()
+        .filter(|(factor, _)| term >> factor & 1 == 1)
+        .map(|(_, name)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Design-matrix row of one observation: the intercept, then for every term
+/// the products of the sum-to-zero codes of its factors.
+fn design_row(
+    observation: usize,
+    codes: &[Vec<usize>],
+    level_counts: &[usize],
+    included: &[usize],
+) -> Vec<f64> {
+    let mut row = vec![1.0];
+    for &term in included {
+        let mut columns = vec![1.0];
+        for (factor, factor_codes) in codes.iter().enumerate() {
+            if term >> factor & 1 == 0 {
+                continue;
+            }
+            let (level, last) = (factor_codes[observation], level_counts[factor] - 1);
+            let factor_columns: Vec<f64> = (0..last)
+                .map(|column| {
+                    if level == column {
+                        1.0
+                    } else if level == last {
+                        -1.0
+                    } else {
+                        0.0
+                    }
+                })
+                .collect();
+            columns = columns
+                .iter()
+                .flat_map(|product| factor_columns.iter().map(move |code| product * code))
+                .collect();
+        }
+        row.extend(columns);
+    }
+    row
+}
+
+/// Residual sum of squares and rank of the least-squares fit of `y` on the
+/// intercept and the `included` terms.
+fn fit_terms(
+    y: &DVector<f64>,
+    codes: &[Vec<usize>],
+    level_counts: &[usize],
+    included: &[usize],
+) -> Result<(f64, usize), StatisticsError> {
+    let rows: Vec<Vec<f64>> = (0..y.len())
+        .map(|observation| design_row(observation, codes, level_counts, included))
+        .collect();
+    let columns = rows.first().map_or(1, Vec::len);
+    let design = DMatrix::from_fn(y.len(), columns, |row, column| rows[row][column]);
+    let svd = design.clone().svd(true, true);
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Matrix dimension casting to f64"
+    )]
+    let tolerance = svd.singular_values.max() * y.len().max(columns) as f64 * f64::EPSILON;
+    let coefficients = svd.solve(y, tolerance).map_err(str::to_owned)?;
+    let residual_ss = (y - design * coefficients).norm_squared();
+    Ok((residual_ss, svd.rank(tolerance)))
+}
+
+/// Count, mean and standard error of every cell of a term, its first
+/// factor varying slowest.
+fn term_cells(
+    term: usize,
+    y: &DVector<f64>,
+    codes: &[Vec<usize>],
+    levels: &[Vec<String>],
+    factor_names: &[String],
+) -> TermCellMeans {
+    let members: Vec<usize> = (0..levels.len())
+        .filter(|factor| term >> factor & 1 == 1)
+        .collect();
+    let cell_count: usize = members.iter().map(|&factor| levels[factor].len()).product();
+    let mut values: Vec<Vec<f64>> = vec![Vec::new(); cell_count];
+    for (observation, value) in y.iter().enumerate() {
+        let cell = members.iter().fold(0, |cell, &factor| {
+            cell * levels[factor].len() + codes[factor][observation]
+        });
+        values[cell].push(*value);
+    }
+
+    let cells = values
+        .iter()
+        .enumerate()
+        .map(|(cell, cell_values)| {
+            let mut remainder = cell;
+            let mut cell_levels = vec![String::new(); members.len()];
+            for (slot, &factor) in members.iter().enumerate().rev() {
+                let count = levels[factor].len();
+                cell_levels[slot].clone_from(&levels[factor][remainder % count]);
+                remainder /= count;
+            }
+            #[allow(clippy::cast_precision_loss, reason = "Cell size casting to f64")]
+            let size = cell_values.len() as f64;
+            AnovaCell {
+                levels: cell_levels,
+                count: cell_values.len(),
+                mean: mean(cell_values),
+                standard_error: sample_variance(cell_values)
+                    .map(|variance| (variance / size).sqrt()),
+            }
+        })
+        .collect();
+
+    TermCellMeans {
+        term: term_name(term, factor_names),
+        factors: members
+            .iter()
+            .map(|&factor| factor_names[factor].clone())
+            .collect(),
+        cells,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_one_way_anova_matches_closed_form() {
+        let groups = vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0, 5.0], vec![9.0]];
+        let result = AnovaTesting::one_way_anova(&groups).unwrap();
+        assert_relative_eq!(result.table[0].sum_of_squares, 39.875, epsilon = 1e-12);
+        assert_relative_eq!(result.table[1].sum_of_squares, 4.0, epsilon = 1e-12);
+        assert_relative_eq!(result.f_statistic, 24.921_875, epsilon = 1e-12);
+        // With two numerator degrees of freedom, P(F > f) = (1 + 2 f / d)^(-d / 2).
+        let reference = (1.0 + 2.0 * 24.921_875 / 5.0_f64).powf(-2.5);
+        assert_relative_eq!(result.p_value, reference, max_relative = 1e-9);
+        assert_eq!(result.table[2].degrees_of_freedom, 7);
+    }
+
+    #[test]
+    fn test_one_way_anova_rejects_degenerate_input() {
+        assert!(AnovaTesting::one_way_anova(&[vec![1.0, 2.0]]).is_err());
+        assert!(matches!(
+            AnovaTesting::one_way_anova(&[vec![1.0], vec![2.0]]),
+            Err(StatisticsError::InsufficientData { .. })
+        ));
+        assert!(AnovaTesting::one_way_anova(&[vec![1.0, 1.0], vec![2.0, 2.0]]).is_err());
+    }
+
+    /// A 2x3 design; `extra` adds observations to make it unbalanced.
+    fn two_by_three(extra: &[(&str, &str, f64)]) -> (Vec<f64>, Vec<Vec<String>>) {
+        let balanced = [
+            ("a1", "b1", 4.0),
+            ("a1", "b1", 6.0),
+            ("a1", "b2", 8.0),
+            ("a1", "b2", 10.0),
+            ("a1", "b3", 12.0),
+            ("a1", "b3", 11.0),
+            ("a2", "b1", 5.0),
+            ("a2", "b1", 7.0),
+            ("a2", "b2", 6.0),
+            ("a2", "b2", 9.0),
+            ("a2", "b3", 15.0),
+            ("a2", "b3", 16.0),
+        ];
+        let rows: Vec<_> = balanced.iter().chain(extra).collect();
+        let response = rows.iter().map(|row| row.2).collect();
+        let factors = vec![
+            rows.iter().map(|row| row.0.to_owned()).collect(),
+            rows.iter().map(|row| row.1.to_owned()).collect(),
+        ];
+        (response, factors)
+    }
+
+    fn names() -> Vec<String> {
+        vec!["A".to_owned(), "B".to_owned()]
+    }
+
+    #[test]
+    fn test_factorial_anova_balanced_matches_reference() {
+        let (response, factors) = two_by_three(&[]);
+        let result = AnovaTesting::factorial_anova(&response, &factors, &names()).unwrap();
+        let sources: Vec<&str> = result.table.iter().map(|row| row.source.as_str()).collect();
+        assert_eq!(sources, ["A", "B", "A:B", "Residuals", "Total"]);
+        assert!(result.balanced);
+        // Classical balanced decomposition, residual mean square 23 / 12.
+        assert_relative_eq!(result.table[0].sum_of_squares, 49.0 / 12.0, epsilon = 1e-9);
+        assert_relative_eq!(result.table[1].sum_of_squares, 793.0 / 6.0, epsilon = 1e-9);
+        assert_relative_eq!(result.table[2].sum_of_squares, 91.0 / 6.0, epsilon = 1e-9);
+        assert_relative_eq!(result.table[3].sum_of_squares, 11.5, epsilon = 1e-9);
+        let f_values: Vec<f64> = result.table[..3]
+            .iter()
+            .map(|row| row.f_statistic.unwrap())
+            .collect();
+        assert_relative_eq!(f_values[0], 49.0 / 23.0, epsilon = 1e-9);
+        assert_relative_eq!(f_values[1], 793.0 / 23.0, epsilon = 1e-9);
+        assert_relative_eq!(f_values[2], 91.0 / 23.0, epsilon = 1e-9);
+        assert_eq!(result.table[2].degrees_of_freedom, 2);
+        // With two numerator degrees of freedom, P(F > f) = (1 + 2 f / d)^(-d / 2).
+        let reference = (1.0 + 2.0 * f_values[1] / 6.0).powi(-3);
+        assert_relative_eq!(
+            result.table[1].p_value.unwrap(),
+            reference,
+            max_relative = 1e-9
+        );
+        assert_relative_eq!(
+            result.partial_eta_squared[1],
+            (793.0 / 6.0) / (793.0 / 6.0 + 11.5),
+            epsilon = 1e-12
+        );
+
+        let interaction = &result.cell_means[2];
+        assert_eq!(interaction.factors, ["A", "B"]);
+        assert_eq!(interaction.cells[1].levels, ["a1", "b2"]);
+        assert_eq!(interaction.cells[1].count, 2);
+        assert_relative_eq!(interaction.cells[1].mean.unwrap(), 9.0);
+        assert_relative_eq!(interaction.cells[1].standard_error.unwrap(), 1.0);
+    }
+
+    #[test]
+    fn test_factorial_anova_unbalanced_uses_type_ii() {
+        let (response, factors) = two_by_three(&[("a1", "b1", 5.0), ("a2", "b2", 8.0)]);
+        let result = AnovaTesting::factorial_anova(&response, &factors, &names()).unwrap();
+        assert!(!result.balanced);
+        // Reference values from exact rational least squares: SS(A | B),
+        // SS(B | A) and SS(A:B | A, B).
+        assert_relative_eq!(
+            result.table[0].sum_of_squares,
+            3.811_764_705_882_353,
+            epsilon = 1e-9
+        );
+        assert_relative_eq!(
+            result.table[1].sum_of_squares,
+            144.526_050_420_168_1,
+            epsilon = 1e-9
+        );
+        assert_relative_eq!(
+            result.table[2].sum_of_squares,
+            15.521_568_627_450_98,
+            epsilon = 1e-9
+        );
+        assert_eq!(result.table[3].degrees_of_freedom, 8);
+        assert_relative_eq!(
+            result.table[0].f_statistic.unwrap(),
+            2.613_781_512_605_042,
+            epsilon = 1e-9
+        );
+        assert_relative_eq!(
+            result.table[1].f_statistic.unwrap(),
+            49.551_788_715_486_2,
+            epsilon = 1e-9
+        );
+        assert_relative_eq!(
+            result.table[2].f_statistic.unwrap(),
+            5.321_680_672_268_908,
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn test_factorial_anova_reports_missing_cells() {
+        let (mut response, mut factors) = two_by_three(&[]);
+        response.truncate(10);
+        for factor in &mut factors {
+            factor.truncate(10);
+        }
+        let result = AnovaTesting::factorial_anova(&response, &factors, &names()).unwrap();
+        assert_eq!(
+            result.missing_cells,
+            vec![vec!["a2".to_owned(), "b3".to_owned()]]
+        );
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.table[2].degrees_of_freedom, 1);
+        assert_eq!(result.table[3].degrees_of_freedom, 5);
+        assert!(result.cell_means[2].cells[5].mean.is_none());
+
+        let constant = vec![vec!["a".to_owned(); 12]];
+        assert!(AnovaTesting::factorial_anova(&response, &constant, &names()[..1]).is_err());
+    }
+}
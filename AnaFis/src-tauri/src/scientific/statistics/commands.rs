@@ -0,0 +1,345 @@
+use super::anova::AnovaTesting;
+use super::contingency::ChiSquareTesting;
+use super::descriptive::DescriptiveStatisticsEngine;
+use super::normality::NormalityTestingEngine;
+use super::outliers::OutlierDetectionEngine;
+use super::pipeline::StatisticalAnalysisPipeline;
+use super::robust_regression::RobustRegressionEngine;
+use super::selection::SelectionEngine;
+use super::stationarity::StationarityEngine;
+use super::survival::SurvivalAnalysisEngine;
+use super::types::{
+    AdfResult, ClusterLinkage, CrosstabResult, FactorialAnovaResult, GroupedStatisticsResult,
+    KmResult, LogRankResult, MEstimatorOptions, MEstimatorResult, Measurement,
+    MeasurementCombinationOptions, MeasurementCombinationResult, MissingCategoryPolicy,
+    MultiColumnReport, MultipleComparisonAdjustment, NamedColumn, NormalityBundleResult,
+    OutlierAnalysisResult, OutlierDetectionOptions, OutlierReport, PreparedSelection,
+    RansacOptions, RobustRegressionResult, SelectionOptions,
+};
+use super::uncertainty::MeasurementCombinationEngine;
+use crate::error::{CommandError, CommandResult, internal_error};
+use crate::utils::tasks::run_heavy;
+
+/// Run an augmented Dickey-Fuller unit-root test on a series.
+///
+/// # Errors
+/// Returns a structured error if the series is too short, non-finite, or
+/// the test regression is singular.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn adf_test(series: Vec<f64>, max_lags: Option<usize>) -> CommandResult<AdfResult> {
+    StationarityEngine::adf_test(&series, max_lags).map_err(CommandError::from)
+}
+
+/// Significance level of normality verdicts when none is given.
+const DEFAULT_NORMALITY_ALPHA: f64 = 0.05;
+
+/// Run a bundle of normality tests on a column.
+///
+/// The Shapiro-Wilk, Anderson-Darling, Jarque-Bera and D'Agostino K^2 tests
+/// come with the column's skewness, kurtosis and normal Q-Q plot points.
+/// Tests that need more observations are skipped with a reason.
+///
+/// # Errors
+/// Returns a structured error if alpha is outside (0, 1), the data are
+/// constant or non-finite, or fewer than three values are given.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn test_normality(data: Vec<f64>, alpha: Option<f64>) -> CommandResult<NormalityBundleResult> {
+    NormalityTestingEngine::test_normality(&data, alpha.unwrap_or(DEFAULT_NORMALITY_ALPHA))
+        .map_err(CommandError::from)
+}
+
+/// Groups smaller than this are flagged when no minimum size is given.
+const DEFAULT_MIN_GROUP_SIZE: usize = 2;
+
+/// Summarize a value column per group key and compare the groups.
+///
+/// Group means are inverse-variance weighted when uncertainties are given,
+/// and the groups are compared by one-way ANOVA. Missing values are skipped.
+///
+/// # Errors
+/// Returns a structured error if the columns differ in length, an
+/// uncertainty is not positive, or no value is present.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn grouped_statistics(
+    values: Vec<Option<f64>>,
+    uncertainties: Option<Vec<f64>>,
+    keys: Vec<String>,
+    min_group_size: Option<usize>,
+) -> CommandResult<GroupedStatisticsResult> {
+    let values: Vec<f64> = values
+        .iter()
+        .map(|value| value.unwrap_or(f64::NAN))
+        .collect();
+    DescriptiveStatisticsEngine::grouped_statistics(
+        &values,
+        uncertainties.as_deref(),
+        &keys,
+        min_group_size.unwrap_or(DEFAULT_MIN_GROUP_SIZE),
+    )
+    .map_err(CommandError::from)
+}
+
+/// Factorial ANOVA of a response column on categorical factor columns.
+///
+/// Reports Type II sums of squares, partial eta-squared effect sizes and the
+/// cell means of every term for interaction plots. Missing responses and
+/// blank levels are skipped; factors are named "Factor 1", "Factor 2", ...
+/// unless names are given.
+///
+/// # Errors
+/// Returns a structured error if the columns differ in length, a factor has
+/// fewer than two levels, or no residual degrees of freedom remain.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn run_factorial_anova(
+    response: Vec<Option<f64>>,
+    factors: Vec<Vec<String>>,
+    factor_names: Option<Vec<String>>,
+) -> CommandResult<FactorialAnovaResult> {
+    let response: Vec<f64> = response
+        .iter()
+        .map(|value| value.unwrap_or(f64::NAN))
+        .collect();
+    let factor_names = factor_names.unwrap_or_else(|| {
+        (1..=factors.len())
+            .map(|factor| format!("Factor {factor}"))
+            .collect()
+    });
+    AnovaTesting::factorial_anova(&response, &factors, &factor_names).map_err(CommandError::from)
+}
+
+/// Estimate a survival curve with the Kaplan-Meier product-limit estimator.
+///
+/// # Errors
+/// Returns a structured error if the inputs are empty, mismatched in length,
+/// or contain negative or non-finite times.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn kaplan_meier(times: Vec<f64>, events: Vec<bool>) -> CommandResult<KmResult> {
+    SurvivalAnalysisEngine::kaplan_meier(&times, &events).map_err(CommandError::from)
+}
+
+/// Compare two survival samples with the log-rank test.
+///
+/// # Errors
+/// Returns a structured error if either sample is invalid or the groups
+/// share no comparable events.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn log_rank_test(
+    first_times: Vec<f64>,
+    first_events: Vec<bool>,
+    second_times: Vec<f64>,
+    second_events: Vec<bool>,
+) -> CommandResult<LogRankResult> {
+    let first = SurvivalAnalysisEngine::kaplan_meier(&first_times, &first_events)
+        .map_err(CommandError::from)?;
+    let second = SurvivalAnalysisEngine::kaplan_meier(&second_times, &second_events)
+        .map_err(CommandError::from)?;
+    SurvivalAnalysisEngine::log_rank_test(&first, &second).map_err(CommandError::from)
+}
+
+/// Cross-tabulate two categorical columns, optionally weighted, and test
+/// their independence by chi-square, with Cramer's V and cell residuals.
+///
+/// Null and blank categories are dropped unless `missing` keeps them as a
+/// category. 2x2 tables use Yates' correction unless `continuity_correction`
+/// is false, and also report the Fisher exact p-value.
+///
+/// # Errors
+/// Returns a structured error if the columns differ in length, a weight is
+/// negative, or either column has fewer than two categories.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn crosstab_and_test(
+    row_values: Vec<Option<String>>,
+    column_values: Vec<Option<String>>,
+    weights: Option<Vec<f64>>,
+    missing: Option<MissingCategoryPolicy>,
+    continuity_correction: Option<bool>,
+) -> CommandResult<CrosstabResult> {
+    ChiSquareTesting::crosstab_and_test(
+        &row_values,
+        &column_values,
+        weights.as_deref(),
+        missing.unwrap_or_default(),
+        continuity_correction.unwrap_or(true),
+    )
+    .map_err(CommandError::from)
+}
+
+/// Significance level of the multi-column tests when none is given.
+const DEFAULT_COMPARISON_ALPHA: f64 = 0.05;
+
+/// Compare several named columns at once.
+///
+/// Gives descriptive statistics and a Shapiro-Wilk check per column, Welch t
+/// or Mann-Whitney tests of every pair with Holm-adjusted p-values (or the
+/// given adjustment), the correlation matrix, and a complete-linkage (or
+/// single-linkage) clustering of the columns by correlation distance.
+/// Columns with fewer than three values are excluded with a note. The series
+/// of `prepare_selection` are accepted as columns.
+///
+/// # Errors
+/// Returns a structured error if alpha is outside (0, 1) or no column has
+/// three values.
+#[tauri::command]
+pub async fn analyze_columns(
+    columns: Vec<NamedColumn>,
+    alpha: Option<f64>,
+    adjustment: Option<MultipleComparisonAdjustment>,
+    linkage: Option<ClusterLinkage>,
+) -> CommandResult<MultiColumnReport> {
+    run_heavy("analyze_columns", move || {
+        StatisticalAnalysisPipeline::analyze_columns(
+            &columns,
+            alpha.unwrap_or(DEFAULT_COMPARISON_ALPHA),
+            adjustment.unwrap_or_default(),
+            linkage.unwrap_or_default(),
+        )
+    })
+    .await
+    .map_err(internal_error)?
+    .map_err(CommandError::from)
+}
+
+/// Fit a linear model with intercept by RANSAC, resistant to outlying rows.
+///
+/// Predictors are given as one row per observation. With a seed in the
+/// options the fit is reproducible; without one a seed is drawn and
+/// reported. Inlier-count ties go to the lower median inlier residual.
+///
+/// # Errors
+/// Returns a structured error for ragged or non-finite input, invalid
+/// options, or when every sampled subset has a singular design.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn ransac_regression(
+    predictors: Vec<Vec<f64>>,
+    response: Vec<f64>,
+    options: Option<RansacOptions>,
+) -> CommandResult<RobustRegressionResult> {
+    RobustRegressionEngine::ransac_regression(&predictors, &response, &options.unwrap_or_default())
+        .map_err(CommandError::from)
+}
+
+/// Fit a linear model with intercept by Huber M-estimation.
+///
+/// Residuals are weighted in units of a robust scale re-estimated every
+/// iteration, and coefficient standard errors come from the sandwich
+/// estimator.
+///
+/// # Errors
+/// Returns a structured error for ragged or non-finite input, too few rows,
+/// invalid options, or a singular weighted design.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn huber_regression(
+    predictors: Vec<Vec<f64>>,
+    response: Vec<f64>,
+    options: Option<MEstimatorOptions>,
+) -> CommandResult<MEstimatorResult> {
+    RobustRegressionEngine::huber_regression(&predictors, &response, &options.unwrap_or_default())
+        .map_err(CommandError::from)
+}
+
+/// Fit a linear model with intercept by iteratively reweighted least squares
+/// with Tukey's biweight, which gives far outliers zero weight.
+///
+/// # Errors
+/// Returns a structured error for ragged or non-finite input, too few rows,
+/// invalid options, or a singular weighted design.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn irls_regression(
+    predictors: Vec<Vec<f64>>,
+    response: Vec<f64>,
+    options: Option<MEstimatorOptions>,
+) -> CommandResult<MEstimatorResult> {
+    RobustRegressionEngine::irls_regression(&predictors, &response, &options.unwrap_or_default())
+        .map_err(CommandError::from)
+}
+
+/// Combine independent measurements of one quantity into their
+/// inverse-variance weighted mean.
+///
+/// Reports the internal and Birge-scaled external uncertainties, a
+/// chi-squared consistency test, each measurement's normalized deviation
+/// with outlier flags and, on request, the DerSimonian-Laird random-effects
+/// estimate.
+///
+/// # Errors
+/// Returns a structured error for fewer than two measurements, non-finite
+/// values, missing or non-positive uncertainties, or an invalid threshold.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn combine_measurements(
+    measurements: Vec<Measurement>,
+    options: Option<MeasurementCombinationOptions>,
+) -> CommandResult<MeasurementCombinationResult> {
+    MeasurementCombinationEngine::combine(&measurements, &options.unwrap_or_default())
+        .map_err(CommandError::from)
+}
+
+/// Flag outliers of a column by Tukey's fences, the modified z-score and
+/// the z-score, with the consensus of the rules.
+///
+/// # Errors
+/// Returns a structured error for fewer than three values, non-finite
+/// values, or invalid thresholds.
+#[tauri::command]
+pub async fn detect_outliers(
+    data: Vec<f64>,
+    options: Option<OutlierDetectionOptions>,
+) -> CommandResult<OutlierAnalysisResult> {
+    run_heavy("detect_outliers", move || {
+        OutlierDetectionEngine::detect(&data, &options.unwrap_or_default())
+    })
+    .await
+    .map_err(internal_error)?
+    .map_err(CommandError::from)
+}
+
+/// Annotate each point with the outlier rules flagging it.
+///
+/// Also compares the statistics of the column with and without the
+/// consensus outliers, and suggests keeping, reviewing or removing each
+/// point.
+///
+/// # Errors
+/// Returns a structured error if the analysis or uncertainties do not match
+/// the data, or removing the outliers leaves nothing.
+#[tauri::command]
+pub async fn generate_outlier_report(
+    data: Vec<f64>,
+    analysis: OutlierAnalysisResult,
+    uncertainties: Option<Vec<f64>>,
+) -> CommandResult<OutlierReport> {
+    run_heavy("generate_outlier_report", move || {
+        OutlierDetectionEngine::report(&data, &analysis, uncertainties.as_deref())
+    })
+    .await
+    .map_err(internal_error)?
+    .map_err(CommandError::from)
+}
+
+/// Split a rectangular spreadsheet selection into named numeric series.
+///
+/// Series are taken by column or by row; every cell left out is reported and
+/// series with too few numbers are rejected. The series can be passed to
+/// `analyze_columns` as they are.
+///
+/// # Errors
+/// Returns a structured error for an empty selection or an invalid minimum
+/// numeric fraction.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn prepare_selection(
+    grid: Vec<Vec<serde_json::Value>>,
+    options: Option<SelectionOptions>,
+) -> CommandResult<PreparedSelection> {
+    SelectionEngine::prepare(&grid, &options.unwrap_or_default()).map_err(CommandError::from)
+}
@@ -0,0 +1,415 @@
+//! Contingency tables and tests of independence.
+//!
+//! Two categorical columns are cross-tabulated, optionally weighted, keeping
+//! categories in order of first appearance. Independence is tested with
+//! Pearson's chi-square, with Yates' continuity correction available for 2x2
+//! tables, and association is measured by Cramer's V. For 2x2 tables of whole
+//! counts the two-sided Fisher exact test sums the hypergeometric
+//! probabilities of every table with the observed margins that is no more
+//! likely than the observed one.
+use std::collections::HashMap;
+
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+use statrs::function::factorial::ln_binomial;
+
+use super::error::StatisticsError;
+use super::types::{
+    ChiSquareIndependenceResult, ContingencyTable, CrosstabResult, MissingCategoryPolicy,
+};
+
+/// Expected count below which the chi-square approximation is doubtful.
+const MIN_EXPECTED_COUNT: f64 = 5.0;
+/// Label of the missing-value category.
+const MISSING_CATEGORY: &str = "(missing)";
+/// Relative slack when comparing hypergeometric probabilities to the observed one.
+const FISHER_RELATIVE_TOLERANCE: f64 = 1e-7;
+
+/// Chi-square and exact tests on contingency tables.
+pub struct ChiSquareTesting;
+
+impl ChiSquareTesting {
+    /// Cross-tabulate `rows` against `columns`, each row weighted by
+    /// `weights` (1 when absent).
+    ///
+    /// Blank and `None` categories follow `missing`; rows with zero weight are
+    /// skipped.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::Validation`] for columns of different
+    /// lengths or a negative or non-finite weight.
+    pub fn crosstab(
+        rows: &[Option<String>],
+        columns: &[Option<String>],
+        weights: Option<&[f64]>,
+        missing: MissingCategoryPolicy,
+    ) -> Result<ContingencyTable, StatisticsError> {
+        if columns.len() != rows.len() {
+            return Err(StatisticsError::validation(
+                "columns",
+                format!(
+                    "Both categorical columns need the same length ({} and {})",
+                    rows.len(),
+                    columns.len()
+                ),
+            ));
+        }
+        if let Some(weights) = weights {
+            if weights.len() != rows.len() {
+                return Err(StatisticsError::validation(
+                    "weights",
+                    format!(
+                        "Expected one weight per row ({} rows, {} weights)",
+                        rows.len(),
+                        weights.len()
+                    ),
+                ));
+            }
+            if weights
+                .iter()
+                .any(|weight| !weight.is_finite() || *weight < 0.0)
+            {
+                return Err(StatisticsError::validation(
+                    "weights",
+                    "Weights must be finite and non-negative",
+                ));
+            }
+        }
+
+        let mut row_index = CategoryIndex::default();
+        let mut column_index = CategoryIndex::default();
+        let mut cells: Vec<(usize, usize, f64)> = Vec::with_capacity(rows.len());
+        let mut skipped_rows = 0;
+        for (position, (row, column)) in rows.iter().zip(columns).enumerate() {
+            let weight = weights.map_or(1.0, |weights| weights[position]);
+            let (Some(row), Some(column)) = (
+                category(row.as_deref(), missing),
+                category(column.as_deref(), missing),
+            ) else {
+                skipped_rows += 1;
+                continue;
+            };
+            if weight == 0.0 {
+                skipped_rows += 1;
+                continue;
+            }
+            cells.push((row_index.get(row), column_index.get(column), weight));
+        }
+
+        let mut counts = vec![vec![0.0; column_index.names.len()]; row_index.names.len()];
+        for (row, column, weight) in cells {
+            counts[row][column] += weight;
+        }
+        Ok(ContingencyTable {
+            row_categories: row_index.names,
+            column_categories: column_index.names,
+            counts,
+            skipped_rows,
+        })
+    }
+
+    /// Pearson chi-square test of independence of `counts`, with Yates'
+    /// continuity correction on 2x2 tables when `continuity_correction`.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::Validation`] for a ragged table, negative or
+    /// non-finite counts, or fewer than two rows or columns with a positive
+    /// total.
+    pub fn chi_square_independence(
+        counts: &[Vec<f64>],
+        continuity_correction: bool,
+    ) -> Result<ChiSquareIndependenceResult, StatisticsError> {
+        let column_count = counts.first().map_or(0, Vec::len);
+        if counts.iter().any(|row| row.len() != column_count) {
+            return Err(StatisticsError::validation(
+                "counts",
+                "Every row of the table needs the same number of columns",
+            ));
+        }
+        if counts
+            .iter()
+            .flatten()
+            .any(|count| !count.is_finite() || *count < 0.0)
+        {
+            return Err(StatisticsError::validation(
+                "counts",
+                "Counts must be finite and non-negative",
+            ));
+        }
+        let row_totals: Vec<f64> = counts.iter().map(|row| row.iter().sum()).collect();
+        let column_totals: Vec<f64> = (0..column_count)
+            .map(|column| counts.iter().map(|row| row[column]).sum())
+            .collect();
+        if row_totals.len() < 2
+            || column_totals.len() < 2
+            || row_totals
+                .iter()
+                .chain(&column_totals)
+                .any(|total| *total <= 0.0)
+        {
+            return Err(StatisticsError::validation(
+                "counts",
+                "The table needs at least two rows and two columns, each with a positive total",
+            ));
+        }
+
+        let total: f64 = row_totals.iter().sum();
+        let corrected = continuity_correction && row_totals.len() == 2 && column_count == 2;
+        let mut statistic = 0.0;
+        let mut uncorrected = 0.0;
+        let mut low_expected_cells = 0;
+        let mut expected = Vec::with_capacity(counts.len());
+        let mut standardized_residuals = Vec::with_capacity(counts.len());
+        for (row, row_total) in counts.iter().zip(&row_totals) {
+            let mut expected_row = Vec::with_capacity(column_count);
+            let mut residual_row = Vec::with_capacity(column_count);
+            for (observed, column_total) in row.iter().zip(&column_totals) {
+                let cell_expected = row_total * column_total / total;
+                let deviation = observed - cell_expected;
+                uncorrected += deviation.powi(2) / cell_expected;
+                statistic += if corrected {
+                    (deviation.abs() - 0.5).max(0.0).powi(2) / cell_expected
+                } else {
+                    deviation.powi(2) / cell_expected
+                };
+                if cell_expected < MIN_EXPECTED_COUNT {
+                    low_expected_cells += 1;
+                }
+                expected_row.push(cell_expected);
+                residual_row.push(deviation / cell_expected.sqrt());
+            }
+            expected.push(expected_row);
+            standardized_residuals.push(residual_row);
+        }
+
+        let degrees_of_freedom = (row_totals.len() - 1) * (column_count - 1);
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Degrees of freedom and table size casting to f64"
+        )]
+        let (df, smaller_side) = (
+            degrees_of_freedom as f64,
+            (row_totals.len().min(column_count) - 1) as f64,
+        );
+        let p_value = ChiSquared::new(df)
+            .map_err(|error| error.to_string())?
+            .sf(statistic);
+        Ok(ChiSquareIndependenceResult {
+            statistic,
+            degrees_of_freedom,
+            p_value,
+            continuity_corrected: corrected,
+            cramers_v: (uncorrected / (total * smaller_side)).sqrt().min(1.0),
+            expected,
+            standardized_residuals,
+            low_expected_cells,
+        })
+    }
+
+    /// Two-sided Fisher exact p-value of the 2x2 table `[[a, b], [c, d]]`.
+    #[must_use]
+    pub fn fisher_exact_2x2(table: [[u64; 2]; 2]) -> f64 {
+        let [[a, b], [c, d]] = table;
+        let (first_row, first_column, total) = (a + b, a + c, a + b + c + d);
+        let log_probability = |x: u64| {
+            ln_binomial(first_column, x) + ln_binomial(total - first_column, first_row - x)
+                - ln_binomial(total, first_row)
+        };
+        let observed = log_probability(a);
+        let threshold = observed + FISHER_RELATIVE_TOLERANCE.ln_1p();
+        let lowest = (first_row + first_column).saturating_sub(total);
+        let p_value: f64 = (lowest..=first_row.min(first_column))
+            .map(log_probability)
+            .filter(|log_p| *log_p <= threshold)
+            .map(f64::exp)
+            .sum();
+        p_value.min(1.0)
+    }
+
+    /// Cross-tabulate two categorical columns and test their independence.
+    ///
+    /// The chi-square test uses Yates' correction on 2x2 tables when
+    /// `continuity_correction`. 2x2 tables of whole counts also get the Fisher
+    /// exact p-value, and expected counts below 5 add a warning.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::Validation`] for invalid columns or weights,
+    /// or a table with fewer than two categories on either side.
+    pub fn crosstab_and_test(
+        rows: &[Option<String>],
+        columns: &[Option<String>],
+        weights: Option<&[f64]>,
+        missing: MissingCategoryPolicy,
+        continuity_correction: bool,
+    ) -> Result<CrosstabResult, StatisticsError> {
+        let table = Self::crosstab(rows, columns, weights, missing)?;
+        let chi_square = Self::chi_square_independence(&table.counts, continuity_correction)?;
+
+        let fisher_exact_p_value = whole_counts_2x2(&table.counts).map(Self::fisher_exact_2x2);
+        let mut warnings = Vec::new();
+        if chi_square.low_expected_cells > 0 {
+            let cells = table.counts.len() * table.column_categories.len();
+            let recommendation = if fisher_exact_p_value.is_some() {
+                "prefer the Fisher exact p-value"
+            } else {
+                "consider Fisher's exact test or merging sparse categories"
+            };
+            warnings.push(format!(
+                "{} of {cells} cells have an expected count below {MIN_EXPECTED_COUNT}; the chi-square approximation may be unreliable, {recommendation}",
+                chi_square.low_expected_cells
+            ));
+        }
+
+        Ok(CrosstabResult {
+            table,
+            chi_square,
+            fisher_exact_p_value,
+            warnings,
+        })
+    }
+}
+
+/// Category names with their indices, in order of first appearance.
+#[derive(Default)]
+struct CategoryIndex {
+    names: Vec<String>,
+    positions: HashMap<String, usize>,
+}
+
+impl CategoryIndex {
+    fn get(&mut self, name: &str) -> usize {
+        if let Some(&position) = self.positions.get(name) {
+            return position;
+        }
+        let position = self.names.len();
+        self.names.push(name.to_owned());
+        self.positions.insert(name.to_owned(), position);
+        position
+    }
+}
+
+/// Category of a cell under the missing-value policy; `None` to drop the row.
+fn category(value: Option<&str>, missing: MissingCategoryPolicy) -> Option<&str> {
+    match value {
+        Some(name) if !name.trim().is_empty() => Some(name),
+        _ => match missing {
+            MissingCategoryPolicy::Drop => None,
+            MissingCategoryPolicy::Category => Some(MISSING_CATEGORY),
+        },
+    }
+}
+
+/// The table as whole counts, when it is 2x2 and every count is an integer.
+fn whole_counts_2x2(counts: &[Vec<f64>]) -> Option<[[u64; 2]; 2]> {
+    let [first, second] = counts else {
+        return None;
+    };
+    let whole = |count: f64| {
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            reason = "Checked to be a non-negative whole number"
+        )]
+        (count.fract() == 0.0).then_some(count as u64)
+    };
+    match (first.as_slice(), second.as_slice()) {
+        (&[a, b], &[c, d]) => Some([[whole(a)?, whole(b)?], [whole(c)?, whole(d)?]]),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn column(values: &[&str]) -> Vec<Option<String>> {
+        values
+            .iter()
+            .map(|&value| (value != "null").then(|| value.to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn test_crosstab_preserves_first_appearance_order() {
+        let rows = column(&["b", "a", "b", "null", "a", "", "c"]);
+        let columns = column(&["y", "x", "x", "x", "y", "y", "x"]);
+        let weights = [1.0, 2.0, 1.0, 1.0, 0.0, 1.0, 3.0];
+
+        let dropped = ChiSquareTesting::crosstab(
+            &rows,
+            &columns,
+            Some(&weights),
+            MissingCategoryPolicy::Drop,
+        )
+        .unwrap();
+        assert_eq!(dropped.row_categories, ["b", "a", "c"]);
+        assert_eq!(dropped.column_categories, ["y", "x"]);
+        assert_eq!(
+            dropped.counts,
+            vec![vec![1.0, 1.0], vec![0.0, 2.0], vec![0.0, 3.0]]
+        );
+        assert_eq!(dropped.skipped_rows, 3);
+
+        let kept =
+            ChiSquareTesting::crosstab(&rows, &columns, None, MissingCategoryPolicy::Category)
+                .unwrap();
+        assert_eq!(kept.row_categories, ["b", "a", MISSING_CATEGORY, "c"]);
+        assert_eq!(kept.counts[2], vec![1.0, 1.0]);
+        assert_eq!(kept.skipped_rows, 0);
+    }
+
+    #[test]
+    fn test_fisher_exact_tea_tasting() {
+        // Fisher's lady tasting tea: three of four cups identified correctly.
+        assert_relative_eq!(
+            ChiSquareTesting::fisher_exact_2x2([[3, 1], [1, 3]]),
+            34.0 / 70.0,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            ChiSquareTesting::fisher_exact_2x2([[4, 0], [0, 4]]),
+            2.0 / 70.0,
+            max_relative = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_crosstab_and_test_warns_on_low_expected_counts() {
+        let rows = column(&["t", "t", "t", "t", "f", "f", "f", "f"]);
+        let columns = column(&["t", "t", "t", "f", "t", "f", "f", "f"]);
+        let result = ChiSquareTesting::crosstab_and_test(
+            &rows,
+            &columns,
+            None,
+            MissingCategoryPolicy::Drop,
+            true,
+        )
+        .unwrap();
+        assert!(result.chi_square.continuity_corrected);
+        assert_eq!(result.chi_square.low_expected_cells, 4);
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("Fisher"));
+        assert_relative_eq!(
+            result.fisher_exact_p_value.unwrap(),
+            34.0 / 70.0,
+            max_relative = 1e-12
+        );
+        // |O - E| = 1 in every cell, so the corrected statistic is 4 * 0.25 / 2.
+        assert_relative_eq!(result.chi_square.statistic, 0.5, epsilon = 1e-12);
+        assert_relative_eq!(result.chi_square.cramers_v, 0.5, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_chi_square_independence_without_correction() {
+        let counts = vec![vec![20.0, 30.0, 50.0], vec![30.0, 20.0, 50.0]];
+        let result = ChiSquareTesting::chi_square_independence(&counts, true).unwrap();
+        assert!(!result.continuity_corrected);
+        assert_eq!(result.degrees_of_freedom, 2);
+        // Expected 25 in the first two columns: 4 * 25 / 25.
+        assert_relative_eq!(result.statistic, 4.0, epsilon = 1e-12);
+        assert_relative_eq!(result.p_value, (-2.0_f64).exp(), epsilon = 1e-12);
+        assert_relative_eq!(result.standardized_residuals[0][0], -1.0, epsilon = 1e-12);
+        assert!(ChiSquareTesting::chi_square_independence(&[vec![1.0, 2.0]], false).is_err());
+    }
+}
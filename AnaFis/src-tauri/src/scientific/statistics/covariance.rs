@@ -0,0 +1,159 @@
+//! Shrinkage covariance estimation.
+//!
+//! The sample covariance is singular when there are fewer observations than
+//! variables and badly conditioned when there are only a few more. Shrinking
+//! it towards `mu * I`, with `mu` the mean variance, gives a well-conditioned
+//! estimate; the weight of the target follows the oracle approximating
+//! shrinkage (OAS) formula of Chen, Wiesel, Eldar and Hero (2010).
+
+use nalgebra::DMatrix;
+
+use super::types::LedoitWolfResult;
+
+/// Covariance estimators.
+pub struct CovarianceOps;
+
+impl CovarianceOps {
+    /// Shrunk covariance of `data`, given as rows of observations.
+    ///
+    /// The sample covariance uses the maximum-likelihood divisor `n`, as the
+    /// shrinkage formula assumes.
+    ///
+    /// # Errors
+    /// Returns an error for fewer than two rows, ragged or non-finite rows, or
+    /// data without any variance.
+    pub fn ledoit_wolf(data: &[Vec<f64>]) -> Result<LedoitWolfResult, String> {
+        if data.len() < 2 {
+            return Err(format!(
+                "Covariance needs at least 2 observations, got {}",
+                data.len()
+            ));
+        }
+        let variables = data[0].len();
+        if variables == 0 {
+            return Err("Covariance needs at least one variable".to_owned());
+        }
+        for (index, row) in data.iter().enumerate() {
+            if row.len() != variables {
+                return Err(format!(
+                    "Row {index} has {} values, expected {variables}",
+                    row.len()
+                ));
+            }
+            if row.iter().any(|value| !value.is_finite()) {
+                return Err(format!("Row {index} contains non-finite values"));
+            }
+        }
+
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Row and variable counts casting to f64"
+        )]
+        let (count, dimension) = (data.len() as f64, variables as f64);
+        let means: Vec<f64> = (0..variables)
+            .map(|column| data.iter().map(|row| row[column]).sum::<f64>() / count)
+            .collect();
+        let centred = DMatrix::from_fn(data.len(), variables, |i, j| data[i][j] - means[j]);
+        let sample = centred.transpose() * &centred / count;
+
+        let trace = sample.trace();
+        if trace <= 0.0 {
+            return Err("Data have no variance".to_owned());
+        }
+        // tr(S^2) is the squared Frobenius norm of the symmetric S.
+        let trace_of_square = sample.norm_squared();
+        let mu = trace / dimension;
+
+        let numerator = (1.0 - 2.0 / dimension).mul_add(trace_of_square, trace * trace);
+        let denominator =
+            (count + 1.0 - 2.0 / dimension) * (trace_of_square - trace * trace / dimension);
+        let shrinkage = if denominator > 0.0 {
+            (numerator / denominator).clamp(0.0, 1.0)
+        } else {
+            // S is already a multiple of the identity.
+            1.0
+        };
+
+        let mut covariance = sample * (1.0 - shrinkage);
+        for i in 0..variables {
+            covariance[(i, i)] = shrinkage.mul_add(mu, covariance[(i, i)]);
+        }
+        let deviations: Vec<f64> = (0..variables).map(|i| covariance[(i, i)].sqrt()).collect();
+        let correlation = DMatrix::from_fn(variables, variables, |i, j| {
+            if i == j {
+                1.0
+            } else if deviations[i] > 0.0 && deviations[j] > 0.0 {
+                covariance[(i, j)] / (deviations[i] * deviations[j])
+            } else {
+                0.0
+            }
+        });
+
+        Ok(LedoitWolfResult {
+            covariance: rows(&covariance),
+            shrinkage,
+            correlation: rows(&correlation),
+        })
+    }
+}
+
+fn rows(matrix: &DMatrix<f64>) -> Vec<Vec<f64>> {
+    matrix
+        .row_iter()
+        .map(|row| row.iter().copied().collect())
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shrinkage_matches_oas_formula() {
+        let data = vec![
+            vec![1.0, 2.0],
+            vec![2.0, 3.5],
+            vec![3.0, 3.0],
+            vec![4.0, 6.5],
+        ];
+        // Sample covariance (divisor n): [[1.25, 1.625], [1.625, 2.8125]].
+        let (variance, covariance) = (1.25_f64, 1.625_f64);
+        let trace = 4.0625_f64;
+        let trace_of_square = 2.0_f64.mul_add(
+            covariance.powi(2),
+            variance.mul_add(variance, 2.8125_f64.powi(2)),
+        );
+        // With p = 2 the OAS numerator reduces to tr(S)^2 and n + 1 - 2/p to n.
+        let expected = trace * trace / (4.0 * (trace_of_square - trace * trace / 2.0));
+
+        let result = CovarianceOps::ledoit_wolf(&data).unwrap();
+        assert!((result.shrinkage - expected).abs() < 1e-12);
+        let mu = trace / 2.0;
+        let shrunk_variance = (1.0 - expected).mul_add(variance, expected * mu);
+        assert!((result.covariance[0][0] - shrunk_variance).abs() < 1e-12);
+        assert!(
+            (1.0 - expected)
+                .mul_add(-covariance, result.covariance[0][1])
+                .abs()
+                < 1e-12
+        );
+        assert!((result.correlation[0][0] - 1.0).abs() < 1e-12);
+        assert!(result.correlation[0][1] < 1.0);
+    }
+
+    #[test]
+    fn test_estimate_is_positive_definite_with_fewer_rows_than_variables() {
+        let data = vec![
+            vec![1.0, 0.5, -0.2, 3.0, 2.0],
+            vec![0.3, 1.5, 0.8, 2.0, -1.0],
+            vec![-0.7, 0.1, 1.9, 2.5, 0.5],
+        ];
+        let result = CovarianceOps::ledoit_wolf(&data).unwrap();
+        assert!(result.shrinkage > 0.0);
+        let covariance = DMatrix::from_fn(5, 5, |i, j| result.covariance[i][j]);
+        assert!(covariance.cholesky().is_some());
+
+        assert!(CovarianceOps::ledoit_wolf(&[vec![1.0, 2.0], vec![1.0, 2.0]]).is_err());
+    }
+}
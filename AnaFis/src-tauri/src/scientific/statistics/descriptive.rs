@@ -0,0 +1,240 @@
+//! Grouped descriptive statistics.
+//!
+//! Values are split by a string key column, keeping the order in which keys
+//! first appear. Each group gets the usual location and spread summaries and
+//! a weighted mean: with measurement uncertainties the weights are
+//! `w = 1 / u^2` and the standard error is `1 / sqrt(sum w)`; without them the
+//! weighted mean is the plain mean with standard error `s / sqrt(n)`. The
+//! groups are then compared by a one-way ANOVA.
+use std::collections::HashMap;
+
+use super::anova::AnovaTesting;
+use super::error::StatisticsError;
+use super::types::{GroupSummary, GroupedStatisticsResult};
+use crate::scientific::primitives::descriptive::{mean, median, sample_variance};
+
+/// Summaries of measurement columns.
+pub struct DescriptiveStatisticsEngine;
+
+impl DescriptiveStatisticsEngine {
+    /// Summaries of `values` grouped by `keys`, with optional standard
+    /// `uncertainties` for inverse-variance weighting.
+    ///
+    /// Rows whose value is not finite are skipped. Groups with fewer than
+    /// `min_group_size` values are still reported, flagged by
+    /// [`GroupSummary::below_min_size`].
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::Validation`] for columns of different
+    /// lengths or a non-positive or non-finite uncertainty on a used row, and
+    /// [`StatisticsError::InsufficientData`] when no value is finite.
+    pub fn grouped_statistics(
+        values: &[f64],
+        uncertainties: Option<&[f64]>,
+        keys: &[String],
+        min_group_size: usize,
+    ) -> Result<GroupedStatisticsResult, StatisticsError> {
+        if keys.len() != values.len() {
+            return Err(StatisticsError::validation(
+                "keys",
+                format!(
+                    "Expected one group key per value ({} values, {} keys)",
+                    values.len(),
+                    keys.len()
+                ),
+            ));
+        }
+        if let Some(uncertainties) = uncertainties
+            && uncertainties.len() != values.len()
+        {
+            return Err(StatisticsError::validation(
+                "uncertainties",
+                format!(
+                    "Expected one uncertainty per value ({} values, {} uncertainties)",
+                    values.len(),
+                    uncertainties.len()
+                ),
+            ));
+        }
+
+        let mut order: Vec<&str> = Vec::new();
+        let mut members: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (row, key) in keys.iter().enumerate() {
+            if !values[row].is_finite() {
+                continue;
+            }
+            if let Some(uncertainties) = uncertainties
+                && !(uncertainties[row].is_finite() && uncertainties[row] > 0.0)
+            {
+                return Err(StatisticsError::validation(
+                    "uncertainties",
+                    format!(
+                        "Uncertainty of row {row} must be positive and finite, got {}",
+                        uncertainties[row]
+                    ),
+                ));
+            }
+            members
+                .entry(key.as_str())
+                .or_insert_with(|| {
+                    order.push(key.as_str());
+                    Vec::new()
+                })
+                .push(row);
+        }
+        if order.is_empty() {
+            return Err(StatisticsError::InsufficientData { needed: 1, got: 0 });
+        }
+
+        let group_values: Vec<Vec<f64>> = order
+            .iter()
+            .map(|key| members[key].iter().map(|&row| values[row]).collect())
+            .collect();
+        let groups = order
+            .iter()
+            .zip(&group_values)
+            .map(|(key, group)| {
+                let weights = uncertainties.map(|uncertainties| {
+                    members[key]
+                        .iter()
+                        .map(|&row| uncertainties[row].powi(-2))
+                        .collect::<Vec<f64>>()
+                });
+                summarize(key, group, weights.as_deref(), min_group_size)
+            })
+            .collect();
+
+        let total_count: usize = group_values.iter().map(Vec::len).sum();
+        let pooled_within_variance = (total_count > group_values.len()).then(|| {
+            let within: f64 = group_values
+                .iter()
+                .map(|group| {
+                    let center = mean(group).unwrap_or(0.0);
+                    group
+                        .iter()
+                        .map(|value| (value - center).powi(2))
+                        .sum::<f64>()
+                })
+                .sum();
+            #[allow(
+                clippy::cast_precision_loss,
+                reason = "Degrees of freedom casting to f64"
+            )]
+            let degrees_of_freedom = (total_count - group_values.len()) as f64;
+            within / degrees_of_freedom
+        });
+
+        Ok(GroupedStatisticsResult {
+            groups,
+            pooled_within_variance,
+            anova: AnovaTesting::one_way_anova(&group_values).ok(),
+        })
+    }
+}
+
+/// Summary of one non-empty group, weighted by `weights` when given.
+pub(super) fn summarize(
+    key: &str,
+    group: &[f64],
+    weights: Option<&[f64]>,
+    min_group_size: usize,
+) -> GroupSummary {
+    let center = mean(group).unwrap_or(0.0);
+    let std_dev = sample_variance(group).map(f64::sqrt);
+    #[allow(clippy::cast_precision_loss, reason = "Group size casting to f64")]
+    let size = group.len() as f64;
+    let (weighted_mean, weighted_mean_standard_error) = weights.map_or_else(
+        || (center, std_dev.map(|std_dev| std_dev / size.sqrt())),
+        |weights| {
+            let total: f64 = weights.iter().sum();
+            let weighted: f64 = weights.iter().zip(group).map(|(w, x)| w * x).sum();
+            (weighted / total, Some(total.sqrt().recip()))
+        },
+    );
+    GroupSummary {
+        key: key.to_owned(),
+        count: group.len(),
+        mean: center,
+        weighted_mean,
+        weighted_mean_standard_error,
+        median: median(group).unwrap_or(center),
+        std_dev,
+        min: group.iter().copied().fold(f64::INFINITY, f64::min),
+        max: group.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        below_min_size: group.len() < min_group_size,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn keys(names: &[&str]) -> Vec<String> {
+        names.iter().map(|&name| name.to_owned()).collect()
+    }
+
+    #[test]
+    fn test_grouped_statistics_three_groups() {
+        let values = [4.0, 1.0, 5.0, 2.0, 9.0, 6.0, f64::NAN, 3.0, 5.0];
+        let uncertainties = [1.0, 1.0, 1.0, 1.0, 2.0, 1.0, 1.0, 0.5, 1.0];
+        let keys = keys(&["b", "a", "b", "a", "c", "b", "c", "a", "b"]);
+        let result = DescriptiveStatisticsEngine::grouped_statistics(
+            &values,
+            Some(&uncertainties),
+            &keys,
+            2,
+        )
+        .unwrap();
+
+        let order: Vec<&str> = result
+            .groups
+            .iter()
+            .map(|group| group.key.as_str())
+            .collect();
+        assert_eq!(order, ["b", "a", "c"]);
+        let (b, a, c) = (&result.groups[0], &result.groups[1], &result.groups[2]);
+        assert_relative_eq!(b.mean, 5.0);
+        assert_relative_eq!(b.weighted_mean, 5.0);
+        assert_relative_eq!(b.weighted_mean_standard_error.unwrap(), 0.5);
+        // Weights 1, 1, 4 on 1, 2, 3.
+        assert_relative_eq!(a.mean, 2.0);
+        assert_relative_eq!(a.weighted_mean, 2.5);
+        assert_relative_eq!(
+            a.weighted_mean_standard_error.unwrap(),
+            6.0_f64.sqrt().recip()
+        );
+        assert_eq!((c.count, c.std_dev), (1, None));
+        assert!(c.below_min_size && !a.below_min_size && !b.below_min_size);
+        assert_relative_eq!(c.weighted_mean_standard_error.unwrap(), 2.0);
+
+        assert_relative_eq!(result.pooled_within_variance.unwrap(), 0.8);
+        let anova = result.anova.unwrap();
+        let reference = (1.0 + 2.0 * 24.921_875 / 5.0_f64).powf(-2.5);
+        assert_relative_eq!(anova.p_value, reference, max_relative = 1e-9);
+    }
+
+    #[test]
+    fn test_grouped_statistics_without_uncertainties() {
+        let result = DescriptiveStatisticsEngine::grouped_statistics(
+            &[1.0, 3.0, 10.0],
+            None,
+            &keys(&["x", "x", "y"]),
+            1,
+        )
+        .unwrap();
+        let x = &result.groups[0];
+        assert_relative_eq!(x.weighted_mean, 2.0);
+        assert_relative_eq!(x.weighted_mean_standard_error.unwrap(), 1.0);
+        assert_relative_eq!(x.median, 2.0);
+        assert!(result.anova.is_some());
+        assert!(
+            DescriptiveStatisticsEngine::grouped_statistics(&[1.0], Some(&[0.0]), &keys(&["x"]), 1)
+                .is_err()
+        );
+        assert!(
+            DescriptiveStatisticsEngine::grouped_statistics(&[1.0], None, &keys(&[]), 1).is_err()
+        );
+    }
+}
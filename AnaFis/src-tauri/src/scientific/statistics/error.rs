@@ -0,0 +1,81 @@
+//! Structured errors shared by the statistics engines.
+use thiserror::Error;
+
+use crate::error::{CommandError, codes, numerical_error, validation_error};
+
+/// Failure of a statistical computation, classified so the frontend can tell
+/// bad input from too little data or a numerical breakdown.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum StatisticsError {
+    /// An input or option is invalid.
+    #[error("{message}")]
+    Validation {
+        /// Request field the error refers to.
+        field: String,
+        /// Human-readable description.
+        message: String,
+    },
+    /// The series or sample is too short for the requested analysis.
+    #[error("At least {needed} observations are required, got {got}")]
+    InsufficientData {
+        /// Minimum number of observations.
+        needed: usize,
+        /// Number of observations supplied.
+        got: usize,
+    },
+    /// The computation failed numerically (singular system, divergence, ...).
+    #[error("{context}")]
+    Numerical {
+        /// What was being computed when it failed.
+        context: String,
+    },
+    /// A linear system or design matrix is singular.
+    #[error("{context}")]
+    Singular {
+        /// Which system was singular.
+        context: String,
+    },
+    /// The requested analysis is not available yet.
+    #[error("This analysis is not implemented yet")]
+    NotImplemented,
+}
+
+impl StatisticsError {
+    /// Validation error for `field`.
+    pub fn validation(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Validation {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Internal helpers still report plain strings; those are numerical failures.
+impl From<String> for StatisticsError {
+    fn from(context: String) -> Self {
+        Self::Numerical { context }
+    }
+}
+
+impl From<StatisticsError> for CommandError {
+    fn from(error: StatisticsError) -> Self {
+        let detail = error.to_string();
+        match error {
+            StatisticsError::Validation { field, .. } => validation_error(detail, Some(field)),
+            StatisticsError::InsufficientData { .. } => Self::Validation {
+                code: codes::INSUFFICIENT_DATA,
+                field: None,
+                detail,
+            },
+            StatisticsError::Numerical { .. } => numerical_error(detail),
+            StatisticsError::Singular { .. } => Self::Numerical {
+                code: codes::MATRIX_SINGULAR,
+                detail,
+            },
+            StatisticsError::NotImplemented => Self::Internal {
+                code: codes::NOT_IMPLEMENTED,
+                detail,
+            },
+        }
+    }
+}
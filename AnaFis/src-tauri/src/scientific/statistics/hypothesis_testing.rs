@@ -0,0 +1,270 @@
+//! Two-sample location tests and normality tests.
+//!
+//! Welch's t-test compares the means of two samples without assuming equal
+//! variances, using the Welch-Satterthwaite degrees of freedom. The
+//! Mann-Whitney U test ranks the pooled samples and compares the rank sum
+//! of the first sample with its expectation under identical distributions.
+//! The p-value uses the normal approximation with tie and continuity
+//! corrections, which is accurate from about eight observations per sample.
+//!
+//! Normality is tested with Anderson-Darling against a normal with estimated
+//! mean and variance, using Stephens' small-sample correction and the
+//! D'Agostino and Stephens (1986) p-value approximation.
+use std::cmp::Ordering;
+
+use statrs::distribution::{ContinuousCDF, Normal, StudentsT};
+
+use super::types::{MannWhitneyResult, NormalityTestResult, WelchTTestResult};
+use crate::scientific::primitives::descriptive::{mean, sample_variance, sorted};
+
+/// Fewest observations accepted by the normality test.
+const MIN_NORMALITY_OBSERVATIONS: usize = 8;
+
+/// Two-sample and normality hypothesis tests.
+pub struct HypothesisTestingEngine;
+
+impl HypothesisTestingEngine {
+    /// Two-sided Mann-Whitney U test of `sample1` against `sample2`.
+    ///
+    /// # Errors
+    /// Returns an error for an empty sample, non-finite values or pooled
+    /// samples whose values are all tied.
+    pub fn mann_whitney_u(sample1: &[f64], sample2: &[f64]) -> Result<MannWhitneyResult, String> {
+        if sample1.is_empty() || sample2.is_empty() {
+            return Err("Mann-Whitney U test requires two non-empty samples".to_owned());
+        }
+        if sample1
+            .iter()
+            .chain(sample2)
+            .any(|value| !value.is_finite())
+        {
+            return Err("Samples must contain only finite values".to_owned());
+        }
+
+        let pooled: Vec<f64> = sample1.iter().chain(sample2).copied().collect();
+        let (ranks, tie_term) = average_ranks(&pooled);
+        #[allow(clippy::cast_precision_loss, reason = "Sample sizes casting to f64")]
+        let (n1, n2) = (sample1.len() as f64, sample2.len() as f64);
+        let total = n1 + n2;
+        let rank_sum: f64 = ranks[..sample1.len()].iter().sum();
+        let u_statistic = n1.mul_add(-(n1 + 1.0) / 2.0, rank_sum);
+
+        let mean_u = n1 * n2 / 2.0;
+        let variance_u = n1 * n2 / 12.0 * (total + 1.0 - tie_term / (total * (total - 1.0)));
+        if variance_u <= 0.0 {
+            return Err("All values are tied; the test is undefined".to_owned());
+        }
+        let deviation = u_statistic - mean_u;
+        let corrected = (deviation.abs() - 0.5).max(0.0);
+        let z_score = corrected.copysign(deviation) / variance_u.sqrt();
+
+        let standard_normal = Normal::new(0.0, 1.0).map_err(|error| error.to_string())?;
+        let p_value = (2.0 * (1.0 - standard_normal.cdf(z_score.abs()))).min(1.0);
+
+        Ok(MannWhitneyResult {
+            u_statistic,
+            z_score,
+            p_value,
+            rank_biserial: 2.0 * u_statistic / (n1 * n2) - 1.0,
+        })
+    }
+
+    /// Two-sided Welch t-test of the means of `sample1` and `sample2`.
+    ///
+    /// # Errors
+    /// Returns an error for a sample with fewer than two values, non-finite
+    /// values or two constant samples.
+    pub fn welch_t_test(sample1: &[f64], sample2: &[f64]) -> Result<WelchTTestResult, String> {
+        if sample1
+            .iter()
+            .chain(sample2)
+            .any(|value| !value.is_finite())
+        {
+            return Err("Samples must contain only finite values".to_owned());
+        }
+        let (Some(variance1), Some(variance2)) =
+            (sample_variance(sample1), sample_variance(sample2))
+        else {
+            return Err("Welch t-test requires at least two values per sample".to_owned());
+        };
+        let mean_difference = mean(sample1).unwrap_or_default() - mean(sample2).unwrap_or_default();
+        #[allow(clippy::cast_precision_loss, reason = "Sample sizes casting to f64")]
+        let (n1, n2) = (sample1.len() as f64, sample2.len() as f64);
+        let (share1, share2) = (variance1 / n1, variance2 / n2);
+        let standard_error_squared = share1 + share2;
+        if standard_error_squared <= 0.0 {
+            return Err("Both samples are constant; the test is undefined".to_owned());
+        }
+
+        let t_statistic = mean_difference / standard_error_squared.sqrt();
+        let degrees_of_freedom = standard_error_squared.powi(2)
+            / (share1.powi(2) / (n1 - 1.0) + share2.powi(2) / (n2 - 1.0));
+        let distribution =
+            StudentsT::new(0.0, 1.0, degrees_of_freedom).map_err(|error| error.to_string())?;
+        let p_value = (2.0 * distribution.sf(t_statistic.abs())).min(1.0);
+
+        Ok(WelchTTestResult {
+            t_statistic,
+            degrees_of_freedom,
+            p_value,
+            mean_difference,
+        })
+    }
+
+    /// Anderson-Darling test of `data` against a normal distribution with
+    /// the sample mean and standard deviation.
+    ///
+    /// # Errors
+    /// Returns an error for fewer than eight observations, non-finite values
+    /// or constant data.
+    pub fn anderson_darling_normality(data: &[f64]) -> Result<NormalityTestResult, String> {
+        if data.len() < MIN_NORMALITY_OBSERVATIONS {
+            return Err(format!(
+                "Normality test requires at least {MIN_NORMALITY_OBSERVATIONS} observations, got {}",
+                data.len()
+            ));
+        }
+        if data.iter().any(|value| !value.is_finite()) {
+            return Err("Data must contain only finite values".to_owned());
+        }
+        let center = mean(data).unwrap_or(0.0);
+        let scale = sample_variance(data).unwrap_or(0.0).sqrt();
+        if scale <= 0.0 {
+            return Err("Data has no variance".to_owned());
+        }
+
+        let standard_normal = Normal::new(0.0, 1.0).map_err(|error| error.to_string())?;
+        let cdf: Vec<f64> = sorted(data)
+            .iter()
+            .map(|value| standard_normal.cdf((value - center) / scale))
+            .collect();
+        #[allow(clippy::cast_precision_loss, reason = "Sample count casting to f64")]
+        let n = data.len() as f64;
+        let sum: f64 = cdf
+            .iter()
+            .zip(cdf.iter().rev())
+            .enumerate()
+            .map(|(index, (lower, upper))| {
+                #[allow(clippy::cast_precision_loss, reason = "Order statistic index")]
+                let weight = 2.0_f64.mul_add(index as f64, 1.0);
+                weight
+                    * (lower.max(f64::MIN_POSITIVE).ln()
+                        + (1.0 - upper).max(f64::MIN_POSITIVE).ln())
+            })
+            .sum();
+        let statistic = -n - sum / n;
+        let corrected = statistic * (1.0 + 0.75 / n + 2.25 / (n * n));
+
+        let p_value = if corrected >= 0.6 {
+            (0.0186 * corrected)
+                .mul_add(corrected, 5.709_f64.mul_add(-corrected, 1.2937))
+                .exp()
+        } else if corrected >= 0.34 {
+            (-1.38 * corrected)
+                .mul_add(corrected, 4.279_f64.mul_add(-corrected, 0.9177))
+                .exp()
+        } else if corrected >= 0.2 {
+            1.0 - (-59.938 * corrected)
+                .mul_add(corrected, 42.796_f64.mul_add(corrected, -8.318))
+                .exp()
+        } else {
+            1.0 - (-223.73 * corrected)
+                .mul_add(corrected, 101.14_f64.mul_add(corrected, -13.436))
+                .exp()
+        };
+
+        Ok(NormalityTestResult {
+            statistic: corrected,
+            p_value: p_value.clamp(0.0, 1.0),
+        })
+    }
+}
+
+/// Ranks of `values` in their original order, ties sharing their average
+/// rank, with the tie correction term `sum(t^3 - t)` over tie groups.
+fn average_ranks(values: &[f64]) -> (Vec<f64>, f64) {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap_or(Ordering::Equal));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut tie_term = 0.0;
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]].total_cmp(&values[order[start]]).is_eq() {
+            end += 1;
+        }
+        #[allow(clippy::cast_precision_loss, reason = "Rank positions casting to f64")]
+        let (average, ties) = ((start + end + 1) as f64 / 2.0, (end - start) as f64);
+        for &index in &order[start..end] {
+            ranks[index] = average;
+        }
+        tie_term += ties.powi(3) - ties;
+        start = end;
+    }
+    (ranks, tie_term)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_mann_whitney_separated_samples() {
+        let first = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+        let second = [9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0];
+        let result = HypothesisTestingEngine::mann_whitney_u(&first, &second).unwrap();
+        assert_relative_eq!(result.u_statistic, 0.0);
+        assert_relative_eq!(result.rank_biserial, -1.0);
+        // z = (0 - 32 + 0.5) / sqrt(8 * 8 * 17 / 12)
+        assert_relative_eq!(result.z_score, -31.5 / (64.0_f64 * 17.0 / 12.0).sqrt());
+        assert!(result.p_value < 0.001);
+    }
+
+    #[test]
+    fn test_mann_whitney_ties_share_average_ranks() {
+        let (ranks, tie_term) = average_ranks(&[3.0, 1.0, 3.0, 2.0, 3.0]);
+        assert_eq!(ranks, vec![4.0, 1.0, 4.0, 2.0, 4.0]);
+        assert_relative_eq!(tie_term, 24.0);
+
+        let result = HypothesisTestingEngine::mann_whitney_u(&[1.0, 2.0], &[2.0, 3.0]).unwrap();
+        assert_relative_eq!(result.u_statistic, 0.5);
+        assert!(HypothesisTestingEngine::mann_whitney_u(&[1.0, 1.0], &[1.0]).is_err());
+    }
+
+    #[test]
+    fn test_welch_t_test() {
+        let result = HypothesisTestingEngine::welch_t_test(
+            &[1.0, 2.0, 3.0, 4.0, 5.0],
+            &[2.0, 4.0, 6.0, 8.0, 10.0],
+        )
+        .unwrap();
+        // t = -3 / sqrt(2.5 / 5 + 10 / 5), df = 2.5^2 / (0.5^2 / 4 + 2^2 / 4)
+        assert_relative_eq!(result.t_statistic, -3.0 / 2.5_f64.sqrt());
+        assert_relative_eq!(result.degrees_of_freedom, 6.25 / 1.0625);
+        assert_relative_eq!(result.p_value, 0.107_531, max_relative = 1e-5);
+        assert_relative_eq!(result.mean_difference, -3.0);
+        assert!(HypothesisTestingEngine::welch_t_test(&[1.0], &[1.0, 2.0]).is_err());
+        assert!(HypothesisTestingEngine::welch_t_test(&[1.0, 1.0], &[2.0, 2.0]).is_err());
+    }
+
+    #[test]
+    fn test_anderson_darling_normality() {
+        let standard_normal = Normal::new(0.0, 1.0).unwrap();
+        let normal_scores: Vec<f64> = (1..=50)
+            .map(|rank| standard_normal.inverse_cdf((f64::from(rank) - 0.5) / 50.0))
+            .collect();
+        let normal = HypothesisTestingEngine::anderson_darling_normality(&normal_scores).unwrap();
+        assert!(normal.p_value > 0.5);
+
+        let skewed: Vec<f64> = normal_scores
+            .iter()
+            .map(|score| score.exp().powi(2))
+            .collect();
+        let skewed = HypothesisTestingEngine::anderson_darling_normality(&skewed).unwrap();
+        assert!(skewed.p_value < 0.001);
+        assert!(HypothesisTestingEngine::anderson_darling_normality(&[1.0; 10]).is_err());
+    }
+}
@@ -0,0 +1,644 @@
+//! Symmetric eigen-decomposition and exploratory factor analysis.
+//!
+//! Factors are extracted by maximum likelihood from the correlation matrix.
+//! For fixed uniquenesses the optimal loadings follow from the leading
+//! eigenvectors of the uniqueness-scaled correlation matrix, so only the
+//! uniquenesses are optimised, by projected gradient descent on the
+//! concentrated discrepancy `ln|Sigma| + tr(Sigma^-1 R) - ln|R| - p`.
+
+use nalgebra::{DMatrix, SymmetricEigen};
+use rand::{Rng, RngCore, SeedableRng};
+use rand_distr::StandardNormal;
+use rand_pcg::Pcg64;
+use rayon::prelude::*;
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+
+use super::types::{EfaResult, FactorRotation, ParallelAnalysisResult};
+use crate::scientific::primitives::descriptive::{quantile_sorted, sorted};
+
+/// Bounds on a uniqueness during extraction.
+const MIN_UNIQUENESS: f64 = 0.005;
+const MAX_UNIQUENESS: f64 = 1.0;
+/// Iteration limit of the uniqueness gradient descent.
+const MAX_EXTRACTION_ITERATIONS: usize = 5000;
+/// Line-search halvings before the descent is declared stalled.
+const MAX_STEP_HALVINGS: usize = 40;
+/// Largest uniqueness update accepted as converged.
+const EXTRACTION_TOLERANCE: f64 = 1e-8;
+/// Iteration limit of the rotation algorithms.
+const MAX_ROTATION_ITERATIONS: usize = 1000;
+/// Fewest random data sets accepted by parallel analysis.
+const MIN_SIMULATIONS: usize = 10;
+/// Percentile of the random eigenvalues used as the retention threshold.
+const PARALLEL_PERCENTILE: f64 = 0.95;
+
+/// Eigenvalues of a symmetric matrix with matching eigenvectors.
+#[derive(Debug, Clone)]
+pub struct EigenDecomposition {
+    /// Eigenvalues in decreasing order.
+    pub values: Vec<f64>,
+    /// Unit eigenvectors as columns, in the order of `values`.
+    pub vectors: DMatrix<f64>,
+}
+
+/// Matrix decompositions and the factor models built on them.
+pub struct MatrixOpsEngine;
+
+impl MatrixOpsEngine {
+    /// Eigen-decomposition of the symmetric `matrix`, sorted by decreasing
+    /// eigenvalue.
+    #[must_use]
+    pub fn eigenvalue_decomposition(matrix: &DMatrix<f64>) -> EigenDecomposition {
+        let eigen = SymmetricEigen::new(matrix.clone());
+        let mut order: Vec<usize> = (0..eigen.eigenvalues.len()).collect();
+        order.sort_by(|&a, &b| eigen.eigenvalues[b].total_cmp(&eigen.eigenvalues[a]));
+        let values = order
+            .iter()
+            .map(|&index| eigen.eigenvalues[index])
+            .collect();
+        let columns: Vec<_> = order
+            .iter()
+            .map(|&index| eigen.eigenvectors.column(index).into_owned())
+            .collect();
+        EigenDecomposition {
+            values,
+            vectors: DMatrix::from_columns(&columns),
+        }
+    }
+
+    /// Pearson correlation matrix of `data`, given as rows of observations.
+    ///
+    /// # Errors
+    /// Returns an error for fewer than two rows, ragged or non-finite rows, or
+    /// a constant variable.
+    pub fn correlation_matrix(data: &[Vec<f64>]) -> Result<DMatrix<f64>, String> {
+        if data.len() < 2 {
+            return Err(format!(
+                "Correlation needs at least 2 observations, got {}",
+                data.len()
+            ));
+        }
+        let variables = data[0].len();
+        if variables == 0 {
+            return Err("Correlation needs at least one variable".to_owned());
+        }
+        for (index, row) in data.iter().enumerate() {
+            if row.len() != variables {
+                return Err(format!(
+                    "Row {index} has {} values, expected {variables}",
+                    row.len()
+                ));
+            }
+            if row.iter().any(|value| !value.is_finite()) {
+                return Err(format!("Row {index} contains non-finite values"));
+            }
+        }
+
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Row count casting to f64 for moments"
+        )]
+        let count = data.len() as f64;
+        let means: Vec<f64> = (0..variables)
+            .map(|column| data.iter().map(|row| row[column]).sum::<f64>() / count)
+            .collect();
+        let mut covariance = DMatrix::<f64>::zeros(variables, variables);
+        for row in data {
+            for i in 0..variables {
+                let centred_i = row[i] - means[i];
+                for j in 0..=i {
+                    covariance[(i, j)] = centred_i.mul_add(row[j] - means[j], covariance[(i, j)]);
+                }
+            }
+        }
+        let deviations: Vec<f64> = (0..variables).map(|i| covariance[(i, i)].sqrt()).collect();
+        if let Some(constant) = deviations.iter().position(|&deviation| deviation <= 0.0) {
+            return Err(format!("Variable {constant} is constant"));
+        }
+        let mut correlation = DMatrix::<f64>::identity(variables, variables);
+        for i in 0..variables {
+            for j in 0..i {
+                let value = covariance[(i, j)] / (deviations[i] * deviations[j]);
+                correlation[(i, j)] = value;
+                correlation[(j, i)] = value;
+            }
+        }
+        Ok(correlation)
+    }
+
+    /// Maximum-likelihood exploratory factor analysis of `data`.
+    ///
+    /// Loadings are reported one vector per factor over the variables. Each
+    /// rotated factor is oriented to have a positive loading sum, and factors
+    /// are ordered by the variance they explain.
+    ///
+    /// # Errors
+    /// Returns an error for invalid data, a singular correlation matrix, or a
+    /// factor count leaving negative degrees of freedom.
+    pub fn efa(
+        data: &[Vec<f64>],
+        n_factors: usize,
+        rotation: FactorRotation,
+    ) -> Result<EfaResult, String> {
+        let correlation = Self::correlation_matrix(data)?;
+        let variables = correlation.nrows();
+        if n_factors == 0 {
+            return Err("Number of factors must be at least 1".to_owned());
+        }
+        // Free covariances minus free parameters; (p - m)^2 and p + m share parity.
+        let free = variables.saturating_sub(n_factors).pow(2);
+        let parameters = variables + n_factors;
+        if n_factors >= variables || free < parameters {
+            return Err(format!(
+                "{n_factors} factors are too many for {variables} variables"
+            ));
+        }
+        #[allow(clippy::integer_division, reason = "The difference is even")]
+        let degrees_of_freedom = (free - parameters) / 2;
+        let inverse = correlation
+            .clone()
+            .try_inverse()
+            .ok_or_else(|| "Correlation matrix is singular".to_owned())?;
+
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Factor and variable counts casting to f64"
+        )]
+        let start_share = 1.0 - 0.5 * n_factors as f64 / variables as f64;
+        let start: Vec<f64> = (0..variables)
+            .map(|i| (start_share / inverse[(i, i)]).clamp(MIN_UNIQUENESS, MAX_UNIQUENESS))
+            .collect();
+        let extraction = extract_factors(&correlation, n_factors, start);
+
+        let mut unrotated = extraction.loadings.clone();
+        orient_factors(&mut unrotated, None);
+        let (mut rotated, mut factor_correlations) = match rotation {
+            FactorRotation::None => (unrotated.clone(), None),
+            FactorRotation::Varimax => (varimax(&unrotated), None),
+            FactorRotation::Oblimin => {
+                let (pattern, phi) = oblimin(&unrotated)?;
+                (pattern, Some(phi))
+            }
+        };
+        orient_factors(&mut rotated, factor_correlations.as_mut());
+
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Counts casting to f64 for the Bartlett correction"
+        )]
+        let bartlett = 2.0_f64.mul_add(variables as f64, 5.0).mul_add(
+            -1.0 / 6.0,
+            (n_factors as f64).mul_add(-2.0 / 3.0, data.len() as f64 - 1.0),
+        );
+        let chi_square = (bartlett * extraction.discrepancy).max(0.0);
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Degrees of freedom casting to f64"
+        )]
+        let p_value = (degrees_of_freedom > 0)
+            .then(|| ChiSquared::new(degrees_of_freedom as f64).ok())
+            .flatten()
+            .map(|chi| chi.sf(chi_square));
+
+        Ok(EfaResult {
+            communalities: unrotated
+                .row_iter()
+                .map(|row| row.iter().map(|value| value * value).sum())
+                .collect(),
+            unrotated_loadings: factor_vectors(&unrotated),
+            rotated_loadings: factor_vectors(&rotated),
+            uniquenesses: extraction.uniquenesses,
+            factor_correlations: factor_correlations.map(|phi| {
+                phi.row_iter()
+                    .map(|row| row.iter().copied().collect())
+                    .collect()
+            }),
+            rotation,
+            chi_square,
+            degrees_of_freedom,
+            p_value,
+            iterations: extraction.iterations,
+            converged: extraction.converged,
+        })
+    }
+
+    /// Horn's parallel analysis: keep the leading factors whose correlation
+    /// eigenvalues exceed the 95th percentile of those of random normal data
+    /// of the same shape.
+    ///
+    /// # Errors
+    /// Returns an error for invalid data as in [`MatrixOpsEngine::efa`], or
+    /// fewer than ten simulations.
+    pub fn parallel_analysis_scree(
+        data: &[Vec<f64>],
+        n_simulations: usize,
+        seed: u64,
+    ) -> Result<ParallelAnalysisResult, String> {
+        let correlation = Self::correlation_matrix(data)?;
+        if n_simulations < MIN_SIMULATIONS {
+            return Err(format!(
+                "At least {MIN_SIMULATIONS} simulations are required, got {n_simulations}"
+            ));
+        }
+        let observed_eigenvalues = Self::eigenvalue_decomposition(&correlation).values;
+        let (rows, variables) = (data.len(), correlation.nrows());
+
+        let mut seeder = Pcg64::seed_from_u64(seed);
+        let seeds: Vec<u64> = (0..n_simulations).map(|_| seeder.next_u64()).collect();
+        let simulated: Vec<Vec<f64>> = seeds
+            .into_par_iter()
+            .map(|simulation_seed| {
+                let mut rng = Pcg64::seed_from_u64(simulation_seed);
+                let noise: Vec<Vec<f64>> = (0..rows)
+                    .map(|_| {
+                        (0..variables)
+                            .map(|_| rng.sample::<f64, _>(StandardNormal))
+                            .collect()
+                    })
+                    .collect();
+                // Continuous noise is never exactly constant.
+                Self::correlation_matrix(&noise).map_or_else(
+                    |_| vec![1.0; variables],
+                    |matrix| Self::eigenvalue_decomposition(&matrix).values,
+                )
+            })
+            .collect();
+
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Simulation count casting to f64 for the mean"
+        )]
+        let random_mean_eigenvalues: Vec<f64> = (0..variables)
+            .map(|index| {
+                simulated.iter().map(|values| values[index]).sum::<f64>() / n_simulations as f64
+            })
+            .collect();
+        let random_percentile_eigenvalues: Vec<f64> = (0..variables)
+            .map(|index| {
+                let values: Vec<f64> = simulated.iter().map(|values| values[index]).collect();
+                quantile_sorted(&sorted(&values), PARALLEL_PERCENTILE).unwrap_or(f64::NAN)
+            })
+            .collect();
+        let n_factors = observed_eigenvalues
+            .iter()
+            .zip(&random_percentile_eigenvalues)
+            .take_while(|(observed, threshold)| observed > threshold)
+            .count();
+
+        Ok(ParallelAnalysisResult {
+            observed_eigenvalues,
+            random_mean_eigenvalues,
+            random_percentile_eigenvalues,
+            n_factors,
+        })
+    }
+}
+
+/// Outcome of the maximum-likelihood extraction.
+struct Extraction {
+    loadings: DMatrix<f64>,
+    uniquenesses: Vec<f64>,
+    discrepancy: f64,
+    iterations: usize,
+    converged: bool,
+}
+
+/// Concentrated discrepancy and optimal loadings for fixed uniquenesses.
+fn discrepancy(
+    correlation: &DMatrix<f64>,
+    factors: usize,
+    uniquenesses: &[f64],
+) -> (f64, DMatrix<f64>) {
+    let variables = correlation.nrows();
+    let scales: Vec<f64> = uniquenesses.iter().map(|psi| psi.sqrt()).collect();
+    let standardised = DMatrix::from_fn(variables, variables, |i, j| {
+        correlation[(i, j)] / (scales[i] * scales[j])
+    });
+    let eigen = MatrixOpsEngine::eigenvalue_decomposition(&standardised);
+    let value = eigen.values[factors..]
+        .iter()
+        .map(|&theta| theta - theta.max(f64::MIN_POSITIVE).ln() - 1.0)
+        .sum();
+    let loadings = DMatrix::from_fn(variables, factors, |i, k| {
+        scales[i] * eigen.vectors[(i, k)] * (eigen.values[k] - 1.0).max(0.0).sqrt()
+    });
+    (value, loadings)
+}
+
+/// Minimise the discrepancy over the uniquenesses by projected gradient
+/// descent with a backtracking line search.
+fn extract_factors(correlation: &DMatrix<f64>, factors: usize, start: Vec<f64>) -> Extraction {
+    let mut uniquenesses = start;
+    let (mut value, mut loadings) = discrepancy(correlation, factors, &uniquenesses);
+    let mut step = 1.0_f64;
+    let mut converged = false;
+    let mut iterations = 0;
+    while iterations < MAX_EXTRACTION_ITERATIONS {
+        iterations += 1;
+        // d F / d psi_i = (Lambda Lambda' + Psi - R)_ii / psi_i^2
+        let gradient: Vec<f64> = (0..uniquenesses.len())
+            .map(|i| {
+                let common: f64 = loadings
+                    .row(i)
+                    .iter()
+                    .map(|loading| loading * loading)
+                    .sum();
+                (common + uniquenesses[i] - correlation[(i, i)]) / uniquenesses[i].powi(2)
+            })
+            .collect();
+
+        step *= 2.0;
+        let mut accepted = None;
+        for _ in 0..MAX_STEP_HALVINGS {
+            let candidate: Vec<f64> = uniquenesses
+                .iter()
+                .zip(&gradient)
+                .map(|(psi, slope)| {
+                    step.mul_add(-slope, *psi)
+                        .clamp(MIN_UNIQUENESS, MAX_UNIQUENESS)
+                })
+                .collect();
+            let decrease: f64 = gradient
+                .iter()
+                .zip(uniquenesses.iter().zip(&candidate))
+                .map(|(slope, (old, new))| slope * (old - new))
+                .sum();
+            let (candidate_value, candidate_loadings) =
+                discrepancy(correlation, factors, &candidate);
+            if candidate_value <= 1e-4_f64.mul_add(-decrease, value) {
+                accepted = Some((candidate, candidate_value, candidate_loadings));
+                break;
+            }
+            step /= 2.0;
+        }
+        let Some((candidate, candidate_value, candidate_loadings)) = accepted else {
+            converged = true;
+            break;
+        };
+        let largest_change = uniquenesses
+            .iter()
+            .zip(&candidate)
+            .map(|(old, new)| (old - new).abs())
+            .fold(0.0, f64::max);
+        uniquenesses = candidate;
+        value = candidate_value;
+        loadings = candidate_loadings;
+        if largest_change < EXTRACTION_TOLERANCE {
+            converged = true;
+            break;
+        }
+    }
+    Extraction {
+        loadings,
+        uniquenesses,
+        discrepancy: value,
+        iterations,
+        converged,
+    }
+}
+
+/// Kaiser-normalised varimax rotation of `loadings` (variables by factors).
+fn varimax(loadings: &DMatrix<f64>) -> DMatrix<f64> {
+    let (variables, factors) = loadings.shape();
+    if factors < 2 {
+        return loadings.clone();
+    }
+    let norms: Vec<f64> = loadings
+        .row_iter()
+        .map(|row| row.norm().max(f64::MIN_POSITIVE))
+        .collect();
+    let normalised = DMatrix::from_fn(variables, factors, |i, k| loadings[(i, k)] / norms[i]);
+
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Variable count casting to f64 for the criterion"
+    )]
+    let count = variables as f64;
+    let mut rotation = DMatrix::<f64>::identity(factors, factors);
+    let mut criterion = 0.0;
+    for _ in 0..MAX_ROTATION_ITERATIONS {
+        let rotated = &normalised * &rotation;
+        let column_squares: Vec<f64> = rotated
+            .column_iter()
+            .map(|column| column.iter().map(|value| value * value).sum())
+            .collect();
+        let target = DMatrix::from_fn(variables, factors, |i, k| {
+            let value = rotated[(i, k)];
+            value.powi(3) - value * column_squares[k] / count
+        });
+        let svd = (normalised.transpose() * target).svd(true, true);
+        let (Some(u), Some(v_t)) = (svd.u, svd.v_t) else {
+            break;
+        };
+        rotation = u * v_t;
+        let previous = criterion;
+        criterion = svd.singular_values.sum();
+        if criterion < previous * (1.0 + 1e-7) {
+            break;
+        }
+    }
+    let rotated = normalised * rotation;
+    DMatrix::from_fn(variables, factors, |i, k| rotated[(i, k)] * norms[i])
+}
+
+/// Oblimin (direct quartimin) criterion and its gradient in the loadings.
+fn quartimin(loadings: &DMatrix<f64>) -> (f64, DMatrix<f64>) {
+    let factors = loadings.ncols();
+    let squares = loadings.component_mul(loadings);
+    let others = DMatrix::from_fn(factors, factors, |a, b| if a == b { 0.0 } else { 1.0 });
+    let cross = &squares * others;
+    (
+        squares.component_mul(&cross).sum() / 4.0,
+        loadings.component_mul(&cross),
+    )
+}
+
+/// Gradient-projection oblique rotation minimising the quartimin criterion.
+///
+/// Returns the pattern loadings and the factor correlation matrix.
+fn oblimin(loadings: &DMatrix<f64>) -> Result<(DMatrix<f64>, DMatrix<f64>), String> {
+    let factors = loadings.ncols();
+    if factors < 2 {
+        return Ok((loadings.clone(), DMatrix::identity(factors, factors)));
+    }
+    let singular = || "Oblimin rotation became singular".to_owned();
+    let mut transform = DMatrix::<f64>::identity(factors, factors);
+    let mut inverse = transform.clone();
+    let mut pattern = loadings.clone();
+    let (mut criterion, mut criterion_gradient) = quartimin(&pattern);
+    let mut gradient = -(pattern.transpose() * &criterion_gradient * &inverse).transpose();
+    let mut step = 1.0;
+    for _ in 0..MAX_ROTATION_ITERATIONS {
+        // Project out the component that would change the column norms.
+        let column_dots: Vec<f64> = transform
+            .component_mul(&gradient)
+            .column_iter()
+            .map(|column| column.sum())
+            .collect();
+        let projected = &gradient - &transform * DMatrix::from_diagonal(&column_dots.into());
+        let size = projected.norm();
+        if size < 1e-6 {
+            break;
+        }
+        step *= 2.0;
+        let mut trial = None;
+        for _ in 0..=10 {
+            let mut candidate = &transform - step * &projected;
+            for mut column in candidate.column_iter_mut() {
+                let norm = column.norm();
+                column /= norm;
+            }
+            let candidate_inverse = candidate.clone().try_inverse().ok_or_else(singular)?;
+            let candidate_pattern = loadings * candidate_inverse.transpose();
+            let (candidate_criterion, candidate_gradient) = quartimin(&candidate_pattern);
+            let improved = criterion - candidate_criterion > 0.5 * size * size * step;
+            trial = Some((
+                candidate,
+                candidate_inverse,
+                candidate_pattern,
+                candidate_criterion,
+                candidate_gradient,
+            ));
+            if improved {
+                break;
+            }
+            step /= 2.0;
+        }
+        let Some((next, next_inverse, next_pattern, next_criterion, next_gradient)) = trial else {
+            break;
+        };
+        transform = next;
+        inverse = next_inverse;
+        pattern = next_pattern;
+        criterion = next_criterion;
+        criterion_gradient = next_gradient;
+        gradient = -(pattern.transpose() * &criterion_gradient * &inverse).transpose();
+    }
+    let correlations = transform.transpose() * &transform;
+    Ok((pattern, correlations))
+}
+
+/// Give each factor a positive loading sum and order factors by the sum of
+/// their squared loadings, permuting `correlations` to match.
+fn orient_factors(loadings: &mut DMatrix<f64>, mut correlations: Option<&mut DMatrix<f64>>) {
+    let factors = loadings.ncols();
+    for k in 0..factors {
+        if loadings.column(k).sum() < 0.0 {
+            loadings.column_mut(k).neg_mut();
+            if let Some(phi) = correlations.as_deref_mut() {
+                phi.column_mut(k).neg_mut();
+                phi.row_mut(k).neg_mut();
+            }
+        }
+    }
+    let mut order: Vec<usize> = (0..factors).collect();
+    let explained: Vec<f64> = loadings
+        .column_iter()
+        .map(|column| column.norm_squared())
+        .collect();
+    order.sort_by(|&a, &b| explained[b].total_cmp(&explained[a]));
+    let columns: Vec<_> = order
+        .iter()
+        .map(|&k| loadings.column(k).into_owned())
+        .collect();
+    *loadings = DMatrix::from_columns(&columns);
+    if let Some(phi) = correlations {
+        *phi = DMatrix::from_fn(factors, factors, |a, b| phi[(order[a], order[b])]);
+    }
+}
+
+/// Loadings matrix (variables by factors) as one vector per factor.
+fn factor_vectors(loadings: &DMatrix<f64>) -> Vec<Vec<f64>> {
+    loadings
+        .column_iter()
+        .map(|column| column.iter().copied().collect())
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    /// Six variables driven by two factors correlated at 0.5: the first three
+    /// load 0.8 on the first factor, the last three 0.7 on the second.
+    fn two_factor_data() -> Vec<Vec<f64>> {
+        let mut rng = Pcg64::seed_from_u64(3);
+        (0..600)
+            .map(|_| {
+                let first = rng.sample::<f64, _>(StandardNormal);
+                let second = 0.5_f64.mul_add(
+                    first,
+                    0.75_f64.sqrt() * rng.sample::<f64, _>(StandardNormal),
+                );
+                let unique_first = 0.36_f64.sqrt();
+                let unique_second = 0.51_f64.sqrt();
+                let mut row = Vec::with_capacity(6);
+                for _ in 0..3 {
+                    row.push(
+                        0.8_f64.mul_add(first, unique_first * rng.sample::<f64, _>(StandardNormal)),
+                    );
+                }
+                for _ in 0..3 {
+                    row.push(
+                        0.7_f64
+                            .mul_add(second, unique_second * rng.sample::<f64, _>(StandardNormal)),
+                    );
+                }
+                row
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_eigenvalue_decomposition_is_sorted_and_reconstructs() {
+        let matrix = DMatrix::from_row_slice(3, 3, &[2.0, 1.0, 0.0, 1.0, 2.0, 0.0, 0.0, 0.0, 5.0]);
+        let eigen = MatrixOpsEngine::eigenvalue_decomposition(&matrix);
+        for (value, expected) in eigen.values.iter().zip([5.0, 3.0, 1.0]) {
+            assert!((value - expected).abs() < 1e-12);
+        }
+        let rebuilt = &eigen.vectors
+            * DMatrix::from_diagonal(&eigen.values.clone().into())
+            * eigen.vectors.transpose();
+        assert!((rebuilt - matrix).norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_efa_recovers_simple_structure() {
+        let data = two_factor_data();
+        let varimax = MatrixOpsEngine::efa(&data, 2, FactorRotation::Varimax).unwrap();
+        assert!(varimax.converged);
+        assert_eq!(varimax.degrees_of_freedom, 4);
+        assert!(varimax.p_value.unwrap() > 0.01);
+        for (communality, uniqueness) in varimax.communalities.iter().zip(&varimax.uniquenesses) {
+            assert!((communality + uniqueness - 1.0).abs() < 1e-3);
+        }
+        assert!(varimax.factor_correlations.is_none());
+
+        let oblimin = MatrixOpsEngine::efa(&data, 2, FactorRotation::Oblimin).unwrap();
+        let pattern = &oblimin.rotated_loadings;
+        let (first, second) = (&pattern[0], &pattern[1]);
+        assert!(first[..3].iter().all(|loading| (loading - 0.8).abs() < 0.1));
+        assert!(second[..3].iter().all(|loading| loading.abs() < 0.1));
+        assert!(
+            second[3..]
+                .iter()
+                .all(|loading| (loading - 0.7).abs() < 0.1)
+        );
+        assert!(first[3..].iter().all(|loading| loading.abs() < 0.1));
+        let phi = oblimin.factor_correlations.unwrap();
+        assert!((phi[0][1] - 0.5).abs() < 0.1);
+        assert!((phi[0][0] - 1.0).abs() < 1e-9);
+
+        assert!(MatrixOpsEngine::efa(&data, 4, FactorRotation::None).is_err());
+    }
+
+    #[test]
+    fn test_parallel_analysis_finds_two_factors() {
+        let data = two_factor_data();
+        let result = MatrixOpsEngine::parallel_analysis_scree(&data, 50, 9).unwrap();
+        assert_eq!(result.n_factors, 2);
+        assert!(result.random_mean_eigenvalues[0] > 1.0);
+        assert!(MatrixOpsEngine::parallel_analysis_scree(&data, 5, 9).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+//! Statistical tests and analyses behind the statistics commands.
+//!
+//! Covers test power, normality test bundles, grouped descriptive
+//! statistics, one-way and factorial ANOVA, contingency tables, survival
+//! analysis, principal component and factor analysis, process capability,
+//! scale reliability, RANSAC robust regression, a pipeline comparing several
+//! columns at once, consensus values of repeated measurements, multi-rule
+//! outlier reports, preparation of spreadsheet selections into numeric
+//! series, and the structured error type shared by the statistics engines.
+pub mod anova;
+pub(crate) mod commands;
+pub mod contingency;
+pub mod covariance;
+pub mod descriptive;
+pub mod error;
+pub mod hypothesis_testing;
+pub mod matrix_ops;
+pub mod normality;
+pub mod outliers;
+pub mod pca;
+pub mod pipeline;
+pub mod power;
+pub mod quality_control;
+pub mod reliability;
+pub mod robust_regression;
+pub mod selection;
+pub mod stationarity;
+pub mod survival;
+mod types;
+pub mod uncertainty;
+
+pub use anova::AnovaTesting;
+pub use commands::{
+    adf_test, analyze_columns, combine_measurements, crosstab_and_test, detect_outliers,
+    generate_outlier_report, grouped_statistics, huber_regression, irls_regression, kaplan_meier,
+    log_rank_test, prepare_selection, ransac_regression, run_factorial_anova, test_normality,
+};
+pub use contingency::ChiSquareTesting;
+pub use covariance::CovarianceOps;
+pub use descriptive::DescriptiveStatisticsEngine;
+pub use error::StatisticsError;
+pub use hypothesis_testing::HypothesisTestingEngine;
+pub use matrix_ops::{EigenDecomposition, MatrixOpsEngine};
+pub use normality::NormalityTestingEngine;
+pub use outliers::OutlierDetectionEngine;
+pub use pca::PcaOps;
+pub use pipeline::StatisticalAnalysisPipeline;
+pub use power::PowerAnalysisEngine;
+pub use quality_control::{QualityControlEngine, optimize_ewma_lambda};
+pub use reliability::ReliabilityEngine;
+pub use robust_regression::RobustRegressionEngine;
+pub use selection::SelectionEngine;
+pub use stationarity::StationarityEngine;
+pub use survival::SurvivalAnalysisEngine;
+pub use types::{
+    AdfResult, AnovaCell, AnovaRow, CapabilityDistribution, ChiSquareIndependenceResult,
+    ClusterLinkage, ClusterMerge, CoercionPolicy, ColumnAnalysis, ContingencyTable, CriticalValue,
+    CrosstabResult, CusumChartResult, EfaResult, EwmaChartResult, FactorLevels, FactorRotation,
+    FactorialAnovaResult, GageRrAssessment, GageRrResult, GroupSummary, GroupedStatisticsResult,
+    ItemAnalysisResult, ItemStatistics, KmResult, LedoitWolfResult, LogRankResult,
+    MEstimatorOptions, MEstimatorResult, MannWhitneyResult, Measurement,
+    MeasurementCombinationOptions, MeasurementCombinationResult, MeasurementDeviation,
+    MissingCategoryPolicy, MultiColumnReport, MultipleComparisonAdjustment, NamedColumn,
+    NonnormalCapabilityResult, NormalityBundleResult, NormalityTestOutcome, NormalityTestResult,
+    OneWayAnovaResult, OutlierAction, OutlierAnalysisResult, OutlierDetectionOptions,
+    OutlierMethod, OutlierMethodResult, OutlierPoint, OutlierReport, PairwiseComparison,
+    PairwiseTest, ParallelAnalysisResult, PcaBootstrapResult, PcaResult, PreparedSelection,
+    PreparedSeries, QqPoint, Quantiles, RandomEffectsEstimate, RansacOptions, RejectedSeries,
+    RiskTableEntry, RobustPcaResult, RobustRegressionResult, SampleSummary, SelectionOptions,
+    SelectionOrientation, SimulationDistribution, SkipReason, SkippedCell, StatisticComparison,
+    StatisticalMoments, TermCellMeans, VMaskResult, VarianceComponent, WelchTTestResult,
+};
+pub use uncertainty::MeasurementCombinationEngine;
@@ -0,0 +1,556 @@
+//! Normality test bundle.
+//!
+//! Shapiro-Wilk uses Royston's (1995, AS R94) approximation of the
+//! coefficients and of the null distribution of `W`, valid for 3 to 5000
+//! observations and exact for three. Jarque-Bera combines the sample skewness
+//! and excess kurtosis into a statistic that is asymptotically chi-squared with
+//! two degrees of freedom; D'Agostino's K^2 first transforms each moment to an
+//! approximately standard normal score (D'Agostino, Belanger and D'Agostino,
+//! 1990), which holds from about eight observations. Anderson-Darling comes
+//! from [`HypothesisTestingEngine`]. Moments are the biased (population)
+//! estimates throughout.
+//!
+//! The bundle runs every test that applies to the sample size and calls the
+//! data consistent with normality unless a strict majority of them rejects it.
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use super::error::StatisticsError;
+use super::hypothesis_testing::HypothesisTestingEngine;
+use super::types::{NormalityBundleResult, NormalityTestOutcome, NormalityTestResult, QqPoint};
+use crate::scientific::primitives::descriptive::{mean, sorted};
+
+/// Fewest observations accepted by the Shapiro-Wilk test.
+const MIN_SHAPIRO_WILK_OBSERVATIONS: usize = 3;
+/// Most observations covered by the Shapiro-Wilk approximation.
+const MAX_SHAPIRO_WILK_OBSERVATIONS: usize = 5000;
+/// Fewest observations for the moment-based and Anderson-Darling tests.
+const MIN_ASYMPTOTIC_OBSERVATIONS: usize = 8;
+
+/// Coefficients of the largest Shapiro-Wilk weight, in powers of `1 / sqrt(n)`.
+const SW_FIRST_WEIGHT: [f64; 6] = [0.0, 0.221_157, -0.147_981, -2.071_19, 4.434_685, -2.706_056];
+/// Coefficients of the second largest Shapiro-Wilk weight.
+const SW_SECOND_WEIGHT: [f64; 6] = [
+    0.0, 0.042_981, -0.293_762, -1.752_461, 5.682_633, -3.582_633,
+];
+/// Upper bound of `ln(1 - W)` for `n <= 11`, in powers of `n`.
+const SW_SMALL_GAMMA: [f64; 2] = [-2.273, 0.459];
+/// Mean of the normalized statistic for `n <= 11`, in powers of `n`.
+const SW_SMALL_MEAN: [f64; 4] = [0.544, -0.399_78, 0.025_054, -6.714e-4];
+/// Log standard deviation for `n <= 11`, in powers of `n`.
+const SW_SMALL_LOG_SD: [f64; 4] = [1.3822, -0.778_57, 0.062_767, -0.002_032_2];
+/// Mean of `ln(1 - W)` for `n >= 12`, in powers of `ln(n)`.
+const SW_LARGE_MEAN: [f64; 4] = [-1.5861, -0.310_82, -0.083_751, 0.003_891_5];
+/// Log standard deviation of `ln(1 - W)` for `n >= 12`, in powers of `ln(n)`.
+const SW_LARGE_LOG_SD: [f64; 3] = [-0.4803, -0.082_676, 0.003_030_2];
+
+/// Normality tests and their bundle.
+pub struct NormalityTestingEngine;
+
+impl NormalityTestingEngine {
+    /// Shapiro-Wilk test of `data`.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::Validation`] for non-finite or constant
+    /// data or more than 5000 observations, and
+    /// [`StatisticsError::InsufficientData`] below three.
+    pub fn shapiro_wilk(data: &[f64]) -> Result<NormalityTestResult, StatisticsError> {
+        validate(data, MIN_SHAPIRO_WILK_OBSERVATIONS)?;
+        let n = data.len();
+        if n > MAX_SHAPIRO_WILK_OBSERVATIONS {
+            return Err(StatisticsError::validation(
+                "data",
+                format!(
+                    "Shapiro-Wilk is valid for at most {MAX_SHAPIRO_WILK_OBSERVATIONS} observations, got {n}"
+                ),
+            ));
+        }
+
+        let standard_normal = Normal::new(0.0, 1.0).map_err(|error| error.to_string())?;
+        #[allow(clippy::cast_precision_loss, reason = "Sample size casting to f64")]
+        let size = n as f64;
+        #[allow(clippy::integer_division, reason = "Pairs of order statistics")]
+        let half = n / 2;
+        let weights = if n == MIN_SHAPIRO_WILK_OBSERVATIONS {
+            vec![std::f64::consts::FRAC_1_SQRT_2]
+        } else {
+            // Expected normal order statistics of the lower half, by Blom's
+            // approximation; negative, so the weights come out positive
+            let scores: Vec<f64> = (1..=half)
+                .map(|rank| {
+                    #[allow(clippy::cast_precision_loss, reason = "Rank casting to f64")]
+                    let rank = rank as f64;
+                    standard_normal.inverse_cdf((rank - 0.375) / (size + 0.25))
+                })
+                .collect();
+            let score_norm_squared = 2.0 * scores.iter().map(|score| score * score).sum::<f64>();
+            let inverse_root = size.sqrt().recip();
+            let first =
+                polynomial(&SW_FIRST_WEIGHT, inverse_root) - scores[0] / score_norm_squared.sqrt();
+            let mut weights: Vec<f64> = Vec::with_capacity(half);
+            let (leading, remaining_norm) = if n > 5 {
+                let second = polynomial(&SW_SECOND_WEIGHT, inverse_root)
+                    - scores[1] / score_norm_squared.sqrt();
+                weights.extend([first, second]);
+                (
+                    2,
+                    (2.0 * scores[1]).mul_add(
+                        -scores[1],
+                        (2.0 * scores[0]).mul_add(-scores[0], score_norm_squared),
+                    ) / (2.0 * second).mul_add(-second, (2.0 * first).mul_add(-first, 1.0)),
+                )
+            } else {
+                weights.push(first);
+                (
+                    1,
+                    (2.0 * scores[0]).mul_add(-scores[0], score_norm_squared)
+                        / (2.0 * first).mul_add(-first, 1.0),
+                )
+            };
+            let scale = remaining_norm.sqrt();
+            weights.extend(scores[leading..].iter().map(|score| -score / scale));
+            weights
+        };
+
+        let ordered = sorted(data);
+        let center = mean(&ordered).unwrap_or(0.0);
+        let sum_of_squares: f64 = ordered.iter().map(|value| (value - center).powi(2)).sum();
+        let numerator: f64 = weights
+            .iter()
+            .enumerate()
+            .map(|(index, weight)| weight * (ordered[n - 1 - index] - ordered[index]))
+            .sum();
+        let statistic = (numerator * numerator / sum_of_squares).min(1.0);
+
+        let p_value = if n == MIN_SHAPIRO_WILK_OBSERVATIONS {
+            // Exact: P(W < w) = 6 / pi (asin(sqrt(w)) - pi / 3)
+            (6.0 / std::f64::consts::PI * (statistic.sqrt().asin() - std::f64::consts::FRAC_PI_3))
+                .max(0.0)
+        } else {
+            let mut transformed = (1.0 - statistic).ln();
+            let (null_mean, log_scale) = if n <= 11 {
+                let gamma = polynomial(&SW_SMALL_GAMMA, size);
+                if transformed >= gamma {
+                    // Beyond the range of the approximation
+                    return Ok(NormalityTestResult {
+                        statistic,
+                        p_value: 0.0,
+                    });
+                }
+                transformed = -(gamma - transformed).ln();
+                (
+                    polynomial(&SW_SMALL_MEAN, size),
+                    polynomial(&SW_SMALL_LOG_SD, size),
+                )
+            } else {
+                (
+                    polynomial(&SW_LARGE_MEAN, size.ln()),
+                    polynomial(&SW_LARGE_LOG_SD, size.ln()),
+                )
+            };
+            standard_normal.sf((transformed - null_mean) / log_scale.exp())
+        };
+
+        Ok(NormalityTestResult {
+            statistic,
+            p_value: p_value.clamp(0.0, 1.0),
+        })
+    }
+
+    /// Jarque-Bera test of `data`: `n / 6 (S^2 + K^2 / 4)` with sample
+    /// skewness `S` and excess kurtosis `K`, against chi-squared with two
+    /// degrees of freedom.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::Validation`] for non-finite or constant
+    /// data and [`StatisticsError::InsufficientData`] below three
+    /// observations.
+    pub fn jarque_bera(data: &[f64]) -> Result<NormalityTestResult, StatisticsError> {
+        validate(data, MIN_SHAPIRO_WILK_OBSERVATIONS)?;
+        let (skewness, excess_kurtosis) = shape_moments(data);
+        #[allow(clippy::cast_precision_loss, reason = "Sample size casting to f64")]
+        let n = data.len() as f64;
+        let statistic =
+            n / 6.0 * skewness.mul_add(skewness, excess_kurtosis * excess_kurtosis / 4.0);
+        Ok(NormalityTestResult {
+            statistic,
+            // Chi-squared survival function with two degrees of freedom
+            p_value: (-statistic / 2.0).exp(),
+        })
+    }
+
+    /// D'Agostino's K^2 omnibus test of `data`: the sum of the squared
+    /// normalized skewness and kurtosis scores, against chi-squared with two
+    /// degrees of freedom.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::Validation`] for non-finite or constant
+    /// data and [`StatisticsError::InsufficientData`] below eight
+    /// observations.
+    pub fn dagostino_k2(data: &[f64]) -> Result<NormalityTestResult, StatisticsError> {
+        validate(data, MIN_ASYMPTOTIC_OBSERVATIONS)?;
+        let (skewness, excess_kurtosis) = shape_moments(data);
+        #[allow(clippy::cast_precision_loss, reason = "Sample size casting to f64")]
+        let n = data.len() as f64;
+
+        // Skewness score
+        let y = skewness * ((n + 1.0) * (n + 3.0) / (6.0 * (n - 2.0))).sqrt();
+        let beta2 = 3.0 * (n.mul_add(n, 27.0_f64.mul_add(n, -70.0))) * (n + 1.0) * (n + 3.0)
+            / ((n - 2.0) * (n + 5.0) * (n + 7.0) * (n + 9.0));
+        let w2 = (2.0 * (beta2 - 1.0)).sqrt() - 1.0;
+        let delta = (0.5 * w2.ln()).sqrt().recip();
+        let alpha = (2.0 / (w2 - 1.0)).sqrt();
+        let skewness_score = delta * (y / alpha).asinh();
+
+        // Kurtosis score
+        let expected = 3.0 * (n - 1.0) / (n + 1.0) - 3.0;
+        let variance =
+            24.0 * n * (n - 2.0) * (n - 3.0) / ((n + 1.0) * (n + 1.0) * (n + 3.0) * (n + 5.0));
+        let standardized = (excess_kurtosis - expected) / variance.sqrt();
+        let root_beta1 = 6.0 * n.mul_add(n, 5.0_f64.mul_add(-n, 2.0)) / ((n + 7.0) * (n + 9.0))
+            * (6.0 * (n + 3.0) * (n + 5.0) / (n * (n - 2.0) * (n - 3.0))).sqrt();
+        let a = (8.0 / root_beta1).mul_add(
+            2.0 / root_beta1 + (1.0 + 4.0 / (root_beta1 * root_beta1)).sqrt(),
+            6.0,
+        );
+        let denominator = standardized.mul_add((2.0 / (a - 4.0)).sqrt(), 1.0);
+        let cube_root = ((1.0 - 2.0 / a) / denominator.abs())
+            .cbrt()
+            .copysign(denominator);
+        let kurtosis_score = (1.0 - 2.0 / (9.0 * a) - cube_root) / (2.0 / (9.0 * a)).sqrt();
+
+        let statistic = skewness_score.mul_add(skewness_score, kurtosis_score * kurtosis_score);
+        if !statistic.is_finite() {
+            return Err(StatisticsError::Numerical {
+                context: "The kurtosis score of the K^2 test is undefined for this sample"
+                    .to_owned(),
+            });
+        }
+        Ok(NormalityTestResult {
+            statistic,
+            p_value: (-statistic / 2.0).exp(),
+        })
+    }
+
+    /// Run every applicable normality test on `data` at level `alpha`, with
+    /// the sample skewness and excess kurtosis and the points of a normal
+    /// Q-Q plot.
+    ///
+    /// Tests that do not apply to the sample size are listed with the reason
+    /// they were skipped.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::Validation`] for `alpha` outside `(0, 1)`
+    /// or non-finite or constant data, and
+    /// [`StatisticsError::InsufficientData`] below three observations.
+    pub fn test_normality(
+        data: &[f64],
+        alpha: f64,
+    ) -> Result<NormalityBundleResult, StatisticsError> {
+        if !(alpha > 0.0 && alpha < 1.0) {
+            return Err(StatisticsError::validation(
+                "alpha",
+                format!("Alpha must be in (0, 1), got {alpha}"),
+            ));
+        }
+        validate(data, MIN_SHAPIRO_WILK_OBSERVATIONS)?;
+        let n = data.len();
+        let shapiro_wilk = if n > MAX_SHAPIRO_WILK_OBSERVATIONS {
+            Err(format!(
+                "Valid for at most {MAX_SHAPIRO_WILK_OBSERVATIONS} observations, got {n}"
+            ))
+        } else {
+            Self::shapiro_wilk(data).map_err(|error| error.to_string())
+        };
+        let asymptotic = |run: &dyn Fn() -> Result<NormalityTestResult, String>| {
+            if n < MIN_ASYMPTOTIC_OBSERVATIONS {
+                Err(format!(
+                    "Needs at least {MIN_ASYMPTOTIC_OBSERVATIONS} observations, got {n}"
+                ))
+            } else {
+                run()
+            }
+        };
+        let tests = vec![
+            outcome("Shapiro-Wilk", shapiro_wilk, alpha),
+            outcome(
+                "Anderson-Darling",
+                asymptotic(&|| HypothesisTestingEngine::anderson_darling_normality(data)),
+                alpha,
+            ),
+            outcome(
+                "Jarque-Bera",
+                asymptotic(&|| Self::jarque_bera(data).map_err(|error| error.to_string())),
+                alpha,
+            ),
+            outcome(
+                "D'Agostino K^2",
+                asymptotic(&|| Self::dagostino_k2(data).map_err(|error| error.to_string())),
+                alpha,
+            ),
+        ];
+
+        let verdicts: Vec<bool> = tests
+            .iter()
+            .filter_map(|test| test.rejects_normality)
+            .collect();
+        let rejections = verdicts.iter().filter(|&&rejects| rejects).count();
+        let (skewness, excess_kurtosis) = shape_moments(data);
+        Ok(NormalityBundleResult {
+            count: n,
+            alpha,
+            consistent_with_normal: 2 * rejections <= verdicts.len(),
+            tests,
+            skewness,
+            excess_kurtosis,
+            qq_points: qq_points(data)?,
+        })
+    }
+}
+
+/// Bundle entry of a test that ran, or was skipped for the reason in `result`.
+fn outcome(
+    test: &str,
+    result: Result<NormalityTestResult, String>,
+    alpha: f64,
+) -> NormalityTestOutcome {
+    match result {
+        Ok(result) => NormalityTestOutcome {
+            test: test.to_owned(),
+            statistic: Some(result.statistic),
+            p_value: Some(result.p_value),
+            rejects_normality: Some(result.p_value < alpha),
+            skipped_reason: None,
+        },
+        Err(reason) => NormalityTestOutcome {
+            test: test.to_owned(),
+            statistic: None,
+            p_value: None,
+            rejects_normality: None,
+            skipped_reason: Some(reason),
+        },
+    }
+}
+
+/// Check that `data` is finite, non-constant and has at least `minimum`
+/// observations.
+fn validate(data: &[f64], minimum: usize) -> Result<(), StatisticsError> {
+    if data.len() < minimum {
+        return Err(StatisticsError::InsufficientData {
+            needed: minimum,
+            got: data.len(),
+        });
+    }
+    if data.iter().any(|value| !value.is_finite()) {
+        return Err(StatisticsError::validation(
+            "data",
+            "Data must contain only finite values",
+        ));
+    }
+    if data.iter().all(|value| value.total_cmp(&data[0]).is_eq()) {
+        return Err(StatisticsError::validation("data", "Data has no variance"));
+    }
+    Ok(())
+}
+
+/// Biased sample skewness `m3 / m2^1.5` and excess kurtosis `m4 / m2^2 - 3`.
+fn shape_moments(data: &[f64]) -> (f64, f64) {
+    let center = mean(data).unwrap_or(0.0);
+    #[allow(clippy::cast_precision_loss, reason = "Sample size casting to f64")]
+    let n = data.len() as f64;
+    let moment = |power: i32| {
+        data.iter()
+            .map(|value| (value - center).powi(power))
+            .sum::<f64>()
+            / n
+    };
+    let variance = moment(2);
+    (
+        moment(3) / variance.powf(1.5),
+        moment(4) / (variance * variance) - 3.0,
+    )
+}
+
+/// Sorted data against standard normal quantiles at Filliben's plotting
+/// positions, as in `scipy.stats.probplot`.
+fn qq_points(data: &[f64]) -> Result<Vec<QqPoint>, StatisticsError> {
+    let standard_normal = Normal::new(0.0, 1.0).map_err(|error| error.to_string())?;
+    #[allow(clippy::cast_precision_loss, reason = "Sample size casting to f64")]
+    let n = data.len() as f64;
+    let last = 0.5_f64.powf(n.recip());
+    Ok(sorted(data)
+        .into_iter()
+        .enumerate()
+        .map(|(index, sample)| {
+            let position = if index == 0 {
+                1.0 - last
+            } else if index + 1 == data.len() {
+                last
+            } else {
+                #[allow(clippy::cast_precision_loss, reason = "Rank casting to f64")]
+                let rank = (index + 1) as f64;
+                (rank - 0.3175) / (n + 0.365)
+            };
+            QqPoint {
+                theoretical: standard_normal.inverse_cdf(position),
+                sample,
+            }
+        })
+        .collect())
+}
+
+/// Evaluate the polynomial with ascending `coefficients` at `x`.
+fn polynomial(coefficients: &[f64], x: f64) -> f64 {
+    coefficients
+        .iter()
+        .rev()
+        .fold(0.0, |sum, coefficient| sum.mul_add(x, *coefficient))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    /// Normal scores with a small deterministic perturbation.
+    fn normal_sample() -> Vec<f64> {
+        let standard_normal = Normal::new(0.0, 1.0).unwrap();
+        (1..=30)
+            .map(|rank| {
+                let rank = f64::from(rank);
+                0.3_f64.mul_add(
+                    (1.7 * rank).sin(),
+                    standard_normal.inverse_cdf((rank - 0.5) / 30.0),
+                )
+            })
+            .collect()
+    }
+
+    /// Quantiles of the unit exponential distribution.
+    fn exponential_sample() -> Vec<f64> {
+        (1..=30)
+            .map(|rank| -(1.0 - (f64::from(rank) - 0.5) / 30.0).ln())
+            .collect()
+    }
+
+    // Reference values evaluate the algorithms of scipy.stats.shapiro,
+    // jarque_bera and normaltest on the same samples.
+    #[test]
+    fn test_normal_sample_matches_reference() {
+        let data = normal_sample();
+        let shapiro = NormalityTestingEngine::shapiro_wilk(&data).unwrap();
+        assert_relative_eq!(
+            shapiro.statistic,
+            0.986_013_933_195_064_7,
+            max_relative = 1e-9
+        );
+        assert_relative_eq!(
+            shapiro.p_value,
+            0.953_160_475_624_212_4,
+            max_relative = 1e-6
+        );
+        let jarque_bera = NormalityTestingEngine::jarque_bera(&data).unwrap();
+        assert_relative_eq!(
+            jarque_bera.statistic,
+            0.259_669_591_882_397_9,
+            max_relative = 1e-9
+        );
+        assert_relative_eq!(
+            jarque_bera.p_value,
+            0.878_240_507_833_081_5,
+            max_relative = 1e-9
+        );
+        let k2 = NormalityTestingEngine::dagostino_k2(&data).unwrap();
+        assert_relative_eq!(k2.statistic, 0.088_006_300_862_007_04, max_relative = 1e-7);
+        assert_relative_eq!(k2.p_value, 0.956_950_942_660_379_1, max_relative = 1e-9);
+
+        let bundle = NormalityTestingEngine::test_normality(&data, 0.05).unwrap();
+        assert!(bundle.consistent_with_normal);
+        assert!(
+            bundle
+                .tests
+                .iter()
+                .all(|test| test.rejects_normality == Some(false))
+        );
+        assert_relative_eq!(
+            bundle.skewness,
+            0.102_562_530_499_346_27,
+            max_relative = 1e-9
+        );
+        assert_eq!(bundle.qq_points.len(), 30);
+        assert!(
+            bundle
+                .qq_points
+                .windows(2)
+                .all(|pair| pair[0].sample <= pair[1].sample)
+        );
+        assert_relative_eq!(
+            bundle.qq_points[0].theoretical,
+            -bundle.qq_points[29].theoretical
+        );
+    }
+
+    #[test]
+    fn test_exponential_sample_matches_reference() {
+        let data = exponential_sample();
+        let shapiro = NormalityTestingEngine::shapiro_wilk(&data).unwrap();
+        assert_relative_eq!(
+            shapiro.statistic,
+            0.846_723_369_882_044_6,
+            max_relative = 1e-9
+        );
+        assert_relative_eq!(
+            shapiro.p_value,
+            5.298_399_976_911_838e-4,
+            max_relative = 1e-6
+        );
+        let jarque_bera = NormalityTestingEngine::jarque_bera(&data).unwrap();
+        assert_relative_eq!(
+            jarque_bera.statistic,
+            16.911_845_083_850_51,
+            max_relative = 1e-9
+        );
+        assert_relative_eq!(
+            jarque_bera.p_value,
+            2.126_373_252_340_162_6e-4,
+            max_relative = 1e-9
+        );
+        let k2 = NormalityTestingEngine::dagostino_k2(&data).unwrap();
+        assert_relative_eq!(k2.statistic, 15.741_921_586_900_78, max_relative = 1e-9);
+        assert_relative_eq!(k2.p_value, 3.816_674_823_631_161_7e-4, max_relative = 1e-9);
+
+        let bundle = NormalityTestingEngine::test_normality(&data, 0.05).unwrap();
+        assert!(!bundle.consistent_with_normal);
+        assert_relative_eq!(
+            bundle.excess_kurtosis,
+            2.090_188_847_674_758,
+            max_relative = 1e-9
+        );
+    }
+
+    #[test]
+    fn test_small_samples_skip_asymptotic_tests() {
+        let three = NormalityTestingEngine::shapiro_wilk(&[1.0, 2.0, 4.0]).unwrap();
+        assert_relative_eq!(three.statistic, 27.0 / 28.0, max_relative = 1e-12);
+        assert_relative_eq!(three.p_value, 0.636_886_845_028_965_4, max_relative = 1e-9);
+        let six = NormalityTestingEngine::shapiro_wilk(&[1.0, 2.0, 3.0, 4.5, 6.0, 10.0]).unwrap();
+        assert_relative_eq!(six.statistic, 0.931_984_415_142_7, max_relative = 1e-9);
+        assert_relative_eq!(six.p_value, 0.595_511_515_246_659_9, max_relative = 1e-6);
+
+        let bundle =
+            NormalityTestingEngine::test_normality(&[1.0, 2.0, 3.0, 4.5, 6.0, 10.0], 0.05).unwrap();
+        let skipped: Vec<&str> = bundle
+            .tests
+            .iter()
+            .filter(|test| test.skipped_reason.is_some())
+            .map(|test| test.test.as_str())
+            .collect();
+        assert_eq!(
+            skipped,
+            ["Anderson-Darling", "Jarque-Bera", "D'Agostino K^2"]
+        );
+        assert!(bundle.consistent_with_normal);
+        assert!(NormalityTestingEngine::test_normality(&[1.0, 2.0], 0.05).is_err());
+        assert!(NormalityTestingEngine::test_normality(&[1.0; 10], 0.05).is_err());
+    }
+}
@@ -0,0 +1,554 @@
+//! Outliers of a single column under several rules, and a report of what
+//! removing them would change.
+//!
+//! Each point is tested against Tukey's fences, the modified z-score of
+//! Iglewicz and Hoaglin and the classical z-score. Points flagged by enough
+//! rules are consensus outliers; the report annotates every point with the
+//! rules that flag it, compares the moments and quantiles of the column with
+//! and without the consensus outliers, and suggests keeping, reviewing or
+//! removing each point.
+use std::fmt;
+
+use super::error::StatisticsError;
+use super::types::{
+    OutlierAction, OutlierAnalysisResult, OutlierDetectionOptions, OutlierMethod,
+    OutlierMethodResult, OutlierPoint, OutlierReport, Quantiles, SampleSummary,
+    StatisticComparison, StatisticalMoments,
+};
+use crate::scientific::primitives::descriptive::{
+    mean, median, modified_z_scores, quantile_sorted, sample_variance, sorted,
+};
+
+/// Multiple of the interquartile range beyond the quartiles by default.
+const DEFAULT_IQR_MULTIPLIER: f64 = 1.5;
+/// Modified z-score above which a point is flagged by default.
+const DEFAULT_MODIFIED_Z_THRESHOLD: f64 = 3.5;
+/// Z-score above which a point is flagged by default.
+const DEFAULT_Z_THRESHOLD: f64 = 3.0;
+/// Rules that must agree on a consensus outlier by default.
+const DEFAULT_MIN_AGREEMENT: usize = 2;
+/// Coverage factor applied to a point's uncertainty when judging whether it
+/// explains the point's distance from the cleaned median.
+const UNCERTAINTY_COVERAGE: f64 = 2.0;
+
+/// Rules applied by the detection engine, in report order.
+const METHODS: [OutlierMethod; 3] = [
+    OutlierMethod::Iqr,
+    OutlierMethod::ModifiedZScore,
+    OutlierMethod::ZScore,
+];
+
+impl OutlierMethod {
+    /// Short name used in annotations and text reports.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Iqr => "iqr",
+            Self::ModifiedZScore => "modified_z",
+            Self::ZScore => "z_score",
+        }
+    }
+}
+
+/// Multi-rule outlier detection and removal reports.
+pub struct OutlierDetectionEngine;
+
+impl OutlierDetectionEngine {
+    /// Points of `data` flagged by each rule and by the consensus of at
+    /// least `min_agreement` rules.
+    ///
+    /// # Errors
+    /// Returns a structured error for fewer than three values, non-finite
+    /// values, non-positive thresholds, or an agreement count outside
+    /// `1..=3`.
+    pub fn detect(
+        data: &[f64],
+        options: &OutlierDetectionOptions,
+    ) -> Result<OutlierAnalysisResult, StatisticsError> {
+        if data.len() < 3 {
+            return Err(StatisticsError::InsufficientData {
+                needed: 3,
+                got: data.len(),
+            });
+        }
+        if data.iter().any(|value| !value.is_finite()) {
+            return Err(StatisticsError::validation(
+                "data",
+                "Outlier detection needs finite values",
+            ));
+        }
+        let iqr_multiplier = positive_option(
+            options.iqr_multiplier,
+            DEFAULT_IQR_MULTIPLIER,
+            "iqrMultiplier",
+        )?;
+        let modified_z_threshold = positive_option(
+            options.modified_z_threshold,
+            DEFAULT_MODIFIED_Z_THRESHOLD,
+            "modifiedZThreshold",
+        )?;
+        let z_threshold = positive_option(options.z_threshold, DEFAULT_Z_THRESHOLD, "zThreshold")?;
+        let min_agreement = options.min_agreement.unwrap_or(DEFAULT_MIN_AGREEMENT);
+        if !(1..=METHODS.len()).contains(&min_agreement) {
+            return Err(StatisticsError::validation(
+                "minAgreement",
+                format!(
+                    "Agreement must be between 1 and {} rules, got {min_agreement}",
+                    METHODS.len()
+                ),
+            ));
+        }
+
+        let ordered = sorted(data);
+        let q1 = quantile_sorted(&ordered, 0.25).unwrap_or(f64::NAN);
+        let q3 = quantile_sorted(&ordered, 0.75).unwrap_or(f64::NAN);
+        let iqr = q3 - q1;
+        let (low_fence, high_fence) = (
+            iqr_multiplier.mul_add(-iqr, q1),
+            iqr_multiplier.mul_add(iqr, q3),
+        );
+        let modified_scores = modified_z_scores(data).unwrap_or_default();
+        let center = mean(data).unwrap_or(f64::NAN);
+        let spread = sample_variance(data).unwrap_or(0.0).sqrt();
+
+        let flagged = |method: OutlierMethod| -> Vec<usize> {
+            (0..data.len())
+                .filter(|&index| {
+                    let value = data[index];
+                    match method {
+                        OutlierMethod::Iqr => value < low_fence || value > high_fence,
+                        OutlierMethod::ModifiedZScore => {
+                            modified_scores[index].abs() > modified_z_threshold
+                        }
+                        OutlierMethod::ZScore => {
+                            spread > 0.0 && ((value - center) / spread).abs() > z_threshold
+                        }
+                    }
+                })
+                .collect()
+        };
+        let methods: Vec<OutlierMethodResult> = METHODS
+            .iter()
+            .map(|&method| OutlierMethodResult {
+                method,
+                indices: flagged(method),
+            })
+            .collect();
+        let consensus_indices = (0..data.len())
+            .filter(|&index| votes(&methods, index).len() >= min_agreement)
+            .collect();
+
+        Ok(OutlierAnalysisResult {
+            count: data.len(),
+            methods,
+            min_agreement,
+            consensus_indices,
+        })
+    }
+
+    /// Annotation column, side-by-side statistics with and without the
+    /// consensus outliers of `analysis`, and a suggested action per point.
+    ///
+    /// A point flagged by `min_agreement` rules is removed and one flagged
+    /// by fewer is reviewed. When `uncertainties` are given, a point whose
+    /// distance from the cleaned median lies within twice its uncertainty is
+    /// moved one step towards keeping, since its measurement error could
+    /// explain the deviation.
+    ///
+    /// # Errors
+    /// Returns a structured error if `analysis` was computed for a different
+    /// number of points or refers to indices outside `data`, if the
+    /// uncertainties do not match the data or are negative, or if removing
+    /// the consensus outliers leaves no data.
+    pub fn report(
+        data: &[f64],
+        analysis: &OutlierAnalysisResult,
+        uncertainties: Option<&[f64]>,
+    ) -> Result<OutlierReport, StatisticsError> {
+        validate_report_inputs(data, analysis, uncertainties)?;
+
+        let kept: Vec<f64> = data
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !analysis.consensus_indices.contains(index))
+            .map(|(_, &value)| value)
+            .collect();
+        if kept.is_empty() {
+            return Err(StatisticsError::validation(
+                "analysis",
+                "Removing the consensus outliers leaves no data",
+            ));
+        }
+        let full = summarize(data);
+        let without_outliers = summarize(&kept);
+        let cleaned_median = without_outliers.quantiles.median;
+
+        let mut annotations = Vec::with_capacity(data.len());
+        let mut actions = Vec::with_capacity(data.len());
+        let mut points = Vec::new();
+        for (index, &value) in data.iter().enumerate() {
+            let methods = votes(&analysis.methods, index);
+            let explained = uncertainties
+                .is_some_and(|u| (value - cleaned_median).abs() <= UNCERTAINTY_COVERAGE * u[index]);
+            let consensus = analysis.consensus_indices.contains(&index);
+            let action = match (methods.len(), consensus, explained) {
+                (0, ..) | (_, false, true) => OutlierAction::Keep,
+                (_, false, false) | (_, true, true) => OutlierAction::Review,
+                (_, true, false) => OutlierAction::Remove,
+            };
+            annotations.push(if methods.is_empty() {
+                "ok".to_owned()
+            } else {
+                methods
+                    .iter()
+                    .map(|method| method.label())
+                    .collect::<Vec<_>>()
+                    .join(";")
+            });
+            actions.push(action);
+            if !methods.is_empty() {
+                points.push(OutlierPoint {
+                    index,
+                    value,
+                    methods,
+                    action,
+                });
+            }
+        }
+
+        Ok(OutlierReport {
+            annotations,
+            points,
+            actions,
+            comparison: compare(&full, &without_outliers),
+            full,
+            without_outliers,
+            removed_count: analysis.consensus_indices.len(),
+            min_agreement: analysis.min_agreement,
+            method_count: analysis.methods.len(),
+        })
+    }
+}
+
+/// Value of an optional threshold, which must be positive and finite.
+fn positive_option(value: Option<f64>, default: f64, field: &str) -> Result<f64, StatisticsError> {
+    let value = value.unwrap_or(default);
+    if value.is_finite() && value > 0.0 {
+        Ok(value)
+    } else {
+        Err(StatisticsError::validation(
+            field,
+            format!("{field} must be a positive number, got {value}"),
+        ))
+    }
+}
+
+/// Rules of `methods` that flag the point at `index`.
+fn votes(methods: &[OutlierMethodResult], index: usize) -> Vec<OutlierMethod> {
+    methods
+        .iter()
+        .filter(|result| result.indices.contains(&index))
+        .map(|result| result.method)
+        .collect()
+}
+
+/// Check that an analysis and the uncertainties belong to `data`.
+fn validate_report_inputs(
+    data: &[f64],
+    analysis: &OutlierAnalysisResult,
+    uncertainties: Option<&[f64]>,
+) -> Result<(), StatisticsError> {
+    if analysis.count != data.len() {
+        return Err(StatisticsError::validation(
+            "analysis",
+            format!(
+                "Analysis covers {} points but the data has {}",
+                analysis.count,
+                data.len()
+            ),
+        ));
+    }
+    let indices = analysis
+        .methods
+        .iter()
+        .flat_map(|result| &result.indices)
+        .chain(&analysis.consensus_indices);
+    for &index in indices {
+        if index >= data.len() {
+            return Err(StatisticsError::validation(
+                "analysis",
+                format!(
+                    "Flagged index {index} is outside data of length {}",
+                    data.len()
+                ),
+            ));
+        }
+    }
+    if let Some(uncertainties) = uncertainties {
+        if uncertainties.len() != data.len() {
+            return Err(StatisticsError::validation(
+                "uncertainties",
+                format!(
+                    "Expected one uncertainty per point ({}), got {}",
+                    data.len(),
+                    uncertainties.len()
+                ),
+            ));
+        }
+        if uncertainties
+            .iter()
+            .any(|value| !(value.is_finite() && *value >= 0.0))
+        {
+            return Err(StatisticsError::validation(
+                "uncertainties",
+                "Uncertainties must be finite and non-negative",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Moments and five-number summary of a non-empty slice.
+fn summarize(values: &[f64]) -> SampleSummary {
+    let center = mean(values).unwrap_or(f64::NAN);
+    let std_dev = sample_variance(values).map(f64::sqrt);
+    let ordered = sorted(values);
+    let quantile = |probability| quantile_sorted(&ordered, probability).unwrap_or(f64::NAN);
+    SampleSummary {
+        moments: StatisticalMoments {
+            count: values.len(),
+            mean: center,
+            std_dev,
+            skewness: skewness(values, center),
+        },
+        quantiles: Quantiles {
+            min: ordered[0],
+            q1: quantile(0.25),
+            median: median(values).unwrap_or(f64::NAN),
+            q3: quantile(0.75),
+            max: ordered[ordered.len() - 1],
+        },
+    }
+}
+
+/// Sample skewness `g1 = m3 / m2^(3/2)` from the central moments.
+fn skewness(values: &[f64], center: f64) -> Option<f64> {
+    if values.len() < 3 {
+        return None;
+    }
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for moments"
+    )]
+    let count = values.len() as f64;
+    let m2 = values.iter().map(|v| (v - center).powi(2)).sum::<f64>() / count;
+    let m3 = values.iter().map(|v| (v - center).powi(3)).sum::<f64>() / count;
+    (m2 > 0.0).then(|| m3 / m2.powf(1.5))
+}
+
+/// Side-by-side rows of two summaries.
+fn compare(full: &SampleSummary, without: &SampleSummary) -> Vec<StatisticComparison> {
+    let rows = |summary: &SampleSummary| {
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Sample count casting to f64 for the comparison table"
+        )]
+        let count = summary.moments.count as f64;
+        [
+            ("count", Some(count)),
+            ("mean", Some(summary.moments.mean)),
+            ("std_dev", summary.moments.std_dev),
+            ("skewness", summary.moments.skewness),
+            ("min", Some(summary.quantiles.min)),
+            ("q1", Some(summary.quantiles.q1)),
+            ("median", Some(summary.quantiles.median)),
+            ("q3", Some(summary.quantiles.q3)),
+            ("max", Some(summary.quantiles.max)),
+        ]
+    };
+    rows(full)
+        .into_iter()
+        .zip(rows(without))
+        .map(|((statistic, before), (_, after))| StatisticComparison {
+            statistic: statistic.to_owned(),
+            full: before,
+            without_outliers: after,
+            change: before.zip(after).map(|(before, after)| after - before),
+        })
+        .collect()
+}
+
+/// Table cell for an optional statistic.
+fn cell(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_owned(), |value| format!("{value:.6}"))
+}
+
+impl fmt::Display for OutlierAction {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Keep => "keep",
+            Self::Review => "review",
+            Self::Remove => "remove",
+        })
+    }
+}
+
+impl fmt::Display for OutlierReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            formatter,
+            "Outlier report: {} of {} points flagged by at least {} of {} rules",
+            self.removed_count,
+            self.annotations.len(),
+            self.min_agreement,
+            self.method_count
+        )?;
+        if !self.points.is_empty() {
+            writeln!(formatter)?;
+            writeln!(
+                formatter,
+                "{:>6}  {:>14}  {:<28}  action",
+                "index", "value", "rules"
+            )?;
+            for point in &self.points {
+                writeln!(
+                    formatter,
+                    "{:>6}  {:>14.6}  {:<28}  {}",
+                    point.index, point.value, self.annotations[point.index], point.action
+                )?;
+            }
+        }
+        writeln!(formatter)?;
+        writeln!(
+            formatter,
+            "{:<10}  {:>14}  {:>16}  {:>14}",
+            "statistic", "all data", "without outliers", "change"
+        )?;
+        for row in &self.comparison {
+            writeln!(
+                formatter,
+                "{:<10}  {:>14}  {:>16}  {:>14}",
+                row.statistic,
+                cell(row.full),
+                cell(row.without_outliers),
+                cell(row.change)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    /// Twenty readings around 10 with a spike at index 7.
+    fn spiked() -> Vec<f64> {
+        let mut data: Vec<f64> = (0..20)
+            .map(|i| 0.1_f64.mul_add(f64::from(i % 5), 9.8))
+            .collect();
+        data[7] = 25.0;
+        data
+    }
+
+    #[test]
+    fn test_removing_spike_restores_statistics() {
+        let data = spiked();
+        let analysis =
+            OutlierDetectionEngine::detect(&data, &OutlierDetectionOptions::default()).unwrap();
+        assert_eq!(analysis.consensus_indices, [7]);
+        assert!(analysis.methods.iter().all(|result| result.indices == [7]));
+
+        let report = OutlierDetectionEngine::report(&data, &analysis, None).unwrap();
+        assert_eq!(report.annotations.len(), data.len());
+        for (index, annotation) in report.annotations.iter().enumerate() {
+            if index == 7 {
+                assert_eq!(annotation, "iqr;modified_z;z_score");
+                assert_eq!(report.actions[index], OutlierAction::Remove);
+            } else {
+                assert_eq!(annotation, "ok");
+                assert_eq!(report.actions[index], OutlierAction::Keep);
+            }
+        }
+
+        let clean: Vec<f64> = data
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != 7)
+            .map(|(_, &value)| value)
+            .collect();
+        assert_abs_diff_eq!(
+            report.without_outliers.moments.mean,
+            mean(&clean).unwrap(),
+            epsilon = 1e-12
+        );
+        assert_eq!(report.without_outliers.moments.count, 19);
+        assert_abs_diff_eq!(report.full.quantiles.max, 25.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(report.without_outliers.quantiles.max, 10.2, epsilon = 1e-12);
+
+        let change = |name: &str| {
+            report
+                .comparison
+                .iter()
+                .find(|row| row.statistic == name)
+                .and_then(|row| row.change)
+                .unwrap()
+        };
+        // The spike adds (25 - 10) / 20 to the mean of the clean readings
+        assert_abs_diff_eq!(change("mean"), -0.75, epsilon = 0.01);
+        assert_abs_diff_eq!(change("count"), -1.0, epsilon = 1e-12);
+        assert!(change("std_dev") < -3.0);
+        assert!(change("skewness") < -3.0);
+        assert_abs_diff_eq!(change("median"), 0.0, epsilon = 1e-12);
+
+        let text = report.to_string();
+        assert!(text.starts_with("Outlier report: 1 of 20 points"), "{text}");
+        let spike_row = text
+            .lines()
+            .find(|line| line.trim_start().starts_with("7 "))
+            .unwrap();
+        assert!(spike_row.contains("iqr;modified_z;z_score"), "{text}");
+        assert!(spike_row.ends_with("remove"), "{text}");
+    }
+
+    #[test]
+    fn test_uncertainty_softens_actions_and_validation() {
+        let data = spiked();
+        let analysis =
+            OutlierDetectionEngine::detect(&data, &OutlierDetectionOptions::default()).unwrap();
+        let mut uncertainties = vec![0.05; data.len()];
+        uncertainties[7] = 10.0;
+        let report =
+            OutlierDetectionEngine::report(&data, &analysis, Some(&uncertainties)).unwrap();
+        assert_eq!(report.actions[7], OutlierAction::Review);
+        assert_eq!(report.points.len(), 1);
+        assert_eq!(report.points[0].index, 7);
+
+        let strict = OutlierDetectionOptions {
+            min_agreement: Some(3),
+            z_threshold: Some(100.0),
+            ..OutlierDetectionOptions::default()
+        };
+        let partial = OutlierDetectionEngine::detect(&data, &strict).unwrap();
+        assert!(partial.consensus_indices.is_empty());
+        let partial_report = OutlierDetectionEngine::report(&data, &partial, None).unwrap();
+        assert_eq!(partial_report.actions[7], OutlierAction::Review);
+        assert_eq!(partial_report.annotations[7], "iqr;modified_z");
+        assert_eq!(partial_report.removed_count, 0);
+
+        assert!(matches!(
+            OutlierDetectionEngine::report(&data[..10], &analysis, None),
+            Err(StatisticsError::Validation { field, .. }) if field == "analysis"
+        ));
+        assert!(matches!(
+            OutlierDetectionEngine::report(&data, &analysis, Some(&uncertainties[..3])),
+            Err(StatisticsError::Validation { field, .. }) if field == "uncertainties"
+        ));
+        assert!(matches!(
+            OutlierDetectionEngine::detect(&data[..2], &OutlierDetectionOptions::default()),
+            Err(StatisticsError::InsufficientData { needed: 3, got: 2 })
+        ));
+    }
+}
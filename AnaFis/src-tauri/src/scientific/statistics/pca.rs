@@ -0,0 +1,665 @@
+//! Principal component analysis with bootstrap uncertainty.
+//!
+//! Components are eigenvectors of the sample covariance matrix. The bootstrap
+//! resamples rows with replacement, flips each resampled loading vector to
+//! agree in sign with the full-data one, and reports percentile intervals.
+//! Components whose eigenvalues are close may swap order between resamples;
+//! their intervals are then wide, which itself signals an unstable axis.
+//!
+//! Robust PCA uses projection pursuit: starting from the median-centred
+//! observations as candidate directions, each component maximises a bisquare
+//! M-scale of the projections by gradient ascent on the unit sphere, and the
+//! data are deflated before searching for the next one.
+
+use nalgebra::{DMatrix, SymmetricEigen};
+use rand::{RngCore, SeedableRng};
+use rand_pcg::Pcg64;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+use tracing::info;
+
+use super::types::{PcaBootstrapResult, PcaResult, RobustPcaResult};
+use crate::scientific::primitives::descriptive::{
+    median, quantile_sorted, sample_variance, sorted,
+};
+use crate::utils::progress::ProgressReporter;
+
+/// Fewest bootstrap resamples accepted.
+const MIN_BOOTSTRAP: usize = 10;
+/// Two-sided tail probability of the percentile intervals.
+const CI_ALPHA: f64 = 0.05;
+/// Bisquare tuning constant giving a 50 % breakdown M-scale.
+const BISQUARE_C: f64 = 1.547;
+/// Right-hand side of the M-scale equation, `E[rho] = 0.5` under normality.
+const M_SCALE_DELTA: f64 = 0.5;
+/// MAD to standard deviation factor under normality.
+const MAD_TO_SIGMA: f64 = 1.482_602_218_505_602;
+/// Gradient-ascent iterations per component.
+const MAX_ASCENT_ITERATIONS: usize = 200;
+/// Observations weighted below this are reported as outliers.
+const OUTLIER_WEIGHT: f64 = 0.1;
+/// Probability of the chi-squared and normal cut-offs for the weights.
+const WEIGHT_QUANTILE: f64 = 0.975;
+/// Standard normal quantile at [`WEIGHT_QUANTILE`].
+const NORMAL_975: f64 = 1.959_963_984_540_054;
+
+/// Principal component analysis.
+pub struct PcaOps;
+
+impl PcaOps {
+    /// Principal components of `data`, given as rows of observations.
+    ///
+    /// Keeps `n_components` components (all by default).
+    ///
+    /// # Errors
+    /// Returns an error for fewer than two rows, ragged or non-finite rows, or
+    /// an invalid component count.
+    pub fn pca(data: &[Vec<f64>], n_components: Option<usize>) -> Result<PcaResult, String> {
+        let components = validate(data, n_components)?;
+        let rows: Vec<usize> = (0..data.len()).collect();
+        Ok(decompose(data, &rows, components))
+    }
+
+    /// Principal components of `data` with bootstrap percentile intervals.
+    ///
+    /// Rows are resampled with replacement `n_bootstrap` times in parallel;
+    /// each resample draws from its own generator seeded from `seed`, so the
+    /// result does not depend on thread scheduling. Completed resamples are
+    /// reported to `progress`; once it is cancelled, the remaining resamples
+    /// are skipped.
+    ///
+    /// # Errors
+    /// Returns an error for invalid data as in [`PcaOps::pca`], fewer than
+    /// ten resamples, or when the computation is cancelled.
+    pub fn pca_bootstrap(
+        data: &[Vec<f64>],
+        n_components: Option<usize>,
+        n_bootstrap: usize,
+        seed: u64,
+        progress: Option<&ProgressReporter>,
+    ) -> Result<PcaBootstrapResult, String> {
+        let components = validate(data, n_components)?;
+        if n_bootstrap < MIN_BOOTSTRAP {
+            return Err(format!(
+                "At least {MIN_BOOTSTRAP} bootstrap resamples are required, got {n_bootstrap}"
+            ));
+        }
+        let all_rows: Vec<usize> = (0..data.len()).collect();
+        let estimate = decompose(data, &all_rows, components);
+
+        let mut seeder = Pcg64::seed_from_u64(seed);
+        let seeds: Vec<u64> = (0..n_bootstrap).map(|_| seeder.next_u64()).collect();
+        let rows = data.len() as u64;
+        let finished = AtomicUsize::new(0);
+        let replicates: Option<Vec<PcaResult>> = seeds
+            .into_par_iter()
+            .map(|replicate_seed| {
+                if progress.is_some_and(ProgressReporter::is_cancelled) {
+                    return None;
+                }
+                let mut rng = Pcg64::seed_from_u64(replicate_seed);
+                #[allow(
+                    clippy::cast_possible_truncation,
+                    reason = "Remainder is below the row count, which fits in usize"
+                )]
+                let resample: Vec<usize> = (0..data.len())
+                    .map(|_| (rng.next_u64() % rows) as usize)
+                    .collect();
+                let mut replicate = decompose(data, &resample, components);
+                align_signs(&mut replicate.loadings, &estimate.loadings);
+                if let Some(progress) = progress {
+                    let done = finished.fetch_add(1, Ordering::Relaxed) + 1;
+                    progress.report(done, n_bootstrap, None);
+                }
+                Some(replicate)
+            })
+            .collect();
+        if let Some(progress) = progress {
+            progress.finish(finished.load(Ordering::Relaxed), n_bootstrap, None);
+        }
+        let replicates = replicates.ok_or_else(|| "PCA bootstrap was cancelled".to_owned())?;
+
+        let variables = estimate.means.len();
+        let mut result = PcaBootstrapResult {
+            eigenvalue_ci_lower: Vec::with_capacity(components),
+            eigenvalue_ci_upper: Vec::with_capacity(components),
+            loading_ci_lower: vec![Vec::with_capacity(variables); components],
+            loading_ci_upper: vec![Vec::with_capacity(variables); components],
+            loading_se: vec![Vec::with_capacity(variables); components],
+            loading_stable: vec![Vec::with_capacity(variables); components],
+            n_bootstrap,
+            estimate,
+        };
+        for component in 0..components {
+            let eigenvalues: Vec<f64> = replicates
+                .iter()
+                .map(|replicate| replicate.eigenvalues[component])
+                .collect();
+            let (eigen_lower, eigen_upper) = percentile_interval(&eigenvalues);
+            result.eigenvalue_ci_lower.push(eigen_lower);
+            result.eigenvalue_ci_upper.push(eigen_upper);
+
+            for variable in 0..variables {
+                let loadings: Vec<f64> = replicates
+                    .iter()
+                    .map(|replicate| replicate.loadings[component][variable])
+                    .collect();
+                let (lower, upper) = percentile_interval(&loadings);
+                result.loading_ci_lower[component].push(lower);
+                result.loading_ci_upper[component].push(upper);
+                result.loading_se[component]
+                    .push(sample_variance(&loadings).unwrap_or_default().sqrt());
+                result.loading_stable[component].push(lower > 0.0 || upper < 0.0);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Outlier-resistant principal components of `data` by projection pursuit.
+    ///
+    /// Each observation gets a weight from its robust score distance within
+    /// the retained components and its distance to that subspace, so that
+    /// outliers in either sense are down-weighted. The classical PCA is
+    /// returned alongside for comparison.
+    ///
+    /// # Errors
+    /// Returns an error for invalid data as in [`PcaOps::pca`].
+    pub fn robust_pca(data: &[Vec<f64>], n_components: usize) -> Result<RobustPcaResult, String> {
+        let components = validate(data, Some(n_components))?;
+        let variables = data[0].len();
+        let all_rows: Vec<usize> = (0..data.len()).collect();
+        let classical = decompose(data, &all_rows, components);
+
+        let centre: Vec<f64> = (0..variables)
+            .map(|column| {
+                let values: Vec<f64> = data.iter().map(|row| row[column]).collect();
+                median(&values).unwrap_or_default()
+            })
+            .collect();
+        let centred: Vec<Vec<f64>> = data
+            .iter()
+            .map(|row| row.iter().zip(&centre).map(|(x, c)| x - c).collect())
+            .collect();
+
+        // Search every direction so the explained ratios have a robust total.
+        let mut residual = centred.clone();
+        let mut directions: Vec<(Vec<f64>, f64)> = Vec::with_capacity(variables);
+        for _ in 0..variables {
+            let Some((direction, scale)) = pursue_direction(&residual) else {
+                break;
+            };
+            for row in &mut residual {
+                let score = dot(row, &direction);
+                for (value, weight) in row.iter_mut().zip(&direction) {
+                    *value = score.mul_add(-weight, *value);
+                }
+            }
+            directions.push((direction, scale));
+        }
+        let total: f64 = directions.iter().map(|(_, scale)| scale * scale).sum();
+
+        let mut robust = PcaResult {
+            eigenvalues: Vec::with_capacity(components),
+            explained_variance_ratio: Vec::with_capacity(components),
+            loadings: Vec::with_capacity(components),
+            means: centre,
+        };
+        let mut scales = Vec::with_capacity(components);
+        for (mut loading, scale) in directions.into_iter().take(components) {
+            orient(&mut loading);
+            let eigenvalue = scale * scale;
+            robust.eigenvalues.push(eigenvalue);
+            robust.explained_variance_ratio.push(if total > 0.0 {
+                eigenvalue / total
+            } else {
+                0.0
+            });
+            robust.loadings.push(loading);
+            scales.push(scale);
+        }
+
+        let robustness_weights = robustness_weights(&centred, &robust.loadings, &scales);
+        let outliers = robustness_weights
+            .iter()
+            .filter(|&&weight| weight < OUTLIER_WEIGHT)
+            .count();
+        info!(
+            "Robust PCA down-weighted {outliers} of {} observations below {OUTLIER_WEIGHT}",
+            data.len()
+        );
+
+        Ok(RobustPcaResult {
+            robust,
+            robustness_weights,
+            classical,
+        })
+    }
+}
+
+/// Check the data shape and resolve the number of components.
+fn validate(data: &[Vec<f64>], n_components: Option<usize>) -> Result<usize, String> {
+    if data.len() < 2 {
+        return Err(format!(
+            "PCA needs at least 2 observations, got {}",
+            data.len()
+        ));
+    }
+    let variables = data[0].len();
+    if variables == 0 {
+        return Err("PCA needs at least one variable".to_owned());
+    }
+    for (index, row) in data.iter().enumerate() {
+        if row.len() != variables {
+            return Err(format!(
+                "Row {index} has {} values, expected {variables}",
+                row.len()
+            ));
+        }
+        if row.iter().any(|value| !value.is_finite()) {
+            return Err(format!("Row {index} contains non-finite values"));
+        }
+    }
+    match n_components {
+        Some(0) => Err("Number of components must be at least 1".to_owned()),
+        Some(count) if count > variables => Err(format!(
+            "Cannot keep {count} components from {variables} variables"
+        )),
+        Some(count) => Ok(count),
+        None => Ok(variables),
+    }
+}
+
+/// Eigen-decomposition of the sample covariance of `data[rows]`.
+fn decompose(data: &[Vec<f64>], rows: &[usize], components: usize) -> PcaResult {
+    let variables = data[0].len();
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Row count casting to f64 for moments"
+    )]
+    let count = rows.len() as f64;
+    let means: Vec<f64> = (0..variables)
+        .map(|column| rows.iter().map(|&row| data[row][column]).sum::<f64>() / count)
+        .collect();
+    let mut covariance = DMatrix::<f64>::zeros(variables, variables);
+    for &row in rows {
+        for i in 0..variables {
+            let centred_i = data[row][i] - means[i];
+            for j in 0..=i {
+                covariance[(i, j)] = centred_i.mul_add(data[row][j] - means[j], covariance[(i, j)]);
+            }
+        }
+    }
+    for i in 0..variables {
+        for j in 0..=i {
+            covariance[(i, j)] /= count - 1.0;
+            covariance[(j, i)] = covariance[(i, j)];
+        }
+    }
+
+    let eigen = SymmetricEigen::new(covariance);
+    let mut order: Vec<usize> = (0..variables).collect();
+    order.sort_by(|&a, &b| eigen.eigenvalues[b].total_cmp(&eigen.eigenvalues[a]));
+    let total: f64 = eigen.eigenvalues.iter().map(|value| value.max(0.0)).sum();
+
+    let mut result = PcaResult {
+        eigenvalues: Vec::with_capacity(components),
+        explained_variance_ratio: Vec::with_capacity(components),
+        loadings: Vec::with_capacity(components),
+        means,
+    };
+    for &index in order.iter().take(components) {
+        let eigenvalue = eigen.eigenvalues[index].max(0.0);
+        let mut loading: Vec<f64> = eigen.eigenvectors.column(index).iter().copied().collect();
+        orient(&mut loading);
+        result.eigenvalues.push(eigenvalue);
+        result
+            .explained_variance_ratio
+            .push(if total > 0.0 { eigenvalue / total } else { 0.0 });
+        result.loadings.push(loading);
+    }
+    result
+}
+
+/// Direction of largest M-scale of the projected `rows`, with that scale.
+///
+/// Every normalised observation is tried as a starting direction; the best
+/// is refined by gradient ascent. Returns `None` once the rows carry no
+/// spread in any direction.
+fn pursue_direction(rows: &[Vec<f64>]) -> Option<(Vec<f64>, f64)> {
+    let mut best: Option<(Vec<f64>, f64)> = None;
+    for row in rows {
+        let norm = dot(row, row).sqrt();
+        if norm <= f64::EPSILON {
+            continue;
+        }
+        let candidate: Vec<f64> = row.iter().map(|value| value / norm).collect();
+        let scale = m_scale(&project(rows, &candidate));
+        if best
+            .as_ref()
+            .is_none_or(|(_, best_scale)| scale > *best_scale)
+        {
+            best = Some((candidate, scale));
+        }
+    }
+    let (mut direction, mut scale) = best.filter(|(_, scale)| *scale > 0.0)?;
+
+    let mut step = 1.0;
+    for _ in 0..MAX_ASCENT_ITERATIONS {
+        // Implicit derivative of the M-scale: sigma' ~ sum psi(u) x / sum psi(u) u.
+        let mut gradient = vec![0.0; direction.len()];
+        let mut normaliser = 0.0;
+        for row in rows {
+            let u = dot(row, &direction) / scale;
+            let psi = bisquare_psi(u);
+            normaliser = psi.mul_add(u, normaliser);
+            for (component, value) in gradient.iter_mut().zip(row) {
+                *component = psi.mul_add(*value, *component);
+            }
+        }
+        if normaliser <= 0.0 {
+            break;
+        }
+        // Keep only the part of the gradient tangent to the unit sphere.
+        let radial = dot(&gradient, &direction);
+        for (component, value) in gradient.iter_mut().zip(&direction) {
+            *component = radial.mul_add(-value, *component);
+        }
+        let length = dot(&gradient, &gradient).sqrt();
+        if length <= f64::EPSILON * normaliser {
+            break;
+        }
+
+        let mut candidate: Vec<f64> = direction
+            .iter()
+            .zip(&gradient)
+            .map(|(value, slope)| (step / length).mul_add(*slope, *value))
+            .collect();
+        let norm = dot(&candidate, &candidate).sqrt();
+        for value in &mut candidate {
+            *value /= norm;
+        }
+        let candidate_scale = m_scale(&project(rows, &candidate));
+        if candidate_scale > scale {
+            direction = candidate;
+            scale = candidate_scale;
+        } else {
+            step /= 2.0;
+            if step < 1e-10 {
+                break;
+            }
+        }
+    }
+    Some((direction, scale))
+}
+
+/// Bisquare M-scale of `values` around zero, started from the MAD.
+fn m_scale(values: &[f64]) -> f64 {
+    let magnitudes: Vec<f64> = values.iter().map(|value| value.abs()).collect();
+    let mut scale = median(&magnitudes).unwrap_or_default() * MAD_TO_SIGMA;
+    if scale <= 0.0 {
+        return 0.0;
+    }
+    for _ in 0..100 {
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Observation count casting to f64 for the mean"
+        )]
+        let mean_rho = values
+            .iter()
+            .map(|value| bisquare_rho(value / scale))
+            .sum::<f64>()
+            / values.len() as f64;
+        let updated = scale * (mean_rho / M_SCALE_DELTA).sqrt();
+        let converged = (updated - scale).abs() <= 1e-10 * scale;
+        scale = updated;
+        if converged {
+            break;
+        }
+    }
+    scale
+}
+
+/// Tukey bisquare loss, scaled to 1 beyond [`BISQUARE_C`].
+fn bisquare_rho(u: f64) -> f64 {
+    let ratio = u / BISQUARE_C;
+    if ratio.abs() >= 1.0 {
+        1.0
+    } else {
+        let inner = ratio.mul_add(-ratio, 1.0);
+        (inner * inner).mul_add(-inner, 1.0)
+    }
+}
+
+/// Derivative of [`bisquare_rho`] up to a constant factor.
+fn bisquare_psi(u: f64) -> f64 {
+    let ratio = u / BISQUARE_C;
+    if ratio.abs() >= 1.0 {
+        0.0
+    } else {
+        let inner = ratio.mul_add(-ratio, 1.0);
+        u * inner * inner
+    }
+}
+
+/// Observation weights from robust score and orthogonal distances.
+///
+/// The score distance is compared with a chi-squared cut-off on the number
+/// of components, the orthogonal distance with a normal cut-off on its
+/// two-thirds power; each weight is `min(1, (cutoff / distance)^2)`.
+fn robustness_weights(centred: &[Vec<f64>], loadings: &[Vec<f64>], scales: &[f64]) -> Vec<f64> {
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Component count casting to f64 for degrees of freedom"
+    )]
+    let score_cutoff = ChiSquared::new(loadings.len() as f64)
+        .map_or(f64::INFINITY, |chi| chi.inverse_cdf(WEIGHT_QUANTILE));
+
+    let mut score_distances = Vec::with_capacity(centred.len());
+    let mut orthogonal_distances = Vec::with_capacity(centred.len());
+    for row in centred {
+        let mut distance = 0.0;
+        let mut fitted = vec![0.0; row.len()];
+        for (loading, scale) in loadings.iter().zip(scales) {
+            let score = dot(row, loading);
+            if *scale > 0.0 {
+                distance += (score / scale).powi(2);
+            }
+            for (value, weight) in fitted.iter_mut().zip(loading) {
+                *value = score.mul_add(*weight, *value);
+            }
+        }
+        score_distances.push(distance);
+        let orthogonal: f64 = row
+            .iter()
+            .zip(&fitted)
+            .map(|(value, fit)| (value - fit).powi(2))
+            .sum();
+        orthogonal_distances.push(orthogonal.sqrt());
+    }
+
+    // Orthogonal distances to the power 2/3 are roughly normal.
+    let powered: Vec<f64> = orthogonal_distances
+        .iter()
+        .map(|distance| distance.powf(2.0 / 3.0))
+        .collect();
+    let centre = median(&powered).unwrap_or_default();
+    let deviations: Vec<f64> = powered.iter().map(|value| (value - centre).abs()).collect();
+    let spread = median(&deviations).unwrap_or_default() * MAD_TO_SIGMA;
+    let orthogonal_cutoff = spread.mul_add(NORMAL_975, centre).max(0.0).powf(1.5);
+
+    score_distances
+        .iter()
+        .zip(&orthogonal_distances)
+        .map(|(&score_distance, &orthogonal_distance)| {
+            let score_weight = if score_distance > score_cutoff {
+                score_cutoff / score_distance
+            } else {
+                1.0
+            };
+            let orthogonal_weight = if orthogonal_distance > orthogonal_cutoff {
+                (orthogonal_cutoff / orthogonal_distance).powi(2)
+            } else {
+                1.0
+            };
+            score_weight.min(orthogonal_weight)
+        })
+        .collect()
+}
+
+/// Projections of `rows` onto `direction`.
+fn project(rows: &[Vec<f64>], direction: &[f64]) -> Vec<f64> {
+    rows.iter().map(|row| dot(row, direction)).collect()
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Flip `loading` so that its largest entry is positive.
+fn orient(loading: &mut [f64]) {
+    let largest = loading
+        .iter()
+        .copied()
+        .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+        .unwrap_or_default();
+    if largest < 0.0 {
+        for value in loading.iter_mut() {
+            *value = -*value;
+        }
+    }
+}
+
+/// Flip each loading vector that points away from its reference.
+fn align_signs(loadings: &mut [Vec<f64>], reference: &[Vec<f64>]) {
+    for (loading, target) in loadings.iter_mut().zip(reference) {
+        let dot: f64 = loading.iter().zip(target).map(|(a, b)| a * b).sum();
+        if dot < 0.0 {
+            for value in loading.iter_mut() {
+                *value = -*value;
+            }
+        }
+    }
+}
+
+/// Central `1 - CI_ALPHA` percentile interval of `values`.
+fn percentile_interval(values: &[f64]) -> (f64, f64) {
+    let ordered = sorted(values);
+    (
+        quantile_sorted(&ordered, CI_ALPHA / 2.0).unwrap_or(f64::NAN),
+        quantile_sorted(&ordered, 1.0 - CI_ALPHA / 2.0).unwrap_or(f64::NAN),
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+
+    /// Two strongly correlated variables plus an independent low-variance one.
+    fn correlated_data() -> Vec<Vec<f64>> {
+        let mut rng = Pcg64::seed_from_u64(11);
+        let mut uniform = || {
+            #[allow(
+                clippy::cast_precision_loss,
+                reason = "53 random bits are exactly representable in f64"
+            )]
+            let mantissa = (rng.next_u64() >> 11) as f64;
+            mantissa * f64::EPSILON - 1.0
+        };
+        (0..200)
+            .map(|_| {
+                let driver = 3.0 * uniform();
+                vec![
+                    0.2_f64.mul_add(uniform(), driver),
+                    2.0_f64.mul_add(driver, 0.2 * uniform()),
+                    0.3 * uniform(),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pca_recovers_dominant_axis() {
+        let data = vec![
+            vec![1.0, 1.0],
+            vec![2.0, 2.0],
+            vec![3.0, 3.0],
+            vec![4.0, 4.0],
+        ];
+        let result = PcaOps::pca(&data, None).unwrap();
+        assert!((result.eigenvalues[0] - 10.0 / 3.0).abs() < 1e-12);
+        assert!(result.eigenvalues[1].abs() < 1e-12);
+        assert!((result.explained_variance_ratio[0] - 1.0).abs() < 1e-12);
+        let component = std::f64::consts::FRAC_1_SQRT_2;
+        assert!((result.loadings[0][0] - component).abs() < 1e-12);
+        assert!((result.loadings[0][1] - component).abs() < 1e-12);
+        assert_eq!(result.means, vec![2.5, 2.5]);
+
+        assert!(PcaOps::pca(&data, Some(3)).is_err());
+        assert!(PcaOps::pca(&[vec![1.0, 2.0], vec![1.0]], None).is_err());
+    }
+
+    #[test]
+    fn test_bootstrap_intervals_flag_stable_loadings() {
+        let data = correlated_data();
+        let result = PcaOps::pca_bootstrap(&data, Some(2), 200, 42, None).unwrap();
+        assert_eq!(result.n_bootstrap, 200);
+
+        let estimate = &result.estimate;
+        assert!(result.eigenvalue_ci_lower[0] <= estimate.eigenvalues[0]);
+        assert!(result.eigenvalue_ci_upper[0] >= estimate.eigenvalues[0]);
+        // The first component spans the correlated pair, not the noise column.
+        assert_eq!(result.loading_stable[0][..2], [true, true]);
+        assert!(!result.loading_stable[0][2]);
+        assert!(result.loading_se[0][1] < 0.05);
+        assert!(result.loading_ci_lower[0][1] > 0.8);
+
+        let last_reported = Arc::new(AtomicUsize::new(0));
+        let sink_reported = Arc::clone(&last_reported);
+        let reporter = ProgressReporter::new("pca".to_owned(), move |progress| {
+            sink_reported.store(progress.iteration, Ordering::Relaxed);
+        });
+        let repeated = PcaOps::pca_bootstrap(&data, Some(2), 200, 42, Some(&reporter)).unwrap();
+        assert_eq!(repeated.loading_ci_lower, result.loading_ci_lower);
+        assert_eq!(last_reported.load(Ordering::Relaxed), 200);
+        assert!(PcaOps::pca_bootstrap(&data, None, 5, 42, None).is_err());
+
+        let cancelled = ProgressReporter::new("pca".to_owned(), |_| {})
+            .with_cancellation(Arc::new(AtomicBool::new(true)));
+        assert!(PcaOps::pca_bootstrap(&data, Some(2), 200, 42, Some(&cancelled)).is_err());
+    }
+
+    #[test]
+    fn test_robust_pca_resists_planted_outliers() {
+        let mut data = correlated_data();
+        // Ten percent of gross outliers along the noise axis.
+        for row in data.iter_mut().take(20) {
+            row[2] = 40.0;
+        }
+        let result = PcaOps::robust_pca(&data, 2).unwrap();
+
+        // Classical PCA is dragged towards the outliers, robust PCA is not.
+        assert!(result.classical.loadings[0][2] > 0.5);
+        let axis = [1.0 / 5.0_f64.sqrt(), 2.0 / 5.0_f64.sqrt(), 0.0];
+        assert!(dot(&result.classical.loadings[0], &axis).abs() < 0.1);
+        assert!(dot(&result.robust.loadings[0], &axis).abs() > 0.95);
+        assert_eq!(result.robust.means.len(), 3);
+
+        let weights = &result.robustness_weights;
+        assert!(weights[..20].iter().all(|&weight| weight < OUTLIER_WEIGHT));
+        let clean = weights[20..]
+            .iter()
+            .filter(|&&weight| weight >= 1.0)
+            .count();
+        assert!(
+            clean > 160,
+            "only {clean} clean observations kept full weight"
+        );
+
+        assert!(PcaOps::robust_pca(&data, 0).is_err());
+    }
+}
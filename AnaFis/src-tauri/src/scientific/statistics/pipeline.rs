@@ -0,0 +1,450 @@
+//! Multi-column analysis pipeline.
+//!
+//! Columns with at least three finite values are summarized and checked for
+//! normality with Shapiro-Wilk. Every pair of columns is compared by Welch's
+//! t-test when both are consistent with normality and by the Mann-Whitney U
+//! test otherwise, and the p-values of the family are adjusted for multiple
+//! comparisons. The Pearson correlation matrix uses the rows where every
+//! column has a value, and the columns are clustered agglomeratively by the
+//! correlation distance `1 - r` with single or complete linkage.
+use super::descriptive::summarize;
+use super::error::StatisticsError;
+use super::hypothesis_testing::HypothesisTestingEngine;
+use super::matrix_ops::MatrixOpsEngine;
+use super::normality::NormalityTestingEngine;
+use super::types::{
+    ClusterLinkage, ClusterMerge, ColumnAnalysis, MultiColumnReport, MultipleComparisonAdjustment,
+    NamedColumn, PairwiseComparison, PairwiseTest,
+};
+
+/// Fewest finite values for a column to be analyzed.
+const MIN_COLUMN_OBSERVATIONS: usize = 3;
+
+/// Analyses spanning several measurement columns.
+pub struct StatisticalAnalysisPipeline;
+
+impl StatisticalAnalysisPipeline {
+    /// Compare `columns` in one pass.
+    ///
+    /// Runs descriptive statistics, normality pre-checks, pairwise
+    /// two-sample tests, the correlation matrix and a correlation clustering.
+    /// Columns with fewer than three finite values are excluded with a note.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::Validation`] for an alpha outside (0, 1)
+    /// and [`StatisticsError::InsufficientData`] when no column has three
+    /// finite values.
+    pub fn analyze_columns(
+        columns: &[NamedColumn],
+        alpha: f64,
+        adjustment: MultipleComparisonAdjustment,
+        linkage: ClusterLinkage,
+    ) -> Result<MultiColumnReport, StatisticsError> {
+        if !(alpha > 0.0 && alpha < 1.0) {
+            return Err(StatisticsError::validation(
+                "alpha",
+                format!("Alpha must be between 0 and 1, got {alpha}"),
+            ));
+        }
+
+        let mut notes = Vec::new();
+        let mut excluded_columns = Vec::new();
+        let mut included: Vec<(&NamedColumn, Vec<f64>)> = Vec::new();
+        for column in columns {
+            let values: Vec<f64> = column
+                .values
+                .iter()
+                .flatten()
+                .copied()
+                .filter(|value| value.is_finite())
+                .collect();
+            if values.len() < MIN_COLUMN_OBSERVATIONS {
+                notes.push(format!(
+                    "'{}' was excluded: it has {} valid observations, at least {MIN_COLUMN_OBSERVATIONS} are needed",
+                    column.name,
+                    values.len()
+                ));
+                excluded_columns.push(column.name.clone());
+            } else {
+                included.push((column, values));
+            }
+        }
+        if included.is_empty() {
+            let got = columns
+                .iter()
+                .map(|column| column.values.iter().flatten().count())
+                .max()
+                .unwrap_or(0);
+            return Err(StatisticsError::InsufficientData {
+                needed: MIN_COLUMN_OBSERVATIONS,
+                got,
+            });
+        }
+
+        let analyses: Vec<ColumnAnalysis> = included
+            .iter()
+            .map(|(column, values)| {
+                let shapiro_wilk_p_value = NormalityTestingEngine::shapiro_wilk(values)
+                    .ok()
+                    .map(|result| result.p_value);
+                ColumnAnalysis {
+                    summary: summarize(&column.name, values, None, MIN_COLUMN_OBSERVATIONS),
+                    shapiro_wilk_p_value,
+                    consistent_with_normal: shapiro_wilk_p_value.is_some_and(|p| p >= alpha),
+                }
+            })
+            .collect();
+        let (comparisons, comparison_matrix) =
+            compare_pairs(&included, &analyses, alpha, adjustment, &mut notes);
+
+        let (correlation_matrix, correlation_observations) = correlation_of(&included, &mut notes);
+        let (merges, dendrogram_order) = correlation_matrix.as_ref().map_or_else(
+            || (Vec::new(), (0..included.len()).collect()),
+            |correlation| {
+                let distances: Vec<Vec<f64>> = correlation
+                    .iter()
+                    .map(|row| row.iter().map(|r| 1.0 - r).collect())
+                    .collect();
+                cluster(&distances, linkage)
+            },
+        );
+
+        Ok(MultiColumnReport {
+            columns: analyses,
+            excluded_columns,
+            alpha,
+            correlation_matrix,
+            correlation_observations,
+            adjustment,
+            comparisons,
+            comparison_matrix,
+            linkage,
+            merges,
+            dendrogram_order,
+            notes,
+        })
+    }
+}
+
+/// Pearson correlation matrix of the columns over the rows where every
+/// column has a value, with the number of those rows. Failures are noted.
+fn correlation_of(
+    columns: &[(&NamedColumn, Vec<f64>)],
+    notes: &mut Vec<String>,
+) -> (Option<Vec<Vec<f64>>>, usize) {
+    let length = columns
+        .iter()
+        .map(|(column, _)| column.values.len())
+        .min()
+        .unwrap_or(0);
+    let rows: Vec<Vec<f64>> = (0..length)
+        .filter_map(|row| {
+            columns
+                .iter()
+                .map(|(column, _)| column.values[row].filter(|value| value.is_finite()))
+                .collect()
+        })
+        .collect();
+    if columns.len() < 2 {
+        notes.push("Correlation and clustering need at least two columns".to_owned());
+        return (None, rows.len());
+    }
+    let matrix = MatrixOpsEngine::correlation_matrix(&rows)
+        .inspect_err(|e| notes.push(format!("Correlation matrix not computed: {e}")))
+        .ok()
+        .map(|matrix| {
+            matrix
+                .row_iter()
+                .map(|row| row.iter().copied().collect())
+                .collect()
+        });
+    (matrix, rows.len())
+}
+
+/// Two-sample tests of every pair of columns, with their adjusted p-values
+/// also laid out as a symmetric matrix. Pairs whose test fails get a note.
+fn compare_pairs(
+    columns: &[(&NamedColumn, Vec<f64>)],
+    analyses: &[ColumnAnalysis],
+    alpha: f64,
+    adjustment: MultipleComparisonAdjustment,
+    notes: &mut Vec<String>,
+) -> (Vec<PairwiseComparison>, Vec<Vec<Option<f64>>>) {
+    let count = columns.len();
+    let mut tests = Vec::new();
+    for first in 0..count {
+        for second in first + 1..count {
+            let (sample1, sample2) = (&columns[first].1, &columns[second].1);
+            let outcome = if analyses[first].consistent_with_normal
+                && analyses[second].consistent_with_normal
+            {
+                HypothesisTestingEngine::welch_t_test(sample1, sample2)
+                    .map(|result| (PairwiseTest::WelchT, result.t_statistic, result.p_value))
+            } else {
+                HypothesisTestingEngine::mann_whitney_u(sample1, sample2)
+                    .map(|result| (PairwiseTest::MannWhitney, result.z_score, result.p_value))
+            };
+            match outcome {
+                Ok((test, statistic, p_value)) => {
+                    tests.push((first, second, test, statistic, p_value));
+                }
+                Err(e) => notes.push(format!(
+                    "'{}' and '{}' were not compared: {e}",
+                    columns[first].0.name, columns[second].0.name
+                )),
+            }
+        }
+    }
+
+    let p_values: Vec<f64> = tests.iter().map(|test| test.4).collect();
+    let adjusted = adjust_p_values(&p_values, adjustment);
+    let mut matrix: Vec<Vec<Option<f64>>> = (0..count)
+        .map(|row| {
+            (0..count)
+                .map(|column| (row == column).then_some(1.0))
+                .collect()
+        })
+        .collect();
+    let comparisons = tests
+        .into_iter()
+        .zip(adjusted)
+        .map(
+            |((first, second, test, statistic, p_value), adjusted_p_value)| {
+                matrix[first][second] = Some(adjusted_p_value);
+                matrix[second][first] = Some(adjusted_p_value);
+                PairwiseComparison {
+                    first,
+                    second,
+                    test,
+                    statistic,
+                    p_value,
+                    adjusted_p_value,
+                    significant: adjusted_p_value < alpha,
+                }
+            },
+        )
+        .collect();
+    (comparisons, matrix)
+}
+
+/// `p_values` adjusted for being tested together, in their original order.
+fn adjust_p_values(p_values: &[f64], adjustment: MultipleComparisonAdjustment) -> Vec<f64> {
+    #[allow(clippy::cast_precision_loss, reason = "Test count casting to f64")]
+    let tests = p_values.len() as f64;
+    let mut order: Vec<usize> = (0..p_values.len()).collect();
+    order.sort_by(|&a, &b| p_values[a].total_cmp(&p_values[b]));
+
+    let mut adjusted = vec![0.0; p_values.len()];
+    match adjustment {
+        MultipleComparisonAdjustment::Bonferroni => {
+            for (value, p_value) in adjusted.iter_mut().zip(p_values) {
+                *value = (p_value * tests).min(1.0);
+            }
+        }
+        MultipleComparisonAdjustment::Holm => {
+            // Step down: the k-th smallest is scaled by m - k, kept monotone
+            let mut running: f64 = 0.0;
+            for (rank, &index) in order.iter().enumerate() {
+                #[allow(clippy::cast_precision_loss, reason = "Rank casting to f64")]
+                let scale = tests - rank as f64;
+                running = running.max((p_values[index] * scale).min(1.0));
+                adjusted[index] = running;
+            }
+        }
+        MultipleComparisonAdjustment::BenjaminiHochberg => {
+            // Step up: the k-th smallest is scaled by m / k, kept monotone
+            let mut running: f64 = 1.0;
+            for (rank, &index) in order.iter().enumerate().rev() {
+                #[allow(clippy::cast_precision_loss, reason = "Rank casting to f64")]
+                let scale = tests / (rank + 1) as f64;
+                running = running.min(p_values[index] * scale);
+                adjusted[index] = running;
+            }
+        }
+    }
+    adjusted
+}
+
+/// Agglomerative clustering of the items of a distance matrix. Returns the
+/// merges in order and the items in dendrogram leaf order.
+fn cluster(distances: &[Vec<f64>], linkage: ClusterLinkage) -> (Vec<ClusterMerge>, Vec<usize>) {
+    let count = distances.len();
+    let mut clusters: Vec<(usize, Vec<usize>)> =
+        (0..count).map(|item| (item, vec![item])).collect();
+    let mut merges = Vec::with_capacity(count.saturating_sub(1));
+    while clusters.len() > 1 {
+        let mut closest = (0, 1, f64::INFINITY);
+        for first in 0..clusters.len() {
+            for second in first + 1..clusters.len() {
+                let distance =
+                    linkage_distance(&clusters[first].1, &clusters[second].1, distances, linkage);
+                if distance < closest.2 {
+                    closest = (first, second, distance);
+                }
+            }
+        }
+
+        let (first, second, distance) = closest;
+        let (second_id, second_members) = clusters.remove(second);
+        let (first_id, mut members) = clusters.remove(first);
+        members.extend(second_members);
+        merges.push(ClusterMerge {
+            first: first_id,
+            second: second_id,
+            distance,
+            size: members.len(),
+        });
+        clusters.push((count + merges.len() - 1, members));
+    }
+    let order = clusters
+        .pop()
+        .map(|(_, members)| members)
+        .unwrap_or_default();
+    (merges, order)
+}
+
+/// Distance between two clusters under `linkage`.
+fn linkage_distance(
+    first: &[usize],
+    second: &[usize],
+    distances: &[Vec<f64>],
+    linkage: ClusterLinkage,
+) -> f64 {
+    let pairs = first
+        .iter()
+        .flat_map(|&a| second.iter().map(move |&b| distances[a][b]));
+    match linkage {
+        ClusterLinkage::Single => pairs.fold(f64::INFINITY, f64::min),
+        ClusterLinkage::Complete => pairs.fold(f64::NEG_INFINITY, f64::max),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn column(name: &str, values: impl IntoIterator<Item = f64>) -> NamedColumn {
+        NamedColumn {
+            name: name.to_owned(),
+            values: values.into_iter().map(Some).collect(),
+        }
+    }
+
+    #[test]
+    fn test_near_duplicate_columns_cluster_first() {
+        let signal: Vec<f64> = (0..30)
+            .map(|row| (f64::from(row) * 0.7).sin().mul_add(3.0, 10.0))
+            .collect();
+        let copy: Vec<f64> = signal
+            .iter()
+            .zip(0..30)
+            .map(|(value, row)| (f64::from(row) * 1.3).cos().mul_add(0.01, *value))
+            .collect();
+        let other: Vec<f64> = (0..30)
+            .map(|row| (f64::from(row) * 2.9).cos().mul_add(2.0, 20.0))
+            .collect();
+        let mut sparse = column("sparse", [1.0, 2.0]);
+        sparse.values.push(None);
+        let columns = [
+            column("a", signal),
+            column("c", other),
+            column("b", copy),
+            sparse,
+        ];
+
+        let report = StatisticalAnalysisPipeline::analyze_columns(
+            &columns,
+            0.05,
+            MultipleComparisonAdjustment::Holm,
+            ClusterLinkage::Complete,
+        )
+        .unwrap();
+
+        assert_eq!(report.excluded_columns, ["sparse"]);
+        assert!(report.notes[0].contains("'sparse' was excluded"));
+        assert_eq!(report.columns.len(), 3);
+        assert_eq!(report.correlation_observations, 30);
+
+        let first_merge = report.merges[0];
+        assert_eq!((first_merge.first, first_merge.second), (0, 2));
+        assert_eq!(report.merges[1].size, 3);
+        assert_eq!(report.dendrogram_order.len(), 3);
+
+        let duplicates = report
+            .comparisons
+            .iter()
+            .find(|comparison| (comparison.first, comparison.second) == (0, 2))
+            .unwrap();
+        assert!(!duplicates.significant);
+        assert!(duplicates.p_value > 0.5);
+        assert_eq!(
+            report
+                .comparisons
+                .iter()
+                .filter(|comparison| comparison.significant)
+                .count(),
+            2
+        );
+
+        let matrix = &report.comparison_matrix;
+        assert_eq!(matrix[1][1], Some(1.0));
+        assert_eq!(matrix[0][2], matrix[2][0]);
+        assert_eq!(matrix[0][2], Some(duplicates.adjusted_p_value));
+    }
+
+    #[test]
+    fn test_p_value_adjustments() {
+        let p_values = [0.01, 0.04, 0.03];
+        let expected = [
+            (MultipleComparisonAdjustment::Bonferroni, [0.03, 0.12, 0.09]),
+            (MultipleComparisonAdjustment::Holm, [0.03, 0.06, 0.06]),
+            (
+                MultipleComparisonAdjustment::BenjaminiHochberg,
+                [0.03, 0.04, 0.04],
+            ),
+        ];
+        for (adjustment, values) in expected {
+            for (adjusted, value) in adjust_p_values(&p_values, adjustment).iter().zip(values) {
+                assert_relative_eq!(*adjusted, value, max_relative = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_and_complete_linkage() {
+        // Points 0, 1, 3 and 7 on a line
+        let positions: [f64; 4] = [0.0, 1.0, 3.0, 7.0];
+        let distances: Vec<Vec<f64>> = positions
+            .iter()
+            .map(|a| positions.iter().map(|b| (a - b).abs()).collect())
+            .collect();
+
+        let (single, order) = cluster(&distances, ClusterLinkage::Single);
+        let steps: Vec<(usize, usize, f64)> = single
+            .iter()
+            .map(|merge| (merge.first, merge.second, merge.distance))
+            .collect();
+        assert_eq!(steps, [(0, 1, 1.0), (2, 4, 2.0), (3, 5, 4.0)]);
+        assert_eq!(order, [3, 2, 0, 1]);
+
+        let (complete, _) = cluster(&distances, ClusterLinkage::Complete);
+        assert_relative_eq!(complete[1].distance, 3.0);
+        assert_relative_eq!(complete[2].distance, 7.0);
+    }
+
+    #[test]
+    fn test_rejects_columns_without_data() {
+        let columns = [column("x", [1.0, 2.0])];
+        assert!(matches!(
+            StatisticalAnalysisPipeline::analyze_columns(
+                &columns,
+                0.05,
+                MultipleComparisonAdjustment::Holm,
+                ClusterLinkage::Single,
+            ),
+            Err(StatisticsError::InsufficientData { needed: 3, got: 2 })
+        ));
+    }
+}
@@ -0,0 +1,257 @@
+//! Statistical power of two-sample location tests.
+//!
+//! The Mann-Whitney U test has no closed-form power, so it is estimated by
+//! simulation: both samples are drawn from the same unit-variance
+//! distribution, the second shifted by the effect size, and the share of
+//! significant tests is the power. The asymptotic relative efficiency (ARE)
+//! of the test against the t-test gives an analytical approximation for
+//! comparison: the U test behaves like a t-test on `ARE` times as many
+//! observations, with `ARE = 3 / pi` for normal data.
+use rand::{Rng, RngCore, SeedableRng};
+use rand_distr::StandardNormal;
+use rand_pcg::Pcg64;
+use rayon::prelude::*;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use super::hypothesis_testing::HypothesisTestingEngine;
+use super::types::SimulationDistribution;
+
+/// Fewest simulated sample pairs accepted by the power simulation.
+const MIN_SIMULATIONS: usize = 10;
+
+impl SimulationDistribution {
+    /// Draw with mean zero and unit variance.
+    fn sample(self, rng: &mut Pcg64) -> f64 {
+        // Strictly inside (0, 1), so the inverse CDFs stay finite.
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "53 random bits are exactly representable in f64"
+        )]
+        let uniform =
+            |source: &mut Pcg64| ((source.next_u64() >> 11) as f64 + 0.5) * f64::EPSILON / 2.0;
+        match self {
+            Self::Normal => rng.sample(StandardNormal),
+            Self::Uniform => 12.0_f64.sqrt() * (uniform(rng) - 0.5),
+            Self::Laplace => {
+                let centered = uniform(rng) - 0.5;
+                -centered.signum() * 2.0_f64.mul_add(-centered.abs(), 1.0).ln()
+                    / std::f64::consts::SQRT_2
+            }
+            Self::Logistic => {
+                let probability = uniform(rng);
+                3.0_f64.sqrt() / std::f64::consts::PI * (probability / (1.0 - probability)).ln()
+            }
+            Self::Exponential => -uniform(rng).ln() - 1.0,
+        }
+    }
+
+    /// Asymptotic relative efficiency of the Mann-Whitney U test against
+    /// the t-test for shifts of this distribution.
+    #[must_use]
+    pub const fn asymptotic_relative_efficiency(self) -> f64 {
+        match self {
+            Self::Normal => 3.0 / std::f64::consts::PI,
+            Self::Uniform => 1.0,
+            Self::Laplace => 1.5,
+            Self::Logistic => std::f64::consts::PI * std::f64::consts::PI / 9.0,
+            Self::Exponential => 3.0,
+        }
+    }
+}
+
+/// Sample size and power calculations for hypothesis tests.
+pub struct PowerAnalysisEngine;
+
+impl PowerAnalysisEngine {
+    /// Power of the two-sided two-sample t-test to detect a shift of
+    /// `effect_size` standard deviations (Cohen's d), by the normal
+    /// approximation of the noncentral t distribution.
+    ///
+    /// # Errors
+    /// Returns an error for a non-finite effect size, fewer than two
+    /// observations per group or `alpha` outside `(0, 1)`.
+    pub fn two_sample_t_test_power(
+        effect_size: f64,
+        n1: usize,
+        n2: usize,
+        alpha: f64,
+    ) -> Result<f64, String> {
+        validate_design(effect_size, n1, n2, alpha)?;
+        normal_power(effect_size, n1, n2, alpha, 1.0)
+    }
+
+    /// Power of the two-sided Mann-Whitney U test at level `alpha` to detect
+    /// a shift of `effect_size` standard deviations between samples of
+    /// `distribution`, estimated from `n_simulations` sample pairs.
+    ///
+    /// # Errors
+    /// Returns an error for a non-finite effect size, fewer than two
+    /// observations per group, `alpha` outside `(0, 1)` or fewer than ten
+    /// simulations.
+    pub fn mann_whitney_u_power(
+        effect_size: f64,
+        n1: usize,
+        n2: usize,
+        alpha: f64,
+        n_simulations: usize,
+        seed: u64,
+        distribution: SimulationDistribution,
+    ) -> Result<f64, String> {
+        validate_design(effect_size, n1, n2, alpha)?;
+        if n_simulations < MIN_SIMULATIONS {
+            return Err(format!(
+                "At least {MIN_SIMULATIONS} simulations are required, got {n_simulations}"
+            ));
+        }
+
+        let mut seeder = Pcg64::seed_from_u64(seed);
+        let seeds: Vec<u64> = (0..n_simulations).map(|_| seeder.next_u64()).collect();
+        let significant = seeds
+            .into_par_iter()
+            .filter(|&simulation_seed| {
+                let mut rng = Pcg64::seed_from_u64(simulation_seed);
+                let first: Vec<f64> = (0..n1).map(|_| distribution.sample(&mut rng)).collect();
+                let second: Vec<f64> = (0..n2)
+                    .map(|_| distribution.sample(&mut rng) + effect_size)
+                    .collect();
+                // Continuous samples are never all tied.
+                HypothesisTestingEngine::mann_whitney_u(&first, &second)
+                    .is_ok_and(|result| result.p_value < alpha)
+            })
+            .count();
+
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Simulation counts casting to f64 for the rejection rate"
+        )]
+        Ok(significant as f64 / n_simulations as f64)
+    }
+
+    /// Analytical approximation of [`PowerAnalysisEngine::mann_whitney_u_power`]:
+    /// the t-test power with both sample sizes scaled by the asymptotic
+    /// relative efficiency of `distribution`.
+    ///
+    /// # Errors
+    /// Returns an error for a non-finite effect size, fewer than two
+    /// observations per group or `alpha` outside `(0, 1)`.
+    pub fn mann_whitney_u_power_approximation(
+        effect_size: f64,
+        n1: usize,
+        n2: usize,
+        alpha: f64,
+        distribution: SimulationDistribution,
+    ) -> Result<f64, String> {
+        validate_design(effect_size, n1, n2, alpha)?;
+        normal_power(
+            effect_size,
+            n1,
+            n2,
+            alpha,
+            distribution.asymptotic_relative_efficiency(),
+        )
+    }
+}
+
+/// Check the effect size, group sizes and significance level.
+fn validate_design(effect_size: f64, n1: usize, n2: usize, alpha: f64) -> Result<(), String> {
+    if !effect_size.is_finite() {
+        return Err(format!("Effect size must be finite, got {effect_size}"));
+    }
+    if n1 < 2 || n2 < 2 {
+        return Err(format!(
+            "Each group needs at least two observations, got {n1} and {n2}"
+        ));
+    }
+    if !(alpha > 0.0 && alpha < 1.0) {
+        return Err(format!("Alpha must be in (0, 1), got {alpha}"));
+    }
+    Ok(())
+}
+
+/// Two-sided power `Phi(delta - z) + Phi(-delta - z)` of a z-test with
+/// noncentrality `delta = d * sqrt(efficiency * n1 * n2 / (n1 + n2))`.
+fn normal_power(
+    effect_size: f64,
+    n1: usize,
+    n2: usize,
+    alpha: f64,
+    efficiency: f64,
+) -> Result<f64, String> {
+    let standard_normal = Normal::new(0.0, 1.0).map_err(|error| error.to_string())?;
+    #[allow(clippy::cast_precision_loss, reason = "Group sizes casting to f64")]
+    let (n1, n2) = (n1 as f64, n2 as f64);
+    let noncentrality = effect_size.abs() * (efficiency * n1 * n2 / (n1 + n2)).sqrt();
+    let critical = standard_normal.inverse_cdf(1.0 - alpha / 2.0);
+    Ok(standard_normal.cdf(noncentrality - critical)
+        + standard_normal.cdf(-noncentrality - critical))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn test_mann_whitney_power_matches_are_approximation() {
+        let simulated = PowerAnalysisEngine::mann_whitney_u_power(
+            1.0,
+            20,
+            20,
+            0.05,
+            2000,
+            7,
+            SimulationDistribution::Normal,
+        )
+        .unwrap();
+        let approximate = PowerAnalysisEngine::mann_whitney_u_power_approximation(
+            1.0,
+            20,
+            20,
+            0.05,
+            SimulationDistribution::Normal,
+        )
+        .unwrap();
+        assert_abs_diff_eq!(simulated, approximate, epsilon = 0.05);
+        let t_test = PowerAnalysisEngine::two_sample_t_test_power(1.0, 20, 20, 0.05).unwrap();
+        assert!(approximate < t_test);
+    }
+
+    #[test]
+    fn test_mann_whitney_power_without_effect_is_alpha() {
+        let power = PowerAnalysisEngine::mann_whitney_u_power(
+            0.0,
+            15,
+            25,
+            0.05,
+            2000,
+            11,
+            SimulationDistribution::Exponential,
+        )
+        .unwrap();
+        assert_abs_diff_eq!(power, 0.05, epsilon = 0.02);
+    }
+
+    #[test]
+    fn test_heavy_tails_favour_mann_whitney() {
+        let power = |distribution| {
+            PowerAnalysisEngine::mann_whitney_u_power(0.5, 30, 30, 0.05, 2000, 3, distribution)
+                .unwrap()
+        };
+        assert!(
+            power(SimulationDistribution::Laplace) > power(SimulationDistribution::Normal) + 0.1
+        );
+        assert!(
+            PowerAnalysisEngine::mann_whitney_u_power(
+                0.5,
+                30,
+                30,
+                0.05,
+                5,
+                3,
+                SimulationDistribution::Normal
+            )
+            .is_err()
+        );
+    }
+}
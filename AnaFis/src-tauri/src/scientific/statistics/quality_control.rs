@@ -0,0 +1,983 @@
+//! Process capability analysis.
+//!
+//! Normal-theory indices misstate capability for skewed processes such as
+//! failure times or material strengths. The non-normal analysis fits a
+//! distribution by maximum likelihood and replaces `mean -/+ 3 sigma` with its
+//! 0.135 % and 99.865 % quantiles (Clements' percentile method).
+//!
+//! Tabular CUSUM charts accumulate deviations beyond a reference value `k` and
+//! signal once either sum passes the decision interval `h`, catching small
+//! sustained shifts long before Shewhart limits do. The V-mask form applies
+//! the same test to the plain cumulative sum. EWMA charts smooth the series
+//! instead; their in-control average run length comes from the Markov chain
+//! approximation of Lucas and Saccucci (1990).
+//!
+//! Gage R&R studies split measurement variation of a crossed
+//! part x operator x replicate design into repeatability, reproducibility
+//! and part-to-part components with a two-way random-effects ANOVA, following
+//! the AIAG MSA manual.
+use nalgebra::{DMatrix, DVector};
+use statrs::distribution::{ContinuousCDF, FisherSnedecor, Normal};
+
+use crate::scientific::distributions::{
+    DistributionFamily, DistributionFitter, FittedDistribution,
+};
+
+use super::types::{
+    AnovaRow, CapabilityDistribution, CusumChartResult, EwmaChartResult, GageRrAssessment,
+    GageRrResult, NonnormalCapabilityResult, VMaskResult, VarianceComponent,
+};
+
+/// Tail probability beyond three standard deviations of a normal distribution.
+const THREE_SIGMA_TAIL: f64 = 0.001_35;
+/// Parts per million in a probability of one.
+const PPM: f64 = 1e6;
+/// Control chart constant `d2` for moving ranges of two observations.
+const D2_MOVING_RANGE: f64 = 1.128;
+/// Recommended reference value, in process standard deviations.
+const CUSUM_K_SIGMAS: f64 = 0.5;
+/// Recommended decision interval, in process standard deviations.
+const CUSUM_H_SIGMAS: f64 = 5.0;
+/// Default EWMA limit width, in standard deviations of the EWMA.
+const EWMA_DEFAULT_L_SIGMA: f64 = 3.0;
+/// Smallest lambda considered by [`optimize_ewma_lambda`].
+const EWMA_MIN_LAMBDA: f64 = 0.01;
+/// Bisection steps of [`optimize_ewma_lambda`].
+const EWMA_LAMBDA_ITERATIONS: usize = 50;
+/// States of the Markov chain approximating the EWMA run length; odd so one
+/// state is centred on zero.
+const ARL_STATES: usize = 101;
+/// Interaction p-value from which the part x operator term is pooled.
+const GAGE_POOLING_P_VALUE: f64 = 0.25;
+/// Study variation spans six standard deviations.
+const STUDY_VARIATION_SIGMAS: f64 = 6.0;
+/// Factor `sqrt(2)`, rounded as in the MSA manual, of the number of distinct
+/// categories.
+const NDC_FACTOR: f64 = 1.41;
+/// Largest %GRR of an acceptable measurement system.
+const GRR_ACCEPTABLE_PERCENT: f64 = 10.0;
+/// Largest %GRR of a conditionally acceptable measurement system.
+const GRR_CONDITIONAL_PERCENT: f64 = 30.0;
+
+impl CapabilityDistribution {
+    const fn family(self) -> DistributionFamily {
+        match self {
+            Self::Weibull => DistributionFamily::Weibull,
+            Self::LogNormal => DistributionFamily::LogNormal,
+            Self::Gamma => DistributionFamily::Gamma,
+        }
+    }
+}
+
+/// Statistical process control and capability analysis.
+pub struct QualityControlEngine;
+
+impl GageRrAssessment {
+    /// AIAG verdict for a %GRR: below 10 % acceptable, up to 30 % conditional.
+    fn from_percent_grr(percent_grr: f64) -> Self {
+        if percent_grr < GRR_ACCEPTABLE_PERCENT {
+            Self::Acceptable
+        } else if percent_grr <= GRR_CONDITIONAL_PERCENT {
+            Self::Conditional
+        } else {
+            Self::Unacceptable
+        }
+    }
+}
+
+impl QualityControlEngine {
+    /// Capability of a process following `distribution`, with specification
+    /// limits `lsl < usl`.
+    ///
+    /// # Errors
+    /// Returns an error for invalid limits, non-positive data, too few or
+    /// constant observations, or a fit that fails to converge.
+    pub fn analyze_process_nonnormal(
+        data: &[f64],
+        lsl: f64,
+        usl: f64,
+        distribution: CapabilityDistribution,
+    ) -> Result<NonnormalCapabilityResult, String> {
+        if !(lsl.is_finite() && usl.is_finite() && lsl < usl) {
+            return Err(format!(
+                "Specification limits must be finite with LSL < USL, got [{lsl}, {usl}]"
+            ));
+        }
+        let family = distribution.family();
+        if data.iter().any(|&value| value <= 0.0) {
+            return Err(format!(
+                "{} capability requires strictly positive data",
+                family.label()
+            ));
+        }
+
+        let fit = DistributionFitter::fit_all(data, &[family])?
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("{} fit did not converge", family.label()))?;
+        let values: Vec<f64> = fit.parameters.iter().map(|p| p.value).collect();
+        let fitted = FittedDistribution::from_parameters(family, &values)
+            .ok_or_else(|| format!("{} fit produced invalid parameters", family.label()))?;
+
+        let lower_quantile = fitted.quantile(THREE_SIGMA_TAIL);
+        let median = fitted.quantile(0.5);
+        let upper_quantile = fitted.quantile(1.0 - THREE_SIGMA_TAIL);
+        let cnp = (usl - lsl) / (upper_quantile - lower_quantile);
+        let cnpk = ((usl - median) / (upper_quantile - median))
+            .min((median - lsl) / (median - lower_quantile));
+
+        let ppm_below_lsl = fitted.cdf(lsl) * PPM;
+        let ppm_above_usl = (1.0 - fitted.cdf(usl)) * PPM;
+        Ok(NonnormalCapabilityResult {
+            distribution,
+            parameters: fit.parameters,
+            lower_quantile,
+            median,
+            upper_quantile,
+            cnp,
+            cnpk,
+            ppm_below_lsl,
+            ppm_above_usl,
+            ppm_total: ppm_below_lsl + ppm_above_usl,
+        })
+    }
+
+    /// Tabular CUSUM of `data` around `target` with reference value `k` and
+    /// decision interval `h`, both in data units.
+    ///
+    /// # Errors
+    /// Returns an error for fewer than two observations, non-finite values,
+    /// a negative `k` or a non-positive `h`.
+    pub fn cusum_chart(
+        data: &[f64],
+        target: f64,
+        k: f64,
+        h: f64,
+    ) -> Result<CusumChartResult, String> {
+        cusum(data, target, k, h, false)
+    }
+
+    /// [`Self::cusum_chart`] with the equivalent V-mask chart alongside.
+    ///
+    /// # Errors
+    /// As for [`Self::cusum_chart`].
+    pub fn cusum_chart_with_v_mask(
+        data: &[f64],
+        target: f64,
+        k: f64,
+        h: f64,
+    ) -> Result<CusumChartResult, String> {
+        cusum(data, target, k, h, true)
+    }
+
+    /// EWMA chart of `data` with smoothing weight `lambda` and limits
+    /// `l_sigma` (default 3) EWMA standard deviations from the mean.
+    ///
+    /// # Errors
+    /// Returns an error for fewer than two or non-finite observations, data
+    /// without variation, `lambda` outside `(0, 1]` or a non-positive
+    /// `l_sigma`.
+    pub fn ewma_chart(
+        data: &[f64],
+        lambda: f64,
+        l_sigma: Option<f64>,
+    ) -> Result<EwmaChartResult, String> {
+        if data.len() < 2 {
+            return Err(format!(
+                "EWMA needs at least 2 observations, got {}",
+                data.len()
+            ));
+        }
+        if data.iter().any(|value| !value.is_finite()) {
+            return Err("EWMA data must be finite".to_owned());
+        }
+        if !(lambda > 0.0 && lambda <= 1.0) {
+            return Err(format!("Lambda must lie in (0, 1], got {lambda}"));
+        }
+        let l_sigma = l_sigma.unwrap_or(EWMA_DEFAULT_L_SIGMA);
+        if !(l_sigma.is_finite() && l_sigma > 0.0) {
+            return Err(format!("Limit width must be positive, got {l_sigma}"));
+        }
+        let sigma_estimate = moving_range_sigma(data);
+        if sigma_estimate <= 0.0 {
+            return Err("EWMA data have no variation".to_owned());
+        }
+
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Observation count casting to f64"
+        )]
+        let center_line = data.iter().sum::<f64>() / data.len() as f64;
+        let decay = 1.0 - lambda;
+        let steady_variance = lambda / (2.0 - lambda);
+
+        let mut ewma = Vec::with_capacity(data.len());
+        let mut upper_limits = Vec::with_capacity(data.len());
+        let mut lower_limits = Vec::with_capacity(data.len());
+        let mut signal_indices = Vec::new();
+        let mut z = center_line;
+        // (1 - lambda)^(2i), updated as i grows.
+        let mut startup = 1.0;
+        for (index, &value) in data.iter().enumerate() {
+            z = lambda.mul_add(value, decay * z);
+            startup *= decay * decay;
+            let half_width = l_sigma * sigma_estimate * (steady_variance * (1.0 - startup)).sqrt();
+            if (z - center_line).abs() > half_width {
+                signal_indices.push(index);
+            }
+            ewma.push(z);
+            upper_limits.push(center_line + half_width);
+            lower_limits.push(center_line - half_width);
+        }
+
+        Ok(EwmaChartResult {
+            ewma,
+            center_line,
+            upper_limits,
+            lower_limits,
+            signal_indices,
+            sigma_estimate,
+            lambda,
+            l_sigma,
+            steady_state_arl: ewma_arl(lambda, l_sigma),
+        })
+    }
+
+    /// Crossed gage R&R study of `measurements[part][operator][replicate]`
+    /// by the ANOVA method.
+    ///
+    /// The part x operator interaction is tested against repeatability and
+    /// pooled into it when its p-value is at least 0.25. `usl_lsl_range`, the
+    /// tolerance width, adds percentages of tolerance and then decides the
+    /// assessment; otherwise the %GRR of total variation does.
+    ///
+    /// # Errors
+    /// Returns an error for fewer than two parts, operators or replicates,
+    /// measurements not matching the stated dimensions, non-finite values, a
+    /// non-positive tolerance or measurements without any variation.
+    pub fn gage_rr(
+        measurements: &[Vec<Vec<f64>>],
+        n_parts: usize,
+        n_operators: usize,
+        n_replicates: usize,
+        usl_lsl_range: Option<f64>,
+    ) -> Result<GageRrResult, String> {
+        validate_gage_design(measurements, n_parts, n_operators, n_replicates)?;
+        if let Some(tolerance) = usl_lsl_range
+            && !(tolerance.is_finite() && tolerance > 0.0)
+        {
+            return Err(format!("Tolerance must be positive, got {tolerance}"));
+        }
+
+        let sums = GageSums::new(measurements);
+        if sums.total <= 0.0 {
+            return Err("Measurements have no variation".to_owned());
+        }
+        let anova = sums.anova(n_parts, n_operators, n_replicates);
+        #[allow(clippy::cast_precision_loss, reason = "Design sizes casting to f64")]
+        let (parts, operators, replicates) =
+            (n_parts as f64, n_operators as f64, n_replicates as f64);
+
+        let interaction = if anova.interaction_pooled {
+            0.0
+        } else {
+            ((anova.interaction - anova.error) / replicates).max(0.0)
+        };
+        let operator = ((anova.operator - anova.against) / (parts * replicates)).max(0.0);
+        let part = ((anova.part - anova.against) / (operators * replicates)).max(0.0);
+        let reproducibility = operator + interaction;
+        let gage = anova.residual + reproducibility;
+
+        let total = gage + part;
+        let variances = [
+            Some(("Total Gage R&R", gage)),
+            Some(("Repeatability", anova.residual)),
+            Some(("Reproducibility", reproducibility)),
+            Some(("Operator", operator)),
+            (!anova.interaction_pooled).then_some(("Part x Operator", interaction)),
+            Some(("Part-to-Part", part)),
+            Some(("Total Variation", total)),
+        ];
+        let variance_components: Vec<VarianceComponent> = variances
+            .into_iter()
+            .flatten()
+            .map(|(source, variance)| variance_component(source, variance, total, usl_lsl_range))
+            .collect();
+
+        let percent_grr_total_variation = variance_components[0].percent_study_variation;
+        let percent_grr_tolerance = variance_components[0].percent_tolerance;
+        let assessment = GageRrAssessment::from_percent_grr(
+            percent_grr_tolerance.unwrap_or(percent_grr_total_variation),
+        );
+        Ok(GageRrResult {
+            anova: anova.rows,
+            interaction_pooled: anova.interaction_pooled,
+            variance_components,
+            percent_grr_total_variation,
+            percent_grr_tolerance,
+            ndc: distinct_categories(part, gage),
+            assessment,
+        })
+    }
+}
+
+/// Lambda whose in-control ARL with 3-sigma limits is `target_arl`.
+///
+/// The ARL falls as lambda grows, from very long runs near zero to the
+/// Shewhart value of about 370 at lambda = 1, so targets outside that range
+/// are clamped to lambda = 1 or the smallest lambda considered (0.01).
+#[must_use]
+pub fn optimize_ewma_lambda(target_arl: f64) -> f64 {
+    let arl = |lambda: f64| ewma_arl(lambda, EWMA_DEFAULT_L_SIGMA);
+    if target_arl.is_nan() || target_arl <= arl(1.0) {
+        return 1.0;
+    }
+    if target_arl >= arl(EWMA_MIN_LAMBDA) {
+        return EWMA_MIN_LAMBDA;
+    }
+    let (mut low, mut high) = (EWMA_MIN_LAMBDA, 1.0);
+    for _ in 0..EWMA_LAMBDA_ITERATIONS {
+        let middle = 0.5 * (low + high);
+        if arl(middle) > target_arl {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    0.5 * (low + high)
+}
+
+/// In-control average run length of an EWMA chart with steady-state limits.
+///
+/// The standardized EWMA is discretized into [`ARL_STATES`] cells between
+/// the limits; with `P` the transitions between in-control cells, the run
+/// lengths from every cell solve `(I - P) arl = 1`.
+fn ewma_arl(lambda: f64, l_sigma: f64) -> f64 {
+    let limit = l_sigma * (lambda / (2.0 - lambda)).sqrt();
+    #[allow(clippy::cast_precision_loss, reason = "Small state count")]
+    let width = 2.0 * limit / ARL_STATES as f64;
+    #[allow(clippy::cast_precision_loss, reason = "Small state index")]
+    let centre = |state: usize| (state as f64 + 0.5).mul_add(width, -limit);
+    let normal = Normal::standard();
+    let decay = 1.0 - lambda;
+
+    let transitions = DMatrix::from_fn(ARL_STATES, ARL_STATES, |from, to| {
+        let carried = decay * centre(from);
+        let upper = (0.5_f64.mul_add(width, centre(to)) - carried) / lambda;
+        let lower = (0.5_f64.mul_add(-width, centre(to)) - carried) / lambda;
+        normal.cdf(upper) - normal.cdf(lower)
+    });
+    let system = DMatrix::identity(ARL_STATES, ARL_STATES) - transitions;
+    #[allow(clippy::integer_division, reason = "Middle state of an odd count")]
+    let middle = ARL_STATES / 2;
+    system
+        .lu()
+        .solve(&DVector::from_element(ARL_STATES, 1.0))
+        .map_or(f64::INFINITY, |run_lengths| run_lengths[middle])
+}
+
+/// Sums of squares of a balanced crossed gage study.
+struct GageSums {
+    part: f64,
+    operator: f64,
+    interaction: f64,
+    error: f64,
+    total: f64,
+}
+
+impl GageSums {
+    /// Sums of squares of validated `measurements[part][operator][replicate]`.
+    fn new(measurements: &[Vec<Vec<f64>>]) -> Self {
+        let (n_parts, n_operators) = (measurements.len(), measurements[0].len());
+        #[allow(clippy::cast_precision_loss, reason = "Design sizes casting to f64")]
+        let (parts, operators, replicates) = (
+            n_parts as f64,
+            n_operators as f64,
+            measurements[0][0].len() as f64,
+        );
+        let cell_means: Vec<Vec<f64>> = measurements
+            .iter()
+            .map(|part| {
+                part.iter()
+                    .map(|cell| cell.iter().sum::<f64>() / replicates)
+                    .collect()
+            })
+            .collect();
+        let part_means: Vec<f64> = cell_means
+            .iter()
+            .map(|part| part.iter().sum::<f64>() / operators)
+            .collect();
+        let operator_means: Vec<f64> = (0..n_operators)
+            .map(|operator| cell_means.iter().map(|part| part[operator]).sum::<f64>() / parts)
+            .collect();
+        let grand_mean = part_means.iter().sum::<f64>() / parts;
+
+        let squares_about = |values: &[f64], mean: f64| {
+            values
+                .iter()
+                .map(|value| (value - mean).powi(2))
+                .sum::<f64>()
+        };
+        let ss_part = operators * replicates * squares_about(&part_means, grand_mean);
+        let ss_operator = parts * replicates * squares_about(&operator_means, grand_mean);
+        let mut ss_error = 0.0;
+        let mut ss_total = 0.0;
+        for (part, means) in measurements.iter().zip(&cell_means) {
+            for (cell, &mean) in part.iter().zip(means) {
+                ss_error += squares_about(cell, mean);
+                ss_total += squares_about(cell, grand_mean);
+            }
+        }
+        Self {
+            part: ss_part,
+            operator: ss_operator,
+            interaction: (ss_total - ss_part - ss_operator - ss_error).max(0.0),
+            error: ss_error,
+            total: ss_total,
+        }
+    }
+
+    /// ANOVA table of a `n_parts x n_operators x n_replicates` design,
+    /// pooling a negligible interaction into repeatability.
+    fn anova(&self, n_parts: usize, n_operators: usize, n_replicates: usize) -> GageAnova {
+        let df_part = n_parts - 1;
+        let df_operator = n_operators - 1;
+        let df_interaction = df_part * df_operator;
+        let df_error = n_parts * n_operators * (n_replicates - 1);
+        let ms_part = mean_square(self.part, df_part);
+        let ms_operator = mean_square(self.operator, df_operator);
+        let ms_interaction = mean_square(self.interaction, df_interaction);
+        let ms_error = mean_square(self.error, df_error);
+
+        let interaction_test = f_test(ms_interaction, df_interaction, ms_error, df_error);
+        let interaction_pooled = interaction_test
+            .1
+            .is_some_and(|p| p >= GAGE_POOLING_P_VALUE);
+        // Part and operator effects are tested against the interaction, or
+        // against the pooled error once the interaction is dropped.
+        let (ss_residual, df_residual) = if interaction_pooled {
+            (self.interaction + self.error, df_interaction + df_error)
+        } else {
+            (self.error, df_error)
+        };
+        let ms_residual = mean_square(ss_residual, df_residual);
+        let (ms_against, df_against) = if interaction_pooled {
+            (ms_residual, df_residual)
+        } else {
+            (ms_interaction, df_interaction)
+        };
+
+        let df_total = n_parts * n_operators * n_replicates - 1;
+        let rows = [
+            Some(anova_row(
+                "Part",
+                df_part,
+                self.part,
+                f_test(ms_part, df_part, ms_against, df_against),
+            )),
+            Some(anova_row(
+                "Operator",
+                df_operator,
+                self.operator,
+                f_test(ms_operator, df_operator, ms_against, df_against),
+            )),
+            (!interaction_pooled).then(|| {
+                anova_row(
+                    "Part x Operator",
+                    df_interaction,
+                    self.interaction,
+                    interaction_test,
+                )
+            }),
+            Some(anova_row(
+                "Repeatability",
+                df_residual,
+                ss_residual,
+                (None, None),
+            )),
+            Some(AnovaRow {
+                mean_square: None,
+                ..anova_row("Total", df_total, self.total, (None, None))
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        GageAnova {
+            rows,
+            interaction_pooled,
+            part: ms_part,
+            operator: ms_operator,
+            interaction: ms_interaction,
+            error: ms_error,
+            residual: ms_residual,
+            against: ms_against,
+        }
+    }
+}
+
+/// ANOVA table and mean squares of a gage study.
+struct GageAnova {
+    rows: Vec<AnovaRow>,
+    interaction_pooled: bool,
+    part: f64,
+    operator: f64,
+    interaction: f64,
+    error: f64,
+    /// Repeatability, including a pooled interaction.
+    residual: f64,
+    /// Denominator of the part and operator F tests.
+    against: f64,
+}
+
+fn validate_gage_design(
+    measurements: &[Vec<Vec<f64>>],
+    n_parts: usize,
+    n_operators: usize,
+    n_replicates: usize,
+) -> Result<(), String> {
+    if n_parts < 2 || n_operators < 2 || n_replicates < 2 {
+        return Err(format!(
+            "Gage R&R needs at least 2 parts, operators and replicates, got {n_parts}, {n_operators} and {n_replicates}"
+        ));
+    }
+    if measurements.len() != n_parts {
+        return Err(format!(
+            "Expected measurements for {n_parts} parts, got {}",
+            measurements.len()
+        ));
+    }
+    for (part, operators) in measurements.iter().enumerate() {
+        if operators.len() != n_operators {
+            return Err(format!(
+                "Part {part} has {} operators, expected {n_operators}",
+                operators.len()
+            ));
+        }
+        for (operator, replicates) in operators.iter().enumerate() {
+            if replicates.len() != n_replicates {
+                return Err(format!(
+                    "Part {part}, operator {operator} has {} replicates, expected {n_replicates}",
+                    replicates.len()
+                ));
+            }
+            if replicates.iter().any(|value| !value.is_finite()) {
+                return Err(format!(
+                    "Part {part}, operator {operator} has non-finite measurements"
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn variance_component(
+    source: &str,
+    variance: f64,
+    total: f64,
+    tolerance: Option<f64>,
+) -> VarianceComponent {
+    let study_variation = STUDY_VARIATION_SIGMAS * variance.sqrt();
+    VarianceComponent {
+        source: source.to_owned(),
+        variance,
+        percent_contribution: 100.0 * variance / total,
+        study_variation,
+        percent_study_variation: 100.0 * (variance / total).sqrt(),
+        percent_tolerance: tolerance.map(|tolerance| 100.0 * study_variation / tolerance),
+    }
+}
+
+/// ANOVA row with the mean square of `sum_of_squares` and the F statistic
+/// and p-value of `test`.
+fn anova_row(
+    source: &str,
+    degrees_of_freedom: usize,
+    sum_of_squares: f64,
+    (f_statistic, p_value): (Option<f64>, Option<f64>),
+) -> AnovaRow {
+    AnovaRow {
+        source: source.to_owned(),
+        degrees_of_freedom,
+        sum_of_squares,
+        mean_square: Some(mean_square(sum_of_squares, degrees_of_freedom)),
+        f_statistic,
+        p_value,
+    }
+}
+
+/// Number of distinct categories `floor(1.41 sigma_part / sigma_grr)`; a
+/// gauge without measurement error saturates rather than dividing by zero.
+fn distinct_categories(part: f64, gage: f64) -> usize {
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "Floored non-negative category count; saturating cast"
+    )]
+    let ndc = (NDC_FACTOR * (part / gage).sqrt()).floor() as usize;
+    ndc
+}
+
+fn mean_square(sum_of_squares: f64, degrees_of_freedom: usize) -> f64 {
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Degrees of freedom casting to f64"
+    )]
+    let divisor = degrees_of_freedom as f64;
+    sum_of_squares / divisor
+}
+
+/// F statistic and upper-tail p-value of `numerator / denominator`, or
+/// `None` when the denominator mean square vanishes.
+fn f_test(
+    numerator: f64,
+    df_numerator: usize,
+    denominator: f64,
+    df_denominator: usize,
+) -> (Option<f64>, Option<f64>) {
+    if denominator <= 0.0 {
+        return (None, None);
+    }
+    let statistic = numerator / denominator;
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Degrees of freedom casting to f64"
+    )]
+    let p_value = FisherSnedecor::new(df_numerator as f64, df_denominator as f64)
+        .ok()
+        .map(|distribution| distribution.sf(statistic));
+    (Some(statistic), p_value)
+}
+
+/// Process standard deviation from the mean moving range of two.
+fn moving_range_sigma(data: &[f64]) -> f64 {
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Observation count casting to f64"
+    )]
+    let ranges = (data.len() - 1) as f64;
+    let mean_moving_range = data
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).abs())
+        .sum::<f64>()
+        / ranges;
+    mean_moving_range / D2_MOVING_RANGE
+}
+
+fn cusum(
+    data: &[f64],
+    target: f64,
+    k: f64,
+    h: f64,
+    with_v_mask: bool,
+) -> Result<CusumChartResult, String> {
+    if data.len() < 2 {
+        return Err(format!(
+            "CUSUM needs at least 2 observations, got {}",
+            data.len()
+        ));
+    }
+    if !target.is_finite() || data.iter().any(|value| !value.is_finite()) {
+        return Err("CUSUM data and target must be finite".to_owned());
+    }
+    if !(k.is_finite() && k >= 0.0) {
+        return Err(format!("Reference value k must be non-negative, got {k}"));
+    }
+    if !(h.is_finite() && h > 0.0) {
+        return Err(format!("Decision interval h must be positive, got {h}"));
+    }
+
+    let mut upper = Vec::with_capacity(data.len());
+    let mut lower = Vec::with_capacity(data.len());
+    let mut signal_indices = Vec::new();
+    let (mut high, mut low) = (0.0_f64, 0.0_f64);
+    for (index, &value) in data.iter().enumerate() {
+        high = (high + value - target - k).max(0.0);
+        low = (low + value - target + k).min(0.0);
+        if high > h || low < -h {
+            signal_indices.push(index);
+        }
+        upper.push(high);
+        lower.push(low);
+    }
+
+    let sigma_estimate = moving_range_sigma(data);
+    Ok(CusumChartResult {
+        upper,
+        lower,
+        signal_indices,
+        sigma_estimate,
+        recommended_k: CUSUM_K_SIGMAS * sigma_estimate,
+        recommended_h: CUSUM_H_SIGMAS * sigma_estimate,
+        v_mask: with_v_mask.then(|| v_mask(data, target, k, h)),
+    })
+}
+
+/// V-mask with its vertex `h / k` ahead of each point and arms of slope `k`.
+///
+/// An earlier point `j` (or the origin) lies below the lower arm at `i` when
+/// `(S_i - k i) - (S_j - k j) > h`, and above the upper arm when
+/// `(S_j + k j) - (S_i + k i) > h`, so running extremes of the tilted sums
+/// test every earlier point at once.
+fn v_mask(data: &[f64], target: f64, k: f64, h: f64) -> VMaskResult {
+    let mut cumulative_sum = Vec::with_capacity(data.len());
+    let mut signal_indices = Vec::new();
+    let mut sum = 0.0;
+    // The origin sits one step before the first observation.
+    let (mut lowest_falling, mut highest_rising) = (k, -k);
+    for (index, &value) in data.iter().enumerate() {
+        sum += value - target;
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Observation index casting to f64"
+        )]
+        let position = index as f64;
+        let falling = k.mul_add(-position, sum);
+        let rising = k.mul_add(position, sum);
+        if falling - lowest_falling > h || highest_rising - rising > h {
+            signal_indices.push(index);
+        }
+        lowest_falling = lowest_falling.min(falling);
+        highest_rising = highest_rising.max(rising);
+        cumulative_sum.push(sum);
+    }
+    VMaskResult {
+        cumulative_sum,
+        lead_distance: if k > 0.0 { h / k } else { f64::INFINITY },
+        slope: k,
+        signal_indices,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    /// Weibull quantiles at evenly spaced plotting positions.
+    fn weibull_sample(shape: f64, scale: f64, count: usize) -> Vec<f64> {
+        (0..count)
+            .map(|i| {
+                #[allow(clippy::cast_precision_loss, reason = "Small sample indices")]
+                let p = (i as f64 + 0.5) / count as f64;
+                scale * (-(1.0 - p).ln()).powf(1.0 / shape)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_weibull_capability_uses_fitted_quantiles() {
+        let (shape, scale) = (1.5, 10.0);
+        let data = weibull_sample(shape, scale, 2000);
+        let quantile = |p: f64| scale * (-(1.0_f64 - p).ln()).powf(1.0 / shape);
+        let (lsl, usl) = (0.05, 30.0);
+
+        let result = QualityControlEngine::analyze_process_nonnormal(
+            &data,
+            lsl,
+            usl,
+            CapabilityDistribution::Weibull,
+        )
+        .unwrap();
+        let expected_cnp = (usl - lsl) / (quantile(0.998_65) - quantile(0.001_35));
+        assert!((result.cnp - expected_cnp).abs() < 0.02 * expected_cnp);
+        assert!((result.median - quantile(0.5)).abs() < 0.2);
+        // The long right tail makes the upper side the limiting one.
+        let upper_index = (usl - result.median) / (result.upper_quantile - result.median);
+        assert!((result.cnpk - upper_index).abs() < 1e-12);
+        assert!(result.cnpk < result.cnp);
+        let expected_ppm = (-(usl / scale).powf(shape)).exp() * 1e6;
+        assert!((result.ppm_above_usl - expected_ppm).abs() < 0.3 * expected_ppm);
+    }
+
+    #[test]
+    fn test_rejects_invalid_limits_and_non_positive_data() {
+        let data = weibull_sample(2.0, 1.0, 50);
+        for distribution in [
+            CapabilityDistribution::LogNormal,
+            CapabilityDistribution::Gamma,
+        ] {
+            assert!(
+                QualityControlEngine::analyze_process_nonnormal(&data, 0.1, 3.0, distribution)
+                    .unwrap()
+                    .cnp
+                    > 0.0
+            );
+        }
+        assert!(
+            QualityControlEngine::analyze_process_nonnormal(
+                &data,
+                2.0,
+                1.0,
+                CapabilityDistribution::Gamma
+            )
+            .is_err()
+        );
+        assert!(
+            QualityControlEngine::analyze_process_nonnormal(
+                &[-1.0, 2.0, 3.0, 4.0, 5.0],
+                0.0,
+                6.0,
+                CapabilityDistribution::LogNormal
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cusum_detects_small_sustained_shift() {
+        // Alternating noise of +/-0.3 around 10, then a one-unit upward shift.
+        let data: Vec<f64> = (0..40)
+            .map(|i| {
+                let noise = if i % 2 == 0 { 0.3 } else { -0.3 };
+                let shift = if i >= 20 { 1.0 } else { 0.0 };
+                10.0 + noise + shift
+            })
+            .collect();
+        let result = QualityControlEngine::cusum_chart_with_v_mask(&data, 10.0, 0.5, 3.9).unwrap();
+
+        assert!(result.upper[..20].iter().all(|&c| c < 0.5));
+        assert!(result.lower.iter().all(|&c| c <= 0.0));
+        // After the shift C+ climbs 0.8, 1.0, 1.8, 2.0, ... and first exceeds
+        // h = 3.9 at the eighth shifted observation.
+        assert_eq!(result.signal_indices.first(), Some(&27));
+        assert!((result.upper[39] - 10.0).abs() < 0.31);
+
+        let v_mask = result.v_mask.unwrap();
+        assert_eq!(v_mask.signal_indices, result.signal_indices);
+        assert!((v_mask.cumulative_sum[39] - 20.0).abs() < 1e-9);
+        assert!((v_mask.lead_distance - 7.8).abs() < 1e-12);
+
+        let sigma = result.sigma_estimate;
+        assert!(CUSUM_H_SIGMAS.mul_add(-sigma, result.recommended_h).abs() < 1e-12);
+        assert!(CUSUM_K_SIGMAS.mul_add(-sigma, result.recommended_k).abs() < 1e-12);
+        assert!(
+            QualityControlEngine::cusum_chart(&data, 10.0, 0.5, 4.0)
+                .unwrap()
+                .v_mask
+                .is_none()
+        );
+        assert!(QualityControlEngine::cusum_chart(&data, 10.0, 0.5, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_ewma_arl_matches_published_values() {
+        // Shewhart limit: 1 / (2 * (1 - Phi(3))) = 370.4.
+        assert!((ewma_arl(1.0, 3.0) - 370.4).abs() < 0.5);
+        // Lucas and Saccucci (1990), Table 3: lambda 0.2, L 3 gives 558.
+        assert!((ewma_arl(0.2, 3.0) - 558.0).abs() < 6.0);
+        assert!((ewma_arl(0.1, 2.814) - 500.0).abs() < 5.0);
+
+        let lambda = optimize_ewma_lambda(558.0);
+        assert!((lambda - 0.2).abs() < 0.02);
+        assert!((optimize_ewma_lambda(100.0) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_ewma_limits_widen_and_flag_shift() {
+        let data: Vec<f64> = (0..60)
+            .map(|i| {
+                let noise = [0.4, -0.3, 0.1, -0.2][i % 4];
+                let shift = if i >= 40 { 1.0 } else { 0.0 };
+                5.0 + noise + shift
+            })
+            .collect();
+        let result = QualityControlEngine::ewma_chart(&data, 0.2, None).unwrap();
+
+        assert!((result.l_sigma - 3.0).abs() < f64::EPSILON);
+        assert!(
+            result
+                .upper_limits
+                .windows(2)
+                .all(|pair| pair[1] >= pair[0])
+        );
+        let steady = 3.0 * result.sigma_estimate * (0.2_f64 / 1.8).sqrt();
+        assert!((result.upper_limits[59] - result.center_line - steady).abs() < 1e-9);
+        let first = 0.2 * 3.0 * result.sigma_estimate;
+        assert!((result.upper_limits[0] - result.center_line - first).abs() < 1e-9);
+        assert!(result.signal_indices.iter().all(|&index| index >= 40));
+        assert!(!result.signal_indices.is_empty());
+        assert!((result.steady_state_arl - 558.0).abs() < 6.0);
+
+        assert!(QualityControlEngine::ewma_chart(&data, 0.0, None).is_err());
+        assert!(QualityControlEngine::ewma_chart(&[1.0; 5], 0.2, None).is_err());
+    }
+
+    /// Part effects `0..5`, operator offsets `0, 0.1, 0.2` and replicates
+    /// `-/+ noise`: additive, so the interaction sum of squares is zero.
+    fn additive_study(noise: f64) -> Vec<Vec<Vec<f64>>> {
+        (0..5)
+            .map(|part| {
+                (0..3)
+                    .map(|operator| {
+                        let value = 0.1_f64.mul_add(f64::from(operator), f64::from(part));
+                        vec![value - noise, value + noise]
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_gage_rr_pools_negligible_interaction() {
+        let result = QualityControlEngine::gage_rr(&additive_study(0.05), 5, 3, 2, None).unwrap();
+        assert!(result.interaction_pooled);
+        let sources: Vec<&str> = result.anova.iter().map(|row| row.source.as_str()).collect();
+        assert_eq!(sources, ["Part", "Operator", "Repeatability", "Total"]);
+
+        // SS_part = 3 * 2 * 10, SS_operator = 5 * 2 * 0.02, SS_error = 30 * 0.05^2,
+        // pooled over 8 + 15 degrees of freedom.
+        let repeatability = 0.075 / 23.0;
+        assert!((result.anova[0].sum_of_squares - 60.0).abs() < 1e-9);
+        assert!((result.anova[1].sum_of_squares - 0.2).abs() < 1e-9);
+        assert_eq!(result.anova[2].degrees_of_freedom, 23);
+        assert!((result.anova[2].mean_square.unwrap() - repeatability).abs() < 1e-12);
+        assert!(result.anova[0].p_value.unwrap() < 1e-6);
+
+        let variance = |source: &str| {
+            result
+                .variance_components
+                .iter()
+                .find(|component| component.source == source)
+                .unwrap()
+                .variance
+        };
+        let operator = (0.1 - repeatability) / 10.0;
+        let part = (15.0 - repeatability) / 6.0;
+        assert!((variance("Repeatability") - repeatability).abs() < 1e-12);
+        assert!((variance("Operator") - operator).abs() < 1e-12);
+        assert!((variance("Part-to-Part") - part).abs() < 1e-12);
+        let gage = repeatability + operator;
+        let percent_grr = 100.0 * (gage / (gage + part)).sqrt();
+        assert!((result.percent_grr_total_variation - percent_grr).abs() < 1e-9);
+        assert_eq!(result.ndc, 19);
+        assert_eq!(result.assessment, GageRrAssessment::Acceptable);
+        assert!(result.percent_grr_tolerance.is_none());
+    }
+
+    #[test]
+    fn test_gage_rr_tolerance_decides_assessment() {
+        let data = additive_study(0.05);
+        let gage = QualityControlEngine::gage_rr(&data, 5, 3, 2, None)
+            .unwrap()
+            .variance_components[0]
+            .study_variation;
+
+        let wide = QualityControlEngine::gage_rr(&data, 5, 3, 2, Some(20.0 * gage)).unwrap();
+        assert!((wide.percent_grr_tolerance.unwrap() - 5.0).abs() < 1e-9);
+        assert_eq!(wide.assessment, GageRrAssessment::Acceptable);
+        let narrow = QualityControlEngine::gage_rr(&data, 5, 3, 2, Some(2.0 * gage)).unwrap();
+        assert_eq!(narrow.assessment, GageRrAssessment::Unacceptable);
+    }
+
+    #[test]
+    fn test_gage_rr_rejects_mismatched_design() {
+        let mut data = additive_study(0.05);
+        assert!(QualityControlEngine::gage_rr(&data, 4, 3, 2, None).is_err());
+        assert!(QualityControlEngine::gage_rr(&data, 5, 3, 2, Some(0.0)).is_err());
+        data[2][1].push(1.0);
+        assert!(QualityControlEngine::gage_rr(&data, 5, 3, 2, None).is_err());
+        assert!(QualityControlEngine::gage_rr(&additive_study(0.0)[..1], 1, 3, 2, None).is_err());
+    }
+}
@@ -0,0 +1,518 @@
+//! Internal consistency of multi-item scales.
+//!
+//! Cronbach's alpha measures how consistently the items of a questionnaire or
+//! test scale measure the same construct. Item analysis repeats it with each
+//! item left out: an item whose removal raises alpha weakens the scale.
+//!
+//! Categorical ratings call for kappa instead: Cohen's (optionally weighted
+//! for ordinal categories) for two raters and Fleiss' for three or more.
+use crate::scientific::primitives::descriptive::{mean, sample_variance};
+
+use super::types::{ItemAnalysisResult, ItemStatistics, KappaResult, KappaWeights};
+
+/// Two-sided 97.5 % standard normal quantile.
+const Z_95: f64 = 1.959_963_984_540_054;
+/// Upper limits and labels of the Landis and Koch (1977) agreement bands.
+const KAPPA_BANDS: [(f64, &str); 5] = [
+    (0.2, "Slight"),
+    (0.4, "Fair"),
+    (0.6, "Moderate"),
+    (0.8, "Substantial"),
+    (1.0, "Almost perfect"),
+];
+
+/// Reliability analysis of scales given as one score vector per item.
+pub struct ReliabilityEngine;
+
+impl ReliabilityEngine {
+    /// Cronbach's alpha `k / (k - 1) * (1 - sum(var_item) / var_total)` of
+    /// `items`, each holding one score per respondent.
+    ///
+    /// # Errors
+    /// Returns an error for fewer than two items, items of unequal length,
+    /// fewer than two respondents, non-finite scores or total scores without
+    /// variance.
+    pub fn calculate_cronbach_alpha(items: &[Vec<f64>]) -> Result<f64, String> {
+        validate_items(items, 2)?;
+        let totals = total_scores(items);
+        let total_variance = sample_variance(&totals).unwrap_or(0.0);
+        if total_variance <= 0.0 {
+            return Err("Total scores have no variance".to_owned());
+        }
+        let item_variance: f64 = items
+            .iter()
+            .map(|item| sample_variance(item).unwrap_or(0.0))
+            .sum();
+        #[allow(clippy::cast_precision_loss, reason = "Item count casting to f64")]
+        let count = items.len() as f64;
+        Ok(count / (count - 1.0) * (1.0 - item_variance / total_variance))
+    }
+
+    /// Correlation of each item with the total score, the item included;
+    /// `None` for an item or total without variance.
+    ///
+    /// # Errors
+    /// Returns an error for fewer than two items, items of unequal length,
+    /// fewer than two respondents or non-finite scores.
+    pub fn calculate_item_total_correlations(
+        items: &[Vec<f64>],
+    ) -> Result<Vec<Option<f64>>, String> {
+        validate_items(items, 2)?;
+        let totals = total_scores(items);
+        Ok(items.iter().map(|item| pearson(item, &totals)).collect())
+    }
+
+    /// Descriptive statistics, item-total and item-rest correlations and
+    /// alpha-if-item-deleted for every item of `datasets`.
+    ///
+    /// # Errors
+    /// Returns an error for fewer than three items, items of unequal length,
+    /// fewer than two respondents, non-finite scores, or when the scale or
+    /// a scale without one item has no total-score variance.
+    pub fn item_analysis(datasets: &[Vec<f64>]) -> Result<ItemAnalysisResult, String> {
+        // Alpha if deleted still needs two items.
+        validate_items(datasets, 3)?;
+        let alpha = Self::calculate_cronbach_alpha(datasets)?;
+        let item_total = Self::calculate_item_total_correlations(datasets)?;
+        let totals = total_scores(datasets);
+
+        let mut items = Vec::with_capacity(datasets.len());
+        for (index, (item, item_total_correlation)) in datasets.iter().zip(item_total).enumerate() {
+            let rest: Vec<f64> = totals
+                .iter()
+                .zip(item)
+                .map(|(total, score)| total - score)
+                .collect();
+            let remaining: Vec<Vec<f64>> = datasets
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != index)
+                .map(|(_, other)| other.clone())
+                .collect();
+            let alpha_if_deleted = Self::calculate_cronbach_alpha(&remaining)
+                .map_err(|e| format!("Without item {index}: {e}"))?;
+            items.push(ItemStatistics {
+                index,
+                mean: mean(item).unwrap_or(0.0),
+                std_dev: sample_variance(item).unwrap_or(0.0).sqrt(),
+                item_total_correlation,
+                item_rest_correlation: pearson(item, &rest),
+                alpha_if_deleted,
+                degrades_reliability: alpha_if_deleted > alpha,
+            });
+        }
+        let flagged_items = items
+            .iter()
+            .filter(|item| item.degrades_reliability)
+            .map(|item| item.index)
+            .collect();
+
+        Ok(ItemAnalysisResult {
+            alpha,
+            items,
+            flagged_items,
+        })
+    }
+
+    /// Cohen's kappa of two raters assigning each subject one of
+    /// `n_categories` categories `0..n_categories`.
+    ///
+    /// Linear and quadratic weights give partial credit to near misses on
+    /// ordinal scales. The standard error is the large-sample non-null
+    /// formula of Fleiss, Cohen and Everitt (1969).
+    ///
+    /// # Errors
+    /// Returns an error for fewer than two categories, rating lists of
+    /// unequal length or with fewer than two subjects, ratings outside the
+    /// categories, or when chance agreement is already perfect.
+    pub fn cohens_kappa(
+        rater1: &[usize],
+        rater2: &[usize],
+        n_categories: usize,
+        weights: Option<KappaWeights>,
+    ) -> Result<KappaResult, String> {
+        if n_categories < 2 {
+            return Err(format!(
+                "At least 2 categories are required, got {n_categories}"
+            ));
+        }
+        if rater1.len() != rater2.len() {
+            return Err(format!(
+                "Raters scored {} and {} subjects",
+                rater1.len(),
+                rater2.len()
+            ));
+        }
+        if rater1.len() < 2 {
+            return Err(format!(
+                "At least 2 subjects are required, got {}",
+                rater1.len()
+            ));
+        }
+        if let Some(&category) = rater1
+            .iter()
+            .chain(rater2)
+            .find(|&&category| category >= n_categories)
+        {
+            return Err(format!("Category {category} is outside 0..{n_categories}"));
+        }
+
+        #[allow(clippy::cast_precision_loss, reason = "Subject count casting to f64")]
+        let subjects = rater1.len() as f64;
+        let mut joint = vec![vec![0.0; n_categories]; n_categories];
+        for (&first, &second) in rater1.iter().zip(rater2) {
+            joint[first][second] += 1.0 / subjects;
+        }
+        let rows: Vec<f64> = joint.iter().map(|row| row.iter().sum()).collect();
+        let columns: Vec<f64> = (0..n_categories)
+            .map(|j| joint.iter().map(|row| row[j]).sum())
+            .collect();
+        let weight = agreement_weights(n_categories, weights.unwrap_or_default());
+
+        let mut observed = 0.0;
+        let mut expected = 0.0;
+        for i in 0..n_categories {
+            for j in 0..n_categories {
+                observed = weight[i][j].mul_add(joint[i][j], observed);
+                expected = weight[i][j].mul_add(rows[i] * columns[j], expected);
+            }
+        }
+        if expected >= 1.0 {
+            return Err("Chance agreement is perfect; kappa is undefined".to_owned());
+        }
+        let kappa = (observed - expected) / (1.0 - expected);
+
+        // Mean weights of each row and column category.
+        let row_weights: Vec<f64> = weight
+            .iter()
+            .map(|row| row.iter().zip(&columns).map(|(w, p)| w * p).sum())
+            .collect();
+        let column_weights: Vec<f64> = (0..n_categories)
+            .map(|j| (0..n_categories).map(|i| weight[i][j] * rows[i]).sum())
+            .collect();
+        let mut spread = 0.0;
+        for i in 0..n_categories {
+            for j in 0..n_categories {
+                let deviation =
+                    (row_weights[i] + column_weights[j]).mul_add(-(1.0 - kappa), weight[i][j]);
+                spread = joint[i][j].mul_add(deviation * deviation, spread);
+            }
+        }
+        let bias = expected.mul_add(-(1.0 - kappa), kappa);
+        let variance = bias.mul_add(-bias, spread) / (subjects * (1.0 - expected).powi(2));
+
+        Ok(kappa_result(
+            kappa,
+            observed,
+            expected,
+            variance.max(0.0).sqrt(),
+        ))
+    }
+
+    /// Fleiss' kappa of `ratings[subject][rater]`, each subject rated by the
+    /// same number (at least three) of raters into `0..n_categories`.
+    ///
+    /// Chance agreement uses the category proportions pooled over all raters,
+    /// which assumes marginal homogeneity. The standard error is the
+    /// large-sample formula of Fleiss, Nee and Landis (1979).
+    ///
+    /// # Errors
+    /// Returns an error for fewer than two categories or subjects, fewer than
+    /// three raters, subjects with different rater counts, ratings outside the
+    /// categories, or a single category used throughout.
+    pub fn fleiss_kappa(
+        ratings: &[Vec<usize>],
+        n_categories: usize,
+    ) -> Result<KappaResult, String> {
+        if n_categories < 2 {
+            return Err(format!(
+                "At least 2 categories are required, got {n_categories}"
+            ));
+        }
+        if ratings.len() < 2 {
+            return Err(format!(
+                "At least 2 subjects are required, got {}",
+                ratings.len()
+            ));
+        }
+        let raters = ratings[0].len();
+        if raters < 3 {
+            return Err(format!(
+                "Fleiss' kappa needs at least 3 raters, got {raters}; use Cohen's kappa for 2"
+            ));
+        }
+        for (subject, row) in ratings.iter().enumerate() {
+            if row.len() != raters {
+                return Err(format!(
+                    "Subject {subject} has {} ratings, expected {raters}",
+                    row.len()
+                ));
+            }
+            if let Some(category) = row.iter().find(|&&category| category >= n_categories) {
+                return Err(format!(
+                    "Subject {subject}: category {category} is outside 0..{n_categories}"
+                ));
+            }
+        }
+
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Subject and rater counts casting to f64"
+        )]
+        let (subjects, raters) = (ratings.len() as f64, raters as f64);
+        let mut proportions = vec![0.0; n_categories];
+        let mut observed = 0.0;
+        for row in ratings {
+            let mut counts = vec![0.0_f64; n_categories];
+            for &category in row {
+                counts[category] += 1.0;
+            }
+            for (proportion, count) in proportions.iter_mut().zip(&counts) {
+                *proportion += count / (subjects * raters);
+            }
+            let pairs: f64 = counts.iter().map(|count| count * (count - 1.0)).sum();
+            observed += pairs / (raters * (raters - 1.0)) / subjects;
+        }
+        let expected: f64 = proportions.iter().map(|p| p * p).sum();
+        if expected >= 1.0 {
+            return Err("All ratings fall in one category; kappa is undefined".to_owned());
+        }
+        let kappa = (observed - expected) / (1.0 - expected);
+
+        let spread: f64 = proportions.iter().map(|p| p * (1.0 - p)).sum();
+        let skew: f64 = proportions
+            .iter()
+            .map(|p| p * (1.0 - p) * (1.0 - 2.0 * p))
+            .sum();
+        let standard_error = (2.0 / (subjects * raters * (raters - 1.0))).sqrt()
+            * spread.mul_add(spread, -skew).max(0.0).sqrt()
+            / spread;
+
+        Ok(kappa_result(kappa, observed, expected, standard_error))
+    }
+}
+
+/// Agreement credit of each pair of categories.
+fn agreement_weights(n_categories: usize, weights: KappaWeights) -> Vec<Vec<f64>> {
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Category indices casting to f64"
+    )]
+    let span = (n_categories - 1) as f64;
+    (0..n_categories)
+        .map(|i| {
+            (0..n_categories)
+                .map(|j| {
+                    #[allow(
+                        clippy::cast_precision_loss,
+                        reason = "Category indices casting to f64"
+                    )]
+                    let distance = i.abs_diff(j) as f64 / span;
+                    match weights {
+                        KappaWeights::None => f64::from(u8::from(i == j)),
+                        KappaWeights::Linear => 1.0 - distance,
+                        KappaWeights::Quadratic => distance.mul_add(-distance, 1.0),
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn kappa_result(kappa: f64, observed: f64, expected: f64, standard_error: f64) -> KappaResult {
+    let interpretation = if kappa < 0.0 {
+        "Poor"
+    } else {
+        KAPPA_BANDS
+            .iter()
+            .find(|&&(upper, _)| kappa <= upper)
+            .map_or("Almost perfect", |&(_, label)| label)
+    };
+    KappaResult {
+        kappa,
+        observed_agreement: observed,
+        expected_agreement: expected,
+        standard_error,
+        ci_lower: Z_95.mul_add(-standard_error, kappa),
+        ci_upper: Z_95.mul_add(standard_error, kappa),
+        interpretation: interpretation.to_owned(),
+    }
+}
+
+fn validate_items(items: &[Vec<f64>], min_items: usize) -> Result<(), String> {
+    if items.len() < min_items {
+        return Err(format!(
+            "At least {min_items} items are required, got {}",
+            items.len()
+        ));
+    }
+    let respondents = items[0].len();
+    if respondents < 2 {
+        return Err(format!(
+            "At least 2 respondents are required, got {respondents}"
+        ));
+    }
+    for (index, item) in items.iter().enumerate() {
+        if item.len() != respondents {
+            return Err(format!(
+                "Item {index} has {} scores, expected {respondents}",
+                item.len()
+            ));
+        }
+        if item.iter().any(|score| !score.is_finite()) {
+            return Err(format!("Item {index} contains non-finite scores"));
+        }
+    }
+    Ok(())
+}
+
+/// Sum of the item scores of each respondent.
+fn total_scores(items: &[Vec<f64>]) -> Vec<f64> {
+    (0..items[0].len())
+        .map(|respondent| items.iter().map(|item| item[respondent]).sum())
+        .collect()
+}
+
+/// Pearson correlation of two equally long samples, or `None` when either
+/// has no variance.
+fn pearson(first: &[f64], second: &[f64]) -> Option<f64> {
+    let (first_mean, second_mean) = (mean(first)?, mean(second)?);
+    let mut covariance = 0.0;
+    let mut first_squares = 0.0;
+    let mut second_squares = 0.0;
+    for (x, y) in first.iter().zip(second) {
+        let (dx, dy) = (x - first_mean, y - second_mean);
+        covariance = dx.mul_add(dy, covariance);
+        first_squares = dx.mul_add(dx, first_squares);
+        second_squares = dy.mul_add(dy, second_squares);
+    }
+    let scale = (first_squares * second_squares).sqrt();
+    (scale > 0.0).then(|| covariance / scale)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    /// Three consistent items and a fourth unrelated to them.
+    fn scale() -> Vec<Vec<f64>> {
+        vec![
+            vec![4.0, 3.0, 5.0, 2.0, 4.0, 1.0],
+            vec![5.0, 3.0, 4.0, 2.0, 5.0, 2.0],
+            vec![4.0, 2.0, 5.0, 1.0, 4.0, 2.0],
+            vec![1.0, 5.0, 2.0, 4.0, 3.0, 5.0],
+        ]
+    }
+
+    #[test]
+    fn test_alpha_matches_formula() {
+        let items = vec![vec![1.0, 2.0, 3.0], vec![2.0, 2.0, 4.0]];
+        // Item variances 1 and 4/3; totals 3, 4, 7 have variance 13/3.
+        let expected = 2.0 * (1.0 - (7.0 / 3.0) / (13.0 / 3.0));
+        let alpha = ReliabilityEngine::calculate_cronbach_alpha(&items).unwrap();
+        assert!((alpha - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_item_analysis_flags_inconsistent_item() {
+        let data = scale();
+        let result = ReliabilityEngine::item_analysis(&data).unwrap();
+        assert_eq!(result.flagged_items, [3]);
+
+        let unrelated = &result.items[3];
+        assert!(unrelated.alpha_if_deleted > result.alpha);
+        assert!(unrelated.item_rest_correlation.unwrap() < 0.0);
+        let without = ReliabilityEngine::calculate_cronbach_alpha(&data[..3]).unwrap();
+        assert!((unrelated.alpha_if_deleted - without).abs() < 1e-12);
+
+        let first = &result.items[0];
+        assert!((first.mean - 19.0 / 6.0).abs() < 1e-12);
+        assert!(!first.degrades_reliability);
+        // The item inflates its own total, so item-total exceeds item-rest.
+        assert!(first.item_total_correlation.unwrap() > first.item_rest_correlation.unwrap());
+    }
+
+    #[test]
+    fn test_item_analysis_rejects_invalid_scales() {
+        let data = scale();
+        assert!(ReliabilityEngine::item_analysis(&data[..2]).is_err());
+        let mut ragged = data;
+        ragged[1].pop();
+        assert!(ReliabilityEngine::item_analysis(&ragged).is_err());
+    }
+
+    #[test]
+    fn test_cohens_kappa_two_by_two() {
+        // 20 yes/yes, 5 yes/no, 10 no/yes and 15 no/no: Po = 0.7, Pe = 0.5.
+        let mut rater1 = vec![0; 25];
+        rater1.extend([1; 25]);
+        let mut rater2 = vec![0; 20];
+        rater2.extend([1; 5]);
+        rater2.extend([0; 10]);
+        rater2.extend([1; 15]);
+        let result = ReliabilityEngine::cohens_kappa(&rater1, &rater2, 2, None).unwrap();
+        assert!((result.kappa - 0.4).abs() < 1e-12);
+        assert!((result.observed_agreement - 0.7).abs() < 1e-12);
+        assert!((result.expected_agreement - 0.5).abs() < 1e-12);
+        assert!((result.standard_error - 0.126_996_062_931_100_3).abs() < 1e-12);
+        let upper = Z_95.mul_add(result.standard_error, result.kappa);
+        assert!((result.ci_upper - upper).abs() < 1e-12);
+        assert_eq!(result.interpretation, "Fair");
+
+        assert!(ReliabilityEngine::cohens_kappa(&[0, 1], &[0], 2, None).is_err());
+        assert!(ReliabilityEngine::cohens_kappa(&[0, 2], &[0, 1], 2, None).is_err());
+        assert!(ReliabilityEngine::cohens_kappa(&[1, 1], &[1, 1], 2, None).is_err());
+    }
+
+    #[test]
+    fn test_weighted_kappa_credits_near_misses() {
+        let rater1 = [0, 0, 1, 1, 2, 2, 0, 1, 2, 2];
+        let rater2 = [0, 1, 1, 2, 2, 2, 0, 0, 2, 1];
+        let quadratic =
+            ReliabilityEngine::cohens_kappa(&rater1, &rater2, 3, Some(KappaWeights::Quadratic))
+                .unwrap();
+        assert!((quadratic.kappa - 0.710_144_927_536_232).abs() < 1e-12);
+        assert!((quadratic.observed_agreement - 0.9).abs() < 1e-12);
+        let linear =
+            ReliabilityEngine::cohens_kappa(&rater1, &rater2, 3, Some(KappaWeights::Linear))
+                .unwrap();
+        let plain = ReliabilityEngine::cohens_kappa(&rater1, &rater2, 3, None).unwrap();
+        // Every disagreement is off by one category, so weighting raises kappa.
+        assert!(plain.kappa < linear.kappa && linear.kappa < quadratic.kappa);
+    }
+
+    #[test]
+    fn test_fleiss_kappa_matches_published_example() {
+        // Fleiss (1971): 10 subjects, 14 raters, 5 categories.
+        let counts = [
+            [0, 0, 0, 0, 14],
+            [0, 2, 6, 4, 2],
+            [0, 0, 3, 5, 6],
+            [0, 3, 9, 2, 0],
+            [2, 2, 8, 1, 1],
+            [7, 7, 0, 0, 0],
+            [3, 2, 6, 3, 0],
+            [2, 5, 3, 2, 2],
+            [6, 5, 2, 1, 0],
+            [0, 2, 2, 3, 7],
+        ];
+        let ratings: Vec<Vec<usize>> = counts
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .flat_map(|(category, &count)| std::iter::repeat_n(category, count))
+                    .collect()
+            })
+            .collect();
+        let result = ReliabilityEngine::fleiss_kappa(&ratings, 5).unwrap();
+        assert!((result.observed_agreement - 0.378).abs() < 1e-3);
+        assert!((result.expected_agreement - 0.213).abs() < 1e-3);
+        assert!((result.kappa - 0.210).abs() < 1e-3);
+        assert!(result.standard_error > 0.0);
+        assert_eq!(result.interpretation, "Fair");
+
+        assert!(ReliabilityEngine::fleiss_kappa(&[vec![0, 1], vec![1, 1]], 2).is_err());
+    }
+}
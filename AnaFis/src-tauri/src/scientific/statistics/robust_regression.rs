@@ -0,0 +1,826 @@
+//! Robust linear regression: RANSAC and M-estimators.
+//!
+//! Each RANSAC trial fits ordinary least squares with an intercept to a
+//! random subset of rows and counts the rows whose absolute residual is
+//! within the threshold. Trials run in parallel, each drawing from its own
+//! generator seeded from the run's seed, so the outcome is a pure function of
+//! the data and the seed. The selected trial has the most inliers, ties going
+//! to the lower median absolute inlier residual and then to the earlier
+//! trial; the final model is refitted on its inliers. Samples whose design
+//! matrix is singular are counted as degenerate and skipped.
+//!
+//! The M-estimators (Huber, and Tukey's biweight started from the Huber fit)
+//! iterate weighted least squares. Weights are computed on residuals divided
+//! by a robust scale, the median absolute deviation of the residuals over
+//! 0.6745, which is re-estimated every iteration, so the tuning constant
+//! means the same for data of any spread. Standard errors come from the
+//! sandwich `(X'DX)^-1 X' diag((w_i r_i)^2) X (X'DX)^-1 n / (n - p)` at the
+//! final weights `w_i`, where `D` holds the slopes `psi'(r_i / s)` of the
+//! influence function.
+use std::cmp::Ordering;
+use std::hash::{BuildHasher, RandomState};
+
+use nalgebra::{DMatrix, DVector};
+use rand::{RngCore, SeedableRng};
+use rand_pcg::Pcg64;
+use rayon::prelude::*;
+
+use super::error::StatisticsError;
+use super::types::{MEstimatorOptions, MEstimatorResult, RansacOptions, RobustRegressionResult};
+use crate::scientific::primitives::descriptive::{median, median_absolute_deviation};
+
+/// Trials run when no count is given.
+const DEFAULT_MAX_TRIALS: usize = 100;
+/// Smallest singular value, relative to the largest, of a usable design.
+const RANK_TOLERANCE: f64 = 1e-10;
+/// Huber tuning constant with 95% efficiency for normal errors.
+const HUBER_TUNING: f64 = 1.345;
+/// Tukey biweight tuning constant with 95% efficiency for normal errors.
+const TUKEY_TUNING: f64 = 4.685;
+/// Reweighting iterations run when no limit is given.
+const DEFAULT_MAX_ITERATIONS: usize = 50;
+/// Relative convergence tolerance used when none is given.
+const DEFAULT_TOLERANCE: f64 = 1e-8;
+/// Median absolute deviation of the standard normal distribution.
+const NORMAL_MAD: f64 = 0.674_489_750_196_081_7;
+
+/// Regression resistant to outlying rows.
+pub struct RobustRegressionEngine;
+
+/// Model of one non-degenerate RANSAC trial.
+#[derive(Debug, Clone)]
+struct Trial {
+    coefficients: DVector<f64>,
+    inlier_mask: Vec<bool>,
+    inlier_count: usize,
+    residual_median: f64,
+}
+
+/// Weight function of an M-estimator, with its tuning constant.
+#[derive(Debug, Clone, Copy)]
+enum Weighting {
+    Huber(f64),
+    Tukey(f64),
+}
+
+impl Weighting {
+    /// Weight of a residual of `standardized` scale units.
+    fn weight(self, standardized: f64) -> f64 {
+        let size = standardized.abs();
+        match self {
+            Self::Huber(tuning) if size > tuning => tuning / size,
+            Self::Huber(_) => 1.0,
+            Self::Tukey(tuning) if size < tuning => {
+                let shrink = (standardized / tuning).mul_add(-(standardized / tuning), 1.0);
+                shrink * shrink
+            }
+            Self::Tukey(_) => 0.0,
+        }
+    }
+
+    /// Slope `psi'` of the influence function `psi(u) = u w(u)` at
+    /// `standardized`.
+    fn slope(self, standardized: f64) -> f64 {
+        let size = standardized.abs();
+        match self {
+            Self::Huber(tuning) if size > tuning => 0.0,
+            Self::Huber(_) => 1.0,
+            Self::Tukey(tuning) if size < tuning => {
+                let ratio = (standardized / tuning).powi(2);
+                (1.0 - ratio) * 5.0_f64.mul_add(-ratio, 1.0)
+            }
+            Self::Tukey(_) => 0.0,
+        }
+    }
+}
+
+/// Validated data and settings of an M-estimator fit.
+struct MEstimation {
+    design: DMatrix<f64>,
+    target: DVector<f64>,
+    tuning_constant: f64,
+    max_iterations: usize,
+    tolerance: f64,
+}
+
+/// Coefficients and scale reached by reweighting.
+struct Reweighted {
+    coefficients: DVector<f64>,
+    scale: f64,
+    iterations: usize,
+    converged: bool,
+}
+
+impl RobustRegressionEngine {
+    /// RANSAC fit of `response` on `predictors`, given as one row of
+    /// predictor values per observation.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::Validation`] for ragged or non-finite
+    /// input, a non-positive threshold, no trials or a sample size outside
+    /// the number of coefficients to the number of rows, and
+    /// [`StatisticsError::Singular`] when every trial is degenerate.
+    pub fn ransac_regression(
+        predictors: &[Vec<f64>],
+        response: &[f64],
+        options: &RansacOptions,
+    ) -> Result<RobustRegressionResult, StatisticsError> {
+        let rows = response.len();
+        let width = predictors.first().map_or(0, Vec::len);
+        validate(predictors, response, width)?;
+        let parameters = width + 1;
+        let min_samples = options.min_samples.unwrap_or(parameters);
+        if min_samples < parameters || min_samples > rows {
+            return Err(StatisticsError::validation(
+                "minSamples",
+                format!("Each trial needs between {parameters} and {rows} rows, got {min_samples}"),
+            ));
+        }
+        let max_trials = options.max_trials.unwrap_or(DEFAULT_MAX_TRIALS);
+        if max_trials == 0 {
+            return Err(StatisticsError::validation(
+                "maxTrials",
+                "At least one trial is required",
+            ));
+        }
+        let residual_threshold = match options.residual_threshold {
+            None => median_absolute_deviation(response).unwrap_or(0.0),
+            Some(threshold) if threshold.is_finite() && threshold > 0.0 => threshold,
+            Some(threshold) => {
+                return Err(StatisticsError::validation(
+                    "residualThreshold",
+                    format!("Residual threshold must be positive and finite, got {threshold}"),
+                ));
+            }
+        };
+        let seed = options
+            .seed
+            .unwrap_or_else(|| RandomState::new().hash_one(rows));
+
+        let design = design_matrix(predictors, rows, parameters);
+        let target = DVector::from_column_slice(response);
+
+        let mut seeder = Pcg64::seed_from_u64(seed);
+        let seeds: Vec<u64> = (0..max_trials).map(|_| seeder.next_u64()).collect();
+        let trials: Vec<Option<Trial>> = seeds
+            .into_par_iter()
+            .map(|trial_seed| {
+                let mut rng = Pcg64::seed_from_u64(trial_seed);
+                let sample = sample_rows(&mut rng, rows, min_samples);
+                let coefficients =
+                    least_squares(&design.select_rows(&sample), &target.select_rows(&sample))?;
+                Some(score(&design, &target, coefficients, residual_threshold))
+            })
+            .collect();
+        let degenerate_trials = trials.iter().filter(|trial| trial.is_none()).count();
+        let best = best_trial(&trials).ok_or_else(|| StatisticsError::Singular {
+            context: format!("All {max_trials} RANSAC samples had a singular design matrix"),
+        })?;
+
+        let inliers: Vec<usize> = best
+            .inlier_mask
+            .iter()
+            .enumerate()
+            .filter_map(|(row, &inlier)| inlier.then_some(row))
+            .collect();
+        let coefficients =
+            least_squares(&design.select_rows(&inliers), &target.select_rows(&inliers))
+                .unwrap_or_else(|| best.coefficients.clone());
+        let (std_errors, scale) = least_squares_std_errors(
+            &design.select_rows(&inliers),
+            &target.select_rows(&inliers),
+            &coefficients,
+        );
+
+        Ok(RobustRegressionResult {
+            intercept: coefficients[0],
+            coefficients: coefficients.iter().skip(1).copied().collect(),
+            intercept_std_error: std_errors[0],
+            coefficient_std_errors: std_errors.iter().skip(1).copied().collect(),
+            scale,
+            inlier_mask: best.inlier_mask.clone(),
+            inlier_count: best.inlier_count,
+            residual_median: best.residual_median,
+            residual_threshold,
+            trials: max_trials,
+            degenerate_trials,
+            seed,
+        })
+    }
+
+    /// Huber M-estimate of `response` on `predictors`, given as one row of
+    /// predictor values per observation, started from least squares.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::Validation`] for ragged or non-finite
+    /// input, no more rows than coefficients or unusable options, and
+    /// [`StatisticsError::Singular`] when a weighted design is singular.
+    pub fn huber_regression(
+        predictors: &[Vec<f64>],
+        response: &[f64],
+        options: &MEstimatorOptions,
+    ) -> Result<MEstimatorResult, StatisticsError> {
+        let estimation = MEstimation::new(predictors, response, options, HUBER_TUNING)?;
+        let start =
+            least_squares(&estimation.design, &estimation.target).ok_or_else(singular_design)?;
+        let weighting = Weighting::Huber(estimation.tuning_constant);
+        let fit = estimation.reweight(start, weighting)?;
+        Ok(estimation.result(&fit, weighting))
+    }
+
+    /// Tukey biweight M-estimate of `response` on `predictors`.
+    ///
+    /// The biweight rejects far outliers entirely, so it can settle on a
+    /// poor local fit from a least-squares start; it starts from the Huber
+    /// fit instead.
+    ///
+    /// # Errors
+    /// As [`Self::huber_regression`].
+    pub fn irls_regression(
+        predictors: &[Vec<f64>],
+        response: &[f64],
+        options: &MEstimatorOptions,
+    ) -> Result<MEstimatorResult, StatisticsError> {
+        let estimation = MEstimation::new(predictors, response, options, TUKEY_TUNING)?;
+        let start =
+            least_squares(&estimation.design, &estimation.target).ok_or_else(singular_design)?;
+        let huber = estimation.reweight(start, Weighting::Huber(HUBER_TUNING))?;
+        let weighting = Weighting::Tukey(estimation.tuning_constant);
+        let mut fit = estimation.reweight(huber.coefficients, weighting)?;
+        fit.iterations += huber.iterations;
+        Ok(estimation.result(&fit, weighting))
+    }
+}
+
+impl MEstimation {
+    fn new(
+        predictors: &[Vec<f64>],
+        response: &[f64],
+        options: &MEstimatorOptions,
+        default_tuning: f64,
+    ) -> Result<Self, StatisticsError> {
+        let rows = response.len();
+        let width = predictors.first().map_or(0, Vec::len);
+        validate(predictors, response, width)?;
+        let parameters = width + 1;
+        if rows <= parameters {
+            return Err(StatisticsError::InsufficientData {
+                needed: parameters + 1,
+                got: rows,
+            });
+        }
+        let tuning_constant = options.tuning_constant.unwrap_or(default_tuning);
+        if !tuning_constant.is_finite() || tuning_constant <= 0.0 {
+            return Err(StatisticsError::validation(
+                "tuningConstant",
+                format!("Tuning constant must be positive and finite, got {tuning_constant}"),
+            ));
+        }
+        let max_iterations = options.max_iterations.unwrap_or(DEFAULT_MAX_ITERATIONS);
+        if max_iterations == 0 {
+            return Err(StatisticsError::validation(
+                "maxIterations",
+                "At least one iteration is required",
+            ));
+        }
+        let tolerance = options.tolerance.unwrap_or(DEFAULT_TOLERANCE);
+        if !tolerance.is_finite() || tolerance <= 0.0 {
+            return Err(StatisticsError::validation(
+                "tolerance",
+                format!("Tolerance must be positive and finite, got {tolerance}"),
+            ));
+        }
+        Ok(Self {
+            design: design_matrix(predictors, rows, parameters),
+            target: DVector::from_column_slice(response),
+            tuning_constant,
+            max_iterations,
+            tolerance,
+        })
+    }
+
+    fn residuals(&self, coefficients: &DVector<f64>) -> DVector<f64> {
+        &self.target - &self.design * coefficients
+    }
+
+    /// Whether `scale` is rounding noise, the data lying on the model.
+    fn is_exact(&self, scale: f64) -> bool {
+        scale <= f64::EPSILON * self.target.amax()
+    }
+
+    /// `function` of each row's standardized residual; ones for an exact fit.
+    fn per_row(
+        &self,
+        residuals: &DVector<f64>,
+        scale: f64,
+        function: impl Fn(f64) -> f64,
+    ) -> DVector<f64> {
+        if self.is_exact(scale) {
+            residuals.map(|_| 1.0)
+        } else {
+            residuals.map(|residual| function(residual / scale))
+        }
+    }
+
+    /// Alternate weighted least squares and scale estimation from `start`
+    /// until both the coefficients and the scale stop changing.
+    fn reweight(
+        &self,
+        start: DVector<f64>,
+        weighting: Weighting,
+    ) -> Result<Reweighted, StatisticsError> {
+        let mut coefficients = start;
+        let mut scale = residual_scale(&self.residuals(&coefficients));
+        for iteration in 1..=self.max_iterations {
+            if self.is_exact(scale) {
+                return Ok(Reweighted {
+                    coefficients,
+                    scale,
+                    iterations: iteration - 1,
+                    converged: true,
+                });
+            }
+            let weights = self.per_row(&self.residuals(&coefficients), scale, |standardized| {
+                weighting.weight(standardized)
+            });
+            let next = weighted_least_squares(&self.design, &self.target, &weights)
+                .ok_or_else(singular_design)?;
+            let next_scale = residual_scale(&self.residuals(&next));
+            let settled = (&next - &coefficients).amax() <= self.tolerance * next.amax().max(1.0)
+                && (next_scale - scale).abs() <= self.tolerance * next_scale;
+            coefficients = next;
+            scale = next_scale;
+            if settled {
+                return Ok(Reweighted {
+                    coefficients,
+                    scale,
+                    iterations: iteration,
+                    converged: true,
+                });
+            }
+        }
+        Ok(Reweighted {
+            coefficients,
+            scale,
+            iterations: self.max_iterations,
+            converged: false,
+        })
+    }
+
+    fn result(&self, fit: &Reweighted, weighting: Weighting) -> MEstimatorResult {
+        let residuals = self.residuals(&fit.coefficients);
+        let weights = self.per_row(&residuals, fit.scale, |standardized| {
+            weighting.weight(standardized)
+        });
+        let slopes = self.per_row(&residuals, fit.scale, |standardized| {
+            weighting.slope(standardized)
+        });
+        let std_errors = sandwich_std_errors(&self.design, &residuals, &weights, &slopes);
+        MEstimatorResult {
+            intercept: fit.coefficients[0],
+            coefficients: fit.coefficients.iter().skip(1).copied().collect(),
+            intercept_std_error: std_errors[0],
+            coefficient_std_errors: std_errors.iter().skip(1).copied().collect(),
+            scale: fit.scale,
+            weights: weights.iter().copied().collect(),
+            tuning_constant: self.tuning_constant,
+            iterations: fit.iterations,
+            converged: fit.converged,
+        }
+    }
+}
+
+fn singular_design() -> StatisticsError {
+    StatisticsError::Singular {
+        context: "Weighted design matrix is singular".to_owned(),
+    }
+}
+
+/// Design matrix with a leading column of ones for the intercept.
+fn design_matrix(predictors: &[Vec<f64>], rows: usize, parameters: usize) -> DMatrix<f64> {
+    DMatrix::from_fn(rows, parameters, |row, column| {
+        if column == 0 {
+            1.0
+        } else {
+            predictors[row][column - 1]
+        }
+    })
+}
+
+/// Check that there is one finite predictor row of `width` values per
+/// finite response value.
+fn validate(
+    predictors: &[Vec<f64>],
+    response: &[f64],
+    width: usize,
+) -> Result<(), StatisticsError> {
+    if predictors.len() != response.len() {
+        return Err(StatisticsError::validation(
+            "predictors",
+            format!(
+                "Expected one predictor row per response value ({} values, {} rows)",
+                response.len(),
+                predictors.len()
+            ),
+        ));
+    }
+    if let Some(row) = predictors.iter().position(|row| row.len() != width) {
+        return Err(StatisticsError::validation(
+            "predictors",
+            format!(
+                "Row {row} has {} predictors, expected {width}",
+                predictors[row].len()
+            ),
+        ));
+    }
+    if predictors
+        .iter()
+        .flatten()
+        .chain(response)
+        .any(|value| !value.is_finite())
+    {
+        return Err(StatisticsError::validation(
+            "predictors",
+            "Predictors and response must be finite",
+        ));
+    }
+    Ok(())
+}
+
+/// Trial with the most inliers; ties go to the lower residual median and
+/// then to the earlier trial.
+fn best_trial(trials: &[Option<Trial>]) -> Option<&Trial> {
+    trials.iter().flatten().min_by(|first, second| {
+        second
+            .inlier_count
+            .cmp(&first.inlier_count)
+            .then_with(|| first.residual_median.total_cmp(&second.residual_median))
+    })
+}
+
+/// `count` distinct rows out of `rows`, by a partial Fisher-Yates shuffle.
+fn sample_rows(rng: &mut Pcg64, rows: usize, count: usize) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..rows).collect();
+    for position in 0..count {
+        let remaining = (rows - position) as u64;
+        #[allow(
+            clippy::cast_possible_truncation,
+            reason = "Remainder is below the row count, which fits in usize"
+        )]
+        let pick = position + (rng.next_u64() % remaining) as usize;
+        indices.swap(position, pick);
+    }
+    indices.truncate(count);
+    indices
+}
+
+/// Least-squares coefficients of `target` on `design`, or `None` when the
+/// design is singular.
+fn least_squares(design: &DMatrix<f64>, target: &DVector<f64>) -> Option<DVector<f64>> {
+    let svd = design.clone().svd(true, true);
+    let largest = svd.singular_values.max();
+    if largest.partial_cmp(&0.0) != Some(Ordering::Greater)
+        || svd.singular_values.min() <= largest * RANK_TOLERANCE
+    {
+        return None;
+    }
+    svd.solve(target, 0.0).ok()
+}
+
+/// Least squares with each row weighted by `weights`.
+fn weighted_least_squares(
+    design: &DMatrix<f64>,
+    target: &DVector<f64>,
+    weights: &DVector<f64>,
+) -> Option<DVector<f64>> {
+    let roots = weights.map(f64::sqrt);
+    let mut scaled = design.clone();
+    for (mut row, root) in scaled.row_iter_mut().zip(roots.iter()) {
+        row *= *root;
+    }
+    least_squares(&scaled, &target.component_mul(&roots))
+}
+
+/// Least-squares standard errors of `coefficients` and the residual
+/// standard deviation; NaN without spare degrees of freedom.
+fn least_squares_std_errors(
+    design: &DMatrix<f64>,
+    target: &DVector<f64>,
+    coefficients: &DVector<f64>,
+) -> (DVector<f64>, f64) {
+    let (rows, parameters) = design.shape();
+    let unavailable = || (DVector::from_element(parameters, f64::NAN), f64::NAN);
+    if rows <= parameters {
+        return unavailable();
+    }
+    let residuals = target - design * coefficients;
+    #[allow(clippy::cast_precision_loss, reason = "Row counts are far below 2^52")]
+    let scale = (residuals.norm_squared() / (rows - parameters) as f64).sqrt();
+    design
+        .tr_mul(design)
+        .try_inverse()
+        .map_or_else(unavailable, |inverse| {
+            (
+                inverse.diagonal().map(|variance| scale * variance.sqrt()),
+                scale,
+            )
+        })
+}
+
+/// Sandwich standard errors of an M-estimate.
+///
+/// Uses the final `weights` and influence slopes `slopes`. The score of a
+/// row is its weighted residual `w_i r_i`, which is `s psi(r_i / s)`.
+fn sandwich_std_errors(
+    design: &DMatrix<f64>,
+    residuals: &DVector<f64>,
+    weights: &DVector<f64>,
+    slopes: &DVector<f64>,
+) -> DVector<f64> {
+    let (rows, parameters) = design.shape();
+    let mut bread_rows = design.clone();
+    let mut scores = design.clone();
+    for row in 0..rows {
+        bread_rows.row_mut(row).scale_mut(slopes[row]);
+        scores.row_mut(row).scale_mut(weights[row] * residuals[row]);
+    }
+    let meat = scores.tr_mul(&scores);
+    #[allow(clippy::cast_precision_loss, reason = "Row counts are far below 2^52")]
+    let correction = rows as f64 / (rows - parameters) as f64;
+    design.tr_mul(&bread_rows).try_inverse().map_or_else(
+        || DVector::from_element(parameters, f64::NAN),
+        |bread| {
+            (&bread * meat * &bread)
+                .diagonal()
+                .map(|variance| (variance * correction).sqrt())
+        },
+    )
+}
+
+/// Robust scale of `residuals`: their median absolute deviation, made
+/// consistent for the standard deviation of normal errors.
+fn residual_scale(residuals: &DVector<f64>) -> f64 {
+    median_absolute_deviation(residuals.as_slice()).unwrap_or(0.0) / NORMAL_MAD
+}
+
+/// Inliers of the model `coefficients` and the median absolute residual
+/// over them (infinite without inliers).
+fn score(
+    design: &DMatrix<f64>,
+    target: &DVector<f64>,
+    coefficients: DVector<f64>,
+    threshold: f64,
+) -> Trial {
+    let residuals = target - design * &coefficients;
+    let inlier_mask: Vec<bool> = residuals
+        .iter()
+        .map(|residual| residual.abs() <= threshold)
+        .collect();
+    let inlier_residuals: Vec<f64> = residuals
+        .iter()
+        .zip(&inlier_mask)
+        .filter(|(_, inlier)| **inlier)
+        .map(|(residual, _)| residual.abs())
+        .collect();
+    Trial {
+        coefficients,
+        inlier_count: inlier_residuals.len(),
+        residual_median: median(&inlier_residuals).unwrap_or(f64::INFINITY),
+        inlier_mask,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::Rng;
+    use rand_distr::StandardNormal;
+
+    /// `y = 2x + 1` plus uniform noise of half-width `noise` on `x = 0..n`,
+    /// with every seventh point raised by 30.
+    fn line_with_outliers(rows: u32, noise: f64) -> (Vec<Vec<f64>>, Vec<f64>) {
+        let mut rng = Pcg64::seed_from_u64(5);
+        (0..rows)
+            .map(|row| {
+                let x = f64::from(row);
+                #[allow(clippy::cast_precision_loss, reason = "Random bits scaled to [0, 1)")]
+                let jitter = ((rng.next_u64() >> 11) as f64 * f64::EPSILON).mul_add(noise, -noise);
+                let outlier = if row % 7 == 3 { 30.0 } else { 0.0 };
+                (vec![x], 2.0_f64.mul_add(x, 1.0) + jitter + outlier)
+            })
+            .unzip()
+    }
+
+    fn options(seed: u64) -> RansacOptions {
+        RansacOptions {
+            residual_threshold: Some(1.0),
+            seed: Some(seed),
+            ..RansacOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_ransac_rejects_outliers() {
+        let (predictors, response) = line_with_outliers(50, 0.0);
+        let result =
+            RobustRegressionEngine::ransac_regression(&predictors, &response, &options(1)).unwrap();
+        assert_relative_eq!(result.intercept, 1.0, epsilon = 1e-9);
+        assert_relative_eq!(result.coefficients[0], 2.0, epsilon = 1e-9);
+        assert_eq!(result.inlier_count, 43);
+        assert!((0..50).all(|row| result.inlier_mask[row] == (row % 7 != 3)));
+        assert!(result.scale < 1e-9 && result.coefficient_std_errors[0] < 1e-9);
+        assert_eq!((result.trials, result.seed), (100, 1));
+    }
+
+    #[test]
+    fn test_seeded_runs_are_reproducible() {
+        let (predictors, response) = line_with_outliers(60, 1.5);
+        let ransac = |seed: u64| {
+            let options = RansacOptions {
+                max_trials: Some(8),
+                ..options(seed)
+            };
+            RobustRegressionEngine::ransac_regression(&predictors, &response, &options).unwrap()
+        };
+        let bits = |result: &RobustRegressionResult| {
+            (result.intercept.to_bits(), result.coefficients[0].to_bits())
+        };
+
+        let (first, second) = (ransac(42), ransac(42));
+        assert_eq!(bits(&first), bits(&second));
+        assert_eq!(first.inlier_mask, second.inlier_mask);
+
+        // With noise wider than the threshold, a different seed draws other
+        // samples, keeps another inlier set and so refits a slightly
+        // different line close to the true one.
+        let other = ransac(7);
+        assert_ne!(bits(&first), bits(&other));
+        assert_ne!(first.inlier_mask, other.inlier_mask);
+        assert_relative_eq!(other.coefficients[0], 2.0, epsilon = 0.05);
+    }
+
+    #[test]
+    fn test_degenerate_samples_are_counted() {
+        // Most pairs of rows share x = 0 and cannot define a line
+        let x = [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 2.0];
+        let predictors: Vec<Vec<f64>> = x.iter().map(|&x| vec![x]).collect();
+        let response: Vec<f64> = x.iter().map(|&x| 3.0 * x).collect();
+        let result =
+            RobustRegressionEngine::ransac_regression(&predictors, &response, &options(3)).unwrap();
+        assert!(result.degenerate_trials > 0 && result.degenerate_trials < result.trials);
+        assert_relative_eq!(result.coefficients[0], 3.0, epsilon = 1e-9);
+
+        let constant = vec![vec![1.0]; 5];
+        assert!(matches!(
+            RobustRegressionEngine::ransac_regression(&constant, &[1.0; 5], &options(3)),
+            Err(StatisticsError::Singular { .. })
+        ));
+        assert!(
+            RobustRegressionEngine::ransac_regression(&constant, &[1.0; 4], &options(3)).is_err()
+        );
+    }
+
+    /// `y = 2x + 1` on 400 points of `x` in [0, 10) with normal noise of
+    /// standard deviation 0.5, and with every tenth point raised by 30
+    /// when `contaminated`.
+    fn gaussian_line(contaminated: bool) -> (Vec<Vec<f64>>, Vec<f64>) {
+        let mut rng = Pcg64::seed_from_u64(11);
+        (0..400_u32)
+            .map(|row| {
+                let x = f64::from(row) / 40.0;
+                let noise: f64 = rng.sample(StandardNormal);
+                let outlier = if contaminated && row % 10 == 5 {
+                    30.0
+                } else {
+                    0.0
+                };
+                (
+                    vec![x],
+                    0.5_f64.mul_add(noise, 2.0_f64.mul_add(x, 1.0)) + outlier,
+                )
+            })
+            .unzip()
+    }
+
+    fn least_squares_fit(predictors: &[Vec<f64>], response: &[f64]) -> (DVector<f64>, f64) {
+        let design = design_matrix(predictors, response.len(), 2);
+        let target = DVector::from_column_slice(response);
+        let coefficients = least_squares(&design, &target).unwrap();
+        least_squares_std_errors(&design, &target, &coefficients)
+    }
+
+    #[test]
+    fn test_huber_matches_least_squares_on_clean_data() {
+        let (predictors, response) = gaussian_line(false);
+        let (std_errors, sigma) = least_squares_fit(&predictors, &response);
+        let huber = RobustRegressionEngine::huber_regression(
+            &predictors,
+            &response,
+            &MEstimatorOptions::default(),
+        )
+        .unwrap();
+
+        assert!(huber.converged);
+        assert_relative_eq!(huber.coefficients[0], 2.0, epsilon = 0.03);
+        assert_relative_eq!(
+            huber.intercept_std_error,
+            std_errors[0],
+            max_relative = 0.05
+        );
+        assert_relative_eq!(
+            huber.coefficient_std_errors[0],
+            std_errors[1],
+            max_relative = 0.05
+        );
+        assert_relative_eq!(huber.scale, sigma, max_relative = 0.1);
+    }
+
+    #[test]
+    fn test_scale_tracks_clean_noise_under_contamination() {
+        let (predictors, response) = gaussian_line(true);
+        let (_, contaminated_sigma) = least_squares_fit(&predictors, &response);
+        assert!(contaminated_sigma > 5.0);
+
+        let options = MEstimatorOptions::default();
+        let huber =
+            RobustRegressionEngine::huber_regression(&predictors, &response, &options).unwrap();
+        let tukey =
+            RobustRegressionEngine::irls_regression(&predictors, &response, &options).unwrap();
+        for fit in [&huber, &tukey] {
+            assert!(fit.converged);
+            assert_relative_eq!(fit.scale, 0.5, max_relative = 0.15);
+        }
+
+        // The biweight drops the raised rows and recovers the clean line
+        assert_relative_eq!(tukey.coefficients[0], 2.0, epsilon = 0.03);
+        assert_relative_eq!(tukey.intercept, 1.0, epsilon = 0.15);
+        assert!((0..400).all(|row| (tukey.weights[row] == 0.0) == (row % 10 == 5)));
+        assert_relative_eq!(tukey.tuning_constant, TUKEY_TUNING);
+    }
+
+    #[test]
+    fn test_m_estimator_options_are_validated() {
+        let (predictors, response) = gaussian_line(false);
+        let fit = |options: MEstimatorOptions| {
+            RobustRegressionEngine::huber_regression(&predictors, &response, &options)
+        };
+        for options in [
+            MEstimatorOptions {
+                tuning_constant: Some(0.0),
+                ..MEstimatorOptions::default()
+            },
+            MEstimatorOptions {
+                max_iterations: Some(0),
+                ..MEstimatorOptions::default()
+            },
+            MEstimatorOptions {
+                tolerance: Some(f64::NAN),
+                ..MEstimatorOptions::default()
+            },
+        ] {
+            assert!(fit(options).is_err());
+        }
+        assert!(matches!(
+            RobustRegressionEngine::irls_regression(
+                &predictors[..2],
+                &response[..2],
+                &MEstimatorOptions::default()
+            ),
+            Err(StatisticsError::InsufficientData { needed: 3, got: 2 })
+        ));
+
+        // An exact line has zero scale and unit weights
+        let exact: Vec<f64> = predictors.iter().map(|row| 3.0 * row[0]).collect();
+        let result = RobustRegressionEngine::huber_regression(
+            &predictors,
+            &exact,
+            &MEstimatorOptions::default(),
+        )
+        .unwrap();
+        assert_relative_eq!(result.coefficients[0], 3.0, epsilon = 1e-9);
+        assert!(result.scale < 1e-9);
+        assert!(result.weights.iter().all(|&weight| weight > 0.99));
+    }
+
+    #[test]
+    fn test_ties_go_to_lower_residual_median() {
+        let trial = |inlier_count: usize, residual_median: f64, intercept: f64| {
+            Some(Trial {
+                coefficients: DVector::from_vec(vec![intercept]),
+                inlier_mask: Vec::new(),
+                inlier_count,
+                residual_median,
+            })
+        };
+        let trials = [
+            trial(5, 0.3, 0.0),
+            None,
+            trial(6, 0.2, 1.0),
+            trial(6, 0.1, 2.0),
+            trial(6, 0.1, 3.0),
+        ];
+        assert_relative_eq!(best_trial(&trials).unwrap().coefficients[0], 2.0);
+    }
+}
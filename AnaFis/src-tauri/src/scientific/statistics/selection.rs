@@ -0,0 +1,344 @@
+//! Preparation of spreadsheet selections for the statistics engines.
+//!
+//! The statistics sidebar sends a rectangular selection as JSON cells. It is
+//! split into series by column or by row, the header is taken or detected,
+//! and every cell is coerced to a number or recorded as skipped with the
+//! reason. Series holding too few numbers are rejected as non-numeric. The
+//! engines then receive clean numeric series, and the coercion rules live in
+//! this one place.
+use serde_json::Value;
+
+use super::error::StatisticsError;
+use super::types::{
+    CoercionPolicy, PreparedSelection, PreparedSeries, RejectedSeries, SelectionOptions,
+    SelectionOrientation, SkipReason, SkippedCell,
+};
+
+/// Smallest fraction of numeric cells of an accepted series by default.
+const DEFAULT_MIN_NUMERIC_FRACTION: f64 = 0.8;
+
+/// Cell beyond the end of a short row.
+static EMPTY_CELL: Value = Value::Null;
+
+/// Conversion of spreadsheet selections into numeric series.
+pub struct SelectionEngine;
+
+impl SelectionEngine {
+    /// Split `grid`, a list of rows, into named numeric series.
+    ///
+    /// Short rows are padded with empty cells. Without an explicit
+    /// `has_header`, the first cells are taken as names when none of them is
+    /// numeric, at least one is text, and a later cell is numeric.
+    ///
+    /// # Errors
+    /// Returns a structured error for an empty selection or a minimum
+    /// numeric fraction outside `[0, 1]`.
+    pub fn prepare(
+        grid: &[Vec<Value>],
+        options: &SelectionOptions,
+    ) -> Result<PreparedSelection, StatisticsError> {
+        let min_numeric_fraction = options
+            .min_numeric_fraction
+            .unwrap_or(DEFAULT_MIN_NUMERIC_FRACTION);
+        if !(0.0..=1.0).contains(&min_numeric_fraction) {
+            return Err(StatisticsError::validation(
+                "minNumericFraction",
+                format!("Numeric fraction must be between 0 and 1, got {min_numeric_fraction}"),
+            ));
+        }
+        let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+        if width == 0 {
+            return Err(StatisticsError::validation(
+                "grid",
+                "Selection has no cells",
+            ));
+        }
+
+        let (label, series_cells): (&str, Vec<Vec<&Value>>) = match options.orientation {
+            SelectionOrientation::Columns => (
+                "Column",
+                (0..width)
+                    .map(|column| grid.iter().map(|row| cell_at(row, column)).collect())
+                    .collect(),
+            ),
+            SelectionOrientation::Rows => (
+                "Row",
+                grid.iter()
+                    .map(|row| (0..width).map(|column| cell_at(row, column)).collect())
+                    .collect(),
+            ),
+        };
+        let has_header = options
+            .has_header
+            .unwrap_or_else(|| detect_header(&series_cells, options.coercion));
+
+        let mut series = Vec::new();
+        let mut rejected = Vec::new();
+        for (position, cells) in series_cells.iter().enumerate() {
+            let (header, body) = match cells.split_first() {
+                Some((first, rest)) if has_header => (header_name(first), rest),
+                _ => (None, cells.as_slice()),
+            };
+            let name = header.unwrap_or_else(|| format!("{label} {}", position + 1));
+            let (prepared, non_empty_count) = prepare_series(name, body, options.coercion);
+            if non_empty_count == 0 || prepared.numeric_fraction < min_numeric_fraction {
+                rejected.push(RejectedSeries {
+                    name: prepared.name,
+                    numeric_fraction: prepared.numeric_fraction,
+                    non_empty_count,
+                });
+            } else {
+                series.push(prepared);
+            }
+        }
+
+        Ok(PreparedSelection {
+            series,
+            rejected,
+            has_header,
+            orientation: options.orientation,
+            min_numeric_fraction,
+        })
+    }
+}
+
+/// Cell `column` of `row`, empty beyond its end.
+fn cell_at(row: &[Value], column: usize) -> &Value {
+    row.get(column).unwrap_or(&EMPTY_CELL)
+}
+
+/// Coerce the cells of one series, recording those left out. Also returns
+/// the number of non-empty cells.
+fn prepare_series(
+    name: String,
+    cells: &[&Value],
+    coercion: CoercionPolicy,
+) -> (PreparedSeries, usize) {
+    let mut values = Vec::with_capacity(cells.len());
+    let mut skipped = Vec::new();
+    for (index, &cell) in cells.iter().enumerate() {
+        match coerce(cell, coercion) {
+            Ok(value) => values.push(Some(value)),
+            Err(reason) => {
+                values.push(None);
+                skipped.push(SkippedCell {
+                    index,
+                    value: cell.clone(),
+                    reason,
+                });
+            }
+        }
+    }
+    let numeric_count = values.iter().flatten().count();
+    let non_empty_count = numeric_count
+        + skipped
+            .iter()
+            .filter(|skipped| skipped.reason != SkipReason::Empty)
+            .count();
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Cell counts casting to f64 for the numeric fraction"
+    )]
+    let numeric_fraction = if non_empty_count == 0 {
+        0.0
+    } else {
+        numeric_count as f64 / non_empty_count as f64
+    };
+    let series = PreparedSeries {
+        name,
+        values,
+        numeric_count,
+        numeric_fraction,
+        skipped,
+    };
+    (series, non_empty_count)
+}
+
+/// Number held by `cell` under `coercion`, or why it is not one.
+fn coerce(cell: &Value, coercion: CoercionPolicy) -> Result<f64, SkipReason> {
+    match cell {
+        Value::Null => Err(SkipReason::Empty),
+        Value::Number(number) => number.as_f64().ok_or(SkipReason::Text),
+        Value::Bool(flag) => match coercion {
+            CoercionPolicy::NumericTextAndBool => Ok(if *flag { 1.0 } else { 0.0 }),
+            CoercionPolicy::Strict | CoercionPolicy::NumericText => Err(SkipReason::Bool),
+        },
+        Value::String(text) => {
+            let text = text.trim();
+            if text.is_empty() {
+                Err(SkipReason::Empty)
+            } else if coercion == CoercionPolicy::Strict {
+                Err(SkipReason::Text)
+            } else {
+                parse_number(text).ok_or(SkipReason::Text)
+            }
+        }
+        Value::Array(_) | Value::Object(_) => Err(SkipReason::Text),
+    }
+}
+
+/// Finite number written in `text`, accepting a single decimal comma.
+fn parse_number(text: &str) -> Option<f64> {
+    let parsed = text.parse::<f64>().ok().or_else(|| {
+        (text.matches(',').count() == 1 && !text.contains('.'))
+            .then(|| text.replace(',', "."))
+            .and_then(|decimal| decimal.parse().ok())
+    })?;
+    parsed.is_finite().then_some(parsed)
+}
+
+/// Whether the first cell of each series looks like a name: none is
+/// numeric, at least one is text, and some later cell is numeric.
+fn detect_header(series_cells: &[Vec<&Value>], coercion: CoercionPolicy) -> bool {
+    let mut any_text = false;
+    let mut any_numeric_body = false;
+    for cells in series_cells {
+        let Some((first, rest)) = cells.split_first() else {
+            continue;
+        };
+        match coerce(first, coercion) {
+            Ok(_) => return false,
+            Err(SkipReason::Text) => any_text = true,
+            Err(SkipReason::Empty | SkipReason::Bool) => {}
+        }
+        any_numeric_body |= rest.iter().any(|cell| coerce(cell, coercion).is_ok());
+    }
+    any_text && any_numeric_body
+}
+
+/// Name written in a header cell.
+fn header_name(cell: &Value) -> Option<String> {
+    match cell {
+        Value::String(text) if !text.trim().is_empty() => Some(text.trim().to_owned()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use crate::scientific::statistics::types::NamedColumn;
+    use serde_json::json;
+
+    fn grid(rows: &Value) -> Vec<Vec<Value>> {
+        serde_json::from_value(rows.clone()).unwrap()
+    }
+
+    #[test]
+    fn test_columns_with_detected_header_and_skipped_cells() {
+        let cells = grid(&json!([
+            ["Time", "Voltage", "Note"],
+            [0, "1.5", "ok"],
+            [1, null, "ok"],
+            [2, "2,5", 3],
+            [3, true, "bad"],
+            [4, 5.0, "ok"],
+            [5]
+        ]));
+        let options = SelectionOptions {
+            min_numeric_fraction: Some(0.7),
+            ..SelectionOptions::default()
+        };
+        let selection = SelectionEngine::prepare(&cells, &options).unwrap();
+        assert!(selection.has_header);
+        let names: Vec<&str> = selection.series.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Time", "Voltage"]);
+
+        let voltage = &selection.series[1];
+        assert_eq!(
+            voltage.values,
+            [Some(1.5), None, Some(2.5), None, Some(5.0), None]
+        );
+        let skipped: Vec<(usize, SkipReason)> = voltage
+            .skipped
+            .iter()
+            .map(|cell| (cell.index, cell.reason))
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                (1, SkipReason::Empty),
+                (3, SkipReason::Bool),
+                (5, SkipReason::Empty)
+            ]
+        );
+        assert_eq!(voltage.skipped[1].value, json!(true));
+        assert!((voltage.numeric_fraction - 0.75).abs() < 1e-12);
+
+        assert_eq!(selection.rejected.len(), 1);
+        assert_eq!(selection.rejected[0].name, "Note");
+        assert_eq!(selection.rejected[0].non_empty_count, 5);
+        assert!((selection.rejected[0].numeric_fraction - 0.2).abs() < 1e-12);
+
+        // Prepared series feed the multi-column commands directly
+        let column: NamedColumn =
+            serde_json::from_value(serde_json::to_value(voltage).unwrap()).unwrap();
+        assert_eq!(column.values, voltage.values);
+
+        let headerless = SelectionOptions {
+            has_header: Some(false),
+            ..options
+        };
+        let raw = SelectionEngine::prepare(&cells, &headerless).unwrap();
+        assert_eq!(raw.series[0].name, "Column 1");
+        assert_eq!(raw.series[0].skipped[0].index, 0);
+        assert_eq!(raw.series[0].skipped[0].reason, SkipReason::Text);
+        assert_eq!(raw.series[0].values[1], Some(0.0));
+    }
+
+    #[test]
+    fn test_rows_and_coercion_policies() {
+        let cells = grid(&json!([
+            ["x", 1, 2, 3],
+            ["y", "4", " 5 ", "6e0"],
+            ["z", true, false, 1]
+        ]));
+        let rows = SelectionOptions {
+            orientation: SelectionOrientation::Rows,
+            ..SelectionOptions::default()
+        };
+        let selection = SelectionEngine::prepare(&cells, &rows).unwrap();
+        assert!(selection.has_header);
+        assert_eq!(selection.series.len(), 2);
+        assert_eq!(selection.series[1].name, "y");
+        assert_eq!(
+            selection.series[1].values,
+            [Some(4.0), Some(5.0), Some(6.0)]
+        );
+        assert_eq!(selection.rejected[0].name, "z");
+
+        let strict = SelectionOptions {
+            coercion: CoercionPolicy::Strict,
+            ..rows
+        };
+        let strict_selection = SelectionEngine::prepare(&cells, &strict).unwrap();
+        let rejected: Vec<&str> = strict_selection
+            .rejected
+            .iter()
+            .map(|series| series.name.as_str())
+            .collect();
+        assert_eq!(rejected, ["y", "z"]);
+
+        let booleans = SelectionOptions {
+            coercion: CoercionPolicy::NumericTextAndBool,
+            ..rows
+        };
+        let with_booleans = SelectionEngine::prepare(&cells, &booleans).unwrap();
+        assert_eq!(
+            with_booleans.series[2].values,
+            [Some(1.0), Some(0.0), Some(1.0)]
+        );
+
+        assert!(SelectionEngine::prepare(&[], &SelectionOptions::default()).is_err());
+        let invalid = SelectionOptions {
+            min_numeric_fraction: Some(1.5),
+            ..SelectionOptions::default()
+        };
+        assert!(matches!(
+            SelectionEngine::prepare(&cells, &invalid),
+            Err(StatisticsError::Validation { field, .. }) if field == "minNumericFraction"
+        ));
+    }
+}
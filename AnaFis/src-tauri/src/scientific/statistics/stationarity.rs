@@ -0,0 +1,235 @@
+//! Unit-root testing.
+//!
+//! The augmented Dickey-Fuller test regresses `Δy_t` on a constant, `y_{t-1}`
+//! and `p` lagged differences, and compares the t-statistic of `y_{t-1}` with
+//! the response-surface approximation of its null distribution for the
+//! constant-only case.
+use nalgebra::{DMatrix, DVector};
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use super::error::StatisticsError;
+use super::types::{AdfResult, CriticalValue};
+
+/// Minimum series length for an ADF test.
+pub const MIN_ADF_OBSERVATIONS: usize = 10;
+
+/// Stationarity tests.
+pub struct StationarityEngine;
+
+impl StationarityEngine {
+    /// Augmented Dickey-Fuller test with a constant term.
+    ///
+    /// `max_lags` fixes the number of lagged differences; by default Schwert's
+    /// rule `floor(12 (n / 100)^(1/4))` is used, capped by the series length.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::InsufficientData`] for series too short for
+    /// the regression, [`StatisticsError::Validation`] for non-finite values
+    /// and [`StatisticsError::Singular`] if the regression is singular (for
+    /// example a constant series).
+    pub fn adf_test(series: &[f64], max_lags: Option<usize>) -> Result<AdfResult, StatisticsError> {
+        let n = series.len();
+        if n < MIN_ADF_OBSERVATIONS {
+            return Err(StatisticsError::InsufficientData {
+                needed: MIN_ADF_OBSERVATIONS,
+                got: n,
+            });
+        }
+        if series.iter().any(|value| !value.is_finite()) {
+            return Err(StatisticsError::validation(
+                "series",
+                "Series contains non-finite values",
+            ));
+        }
+        #[allow(clippy::integer_division, reason = "Largest lag order that fits")]
+        let lags = max_lags.unwrap_or_else(|| schwert_lags(n).min((n - 4) / 2));
+        let needed = 2 * lags + 4;
+        if n < needed {
+            return Err(StatisticsError::InsufficientData { needed, got: n });
+        }
+
+        let differences: Vec<f64> = series.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        let rows = n - 1 - lags;
+        let columns = 2 + lags;
+        // Row r describes time t = r + lags + 1.
+        let design = DMatrix::from_fn(rows, columns, |row, column| match column {
+            0 => 1.0,
+            1 => series[row + lags],
+            lag => differences[row + lags + 1 - lag],
+        });
+        let target = DVector::from_iterator(rows, (0..rows).map(|row| differences[row + lags]));
+
+        let normal = design.transpose() * &design;
+        let inverse = normal
+            .try_inverse()
+            .ok_or_else(|| StatisticsError::Singular {
+                context: "ADF regression is singular (is the series constant?)".to_owned(),
+            })?;
+        let coefficients = &inverse * design.transpose() * &target;
+        let residuals = &target - &design * &coefficients;
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Degrees of freedom casting to f64 for residual variance"
+        )]
+        let residual_variance = residuals.norm_squared() / (rows - columns) as f64;
+        let standard_error = (residual_variance * inverse[(1, 1)]).sqrt();
+        if !(standard_error.is_finite() && standard_error > 0.0) {
+            return Err(StatisticsError::Numerical {
+                context: "ADF regression has a zero residual variance".to_owned(),
+            });
+        }
+        let statistic = coefficients[1] / standard_error;
+
+        let critical_values = critical_values(rows);
+        let is_stationary = statistic < critical_values[1].value;
+        Ok(AdfResult {
+            statistic,
+            p_value: mackinnon_p_value(statistic)?,
+            lags,
+            observations: rows,
+            critical_values,
+            is_stationary,
+        })
+    }
+}
+
+/// Schwert's default lag order `floor(12 (n / 100)^(1/4))`.
+fn schwert_lags(n: usize) -> usize {
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "Lag order is a small non-negative integer"
+    )]
+    let lags = (12.0 * (n as f64 / 100.0).powf(0.25)).floor() as usize;
+    lags
+}
+
+/// Finite-sample critical values for the constant-only case.
+fn critical_values(observations: usize) -> Vec<CriticalValue> {
+    // Response-surface coefficients from MacKinnon (2010), Table 2.
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for response surface"
+    )]
+    let inverse_t = 1.0 / observations as f64;
+    [
+        (0.01, [-3.430_35_f64, -6.5393, -16.786]),
+        (0.05, [-2.861_54, -2.8903, -4.234]),
+        (0.10, [-2.566_77, -1.5384, -2.809]),
+    ]
+    .into_iter()
+    .map(|(significance, [b0, b1, b2])| CriticalValue {
+        significance,
+        value: b2.mul_add(inverse_t * inverse_t, b1.mul_add(inverse_t, b0)),
+    })
+    .collect()
+}
+
+/// Approximate asymptotic p-value for the constant-only case.
+fn mackinnon_p_value(statistic: f64) -> Result<f64, StatisticsError> {
+    // Polynomial approximation from MacKinnon (1994), one-regressor case.
+    const TAU_MAX: f64 = 2.74;
+    const TAU_MIN: f64 = -18.83;
+    const TAU_STAR: f64 = -1.61;
+    if statistic > TAU_MAX {
+        return Ok(1.0);
+    }
+    if statistic < TAU_MIN {
+        return Ok(0.0);
+    }
+    let polynomial = if statistic <= TAU_STAR {
+        0.038_269_f64
+            .mul_add(statistic, 1.4412)
+            .mul_add(statistic, 2.1659)
+    } else {
+        (-0.010_368_f64)
+            .mul_add(statistic, -0.127_45)
+            .mul_add(statistic, 0.932_02)
+            .mul_add(statistic, 1.7339)
+    };
+    let standard_normal = Normal::new(0.0, 1.0).map_err(|error| error.to_string())?;
+    Ok(standard_normal.cdf(polynomial))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use crate::error::{CommandError, codes};
+
+    /// Deterministic standard-normal-ish noise (sum of uniforms).
+    fn noise(count: usize) -> Vec<f64> {
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+        (0..count)
+            .map(|_| {
+                (0..12)
+                    .map(|_| {
+                        state ^= state << 13;
+                        state ^= state >> 7;
+                        state ^= state << 17;
+                        #[allow(
+                            clippy::cast_precision_loss,
+                            reason = "Top 53 bits form an exact uniform mantissa"
+                        )]
+                        let uniform = (state >> 11) as f64 / (1_u64 << 53) as f64;
+                        uniform
+                    })
+                    .sum::<f64>()
+                    - 6.0
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_short_series_is_insufficient_data() {
+        let error = StationarityEngine::adf_test(&[1.0, 2.0, 1.5, 3.0, 2.5], None).unwrap_err();
+        assert_eq!(
+            error,
+            StatisticsError::InsufficientData {
+                needed: MIN_ADF_OBSERVATIONS,
+                got: 5
+            }
+        );
+        let response = CommandError::from(error);
+        assert_eq!(response.code(), codes::INSUFFICIENT_DATA);
+        assert_eq!(
+            response.detail(),
+            "At least 10 observations are required, got 5"
+        );
+
+        let lagged = StationarityEngine::adf_test(&noise(12), Some(6)).unwrap_err();
+        assert_eq!(
+            lagged,
+            StatisticsError::InsufficientData {
+                needed: 16,
+                got: 12
+            }
+        );
+    }
+
+    #[test]
+    fn test_white_noise_is_stationary_and_random_walk_is_not() {
+        let shocks = noise(400);
+        let white = StationarityEngine::adf_test(&shocks, None).unwrap();
+        assert!(white.is_stationary);
+        assert!(white.p_value < 0.01);
+
+        let walk: Vec<f64> = shocks
+            .iter()
+            .scan(0.0, |level, shock| {
+                *level += shock;
+                Some(*level)
+            })
+            .collect();
+        let random_walk = StationarityEngine::adf_test(&walk, None).unwrap();
+        assert!(!random_walk.is_stationary);
+        assert!(random_walk.p_value > 0.05);
+    }
+
+    #[test]
+    fn test_p_value_matches_critical_values() {
+        let five_percent = critical_values(usize::MAX)[1].value;
+        assert!((mackinnon_p_value(five_percent).unwrap() - 0.05).abs() < 2e-3);
+    }
+}
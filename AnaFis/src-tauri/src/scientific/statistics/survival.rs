@@ -0,0 +1,328 @@
+//! Survival analysis.
+//!
+//! The Kaplan-Meier product-limit estimator with Greenwood variances. Pointwise
+//! confidence intervals use the log(-log) transform so they stay inside [0, 1];
+//! the median confidence interval inverts those bands (Brookmeyer-Crowley). Two
+//! curves are compared with the unweighted log-rank (Mantel-Cox) test, computed
+//! from the risk tables carried by each estimate.
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+
+use super::error::StatisticsError;
+use super::types::{KmResult, LogRankResult, RiskTableEntry};
+
+/// Two-sided 95 % normal quantile.
+const Z_95: f64 = 1.959_963_984_540_054;
+
+/// Survival analysis estimators and tests.
+pub struct SurvivalAnalysisEngine;
+
+impl SurvivalAnalysisEngine {
+    /// Kaplan-Meier estimate from observation `times` and event indicators
+    /// (`true` = event observed, `false` = right-censored).
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::Validation`] for mismatched lengths and
+    /// negative or non-finite times, and [`StatisticsError::InsufficientData`]
+    /// for an empty sample.
+    pub fn kaplan_meier(times: &[f64], events: &[bool]) -> Result<KmResult, StatisticsError> {
+        if times.len() != events.len() {
+            return Err(StatisticsError::validation(
+                "events",
+                format!(
+                    "Expected one event flag per time ({} times, {} flags)",
+                    times.len(),
+                    events.len()
+                ),
+            ));
+        }
+        if times.is_empty() {
+            return Err(StatisticsError::InsufficientData { needed: 1, got: 0 });
+        }
+        if times.iter().any(|time| !time.is_finite() || *time < 0.0) {
+            return Err(StatisticsError::validation(
+                "times",
+                "Survival times must be finite and non-negative",
+            ));
+        }
+
+        let risk_table = build_risk_table(times, events);
+        let mut step_times = vec![0.0];
+        let mut survival = vec![1.0];
+        let mut standard_errors = vec![0.0];
+        let mut lower_ci = vec![1.0];
+        let mut upper_ci = vec![1.0];
+        let mut current = 1.0;
+        let mut greenwood_sum = 0.0;
+        for entry in risk_table.iter().filter(|entry| entry.events > 0) {
+            #[allow(
+                clippy::cast_precision_loss,
+                reason = "Subject counts casting to f64 for the product-limit estimate"
+            )]
+            let (at_risk, deaths) = (entry.at_risk as f64, entry.events as f64);
+            current *= 1.0 - deaths / at_risk;
+            greenwood_sum += if entry.at_risk > entry.events {
+                deaths / (at_risk * (at_risk - deaths))
+            } else {
+                f64::INFINITY
+            };
+            let (lower, upper) = log_log_interval(current, greenwood_sum);
+            step_times.push(entry.time);
+            survival.push(current);
+            standard_errors.push(if greenwood_sum.is_finite() {
+                current * greenwood_sum.sqrt()
+            } else {
+                0.0
+            });
+            lower_ci.push(lower);
+            upper_ci.push(upper);
+        }
+
+        let first_at_or_below_half = |curve: &[f64]| {
+            step_times
+                .iter()
+                .zip(curve)
+                .find(|&(_, &value)| value <= 0.5)
+                .map(|(&time, _)| time)
+        };
+        let event_count = events.iter().filter(|&&event| event).count();
+        Ok(KmResult {
+            median_survival: first_at_or_below_half(&survival),
+            median_ci_lower: first_at_or_below_half(&lower_ci),
+            median_ci_upper: first_at_or_below_half(&upper_ci),
+            times: step_times,
+            survival_probabilities: survival,
+            lower_ci,
+            upper_ci,
+            standard_errors,
+            censor_times: risk_table
+                .iter()
+                .filter(|entry| entry.censored > 0)
+                .map(|entry| entry.time)
+                .collect(),
+            total_count: times.len(),
+            event_count,
+            censored_count: times.len() - event_count,
+            risk_table,
+        })
+    }
+
+    /// Log-rank test of equal hazards for two Kaplan-Meier estimates.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::Numerical`] if the pooled sample has no
+    /// events or no variance (e.g. every event happens with one subject left).
+    pub fn log_rank_test(km1: &KmResult, km2: &KmResult) -> Result<LogRankResult, StatisticsError> {
+        let mut event_times: Vec<f64> = km1
+            .risk_table
+            .iter()
+            .chain(&km2.risk_table)
+            .filter(|entry| entry.events > 0)
+            .map(|entry| entry.time)
+            .collect();
+        event_times.sort_by(f64::total_cmp);
+        event_times.dedup_by(|a, b| a.total_cmp(b).is_eq());
+
+        let mut expected_first = 0.0;
+        let mut variance = 0.0;
+        for &time in &event_times {
+            let (risk_first, deaths_first) = counts_at(&km1.risk_table, time);
+            let (risk_second, deaths_second) = counts_at(&km2.risk_table, time);
+            #[allow(
+                clippy::cast_precision_loss,
+                reason = "Subject counts casting to f64 for hypergeometric moments"
+            )]
+            let (n1, n, d) = (
+                risk_first as f64,
+                (risk_first + risk_second) as f64,
+                (deaths_first + deaths_second) as f64,
+            );
+            expected_first += d * n1 / n;
+            if n > 1.0 {
+                variance += n1 * (n - n1) * d * (n - d) / (n * n * (n - 1.0));
+            }
+        }
+
+        let observed_first = km1.event_count;
+        let observed_second = km2.event_count;
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Event counts casting to f64 for the test statistic"
+        )]
+        let (o1, o2) = (observed_first as f64, observed_second as f64);
+        let expected_second = o1 + o2 - expected_first;
+        if !(variance > 0.0 && expected_first > 0.0 && expected_second > 0.0) {
+            return Err(StatisticsError::Numerical {
+                context: "Log-rank variance is zero; the groups have no comparable events"
+                    .to_owned(),
+            });
+        }
+
+        let chi_square = (o1 - expected_first).powi(2) / variance;
+        let p_value = ChiSquared::new(1.0)
+            .map_err(|error| StatisticsError::from(error.to_string()))?
+            .sf(chi_square);
+        let hazard_ratio = (o1 / expected_first) / (o2 / expected_second);
+        let log_standard_error = (1.0 / expected_first + 1.0 / expected_second).sqrt();
+        Ok(LogRankResult {
+            chi_square,
+            p_value,
+            observed_first,
+            expected_first,
+            observed_second,
+            expected_second,
+            hazard_ratio,
+            hazard_ratio_ci_lower: hazard_ratio * (-Z_95 * log_standard_error).exp(),
+            hazard_ratio_ci_upper: hazard_ratio * (Z_95 * log_standard_error).exp(),
+        })
+    }
+}
+
+/// Groups observations by distinct time, in increasing order.
+fn build_risk_table(times: &[f64], events: &[bool]) -> Vec<RiskTableEntry> {
+    let mut order: Vec<usize> = (0..times.len()).collect();
+    order.sort_by(|&a, &b| times[a].total_cmp(&times[b]));
+
+    let mut table: Vec<RiskTableEntry> = Vec::new();
+    let mut at_risk = times.len();
+    for idx in order {
+        let is_new_time = table
+            .last()
+            .is_none_or(|entry| !entry.time.total_cmp(&times[idx]).is_eq());
+        if is_new_time {
+            if let Some(previous) = table.last() {
+                at_risk -= previous.events + previous.censored;
+            }
+            table.push(RiskTableEntry {
+                time: times[idx],
+                at_risk,
+                events: 0,
+                censored: 0,
+            });
+        }
+        if let Some(entry) = table.last_mut() {
+            if events[idx] {
+                entry.events += 1;
+            } else {
+                entry.censored += 1;
+            }
+        }
+    }
+    table
+}
+
+/// Subjects at risk just before `time` and events at `time`.
+fn counts_at(table: &[RiskTableEntry], time: f64) -> (usize, usize) {
+    table
+        .iter()
+        .find(|entry| entry.time >= time)
+        .map_or((0, 0), |entry| {
+            let deaths = if entry.time.total_cmp(&time).is_eq() {
+                entry.events
+            } else {
+                0
+            };
+            (entry.at_risk, deaths)
+        })
+}
+
+/// 95 % interval for `survival` on the log(-log) scale, with Greenwood's
+/// `sum d / (n (n - d))` as `greenwood_sum`.
+fn log_log_interval(survival: f64, greenwood_sum: f64) -> (f64, f64) {
+    if survival <= 0.0 || !greenwood_sum.is_finite() {
+        return (0.0, 0.0);
+    }
+    if survival >= 1.0 {
+        return (1.0, 1.0);
+    }
+    let log_survival = survival.ln();
+    let spread = Z_95 * greenwood_sum.sqrt() / log_survival.abs();
+    (survival.powf(spread.exp()), survival.powf((-spread).exp()))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use statrs::distribution::Normal;
+
+    /// Freireich et al. (1963) leukaemia remission times, 6-MP arm.
+    fn treated() -> (Vec<f64>, Vec<bool>) {
+        let times = vec![
+            6.0, 6.0, 6.0, 6.0, 7.0, 9.0, 10.0, 10.0, 11.0, 13.0, 16.0, 17.0, 19.0, 20.0, 22.0,
+            23.0, 25.0, 32.0, 32.0, 34.0, 35.0,
+        ];
+        let events = vec![
+            true, true, true, false, true, false, true, false, false, true, true, false, false,
+            false, true, true, false, false, false, false, false,
+        ];
+        (times, events)
+    }
+
+    /// Placebo arm of the same trial; every remission ended in relapse.
+    fn placebo() -> (Vec<f64>, Vec<bool>) {
+        let times = vec![
+            1.0, 1.0, 2.0, 2.0, 3.0, 4.0, 4.0, 5.0, 5.0, 8.0, 8.0, 8.0, 8.0, 11.0, 11.0, 12.0,
+            12.0, 15.0, 17.0, 22.0, 23.0,
+        ];
+        (times, vec![true; 21])
+    }
+
+    #[test]
+    fn test_kaplan_meier_matches_reference_curve() {
+        assert!((Normal::standard().inverse_cdf(0.975) - Z_95).abs() < 1e-12);
+        let (times, events) = treated();
+        let km = SurvivalAnalysisEngine::kaplan_meier(&times, &events).unwrap();
+
+        assert_eq!(km.times, vec![0.0, 6.0, 7.0, 10.0, 13.0, 16.0, 22.0, 23.0]);
+        let reference = [1.0, 0.8571, 0.8067, 0.7529, 0.6902, 0.6275, 0.5378, 0.4482];
+        for (value, expected) in km.survival_probabilities.iter().zip(reference) {
+            assert!((value - expected).abs() < 1e-4, "{value} vs {expected}");
+        }
+        // Greenwood standard errors and log(-log) limits at t = 6.
+        assert!((km.standard_errors[1] - 0.0764).abs() < 1e-4);
+        assert!((km.lower_ci[1] - 0.620).abs() < 1e-3);
+        assert!((km.upper_ci[1] - 0.952).abs() < 1e-3);
+        assert_eq!(km.median_survival, Some(23.0));
+        assert_eq!(km.median_ci_lower, Some(13.0));
+        assert_eq!(km.median_ci_upper, None);
+        assert_eq!(
+            (km.total_count, km.event_count, km.censored_count),
+            (21, 9, 12)
+        );
+        assert_eq!(km.risk_table[0].at_risk, 21);
+        assert_eq!(km.censor_times.len(), 11);
+    }
+
+    #[test]
+    fn test_log_rank_separates_treatment_arms() {
+        let (times, events) = treated();
+        let km_treated = SurvivalAnalysisEngine::kaplan_meier(&times, &events).unwrap();
+        let (placebo_times, placebo_events) = placebo();
+        let km_placebo =
+            SurvivalAnalysisEngine::kaplan_meier(&placebo_times, &placebo_events).unwrap();
+        assert_eq!(km_placebo.median_survival, Some(8.0));
+
+        let test = SurvivalAnalysisEngine::log_rank_test(&km_treated, &km_placebo).unwrap();
+        assert!(
+            (test.chi_square - 16.79).abs() < 0.01,
+            "{}",
+            test.chi_square
+        );
+        assert!((test.expected_first - 19.25).abs() < 0.01);
+        assert!(test.p_value < 1e-4);
+        assert!(test.hazard_ratio < 1.0 && test.hazard_ratio_ci_upper < 1.0);
+    }
+
+    #[test]
+    fn test_rejects_mismatched_or_invalid_input() {
+        assert!(matches!(
+            SurvivalAnalysisEngine::kaplan_meier(&[1.0, 2.0], &[true]),
+            Err(StatisticsError::Validation { .. })
+        ));
+        assert!(matches!(
+            SurvivalAnalysisEngine::kaplan_meier(&[], &[]),
+            Err(StatisticsError::InsufficientData { .. })
+        ));
+        assert!(SurvivalAnalysisEngine::kaplan_meier(&[-1.0], &[true]).is_err());
+    }
+}
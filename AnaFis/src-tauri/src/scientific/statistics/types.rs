@@ -0,0 +1,1291 @@
+use serde::{Deserialize, Serialize};
+
+use crate::scientific::distributions::FittedParameter;
+
+/// Critical value of a test statistic at a significance level.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CriticalValue {
+    /// Significance level (e.g. 0.05).
+    pub significance: f64,
+    /// Statistic value below which the null hypothesis is rejected.
+    pub value: f64,
+}
+
+/// Output of an augmented Dickey-Fuller unit-root test.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdfResult {
+    /// t-statistic of the lagged level coefficient.
+    pub statistic: f64,
+    /// Approximate asymptotic p-value.
+    pub p_value: f64,
+    /// Number of lagged differences in the test regression.
+    pub lags: usize,
+    /// Number of observations used in the test regression.
+    pub observations: usize,
+    /// 1 %, 5 % and 10 % critical values.
+    pub critical_values: Vec<CriticalValue>,
+    /// Whether the unit-root null is rejected at the 5 % level.
+    pub is_stationary: bool,
+}
+
+/// Counts at one distinct observation time of a survival sample.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskTableEntry {
+    /// Observation time.
+    pub time: f64,
+    /// Subjects still at risk just before `time`.
+    pub at_risk: usize,
+    /// Events at `time`.
+    pub events: usize,
+    /// Censored observations at `time`.
+    pub censored: usize,
+}
+
+/// Kaplan-Meier estimate of a survival function.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KmResult {
+    /// Step times: 0 followed by every distinct event time.
+    pub times: Vec<f64>,
+    /// Survival probability from each step time until the next.
+    pub survival_probabilities: Vec<f64>,
+    /// Lower bound of the pointwise 95 % confidence interval.
+    pub lower_ci: Vec<f64>,
+    /// Upper bound of the pointwise 95 % confidence interval.
+    pub upper_ci: Vec<f64>,
+    /// Greenwood standard error of the survival probability.
+    pub standard_errors: Vec<f64>,
+    /// Times of censored observations, for plot tick marks.
+    pub censor_times: Vec<f64>,
+    /// Earliest time at which survival drops to 0.5 or below.
+    pub median_survival: Option<f64>,
+    /// Lower limit of the 95 % confidence interval for the median.
+    pub median_ci_lower: Option<f64>,
+    /// Upper limit of the 95 % confidence interval for the median.
+    pub median_ci_upper: Option<f64>,
+    /// Number of subjects.
+    pub total_count: usize,
+    /// Number of observed events.
+    pub event_count: usize,
+    /// Number of censored observations.
+    pub censored_count: usize,
+    /// At-risk, event and censoring counts at every distinct observation time.
+    pub risk_table: Vec<RiskTableEntry>,
+}
+
+/// Log-rank (Mantel-Cox) comparison of two survival curves.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRankResult {
+    /// Chi-square statistic with one degree of freedom.
+    pub chi_square: f64,
+    /// Upper-tail p-value.
+    pub p_value: f64,
+    /// Observed events in the first group.
+    pub observed_first: usize,
+    /// Expected events in the first group under the null hypothesis.
+    pub expected_first: f64,
+    /// Observed events in the second group.
+    pub observed_second: usize,
+    /// Expected events in the second group under the null hypothesis.
+    pub expected_second: f64,
+    /// Hazard ratio of the first group relative to the second
+    /// (`(O1 / E1) / (O2 / E2)`).
+    pub hazard_ratio: f64,
+    /// Lower limit of the 95 % confidence interval for the hazard ratio.
+    pub hazard_ratio_ci_lower: f64,
+    /// Upper limit of the 95 % confidence interval for the hazard ratio.
+    pub hazard_ratio_ci_upper: f64,
+}
+
+/// Principal component analysis of a data matrix.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PcaResult {
+    /// Variance along each retained component, in decreasing order.
+    pub eigenvalues: Vec<f64>,
+    /// Share of the total variance explained by each retained component.
+    pub explained_variance_ratio: Vec<f64>,
+    /// Unit loading vector of each component over the variables; the largest
+    /// entry of each is positive.
+    pub loadings: Vec<Vec<f64>>,
+    /// Column means removed before the decomposition.
+    pub means: Vec<f64>,
+}
+
+/// Outlier-resistant principal component analysis by projection pursuit.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RobustPcaResult {
+    /// Robust components. Eigenvalues are squared M-scales of the projected
+    /// data and `means` holds the coordinate-wise medians.
+    #[serde(flatten)]
+    pub robust: PcaResult,
+    /// Weight of each observation in `[0, 1]`; outliers are close to zero.
+    pub robustness_weights: Vec<f64>,
+    /// Classical PCA of the same data, for comparison.
+    pub classical: PcaResult,
+}
+
+/// Bootstrap uncertainty of a principal component analysis.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PcaBootstrapResult {
+    /// Point estimates from the full data.
+    pub estimate: PcaResult,
+    /// Lower bound of the 95 % percentile interval of each eigenvalue.
+    pub eigenvalue_ci_lower: Vec<f64>,
+    /// Upper bound of the 95 % percentile interval of each eigenvalue.
+    pub eigenvalue_ci_upper: Vec<f64>,
+    /// Lower bound of the 95 % percentile interval of each loading.
+    pub loading_ci_lower: Vec<Vec<f64>>,
+    /// Upper bound of the 95 % percentile interval of each loading.
+    pub loading_ci_upper: Vec<Vec<f64>>,
+    /// Bootstrap standard error of each loading.
+    pub loading_se: Vec<Vec<f64>>,
+    /// Whether the loading's interval excludes zero.
+    pub loading_stable: Vec<Vec<bool>>,
+    /// Number of bootstrap resamples.
+    pub n_bootstrap: usize,
+}
+
+/// Rotation applied to factor loadings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FactorRotation {
+    /// Keep the unrotated maximum-likelihood loadings.
+    #[default]
+    None,
+    /// Orthogonal rotation maximising the variance of squared loadings.
+    Varimax,
+    /// Oblique direct quartimin rotation; factors may correlate.
+    Oblimin,
+}
+
+/// Exploratory factor analysis by maximum likelihood.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EfaResult {
+    /// Loadings before rotation, one vector per factor over the variables.
+    pub unrotated_loadings: Vec<Vec<f64>>,
+    /// Loadings after rotation (pattern loadings for oblique rotations).
+    pub rotated_loadings: Vec<Vec<f64>>,
+    /// Share of each variable's variance explained by the common factors.
+    pub communalities: Vec<f64>,
+    /// Share of each variable's variance left to its unique factor.
+    pub uniquenesses: Vec<f64>,
+    /// Correlations between rotated factors; `None` for orthogonal rotations.
+    pub factor_correlations: Option<Vec<Vec<f64>>>,
+    /// Rotation that produced `rotated_loadings`.
+    pub rotation: FactorRotation,
+    /// Bartlett-corrected likelihood-ratio statistic of the factor model.
+    pub chi_square: f64,
+    /// Degrees of freedom of the chi-square test.
+    pub degrees_of_freedom: usize,
+    /// p-value of the chi-square test; `None` when the model is saturated.
+    pub p_value: Option<f64>,
+    /// Gradient-descent iterations used for the uniquenesses.
+    pub iterations: usize,
+    /// Whether the uniquenesses converged before the iteration limit.
+    pub converged: bool,
+}
+
+/// Horn's parallel analysis for choosing the number of factors.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParallelAnalysisResult {
+    /// Eigenvalues of the observed correlation matrix, in decreasing order.
+    pub observed_eigenvalues: Vec<f64>,
+    /// Mean eigenvalues of correlation matrices of random normal data.
+    pub random_mean_eigenvalues: Vec<f64>,
+    /// 95th percentile of the random eigenvalues, used as the threshold.
+    pub random_percentile_eigenvalues: Vec<f64>,
+    /// Leading observed eigenvalues above their threshold.
+    pub n_factors: usize,
+}
+
+/// Covariance estimate shrunk towards a scaled identity.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedoitWolfResult {
+    /// Shrunk covariance matrix, row by row.
+    pub covariance: Vec<Vec<f64>>,
+    /// Weight of the scaled-identity target, in `[0, 1]`.
+    pub shrinkage: f64,
+    /// Correlation matrix implied by the shrunk covariance.
+    pub correlation: Vec<Vec<f64>>,
+}
+
+/// Distribution assumed for a non-normal capability analysis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CapabilityDistribution {
+    /// Two-parameter Weibull.
+    Weibull,
+    /// Log-normal.
+    LogNormal,
+    /// Gamma.
+    Gamma,
+}
+
+/// Process capability from the quantiles of a fitted non-normal distribution
+/// (Clements' percentile method).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NonnormalCapabilityResult {
+    /// Distribution fitted to the data.
+    pub distribution: CapabilityDistribution,
+    /// Maximum-likelihood parameters of the fitted distribution.
+    pub parameters: Vec<FittedParameter>,
+    /// 0.135 % quantile, the counterpart of `mean - 3 sigma`.
+    pub lower_quantile: f64,
+    /// Median of the fitted distribution.
+    pub median: f64,
+    /// 99.865 % quantile, the counterpart of `mean + 3 sigma`.
+    pub upper_quantile: f64,
+    /// Potential capability `(usl - lsl) / (upper_quantile - lower_quantile)`.
+    pub cnp: f64,
+    /// Capability accounting for location: the smaller of the upper and lower
+    /// one-sided indices measured from the median.
+    pub cnpk: f64,
+    /// Expected parts per million below the lower specification limit.
+    pub ppm_below_lsl: f64,
+    /// Expected parts per million above the upper specification limit.
+    pub ppm_above_usl: f64,
+    /// Expected parts per million outside the specification limits.
+    pub ppm_total: f64,
+}
+
+/// Tabular CUSUM chart for detecting small sustained shifts from a target.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CusumChartResult {
+    /// Upper CUSUM `C+_i = max(0, C+_{i-1} + x_i - target - k)`.
+    pub upper: Vec<f64>,
+    /// Lower CUSUM `C-_i = min(0, C-_{i-1} + x_i - target + k)`.
+    pub lower: Vec<f64>,
+    /// Indices where `C+_i > h` or `C-_i < -h`.
+    pub signal_indices: Vec<usize>,
+    /// Process standard deviation estimated from the mean moving range.
+    pub sigma_estimate: f64,
+    /// Reference value `0.5 sigma`, tuned to detect a one-sigma shift.
+    pub recommended_k: f64,
+    /// Decision interval `5 sigma`, giving an in-control ARL of about 370.
+    pub recommended_h: f64,
+    /// Equivalent V-mask chart, when requested.
+    pub v_mask: Option<VMaskResult>,
+}
+
+/// V-mask applied to the plain cumulative sum of deviations from target.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VMaskResult {
+    /// Cumulative sum `S_i` of `x_j - target`.
+    pub cumulative_sum: Vec<f64>,
+    /// Distance `h / k` from the mask vertex to the current point.
+    pub lead_distance: f64,
+    /// Slope `k` of the mask arms per observation.
+    pub slope: f64,
+    /// Indices where an earlier point of the sum falls outside the mask arms.
+    pub signal_indices: Vec<usize>,
+}
+
+/// Exponentially weighted moving average chart.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EwmaChartResult {
+    /// EWMA `z_i = lambda x_i + (1 - lambda) z_{i-1}`, starting from the mean.
+    pub ewma: Vec<f64>,
+    /// Centre line, the sample mean.
+    pub center_line: f64,
+    /// Upper control limit at each observation; widens towards steady state.
+    pub upper_limits: Vec<f64>,
+    /// Lower control limit at each observation.
+    pub lower_limits: Vec<f64>,
+    /// Indices where the EWMA falls outside its limits.
+    pub signal_indices: Vec<usize>,
+    /// Process standard deviation estimated from the mean moving range.
+    pub sigma_estimate: f64,
+    /// Smoothing weight of the newest observation.
+    pub lambda: f64,
+    /// Width of the limits in standard deviations of the EWMA.
+    pub l_sigma: f64,
+    /// In-control average run length with steady-state limits.
+    pub steady_state_arl: f64,
+}
+
+/// One source of variation in an analysis-of-variance table.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnovaRow {
+    /// Source of variation.
+    pub source: String,
+    /// Degrees of freedom.
+    pub degrees_of_freedom: usize,
+    /// Sum of squares.
+    pub sum_of_squares: f64,
+    /// Mean square; `None` for the total row.
+    pub mean_square: Option<f64>,
+    /// F statistic, for tested sources.
+    pub f_statistic: Option<f64>,
+    /// p-value of the F test, for tested sources.
+    pub p_value: Option<f64>,
+}
+
+/// Variance component of a measurement system study.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VarianceComponent {
+    /// Source of variation.
+    pub source: String,
+    /// Estimated variance, truncated at zero.
+    pub variance: f64,
+    /// Share of the total variance, in percent.
+    pub percent_contribution: f64,
+    /// Study variation `6 sigma`.
+    pub study_variation: f64,
+    /// Study variation as a percentage of the total study variation.
+    pub percent_study_variation: f64,
+    /// Study variation as a percentage of the tolerance, when given.
+    pub percent_tolerance: Option<f64>,
+}
+
+/// AIAG verdict on a measurement system from its %GRR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GageRrAssessment {
+    /// %GRR below 10 %.
+    Acceptable,
+    /// %GRR from 10 % to 30 %; may be acceptable depending on the application.
+    Conditional,
+    /// %GRR above 30 %.
+    Unacceptable,
+}
+
+/// Crossed gage repeatability and reproducibility study by the ANOVA method.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GageRrResult {
+    /// ANOVA table: part, operator, part x operator (unless pooled),
+    /// repeatability and total.
+    pub anova: Vec<AnovaRow>,
+    /// Whether the part x operator interaction (p >= 0.25) was pooled into
+    /// repeatability.
+    pub interaction_pooled: bool,
+    /// Repeatability, reproducibility (operator and interaction), total gage
+    /// R&R, part-to-part and total variation.
+    pub variance_components: Vec<VarianceComponent>,
+    /// Gage R&R study variation as a percentage of the total variation.
+    pub percent_grr_total_variation: f64,
+    /// Gage R&R study variation as a percentage of the tolerance.
+    pub percent_grr_tolerance: Option<f64>,
+    /// Number of distinct categories `floor(1.41 sigma_part / sigma_grr)`.
+    pub ndc: usize,
+    /// Verdict from %GRR of tolerance when given, else of total variation.
+    pub assessment: GageRrAssessment,
+}
+
+/// Statistics of one scale item.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemStatistics {
+    /// Position of the item in the input.
+    pub index: usize,
+    /// Mean score.
+    pub mean: f64,
+    /// Sample standard deviation of the scores.
+    pub std_dev: f64,
+    /// Correlation with the total score, the item included.
+    pub item_total_correlation: Option<f64>,
+    /// Correlation with the total of the other items.
+    pub item_rest_correlation: Option<f64>,
+    /// Cronbach's alpha of the scale without this item.
+    pub alpha_if_deleted: f64,
+    /// Whether removing the item would raise alpha.
+    pub degrades_reliability: bool,
+}
+
+/// Item analysis of a multi-item scale.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemAnalysisResult {
+    /// Cronbach's alpha of the full scale.
+    pub alpha: f64,
+    /// Per-item statistics, in input order.
+    pub items: Vec<ItemStatistics>,
+    /// Indices of items whose removal would raise alpha.
+    pub flagged_items: Vec<usize>,
+}
+
+/// Disagreement weighting of Cohen's kappa.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KappaWeights {
+    /// Only exact agreement counts.
+    #[default]
+    None,
+    /// Partial credit falling linearly with the category distance.
+    Linear,
+    /// Partial credit falling with the squared category distance.
+    Quadratic,
+}
+
+/// Chance-corrected agreement between raters.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KappaResult {
+    /// Kappa `(Po - Pe) / (1 - Pe)`.
+    pub kappa: f64,
+    /// Observed (weighted) agreement `Po`.
+    pub observed_agreement: f64,
+    /// Agreement expected by chance `Pe`.
+    pub expected_agreement: f64,
+    /// Asymptotic standard error of kappa.
+    pub standard_error: f64,
+    /// Lower limit of the 95 % confidence interval.
+    pub ci_lower: f64,
+    /// Upper limit of the 95 % confidence interval.
+    pub ci_upper: f64,
+    /// Landis and Koch strength of agreement, e.g. "Substantial".
+    pub interpretation: String,
+}
+
+/// Output of a two-sided Mann-Whitney U test.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MannWhitneyResult {
+    /// U statistic of the first sample.
+    pub u_statistic: f64,
+    /// Continuity- and tie-corrected normal score of U.
+    pub z_score: f64,
+    /// Two-sided p-value from the normal approximation.
+    pub p_value: f64,
+    /// Rank-biserial correlation `2U / (n1 * n2) - 1`.
+    pub rank_biserial: f64,
+}
+
+/// Outcome of a normality test.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalityTestResult {
+    /// Test statistic (for Anderson-Darling the small-sample corrected `A*^2`).
+    pub statistic: f64,
+    /// p-value of the normality null hypothesis.
+    pub p_value: f64,
+}
+
+/// One test of a normality test bundle.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalityTestOutcome {
+    /// Test name.
+    pub test: String,
+    /// Test statistic; `None` when skipped.
+    pub statistic: Option<f64>,
+    /// p-value of the normality null hypothesis; `None` when skipped.
+    pub p_value: Option<f64>,
+    /// Whether the p-value is below the bundle's alpha; `None` when skipped.
+    pub rejects_normality: Option<bool>,
+    /// Why the test did not run.
+    pub skipped_reason: Option<String>,
+}
+
+/// Point of a normal quantile-quantile plot.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QqPoint {
+    /// Standard normal quantile.
+    pub theoretical: f64,
+    /// Sample order statistic.
+    pub sample: f64,
+}
+
+/// Normality tests of one sample with its shape and Q-Q plot data.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalityBundleResult {
+    /// Number of observations.
+    pub count: usize,
+    /// Significance level of the verdicts.
+    pub alpha: f64,
+    /// Shapiro-Wilk, Anderson-Darling, Jarque-Bera and D'Agostino K^2, run or
+    /// skipped.
+    pub tests: Vec<NormalityTestOutcome>,
+    /// Sample skewness (biased estimate).
+    pub skewness: f64,
+    /// Sample excess kurtosis (biased estimate).
+    pub excess_kurtosis: f64,
+    /// Sorted sample against standard normal quantiles.
+    pub qq_points: Vec<QqPoint>,
+    /// Whether no strict majority of the tests that ran rejects normality.
+    pub consistent_with_normal: bool,
+}
+
+/// Unit-variance distribution the samples of a power simulation are drawn from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SimulationDistribution {
+    /// Standard normal.
+    #[default]
+    Normal,
+    /// Uniform, lighter tails than normal.
+    Uniform,
+    /// Laplace (double exponential), heavy tails.
+    Laplace,
+    /// Logistic, slightly heavier tails than normal.
+    Logistic,
+    /// Exponential, strongly right-skewed.
+    Exponential,
+}
+
+/// One-way analysis of variance across groups.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OneWayAnovaResult {
+    /// ANOVA table: between groups, within groups and total.
+    pub table: Vec<AnovaRow>,
+    /// F statistic of the between-group mean square over the within-group one.
+    pub f_statistic: f64,
+    /// p-value of the equal-means null hypothesis.
+    pub p_value: f64,
+}
+
+/// Levels of one factor, in order of first appearance.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FactorLevels {
+    /// Factor name.
+    pub name: String,
+    /// Distinct levels.
+    pub levels: Vec<String>,
+}
+
+/// Observations sharing one combination of factor levels.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnovaCell {
+    /// Level of each factor of the term, in term order.
+    pub levels: Vec<String>,
+    /// Number of observations.
+    pub count: usize,
+    /// Mean response; `None` for an empty cell.
+    pub mean: Option<f64>,
+    /// Standard error of the mean; `None` below two observations.
+    pub standard_error: Option<f64>,
+}
+
+/// Cell summaries of one ANOVA term, for main-effect and interaction plots.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TermCellMeans {
+    /// Term name, its factors joined by `:`.
+    pub term: String,
+    /// Factors of the term.
+    pub factors: Vec<String>,
+    /// Every level combination, the first factor varying slowest.
+    pub cells: Vec<AnovaCell>,
+}
+
+/// Factorial analysis of variance with Type II sums of squares.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FactorialAnovaResult {
+    /// ANOVA table: main effects, then interactions by order, then the
+    /// residuals and total rows.
+    pub table: Vec<AnovaRow>,
+    /// Partial eta-squared of each term, in table order.
+    pub partial_eta_squared: Vec<f64>,
+    /// Factors and their levels.
+    pub factors: Vec<FactorLevels>,
+    /// Cell summaries of each term, in table order.
+    pub cell_means: Vec<TermCellMeans>,
+    /// Combinations of all factor levels without observations.
+    pub missing_cells: Vec<Vec<String>>,
+    /// Whether every combination of all factor levels has the same count.
+    pub balanced: bool,
+    /// Observations used.
+    pub observations: usize,
+    /// Rows skipped for a missing response or factor level.
+    pub skipped_rows: usize,
+    /// Caveats about the design.
+    pub warnings: Vec<String>,
+}
+
+/// Summary of the values sharing one group key.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupSummary {
+    /// Group key.
+    pub key: String,
+    /// Number of finite values.
+    pub count: usize,
+    /// Arithmetic mean.
+    pub mean: f64,
+    /// Inverse-variance weighted mean when uncertainties are given, else the
+    /// arithmetic mean.
+    pub weighted_mean: f64,
+    /// Standard error of `weighted_mean`: `1 / sqrt(sum w)` with
+    /// uncertainties, else `s / sqrt(n)` (`None` for a single value).
+    pub weighted_mean_standard_error: Option<f64>,
+    /// Median.
+    pub median: f64,
+    /// Sample standard deviation; `None` for a single value.
+    pub std_dev: Option<f64>,
+    /// Smallest value.
+    pub min: f64,
+    /// Largest value.
+    pub max: f64,
+    /// Whether the group has fewer values than the requested minimum size.
+    pub below_min_size: bool,
+}
+
+/// Per-group summaries with the between-group comparison.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupedStatisticsResult {
+    /// Groups in order of first appearance of their key.
+    pub groups: Vec<GroupSummary>,
+    /// Pooled within-group variance `SS_within / (N - k)`; `None` when every
+    /// group has a single value.
+    pub pooled_within_variance: Option<f64>,
+    /// One-way ANOVA across all groups; `None` with fewer than two groups or
+    /// no within-group degrees of freedom.
+    pub anova: Option<OneWayAnovaResult>,
+}
+
+/// How missing (null or blank) categories are handled when cross-tabulating.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MissingCategoryPolicy {
+    /// Skip rows where either category is missing.
+    #[default]
+    Drop,
+    /// Count missing values as their own category.
+    Category,
+}
+
+/// Weighted counts of two categorical columns.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContingencyTable {
+    /// Categories of the first column, in order of first appearance.
+    pub row_categories: Vec<String>,
+    /// Categories of the second column, in order of first appearance.
+    pub column_categories: Vec<String>,
+    /// `counts[row][column]`, summed weights of each category pair.
+    pub counts: Vec<Vec<f64>>,
+    /// Rows skipped for a missing category or a zero weight.
+    pub skipped_rows: usize,
+}
+
+/// Pearson chi-square test of independence of a contingency table.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChiSquareIndependenceResult {
+    /// Chi-square statistic, continuity corrected when `continuity_corrected`.
+    pub statistic: f64,
+    /// Degrees of freedom `(rows - 1) (columns - 1)`.
+    pub degrees_of_freedom: usize,
+    /// p-value of the independence null hypothesis.
+    pub p_value: f64,
+    /// Whether Yates' continuity correction was applied (2x2 tables only).
+    pub continuity_corrected: bool,
+    /// Cramer's V from the uncorrected statistic, in `[0, 1]`.
+    pub cramers_v: f64,
+    /// Expected counts under independence.
+    pub expected: Vec<Vec<f64>>,
+    /// Standardized residuals `(observed - expected) / sqrt(expected)`.
+    pub standardized_residuals: Vec<Vec<f64>>,
+    /// Number of cells with an expected count below 5.
+    pub low_expected_cells: usize,
+}
+
+/// Contingency table of two categorical columns with its independence tests.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrosstabResult {
+    /// Weighted counts.
+    pub table: ContingencyTable,
+    /// Chi-square test of independence.
+    pub chi_square: ChiSquareIndependenceResult,
+    /// Two-sided Fisher exact p-value, for 2x2 tables of whole counts.
+    pub fisher_exact_p_value: Option<f64>,
+    /// Caveats about the validity of the chi-square approximation.
+    pub warnings: Vec<String>,
+}
+
+/// Output of a two-sided Welch two-sample t-test.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WelchTTestResult {
+    /// t statistic of `mean1 - mean2`.
+    pub t_statistic: f64,
+    /// Welch-Satterthwaite degrees of freedom.
+    pub degrees_of_freedom: f64,
+    /// Two-sided p-value.
+    pub p_value: f64,
+    /// Difference of the sample means, first minus second.
+    pub mean_difference: f64,
+}
+
+/// Named measurement column of a multi-column analysis; missing values are
+/// `None`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedColumn {
+    /// Column name.
+    pub name: String,
+    /// Values, row by row.
+    pub values: Vec<Option<f64>>,
+}
+
+/// Adjustment of the p-values of a family of tests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MultipleComparisonAdjustment {
+    /// Multiply by the number of tests (family-wise error rate).
+    Bonferroni,
+    /// Holm step-down (family-wise error rate, uniformly more powerful than
+    /// Bonferroni).
+    #[default]
+    Holm,
+    /// Benjamini-Hochberg step-up (false discovery rate).
+    BenjaminiHochberg,
+}
+
+/// Distance between two clusters of a hierarchical clustering.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ClusterLinkage {
+    /// Distance of the closest members.
+    Single,
+    /// Distance of the farthest members.
+    #[default]
+    Complete,
+}
+
+/// Two-sample test chosen for a pair of columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PairwiseTest {
+    /// Welch t-test, when both columns are consistent with normality.
+    WelchT,
+    /// Mann-Whitney U test otherwise.
+    MannWhitney,
+}
+
+/// Summary of one column of a multi-column analysis.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnAnalysis {
+    /// Descriptive statistics, keyed by the column name.
+    pub summary: GroupSummary,
+    /// Shapiro-Wilk p-value; `None` when the test could not run.
+    pub shapiro_wilk_p_value: Option<f64>,
+    /// Whether Shapiro-Wilk does not reject normality at the report's alpha.
+    pub consistent_with_normal: bool,
+}
+
+/// Two-sample test of a pair of columns.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairwiseComparison {
+    /// Index of the first column in [`MultiColumnReport::columns`].
+    pub first: usize,
+    /// Index of the second column in [`MultiColumnReport::columns`].
+    pub second: usize,
+    /// Test chosen by the normality pre-check.
+    pub test: PairwiseTest,
+    /// t statistic or Mann-Whitney z score.
+    pub statistic: f64,
+    /// Unadjusted two-sided p-value.
+    pub p_value: f64,
+    /// p-value adjusted for the number of comparisons.
+    pub adjusted_p_value: f64,
+    /// Whether the adjusted p-value is below alpha.
+    pub significant: bool,
+}
+
+/// Merge step of an agglomerative clustering. Columns are clusters
+/// `0..n`; the cluster formed at step `k` is `n + k`.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterMerge {
+    /// First merged cluster.
+    pub first: usize,
+    /// Second merged cluster.
+    pub second: usize,
+    /// Linkage distance at which they merge.
+    pub distance: f64,
+    /// Number of columns in the merged cluster.
+    pub size: usize,
+}
+
+/// Descriptive statistics, correlations, pairwise tests and a clustering of
+/// several columns.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiColumnReport {
+    /// Columns with enough observations, in input order.
+    pub columns: Vec<ColumnAnalysis>,
+    /// Names of the columns left out for having too few observations.
+    pub excluded_columns: Vec<String>,
+    /// Significance level of the normality pre-check and the pairwise tests.
+    pub alpha: f64,
+    /// Pearson correlation matrix over the rows where every column has a
+    /// value; `None` when it cannot be computed.
+    pub correlation_matrix: Option<Vec<Vec<f64>>>,
+    /// Rows used for the correlation matrix.
+    pub correlation_observations: usize,
+    /// Adjustment applied to the pairwise p-values.
+    pub adjustment: MultipleComparisonAdjustment,
+    /// Tests of every pair of columns that could be compared.
+    pub comparisons: Vec<PairwiseComparison>,
+    /// Symmetric matrix of adjusted p-values for a heatmap: 1 on the
+    /// diagonal, `None` for pairs that could not be tested.
+    pub comparison_matrix: Vec<Vec<Option<f64>>>,
+    /// Linkage of the clustering by correlation distance `1 - r`.
+    pub linkage: ClusterLinkage,
+    /// Merge steps in order; empty without a correlation matrix.
+    pub merges: Vec<ClusterMerge>,
+    /// Column indices in dendrogram leaf order.
+    pub dendrogram_order: Vec<usize>,
+    /// Excluded columns, skipped tests and other caveats.
+    pub notes: Vec<String>,
+}
+
+/// Options of a RANSAC regression; every field has a default.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RansacOptions {
+    /// Number of random trials (default 100).
+    pub max_trials: Option<usize>,
+    /// Rows fitted per trial (default: the number of coefficients).
+    pub min_samples: Option<usize>,
+    /// Largest absolute residual of an inlier (default: the median absolute
+    /// deviation of the response).
+    pub residual_threshold: Option<f64>,
+    /// Seed from which every trial's seed is derived; a random seed is drawn
+    /// when absent and reported in the result.
+    pub seed: Option<u64>,
+}
+
+/// Linear model with intercept fitted by RANSAC.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RobustRegressionResult {
+    /// Intercept of the model refitted on the inliers.
+    pub intercept: f64,
+    /// Slope of each predictor, in input order.
+    pub coefficients: Vec<f64>,
+    /// Least-squares standard error of the intercept over the inliers.
+    pub intercept_std_error: f64,
+    /// Least-squares standard error of each slope over the inliers.
+    pub coefficient_std_errors: Vec<f64>,
+    /// Residual standard deviation of the inliers.
+    pub scale: f64,
+    /// Whether each row is an inlier of the selected trial.
+    pub inlier_mask: Vec<bool>,
+    /// Number of inliers.
+    pub inlier_count: usize,
+    /// Median absolute residual of the inliers under the selected trial.
+    pub residual_median: f64,
+    /// Inlier threshold used.
+    pub residual_threshold: f64,
+    /// Number of trials run.
+    pub trials: usize,
+    /// Trials whose sample had a singular design matrix.
+    pub degenerate_trials: usize,
+    /// Seed the trial seeds were derived from.
+    pub seed: u64,
+}
+
+/// Options of an M-estimator regression; every field has a default.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MEstimatorOptions {
+    /// Tuning constant in units of the residual scale (default 1.345 for
+    /// Huber and 4.685 for Tukey's biweight, both 95% efficient for normal
+    /// errors).
+    pub tuning_constant: Option<f64>,
+    /// Most reweighting iterations (default 50).
+    pub max_iterations: Option<usize>,
+    /// Relative change of the coefficients and the scale below which the
+    /// fit has converged (default 1e-8).
+    pub tolerance: Option<f64>,
+}
+
+/// Linear model with intercept fitted by iteratively reweighted least squares.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MEstimatorResult {
+    /// Intercept of the fitted model.
+    pub intercept: f64,
+    /// Slope of each predictor, in input order.
+    pub coefficients: Vec<f64>,
+    /// Sandwich standard error of the intercept.
+    pub intercept_std_error: f64,
+    /// Sandwich standard error of each slope.
+    pub coefficient_std_errors: Vec<f64>,
+    /// Robust residual scale: the median absolute deviation over 0.6745.
+    pub scale: f64,
+    /// Final weight of each row, from 1 for a typical row down to 0.
+    pub weights: Vec<f64>,
+    /// Tuning constant used.
+    pub tuning_constant: f64,
+    /// Reweighting iterations run.
+    pub iterations: usize,
+    /// Whether coefficients and scale settled before the iteration limit.
+    pub converged: bool,
+}
+
+/// One measurement of a quantity to be combined with others.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Measurement {
+    /// Measured value.
+    pub value: f64,
+    /// Standard uncertainty of the value; required and positive.
+    pub uncertainty: Option<f64>,
+    /// Name shown for the measurement, e.g. the laboratory or run.
+    pub label: Option<String>,
+}
+
+/// Options of a measurement combination; every field has a default.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MeasurementCombinationOptions {
+    /// Absolute normalized deviation above which a measurement is flagged
+    /// as an outlier (default 2).
+    pub outlier_threshold: Option<f64>,
+    /// Also report the DerSimonian-Laird random-effects estimate.
+    pub random_effects: bool,
+}
+
+/// Agreement of one measurement with the weighted mean.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeasurementDeviation {
+    /// Label of the measurement, if given.
+    pub label: Option<String>,
+    /// Measured value.
+    pub value: f64,
+    /// Standard uncertainty of the value.
+    pub uncertainty: f64,
+    /// Share of the measurement in the weighted mean; shares sum to 1.
+    pub weight: f64,
+    /// Difference from the weighted mean over its standard uncertainty,
+    /// which accounts for the measurement's own part in the mean.
+    pub normalized_deviation: f64,
+    /// Whether the normalized deviation exceeds the outlier threshold.
+    pub is_outlier: bool,
+}
+
+/// DerSimonian-Laird random-effects estimate of a measurement set.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomEffectsEstimate {
+    /// Mean weighted by `1 / (u_i^2 + tau^2)`.
+    pub mean: f64,
+    /// Standard uncertainty of the mean.
+    pub uncertainty: f64,
+    /// Between-measurement variance `tau^2`, zero for a consistent set.
+    pub between_variance: f64,
+}
+
+/// Consensus value of independent measurements of one quantity.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeasurementCombinationResult {
+    /// Inverse-variance weighted mean.
+    pub weighted_mean: f64,
+    /// Uncertainty of the mean from the stated uncertainties alone.
+    pub internal_uncertainty: f64,
+    /// Internal uncertainty scaled by the Birge ratio when it exceeds 1.
+    pub external_uncertainty: f64,
+    /// Weighted sum of squared deviations from the mean.
+    pub chi_squared: f64,
+    /// Number of measurements less one.
+    pub degrees_of_freedom: usize,
+    /// Chi-squared over its degrees of freedom.
+    pub reduced_chi_squared: f64,
+    /// Square root of the reduced chi-squared; well above 1 when the stated
+    /// uncertainties do not explain the scatter.
+    pub birge_ratio: f64,
+    /// Probability of a chi-squared at least this large for a consistent set.
+    pub p_value: f64,
+    /// Threshold the measurements were flagged against.
+    pub outlier_threshold: f64,
+    /// Agreement of each measurement with the mean, in input order.
+    pub deviations: Vec<MeasurementDeviation>,
+    /// Random-effects estimate, when requested.
+    pub random_effects: Option<RandomEffectsEstimate>,
+}
+
+/// Rule by which a point is flagged as an outlier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutlierMethod {
+    /// Outside Tukey's fences `Q1 - k IQR` and `Q3 + k IQR`.
+    Iqr,
+    /// Modified z-score about the median, scaled by the MAD, beyond the
+    /// threshold.
+    ModifiedZScore,
+    /// Distance from the mean beyond the threshold in standard deviations.
+    ZScore,
+}
+
+/// Thresholds of the outlier rules; every field has a default.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OutlierDetectionOptions {
+    /// Multiple of the interquartile range beyond the quartiles (default 1.5).
+    pub iqr_multiplier: Option<f64>,
+    /// Absolute modified z-score above which a point is flagged (default 3.5).
+    pub modified_z_threshold: Option<f64>,
+    /// Absolute z-score above which a point is flagged (default 3).
+    pub z_threshold: Option<f64>,
+    /// Number of rules that must agree on a consensus outlier (default 2).
+    pub min_agreement: Option<usize>,
+}
+
+/// Points flagged by one outlier rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlierMethodResult {
+    /// Rule applied.
+    pub method: OutlierMethod,
+    /// Indices of the flagged points, ascending.
+    pub indices: Vec<usize>,
+}
+
+/// Outliers of a column under several rules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlierAnalysisResult {
+    /// Number of points analysed.
+    pub count: usize,
+    /// Flags of each rule.
+    pub methods: Vec<OutlierMethodResult>,
+    /// Number of rules that must agree on a consensus outlier.
+    pub min_agreement: usize,
+    /// Indices flagged by at least `min_agreement` rules, ascending.
+    pub consensus_indices: Vec<usize>,
+}
+
+/// Moments of a sample.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatisticalMoments {
+    /// Number of values.
+    pub count: usize,
+    /// Arithmetic mean.
+    pub mean: f64,
+    /// Sample standard deviation; `None` for a single value.
+    pub std_dev: Option<f64>,
+    /// Sample skewness `g1`; `None` for fewer than three values or no spread.
+    pub skewness: Option<f64>,
+}
+
+/// Five-number summary of a sample, with quartiles interpolated linearly.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Quantiles {
+    /// Smallest value.
+    pub min: f64,
+    /// First quartile.
+    pub q1: f64,
+    /// Median.
+    pub median: f64,
+    /// Third quartile.
+    pub q3: f64,
+    /// Largest value.
+    pub max: f64,
+}
+
+/// Moments and quantiles of a sample.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SampleSummary {
+    /// Count, mean, spread and skewness.
+    pub moments: StatisticalMoments,
+    /// Five-number summary.
+    pub quantiles: Quantiles,
+}
+
+/// One statistic with and without the consensus outliers.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatisticComparison {
+    /// Name of the statistic.
+    pub statistic: String,
+    /// Value over all points.
+    pub full: Option<f64>,
+    /// Value with the consensus outliers removed.
+    pub without_outliers: Option<f64>,
+    /// `without_outliers - full`, when both exist.
+    pub change: Option<f64>,
+}
+
+/// Suggested handling of a point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutlierAction {
+    /// No rule flags the point, or its uncertainty covers the deviation.
+    Keep,
+    /// Some rules flag the point, or its uncertainty could explain it.
+    Review,
+    /// Enough rules agree and the uncertainty does not explain it.
+    Remove,
+}
+
+/// Flags, suggested action and annotation of one point.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlierPoint {
+    /// Index of the point in the data.
+    pub index: usize,
+    /// Value of the point.
+    pub value: f64,
+    /// Rules flagging the point.
+    pub methods: Vec<OutlierMethod>,
+    /// Suggested handling.
+    pub action: OutlierAction,
+}
+
+/// Outlier annotations and the effect of removing the consensus outliers.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlierReport {
+    /// One entry per data point: `ok`, or the flagging rules joined by `;`.
+    pub annotations: Vec<String>,
+    /// Flagged points with their suggested action, in index order.
+    pub points: Vec<OutlierPoint>,
+    /// Suggested action for every data point.
+    pub actions: Vec<OutlierAction>,
+    /// Statistics over all points.
+    pub full: SampleSummary,
+    /// Statistics with the consensus outliers removed.
+    pub without_outliers: SampleSummary,
+    /// Side-by-side statistics with their changes.
+    pub comparison: Vec<StatisticComparison>,
+    /// Number of points removed for `without_outliers`.
+    pub removed_count: usize,
+    /// Number of rules that must agree on a consensus outlier.
+    pub min_agreement: usize,
+    /// Number of rules applied.
+    pub method_count: usize,
+}
+
+/// Direction in which a rectangular selection is read into series.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SelectionOrientation {
+    /// Each column is a series.
+    #[default]
+    Columns,
+    /// Each row is a series.
+    Rows,
+}
+
+/// Which cells other than numbers count as numeric.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CoercionPolicy {
+    /// Only numeric cells.
+    Strict,
+    /// Numbers and text holding a number, such as `" 2.5 "`, `"1e-3"` or
+    /// `"3,5"` with a decimal comma.
+    #[default]
+    NumericText,
+    /// As `NumericText`, with booleans read as 1 and 0.
+    NumericTextAndBool,
+}
+
+/// Options of a selection preparation; every field has a default.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SelectionOptions {
+    /// Whether series are the columns or the rows of the selection.
+    pub orientation: SelectionOrientation,
+    /// Whether the first cell of each series is its name; detected from the
+    /// cells when absent.
+    pub has_header: Option<bool>,
+    /// Which cells count as numeric.
+    pub coercion: CoercionPolicy,
+    /// Smallest fraction of numeric cells among the non-empty cells of a
+    /// series (default 0.8); series below it are rejected as non-numeric.
+    pub min_numeric_fraction: Option<f64>,
+}
+
+/// Why a cell was left out of its series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SkipReason {
+    /// Text that is not a number under the coercion policy.
+    Text,
+    /// Missing, null or blank cell.
+    Empty,
+    /// Boolean under a policy that does not coerce booleans.
+    Bool,
+}
+
+/// Cell left out of a series.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedCell {
+    /// Position in the series, not counting the header.
+    pub index: usize,
+    /// Cell as sent by the spreadsheet.
+    pub value: serde_json::Value,
+    /// Why the cell was skipped.
+    pub reason: SkipReason,
+}
+
+/// Numeric series read from a selection. It deserializes as a
+/// `NamedColumn`, so it can be passed to the multi-column commands as is.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreparedSeries {
+    /// Header text, or `Column k` / `Row k` without a header.
+    pub name: String,
+    /// Values by position in the series; skipped cells are `None`.
+    pub values: Vec<Option<f64>>,
+    /// Number of numeric values.
+    pub numeric_count: usize,
+    /// Numeric values over non-empty cells.
+    pub numeric_fraction: f64,
+    /// Cells left out, in order.
+    pub skipped: Vec<SkippedCell>,
+}
+
+/// Series rejected for holding too few numbers.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedSeries {
+    /// Header text, or `Column k` / `Row k` without a header.
+    pub name: String,
+    /// Numeric values over non-empty cells.
+    pub numeric_fraction: f64,
+    /// Number of non-empty cells.
+    pub non_empty_count: usize,
+}
+
+/// Numeric series of a rectangular selection.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreparedSelection {
+    /// Series accepted as numeric, in selection order.
+    pub series: Vec<PreparedSeries>,
+    /// Series rejected as non-numeric, in selection order.
+    pub rejected: Vec<RejectedSeries>,
+    /// Whether the first cell of each series was taken as its name.
+    pub has_header: bool,
+    /// Orientation the selection was read in.
+    pub orientation: SelectionOrientation,
+    /// Smallest accepted fraction of numeric cells.
+    pub min_numeric_fraction: f64,
+}
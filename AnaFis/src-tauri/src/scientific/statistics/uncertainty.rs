@@ -0,0 +1,282 @@
+//! Consensus values of repeated measurements of one quantity.
+//!
+//! Independent measurements `x_i ± u_i` combine into the inverse-variance
+//! weighted mean. Whether their scatter agrees with the stated uncertainties
+//! is judged by the chi-squared of the set and its Birge ratio; when the set
+//! is inconsistent the uncertainty of the mean is scaled up by that ratio, or
+//! an unexplained between-measurement variance is estimated by the
+//! DerSimonian-Laird random-effects model.
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+
+use super::error::StatisticsError;
+use super::types::{
+    Measurement, MeasurementCombinationOptions, MeasurementCombinationResult, MeasurementDeviation,
+    RandomEffectsEstimate,
+};
+
+/// Normalized deviation above which a measurement is an outlier by default.
+const DEFAULT_OUTLIER_THRESHOLD: f64 = 2.0;
+
+/// Combination of independent measurements into a consensus value.
+pub struct MeasurementCombinationEngine;
+
+impl MeasurementCombinationEngine {
+    /// Weighted mean of `measurements` and the consistency of the set.
+    ///
+    /// Gives the internal and external uncertainties of the mean and the
+    /// normalized deviation of each measurement from it.
+    ///
+    /// The deviation of `x_i` is `(x_i - mean) / sqrt(u_i^2 - u_int^2)`,
+    /// the variance of the difference once the correlation of `x_i` with
+    /// the mean is taken out.
+    ///
+    /// # Errors
+    /// Returns a structured error for fewer than two measurements, a
+    /// non-finite value, a missing, zero or non-finite uncertainty, or an
+    /// invalid outlier threshold.
+    pub fn combine(
+        measurements: &[Measurement],
+        options: &MeasurementCombinationOptions,
+    ) -> Result<MeasurementCombinationResult, StatisticsError> {
+        let threshold = options
+            .outlier_threshold
+            .unwrap_or(DEFAULT_OUTLIER_THRESHOLD);
+        if !(threshold.is_finite() && threshold > 0.0) {
+            return Err(StatisticsError::validation(
+                "outlierThreshold",
+                "Outlier threshold must be a positive number",
+            ));
+        }
+        if measurements.len() < 2 {
+            return Err(StatisticsError::InsufficientData {
+                needed: 2,
+                got: measurements.len(),
+            });
+        }
+        let uncertainties = validate_measurements(measurements)?;
+
+        let weights: Vec<f64> = uncertainties.iter().map(|u| u.powi(-2)).collect();
+        let weight_sum: f64 = weights.iter().sum();
+        let weighted_mean = measurements
+            .iter()
+            .zip(&weights)
+            .map(|(measurement, weight)| weight * measurement.value)
+            .sum::<f64>()
+            / weight_sum;
+        let internal_uncertainty = weight_sum.sqrt().recip();
+        let chi_squared: f64 = measurements
+            .iter()
+            .zip(&weights)
+            .map(|(measurement, weight)| weight * (measurement.value - weighted_mean).powi(2))
+            .sum();
+
+        let degrees_of_freedom = measurements.len() - 1;
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Measurement count casting to f64"
+        )]
+        let dof = degrees_of_freedom as f64;
+        let reduced_chi_squared = chi_squared / dof;
+        let birge_ratio = reduced_chi_squared.sqrt();
+        let p_value = ChiSquared::new(dof)
+            .map_err(|error| error.to_string())?
+            .sf(chi_squared);
+
+        let deviations = measurements
+            .iter()
+            .zip(&uncertainties)
+            .zip(&weights)
+            .map(|((measurement, &uncertainty), weight)| {
+                let deviation_variance =
+                    uncertainty.mul_add(uncertainty, -internal_uncertainty.powi(2));
+                let normalized_deviation =
+                    (measurement.value - weighted_mean) / deviation_variance.max(0.0).sqrt();
+                MeasurementDeviation {
+                    label: measurement.label.clone(),
+                    value: measurement.value,
+                    uncertainty,
+                    weight: weight / weight_sum,
+                    normalized_deviation,
+                    is_outlier: normalized_deviation.abs() > threshold,
+                }
+            })
+            .collect();
+
+        let random_effects = options
+            .random_effects
+            .then(|| dersimonian_laird(measurements, &uncertainties, &weights, chi_squared, dof));
+
+        Ok(MeasurementCombinationResult {
+            weighted_mean,
+            internal_uncertainty,
+            external_uncertainty: internal_uncertainty * birge_ratio.max(1.0),
+            chi_squared,
+            degrees_of_freedom,
+            reduced_chi_squared,
+            birge_ratio,
+            p_value,
+            outlier_threshold: threshold,
+            deviations,
+            random_effects,
+        })
+    }
+}
+
+/// Uncertainties of `measurements`, which must all be present and positive.
+fn validate_measurements(measurements: &[Measurement]) -> Result<Vec<f64>, StatisticsError> {
+    measurements
+        .iter()
+        .enumerate()
+        .map(|(index, measurement)| {
+            let name = measurement
+                .label
+                .clone()
+                .unwrap_or_else(|| format!("Measurement {}", index + 1));
+            if !measurement.value.is_finite() {
+                return Err(StatisticsError::validation(
+                    "value",
+                    format!("{name} has a non-finite value"),
+                ));
+            }
+            match measurement.uncertainty {
+                Some(uncertainty) if uncertainty.is_finite() && uncertainty > 0.0 => {
+                    Ok(uncertainty)
+                }
+                Some(_) => Err(StatisticsError::validation(
+                    "uncertainty",
+                    format!("{name} needs a positive finite uncertainty to be weighted"),
+                )),
+                None => Err(StatisticsError::validation(
+                    "uncertainty",
+                    format!("{name} has no uncertainty and cannot be weighted"),
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Method-of-moments between-measurement variance and the mean weighted by
+/// `1 / (u_i^2 + tau^2)`.
+fn dersimonian_laird(
+    measurements: &[Measurement],
+    uncertainties: &[f64],
+    weights: &[f64],
+    chi_squared: f64,
+    dof: f64,
+) -> RandomEffectsEstimate {
+    let weight_sum: f64 = weights.iter().sum();
+    let squared_weight_sum: f64 = weights.iter().map(|weight| weight * weight).sum();
+    let between_variance =
+        ((chi_squared - dof) / (weight_sum - squared_weight_sum / weight_sum)).max(0.0);
+    let random_weights: Vec<f64> = uncertainties
+        .iter()
+        .map(|u| u.mul_add(*u, between_variance).recip())
+        .collect();
+    let random_weight_sum: f64 = random_weights.iter().sum();
+    let mean = measurements
+        .iter()
+        .zip(&random_weights)
+        .map(|(measurement, weight)| weight * measurement.value)
+        .sum::<f64>()
+        / random_weight_sum;
+    RandomEffectsEstimate {
+        mean,
+        uncertainty: random_weight_sum.sqrt().recip(),
+        between_variance,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn measurements(values: &[(f64, f64)]) -> Vec<Measurement> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(index, &(value, uncertainty))| Measurement {
+                value,
+                uncertainty: Some(uncertainty),
+                label: Some(format!("Lab {}", index + 1)),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_combines_inconsistent_measurements() {
+        // Worked by hand: sum of weights 142.36, chi-squared 10.761 on 3
+        // degrees of freedom, so the Birge ratio is 1.894.
+        let set = measurements(&[(9.8, 0.2), (10.4, 0.3), (10.1, 0.1), (11.2, 0.4)]);
+        let options = MeasurementCombinationOptions {
+            random_effects: true,
+            ..MeasurementCombinationOptions::default()
+        };
+        let result = MeasurementCombinationEngine::combine(&set, &options).unwrap();
+        assert_abs_diff_eq!(result.weighted_mean, 10.1190, epsilon = 5e-5);
+        assert_abs_diff_eq!(result.internal_uncertainty, 0.0838, epsilon = 5e-5);
+        assert_abs_diff_eq!(result.chi_squared, 10.7610, epsilon = 5e-5);
+        assert_abs_diff_eq!(result.birge_ratio, 1.8939, epsilon = 5e-5);
+        assert_abs_diff_eq!(result.external_uncertainty, 0.1587, epsilon = 5e-5);
+        assert_abs_diff_eq!(result.p_value, 0.0131, epsilon = 5e-5);
+        assert_eq!(result.degrees_of_freedom, 3);
+
+        let deviations: Vec<f64> = result
+            .deviations
+            .iter()
+            .map(|deviation| deviation.normalized_deviation)
+            .collect();
+        for (deviation, expected) in deviations.iter().zip([-1.7568, 0.9754, -0.3488, 2.7638]) {
+            assert_abs_diff_eq!(*deviation, expected, epsilon = 5e-5);
+        }
+        let outliers: Vec<_> = result
+            .deviations
+            .iter()
+            .filter(|deviation| deviation.is_outlier)
+            .filter_map(|deviation| deviation.label.as_deref())
+            .collect();
+        assert_eq!(outliers, ["Lab 4"]);
+        let shares: f64 = result.deviations.iter().map(|d| d.weight).sum();
+        assert_abs_diff_eq!(shares, 1.0, epsilon = 1e-12);
+
+        let random = result.random_effects.unwrap();
+        assert_abs_diff_eq!(random.between_variance, 0.1166, epsilon = 5e-5);
+        assert_abs_diff_eq!(random.mean, 10.2545, epsilon = 5e-5);
+        assert_abs_diff_eq!(random.uncertainty, 0.2097, epsilon = 5e-5);
+    }
+
+    #[test]
+    fn test_consistent_set_and_validation() {
+        let consistent = measurements(&[(5.0, 0.1), (5.1, 0.2), (4.95, 0.1)]);
+        let options = MeasurementCombinationOptions {
+            random_effects: true,
+            ..MeasurementCombinationOptions::default()
+        };
+        let result = MeasurementCombinationEngine::combine(&consistent, &options).unwrap();
+        assert!(result.birge_ratio < 1.0);
+        assert_abs_diff_eq!(
+            result.external_uncertainty,
+            result.internal_uncertainty,
+            epsilon = 1e-15
+        );
+        let random = result.random_effects.unwrap();
+        assert_abs_diff_eq!(random.between_variance, 0.0, epsilon = 1e-15);
+        assert_abs_diff_eq!(random.mean, result.weighted_mean, epsilon = 1e-12);
+        assert!(result.deviations.iter().all(|d| !d.is_outlier));
+
+        let mut invalid = consistent.clone();
+        invalid[1].uncertainty = Some(0.0);
+        let zero = MeasurementCombinationEngine::combine(&invalid, &options).unwrap_err();
+        assert!(zero.to_string().contains("Lab 2"), "{zero}");
+        invalid[1].uncertainty = None;
+        let missing = MeasurementCombinationEngine::combine(&invalid, &options).unwrap_err();
+        assert!(
+            matches!(missing, StatisticsError::Validation { field, .. } if field == "uncertainty")
+        );
+        assert!(matches!(
+            MeasurementCombinationEngine::combine(&consistent[..1], &options),
+            Err(StatisticsError::InsufficientData { needed: 2, got: 1 })
+        ));
+    }
+}
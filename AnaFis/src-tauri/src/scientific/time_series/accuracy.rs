@@ -0,0 +1,237 @@
+//! Forecast accuracy metrics.
+//!
+//! Every metric is reported together with its name and formula so exported
+//! reports are self-documenting. CRPS assumes a Gaussian predictive
+//! distribution centred on the point forecast, with the standard deviation
+//! recovered from the supplied prediction interval widths.
+use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+
+use super::types::{AccuracyMetric, ForecastAccuracyResult, PredictionIntervals};
+
+/// Forecast evaluation against realised values.
+pub struct ForecastAccuracyEngine;
+
+impl ForecastAccuracyEngine {
+    /// Compare `forecast` with `actual` and compute MAE, RMSE, sMAPE, and,
+    /// when the inputs allow it, MASE and CRPS.
+    ///
+    /// `naive_errors` are the in-sample one-step errors of the naive forecast
+    /// (`y[t] - y[t-1]`), used to scale MASE. `prediction_intervals` must be
+    /// aligned with `forecast`.
+    ///
+    /// # Errors
+    /// Returns an error if the series are empty, have different lengths or
+    /// contain non-finite values, or if interval bounds are misaligned.
+    pub fn evaluate(
+        actual: &[f64],
+        forecast: &[f64],
+        naive_errors: Option<&[f64]>,
+        prediction_intervals: Option<&PredictionIntervals>,
+    ) -> Result<ForecastAccuracyResult, String> {
+        if actual.is_empty() {
+            return Err("At least one actual value is required".to_owned());
+        }
+        if actual.len() != forecast.len() {
+            return Err(format!(
+                "Actual and forecast lengths differ ({} vs {})",
+                actual.len(),
+                forecast.len()
+            ));
+        }
+        if actual
+            .iter()
+            .chain(forecast)
+            .any(|value| !value.is_finite())
+        {
+            return Err("Actual and forecast values must be finite".to_owned());
+        }
+
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Sample count casting to f64 for averages"
+        )]
+        let n = actual.len() as f64;
+        let errors: Vec<f64> = actual.iter().zip(forecast).map(|(a, f)| a - f).collect();
+        let mae = errors.iter().map(|e| e.abs()).sum::<f64>() / n;
+        let rmse = (errors.iter().map(|e| e * e).sum::<f64>() / n).sqrt();
+        let smape = 100.0
+            * actual
+                .iter()
+                .zip(forecast)
+                .map(|(a, f)| {
+                    let denominator = a.abs() + f.abs();
+                    if denominator == 0.0 {
+                        0.0
+                    } else {
+                        2.0 * (a - f).abs() / denominator
+                    }
+                })
+                .sum::<f64>()
+            / n;
+
+        let scaled_error = match naive_errors {
+            Some(naive) => Some(mean_absolute_scaled_error(mae, naive)?),
+            None => None,
+        };
+        let probabilistic_score = match prediction_intervals {
+            Some(intervals) => Some(gaussian_crps(actual, forecast, intervals)?),
+            None => None,
+        };
+
+        Ok(ForecastAccuracyResult {
+            sample_size: actual.len(),
+            mae: AccuracyMetric::new("MAE", "mean(|y - f|)", mae),
+            rmse: AccuracyMetric::new("RMSE", "sqrt(mean((y - f)^2))", rmse),
+            smape: AccuracyMetric::new(
+                "sMAPE",
+                "100 * mean(2|y - f| / (|y| + |f|)), terms with |y| + |f| = 0 count as 0",
+                smape,
+            ),
+            mase: scaled_error.map(|value| {
+                AccuracyMetric::new("MASE", "MAE / mean(|naive one-step errors|)", value)
+            }),
+            crps: probabilistic_score.map(|value| {
+                AccuracyMetric::new(
+                    "CRPS",
+                    "mean(sigma * [z(2Phi(z) - 1) + 2phi(z) - 1/sqrt(pi)]), z = (y - f) / sigma",
+                    value,
+                )
+            }),
+        })
+    }
+}
+
+fn mean_absolute_scaled_error(mae: f64, naive_errors: &[f64]) -> Result<f64, String> {
+    let finite: Vec<f64> = naive_errors
+        .iter()
+        .copied()
+        .filter(|value| value.is_finite())
+        .collect();
+    if finite.is_empty() {
+        return Err("Naive errors must contain at least one finite value".to_owned());
+    }
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for naive MAE"
+    )]
+    let naive_mae = finite.iter().map(|e| e.abs()).sum::<f64>() / finite.len() as f64;
+    if naive_mae == 0.0 {
+        return Err("Naive forecast errors are all zero; MASE is undefined".to_owned());
+    }
+    Ok(mae / naive_mae)
+}
+
+/// Closed-form CRPS of a Gaussian predictive distribution, averaged over points.
+///
+/// The predictive standard deviation per step is the mean over the supplied
+/// interval levels of `(upper - lower) / (2 z_level)`.
+fn gaussian_crps(
+    actual: &[f64],
+    forecast: &[f64],
+    intervals: &PredictionIntervals,
+) -> Result<f64, String> {
+    if intervals.is_empty() {
+        return Err("At least one prediction interval is required for CRPS".to_owned());
+    }
+    let standard_normal = Normal::new(0.0, 1.0).map_err(|error| error.to_string())?;
+    let mut z_scores = Vec::with_capacity(intervals.len());
+    for interval in intervals {
+        if interval.lower.len() != forecast.len() || interval.upper.len() != forecast.len() {
+            return Err(format!(
+                "{}% interval bounds must have {} values",
+                interval.confidence_level,
+                forecast.len()
+            ));
+        }
+        if !(interval.confidence_level > 0.0 && interval.confidence_level < 100.0) {
+            return Err(format!(
+                "Interval confidence level must be in (0, 100), got {}",
+                interval.confidence_level
+            ));
+        }
+        z_scores.push(standard_normal.inverse_cdf(0.5 + interval.confidence_level / 200.0));
+    }
+
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Interval count casting to f64 for averaging"
+    )]
+    let interval_count = intervals.len() as f64;
+    let inv_sqrt_pi = 1.0 / std::f64::consts::PI.sqrt();
+    let mut total = 0.0;
+    for (index, (&observed, &mean)) in actual.iter().zip(forecast).enumerate() {
+        let sigma = intervals
+            .iter()
+            .zip(&z_scores)
+            .map(|(interval, z)| (interval.upper[index] - interval.lower[index]) / (2.0 * z))
+            .sum::<f64>()
+            / interval_count;
+        if !(sigma.is_finite() && sigma > 0.0) {
+            return Err(format!(
+                "Prediction interval at step {index} has non-positive width"
+            ));
+        }
+        let z = (observed - mean) / sigma;
+        let term = z.mul_add(
+            2.0_f64.mul_add(standard_normal.cdf(z), -1.0),
+            2.0_f64.mul_add(standard_normal.pdf(z), -inv_sqrt_pi),
+        );
+        total = sigma.mul_add(term, total);
+    }
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for averaging"
+    )]
+    let crps = total / actual.len() as f64;
+    Ok(crps)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use crate::scientific::time_series::types::PredictionInterval;
+
+    #[test]
+    fn test_point_metrics() {
+        let actual = [1.0, 2.0, 0.0, 4.0];
+        let forecast = [1.5, 2.0, 0.0, 3.0];
+        let result = ForecastAccuracyEngine::evaluate(&actual, &forecast, None, None).unwrap();
+        assert!((result.mae.value - 0.375).abs() < 1e-12);
+        assert!((result.rmse.value - (1.25_f64 / 4.0).sqrt()).abs() < 1e-12);
+        let expected_smape = 100.0 * (2.0 * 0.5 / 2.5 + 2.0 / 7.0) / 4.0;
+        assert!((result.smape.value - expected_smape).abs() < 1e-12);
+        assert!(result.mase.is_none());
+        assert!(result.crps.is_none());
+    }
+
+    #[test]
+    fn test_mase_and_crps() {
+        let actual = [10.0, 12.0];
+        let forecast = [10.0, 11.0];
+        let naive = [2.0, -2.0, 1.0, -1.0];
+        let interval = PredictionInterval {
+            confidence_level: 95.0,
+            lower: vec![10.0 - 1.959_963_984_540_054, 11.0 - 1.959_963_984_540_054],
+            upper: vec![10.0 + 1.959_963_984_540_054, 11.0 + 1.959_963_984_540_054],
+        };
+        let result =
+            ForecastAccuracyEngine::evaluate(&actual, &forecast, Some(&naive), Some(&[interval]))
+                .unwrap();
+        assert!((result.mase.unwrap().value - 1.0 / 3.0).abs() < 1e-12);
+        // CRPS of N(0, 1) at 0 is 2phi(0) - 1/sqrt(pi) ~ 0.233695; at 1 ~ 0.602441.
+        let crps = result.crps.unwrap().value;
+        assert!(
+            (crps - f64::midpoint(0.233_695, 0.602_441)).abs() < 1e-5,
+            "{crps}"
+        );
+    }
+
+    #[test]
+    fn test_rejects_mismatched_lengths() {
+        assert!(ForecastAccuracyEngine::evaluate(&[1.0], &[1.0, 2.0], None, None).is_err());
+        assert!(
+            ForecastAccuracyEngine::evaluate(&[1.0, 2.0], &[1.0, 2.0], Some(&[0.0]), None).is_err()
+        );
+    }
+}
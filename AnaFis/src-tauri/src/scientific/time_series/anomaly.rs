@@ -0,0 +1,282 @@
+//! Anomaly detection on the residuals of a seasonal-trend baseline.
+//!
+//! A Prophet-style model is fitted to a training window and predicts the
+//! whole series. Residuals are scored by their modified z-score about the
+//! median and median absolute deviation of the training residuals, so a few
+//! anomalies inside the window barely move the scale. The fitted model and
+//! residual scale are returned as a state that scores later observations
+//! without refitting; a level shift after the window keeps being flagged
+//! until the baseline is refitted on data that includes it.
+use super::prophet::ProphetEngine;
+use super::types::{
+    AnomalyModelState, AnomalyThreshold, SeriesAnomaly, SeriesAnomalyRequest, SeriesAnomalyResult,
+};
+use crate::scientific::primitives::descriptive::{
+    median, median_absolute_deviation, modified_z_score,
+};
+use crate::scientific::statistics::StatisticsError;
+
+/// Residual-based anomaly detection for monitored series.
+pub struct SeriesAnomalyEngine;
+
+impl SeriesAnomalyEngine {
+    /// Fit the baseline to the training window and score the whole series.
+    ///
+    /// # Errors
+    /// Returns a structured error if the series, training window, threshold
+    /// or configuration is invalid, or if the baseline fit fails.
+    pub fn detect(request: &SeriesAnomalyRequest) -> Result<SeriesAnomalyResult, StatisticsError> {
+        validate_threshold(&request.threshold)?;
+        validate_series(&request.times, &request.values)?;
+        if request
+            .config
+            .extra_regressors
+            .as_ref()
+            .is_some_and(|regressors| !regressors.is_empty())
+        {
+            return Err(StatisticsError::validation(
+                "extraRegressors",
+                "Extra regressors are not supported by anomaly detection",
+            ));
+        }
+        let end = request.training_end.unwrap_or(request.times.len());
+        if !(request.training_start < end && end <= request.times.len()) {
+            return Err(StatisticsError::validation(
+                "trainingEnd",
+                format!(
+                    "Training window {}..{end} must be a non-empty range within the {} observations",
+                    request.training_start,
+                    request.times.len()
+                ),
+            ));
+        }
+
+        let times = &request.times[request.training_start..end];
+        let values = &request.values[request.training_start..end];
+        let model = ProphetEngine::fit(times, values, &request.config)?;
+        let fitted = ProphetEngine::predict(&model, times, &[])?;
+        let residuals: Vec<f64> = values
+            .iter()
+            .zip(&fitted.yhat)
+            .map(|(actual, predicted)| actual - predicted)
+            .collect();
+        let state = AnomalyModelState {
+            model,
+            residual_median: median(&residuals).unwrap_or(0.0),
+            residual_mad: median_absolute_deviation(&residuals).unwrap_or(0.0),
+            threshold: request.threshold,
+        };
+        Self::score(state, &request.times, &request.values)
+    }
+
+    /// Score observations against the baseline of an earlier detection.
+    ///
+    /// # Errors
+    /// Returns a structured error if the observations or the state's
+    /// threshold are invalid, or if the state's model needs extra regressors.
+    pub fn score(
+        state: AnomalyModelState,
+        times: &[f64],
+        values: &[f64],
+    ) -> Result<SeriesAnomalyResult, StatisticsError> {
+        validate_threshold(&state.threshold)?;
+        validate_series(times, values)?;
+        if !(state.residual_median.is_finite()
+            && state.residual_mad.is_finite()
+            && state.residual_mad >= 0.0)
+        {
+            return Err(StatisticsError::validation(
+                "residualMad",
+                "Residual median and MAD must be finite, and the MAD non-negative",
+            ));
+        }
+
+        let baseline = ProphetEngine::predict(&state.model, times, &[])?;
+        let scores: Vec<f64> = values
+            .iter()
+            .zip(&baseline.yhat)
+            .map(|(actual, expected)| {
+                modified_z_score(actual - expected, state.residual_median, state.residual_mad)
+            })
+            .collect();
+        let threshold = state.threshold;
+        let anomalies = scores
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &score)| {
+                let actual = values[index];
+                let lower = baseline.yhat_lower[index];
+                let upper = baseline.yhat_upper[index];
+                let outside = actual < lower || actual > upper;
+                (score.abs() > threshold.modified_z
+                    && (outside || !threshold.require_outside_interval))
+                    .then(|| SeriesAnomaly {
+                        index,
+                        time: times[index],
+                        actual,
+                        expected: baseline.yhat[index],
+                        residual: actual - baseline.yhat[index],
+                        score,
+                        lower,
+                        upper,
+                    })
+            })
+            .collect();
+        Ok(SeriesAnomalyResult {
+            baseline,
+            scores,
+            anomalies,
+            state,
+        })
+    }
+}
+
+fn validate_threshold(threshold: &AnomalyThreshold) -> Result<(), StatisticsError> {
+    if !(threshold.modified_z.is_finite() && threshold.modified_z > 0.0) {
+        return Err(StatisticsError::validation(
+            "modifiedZ",
+            "Anomaly threshold must be a positive modified z-score",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_series(times: &[f64], values: &[f64]) -> Result<(), StatisticsError> {
+    if times.len() != values.len() {
+        return Err(StatisticsError::validation(
+            "values",
+            format!(
+                "Times and values lengths differ ({} vs {})",
+                times.len(),
+                values.len()
+            ),
+        ));
+    }
+    if times.iter().chain(values).any(|value| !value.is_finite()) {
+        return Err(StatisticsError::validation(
+            "values",
+            "Times and values must be finite",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use super::*;
+    use crate::scientific::time_series::types::ProphetConfig;
+
+    /// Weekly cycle with a slow trend and deterministic jitter, with spikes
+    /// at days 40 and 75 and a level shift of +8 from day 100.
+    fn monitored_series() -> (Vec<f64>, Vec<f64>) {
+        let days = 0..160_u32;
+        let times: Vec<f64> = days.clone().map(f64::from).collect();
+        let values = days
+            .map(|day| {
+                let t = f64::from(day);
+                let jitter = 0.6 * ((t * 12.9898).sin() * 43_758.545_3).fract();
+                let spike = if matches!(day, 40 | 75) { 6.0 } else { 0.0 };
+                let shift = if day >= 100 { 8.0 } else { 0.0 };
+                4.0_f64.mul_add((TAU * t / 7.0).sin(), 0.02_f64.mul_add(t, 20.0))
+                    + jitter
+                    + spike
+                    + shift
+            })
+            .collect();
+        (times, values)
+    }
+
+    fn weekly_config() -> ProphetConfig {
+        ProphetConfig {
+            seasonality_period: Some(7.0),
+            n_changepoints: 0,
+            ..ProphetConfig::default()
+        }
+    }
+
+    fn flagged(result: &SeriesAnomalyResult) -> Vec<usize> {
+        result
+            .anomalies
+            .iter()
+            .map(|anomaly| anomaly.index)
+            .collect()
+    }
+
+    #[test]
+    fn test_flags_spikes_and_level_shift_until_refit() {
+        let (times, values) = monitored_series();
+        let request = SeriesAnomalyRequest {
+            times: times[..130].to_vec(),
+            values: values[..130].to_vec(),
+            training_start: 0,
+            training_end: Some(90),
+            config: weekly_config(),
+            threshold: AnomalyThreshold::default(),
+        };
+        let result = SeriesAnomalyEngine::detect(&request).unwrap();
+        assert_eq!(result.baseline.yhat.len(), 130);
+        let indices = flagged(&result);
+        let expected: Vec<usize> = [40, 75].into_iter().chain(100..130).collect();
+        assert_eq!(indices, expected);
+        let spike = &result.anomalies[0];
+        assert!(spike.residual > 4.0 && spike.score > 10.0, "{spike:?}");
+        assert!(spike.actual > spike.upper);
+        assert!((spike.expected + spike.residual - spike.actual).abs() < 1e-12);
+
+        // New points scored with the old state are still off the baseline
+        let streamed =
+            SeriesAnomalyEngine::score(result.state, &times[130..], &values[130..]).unwrap();
+        assert_eq!(flagged(&streamed).len(), 30);
+
+        // After refitting on post-shift data only the new spike is flagged
+        let mut spiked = values;
+        spiked[150] += 6.0;
+        let refit = SeriesAnomalyEngine::detect(&SeriesAnomalyRequest {
+            times: times.clone(),
+            values: spiked.clone(),
+            training_start: 100,
+            training_end: Some(130),
+            config: weekly_config(),
+            threshold: AnomalyThreshold::default(),
+        })
+        .unwrap();
+        let rescored =
+            SeriesAnomalyEngine::score(refit.state, &times[130..], &spiked[130..]).unwrap();
+        assert_eq!(flagged(&rescored), [20]);
+    }
+
+    #[test]
+    fn test_interval_requirement_and_validation() {
+        let (times, values) = monitored_series();
+        let mut request = SeriesAnomalyRequest {
+            times: times[..90].to_vec(),
+            values: values[..90].to_vec(),
+            training_start: 0,
+            training_end: None,
+            config: weekly_config(),
+            threshold: AnomalyThreshold {
+                modified_z: 0.5,
+                require_outside_interval: false,
+            },
+        };
+        let loose = SeriesAnomalyEngine::detect(&request).unwrap();
+        request.threshold.require_outside_interval = true;
+        let strict = SeriesAnomalyEngine::detect(&request).unwrap();
+        assert!(strict.anomalies.len() < loose.anomalies.len());
+        assert!(
+            strict
+                .anomalies
+                .iter()
+                .all(|anomaly| anomaly.actual < anomaly.lower || anomaly.actual > anomaly.upper)
+        );
+
+        request.training_end = Some(91);
+        assert!(SeriesAnomalyEngine::detect(&request).is_err());
+        request.training_end = None;
+        request.threshold.modified_z = 0.0;
+        assert!(SeriesAnomalyEngine::detect(&request).is_err());
+    }
+}
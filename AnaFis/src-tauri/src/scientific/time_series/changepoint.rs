@@ -0,0 +1,406 @@
+//! Offline changepoint detection by penalised segment costs.
+//!
+//! A segmentation is scored as the sum of Gaussian segment costs (twice the
+//! negative log-likelihood) plus a penalty per changepoint. PELT finds the
+//! exact optimum with pruning; binary segmentation greedily splits at the
+//! best single changepoint while the gain exceeds the penalty.
+use super::types::{
+    ChangepointCost, ChangepointMethod, ChangepointPenalty, ChangepointRequest, ChangepointResult,
+    ChangepointSegment,
+};
+use crate::scientific::primitives::descriptive::median;
+use crate::scientific::statistics::StatisticsError;
+
+/// Default minimum number of observations per segment.
+const DEFAULT_MIN_SEGMENT_LENGTH: usize = 2;
+/// Scale factor turning a median absolute deviation into a Gaussian sigma.
+const MAD_TO_SIGMA: f64 = 1.482_602_218_505_602;
+
+/// Changepoint detection for mean and variance shifts.
+pub struct ChangepointEngine;
+
+impl ChangepointEngine {
+    /// Detect changepoints in `request.values`.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::InsufficientData`] for fewer than two
+    /// observations and a validation error for non-finite values, a zero
+    /// minimum segment length (or one below two for the mean-variance cost)
+    /// or a negative manual penalty.
+    pub fn detect(request: &ChangepointRequest) -> Result<ChangepointResult, StatisticsError> {
+        let values = &request.values;
+        if values.len() < 2 {
+            return Err(StatisticsError::InsufficientData {
+                needed: 2,
+                got: values.len(),
+            });
+        }
+        if values.iter().any(|value| !value.is_finite()) {
+            return Err(StatisticsError::validation(
+                "values",
+                "Values must be finite",
+            ));
+        }
+        let min_length = request
+            .min_segment_length
+            .unwrap_or(DEFAULT_MIN_SEGMENT_LENGTH);
+        let needed_length = match request.cost {
+            ChangepointCost::Mean => 1,
+            ChangepointCost::MeanVariance => 2,
+        };
+        if min_length < needed_length {
+            return Err(StatisticsError::validation(
+                "minSegmentLength",
+                format!("Minimum segment length must be at least {needed_length}"),
+            ));
+        }
+
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Sample count casting to f64 for the BIC penalty"
+        )]
+        let log_n = (values.len() as f64).ln();
+        let penalty = match request.penalty {
+            ChangepointPenalty::Bic => match request.cost {
+                ChangepointCost::Mean => 2.0 * log_n,
+                ChangepointCost::MeanVariance => 3.0 * log_n,
+            },
+            ChangepointPenalty::Manual { value } => {
+                if !(value.is_finite() && value >= 0.0) {
+                    return Err(StatisticsError::validation(
+                        "penalty",
+                        "Manual penalty must be finite and non-negative",
+                    ));
+                }
+                value
+            }
+        };
+
+        let cost = SegmentCost::new(values, request.cost);
+        let changepoints = if values.len() < 2 * min_length {
+            Vec::new()
+        } else {
+            match request.method {
+                ChangepointMethod::Pelt => pelt(&cost, min_length, penalty),
+                ChangepointMethod::BinarySegmentation => {
+                    binary_segmentation(&cost, min_length, penalty)
+                }
+            }
+        };
+        Ok(summarize(&cost, changepoints, penalty))
+    }
+}
+
+/// Prefix sums giving O(1) segment costs.
+struct SegmentCost {
+    prefix: Vec<f64>,
+    prefix_sq: Vec<f64>,
+    model: ChangepointCost,
+    noise_variance: f64,
+    variance_floor: f64,
+}
+
+impl SegmentCost {
+    fn new(values: &[f64], model: ChangepointCost) -> Self {
+        let mut prefix = Vec::with_capacity(values.len() + 1);
+        let mut prefix_sq = Vec::with_capacity(values.len() + 1);
+        prefix.push(0.0);
+        prefix_sq.push(0.0);
+        for &value in values {
+            prefix.push(prefix[prefix.len() - 1] + value);
+            prefix_sq.push(value.mul_add(value, prefix_sq[prefix_sq.len() - 1]));
+        }
+        let mut cost = Self {
+            prefix,
+            prefix_sq,
+            model,
+            noise_variance: 1.0,
+            variance_floor: f64::MIN_POSITIVE,
+        };
+        let overall = cost.moments(0, values.len()).1;
+        if overall > 0.0 {
+            cost.variance_floor = overall * 1e-10;
+            cost.noise_variance = robust_noise_variance(values)
+                .unwrap_or(overall)
+                .max(cost.variance_floor);
+        }
+        cost
+    }
+
+    const fn len(&self) -> usize {
+        self.prefix.len() - 1
+    }
+
+    /// Mean and maximum-likelihood variance of `values[start..end]`.
+    fn moments(&self, start: usize, end: usize) -> (f64, f64) {
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Segment length casting to f64 for moments"
+        )]
+        let count = (end - start) as f64;
+        let sum = self.prefix[end] - self.prefix[start];
+        let mean = sum / count;
+        let squares = self.prefix_sq[end] - self.prefix_sq[start];
+        (mean, (mean.mul_add(-sum, squares) / count).max(0.0))
+    }
+
+    /// Twice the negative log-likelihood of `values[start..end]`, up to a
+    /// constant.
+    fn cost(&self, start: usize, end: usize) -> f64 {
+        let (_, variance) = self.moments(start, end);
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Segment length casting to f64 for the cost"
+        )]
+        let count = (end - start) as f64;
+        match self.model {
+            ChangepointCost::Mean => count * variance / self.noise_variance,
+            ChangepointCost::MeanVariance => count * variance.max(self.variance_floor).ln(),
+        }
+    }
+}
+
+/// Noise variance from first differences, which shifts barely affect.
+///
+/// Differences further than five robust sigmas from their median are treated
+/// as jumps and dropped before averaging the squares; `None` if the estimate
+/// is zero.
+fn robust_noise_variance(values: &[f64]) -> Option<f64> {
+    let differences: Vec<f64> = values.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    let center = median(&differences)?;
+    let deviations: Vec<f64> = differences.iter().map(|d| (d - center).abs()).collect();
+    let cutoff = 5.0 * MAD_TO_SIGMA * median(&deviations)?;
+    let kept: Vec<f64> = deviations
+        .iter()
+        .filter(|&&deviation| deviation <= cutoff)
+        .map(|deviation| deviation * deviation)
+        .collect();
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Difference count casting to f64 for the mean square"
+    )]
+    let mean_square = kept.iter().sum::<f64>() / kept.len().max(1) as f64;
+    // A difference of two independent observations has twice their variance.
+    let variance = mean_square / 2.0;
+    (variance > 0.0).then_some(variance)
+}
+
+/// Optimal partitioning with pruning (Killick, Fearnhead & Eckley 2012).
+fn pelt(cost: &SegmentCost, min_length: usize, penalty: f64) -> Vec<usize> {
+    let n = cost.len();
+    let mut best = vec![f64::INFINITY; n + 1];
+    let mut last = vec![0; n + 1];
+    best[0] = -penalty;
+    let mut candidates = vec![0];
+    for end in min_length..=n {
+        let newest = end - min_length;
+        if newest >= min_length && best[newest].is_finite() {
+            candidates.push(newest);
+        }
+        let scores: Vec<f64> = candidates
+            .iter()
+            .map(|&start| best[start] + cost.cost(start, end))
+            .collect();
+        let (arg, score) = scores
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .map_or((0, f64::INFINITY), |(index, &score)| (index, score));
+        best[end] = score + penalty;
+        last[end] = candidates[arg];
+
+        let threshold = best[end];
+        let mut index = 0;
+        candidates.retain(|_| {
+            let keep = scores[index] <= threshold;
+            index += 1;
+            keep
+        });
+    }
+
+    let mut changepoints = Vec::new();
+    let mut end = n;
+    while end > 0 {
+        end = last[end];
+        if end > 0 {
+            changepoints.push(end);
+        }
+    }
+    changepoints.reverse();
+    changepoints
+}
+
+/// Greedy recursive splitting while the best split beats the penalty.
+fn binary_segmentation(cost: &SegmentCost, min_length: usize, penalty: f64) -> Vec<usize> {
+    let mut changepoints = Vec::new();
+    let mut pending = vec![(0, cost.len())];
+    while let Some((start, end)) = pending.pop() {
+        if end - start < 2 * min_length {
+            continue;
+        }
+        let whole = cost.cost(start, end);
+        let best = (start + min_length..=end - min_length)
+            .map(|split| {
+                (
+                    split,
+                    whole - cost.cost(start, split) - cost.cost(split, end),
+                )
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((split, gain)) = best
+            && gain > penalty
+        {
+            changepoints.push(split);
+            pending.push((start, split));
+            pending.push((split, end));
+        }
+    }
+    changepoints.sort_unstable();
+    changepoints
+}
+
+/// Segments, per-changepoint improvements and total cost of a segmentation.
+fn summarize(cost: &SegmentCost, changepoints: Vec<usize>, penalty: f64) -> ChangepointResult {
+    let mut bounds = Vec::with_capacity(changepoints.len() + 2);
+    bounds.push(0);
+    bounds.extend_from_slice(&changepoints);
+    bounds.push(cost.len());
+
+    let segments = bounds
+        .windows(2)
+        .map(|pair| {
+            let (mean, variance) = cost.moments(pair[0], pair[1]);
+            ChangepointSegment {
+                start: pair[0],
+                end: pair[1],
+                mean,
+                variance,
+            }
+        })
+        .collect();
+    let cost_improvements = bounds
+        .windows(3)
+        .map(|triple| {
+            cost.cost(triple[0], triple[2])
+                - cost.cost(triple[0], triple[1])
+                - cost.cost(triple[1], triple[2])
+        })
+        .collect();
+    let total_cost = bounds
+        .windows(2)
+        .map(|pair| cost.cost(pair[0], pair[1]))
+        .sum();
+
+    ChangepointResult {
+        changepoints,
+        segments,
+        cost_improvements,
+        total_cost,
+        penalty,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use rand::{RngCore, SeedableRng};
+    use rand_pcg::Pcg64;
+
+    use super::*;
+
+    fn gaussian_noise(count: usize, sigma: f64, seed: u64) -> Vec<f64> {
+        let mut rng = Pcg64::seed_from_u64(seed);
+        let mut uniform = || {
+            #[allow(
+                clippy::cast_precision_loss,
+                reason = "53 random bits are exactly representable in f64"
+            )]
+            let mantissa = (rng.next_u64() >> 11) as f64;
+            mantissa * f64::EPSILON / 2.0
+        };
+        (0..count)
+            .map(|_| {
+                let radius = (-2.0 * (1.0 - uniform()).ln()).sqrt();
+                sigma * radius * (TAU * uniform()).cos()
+            })
+            .collect()
+    }
+
+    fn request(values: Vec<f64>, method: ChangepointMethod) -> ChangepointRequest {
+        ChangepointRequest {
+            values,
+            method,
+            cost: ChangepointCost::Mean,
+            penalty: ChangepointPenalty::Bic,
+            min_segment_length: None,
+        }
+    }
+
+    #[test]
+    fn test_two_mean_shifts_are_found() {
+        let values: Vec<f64> = gaussian_noise(200, 0.5, 1)
+            .iter()
+            .enumerate()
+            .map(|(index, noise)| {
+                let level = match index {
+                    0..60 => 0.0,
+                    60..120 => 3.0,
+                    _ => -1.0,
+                };
+                level + noise
+            })
+            .collect();
+        for method in [
+            ChangepointMethod::Pelt,
+            ChangepointMethod::BinarySegmentation,
+        ] {
+            let result = ChangepointEngine::detect(&request(values.clone(), method)).unwrap();
+            assert_eq!(result.changepoints, vec![60, 120], "{method:?}");
+            assert_eq!(result.segments.len(), 3);
+            assert!((result.segments[1].mean - 3.0).abs() < 0.2);
+            assert!(
+                result
+                    .cost_improvements
+                    .iter()
+                    .all(|&gain| gain > result.penalty)
+            );
+        }
+
+        let mut variance_request = request(values, ChangepointMethod::Pelt);
+        variance_request.cost = ChangepointCost::MeanVariance;
+        // Very short segments make the variance estimate unstable.
+        variance_request.min_segment_length = Some(10);
+        let result = ChangepointEngine::detect(&variance_request).unwrap();
+        assert_eq!(result.changepoints, vec![60, 120]);
+    }
+
+    #[test]
+    fn test_white_noise_has_no_changepoints() {
+        for seed in 0..10 {
+            let values = gaussian_noise(200, 1.0, seed);
+            for method in [
+                ChangepointMethod::Pelt,
+                ChangepointMethod::BinarySegmentation,
+            ] {
+                let result = ChangepointEngine::detect(&request(values.clone(), method)).unwrap();
+                assert!(result.changepoints.is_empty(), "seed {seed} {method:?}");
+                assert_eq!(result.segments.len(), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_min_segment_length_is_enforced() {
+        let mut values = vec![0.0; 40];
+        values[20] = 50.0;
+        let mut spike = request(values, ChangepointMethod::Pelt);
+        spike.min_segment_length = Some(5);
+        spike.penalty = ChangepointPenalty::Manual { value: 1.0 };
+        let result = ChangepointEngine::detect(&spike).unwrap();
+        for segment in &result.segments {
+            assert!(segment.end - segment.start >= 5);
+        }
+    }
+}
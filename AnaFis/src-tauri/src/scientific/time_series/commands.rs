@@ -0,0 +1,185 @@
+use super::accuracy::ForecastAccuracyEngine;
+use super::anomaly::SeriesAnomalyEngine;
+use super::changepoint::ChangepointEngine;
+use super::dtw;
+use super::forecasting::TimeSeriesForecastingEngine;
+use super::periodogram::PeriodogramEngine;
+use super::prophet::ProphetEngine;
+use super::resampling::ResamplingEngine;
+use super::types::{
+    ChangepointRequest, ChangepointResult, DtwResult, ForecastAccuracyRequest,
+    ForecastAccuracyResult, HoltWintersRequest, HoltWintersResult, PeriodogramRequest,
+    PeriodogramResult, ProphetAnomalyRequest, ProphetAnomalyResult, ProphetForecastRequest,
+    ProphetForecastResult, ResampleRequest, ResampleResult, SeriesAnomalyRequest,
+    SeriesAnomalyResult, SeriesAnomalyScoreRequest,
+};
+use crate::error::{CommandResult, internal_error, validation_error};
+use crate::utils::tasks::run_heavy;
+
+/// Fit a Holt-Winters model to a series and forecast ahead.
+///
+/// # Errors
+/// Returns an error if the series or parameters are invalid, or if smoothing
+/// parameter estimation fails.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn holt_winters_forecast(request: HoltWintersRequest) -> CommandResult<HoltWintersResult> {
+    TimeSeriesForecastingEngine::holt_winters(
+        &request.series,
+        request.period,
+        request.model,
+        request.alpha,
+        request.beta,
+        request.gamma,
+        request.horizon,
+    )
+    .map_err(|e| validation_error(e, None))
+}
+
+/// Compute the dynamic time warping distance and alignment between two series.
+///
+/// # Errors
+/// Returns an error if either series is empty or non-finite, or if the
+/// problem is too large.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn dynamic_time_warping(
+    series1: Vec<f64>,
+    series2: Vec<f64>,
+    window: Option<usize>,
+) -> CommandResult<DtwResult> {
+    dtw::dynamic_time_warping(&series1, &series2, window).map_err(|e| validation_error(e, None))
+}
+
+/// Evaluate point and interval forecasts against realised values.
+///
+/// # Errors
+/// Returns an error if the inputs are empty, misaligned, or non-finite.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn evaluate_forecast_accuracy(
+    request: ForecastAccuracyRequest,
+) -> CommandResult<ForecastAccuracyResult> {
+    ForecastAccuracyEngine::evaluate(
+        &request.actual,
+        &request.forecast,
+        request.naive_errors.as_deref(),
+        request.prediction_intervals.as_deref(),
+    )
+    .map_err(|e| validation_error(e, None))
+}
+
+/// Fit a Prophet-style trend and seasonality model and forecast at future times.
+///
+/// # Errors
+/// Returns a structured error if the series or configuration is invalid, or
+/// if the least-squares solve fails.
+#[tauri::command]
+pub async fn prophet_forecast(
+    request: ProphetForecastRequest,
+) -> CommandResult<ProphetForecastResult> {
+    run_heavy("prophet_forecast", move || -> CommandResult<_> {
+        let model = ProphetEngine::fit(&request.times, &request.values, &request.config)?;
+        let regressors = request
+            .config
+            .extra_regressors
+            .as_deref()
+            .unwrap_or_default();
+        let training: Vec<&[f64]> = regressors.iter().map(|r| r.values.as_slice()).collect();
+        let future: Vec<&[f64]> = regressors
+            .iter()
+            .map(|r| r.future_values.as_slice())
+            .collect();
+        let fitted = ProphetEngine::predict(&model, &request.times, &training)?;
+        let forecast = ProphetEngine::predict(&model, &request.future_times, &future)?;
+        Ok(ProphetForecastResult {
+            model,
+            fitted,
+            forecast,
+        })
+    })
+    .await
+    .map_err(internal_error)?
+}
+
+/// Fit a Prophet-style model and flag training observations outside
+/// `thresholdSigma` residual standard deviations of the in-sample prediction.
+///
+/// # Errors
+/// Returns a structured error if the series, configuration or threshold is
+/// invalid, or if the least-squares solve fails.
+#[tauri::command]
+pub async fn prophet_detect_anomalies(
+    request: ProphetAnomalyRequest,
+) -> CommandResult<ProphetAnomalyResult> {
+    run_heavy("prophet_detect_anomalies", move || -> CommandResult<_> {
+        let model = ProphetEngine::fit(&request.times, &request.values, &request.config)?;
+        let anomalies = ProphetEngine::detect_anomalies(&model, request.threshold_sigma)?;
+        Ok(ProphetAnomalyResult { model, anomalies })
+    })
+    .await
+    .map_err(internal_error)?
+}
+
+/// Fit a seasonal-trend baseline to a training window and flag observations
+/// whose residual has a large modified z-score.
+///
+/// # Errors
+/// Returns a structured error if the series, training window, threshold or
+/// configuration is invalid, or if the baseline fit fails.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn detect_series_anomalies(
+    request: SeriesAnomalyRequest,
+) -> CommandResult<SeriesAnomalyResult> {
+    Ok(SeriesAnomalyEngine::detect(&request)?)
+}
+
+/// Score new observations against the baseline returned by
+/// `detect_series_anomalies`, without refitting.
+///
+/// # Errors
+/// Returns a structured error if the observations or the state are invalid.
+#[tauri::command]
+pub fn score_series_anomalies(
+    request: SeriesAnomalyScoreRequest,
+) -> CommandResult<SeriesAnomalyResult> {
+    Ok(SeriesAnomalyEngine::score(
+        request.state,
+        &request.times,
+        &request.values,
+    )?)
+}
+
+/// Resample a series onto target timestamps for channel alignment.
+///
+/// # Errors
+/// Returns a structured error if the inputs are invalid, the source has
+/// fewer than two distinct times, or extrapolation is requested to fail.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn resample_series(request: ResampleRequest) -> CommandResult<ResampleResult> {
+    Ok(ResamplingEngine::resample(&request)?)
+}
+
+/// Compute a periodogram and the dominant periods of a series.
+///
+/// # Errors
+/// Returns a structured error if the inputs are invalid or too short, or if
+/// the FFT path is requested for irregular timestamps.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn compute_periodogram(request: PeriodogramRequest) -> CommandResult<PeriodogramResult> {
+    Ok(PeriodogramEngine::compute(&request)?)
+}
+
+/// Detect changepoints in mean, or in mean and variance, of a series.
+///
+/// # Errors
+/// Returns a structured error if the series is too short or non-finite, or
+/// if the segment length or penalty is invalid.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn detect_changepoints(request: ChangepointRequest) -> CommandResult<ChangepointResult> {
+    Ok(ChangepointEngine::detect(&request)?)
+}
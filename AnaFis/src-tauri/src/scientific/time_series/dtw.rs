@@ -0,0 +1,167 @@
+//! Dynamic time warping (DTW).
+//!
+//! Classic O(n·m) dynamic programme with squared local cost, so that the DTW
+//! distance `sqrt(sum of squared aligned differences)` reduces to the Euclidean
+//! distance when the optimal path is the diagonal. An optional Sakoe-Chiba band
+//! limits how far the alignment may stray from the diagonal.
+use super::types::DtwResult;
+
+/// Largest cost matrix (in cells) returned for visualization.
+pub const MAX_COST_MATRIX_CELLS: usize = 250_000;
+/// Largest problem (in cells) accepted at all.
+pub const MAX_DTW_CELLS: usize = 25_000_000;
+
+/// Compute the DTW distance and optimal alignment between two series.
+///
+/// `window` is the Sakoe-Chiba band half-width; it is widened to at least the
+/// length difference of the series so a complete alignment always exists.
+///
+/// # Errors
+/// Returns an error if either series is empty or non-finite, or if the
+/// problem exceeds [`MAX_DTW_CELLS`].
+pub fn dynamic_time_warping(
+    series1: &[f64],
+    series2: &[f64],
+    window: Option<usize>,
+) -> Result<DtwResult, String> {
+    let rows = series1.len();
+    let columns = series2.len();
+    if rows == 0 || columns == 0 {
+        return Err("Both series must contain at least one value".to_owned());
+    }
+    if series1
+        .iter()
+        .chain(series2)
+        .any(|value| !value.is_finite())
+    {
+        return Err("Series contain non-finite values".to_owned());
+    }
+    if rows.saturating_mul(columns) > MAX_DTW_CELLS {
+        return Err(format!(
+            "DTW problem of {rows} x {columns} exceeds the limit of {MAX_DTW_CELLS} cells"
+        ));
+    }
+
+    let band = window.map(|width| width.max(rows.abs_diff(columns)));
+    let in_band = |i: usize, j: usize| band.is_none_or(|width| i.abs_diff(j) <= width);
+
+    // accumulated[i + 1][j + 1] holds the cost of the best path ending at (i, j).
+    let mut accumulated = vec![vec![f64::INFINITY; columns + 1]; rows + 1];
+    accumulated[0][0] = 0.0;
+    for i in 0..rows {
+        for j in 0..columns {
+            if !in_band(i, j) {
+                continue;
+            }
+            let difference = series1[i] - series2[j];
+            let best_previous = accumulated[i][j]
+                .min(accumulated[i][j + 1])
+                .min(accumulated[i + 1][j]);
+            accumulated[i + 1][j + 1] = difference.mul_add(difference, best_previous);
+        }
+    }
+
+    let total_cost = accumulated[rows][columns];
+    let path = backtrack(&accumulated, rows, columns);
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Path length casting to f64 for normalization"
+    )]
+    let normalized_distance = (total_cost / path.len() as f64).sqrt();
+
+    let euclidean_distance = (rows == columns).then(|| {
+        series1
+            .iter()
+            .zip(series2)
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    });
+
+    let cost_matrix = (rows * columns <= MAX_COST_MATRIX_CELLS).then(|| {
+        accumulated[1..]
+            .iter()
+            .map(|row| {
+                row[1..]
+                    .iter()
+                    .map(|&cell| cell.is_finite().then_some(cell))
+                    .collect()
+            })
+            .collect()
+    });
+
+    Ok(DtwResult {
+        distance: total_cost.sqrt(),
+        normalized_distance,
+        path,
+        cost_matrix,
+        euclidean_distance,
+        window: band,
+    })
+}
+
+/// Walk back from the end cell, always stepping to the cheapest predecessor
+/// (diagonal preferred on ties).
+fn backtrack(accumulated: &[Vec<f64>], rows: usize, columns: usize) -> Vec<(usize, usize)> {
+    let mut path = Vec::with_capacity(rows + columns);
+    let (mut i, mut j) = (rows, columns);
+    while i > 0 && j > 0 {
+        path.push((i - 1, j - 1));
+        let diagonal = accumulated[i - 1][j - 1];
+        let up = accumulated[i - 1][j];
+        let left = accumulated[i][j - 1];
+        if diagonal <= up && diagonal <= left {
+            i -= 1;
+            j -= 1;
+        } else if up <= left {
+            i -= 1;
+        } else {
+            j -= 1;
+        }
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_series_have_zero_distance() {
+        let series = [1.0, 3.0, 2.0, 5.0];
+        let result = dynamic_time_warping(&series, &series, None).unwrap();
+        assert!(result.distance.abs() < 1e-12);
+        assert_eq!(result.path, vec![(0, 0), (1, 1), (2, 2), (3, 3)]);
+        assert!(result.euclidean_distance.unwrap().abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_shifted_series_warp_better_than_euclidean() {
+        let base: Vec<f64> = (0..40).map(|t| (f64::from(t) * 0.3).sin()).collect();
+        let shifted: Vec<f64> = (0..40).map(|t| (f64::from(t + 3) * 0.3).sin()).collect();
+        let result = dynamic_time_warping(&base, &shifted, None).unwrap();
+        assert!(result.distance < 0.5 * result.euclidean_distance.unwrap());
+        assert_eq!(result.path.first(), Some(&(0, 0)));
+        assert_eq!(result.path.last(), Some(&(39, 39)));
+    }
+
+    #[test]
+    fn test_window_constrains_path_and_handles_unequal_lengths() {
+        let a = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+        let b = [0.0, 2.0, 4.0];
+        let result = dynamic_time_warping(&a, &b, Some(1)).unwrap();
+        assert_eq!(result.window, Some(3));
+        assert!(result.euclidean_distance.is_none());
+        assert!(result.path.iter().all(|&(i, j)| i.abs_diff(j) <= 3));
+        let matrix = result.cost_matrix.unwrap();
+        assert_eq!(matrix.len(), 6);
+        assert_eq!(matrix[0].len(), 3);
+    }
+
+    #[test]
+    fn test_rejects_empty_series() {
+        assert!(dynamic_time_warping(&[], &[1.0], None).is_err());
+    }
+}
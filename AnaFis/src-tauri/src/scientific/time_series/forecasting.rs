@@ -0,0 +1,488 @@
+//! Exponential-smoothing forecasters.
+//!
+//! Holt-Winters smoothing follows the standard error-correction recursions
+//! (Hyndman & Athanasopoulos, *Forecasting: Principles and Practice*, ch. 8).
+//! Missing smoothing parameters are estimated by minimizing the one-step-ahead
+//! SSE with the bounded Levenberg-Marquardt solver from `primitives`.
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use super::types::{HoltWintersModel, HoltWintersResult, PredictionInterval};
+use crate::scientific::primitives::minimize_bounded_least_squares;
+
+/// Largest forecast horizon accepted in a single request.
+pub const MAX_FORECAST_HORIZON: usize = 10_000;
+/// Maximum LM iterations used when estimating smoothing parameters.
+const SMOOTHING_MAX_ITERATIONS: usize = 200;
+/// Convergence tolerance for the smoothing-parameter search.
+const SMOOTHING_TOLERANCE: f64 = 1e-10;
+/// Coarse starting grid for each free smoothing parameter.
+const SMOOTHING_START_GRID: [f64; 3] = [0.1, 0.4, 0.7];
+/// Prediction interval levels reported with every forecast.
+const INTERVAL_LEVELS: [f64; 2] = [80.0, 95.0];
+
+/// Forecasting models for equally spaced univariate series.
+pub struct TimeSeriesForecastingEngine;
+
+/// State produced by one pass of the smoothing recursion.
+struct SmoothingRun {
+    fitted: Vec<f64>,
+    errors: Vec<f64>,
+    level: f64,
+    trend: f64,
+    seasonal: Vec<f64>,
+}
+
+impl TimeSeriesForecastingEngine {
+    /// Fit a Holt-Winters (triple exponential smoothing) model and forecast `horizon` steps.
+    ///
+    /// Any smoothing parameter passed as `None` is estimated by minimizing the
+    /// in-sample one-step-ahead SSE over `[0, 1]`. The series must contain at
+    /// least two full seasons so that the initial trend and seasonal indices can
+    /// be estimated; multiplicative models additionally require strictly positive data.
+    ///
+    /// # Errors
+    /// Returns an error if the inputs are invalid (short or non-finite series,
+    /// period below 2, out-of-range smoothing parameters, non-positive data for
+    /// the multiplicative model) or if parameter estimation fails.
+    pub fn holt_winters(
+        series: &[f64],
+        period: usize,
+        model: HoltWintersModel,
+        alpha: Option<f64>,
+        beta: Option<f64>,
+        gamma: Option<f64>,
+        horizon: usize,
+    ) -> Result<HoltWintersResult, String> {
+        validate_holt_winters_input(series, period, model, horizon)?;
+        for (name, value) in [("alpha", alpha), ("beta", beta), ("gamma", gamma)] {
+            if let Some(value) = value
+                && (!value.is_finite() || !(0.0..=1.0).contains(&value))
+            {
+                return Err(format!(
+                    "Smoothing parameter {name} must be in [0, 1], got {value}"
+                ));
+            }
+        }
+
+        let fixed = [alpha, beta, gamma];
+        let free_indices: Vec<usize> = (0..3).filter(|&idx| fixed[idx].is_none()).collect();
+        let assemble = |free_values: &[f64]| -> [f64; 3] {
+            let mut params = [0.0; 3];
+            let mut free_iter = free_values.iter();
+            for (slot, fixed_value) in params.iter_mut().zip(fixed) {
+                *slot = fixed_value.unwrap_or_else(|| free_iter.next().copied().unwrap_or(0.0));
+            }
+            params
+        };
+
+        let params = if free_indices.is_empty() {
+            assemble(&[])
+        } else {
+            let objective = |free_values: &[f64]| {
+                let [a, b, g] = assemble(free_values);
+                run_smoothing(series, period, model, a, b, g).errors
+            };
+            let start = best_starting_point(free_indices.len(), &objective);
+            let lower = vec![0.0; free_indices.len()];
+            let upper = vec![1.0; free_indices.len()];
+            let optimum = minimize_bounded_least_squares(
+                objective,
+                &start,
+                &lower,
+                &upper,
+                SMOOTHING_MAX_ITERATIONS,
+                SMOOTHING_TOLERANCE,
+            )?;
+            assemble(&optimum.parameters)
+        };
+
+        let [alpha_value, beta_value, gamma_value] = params;
+        let run = run_smoothing(series, period, model, alpha_value, beta_value, gamma_value);
+        let sse: f64 = run.errors.iter().map(|e| e * e).sum();
+        if !sse.is_finite() {
+            return Err("Holt-Winters recursion diverged for the given parameters".to_owned());
+        }
+
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Sample count casting to f64 for residual variance"
+        )]
+        let residual_variance = sse / series.len() as f64;
+        let residual_std = residual_variance.sqrt();
+
+        let season_offset = series.len();
+        let forecast: Vec<f64> = (1..=horizon)
+            .map(|step| {
+                #[allow(
+                    clippy::cast_precision_loss,
+                    reason = "Forecast step casting to f64 for trend extrapolation"
+                )]
+                let trended = (step as f64).mul_add(run.trend, run.level);
+                let seasonal = run.seasonal[(season_offset + step - 1) % period];
+                match model {
+                    HoltWintersModel::Additive => trended + seasonal,
+                    HoltWintersModel::Multiplicative => trended * seasonal,
+                }
+            })
+            .collect();
+
+        let prediction_intervals = prediction_intervals(
+            &forecast,
+            residual_variance,
+            period,
+            alpha_value,
+            beta_value,
+            gamma_value,
+        )?;
+
+        let names = ["alpha", "beta", "gamma"];
+        Ok(HoltWintersResult {
+            model,
+            period,
+            alpha: alpha_value,
+            beta: beta_value,
+            gamma: gamma_value,
+            fitted: run.fitted,
+            forecast,
+            prediction_intervals,
+            level: run.level,
+            trend: run.trend,
+            seasonal: rotate_seasonal(&run.seasonal, season_offset),
+            sse,
+            residual_std,
+            optimized_parameters: free_indices
+                .iter()
+                .map(|&idx| names[idx].to_owned())
+                .collect(),
+        })
+    }
+}
+
+/// Analytical prediction intervals for the additive error-correction form.
+fn prediction_intervals(
+    forecast: &[f64],
+    residual_variance: f64,
+    period: usize,
+    alpha: f64,
+    beta: f64,
+    gamma: f64,
+) -> Result<Vec<PredictionInterval>, String> {
+    // h-step variance multiplier for the additive error-correction form:
+    // 1 + sum_{j=1}^{h-1} c_j^2 with c_j = alpha(1 + j beta) + gamma [j mod m == 0].
+    // Used as an approximation for the multiplicative model as well.
+    let mut cumulative = 1.0;
+    let step_std: Vec<f64> = (1..=forecast.len())
+        .map(|step| {
+            if step > 1 {
+                let lag = step - 1;
+                #[allow(
+                    clippy::cast_precision_loss,
+                    reason = "Lag casting to f64 for variance multiplier"
+                )]
+                let mut coefficient = alpha * (lag as f64).mul_add(beta, 1.0);
+                if lag % period == 0 {
+                    coefficient += gamma;
+                }
+                cumulative = coefficient.mul_add(coefficient, cumulative);
+            }
+            (residual_variance * cumulative).sqrt()
+        })
+        .collect();
+
+    let standard_normal = Normal::new(0.0, 1.0).map_err(|error| error.to_string())?;
+    Ok(INTERVAL_LEVELS
+        .iter()
+        .map(|&level| {
+            let z = standard_normal.inverse_cdf(1.0 - (1.0 - level / 100.0) / 2.0);
+            PredictionInterval {
+                confidence_level: level,
+                lower: forecast
+                    .iter()
+                    .zip(&step_std)
+                    .map(|(value, sd)| z.mul_add(-sd, *value))
+                    .collect(),
+                upper: forecast
+                    .iter()
+                    .zip(&step_std)
+                    .map(|(value, sd)| z.mul_add(*sd, *value))
+                    .collect(),
+            }
+        })
+        .collect())
+}
+
+fn validate_holt_winters_input(
+    series: &[f64],
+    period: usize,
+    model: HoltWintersModel,
+    horizon: usize,
+) -> Result<(), String> {
+    if period < 2 {
+        return Err(format!("Seasonal period must be at least 2, got {period}"));
+    }
+    if series.len() < 2 * period {
+        return Err(format!(
+            "Holt-Winters needs at least two full seasons ({} points), got {}",
+            2 * period,
+            series.len()
+        ));
+    }
+    if series.iter().any(|value| !value.is_finite()) {
+        return Err("Series contains non-finite values".to_owned());
+    }
+    if model == HoltWintersModel::Multiplicative && series.iter().any(|&value| value <= 0.0) {
+        return Err("Multiplicative Holt-Winters requires strictly positive data".to_owned());
+    }
+    if horizon > MAX_FORECAST_HORIZON {
+        return Err(format!(
+            "Forecast horizon {horizon} exceeds the maximum of {MAX_FORECAST_HORIZON}"
+        ));
+    }
+    Ok(())
+}
+
+/// Classical decomposition start: level from the first season, trend from the
+/// difference between the first two seasons, and seasonal indices averaged over
+/// every complete season after removing each season's mean.
+fn initial_state(series: &[f64], period: usize, model: HoltWintersModel) -> (f64, f64, Vec<f64>) {
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Period casting to f64 for seasonal averages"
+    )]
+    let period_f = period as f64;
+    let first_mean = series[..period].iter().sum::<f64>() / period_f;
+    let second_mean = series[period..2 * period].iter().sum::<f64>() / period_f;
+    let trend = (second_mean - first_mean) / period_f;
+
+    #[allow(clippy::integer_division, reason = "Counting complete seasons only")]
+    let complete_seasons = series.len() / period;
+    let mut seasonal = vec![0.0; period];
+    for season in 0..complete_seasons {
+        let chunk = &series[season * period..(season + 1) * period];
+        let season_mean = chunk.iter().sum::<f64>() / period_f;
+        for (index, &value) in chunk.iter().enumerate() {
+            seasonal[index] += match model {
+                HoltWintersModel::Additive => value - season_mean,
+                HoltWintersModel::Multiplicative => value / season_mean,
+            };
+        }
+    }
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Season count casting to f64 for averaging"
+    )]
+    let season_count = complete_seasons as f64;
+    for index in &mut seasonal {
+        *index /= season_count;
+    }
+
+    (first_mean, trend, seasonal)
+}
+
+fn run_smoothing(
+    series: &[f64],
+    period: usize,
+    model: HoltWintersModel,
+    alpha: f64,
+    beta: f64,
+    gamma: f64,
+) -> SmoothingRun {
+    let (mut level, mut trend, mut seasonal) = initial_state(series, period, model);
+    let mut fitted = Vec::with_capacity(series.len());
+    let mut errors = Vec::with_capacity(series.len());
+
+    for (t, &observed) in series.iter().enumerate() {
+        let slot = t % period;
+        let season = seasonal[slot];
+        let base = level + trend;
+        let prediction = match model {
+            HoltWintersModel::Additive => base + season,
+            HoltWintersModel::Multiplicative => base * season,
+        };
+        fitted.push(prediction);
+        errors.push(observed - prediction);
+
+        let previous_level = level;
+        level = match model {
+            HoltWintersModel::Additive => alpha.mul_add(observed - season, (1.0 - alpha) * base),
+            HoltWintersModel::Multiplicative => {
+                alpha.mul_add(observed / season, (1.0 - alpha) * base)
+            }
+        };
+        trend = beta.mul_add(level - previous_level, (1.0 - beta) * trend);
+        seasonal[slot] = match model {
+            HoltWintersModel::Additive => gamma.mul_add(observed - level, (1.0 - gamma) * season),
+            HoltWintersModel::Multiplicative => {
+                gamma.mul_add(observed / level, (1.0 - gamma) * season)
+            }
+        };
+    }
+
+    SmoothingRun {
+        fitted,
+        errors,
+        level,
+        trend,
+        seasonal,
+    }
+}
+
+/// Pick the best point of a coarse grid as the LM starting point; the SSE
+/// surface of exponential smoothing is frequently multimodal.
+fn best_starting_point<F>(dimension: usize, objective: &F) -> Vec<f64>
+where
+    F: Fn(&[f64]) -> Vec<f64>,
+{
+    let grid_size = SMOOTHING_START_GRID.len();
+    let total = grid_size.pow(u32::try_from(dimension).unwrap_or(3));
+    let mut best = vec![SMOOTHING_START_GRID[0]; dimension];
+    let mut best_cost = f64::INFINITY;
+    let mut candidate = vec![0.0; dimension];
+
+    for combination in 0..total {
+        let mut code = combination;
+        for slot in &mut candidate {
+            *slot = SMOOTHING_START_GRID[code % grid_size];
+            #[allow(clippy::integer_division, reason = "Mixed-radix grid decoding")]
+            {
+                code /= grid_size;
+            }
+        }
+        let cost: f64 = objective(&candidate).iter().map(|e| e * e).sum();
+        if cost.is_finite() && cost < best_cost {
+            best_cost = cost;
+            best.clone_from(&candidate);
+        }
+    }
+    best
+}
+
+/// Re-order the circular seasonal buffer so index 0 is the next forecast step.
+fn rotate_seasonal(seasonal: &[f64], offset: usize) -> Vec<f64> {
+    let period = seasonal.len();
+    (0..period)
+        .map(|index| seasonal[(offset + index) % period])
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    fn seasonal_series(length: usize, multiplicative: bool) -> Vec<f64> {
+        let pattern = [1.0, 3.0, -2.0, -2.0];
+        (0..length)
+            .map(|t| {
+                let level = f64::from(u32::try_from(t).unwrap()).mul_add(0.5, 20.0);
+                if multiplicative {
+                    level * 0.05_f64.mul_add(pattern[t % 4], 1.0)
+                } else {
+                    level + pattern[t % 4]
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_additive_forecast_tracks_trend_and_season() {
+        let series = seasonal_series(40, false);
+        let truth = seasonal_series(48, false);
+        let result = TimeSeriesForecastingEngine::holt_winters(
+            &series,
+            4,
+            HoltWintersModel::Additive,
+            None,
+            None,
+            None,
+            8,
+        )
+        .unwrap();
+
+        assert_eq!(result.forecast.len(), 8);
+        assert_eq!(result.fitted.len(), 40);
+        for (forecast, expected) in result.forecast.iter().zip(&truth[40..]) {
+            assert!(
+                (forecast - expected).abs() < 0.5,
+                "{forecast} vs {expected}"
+            );
+        }
+        assert_eq!(result.optimized_parameters.len(), 3);
+    }
+
+    #[test]
+    fn test_multiplicative_forecast_and_intervals() {
+        let series = seasonal_series(48, true);
+        let truth = seasonal_series(52, true);
+        let result = TimeSeriesForecastingEngine::holt_winters(
+            &series,
+            4,
+            HoltWintersModel::Multiplicative,
+            None,
+            None,
+            None,
+            4,
+        )
+        .unwrap();
+
+        for (forecast, expected) in result.forecast.iter().zip(&truth[48..]) {
+            assert!((forecast - expected).abs() / expected < 0.02);
+        }
+        let [narrow, wide] = [
+            &result.prediction_intervals[0],
+            &result.prediction_intervals[1],
+        ];
+        for step in 0..4 {
+            assert!(wide.lower[step] <= narrow.lower[step]);
+            assert!(wide.upper[step] >= narrow.upper[step]);
+        }
+    }
+
+    #[test]
+    fn test_fixed_parameters_are_not_optimized() {
+        let series = seasonal_series(24, false);
+        let result = TimeSeriesForecastingEngine::holt_winters(
+            &series,
+            4,
+            HoltWintersModel::Additive,
+            Some(0.3),
+            Some(0.1),
+            None,
+            2,
+        )
+        .unwrap();
+        assert!((result.alpha - 0.3).abs() < f64::EPSILON);
+        assert!((result.beta - 0.1).abs() < f64::EPSILON);
+        assert_eq!(result.optimized_parameters, vec!["gamma".to_owned()]);
+    }
+
+    #[test]
+    fn test_rejects_invalid_input() {
+        let short = [1.0, 2.0, 3.0];
+        assert!(
+            TimeSeriesForecastingEngine::holt_winters(
+                &short,
+                4,
+                HoltWintersModel::Additive,
+                None,
+                None,
+                None,
+                1
+            )
+            .is_err()
+        );
+        let with_zero = [1.0, 0.0, 2.0, 1.0, 1.0, 0.5, 2.0, 1.0];
+        assert!(
+            TimeSeriesForecastingEngine::holt_winters(
+                &with_zero,
+                4,
+                HoltWintersModel::Multiplicative,
+                None,
+                None,
+                None,
+                1
+            )
+            .is_err()
+        );
+    }
+}
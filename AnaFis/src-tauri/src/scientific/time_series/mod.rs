@@ -0,0 +1,39 @@
+//! Time-series analysis and forecasting tools.
+pub mod accuracy;
+pub mod anomaly;
+pub mod changepoint;
+pub(crate) mod commands;
+pub mod dtw;
+pub mod forecasting;
+pub mod periodogram;
+pub mod prophet;
+pub mod resampling;
+pub mod spectral;
+mod types;
+
+pub use accuracy::ForecastAccuracyEngine;
+pub use anomaly::SeriesAnomalyEngine;
+pub use changepoint::ChangepointEngine;
+pub use commands::{
+    compute_periodogram, detect_changepoints, detect_series_anomalies, dynamic_time_warping,
+    evaluate_forecast_accuracy, holt_winters_forecast, prophet_detect_anomalies, prophet_forecast,
+    resample_series, score_series_anomalies,
+};
+pub use forecasting::TimeSeriesForecastingEngine;
+pub use periodogram::PeriodogramEngine;
+pub use prophet::ProphetEngine;
+pub use resampling::ResamplingEngine;
+pub use spectral::SpectralEngine;
+pub use types::{
+    AccuracyMetric, AnomalyModelState, AnomalyResult, AnomalyThreshold, ChangepointCost,
+    ChangepointMethod, ChangepointPenalty, ChangepointRequest, ChangepointResult,
+    ChangepointSegment, DominantPeriod, DtwResult, ExtraRegressor, ExtrapolationPolicy,
+    FittedRegressor, FittedSeasonality, ForecastAccuracyRequest, ForecastAccuracyResult,
+    HoltWintersModel, HoltWintersRequest, HoltWintersResult, PeriodogramAlgorithm,
+    PeriodogramMetadata, PeriodogramRequest, PeriodogramResult, PredictionInterval,
+    PredictionIntervals, ProphetAnomalyRequest, ProphetAnomalyResult, ProphetConfig,
+    ProphetFitSummary, ProphetForecastRequest, ProphetForecastResult, ProphetHistory, ProphetModel,
+    ProphetPrediction, RegressorComponent, ResampleMethod, ResampleRequest, ResampleResult,
+    SeasonalComponent, SeriesAnomaly, SeriesAnomalyRequest, SeriesAnomalyResult,
+    SeriesAnomalyScoreRequest, TrendModel, TrendType, WindowFunction,
+};
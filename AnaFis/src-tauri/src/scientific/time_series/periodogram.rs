@@ -0,0 +1,378 @@
+//! Periodograms and dominant-period detection.
+//!
+//! Helps pick a seasonality period before fitting a Prophet model. Evenly
+//! sampled series use a windowed FFT periodogram; irregular sampling falls back
+//! to [`SpectralEngine::lomb_scargle`]. Both report power normalized by the data
+//! variance, which for white noise is approximately exponential with unit mean,
+//! so a peak of height `z` has false-alarm probability `1 - (1 - e^-z)^M` with
+//! `M = n / 2` independent frequencies.
+use std::f64::consts::TAU;
+
+use super::spectral::SpectralEngine;
+use super::types::{
+    DominantPeriod, PeriodogramAlgorithm, PeriodogramMetadata, PeriodogramRequest,
+    PeriodogramResult, WindowFunction,
+};
+use crate::scientific::statistics::StatisticsError;
+
+/// Relative spacing deviation still treated as regular sampling.
+const REGULAR_SPACING_TOLERANCE: f64 = 1e-3;
+/// Default number of reported peaks.
+const DEFAULT_TOP_K: usize = 3;
+/// Default Lomb-Scargle frequency grid oversampling.
+const DEFAULT_OVERSAMPLING: f64 = 5.0;
+/// Fewest observations accepted.
+const MIN_OBSERVATIONS: usize = 4;
+
+/// Spectrum estimation for choosing seasonality periods.
+pub struct PeriodogramEngine;
+
+/// Frequencies, normalized power and grid metadata from one estimator.
+type Spectrum = (Vec<f64>, Vec<f64>, PeriodogramMetadata);
+
+impl PeriodogramEngine {
+    /// Compute the periodogram requested by `request` and its dominant periods.
+    ///
+    /// # Errors
+    /// Returns a validation error for mismatched, non-finite or unsorted
+    /// inputs, invalid options, or an FFT request on irregular timestamps, and
+    /// [`StatisticsError::InsufficientData`] for fewer than four observations.
+    pub fn compute(request: &PeriodogramRequest) -> Result<PeriodogramResult, StatisticsError> {
+        let times = &request.timestamps;
+        validate(request)?;
+        let top_k = request.top_k.unwrap_or(DEFAULT_TOP_K);
+        let oversampling = request.oversampling.unwrap_or(DEFAULT_OVERSAMPLING);
+        if top_k == 0 || !(oversampling.is_finite() && oversampling >= 1.0) {
+            return Err(StatisticsError::validation(
+                "topK",
+                "topK must be positive and oversampling at least 1",
+            ));
+        }
+
+        let n = times.len();
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Sample count casting to f64 for the mean spacing"
+        )]
+        let spacing = (times[n - 1] - times[0]) / (n - 1) as f64;
+        let regular = times.windows(2).all(|pair| {
+            ((pair[1] - pair[0]) - spacing).abs() <= REGULAR_SPACING_TOLERANCE * spacing
+        });
+        let algorithm = match request.algorithm {
+            Some(PeriodogramAlgorithm::Fft) if !regular => {
+                return Err(StatisticsError::validation(
+                    "algorithm",
+                    "The FFT periodogram requires evenly spaced timestamps; use Lomb-Scargle",
+                ));
+            }
+            Some(algorithm) => algorithm,
+            None if regular => PeriodogramAlgorithm::Fft,
+            None => PeriodogramAlgorithm::LombScargle,
+        };
+
+        let residuals = remove_trend(times, &request.values, request.detrend);
+        let (frequencies, power, mut metadata) = match algorithm {
+            PeriodogramAlgorithm::Fft => fft_periodogram(&residuals, spacing, request.window),
+            PeriodogramAlgorithm::LombScargle => {
+                lomb_scargle_periodogram(times, &residuals, oversampling)
+            }
+        };
+        if request.algorithm.is_none() && !regular {
+            metadata.notes.insert(
+                0,
+                "Timestamps are unevenly spaced; using Lomb-Scargle".to_owned(),
+            );
+        }
+
+        #[allow(clippy::integer_division, reason = "Count of Fourier frequencies")]
+        let independent = (n / 2).max(1);
+        let dominant_periods = dominant_peaks(&frequencies, &power, top_k, independent);
+        Ok(PeriodogramResult {
+            algorithm,
+            frequencies,
+            power,
+            dominant_periods,
+            metadata,
+        })
+    }
+}
+
+/// Check lengths, finiteness and ordering.
+fn validate(request: &PeriodogramRequest) -> Result<(), StatisticsError> {
+    let times = &request.timestamps;
+    if request.values.len() != times.len() {
+        return Err(StatisticsError::validation(
+            "values",
+            format!(
+                "Expected one value per timestamp ({} timestamps, {} values)",
+                times.len(),
+                request.values.len()
+            ),
+        ));
+    }
+    if times.len() < MIN_OBSERVATIONS {
+        return Err(StatisticsError::InsufficientData {
+            needed: MIN_OBSERVATIONS,
+            got: times.len(),
+        });
+    }
+    if times
+        .iter()
+        .chain(&request.values)
+        .any(|value| !value.is_finite())
+    {
+        return Err(StatisticsError::validation(
+            "values",
+            "Timestamps and values must be finite",
+        ));
+    }
+    if !times.windows(2).all(|pair| pair[0] < pair[1]) {
+        return Err(StatisticsError::validation(
+            "timestamps",
+            "Timestamps must be strictly increasing",
+        ));
+    }
+    Ok(())
+}
+
+/// Subtract the mean, or the least-squares line when `detrend` is set.
+fn remove_trend(times: &[f64], values: &[f64], detrend: bool) -> Vec<f64> {
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for means"
+    )]
+    let n = values.len() as f64;
+    let value_mean = values.iter().sum::<f64>() / n;
+    let time_mean = times.iter().sum::<f64>() / n;
+    let slope = if detrend {
+        let (covariance, spread) =
+            times
+                .iter()
+                .zip(values)
+                .fold((0.0, 0.0), |(covariance, spread), (&t, &value)| {
+                    let dt = t - time_mean;
+                    (
+                        dt.mul_add(value - value_mean, covariance),
+                        dt.mul_add(dt, spread),
+                    )
+                });
+        covariance / spread
+    } else {
+        0.0
+    };
+    times
+        .iter()
+        .zip(values)
+        .map(|(&t, &value)| (-slope).mul_add(t - time_mean, value - value_mean))
+        .collect()
+}
+
+/// Window weight for sample `index` of `count`.
+fn window_weight(window: WindowFunction, index: usize, count: usize) -> f64 {
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample index casting to f64 for the window phase"
+    )]
+    let phase = TAU * index as f64 / (count - 1) as f64;
+    match window {
+        WindowFunction::None => 1.0,
+        WindowFunction::Hann => 0.5 * (1.0 - phase.cos()),
+        WindowFunction::Hamming => 0.46_f64.mul_add(-phase.cos(), 0.54),
+    }
+}
+
+/// Windowed FFT periodogram from bin 1 up to the Nyquist bin.
+fn fft_periodogram(residuals: &[f64], spacing: f64, window: WindowFunction) -> Spectrum {
+    let n = residuals.len();
+    let weights: Vec<f64> = (0..n).map(|idx| window_weight(window, idx, n)).collect();
+    let tapered: Vec<f64> = residuals.iter().zip(&weights).map(|(y, w)| y * w).collect();
+    let spectrum = SpectralEngine::fft(&tapered);
+    let padded = spectrum.len();
+
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for variance"
+    )]
+    let variance = residuals.iter().map(|y| y * y).sum::<f64>() / (n - 1) as f64;
+    let scale = variance * weights.iter().map(|w| w * w).sum::<f64>();
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "FFT length casting to f64 for the frequency grid"
+    )]
+    let resolution = 1.0 / (padded as f64 * spacing);
+    #[allow(clippy::integer_division, reason = "Nyquist bin index")]
+    let nyquist_bin = padded / 2;
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "FFT bin index casting to f64 for frequency"
+    )]
+    let frequencies = (1..=nyquist_bin).map(|k| k as f64 * resolution).collect();
+    let power = spectrum[1..=nyquist_bin]
+        .iter()
+        .map(|bin| {
+            if scale > 0.0 {
+                bin.norm_sqr() / scale
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    let nyquist_frequency = 0.5 / spacing;
+    let mut notes = vec![format!(
+        "Spectrum stops at the Nyquist frequency {nyquist_frequency}; faster components alias below it"
+    )];
+    if padded > n {
+        notes.push(format!(
+            "Zero-padded from {n} to {padded} samples; bins are interpolated, not independent"
+        ));
+    }
+    let metadata = PeriodogramMetadata {
+        nyquist_frequency,
+        sample_spacing: Some(spacing),
+        input_length: n,
+        padded_length: Some(padded),
+        frequency_resolution: resolution,
+        notes,
+    };
+    (frequencies, power, metadata)
+}
+
+/// Lomb-Scargle periodogram from `1 / T` to the pseudo-Nyquist `n / (2 T)`.
+fn lomb_scargle_periodogram(times: &[f64], residuals: &[f64], oversampling: f64) -> Spectrum {
+    let n = times.len();
+    let span = times[n - 1] - times[0];
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for the pseudo-Nyquist frequency"
+    )]
+    let nyquist_frequency = n as f64 / (2.0 * span);
+    let resolution = 1.0 / (oversampling * span);
+    let lowest = span.recip();
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "Bin count is a small non-negative whole number"
+    )]
+    let bins = ((nyquist_frequency - lowest) / resolution).floor() as usize + 1;
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Bin index casting to f64 for frequency"
+    )]
+    let frequencies: Vec<f64> = (0..bins)
+        .map(|bin| (bin as f64).mul_add(resolution, lowest))
+        .collect();
+    let power = SpectralEngine::lomb_scargle(times, residuals, &frequencies);
+
+    let metadata = PeriodogramMetadata {
+        nyquist_frequency,
+        sample_spacing: None,
+        input_length: n,
+        padded_length: None,
+        frequency_resolution: resolution,
+        notes: vec![
+            format!(
+                "Grid runs from 1/T to the pseudo-Nyquist n/(2T) = {nyquist_frequency}, oversampled {oversampling}x; no zero-padding"
+            ),
+            "Window functions apply to the FFT periodogram only".to_owned(),
+        ],
+    };
+    (frequencies, power, metadata)
+}
+
+/// Strongest local maxima with their false-alarm probabilities.
+fn dominant_peaks(
+    frequencies: &[f64],
+    power: &[f64],
+    top_k: usize,
+    independent: usize,
+) -> Vec<DominantPeriod> {
+    let last = power.len().saturating_sub(1);
+    let mut peaks: Vec<usize> = (0..power.len())
+        .filter(|&idx| {
+            power[idx] > 0.0
+                && (idx == 0 || power[idx] > power[idx - 1])
+                && (idx == last || power[idx] >= power[idx + 1])
+        })
+        .collect();
+    peaks.sort_by(|&a, &b| power[b].total_cmp(&power[a]));
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Frequency count casting to f64 for the false-alarm exponent"
+    )]
+    let trials = independent as f64;
+    peaks
+        .into_iter()
+        .take(top_k)
+        .map(|idx| DominantPeriod {
+            frequency: frequencies[idx],
+            period: frequencies[idx].recip(),
+            power: power[idx],
+            false_alarm_probability: -(trials * (-(-power[idx]).exp()).ln_1p()).exp_m1(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    fn request(timestamps: Vec<f64>, period: f64) -> PeriodogramRequest {
+        let values = timestamps
+            .iter()
+            .map(|t| 0.02_f64.mul_add(*t, (TAU * t / period).sin()))
+            .collect();
+        PeriodogramRequest {
+            timestamps,
+            values,
+            algorithm: None,
+            window: WindowFunction::Hann,
+            detrend: true,
+            top_k: None,
+            oversampling: None,
+        }
+    }
+
+    #[test]
+    fn test_fft_path_finds_regular_period() {
+        let times: Vec<f64> = (0..200).map(f64::from).collect();
+        let result = PeriodogramEngine::compute(&request(times, 12.0)).unwrap();
+        assert_eq!(result.algorithm, PeriodogramAlgorithm::Fft);
+        assert_eq!(result.metadata.padded_length, Some(256));
+        assert!((result.metadata.nyquist_frequency - 0.5).abs() < 1e-12);
+        let top = &result.dominant_periods[0];
+        assert!((top.period - 12.0).abs() < 0.5, "{}", top.period);
+        assert!(top.false_alarm_probability < 1e-10);
+        assert_eq!(result.dominant_periods.len(), 3);
+    }
+
+    #[test]
+    fn test_lomb_scargle_path_finds_irregular_period() {
+        let times: Vec<f64> = (0..150)
+            .map(|i| 0.4_f64.mul_add((1.7 * f64::from(i)).sin(), f64::from(i)))
+            .collect();
+        let result = PeriodogramEngine::compute(&request(times.clone(), 7.0)).unwrap();
+        assert_eq!(result.algorithm, PeriodogramAlgorithm::LombScargle);
+        let top = &result.dominant_periods[0];
+        assert!((top.period - 7.0).abs() < 0.1, "{}", top.period);
+        assert!(top.false_alarm_probability < 1e-10);
+        assert!(result.metadata.notes[0].contains("unevenly"));
+
+        let mut forced = request(times, 7.0);
+        forced.algorithm = Some(PeriodogramAlgorithm::Fft);
+        assert!(PeriodogramEngine::compute(&forced).is_err());
+    }
+
+    #[test]
+    fn test_rejects_invalid_input() {
+        let mut short = request(vec![0.0, 1.0, 2.0], 2.0);
+        assert!(matches!(
+            PeriodogramEngine::compute(&short),
+            Err(StatisticsError::InsufficientData { .. })
+        ));
+        short.timestamps = vec![0.0, 2.0, 1.0, 3.0];
+        short.values = vec![0.0; 4];
+        assert!(PeriodogramEngine::compute(&short).is_err());
+    }
+}
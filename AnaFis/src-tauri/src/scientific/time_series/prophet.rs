@@ -0,0 +1,1244 @@
+//! Prophet-style decomposable forecasting model.
+//!
+//! `y(t) = trend(t) + Σ seasonal_p(t) + Σ β_r x_r(t) + ε`, with a continuous
+//! piecewise-linear or logistic trend, one Fourier series per seasonal period
+//! and linear effects of extra regressors. The linear coefficients are
+//! estimated jointly by ridge-penalised least squares: the changepoint rate
+//! adjustments and Fourier coefficients get Gaussian priors whose scales are
+//! `changepoint_prior_scale` and `seasonality_prior_scale`, with the
+//! observation noise taken from an initial, lightly penalised fit. Regressors
+//! are standardized and otherwise unpenalised (plain OLS). A logistic trend's
+//! growth rate and midpoint are found by Levenberg-Marquardt, solving for the
+//! linear terms at every step (variable projection).
+//!
+//! With `auto_seasonality`, seasonal periods are also taken from the
+//! significant peaks of the detrended series' periodogram; a peak whose period
+//! the history covers fewer than two times is reported but not fitted.
+use nalgebra::{DMatrix, DVector};
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use super::changepoint::ChangepointEngine;
+use super::periodogram::PeriodogramEngine;
+use super::spectral::SpectralEngine;
+use super::types::{
+    AnomalyResult, ChangepointCost, ChangepointMethod, ChangepointPenalty, ChangepointRequest,
+    DominantPeriod, ExtraRegressor, FittedRegressor, FittedSeasonality, PeriodogramRequest,
+    ProphetConfig, ProphetFitSummary, ProphetHistory, ProphetModel, ProphetPrediction,
+    RegressorComponent, SeasonalComponent, TrendModel, TrendType, WindowFunction,
+};
+use crate::scientific::primitives::descriptive::{mean, sample_variance, sorted};
+use crate::scientific::primitives::minimize_bounded_least_squares;
+use crate::scientific::statistics::StatisticsError;
+
+/// Minimum number of observations for a fit.
+const MIN_OBSERVATIONS: usize = 3;
+/// Penalty on the unpenalised trend terms, keeping the normal equations regular.
+const BASE_PENALTY: f64 = 1e-9;
+/// Singular value cut-off for the penalised normal equations.
+const SOLVE_EPS: f64 = 1e-12;
+/// Largest logistic growth rate, per unit of scaled (0..1) time.
+const LOGISTIC_MAX_RATE: f64 = 1e3;
+/// How far outside the history (in scaled time) the logistic midpoint may lie.
+const LOGISTIC_MIDPOINT_MARGIN: f64 = 10.0;
+/// Iteration budget for the logistic trend search.
+const LOGISTIC_MAX_ITERATIONS: usize = 200;
+/// Periodogram peaks examined per seasonal period `auto_seasonality` may add.
+const CANDIDATES_PER_SEASONALITY: usize = 4;
+/// Relative distance within which a detected period duplicates a configured one.
+const DUPLICATE_PERIOD_TOLERANCE: f64 = 0.05;
+
+/// Affine maps from data units to the unit-scale fitting problem.
+#[derive(Debug, Clone, Copy)]
+struct Scaling {
+    t_start: f64,
+    t_scale: f64,
+    y_scale: f64,
+}
+
+/// Scaled training data and the non-trend design columns.
+struct Design<'data> {
+    times: &'data [f64],
+    scaled_times: &'data [f64],
+    scaled_values: &'data [f64],
+    seasonalities: &'data [(f64, usize)],
+    regressors: &'data [Vec<f64>],
+}
+
+/// Coefficients and residuals of a penalised linear fit.
+struct LinearFit {
+    coefficients: Vec<f64>,
+    residuals: Vec<f64>,
+}
+
+/// Seasonal `(period, n_harmonics)` pairs, the spectral power of each and
+/// how they were chosen.
+type SeasonalChoice = (Vec<(f64, usize)>, Vec<Option<f64>>, ProphetFitSummary);
+
+/// Prophet-style trend plus seasonality model.
+pub struct ProphetEngine;
+
+impl ProphetEngine {
+    /// Fit the model to `values` observed at `times`.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::InsufficientData`] for fewer than three
+    /// observations, [`StatisticsError::Validation`] if the inputs are
+    /// misaligned, non-finite or span no time or the configuration is invalid,
+    /// and [`StatisticsError::Numerical`] if the least-squares solve fails.
+    pub fn fit(
+        times: &[f64],
+        values: &[f64],
+        config: &ProphetConfig,
+    ) -> Result<ProphetModel, StatisticsError> {
+        validate_inputs(times, values, config)?;
+        let (seasonalities, spectral_powers, summary) = seasonal_components(times, values, config)?;
+
+        let sorted_times = sorted(times);
+        let y_scale = values.iter().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+        let scaling = Scaling {
+            t_start: sorted_times[0],
+            t_scale: sorted_times[sorted_times.len() - 1] - sorted_times[0],
+            y_scale: if y_scale > 0.0 { y_scale } else { 1.0 },
+        };
+        let scaled_times: Vec<f64> = times
+            .iter()
+            .map(|t| (t - scaling.t_start) / scaling.t_scale)
+            .collect();
+        let scaled_values: Vec<f64> = values.iter().map(|v| v / scaling.y_scale).collect();
+
+        let regressors = config.extra_regressors.as_deref().unwrap_or_default();
+        let (standardization, regressor_columns) = standardize_regressors(regressors)?;
+        let design = Design {
+            times,
+            scaled_times: &scaled_times,
+            scaled_values: &scaled_values,
+            seasonalities: &seasonalities,
+            regressors: &regressor_columns,
+        };
+
+        let (trend, coefficients) = fit_trend(&design, &sorted_times, scaling, config)?;
+
+        let mut seasonal_offset = 0;
+        let fitted_seasonalities = seasonalities
+            .iter()
+            .zip(spectral_powers)
+            .map(|(&(period, harmonics), spectral_power)| {
+                let block = &coefficients[seasonal_offset..seasonal_offset + 2 * harmonics];
+                seasonal_offset += 2 * harmonics;
+                FittedSeasonality {
+                    period,
+                    harmonics,
+                    coefficients: block.iter().map(|c| c * scaling.y_scale).collect(),
+                    spectral_power,
+                }
+            })
+            .collect();
+        let fitted_regressors = regressors
+            .iter()
+            .zip(&standardization)
+            .zip(&coefficients[seasonal_offset..])
+            .map(|((regressor, &(center, spread)), &beta)| FittedRegressor {
+                name: regressor.name.clone(),
+                coefficient: beta * scaling.y_scale / spread,
+                mean: center,
+                std: spread,
+            })
+            .collect();
+
+        let parameter_count = 2 + trend.changepoints.len() + coefficients.len();
+        let mut model = ProphetModel {
+            trend,
+            seasonalities: fitted_seasonalities,
+            regressors: fitted_regressors,
+            residual_std: 0.0,
+            interval_width: config.interval_width,
+            summary,
+            history: ProphetHistory {
+                times: times.to_vec(),
+                values: values.to_vec(),
+                regressor_values: regressors
+                    .iter()
+                    .map(|regressor| regressor.values.clone())
+                    .collect(),
+            },
+        };
+
+        let training_regressors: Vec<&[f64]> = regressors
+            .iter()
+            .map(|regressor| regressor.values.as_slice())
+            .collect();
+        let fitted = Self::predict(&model, times, &training_regressors)?;
+        let sse: f64 = values
+            .iter()
+            .zip(&fitted.yhat)
+            .map(|(v, f)| (v - f).powi(2))
+            .sum();
+        let dof = values.len().saturating_sub(parameter_count).max(1);
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Degrees of freedom casting to f64 for variance"
+        )]
+        let residual_std = (sse / dof as f64).sqrt();
+        model.residual_std = residual_std;
+        Ok(model)
+    }
+
+    /// Evaluate a fitted model at `times`, with per-period seasonal and
+    /// per-regressor breakdowns.
+    ///
+    /// `regressor_values` holds one series per fitted regressor, in model
+    /// order, aligned with `times`.
+    ///
+    /// # Errors
+    /// Returns an error if `times` or the regressor values are non-finite, or
+    /// if the regressor values do not match the model and `times`.
+    pub fn predict(
+        model: &ProphetModel,
+        times: &[f64],
+        regressor_values: &[&[f64]],
+    ) -> Result<ProphetPrediction, StatisticsError> {
+        if times.iter().any(|t| !t.is_finite()) {
+            return Err(StatisticsError::validation(
+                "times",
+                "Prediction times must be finite",
+            ));
+        }
+        let regressor_components = regressor_components(model, times, regressor_values)?;
+
+        let trend = predict_trend(&model.trend, times);
+
+        let seasonal_components: Vec<SeasonalComponent> = model
+            .seasonalities
+            .iter()
+            .map(|seasonality| {
+                let basis = SpectralEngine::generate_fourier_basis(
+                    times,
+                    seasonality.period,
+                    seasonality.harmonics,
+                );
+                let values = (0..times.len())
+                    .map(|row| {
+                        basis
+                            .iter()
+                            .zip(&seasonality.coefficients)
+                            .fold(0.0, |acc, (column, &beta)| beta.mul_add(column[row], acc))
+                    })
+                    .collect();
+                SeasonalComponent {
+                    period: seasonality.period,
+                    values,
+                }
+            })
+            .collect();
+
+        let seasonal: Vec<f64> = (0..times.len())
+            .map(|row| {
+                seasonal_components
+                    .iter()
+                    .map(|component| component.values[row])
+                    .sum()
+            })
+            .collect();
+        let yhat: Vec<f64> = (0..times.len())
+            .map(|row| {
+                trend[row]
+                    + seasonal[row]
+                    + regressor_components
+                        .iter()
+                        .map(|component| component.values[row])
+                        .sum::<f64>()
+            })
+            .collect();
+
+        let standard_normal = Normal::new(0.0, 1.0).map_err(|error| error.to_string())?;
+        let half_width =
+            standard_normal.inverse_cdf(0.5 + model.interval_width / 2.0) * model.residual_std;
+
+        Ok(ProphetPrediction {
+            times: times.to_vec(),
+            yhat_lower: yhat.iter().map(|y| y - half_width).collect(),
+            yhat_upper: yhat.iter().map(|y| y + half_width).collect(),
+            yhat,
+            trend,
+            seasonal,
+            seasonal_components,
+            regressor_components,
+        })
+    }
+
+    /// Flag training observations that fall outside `threshold_sigma`
+    /// residual standard deviations of the in-sample prediction.
+    ///
+    /// Returns the flagged observations in series order; the `score` of each is
+    /// its distance from the prediction in residual standard deviations. Removing
+    /// them and re-fitting gives a model less distorted by outliers.
+    ///
+    /// # Errors
+    /// Returns [`StatisticsError::Validation`] if `threshold_sigma` is not a
+    /// positive finite number, and propagates errors from [`Self::predict`].
+    pub fn detect_anomalies(
+        model: &ProphetModel,
+        threshold_sigma: f64,
+    ) -> Result<Vec<AnomalyResult>, StatisticsError> {
+        if !(threshold_sigma.is_finite() && threshold_sigma > 0.0) {
+            return Err(StatisticsError::validation(
+                "thresholdSigma",
+                "Anomaly threshold must be a positive number of standard deviations",
+            ));
+        }
+        let history = &model.history;
+        let regressor_values: Vec<&[f64]> =
+            history.regressor_values.iter().map(Vec::as_slice).collect();
+        let fitted = Self::predict(model, &history.times, &regressor_values)?;
+
+        let half_width = threshold_sigma * model.residual_std;
+        let anomalies = history
+            .values
+            .iter()
+            .zip(&fitted.yhat)
+            .enumerate()
+            .filter_map(|(index, (&actual, &predicted))| {
+                let deviation = (actual - predicted).abs();
+                let score = if model.residual_std > 0.0 {
+                    deviation / model.residual_std
+                } else if deviation > 0.0 {
+                    f64::INFINITY
+                } else {
+                    0.0
+                };
+                (score > threshold_sigma).then(|| AnomalyResult {
+                    index,
+                    time: history.times[index],
+                    actual,
+                    predicted,
+                    lower: predicted - half_width,
+                    upper: predicted + half_width,
+                    score,
+                })
+            })
+            .collect();
+        Ok(anomalies)
+    }
+}
+
+/// Contribution of every fitted regressor at the prediction times.
+fn regressor_components(
+    model: &ProphetModel,
+    times: &[f64],
+    regressor_values: &[&[f64]],
+) -> Result<Vec<RegressorComponent>, StatisticsError> {
+    if regressor_values.len() != model.regressors.len() {
+        return Err(StatisticsError::validation(
+            "extraRegressors",
+            format!(
+                "Expected values for {} extra regressors, got {}",
+                model.regressors.len(),
+                regressor_values.len()
+            ),
+        ));
+    }
+    let mut components = Vec::with_capacity(model.regressors.len());
+    for (regressor, values) in model.regressors.iter().zip(regressor_values) {
+        if values.len() != times.len() || values.iter().any(|x| !x.is_finite()) {
+            return Err(StatisticsError::validation(
+                "extraRegressors",
+                format!(
+                    "Extra regressor '{}' needs {} finite values",
+                    regressor.name,
+                    times.len()
+                ),
+            ));
+        }
+        components.push(RegressorComponent {
+            name: regressor.name.clone(),
+            values: values
+                .iter()
+                .map(|x| regressor.coefficient * (x - regressor.mean))
+                .collect(),
+        });
+    }
+    Ok(components)
+}
+
+fn validate_inputs(
+    times: &[f64],
+    values: &[f64],
+    config: &ProphetConfig,
+) -> Result<(), StatisticsError> {
+    if times.len() != values.len() {
+        return Err(StatisticsError::validation(
+            "values",
+            format!(
+                "Times and values lengths differ ({} vs {})",
+                times.len(),
+                values.len()
+            ),
+        ));
+    }
+    if times.len() < MIN_OBSERVATIONS {
+        return Err(StatisticsError::InsufficientData {
+            needed: MIN_OBSERVATIONS,
+            got: times.len(),
+        });
+    }
+    if times.iter().chain(values).any(|value| !value.is_finite()) {
+        return Err(StatisticsError::validation(
+            "values",
+            "Times and values must be finite",
+        ));
+    }
+    if !(config.changepoint_range > 0.0 && config.changepoint_range <= 1.0) {
+        return Err(StatisticsError::validation(
+            "changepointRange",
+            "Changepoint range must be in (0, 1]",
+        ));
+    }
+    if !(config.changepoint_prior_scale > 0.0 && config.seasonality_prior_scale > 0.0) {
+        return Err(StatisticsError::validation(
+            "changepointPriorScale",
+            "Prior scales must be positive",
+        ));
+    }
+    if !(config.interval_width > 0.0 && config.interval_width < 1.0) {
+        return Err(StatisticsError::validation(
+            "intervalWidth",
+            "Interval width must be in (0, 1)",
+        ));
+    }
+    if let TrendType::Logistic { capacity, floor } = config.trend_type
+        && !(capacity.is_finite() && floor.is_finite() && capacity > floor)
+    {
+        return Err(StatisticsError::validation(
+            "trendType",
+            format!("Logistic capacity ({capacity}) must exceed the floor ({floor})"),
+        ));
+    }
+    validate_seasonality(config)?;
+    let mut names = std::collections::HashSet::new();
+    for regressor in config.extra_regressors.as_deref().unwrap_or_default() {
+        if regressor.name.trim().is_empty() || !names.insert(regressor.name.as_str()) {
+            return Err(StatisticsError::validation(
+                "extraRegressors",
+                format!(
+                    "Extra regressor names must be non-empty and unique, got '{}'",
+                    regressor.name
+                ),
+            ));
+        }
+        if regressor.values.len() != times.len() || regressor.values.iter().any(|x| !x.is_finite())
+        {
+            return Err(StatisticsError::validation(
+                "extraRegressors",
+                format!(
+                    "Extra regressor '{}' needs {} finite training values",
+                    regressor.name,
+                    times.len()
+                ),
+            ));
+        }
+    }
+    let earliest = times.iter().copied().fold(f64::INFINITY, f64::min);
+    let latest = times.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if latest <= earliest {
+        return Err(StatisticsError::validation(
+            "times",
+            "Times must span a non-zero interval",
+        ));
+    }
+    Ok(())
+}
+
+/// Check the configured seasonal periods and the automatic detection options.
+fn validate_seasonality(config: &ProphetConfig) -> Result<(), StatisticsError> {
+    for (period, harmonics) in seasonality_specs(config) {
+        if !(period.is_finite() && period > 0.0) {
+            return Err(StatisticsError::validation(
+                "seasonalityPeriods",
+                format!("Seasonal period must be positive, got {period}"),
+            ));
+        }
+        if harmonics == 0 {
+            return Err(StatisticsError::validation(
+                "seasonalityPeriods",
+                format!("Seasonal period {period} needs at least one harmonic"),
+            ));
+        }
+    }
+    if config.auto_seasonality
+        && !(config.max_seasonalities > 0
+            && config.fourier_order > 0
+            && config.seasonality_false_alarm > 0.0
+            && config.seasonality_false_alarm < 1.0)
+    {
+        return Err(StatisticsError::validation(
+            "maxSeasonalities",
+            "Automatic seasonality needs at least one period of at least one harmonic and a false-alarm probability in (0, 1)",
+        ));
+    }
+    Ok(())
+}
+
+/// Seasonal components as `(period, n_harmonics)` pairs.
+fn seasonality_specs(config: &ProphetConfig) -> Vec<(f64, usize)> {
+    config.seasonality_periods.clone().unwrap_or_else(|| {
+        config
+            .seasonality_period
+            .map(|period| vec![(period, config.fourier_order)])
+            .unwrap_or_default()
+    })
+}
+
+/// Configured seasonal components followed by the detected ones, the
+/// spectral power of each (`None` when configured) and how they were chosen.
+fn seasonal_components(
+    times: &[f64],
+    values: &[f64],
+    config: &ProphetConfig,
+) -> Result<SeasonalChoice, StatisticsError> {
+    let mut seasonalities = seasonality_specs(config);
+    let mut summary = if config.auto_seasonality {
+        detect_seasonalities(times, values, &seasonalities, config)?
+    } else {
+        ProphetFitSummary::default()
+    };
+    summary.configured_periods = seasonalities.iter().map(|&(period, _)| period).collect();
+    let spectral_powers = seasonalities
+        .iter()
+        .map(|_| None)
+        .chain(summary.detected_periods.iter().map(|peak| Some(peak.power)))
+        .collect();
+    seasonalities.extend(
+        summary
+            .detected_periods
+            .iter()
+            .map(|peak| (peak.period, config.fourier_order)),
+    );
+    Ok((seasonalities, spectral_powers, summary))
+}
+
+/// Significant periodogram peaks of the detrended series, strongest first,
+/// skipping periods within 5% of a configured one. Peaks the history covers
+/// fewer than two times are excluded rather than fitted.
+fn detect_seasonalities(
+    times: &[f64],
+    values: &[f64],
+    configured: &[(f64, usize)],
+    config: &ProphetConfig,
+) -> Result<ProphetFitSummary, StatisticsError> {
+    let mut order: Vec<usize> = (0..times.len()).collect();
+    order.sort_by(|&left, &right| times[left].total_cmp(&times[right]));
+    let request = PeriodogramRequest {
+        timestamps: order.iter().map(|&index| times[index]).collect(),
+        values: order.iter().map(|&index| values[index]).collect(),
+        algorithm: None,
+        window: WindowFunction::Hann,
+        detrend: true,
+        top_k: Some(config.max_seasonalities * CANDIDATES_PER_SEASONALITY),
+        oversampling: None,
+    };
+    let span = request.timestamps[times.len() - 1] - request.timestamps[0];
+    let periodogram = PeriodogramEngine::compute(&request)?;
+
+    let (mut detected_periods, excluded_periods): (Vec<DominantPeriod>, Vec<DominantPeriod>) =
+        periodogram
+            .dominant_periods
+            .into_iter()
+            .filter(|peak| {
+                peak.false_alarm_probability < config.seasonality_false_alarm
+                    && configured.iter().all(|&(period, _)| {
+                        (peak.period - period).abs() > DUPLICATE_PERIOD_TOLERANCE * period
+                    })
+            })
+            .partition(|peak| 2.0 * peak.period <= span);
+    detected_periods.truncate(config.max_seasonalities);
+    Ok(ProphetFitSummary {
+        detected_periods,
+        excluded_periods,
+        ..ProphetFitSummary::default()
+    })
+}
+
+/// Changepoints over the first `changepoint_range` of the observations,
+/// excluding the first one: evenly spread, or at detected slope shifts when
+/// `changepoint_detection` is set.
+fn changepoint_locations(
+    design: &Design<'_>,
+    sorted_times: &[f64],
+    config: &ProphetConfig,
+) -> Vec<f64> {
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "History length is non-negative and bounded by the sample count"
+    )]
+    let history = ((sorted_times.len() as f64) * config.changepoint_range).floor() as usize;
+    let count = config.n_changepoints.min(history.saturating_sub(1));
+    if count == 0 {
+        return Vec::new();
+    }
+    if let Some(method) = config.changepoint_detection {
+        return detected_changepoints(
+            design,
+            (sorted_times[0], sorted_times[history - 1]),
+            method,
+            count,
+        );
+    }
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "Changepoint index is non-negative and below the history length"
+    )]
+    let mut changepoints: Vec<f64> = (1..=count)
+        .map(|k| {
+            let position = (history - 1) as f64 * k as f64 / count as f64;
+            sorted_times[position.round() as usize]
+        })
+        .collect();
+    changepoints.dedup();
+    changepoints
+}
+
+/// Up to `count` changepoints in the open-closed `window` of times where
+/// `method` finds a shift in the local slope, keeping the largest cost
+/// improvements.
+///
+/// A mean shift in the slopes between consecutive observations is a kink in
+/// the trend; strong seasonality also moves the slopes, so this suits series
+/// whose trend changes dominate.
+fn detected_changepoints(
+    design: &Design<'_>,
+    window: (f64, f64),
+    method: ChangepointMethod,
+    count: usize,
+) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..design.times.len()).collect();
+    order.sort_by(|&a, &b| design.times[a].total_cmp(&design.times[b]));
+    let mut slope_times = Vec::with_capacity(order.len());
+    let mut slopes = Vec::with_capacity(order.len());
+    for pair in order.windows(2) {
+        let step = design.scaled_times[pair[1]] - design.scaled_times[pair[0]];
+        if step > 0.0 {
+            slope_times.push(design.times[pair[0]]);
+            slopes.push((design.scaled_values[pair[1]] - design.scaled_values[pair[0]]) / step);
+        }
+    }
+
+    let request = ChangepointRequest {
+        values: slopes,
+        method,
+        cost: ChangepointCost::Mean,
+        penalty: ChangepointPenalty::Bic,
+        min_segment_length: None,
+    };
+    let Ok(detected) = ChangepointEngine::detect(&request) else {
+        return Vec::new();
+    };
+    let mut ranked: Vec<(f64, f64)> = detected
+        .changepoints
+        .iter()
+        .zip(&detected.cost_improvements)
+        .map(|(&index, &gain)| (slope_times[index], gain))
+        .filter(|&(time, _)| time > window.0 && time <= window.1)
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let mut changepoints: Vec<f64> = ranked
+        .into_iter()
+        .take(count)
+        .map(|(time, _)| time)
+        .collect();
+    changepoints.sort_by(f64::total_cmp);
+    changepoints
+}
+
+/// Per-regressor `(mean, std)` pairs and the standardized training columns.
+type StandardizedRegressors = (Vec<(f64, f64)>, Vec<Vec<f64>>);
+
+/// Standardize every regressor, returning its `(mean, std)` and the
+/// standardized training column.
+fn standardize_regressors(
+    regressors: &[ExtraRegressor],
+) -> Result<StandardizedRegressors, StatisticsError> {
+    let mut standardization = Vec::with_capacity(regressors.len());
+    let mut columns = Vec::with_capacity(regressors.len());
+    for regressor in regressors {
+        let center = mean(&regressor.values).unwrap_or_default();
+        let spread = sample_variance(&regressor.values)
+            .unwrap_or_default()
+            .sqrt();
+        if spread <= 0.0 {
+            return Err(StatisticsError::validation(
+                "extraRegressors",
+                format!("Extra regressor '{}' is constant", regressor.name),
+            ));
+        }
+        standardization.push((center, spread));
+        columns.push(
+            regressor
+                .values
+                .iter()
+                .map(|x| (x - center) / spread)
+                .collect(),
+        );
+    }
+    Ok((standardization, columns))
+}
+
+/// Intercept, slope and hinge columns `(t - s_j)+` on the scaled time axis.
+fn trend_columns(scaled_times: &[f64], scaled_changepoints: &[f64]) -> Vec<Vec<f64>> {
+    let mut columns = Vec::with_capacity(2 + scaled_changepoints.len());
+    columns.push(vec![1.0; scaled_times.len()]);
+    columns.push(scaled_times.to_vec());
+    for &changepoint in scaled_changepoints {
+        columns.push(
+            scaled_times
+                .iter()
+                .map(|&t| (t - changepoint).max(0.0))
+                .collect(),
+        );
+    }
+    columns
+}
+
+/// Trend value at each of `times`.
+fn predict_trend(trend: &TrendModel, times: &[f64]) -> Vec<f64> {
+    if let (Some(capacity), Some(midpoint)) = (trend.capacity, trend.midpoint) {
+        return times
+            .iter()
+            .map(|&t| logistic_curve(t, trend.offset, capacity, trend.growth_rate, midpoint))
+            .collect();
+    }
+    times
+        .iter()
+        .map(|&t| {
+            trend
+                .changepoints
+                .iter()
+                .zip(&trend.changepoint_deltas)
+                .fold(
+                    trend.growth_rate.mul_add(t - trend.t_start, trend.offset),
+                    |acc, (&c, &delta)| delta.mul_add((t - c).max(0.0), acc),
+                )
+        })
+        .collect()
+}
+
+/// `floor + (capacity - floor) / (1 + exp(-rate (t - midpoint)))`.
+fn logistic_curve(t: f64, floor: f64, capacity: f64, rate: f64, midpoint: f64) -> f64 {
+    floor + (capacity - floor) / (1.0 + (-rate * (t - midpoint)).exp())
+}
+
+/// Fit the trend jointly with the seasonal and regressor terms, returning the
+/// trend in data units and the seasonal and regressor coefficients on the
+/// scaled problem.
+fn fit_trend(
+    design: &Design<'_>,
+    sorted_times: &[f64],
+    scaling: Scaling,
+    config: &ProphetConfig,
+) -> Result<(TrendModel, Vec<f64>), StatisticsError> {
+    match config.trend_type {
+        TrendType::Linear => {
+            let changepoints = changepoint_locations(design, sorted_times, config);
+            let scaled_changepoints: Vec<f64> = changepoints
+                .iter()
+                .map(|c| (c - scaling.t_start) / scaling.t_scale)
+                .collect();
+            let columns = trend_columns(design.scaled_times, &scaled_changepoints);
+            let fit = fit_seasonal(&columns, design, design.scaled_values, config)?;
+            let rate_scale = scaling.y_scale / scaling.t_scale;
+            let trend = TrendModel {
+                t_start: scaling.t_start,
+                offset: fit.coefficients[0] * scaling.y_scale,
+                growth_rate: fit.coefficients[1] * rate_scale,
+                changepoint_deltas: fit.coefficients[2..columns.len()]
+                    .iter()
+                    .map(|d| d * rate_scale)
+                    .collect(),
+                changepoints,
+                capacity: None,
+                midpoint: None,
+            };
+            Ok((trend, fit.coefficients[columns.len()..].to_vec()))
+        }
+        TrendType::Logistic { capacity, floor } => {
+            fit_logistic_trend(design, scaling, capacity, floor, config)
+        }
+    }
+}
+
+/// Fit a logistic trend by Levenberg-Marquardt over `(rate, midpoint)`,
+/// solving for the seasonal and regressor terms at every evaluation.
+fn fit_logistic_trend(
+    design: &Design<'_>,
+    scaling: Scaling,
+    capacity: f64,
+    floor: f64,
+    config: &ProphetConfig,
+) -> Result<(TrendModel, Vec<f64>), StatisticsError> {
+    let scaled_capacity = capacity / scaling.y_scale;
+    let scaled_floor = floor / scaling.y_scale;
+    let detrended = |parameters: &[f64]| -> Vec<f64> {
+        design
+            .scaled_values
+            .iter()
+            .zip(design.scaled_times)
+            .map(|(&y, &t)| {
+                y - logistic_curve(
+                    t,
+                    scaled_floor,
+                    scaled_capacity,
+                    parameters[0],
+                    parameters[1],
+                )
+            })
+            .collect()
+    };
+    let residual_fn = |parameters: &[f64]| {
+        fit_seasonal(&[], design, &detrended(parameters), config).map_or_else(
+            |_| vec![f64::INFINITY; design.scaled_values.len()],
+            |fit| fit.residuals,
+        )
+    };
+
+    let initial = logistic_initial_guess(design, scaled_floor, scaled_capacity);
+    let solution = minimize_bounded_least_squares(
+        residual_fn,
+        &initial,
+        &[-LOGISTIC_MAX_RATE, -LOGISTIC_MIDPOINT_MARGIN],
+        &[LOGISTIC_MAX_RATE, 1.0 + LOGISTIC_MIDPOINT_MARGIN],
+        LOGISTIC_MAX_ITERATIONS,
+        1e-12,
+    )?;
+    let fit = fit_seasonal(&[], design, &detrended(&solution.parameters), config)?;
+    let trend = TrendModel {
+        t_start: scaling.t_start,
+        offset: floor,
+        growth_rate: solution.parameters[0] / scaling.t_scale,
+        changepoints: Vec::new(),
+        changepoint_deltas: Vec::new(),
+        capacity: Some(capacity),
+        midpoint: Some(solution.parameters[1].mul_add(scaling.t_scale, scaling.t_start)),
+    };
+    Ok((trend, fit.coefficients))
+}
+
+/// Starting `(rate, midpoint)` from a straight-line fit to the logit of the
+/// data's position between floor and capacity.
+fn logistic_initial_guess(design: &Design<'_>, floor: f64, capacity: f64) -> [f64; 2] {
+    let logits: Vec<f64> = design
+        .scaled_values
+        .iter()
+        .map(|&y| {
+            let share = ((y - floor) / (capacity - floor)).clamp(0.01, 0.99);
+            (share / (1.0 - share)).ln()
+        })
+        .collect();
+    let t_mean = mean(design.scaled_times).unwrap_or(0.5);
+    let logit_mean = mean(&logits).unwrap_or_default();
+    let (covariance, variance) = design.scaled_times.iter().zip(&logits).fold(
+        (0.0, 0.0),
+        |(covariance, variance), (&t, &logit)| {
+            (
+                (t - t_mean).mul_add(logit - logit_mean, covariance),
+                (t - t_mean).mul_add(t - t_mean, variance),
+            )
+        },
+    );
+    let rate = covariance / variance;
+    if !(rate.is_finite() && rate.abs() > 1e-6) {
+        return [1.0, 0.5];
+    }
+    [
+        rate.clamp(-LOGISTIC_MAX_RATE, LOGISTIC_MAX_RATE),
+        (t_mean - logit_mean / rate)
+            .clamp(-LOGISTIC_MIDPOINT_MARGIN, 1.0 + LOGISTIC_MIDPOINT_MARGIN),
+    ]
+}
+
+/// Jointly fit `trend` columns, the Fourier bases of every seasonal period
+/// and the standardized regressors to `target`, returning coefficients in
+/// column order (trend, each period's `sin, cos` pairs, then regressors).
+///
+/// The first two trend columns (intercept and slope) are unpenalised; any
+/// further trend columns are changepoint hinges.
+fn fit_seasonal(
+    trend: &[Vec<f64>],
+    design: &Design<'_>,
+    target: &[f64],
+    config: &ProphetConfig,
+) -> Result<LinearFit, String> {
+    let mut columns = trend.to_vec();
+    for &(period, harmonics) in design.seasonalities {
+        columns.extend(SpectralEngine::generate_fourier_basis(
+            design.times,
+            period,
+            harmonics,
+        ));
+    }
+    let seasonal_end = columns.len();
+    columns.extend_from_slice(design.regressors);
+    if columns.is_empty() {
+        return Ok(LinearFit {
+            coefficients: Vec::new(),
+            residuals: target.to_vec(),
+        });
+    }
+    let unpenalised = trend.len().min(2);
+    let matrix = DMatrix::from_fn(target.len(), columns.len(), |row, column| {
+        columns[column][row]
+    });
+    let target = DVector::from_column_slice(target);
+
+    // First pass estimates the noise level; second applies the priors.
+    let initial = solve_penalised(&matrix, &target, &vec![BASE_PENALTY; columns.len()])?;
+    let residuals = &target - &matrix * &initial;
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Sample count casting to f64 for variance"
+    )]
+    let noise_variance = (residuals.norm_squared() / target.len() as f64).max(1e-12);
+
+    let penalties: Vec<f64> = (0..columns.len())
+        .map(|column| {
+            if column < unpenalised || column >= seasonal_end {
+                BASE_PENALTY
+            } else if column < trend.len() {
+                noise_variance / config.changepoint_prior_scale.powi(2)
+            } else {
+                noise_variance / config.seasonality_prior_scale.powi(2)
+            }
+        })
+        .collect();
+    let coefficients = solve_penalised(&matrix, &target, &penalties)?;
+    let final_residuals = &target - &matrix * &coefficients;
+    Ok(LinearFit {
+        coefficients: coefficients.iter().copied().collect(),
+        residuals: final_residuals.iter().copied().collect(),
+    })
+}
+
+/// Solve `(XᵀX + diag(penalties)) β = Xᵀy`.
+fn solve_penalised(
+    design: &DMatrix<f64>,
+    target: &DVector<f64>,
+    penalties: &[f64],
+) -> Result<DVector<f64>, String> {
+    let mut normal = design.transpose() * design;
+    for (index, &penalty) in penalties.iter().enumerate() {
+        normal[(index, index)] += penalty;
+    }
+    let rhs = design.transpose() * target;
+    normal
+        .svd(true, true)
+        .solve(&rhs, SOLVE_EPS)
+        .map_err(|error| format!("Prophet least-squares solve failed: {error}"))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use super::*;
+
+    fn two_season_series(times: &[f64]) -> Vec<f64> {
+        times
+            .iter()
+            .map(|&t| {
+                3.0_f64.mul_add(
+                    (TAU * t / 7.0).sin(),
+                    1.5_f64.mul_add((TAU * t / 30.0).cos(), 0.05_f64.mul_add(t, 10.0)),
+                )
+            })
+            .collect()
+    }
+
+    fn two_season_config() -> ProphetConfig {
+        ProphetConfig {
+            seasonality_periods: Some(vec![(7.0, 3), (30.0, 2)]),
+            n_changepoints: 0,
+            ..ProphetConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_recovers_multiple_seasonalities() {
+        let times: Vec<f64> = (0..180).map(f64::from).collect();
+        let values = two_season_series(&times);
+        let model = ProphetEngine::fit(&times, &values, &two_season_config()).unwrap();
+        assert_eq!(model.seasonalities.len(), 2);
+        assert!((model.trend.growth_rate - 0.05).abs() < 1e-3);
+        assert!((model.seasonalities[0].coefficients[0] - 3.0).abs() < 1e-2);
+        assert!((model.seasonalities[1].coefficients[1] - 1.5).abs() < 1e-2);
+
+        let future: Vec<f64> = (180..210).map(f64::from).collect();
+        let forecast = ProphetEngine::predict(&model, &future, &[]).unwrap();
+        let expected = two_season_series(&future);
+        for (predicted, truth) in forecast.yhat.iter().zip(&expected) {
+            assert!((predicted - truth).abs() < 0.05, "{predicted} vs {truth}");
+        }
+    }
+
+    #[test]
+    fn test_seasonal_breakdown_sums_to_total() {
+        let times: Vec<f64> = (0..120).map(f64::from).collect();
+        let values = two_season_series(&times);
+        let model = ProphetEngine::fit(&times, &values, &two_season_config()).unwrap();
+        let prediction = ProphetEngine::predict(&model, &times, &[]).unwrap();
+        assert_eq!(prediction.seasonal_components.len(), 2);
+        for row in 0..times.len() {
+            let sum: f64 = prediction
+                .seasonal_components
+                .iter()
+                .map(|component| component.values[row])
+                .sum();
+            assert!((sum - prediction.seasonal[row]).abs() < 1e-9);
+            assert!(prediction.yhat_lower[row] <= prediction.yhat[row]);
+            assert!(prediction.yhat_upper[row] >= prediction.yhat[row]);
+        }
+    }
+
+    /// Hourly series with a daily and a weekly cycle on a slow trend.
+    fn hourly_series(hours: u32) -> (Vec<f64>, Vec<f64>) {
+        (0..hours)
+            .map(|hour| {
+                let t = f64::from(hour);
+                let daily = 2.0 * (TAU * t / 24.0).sin();
+                let weekly = 3.0 * (TAU * t / 168.0).cos();
+                (t, 0.01_f64.mul_add(t, daily + weekly + 20.0))
+            })
+            .unzip()
+    }
+
+    #[test]
+    fn test_auto_seasonality_finds_daily_and_weekly_cycles() {
+        let config = ProphetConfig {
+            auto_seasonality: true,
+            n_changepoints: 0,
+            ..ProphetConfig::default()
+        };
+        let (times, values) = hourly_series(24 * 28);
+        let model = ProphetEngine::fit(&times, &values, &config).unwrap();
+        let mut periods: Vec<f64> = model
+            .summary
+            .detected_periods
+            .iter()
+            .map(|peak| peak.period)
+            .collect();
+        periods.sort_by(f64::total_cmp);
+        assert_eq!(periods.len(), 2, "{periods:?}");
+        assert!((periods[0] / 24.0 - 1.0).abs() < 0.05, "{}", periods[0]);
+        assert!((periods[1] / 168.0 - 1.0).abs() < 0.05, "{}", periods[1]);
+        assert!(model.summary.configured_periods.is_empty());
+        assert!(
+            model
+                .seasonalities
+                .iter()
+                .all(|seasonality| seasonality.spectral_power.is_some())
+        );
+        let prediction = ProphetEngine::predict(&model, &times, &[]).unwrap();
+        assert_eq!(prediction.seasonal_components.len(), 2);
+
+        // Ten days hold fewer than two weekly cycles, so only the daily one is fitted
+        let (short_times, short_values) = hourly_series(24 * 10);
+        let short = ProphetEngine::fit(&short_times, &short_values, &config).unwrap();
+        assert_eq!(short.seasonalities.len(), 1);
+        assert!((short.seasonalities[0].period / 24.0 - 1.0).abs() < 0.05);
+        assert!(
+            short
+                .summary
+                .excluded_periods
+                .iter()
+                .any(|peak| peak.period > 120.0)
+        );
+
+        // A configured period is kept as is and not detected a second time
+        let configured = ProphetConfig {
+            seasonality_period: Some(24.0),
+            ..config
+        };
+        let fixed = ProphetEngine::fit(&short_times, &short_values, &configured).unwrap();
+        assert_eq!(fixed.summary.configured_periods, vec![24.0]);
+        assert!(fixed.summary.detected_periods.is_empty());
+        assert_eq!(fixed.seasonalities[0].spectral_power, None);
+    }
+
+    #[test]
+    fn test_changepoint_trend_follows_slope_change() {
+        let times: Vec<f64> = (0..100).map(f64::from).collect();
+        let values: Vec<f64> = times
+            .iter()
+            .map(|&t| {
+                if t < 50.0 {
+                    t
+                } else {
+                    3.0_f64.mul_add(t - 50.0, 50.0)
+                }
+            })
+            .collect();
+        let config = ProphetConfig {
+            n_changepoints: 25,
+            changepoint_prior_scale: 1.0,
+            ..ProphetConfig::default()
+        };
+        let model = ProphetEngine::fit(&times, &values, &config).unwrap();
+        let prediction = ProphetEngine::predict(&model, &[99.0, 110.0], &[]).unwrap();
+        assert!((prediction.yhat[0] - 197.0).abs() < 2.0);
+        assert!((prediction.yhat[1] - 230.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_detected_changepoint_sits_at_slope_change() {
+        let times: Vec<f64> = (0..100).map(f64::from).collect();
+        let values: Vec<f64> = times
+            .iter()
+            .map(|&t| {
+                if t < 40.0 {
+                    t
+                } else {
+                    3.0_f64.mul_add(t - 40.0, 40.0)
+                }
+            })
+            .collect();
+        let config = ProphetConfig {
+            changepoint_prior_scale: 1.0,
+            changepoint_detection: Some(ChangepointMethod::Pelt),
+            ..ProphetConfig::default()
+        };
+        let model = ProphetEngine::fit(&times, &values, &config).unwrap();
+        assert_eq!(model.trend.changepoints, vec![40.0]);
+        let prediction = ProphetEngine::predict(&model, &[110.0], &[]).unwrap();
+        assert!((prediction.yhat[0] - 250.0).abs() < 2.0);
+    }
+
+    #[test]
+    fn test_extra_regressor_effect_is_recovered() {
+        let times: Vec<f64> = (0..150).map(f64::from).collect();
+        let temperature: Vec<f64> = times
+            .iter()
+            .map(|&t| 5.0_f64.mul_add((0.37 * t).sin(), 20.0))
+            .collect();
+        let values: Vec<f64> = times
+            .iter()
+            .zip(&temperature)
+            .map(|(&t, &x)| 2.5_f64.mul_add(x, 0.1_f64.mul_add(t, 4.0)))
+            .collect();
+        let future_temperature = vec![10.0, 30.0];
+        let config = ProphetConfig {
+            n_changepoints: 0,
+            extra_regressors: Some(vec![ExtraRegressor {
+                name: "temperature".to_owned(),
+                values: temperature,
+                future_values: future_temperature.clone(),
+            }]),
+            ..ProphetConfig::default()
+        };
+        let model = ProphetEngine::fit(&times, &values, &config).unwrap();
+        assert!((model.regressors[0].coefficient - 2.5).abs() < 1e-6);
+        assert!((model.regressors[0].mean - 20.0).abs() < 0.5);
+
+        let forecast =
+            ProphetEngine::predict(&model, &[150.0, 151.0], &[future_temperature.as_slice()])
+                .unwrap();
+        assert!((forecast.yhat[0] - 44.0).abs() < 1e-4);
+        assert!((forecast.yhat[1] - 94.1).abs() < 1e-4);
+        assert_eq!(forecast.regressor_components[0].name, "temperature");
+        assert!(ProphetEngine::predict(&model, &[150.0], &[]).is_err());
+    }
+
+    #[test]
+    fn test_logistic_trend_saturates_at_capacity() {
+        let times: Vec<f64> = (0..60).map(f64::from).collect();
+        let values: Vec<f64> = times
+            .iter()
+            .map(|&t| {
+                let growth = 5.0 + 95.0 / (1.0 + (-0.25 * (t - 30.0)).exp());
+                2.0_f64.mul_add((TAU * t / 7.0).sin(), growth)
+            })
+            .collect();
+        let config = ProphetConfig {
+            seasonality_period: Some(7.0),
+            trend_type: TrendType::Logistic {
+                capacity: 100.0,
+                floor: 5.0,
+            },
+            ..ProphetConfig::default()
+        };
+        let model = ProphetEngine::fit(&times, &values, &config).unwrap();
+        assert!((model.trend.growth_rate - 0.25).abs() < 1e-3);
+        assert!((model.trend.midpoint.unwrap() - 30.0).abs() < 1e-2);
+        assert_eq!(model.trend.capacity, Some(100.0));
+
+        let forecast = ProphetEngine::predict(&model, &[120.0, 500.0], &[]).unwrap();
+        for trend in &forecast.trend {
+            assert!(*trend <= 100.0 && *trend > 99.9);
+        }
+        let bad = ProphetConfig {
+            trend_type: TrendType::Logistic {
+                capacity: 1.0,
+                floor: 1.0,
+            },
+            ..ProphetConfig::default()
+        };
+        assert!(ProphetEngine::fit(&times, &values, &bad).is_err());
+    }
+
+    #[test]
+    fn test_detects_injected_anomalies() {
+        let times: Vec<f64> = (0..180).map(f64::from).collect();
+        let mut values = two_season_series(&times);
+        for (value, &t) in values.iter_mut().zip(&times) {
+            *value += 0.3 * (t * 12.9898).sin();
+        }
+        values[40] += 8.0;
+        values[120] -= 8.0;
+
+        let model = ProphetEngine::fit(&times, &values, &two_season_config()).unwrap();
+        let anomalies = ProphetEngine::detect_anomalies(&model, 3.0).unwrap();
+        let indices: Vec<usize> = anomalies.iter().map(|anomaly| anomaly.index).collect();
+        assert_eq!(indices, vec![40, 120]);
+        for anomaly in &anomalies {
+            assert!(anomaly.score > 3.0);
+            assert!(anomaly.actual < anomaly.lower || anomaly.actual > anomaly.upper);
+            assert!((anomaly.time - times[anomaly.index]).abs() < 1e-12);
+        }
+
+        let (clean_times, clean_values): (Vec<f64>, Vec<f64>) = times
+            .iter()
+            .zip(&values)
+            .enumerate()
+            .filter(|(index, _)| !indices.contains(index))
+            .map(|(_, (&t, &v))| (t, v))
+            .unzip();
+        let refit = ProphetEngine::fit(&clean_times, &clean_values, &two_season_config()).unwrap();
+        assert!(refit.residual_std < model.residual_std / 2.0);
+        assert!(
+            ProphetEngine::detect_anomalies(&refit, 3.0)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(matches!(
+            ProphetEngine::detect_anomalies(&refit, 0.0),
+            Err(StatisticsError::Validation { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_invalid_inputs() {
+        let config = ProphetConfig::default();
+        assert_eq!(
+            ProphetEngine::fit(&[0.0, 1.0], &[1.0, 2.0], &config).unwrap_err(),
+            StatisticsError::InsufficientData { needed: 3, got: 2 }
+        );
+        assert!(ProphetEngine::fit(&[0.0, 1.0, 2.0], &[1.0, 2.0], &config).is_err());
+        assert!(ProphetEngine::fit(&[1.0, 1.0, 1.0], &[1.0, 2.0, 3.0], &config).is_err());
+        let bad_period = ProphetConfig {
+            seasonality_periods: Some(vec![(0.0, 2)]),
+            ..ProphetConfig::default()
+        };
+        assert!(ProphetEngine::fit(&[0.0, 1.0, 2.0], &[1.0, 2.0, 3.0], &bad_period).is_err());
+    }
+}
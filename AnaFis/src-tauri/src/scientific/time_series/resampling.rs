@@ -0,0 +1,299 @@
+//! Resampling a series onto new timestamps.
+//!
+//! Used to align channels logged at different rates before correlating them.
+//! Source samples are sorted and duplicated timestamps averaged (unless strict
+//! ordering is requested), then each target time is evaluated with the chosen
+//! [`Interpolation`] rule. Uncertainties propagate through the linear
+//! interpolation weights, `sqrt((1 - w)^2 s_i^2 + w^2 s_(i+1)^2)`; the cubic
+//! method uses the same weights as a first-order approximation, and the
+//! previous/nearest methods carry the selected sample's uncertainty.
+use super::types::{ExtrapolationPolicy, ResampleMethod, ResampleRequest, ResampleResult};
+use crate::scientific::primitives::Interpolation;
+use crate::scientific::statistics::StatisticsError;
+
+/// Resampling of irregular series onto target timestamps.
+pub struct ResamplingEngine;
+
+/// Source samples after sorting and merging duplicates.
+struct SourceSeries {
+    times: Vec<f64>,
+    values: Vec<f64>,
+    uncertainties: Option<Vec<f64>>,
+    merged_duplicates: usize,
+}
+
+impl ResamplingEngine {
+    /// Evaluate the source series of `request` at its target timestamps.
+    ///
+    /// # Errors
+    /// Returns a validation error for mismatched lengths, non-finite inputs,
+    /// negative uncertainties, disordered timestamps under strict ordering,
+    /// or extrapolation under [`ExtrapolationPolicy::Error`], and
+    /// [`StatisticsError::InsufficientData`] with fewer than two distinct
+    /// source times.
+    pub fn resample(request: &ResampleRequest) -> Result<ResampleResult, StatisticsError> {
+        let source = prepare_source(request)?;
+        let times = &source.times;
+        let (first, last) = (times[0], times[times.len() - 1]);
+        let second_derivatives = (request.method == ResampleMethod::Cubic)
+            .then(|| Interpolation::natural_spline_second_derivatives(times, &source.values));
+
+        let mut values = Vec::with_capacity(request.target_timestamps.len());
+        let mut uncertainties = source
+            .uncertainties
+            .as_ref()
+            .map(|_| Vec::with_capacity(request.target_timestamps.len()));
+        let mut extrapolated = Vec::with_capacity(request.target_timestamps.len());
+        for &target in &request.target_timestamps {
+            let outside = target < first || target > last;
+            extrapolated.push(outside);
+            let (value, uncertainty) = if outside {
+                match request.extrapolation {
+                    ExtrapolationPolicy::Error => {
+                        return Err(StatisticsError::validation(
+                            "targetTimestamps",
+                            format!("Target time {target} lies outside [{first}, {last}]"),
+                        ));
+                    }
+                    ExtrapolationPolicy::Nan => (f64::NAN, f64::NAN),
+                    ExtrapolationPolicy::Clamp => {
+                        let edge = if target < first { 0 } else { times.len() - 1 };
+                        (source.values[edge], source.uncertainty(edge))
+                    }
+                }
+            } else {
+                evaluate(
+                    &source,
+                    request.method,
+                    second_derivatives.as_deref(),
+                    target,
+                )
+            };
+            values.push(value);
+            if let Some(output) = uncertainties.as_mut() {
+                output.push(uncertainty);
+            }
+        }
+
+        Ok(ResampleResult {
+            values,
+            uncertainties,
+            extrapolated,
+            merged_duplicates: source.merged_duplicates,
+        })
+    }
+}
+
+impl SourceSeries {
+    /// Uncertainty of source sample `index` (NaN when none were supplied).
+    fn uncertainty(&self, index: usize) -> f64 {
+        self.uncertainties
+            .as_ref()
+            .map_or(f64::NAN, |sigmas| sigmas[index])
+    }
+}
+
+/// Value and uncertainty at a target time inside the source range.
+fn evaluate(
+    source: &SourceSeries,
+    method: ResampleMethod,
+    second_derivatives: Option<&[f64]>,
+    target: f64,
+) -> (f64, f64) {
+    let times = &source.times;
+    let values = &source.values;
+    match method {
+        ResampleMethod::Previous => {
+            let index = Interpolation::previous_index(times, target);
+            (values[index], source.uncertainty(index))
+        }
+        ResampleMethod::Nearest => {
+            let index = Interpolation::nearest_index(times, target);
+            (values[index], source.uncertainty(index))
+        }
+        ResampleMethod::Linear | ResampleMethod::Cubic => {
+            let (index, weight) = Interpolation::linear_weights(times, target);
+            let uncertainty = ((1.0 - weight) * source.uncertainty(index))
+                .hypot(weight * source.uncertainty(index + 1));
+            let value = second_derivatives.map_or_else(
+                || Interpolation::linear(times, values, target),
+                |second| Interpolation::cubic(times, values, second, target),
+            );
+            (value, uncertainty)
+        }
+    }
+}
+
+/// Validate the request and return sorted, duplicate-free source samples.
+fn prepare_source(request: &ResampleRequest) -> Result<SourceSeries, StatisticsError> {
+    let count = request.timestamps.len();
+    if request.values.len() != count {
+        return Err(StatisticsError::validation(
+            "values",
+            format!(
+                "Expected one value per timestamp ({count} timestamps, {} values)",
+                request.values.len()
+            ),
+        ));
+    }
+    if let Some(sigmas) = &request.uncertainties {
+        if sigmas.len() != count {
+            return Err(StatisticsError::validation(
+                "uncertainties",
+                format!(
+                    "Expected one uncertainty per timestamp ({count} timestamps, {} uncertainties)",
+                    sigmas.len()
+                ),
+            ));
+        }
+        if sigmas
+            .iter()
+            .any(|sigma| !sigma.is_finite() || *sigma < 0.0)
+        {
+            return Err(StatisticsError::validation(
+                "uncertainties",
+                "Uncertainties must be finite and non-negative",
+            ));
+        }
+    }
+    if request
+        .timestamps
+        .iter()
+        .chain(&request.values)
+        .chain(&request.target_timestamps)
+        .any(|value| !value.is_finite())
+    {
+        return Err(StatisticsError::validation(
+            "timestamps",
+            "Timestamps and values must be finite",
+        ));
+    }
+    let ordered = request.timestamps.windows(2).all(|pair| pair[0] < pair[1]);
+    if request.strict_ordering && !ordered {
+        return Err(StatisticsError::validation(
+            "timestamps",
+            "Source timestamps must be strictly increasing",
+        ));
+    }
+
+    let mut order: Vec<usize> = (0..count).collect();
+    order.sort_by(|&a, &b| request.timestamps[a].total_cmp(&request.timestamps[b]));
+    let mut source = SourceSeries {
+        times: Vec::with_capacity(count),
+        values: Vec::with_capacity(count),
+        uncertainties: request.uncertainties.as_ref().map(|_| Vec::new()),
+        merged_duplicates: 0,
+    };
+    for group in order.chunk_by(|&a, &b| {
+        request.timestamps[a]
+            .total_cmp(&request.timestamps[b])
+            .is_eq()
+    }) {
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Duplicate count casting to f64 for averaging"
+        )]
+        let size = group.len() as f64;
+        source.times.push(request.timestamps[group[0]]);
+        source
+            .values
+            .push(group.iter().map(|&idx| request.values[idx]).sum::<f64>() / size);
+        if let (Some(merged), Some(sigmas)) =
+            (source.uncertainties.as_mut(), &request.uncertainties)
+        {
+            let variance: f64 = group.iter().map(|&idx| sigmas[idx].powi(2)).sum();
+            merged.push(variance.sqrt() / size);
+        }
+        source.merged_duplicates += group.len() - 1;
+    }
+    if source.times.len() < 2 {
+        return Err(StatisticsError::InsufficientData {
+            needed: 2,
+            got: source.times.len(),
+        });
+    }
+    Ok(source)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    fn request(method: ResampleMethod, targets: &[f64]) -> ResampleRequest {
+        ResampleRequest {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0],
+            values: vec![0.0, 10.0, 20.0, 40.0],
+            uncertainties: Some(vec![1.0, 1.0, 2.0, 2.0]),
+            target_timestamps: targets.to_vec(),
+            method,
+            extrapolation: ExtrapolationPolicy::Nan,
+            strict_ordering: false,
+        }
+    }
+
+    #[test]
+    fn test_exact_hits_and_midpoints() {
+        let linear =
+            ResamplingEngine::resample(&request(ResampleMethod::Linear, &[1.0, 1.5, 2.5])).unwrap();
+        assert_eq!(linear.values, vec![10.0, 15.0, 30.0]);
+        let sigmas = linear.uncertainties.unwrap();
+        assert!((sigmas[0] - 1.0).abs() < 1e-12);
+        assert!((sigmas[1] - 0.5_f64.hypot(1.0)).abs() < 1e-12);
+        assert!((sigmas[2] - 2.0_f64.hypot(2.0) / 2.0).abs() < 1e-12);
+        assert_eq!(linear.extrapolated, vec![false; 3]);
+
+        let previous =
+            ResamplingEngine::resample(&request(ResampleMethod::Previous, &[1.0, 1.9])).unwrap();
+        assert_eq!(previous.values, vec![10.0, 10.0]);
+        let nearest =
+            ResamplingEngine::resample(&request(ResampleMethod::Nearest, &[1.4, 1.6])).unwrap();
+        assert_eq!(nearest.values, vec![10.0, 20.0]);
+        let cubic =
+            ResamplingEngine::resample(&request(ResampleMethod::Cubic, &[2.0, 2.5])).unwrap();
+        assert!((cubic.values[0] - 20.0).abs() < 1e-12);
+        assert!(cubic.values[1] > 20.0 && cubic.values[1] < 40.0);
+    }
+
+    #[test]
+    fn test_extrapolation_policies() {
+        let targets = [-1.0, 1.5, 4.0];
+        let nan = ResamplingEngine::resample(&request(ResampleMethod::Linear, &targets)).unwrap();
+        assert!(nan.values[0].is_nan() && nan.values[2].is_nan());
+        assert_eq!(nan.extrapolated, vec![true, false, true]);
+
+        let mut clamp_request = request(ResampleMethod::Linear, &targets);
+        clamp_request.extrapolation = ExtrapolationPolicy::Clamp;
+        let clamp = ResamplingEngine::resample(&clamp_request).unwrap();
+        assert_eq!(clamp.values, vec![0.0, 15.0, 40.0]);
+        assert_eq!(clamp.extrapolated, vec![true, false, true]);
+
+        let mut error_request = request(ResampleMethod::Linear, &targets);
+        error_request.extrapolation = ExtrapolationPolicy::Error;
+        assert!(matches!(
+            ResamplingEngine::resample(&error_request),
+            Err(StatisticsError::Validation { .. })
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_timestamps_are_averaged_or_rejected() {
+        let mut unsorted = request(ResampleMethod::Linear, &[1.0, 1.5]);
+        unsorted.timestamps = vec![2.0, 1.0, 0.0, 1.0];
+        unsorted.values = vec![20.0, 8.0, 0.0, 12.0];
+        unsorted.uncertainties = Some(vec![1.0, 3.0, 1.0, 4.0]);
+        let merged = ResamplingEngine::resample(&unsorted).unwrap();
+        assert_eq!(merged.merged_duplicates, 1);
+        assert_eq!(merged.values, vec![10.0, 15.0]);
+        assert!((merged.uncertainties.unwrap()[0] - 2.5).abs() < 1e-12);
+
+        unsorted.strict_ordering = true;
+        assert!(ResamplingEngine::resample(&unsorted).is_err());
+        unsorted.timestamps = vec![1.0; 4];
+        unsorted.strict_ordering = false;
+        assert!(matches!(
+            ResamplingEngine::resample(&unsorted),
+            Err(StatisticsError::InsufficientData { .. })
+        ));
+    }
+}
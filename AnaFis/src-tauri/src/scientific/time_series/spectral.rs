@@ -0,0 +1,206 @@
+//! Spectral building blocks for time-series models.
+use std::f64::consts::TAU;
+
+use nalgebra::Complex;
+
+/// Frequency-domain helpers.
+pub struct SpectralEngine;
+
+impl SpectralEngine {
+    /// Fourier basis for a seasonal component of length `period`.
+    ///
+    /// Returns `2 * n_harmonics` columns, each aligned with `times`, ordered
+    /// `sin(2πkt/P), cos(2πkt/P)` for `k = 1..=n_harmonics`. `times` and
+    /// `period` must use the same unit.
+    #[must_use]
+    pub fn generate_fourier_basis(times: &[f64], period: f64, n_harmonics: usize) -> Vec<Vec<f64>> {
+        let mut columns = Vec::with_capacity(2 * n_harmonics);
+        for harmonic in 1..=n_harmonics {
+            #[allow(
+                clippy::cast_precision_loss,
+                reason = "Harmonic index casting to f64 for angular frequency"
+            )]
+            let angular = TAU * harmonic as f64 / period;
+            columns.push(times.iter().map(|&t| (angular * t).sin()).collect());
+            columns.push(times.iter().map(|&t| (angular * t).cos()).collect());
+        }
+        columns
+    }
+
+    /// Discrete Fourier transform of a real series, zero-padded to the next
+    /// power of two (iterative radix-2 Cooley-Tukey).
+    #[must_use]
+    pub fn fft(values: &[f64]) -> Vec<Complex<f64>> {
+        let size = values.len().max(1).next_power_of_two();
+        let mut buffer: Vec<Complex<f64>> = values
+            .iter()
+            .map(|&value| Complex::new(value, 0.0))
+            .chain(std::iter::repeat(Complex::new(0.0, 0.0)))
+            .take(size)
+            .collect();
+
+        let bits = size.trailing_zeros();
+        if bits > 0 {
+            for index in 0..size {
+                let reversed = index.reverse_bits() >> (usize::BITS - bits);
+                if index < reversed {
+                    buffer.swap(index, reversed);
+                }
+            }
+        }
+
+        let mut half = 1;
+        while half < size {
+            let length = 2 * half;
+            #[allow(
+                clippy::cast_precision_loss,
+                reason = "FFT block length casting to f64 for twiddle angle"
+            )]
+            let angle = -TAU / length as f64;
+            let step = Complex::new(angle.cos(), angle.sin());
+            for start in (0..size).step_by(length) {
+                let mut twiddle = Complex::new(1.0, 0.0);
+                for offset in 0..half {
+                    let even = buffer[start + offset];
+                    let odd = buffer[start + offset + half] * twiddle;
+                    buffer[start + offset] = even + odd;
+                    buffer[start + offset + half] = even - odd;
+                    twiddle *= step;
+                }
+            }
+            half = length;
+        }
+        buffer
+    }
+
+    /// Frequency, in cycles per unit of `spacing`, of the strongest non-constant
+    /// component of an evenly sampled series.
+    ///
+    /// The mean is removed and the series zero-padded fourfold before the peak
+    /// search; returns `None` for fewer than four samples, a non-positive spacing
+    /// or a constant series.
+    #[must_use]
+    pub fn dominant_frequency(values: &[f64], spacing: f64) -> Option<f64> {
+        if values.len() < 4 || !(spacing.is_finite() && spacing > 0.0) {
+            return None;
+        }
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Sample count casting to f64 for mean"
+        )]
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let mut padded: Vec<f64> = values.iter().map(|value| value - mean).collect();
+        padded.resize(4 * values.len().next_power_of_two(), 0.0);
+        let spectrum = Self::fft(&padded);
+
+        #[allow(clippy::integer_division, reason = "Nyquist bin index")]
+        let nyquist = spectrum.len() / 2;
+        let (peak, magnitude) = spectrum[1..nyquist]
+            .iter()
+            .enumerate()
+            .map(|(index, bin)| (index + 1, bin.norm_sqr()))
+            .fold((0, 0.0), |best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            });
+        if magnitude <= 0.0 {
+            return None;
+        }
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "FFT bin index casting to f64 for frequency"
+        )]
+        let frequency = peak as f64 / (spectrum.len() as f64 * spacing);
+        Some(frequency)
+    }
+
+    /// Lomb-Scargle periodogram of an irregularly sampled series.
+    ///
+    /// `values` should already be centred. Each frequency (in cycles per unit
+    /// of `times`) uses the phase offset `tau` that makes the sine and cosine
+    /// terms orthogonal, so the power equals a least-squares sinusoid fit.
+    /// Power is normalized by the sample variance (Scargle 1982), giving unit
+    /// mean for white noise; a constant series yields zeros.
+    #[must_use]
+    pub fn lomb_scargle(times: &[f64], values: &[f64], frequencies: &[f64]) -> Vec<f64> {
+        let sum_sq: f64 = values.iter().map(|value| value * value).sum();
+        if values.len() < 2 || sum_sq <= 0.0 {
+            return vec![0.0; frequencies.len()];
+        }
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Sample count casting to f64 for variance"
+        )]
+        let variance = sum_sq / (values.len() - 1) as f64;
+
+        frequencies
+            .iter()
+            .map(|&frequency| {
+                let angular = TAU * frequency;
+                let (sin_2, cos_2) = times.iter().fold((0.0, 0.0), |(s, c), &t| {
+                    let (sin, cos) = (2.0 * angular * t).sin_cos();
+                    (s + sin, c + cos)
+                });
+                let tau = sin_2.atan2(cos_2) / (2.0 * angular);
+                let (mut y_cos, mut y_sin, mut cos_sq, mut sin_sq) = (0.0, 0.0, 0.0, 0.0);
+                for (&t, &value) in times.iter().zip(values) {
+                    let (sin, cos) = (angular * (t - tau)).sin_cos();
+                    y_cos = value.mul_add(cos, y_cos);
+                    y_sin = value.mul_add(sin, y_sin);
+                    cos_sq = cos.mul_add(cos, cos_sq);
+                    sin_sq = sin.mul_add(sin, sin_sq);
+                }
+                let cos_term = if cos_sq > 0.0 {
+                    y_cos * y_cos / cos_sq
+                } else {
+                    0.0
+                };
+                let sin_term = if sin_sq > 0.0 {
+                    y_sin * y_sin / sin_sq
+                } else {
+                    0.0
+                };
+                (cos_term + sin_term) / (2.0 * variance)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fourier_basis_shape_and_periodicity() {
+        let times: Vec<f64> = (0..14).map(f64::from).collect();
+        let basis = SpectralEngine::generate_fourier_basis(&times, 7.0, 3);
+        assert_eq!(basis.len(), 6);
+        for column in &basis {
+            assert_eq!(column.len(), 14);
+            assert!((column[0] - column[7]).abs() < 1e-12);
+        }
+        assert!(basis[0][0].abs() < 1e-12);
+        assert!((basis[1][0] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_fft_and_dominant_frequency() {
+        let spectrum = SpectralEngine::fft(&[1.0, 0.0, -1.0, 0.0]);
+        assert_eq!(spectrum.len(), 4);
+        assert!(spectrum[0].norm() < 1e-12);
+        assert!((spectrum[1].re - 2.0).abs() < 1e-12);
+        assert!((spectrum[3].re - 2.0).abs() < 1e-12);
+
+        let spacing = 0.05;
+        let samples: Vec<f64> = (0..200)
+            .map(|i| (TAU * 0.8 * f64::from(i) * spacing).sin() + 3.0)
+            .collect();
+        let frequency = SpectralEngine::dominant_frequency(&samples, spacing).unwrap();
+        assert!((frequency - 0.8).abs() < 0.03, "{frequency}");
+        assert!(SpectralEngine::dominant_frequency(&[2.0; 16], 1.0).is_none());
+    }
+}
@@ -0,0 +1,815 @@
+use serde::{Deserialize, Serialize};
+
+/// Seasonal component form for Holt-Winters smoothing.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum HoltWintersModel {
+    /// Seasonal effect is added to the level (constant seasonal amplitude).
+    Additive,
+    /// Seasonal effect scales the level (amplitude grows with the level).
+    Multiplicative,
+}
+
+/// Symmetric prediction band around a point forecast.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PredictionInterval {
+    /// Nominal coverage as a percentage (e.g. 95.0).
+    pub confidence_level: f64,
+    /// Lower bound for each forecast step.
+    pub lower: Vec<f64>,
+    /// Upper bound for each forecast step.
+    pub upper: Vec<f64>,
+}
+
+/// A set of prediction intervals at different coverage levels, aligned with one forecast.
+pub type PredictionIntervals = [PredictionInterval];
+
+/// Output of a Holt-Winters fit and forecast.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HoltWintersResult {
+    /// Seasonal form that was fitted.
+    pub model: HoltWintersModel,
+    /// Season length used for the fit.
+    pub period: usize,
+    /// Level smoothing parameter.
+    pub alpha: f64,
+    /// Trend smoothing parameter.
+    pub beta: f64,
+    /// Seasonal smoothing parameter.
+    pub gamma: f64,
+    /// One-step-ahead in-sample fitted values, aligned with the input series.
+    pub fitted: Vec<f64>,
+    /// Point forecasts for the requested horizon.
+    pub forecast: Vec<f64>,
+    /// 80 % and 95 % prediction intervals for the forecasts.
+    pub prediction_intervals: Vec<PredictionInterval>,
+    /// Final level state.
+    pub level: f64,
+    /// Final trend state.
+    pub trend: f64,
+    /// Final seasonal indices (one per position in the season).
+    pub seasonal: Vec<f64>,
+    /// Sum of squared one-step-ahead errors over the training set.
+    pub sse: f64,
+    /// Residual standard deviation of the one-step-ahead errors.
+    pub residual_std: f64,
+    /// Names of the smoothing parameters that were estimated rather than supplied.
+    pub optimized_parameters: Vec<String>,
+}
+
+/// Request payload for the `holt_winters_forecast` command.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HoltWintersRequest {
+    /// Observed series, equally spaced in time.
+    pub series: Vec<f64>,
+    /// Season length in samples.
+    pub period: usize,
+    /// Seasonal form.
+    pub model: HoltWintersModel,
+    /// Optional fixed level smoothing parameter in [0, 1].
+    #[serde(default)]
+    pub alpha: Option<f64>,
+    /// Optional fixed trend smoothing parameter in [0, 1].
+    #[serde(default)]
+    pub beta: Option<f64>,
+    /// Optional fixed seasonal smoothing parameter in [0, 1].
+    #[serde(default)]
+    pub gamma: Option<f64>,
+    /// Number of steps to forecast beyond the end of the series.
+    pub horizon: usize,
+}
+
+/// Output of a dynamic time warping comparison.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DtwResult {
+    /// DTW distance, `sqrt` of the summed squared differences along the optimal path.
+    pub distance: f64,
+    /// Root-mean-square difference per aligned pair (`distance / sqrt(path length)`).
+    pub normalized_distance: f64,
+    /// Optimal alignment as `(index in series1, index in series2)` pairs.
+    pub path: Vec<(usize, usize)>,
+    /// Accumulated cost matrix (`null` outside the band); omitted for large inputs.
+    pub cost_matrix: Option<Vec<Vec<Option<f64>>>>,
+    /// Plain Euclidean distance, when both series have the same length.
+    pub euclidean_distance: Option<f64>,
+    /// Effective Sakoe-Chiba band half-width, if a window was requested.
+    pub window: Option<usize>,
+}
+
+/// A single accuracy metric with its definition.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccuracyMetric {
+    /// Short metric name (e.g. "MAE").
+    pub name: &'static str,
+    /// Formula used to compute the value (`y` = actual, `f` = forecast).
+    pub formula: &'static str,
+    /// Metric value.
+    pub value: f64,
+}
+
+impl AccuracyMetric {
+    /// Create a metric record.
+    #[must_use]
+    pub const fn new(name: &'static str, formula: &'static str, value: f64) -> Self {
+        Self {
+            name,
+            formula,
+            value,
+        }
+    }
+}
+
+/// Forecast accuracy report.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastAccuracyResult {
+    /// Number of forecast/actual pairs compared.
+    pub sample_size: usize,
+    /// Mean absolute error.
+    pub mae: AccuracyMetric,
+    /// Root mean squared error.
+    pub rmse: AccuracyMetric,
+    /// Symmetric mean absolute percentage error, in percent.
+    pub smape: AccuracyMetric,
+    /// Mean absolute scaled error (requires naive errors).
+    pub mase: Option<AccuracyMetric>,
+    /// Continuous ranked probability score (requires prediction intervals).
+    pub crps: Option<AccuracyMetric>,
+}
+
+/// Request payload for the `evaluate_forecast_accuracy` command.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastAccuracyRequest {
+    /// Realised values.
+    pub actual: Vec<f64>,
+    /// Point forecasts aligned with `actual`.
+    pub forecast: Vec<f64>,
+    /// Optional in-sample naive one-step errors for MASE scaling.
+    #[serde(default)]
+    pub naive_errors: Option<Vec<f64>>,
+    /// Optional prediction intervals aligned with `forecast`, for CRPS.
+    #[serde(default)]
+    pub prediction_intervals: Option<Vec<PredictionInterval>>,
+}
+
+/// Trend shape of a Prophet-style model.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TrendType {
+    /// Piecewise-linear trend with changepoints.
+    #[default]
+    Linear,
+    /// Saturating growth `floor + (capacity - floor) / (1 + exp(-m (t - b)))`.
+    Logistic {
+        /// Carrying capacity (upper asymptote), in data units.
+        capacity: f64,
+        /// Lower asymptote, in data units.
+        #[serde(default)]
+        floor: f64,
+    },
+}
+
+/// Configuration of a Prophet-style additive model.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProphetConfig {
+    /// Single seasonal period, in the same unit as the time axis.
+    pub seasonality_period: Option<f64>,
+    /// Number of Fourier harmonics for `seasonality_period`.
+    pub fourier_order: usize,
+    /// Several seasonal components as `(period, n_harmonics)` pairs; takes
+    /// precedence over `seasonality_period`.
+    pub seasonality_periods: Option<Vec<(f64, usize)>>,
+    /// Number of potential trend changepoints.
+    pub n_changepoints: usize,
+    /// Fraction of the history in which changepoints are placed.
+    pub changepoint_range: f64,
+    /// Prior scale of the changepoint rate adjustments (smaller is smoother).
+    pub changepoint_prior_scale: f64,
+    /// Prior scale of the Fourier coefficients (smaller is smoother).
+    pub seasonality_prior_scale: f64,
+    /// Coverage of the prediction interval, in (0, 1).
+    pub interval_width: f64,
+    /// Known covariates fitted as additional linear terms.
+    pub extra_regressors: Option<Vec<ExtraRegressor>>,
+    /// Trend shape.
+    pub trend_type: TrendType,
+    /// Place linear-trend changepoints where this detector finds shifts in
+    /// the local slope, instead of spreading them evenly.
+    pub changepoint_detection: Option<ChangepointMethod>,
+    /// Also fit seasonal components at the significant peaks of the
+    /// detrended series' periodogram, each with `fourier_order` harmonics.
+    pub auto_seasonality: bool,
+    /// Most periods added by `auto_seasonality`.
+    pub max_seasonalities: usize,
+    /// False-alarm probability below which a periodogram peak is significant.
+    pub seasonality_false_alarm: f64,
+}
+
+impl Default for ProphetConfig {
+    fn default() -> Self {
+        Self {
+            seasonality_period: None,
+            fourier_order: 3,
+            seasonality_periods: None,
+            n_changepoints: 10,
+            changepoint_range: 0.8,
+            changepoint_prior_scale: 0.05,
+            seasonality_prior_scale: 10.0,
+            interval_width: 0.8,
+            extra_regressors: None,
+            trend_type: TrendType::Linear,
+            changepoint_detection: None,
+            auto_seasonality: false,
+            max_seasonalities: 3,
+            seasonality_false_alarm: 0.01,
+        }
+    }
+}
+
+/// Continuous covariate with known values over the history and the forecast.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraRegressor {
+    /// Regressor name, unique within a model.
+    pub name: String,
+    /// Values aligned with the training times.
+    pub values: Vec<f64>,
+    /// Values aligned with the forecast times.
+    #[serde(default)]
+    pub future_values: Vec<f64>,
+}
+
+/// Fitted linear effect of an extra regressor.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FittedRegressor {
+    /// Regressor name.
+    pub name: String,
+    /// Effect per unit of the regressor, in data units.
+    pub coefficient: f64,
+    /// Training mean the regressor is centred on.
+    pub mean: f64,
+    /// Training standard deviation used for standardization.
+    pub std: f64,
+}
+
+/// Fitted Fourier series for one seasonal period.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FittedSeasonality {
+    /// Seasonal period, in time-axis units.
+    pub period: f64,
+    /// Number of harmonics.
+    pub harmonics: usize,
+    /// Coefficients ordered `sin, cos` per harmonic, in data units.
+    pub coefficients: Vec<f64>,
+    /// Normalized periodogram power of a detected period; `None` when the
+    /// period was configured.
+    pub spectral_power: Option<f64>,
+}
+
+/// Fitted trend of a Prophet-style model.
+///
+/// A linear trend is `offset + growth_rate (t - t_start) + Σ δ_j (t - c_j)+`;
+/// a logistic trend (when `capacity` is set) is
+/// `offset + (capacity - offset) / (1 + exp(-growth_rate (t - midpoint)))`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendModel {
+    /// Earliest training time; the linear trend is anchored here.
+    pub t_start: f64,
+    /// Linear: trend value at `t_start`. Logistic: the floor.
+    pub offset: f64,
+    /// Linear: initial slope per time unit. Logistic: growth rate per time unit.
+    pub growth_rate: f64,
+    /// Changepoint locations, in time-axis units (linear trend only).
+    pub changepoints: Vec<f64>,
+    /// Slope change applied after each changepoint.
+    pub changepoint_deltas: Vec<f64>,
+    /// Carrying capacity of a logistic trend; `None` for a linear trend.
+    pub capacity: Option<f64>,
+    /// Time of fastest growth of a logistic trend.
+    pub midpoint: Option<f64>,
+}
+
+/// Fitted Prophet-style model: trend plus Fourier seasonalities and regressors.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProphetModel {
+    /// Fitted trend.
+    pub trend: TrendModel,
+    /// Seasonal components, in the order they were configured.
+    pub seasonalities: Vec<FittedSeasonality>,
+    /// Extra regressor effects, in the order they were configured.
+    pub regressors: Vec<FittedRegressor>,
+    /// Residual standard deviation of the fit.
+    pub residual_std: f64,
+    /// Coverage of the prediction interval, in (0, 1).
+    pub interval_width: f64,
+    /// How the seasonal components were chosen.
+    pub summary: ProphetFitSummary,
+    /// Training data, kept for in-sample diagnostics such as anomaly detection.
+    #[serde(skip)]
+    pub history: ProphetHistory,
+}
+
+/// Origin of the seasonal components of a Prophet-style model.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProphetFitSummary {
+    /// Periods taken from the configuration.
+    pub configured_periods: Vec<f64>,
+    /// Periods found by `auto_seasonality` and fitted, strongest first.
+    pub detected_periods: Vec<DominantPeriod>,
+    /// Significant periods left out because the series spans fewer than two
+    /// of their cycles.
+    pub excluded_periods: Vec<DominantPeriod>,
+}
+
+/// Observations a Prophet-style model was fitted to.
+#[derive(Debug, Clone, Default)]
+pub struct ProphetHistory {
+    /// Observation times.
+    pub times: Vec<f64>,
+    /// Observed values aligned with `times`.
+    pub values: Vec<f64>,
+    /// Training values of each extra regressor, in model order.
+    pub regressor_values: Vec<Vec<f64>>,
+}
+
+/// Contribution of a single seasonal period to a prediction.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeasonalComponent {
+    /// Seasonal period, in time-axis units.
+    pub period: f64,
+    /// Component value at each prediction time.
+    pub values: Vec<f64>,
+}
+
+/// Contribution of a single extra regressor to a prediction.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegressorComponent {
+    /// Regressor name.
+    pub name: String,
+    /// Component value at each prediction time.
+    pub values: Vec<f64>,
+}
+
+/// Prediction of a Prophet-style model with its decomposition.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProphetPrediction {
+    /// Prediction times.
+    pub times: Vec<f64>,
+    /// Point prediction (`trend + seasonal + regressor contributions`).
+    pub yhat: Vec<f64>,
+    /// Lower prediction bound.
+    pub yhat_lower: Vec<f64>,
+    /// Upper prediction bound.
+    pub yhat_upper: Vec<f64>,
+    /// Trend component.
+    pub trend: Vec<f64>,
+    /// Total seasonal contribution (sum of `seasonal_components`).
+    pub seasonal: Vec<f64>,
+    /// Per-period seasonal breakdown.
+    pub seasonal_components: Vec<SeasonalComponent>,
+    /// Per-regressor breakdown.
+    pub regressor_components: Vec<RegressorComponent>,
+}
+
+/// Request payload for the `prophet_forecast` command.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProphetForecastRequest {
+    /// Observation times.
+    pub times: Vec<f64>,
+    /// Observed values aligned with `times`.
+    pub values: Vec<f64>,
+    /// Model configuration.
+    #[serde(default)]
+    pub config: ProphetConfig,
+    /// Times at which to forecast.
+    pub future_times: Vec<f64>,
+}
+
+/// Output of the `prophet_forecast` command.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProphetForecastResult {
+    /// Fitted model.
+    pub model: ProphetModel,
+    /// In-sample prediction at the training times.
+    pub fitted: ProphetPrediction,
+    /// Prediction at the requested future times.
+    pub forecast: ProphetPrediction,
+}
+
+/// Training observation that falls outside the model's anomaly band.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnomalyResult {
+    /// Position of the observation in the training series.
+    pub index: usize,
+    /// Observation time.
+    pub time: f64,
+    /// Observed value.
+    pub actual: f64,
+    /// In-sample model prediction.
+    pub predicted: f64,
+    /// Lower bound of the anomaly band.
+    pub lower: f64,
+    /// Upper bound of the anomaly band.
+    pub upper: f64,
+    /// Distance from the prediction in residual standard deviations.
+    pub score: f64,
+}
+
+/// Request payload for the `prophet_detect_anomalies` command.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProphetAnomalyRequest {
+    /// Observation times.
+    pub times: Vec<f64>,
+    /// Observed values aligned with `times`.
+    pub values: Vec<f64>,
+    /// Model configuration.
+    #[serde(default)]
+    pub config: ProphetConfig,
+    /// Half-width of the anomaly band in residual standard deviations.
+    pub threshold_sigma: f64,
+}
+
+/// Result of the `prophet_detect_anomalies` command.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProphetAnomalyResult {
+    /// Fitted model.
+    pub model: ProphetModel,
+    /// Flagged observations, in series order.
+    pub anomalies: Vec<AnomalyResult>,
+}
+
+/// How residuals of a baseline model are turned into anomaly flags.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AnomalyThreshold {
+    /// Modified z-score of a residual above which the observation is
+    /// anomalous; 3.5 by default, after Iglewicz and Hoaglin.
+    pub modified_z: f64,
+    /// Only flag observations that also fall outside the model's prediction
+    /// interval.
+    pub require_outside_interval: bool,
+}
+
+impl Default for AnomalyThreshold {
+    fn default() -> Self {
+        Self {
+            modified_z: 3.5,
+            require_outside_interval: false,
+        }
+    }
+}
+
+/// Baseline model and residual scale needed to score new observations
+/// without refitting.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnomalyModelState {
+    /// Seasonal-trend baseline fitted to the training window.
+    pub model: ProphetModel,
+    /// Median of the training residuals.
+    pub residual_median: f64,
+    /// Median absolute deviation of the training residuals.
+    pub residual_mad: f64,
+    /// Threshold applied when scoring.
+    pub threshold: AnomalyThreshold,
+}
+
+/// An observation flagged by `detect_series_anomalies`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesAnomaly {
+    /// Position of the observation in the request series.
+    pub index: usize,
+    /// Observation time.
+    pub time: f64,
+    /// Observed value.
+    pub actual: f64,
+    /// Baseline prediction.
+    pub expected: f64,
+    /// `actual - expected`.
+    pub residual: f64,
+    /// Modified z-score of the residual.
+    pub score: f64,
+    /// Lower bound of the baseline prediction interval.
+    pub lower: f64,
+    /// Upper bound of the baseline prediction interval.
+    pub upper: f64,
+}
+
+/// Request payload for the `detect_series_anomalies` command.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesAnomalyRequest {
+    /// Observation times.
+    pub times: Vec<f64>,
+    /// Observed values aligned with `times`.
+    pub values: Vec<f64>,
+    /// Index of the first training observation.
+    #[serde(default)]
+    pub training_start: usize,
+    /// Index one past the last training observation; the end of the series
+    /// when absent.
+    pub training_end: Option<usize>,
+    /// Baseline model configuration; extra regressors are not supported.
+    #[serde(default)]
+    pub config: ProphetConfig,
+    /// Anomaly threshold.
+    #[serde(default)]
+    pub threshold: AnomalyThreshold,
+}
+
+/// Request payload for the `score_series_anomalies` command.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesAnomalyScoreRequest {
+    /// State returned by an earlier detection.
+    pub state: AnomalyModelState,
+    /// Times of the observations to score.
+    pub times: Vec<f64>,
+    /// Observations to score, aligned with `times`.
+    pub values: Vec<f64>,
+}
+
+/// Result of the `detect_series_anomalies` and `score_series_anomalies`
+/// commands.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesAnomalyResult {
+    /// Baseline prediction at every scored time, for plotting.
+    pub baseline: ProphetPrediction,
+    /// Modified z-score of every residual, aligned with the series.
+    pub scores: Vec<f64>,
+    /// Flagged observations, in series order.
+    pub anomalies: Vec<SeriesAnomaly>,
+    /// State for scoring later observations with the same baseline.
+    pub state: AnomalyModelState,
+}
+
+/// Interpolation rule used by `resample_series`.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ResampleMethod {
+    /// Straight line between the neighbouring source samples.
+    Linear,
+    /// Last source sample at or before the target time (zero-order hold).
+    Previous,
+    /// Closest source sample in time.
+    Nearest,
+    /// Natural cubic spline through the source samples.
+    Cubic,
+}
+
+/// Handling of target times outside the source time range.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExtrapolationPolicy {
+    /// Return NaN (`null` in JSON) outside the source range.
+    #[default]
+    Nan,
+    /// Hold the first or last source value.
+    Clamp,
+    /// Reject the request.
+    Error,
+}
+
+/// Request payload for the `resample_series` command.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResampleRequest {
+    /// Source sample times.
+    pub timestamps: Vec<f64>,
+    /// Source values aligned with `timestamps`.
+    pub values: Vec<f64>,
+    /// Optional standard uncertainties of the source values.
+    #[serde(default)]
+    pub uncertainties: Option<Vec<f64>>,
+    /// Times at which to evaluate the series.
+    pub target_timestamps: Vec<f64>,
+    /// Interpolation rule.
+    pub method: ResampleMethod,
+    /// Handling of targets outside the source range.
+    #[serde(default)]
+    pub extrapolation: ExtrapolationPolicy,
+    /// Reject unsorted or duplicated source timestamps instead of sorting
+    /// them and averaging duplicates.
+    #[serde(default)]
+    pub strict_ordering: bool,
+}
+
+/// Output of the `resample_series` command.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResampleResult {
+    /// Resampled values aligned with the target timestamps.
+    pub values: Vec<f64>,
+    /// Propagated uncertainties, when source uncertainties were supplied.
+    pub uncertainties: Option<Vec<f64>>,
+    /// Whether each output lies outside the source time range.
+    pub extrapolated: Vec<bool>,
+    /// Number of duplicated source timestamps merged by averaging.
+    pub merged_duplicates: usize,
+}
+
+/// Taper applied before the FFT periodogram.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowFunction {
+    /// Rectangular window (no taper).
+    None,
+    /// Hann (raised cosine) window.
+    #[default]
+    Hann,
+    /// Hamming window.
+    Hamming,
+}
+
+/// Periodogram estimator.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PeriodogramAlgorithm {
+    /// FFT periodogram of an evenly sampled series.
+    Fft,
+    /// Lomb-Scargle periodogram for irregular sampling.
+    LombScargle,
+}
+
+/// Request payload for the `compute_periodogram` command.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodogramRequest {
+    /// Observation times, strictly increasing.
+    pub timestamps: Vec<f64>,
+    /// Observed values aligned with `timestamps`.
+    pub values: Vec<f64>,
+    /// Estimator to use; chosen from the sampling regularity when omitted.
+    #[serde(default)]
+    pub algorithm: Option<PeriodogramAlgorithm>,
+    /// Taper for the FFT periodogram.
+    #[serde(default)]
+    pub window: WindowFunction,
+    /// Remove a least-squares line instead of only the mean.
+    #[serde(default)]
+    pub detrend: bool,
+    /// Number of dominant periods to report (default 3).
+    #[serde(default)]
+    pub top_k: Option<usize>,
+    /// Lomb-Scargle frequency grid oversampling factor (default 5).
+    #[serde(default)]
+    pub oversampling: Option<f64>,
+}
+
+/// A spectral peak reported as a candidate seasonality period.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DominantPeriod {
+    /// Peak frequency in cycles per time unit.
+    pub frequency: f64,
+    /// Period (`1 / frequency`) in time units.
+    pub period: f64,
+    /// Normalized power at the peak.
+    pub power: f64,
+    /// Probability that white noise produces a peak this high anywhere in the spectrum.
+    pub false_alarm_probability: f64,
+}
+
+/// How the frequency grid of a periodogram was built.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodogramMetadata {
+    /// Highest frequency in the grid: the Nyquist frequency for regular
+    /// sampling, the pseudo-Nyquist `n / (2 T)` for irregular sampling.
+    pub nyquist_frequency: f64,
+    /// Sample spacing used by the FFT path.
+    pub sample_spacing: Option<f64>,
+    /// Number of observations.
+    pub input_length: usize,
+    /// FFT length after zero-padding to the next power of two.
+    pub padded_length: Option<usize>,
+    /// Spacing of the frequency grid.
+    pub frequency_resolution: f64,
+    /// Human-readable notes on sampling, padding and ignored options.
+    pub notes: Vec<String>,
+}
+
+/// Output of the `compute_periodogram` command.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodogramResult {
+    /// Estimator that produced the spectrum.
+    pub algorithm: PeriodogramAlgorithm,
+    /// Frequencies in cycles per time unit, increasing.
+    pub frequencies: Vec<f64>,
+    /// Power normalized by the data variance (unit mean for white noise).
+    pub power: Vec<f64>,
+    /// Strongest local maxima, in decreasing power.
+    pub dominant_periods: Vec<DominantPeriod>,
+    /// Frequency grid details.
+    pub metadata: PeriodogramMetadata,
+}
+
+/// Search strategy for changepoint detection.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangepointMethod {
+    /// Greedy recursive splitting at the best single changepoint.
+    BinarySegmentation,
+    /// Pruned exact linear time search (Killick et al. 2012).
+    #[default]
+    Pelt,
+}
+
+/// Segment cost model for changepoint detection.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangepointCost {
+    /// Gaussian change in mean with a common, robustly estimated variance.
+    #[default]
+    Mean,
+    /// Gaussian change in both mean and variance.
+    MeanVariance,
+}
+
+/// Penalty added for every changepoint.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ChangepointPenalty {
+    /// `k ln n`, counting each new segment's parameters and its location.
+    #[default]
+    Bic,
+    /// Fixed penalty on the cost scale (twice the negative log-likelihood).
+    Manual {
+        /// Penalty per changepoint.
+        value: f64,
+    },
+}
+
+/// Request payload for the `detect_changepoints` command.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangepointRequest {
+    /// Series in time order.
+    pub values: Vec<f64>,
+    /// Search strategy.
+    #[serde(default)]
+    pub method: ChangepointMethod,
+    /// Segment cost model.
+    #[serde(default)]
+    pub cost: ChangepointCost,
+    /// Penalty per changepoint.
+    #[serde(default)]
+    pub penalty: ChangepointPenalty,
+    /// Minimum number of observations per segment (default 2).
+    #[serde(default)]
+    pub min_segment_length: Option<usize>,
+}
+
+/// One segment between consecutive changepoints.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangepointSegment {
+    /// Index of the first observation.
+    pub start: usize,
+    /// Index one past the last observation.
+    pub end: usize,
+    /// Sample mean.
+    pub mean: f64,
+    /// Maximum-likelihood variance.
+    pub variance: f64,
+}
+
+/// Output of the `detect_changepoints` command.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangepointResult {
+    /// Index of the first observation of each new segment, increasing.
+    pub changepoints: Vec<usize>,
+    /// Segments delimited by the changepoints.
+    pub segments: Vec<ChangepointSegment>,
+    /// Cost reduction from splitting the two neighbouring segments at each
+    /// changepoint, aligned with `changepoints`.
+    pub cost_improvements: Vec<f64>,
+    /// Total segmentation cost, excluding penalties.
+    pub total_cost: f64,
+    /// Penalty per changepoint that was applied.
+    pub penalty: f64,
+}
@@ -1,12 +1,23 @@
 //! Uncertainty Calculator
 //!
 //! Provides numerical uncertainty propagation using `symb_anafis`.
+//!
+//! The LaTeX output walks through the propagation step by step for reports:
+//! the general formula, each partial derivative symbolically and with the
+//! input values substituted, an uncertainty budget table and the rounded
+//! result.
 
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use symb_anafis::{
-    CovEntry, CovarianceMatrix, Symbol, gradient, parse, symb, uncertainty_propagation,
+    CovEntry, CovarianceMatrix, Expr, Symbol, gradient, parse, symb, uncertainty_propagation,
+};
+
+use crate::error::{
+    CommandError, CommandResult, codes, internal_error, numerical_error, validation_error,
 };
+use crate::export::tex::uncertainty_decimals;
 
 /// Represents a variable input for the uncertainty calculator.
 #[derive(Deserialize, Clone)]
@@ -41,21 +52,46 @@ pub struct LatexResult {
     pub string: String,
     /// LaTeX formatted string of the uncertainty expression.
     pub latex: String,
+    /// Value formula and general propagation formula.
+    pub general: String,
+    /// Per-variable derivative breakdown, in input order.
+    pub derivatives: Vec<LatexDerivative>,
+    /// Uncertainty budget `tabular`, largest contribution first.
+    pub budget: String,
+    /// Result rounded to the precision of its uncertainty.
+    pub result: String,
+}
+
+/// LaTeX breakdown of the partial derivative for one input.
+#[derive(Serialize, Clone)]
+pub struct LatexDerivative {
+    /// Name of the variable.
+    pub name: String,
+    /// Symbolic derivative, the derivative with the values substituted and
+    /// the resulting contribution to the uncertainty.
+    pub section: String,
+    /// Partial derivative at the input values.
+    pub derivative: f64,
+    /// Contribution `sigma * |df/dx|` to the uncertainty.
+    pub contribution: f64,
+    /// Share of the propagated variance, in percent.
+    pub percentage: f64,
 }
 
 /// Normalizes a list of variable names.
 ///
 /// # Errors
 /// Returns an error if there are duplicate names (case-insensitive).
-fn normalize_variable_names(variable_names: &[String]) -> Result<Vec<String>, String> {
+fn normalize_variable_names(variable_names: &[String]) -> CommandResult<Vec<String>> {
     let mut normalized = Vec::with_capacity(variable_names.len());
     let mut seen = HashSet::new();
 
     for name in variable_names {
         let lower = name.to_lowercase();
         if !seen.insert(lower.clone()) {
-            return Err(format!(
-                "Variable names must be unique ignoring case (collision on '{name}')"
+            return Err(validation_error(
+                format!("Variable names must be unique ignoring case (collision on '{name}')"),
+                Some("variables".to_owned()),
             ));
         }
         normalized.push(lower);
@@ -76,7 +112,7 @@ fn normalize_variable_names(variable_names: &[String]) -> Result<Vec<String>, St
 pub fn calculate_uncertainty(
     formula: String,
     variables: Vec<CalculatorVariable>,
-) -> Result<CalculationResult, String> {
+) -> CommandResult<CalculationResult> {
     let variable_names: Vec<String> = variables.iter().map(|v| v.name.clone()).collect();
     let normalized_variable_names = normalize_variable_names(&variable_names)?;
     let known_symbols: HashSet<String> = normalized_variable_names.iter().cloned().collect();
@@ -91,16 +127,16 @@ pub fn calculate_uncertainty(
 
     // Parse formula
     let expr = parse(&formula_normalized, &known_symbols, &HashSet::new(), None)
-        .map_err(|e| format!("Failed to parse formula: {e}"))?;
+        .map_err(|e| parse_error(&e))?;
 
     // Evaluate value
     let value_str = expr.evaluate(&values_map, &HashMap::new()).to_string();
     let value = value_str
         .parse::<f64>()
-        .map_err(|e| format!("Failed to parse result '{value_str}': {e}"))?;
+        .map_err(|e| numerical_error(format!("Failed to parse result '{value_str}': {e}")))?;
 
     if !value.is_finite() {
-        return Err("Expression evaluated to non-finite value".to_owned());
+        return Err(numerical_error("Expression evaluated to non-finite value"));
     }
 
     // Build covariance matrix
@@ -116,19 +152,20 @@ pub fn calculate_uncertainty(
         .map(String::as_str)
         .collect();
     let sigma_expr = uncertainty_propagation(&expr, &var_refs, Some(&cov))
-        .map_err(|e| format!("Uncertainty propagation failed: {e:?}"))?;
+        .map_err(|e| numerical_error(format!("Uncertainty propagation failed: {e:?}")))?;
 
     let sigma_str = sigma_expr
         .evaluate(&values_map, &HashMap::new())
         .to_string();
     let uncertainty = sigma_str
         .parse::<f64>()
-        .map_err(|e| format!("Failed to parse uncertainty '{sigma_str}': {e}"))?;
+        .map_err(|e| numerical_error(format!("Failed to parse uncertainty '{sigma_str}': {e}")))?;
 
     // Calculate derivatives for display
     let symbols: Vec<Symbol> = normalized_variable_names.iter().map(|s| symb(s)).collect();
     let sym_refs: Vec<&Symbol> = symbols.iter().collect();
-    let gradient = gradient(&expr, &sym_refs).map_err(|e| format!("Gradient failed: {e:?}"))?;
+    let gradient = gradient(&expr, &sym_refs)
+        .map_err(|e| numerical_error(format!("Gradient failed: {e:?}")))?;
 
     let mut derivatives = HashMap::new();
     for (i, name) in variable_names.iter().enumerate() {
@@ -155,35 +192,340 @@ pub fn calculate_uncertainty(
 
 /// Generate LaTeX representation
 ///
+/// Set `inline_math` to write each section as inline `$...$` math instead of
+/// `align*` environments.
+///
 /// # Errors
-/// Returns an error if the formula is invalid or symbols are unknown.
+/// Returns an error if the formula is invalid, symbols are unknown, or numerical errors occur.
 #[tauri::command]
 #[allow(
     clippy::needless_pass_by_value,
     reason = "Tauri commands require owned types for arguments"
 )]
-pub fn generate_latex(formula: String, variables: Vec<String>) -> Result<LatexResult, String> {
+pub fn generate_latex(
+    formula: String,
+    variables: Vec<CalculatorVariable>,
+    inline_math: Option<bool>,
+) -> CommandResult<LatexResult> {
     if variables.is_empty() {
-        return Err("Please provide at least one variable".into());
+        return Err(validation_error(
+            "Please provide at least one variable",
+            Some("variables".to_owned()),
+        ));
     }
+    let inline = inline_math.unwrap_or(false);
 
     let formula_normalized = formula.to_lowercase();
-    let normalized_variables = normalize_variable_names(&variables)?;
+    let variable_names: Vec<String> = variables.iter().map(|v| v.name.clone()).collect();
+    let normalized_variables = normalize_variable_names(&variable_names)?;
     let known_symbols: HashSet<String> = normalized_variables.iter().cloned().collect();
+    let values_map: HashMap<&str, f64> = normalized_variables
+        .iter()
+        .map(String::as_str)
+        .zip(variables.iter().map(|v| v.value))
+        .collect();
 
     let expr = parse(&formula_normalized, &known_symbols, &HashSet::new(), None)
-        .map_err(|e| format!("Failed to parse formula: {e}"))?;
+        .map_err(|e| parse_error(&e))?;
+    let value = evaluate(&expr, &values_map, "Expression")?;
 
     let var_refs: Vec<&str> = normalized_variables.iter().map(String::as_str).collect();
     let sigma_expr = uncertainty_propagation(&expr, &var_refs, None)
-        .map_err(|e| format!("Uncertainty propagation failed: {e:?}"))?;
+        .map_err(|e| numerical_error(format!("Uncertainty propagation failed: {e:?}")))?;
+
+    let symbols: Vec<Symbol> = normalized_variables.iter().map(|s| symb(s)).collect();
+    let sym_refs: Vec<&Symbol> = symbols.iter().collect();
+    let gradient = gradient(&expr, &sym_refs)
+        .map_err(|e| numerical_error(format!("Gradient failed: {e:?}")))?;
+
+    let latex_names: Vec<String> = normalized_variables
+        .iter()
+        .map(|name| latex_name(name))
+        .collect();
+    let (general, general_rhs) = general_section(
+        &expr,
+        &sigma_expr,
+        &normalized_variables,
+        &latex_names,
+        inline,
+    );
+    let derivatives = derivative_rows(
+        &gradient,
+        &variables,
+        &normalized_variables,
+        &latex_names,
+        &values_map,
+        inline,
+    )?;
+    let uncertainty = derivatives
+        .iter()
+        .map(|derivative| derivative.contribution.powi(2))
+        .sum::<f64>()
+        .sqrt();
 
     Ok(LatexResult {
         string: format!("sigma_f = {sigma_expr}"),
-        latex: format!("\\sigma_f = {}", sigma_expr.to_latex()),
+        latex: format!(r"\sigma_f = {general_rhs}"),
+        general,
+        budget: budget_table(&variables, &latex_names, &derivatives),
+        derivatives,
+        result: result_section(value, uncertainty, inline),
+    })
+}
+
+/// The formula and its general uncertainty formula; also returns the
+/// right-hand side of the latter.
+fn general_section(
+    expr: &Expr,
+    sigma_expr: &Expr,
+    names: &[String],
+    latex_names: &[String],
+    inline: bool,
+) -> (String, String) {
+    let general_terms: Vec<String> = latex_names
+        .iter()
+        .map(|name| {
+            format!(
+                r"\left(\frac{{\partial f}}{{\partial {name}}}\right)^{{2}} \sigma_{{{name}}}^{{2}}"
+            )
+        })
+        .collect();
+    let sigma_latex = latex_renamed(sigma_expr, names).to_latex();
+    let general_rhs = format!(r"\sqrt{{{}}} = {sigma_latex}", general_terms.join(" + "));
+    let general = math_block(
+        &[
+            ("f".to_owned(), latex_renamed(expr, names).to_latex()),
+            (r"\sigma_f".to_owned(), general_rhs.clone()),
+        ],
+        inline,
+    );
+    (general, general_rhs)
+}
+
+/// One derivative section per variable, with its contribution and share of
+/// the total variance.
+fn derivative_rows(
+    gradient: &[Expr],
+    variables: &[CalculatorVariable],
+    names: &[String],
+    latex_names: &[String],
+    values: &HashMap<&str, f64>,
+    inline: bool,
+) -> CommandResult<Vec<LatexDerivative>> {
+    let mut derivatives = Vec::with_capacity(variables.len());
+    for (index, variable) in variables.iter().enumerate() {
+        let derivative_expr = gradient
+            .get(index)
+            .ok_or_else(|| internal_error(format!("Missing derivative for '{}'", variable.name)))?;
+        let derivative = evaluate(
+            derivative_expr,
+            values,
+            &format!("Derivative with respect to '{}'", variable.name),
+        )?;
+        let contribution = variable.uncertainty.abs() * derivative.abs();
+        let section = derivative_section(
+            &latex_names[index],
+            &latex_renamed(derivative_expr, names).to_latex(),
+            &substituted_latex(derivative_expr, names, variables),
+            variable.uncertainty.abs(),
+            derivative,
+            inline,
+        );
+        derivatives.push(LatexDerivative {
+            name: variable.name.clone(),
+            section,
+            derivative,
+            contribution,
+            percentage: 0.0,
+        });
+    }
+
+    let variance: f64 = derivatives
+        .iter()
+        .map(|derivative| derivative.contribution.powi(2))
+        .sum();
+    if variance > 0.0 {
+        for derivative in &mut derivatives {
+            derivative.percentage = 100.0 * derivative.contribution.powi(2) / variance;
+        }
+    }
+    Ok(derivatives)
+}
+
+/// `f = value \pm uncertainty`, rounded to the uncertainty's precision.
+fn result_section(value: f64, uncertainty: f64, inline: bool) -> String {
+    let result_rhs = if uncertainty > 0.0 && uncertainty.is_finite() {
+        let precision = uncertainty_decimals(uncertainty).unsigned_abs() as usize;
+        format!(r"{value:.precision$} \pm {uncertainty:.precision$}")
+    } else {
+        latex_number(value)
+    };
+    math_block(&[("f".to_owned(), result_rhs)], inline)
+}
+
+/// Evaluate `expr` at `values` to a finite number; `what` names it in errors.
+fn evaluate(expr: &Expr, values: &HashMap<&str, f64>, what: &str) -> CommandResult<f64> {
+    let text = expr.evaluate(values, &HashMap::new()).to_string();
+    let value = text
+        .parse::<f64>()
+        .map_err(|e| numerical_error(format!("Failed to parse result '{text}': {e}")))?;
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(numerical_error(format!(
+            "{what} evaluated to non-finite value"
+        )))
+    }
+}
+
+/// Formula parse failure, reported against the `formula` field.
+fn parse_error(error: &impl std::fmt::Display) -> CommandError {
+    CommandError::Validation {
+        code: codes::PARSING_ERROR,
+        field: Some("formula".to_owned()),
+        detail: format!("Failed to parse formula: {error}"),
+    }
+}
+
+/// `expr` with the input values in place of the variables, negative values
+/// parenthesized.
+fn substituted_latex(expr: &Expr, names: &[String], variables: &[CalculatorVariable]) -> String {
+    names
+        .iter()
+        .zip(variables)
+        .fold(expr.clone(), |substituted, (name, variable)| {
+            let number = latex_number(variable.value);
+            let number = if variable.value < 0.0 {
+                format!(r"\left({number}\right)")
+            } else {
+                number
+            };
+            substituted.substitute(name, &Expr::symbol(number))
+        })
+        .to_latex()
+}
+
+/// Partial derivative with respect to `name`: symbolic, with the values
+/// substituted, and its contribution `\sigma \left|\partial f / \partial x\right|`.
+fn derivative_section(
+    name: &str,
+    symbolic: &str,
+    substituted: &str,
+    uncertainty: f64,
+    derivative: f64,
+    inline: bool,
+) -> String {
+    let partial = format!(r"\frac{{\partial f}}{{\partial {name}}}");
+    math_block(
+        &[
+            (partial.clone(), symbolic.to_owned()),
+            (
+                partial.clone(),
+                format!("{substituted} = {}", latex_number(derivative)),
+            ),
+            (
+                format!(r"\sigma_{{{name}}} \left|{partial}\right|"),
+                format!(
+                    r"{} \cdot {} = {}",
+                    latex_number(uncertainty),
+                    latex_number(derivative.abs()),
+                    latex_number(uncertainty * derivative.abs())
+                ),
+            ),
+        ],
+        inline,
+    )
+}
+
+/// LaTeX for a variable name; underscores are escaped rather than read as
+/// subscripts and Greek letter names become the letters.
+fn latex_name(name: &str) -> String {
+    Expr::symbol(name.replace('_', r"\_")).to_latex()
+}
+
+/// `expr` with each variable renamed to its LaTeX form and each
+/// `sigma_<name>` to `\sigma_{<name>}`.
+fn latex_renamed(expr: &Expr, names: &[String]) -> Expr {
+    names.iter().fold(expr.clone(), |renamed, name| {
+        let latex = latex_name(name);
+        renamed
+            .substitute(
+                &format!("sigma_{name}"),
+                &Expr::symbol(format!(r"\sigma_{{{latex}}}")),
+            )
+            .substitute(name, &Expr::symbol(latex))
     })
 }
 
+/// A number to six significant digits, in `m \times 10^{e}` form when very
+/// large or small.
+fn latex_number(value: f64) -> String {
+    let scientific = format!("{value:.5e}");
+    if value == 0.0 || (1e-3..1e6).contains(&value.abs()) {
+        return scientific.parse::<f64>().unwrap_or(value).to_string();
+    }
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .unwrap_or((scientific.as_str(), "0"));
+    let mantissa = mantissa.trim_end_matches('0').trim_end_matches('.');
+    format!(r"{mantissa} \times 10^{{{exponent}}}")
+}
+
+/// Equations `lhs = rhs` as one `align*` environment or as inline math
+/// separated by commas.
+fn math_block(equations: &[(String, String)], inline: bool) -> String {
+    if inline {
+        equations
+            .iter()
+            .map(|(lhs, rhs)| format!("${lhs} = {rhs}$"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    } else {
+        let rows = equations
+            .iter()
+            .map(|(lhs, rhs)| format!("{lhs} &= {rhs}"))
+            .collect::<Vec<_>>()
+            .join(" \\\\\n");
+        format!("\\begin{{align*}}\n{rows}\n\\end{{align*}}")
+    }
+}
+
+/// Uncertainty budget `tabular` listing each input with its contribution and
+/// share of the variance, largest share first.
+fn budget_table(
+    variables: &[CalculatorVariable],
+    latex_names: &[String],
+    derivatives: &[LatexDerivative],
+) -> String {
+    let mut order: Vec<usize> = (0..derivatives.len()).collect();
+    order.sort_by(|&a, &b| {
+        derivatives[b]
+            .percentage
+            .partial_cmp(&derivatives[a].percentage)
+            .unwrap_or(Ordering::Equal)
+    });
+
+    let mut lines = vec![
+        r"\begin{tabular}{lrrrr}".to_owned(),
+        r"\hline".to_owned(),
+        r"Variable & Value & $\sigma_i$ & $\sigma_i \left|\partial f / \partial x_i\right|$ & Contribution (\%) \\".to_owned(),
+        r"\hline".to_owned(),
+    ];
+    for index in order {
+        lines.push(format!(
+            r"${}$ & ${}$ & ${}$ & ${}$ & {:.1} \\",
+            latex_names[index],
+            latex_number(variables[index].value),
+            latex_number(variables[index].uncertainty.abs()),
+            latex_number(derivatives[index].contribution),
+            derivatives[index].percentage
+        ));
+    }
+    lines.push(r"\hline".to_owned());
+    lines.push(r"\end{tabular}".to_owned());
+    lines.join("\n")
+}
+
 #[cfg(test)]
 #[allow(
     clippy::unwrap_used,
@@ -209,16 +551,125 @@ mod tests {
         assert!((result.uncertainty - 0.6).abs() < 1e-9);
     }
 
+    fn variable(name: &str, value: f64, uncertainty: f64) -> CalculatorVariable {
+        CalculatorVariable {
+            name: name.to_owned(),
+            value,
+            uncertainty,
+        }
+    }
+
     #[test]
     fn test_generate_latex_simple() {
-        let result =
-            generate_latex("x + y".to_owned(), vec!["x".to_owned(), "y".to_owned()]).unwrap();
+        let result = generate_latex(
+            "x + y".to_owned(),
+            vec![variable("x", 1.0, 0.1), variable("y", 2.0, 0.2)],
+            None,
+        )
+        .unwrap();
 
         assert!(result.string.contains("sigma_x"));
         assert!(result.string.contains("sigma_y"));
         assert!(result.latex.contains("\\sigma"));
     }
 
+    #[test]
+    fn test_generate_latex_derivative_breakdown() {
+        let result = generate_latex(
+            "x^2 * y_max".to_owned(),
+            vec![variable("x", 2.0, 0.1), variable("y_max", 3.0, 0.4)],
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            result.general,
+            [
+                r"\begin{align*}",
+                r"f &= x^{2} \cdot y\_max \\",
+                r"\sigma_f &= \sqrt{\left(\frac{\partial f}{\partial x}\right)^{2} \sigma_{x}^{2} + \left(\frac{\partial f}{\partial y\_max}\right)^{2} \sigma_{y\_max}^{2}} = \sqrt{\sigma_{y\_max}^{2} \cdot x^{4} + 4 \cdot \left(\sigma_{x} \cdot x \cdot y\_max\right)^{2}}",
+                r"\end{align*}",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            result.derivatives[0].section,
+            [
+                r"\begin{align*}",
+                r"\frac{\partial f}{\partial x} &= 2 \cdot x \cdot y\_max \\",
+                r"\frac{\partial f}{\partial x} &= 2 \cdot 2 \cdot 3 = 12 \\",
+                r"\sigma_{x} \left|\frac{\partial f}{\partial x}\right| &= 0.1 \cdot 12 = 1.2",
+                r"\end{align*}",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            result.derivatives[1].section,
+            [
+                r"\begin{align*}",
+                r"\frac{\partial f}{\partial y\_max} &= x^{2} \\",
+                r"\frac{\partial f}{\partial y\_max} &= 2^{2} = 4 \\",
+                r"\sigma_{y\_max} \left|\frac{\partial f}{\partial y\_max}\right| &= 0.4 \cdot 4 = 1.6",
+                r"\end{align*}",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            result.budget,
+            [
+                r"\begin{tabular}{lrrrr}",
+                r"\hline",
+                r"Variable & Value & $\sigma_i$ & $\sigma_i \left|\partial f / \partial x_i\right|$ & Contribution (\%) \\",
+                r"\hline",
+                r"$y\_max$ & $3$ & $0.4$ & $1.6$ & 64.0 \\",
+                r"$x$ & $2$ & $0.1$ & $1.2$ & 36.0 \\",
+                r"\hline",
+                r"\end{tabular}",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            result.result,
+            [r"\begin{align*}", r"f &= 12 \pm 2", r"\end{align*}"].join("\n")
+        );
+
+        let total: f64 = result
+            .derivatives
+            .iter()
+            .map(|derivative| derivative.percentage)
+            .sum();
+        assert!((total - 100.0).abs() < 1e-9);
+        assert!((result.derivatives[0].percentage - 36.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_generate_latex_inline_math() {
+        let result = generate_latex(
+            "x * y_max".to_owned(),
+            vec![variable("x", -2.0, 0.1), variable("y_max", 3.0, 0.3)],
+            Some(true),
+        )
+        .unwrap();
+
+        assert!(!result.general.contains("align"));
+        assert_eq!(
+            result.derivatives[1].section,
+            [
+                r"$\frac{\partial f}{\partial y\_max} = x$",
+                r"$\frac{\partial f}{\partial y\_max} = \left(-2\right) = -2$",
+                r"$\sigma_{y\_max} \left|\frac{\partial f}{\partial y\_max}\right| = 0.3 \cdot 2 = 0.6$",
+            ]
+            .join(", ")
+        );
+        assert_eq!(result.result, r"$f = -6.0 \pm 0.7$");
+        let total: f64 = result
+            .derivatives
+            .iter()
+            .map(|derivative| derivative.percentage)
+            .sum();
+        assert!((total - 100.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_calculate_uncertainty_mixed_case_variable_name() {
         let result = calculate_uncertainty(
@@ -255,7 +706,10 @@ mod tests {
 
         match result {
             Ok(_) => panic!("expected case-collision error"),
-            Err(err) => assert!(err.contains("unique ignoring case")),
+            Err(err) => {
+                assert_eq!(err.field(), Some("variables"));
+                assert!(err.detail().contains("unique ignoring case"));
+            }
         }
     }
     #[test]
@@ -3,11 +3,16 @@ use regex::{NoExpand, Regex, escape};
 use std::collections::HashMap;
 use std::hash::BuildHasher;
 use std::sync::LazyLock;
+use symb_anafis::Expr;
 use thiserror::Error;
 
 static EULER_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\b[eE]\b").expect("Valid static regex for Euler constant"));
 
+static IDENTIFIER_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b([A-Za-z_][A-Za-z0-9_]*)(\s*\()?").expect("Valid static regex for identifiers")
+});
+
 /// Error type for Excel conversion operations
 #[derive(Debug, Error)]
 pub enum ConversionError {
@@ -172,6 +177,122 @@ pub fn symb_anafis_to_excel<S: BuildHasher>(
     Ok(excel_formula)
 }
 
+/// Convert `symb_anafis` expression to a `numpy` Python expression
+///
+/// Variables are replaced through `var_map`, `^` becomes `**`, supported
+/// functions map to their `np.` equivalents and `pi`/`e` to `np.pi`/`np.e`.
+///
+/// # Arguments
+/// * `symb_anafis_expr` - The expression string from `symb_anafis`
+/// * `var_map` - Maps variable names to Python identifiers (e.g., "x" -> "length")
+///
+/// # Examples
+/// ```
+/// # use std::collections::HashMap;
+/// # use anafis_lib::scientific::uncertainty_propagation::excel_conversion::symb_anafis_to_python;
+/// let mut var_map = HashMap::new();
+/// var_map.insert("x".to_string(), "length".to_string());
+/// let formula = symb_anafis_to_python("sqrt(x^2)", &var_map).unwrap();
+/// assert_eq!(formula, "np.sqrt(length**2)");
+/// ```
+/// # Errors
+/// Returns `ConversionError::UnsupportedFunction` for functions without a
+/// `numpy` equivalent and `ConversionError::IdentifierNotFound` for symbols
+/// missing from `var_map`.
+pub fn symb_anafis_to_python<S: BuildHasher>(
+    symb_anafis_expr: &str,
+    var_map: &HashMap<String, String, S>,
+) -> Result<String, ConversionError> {
+    let mut python = String::with_capacity(symb_anafis_expr.len());
+    let mut last = 0;
+    for captures in IDENTIFIER_REGEX.captures_iter(symb_anafis_expr) {
+        let (Some(whole), Some(name)) = (captures.get(0), captures.get(1)) else {
+            continue;
+        };
+        // Match bounds always fall on character boundaries.
+        python.push_str(
+            symb_anafis_expr
+                .get(last..whole.start())
+                .unwrap_or_default(),
+        );
+        if captures.get(2).is_some() {
+            let function = numpy_function(name.as_str())
+                .ok_or_else(|| ConversionError::UnsupportedFunction(name.as_str().to_owned()))?;
+            python.push_str(function);
+            python.push('(');
+        } else if let Some(replacement) = var_map.get(name.as_str()) {
+            python.push_str(replacement);
+        } else {
+            match name.as_str() {
+                "pi" => python.push_str("np.pi"),
+                "e" | "E" => python.push_str("np.e"),
+                other => return Err(ConversionError::IdentifierNotFound(other.to_owned())),
+            }
+        }
+        last = whole.end();
+    }
+    python.push_str(symb_anafis_expr.get(last..).unwrap_or_default());
+    Ok(python.replace('^', "**"))
+}
+
+/// `numpy` name of a `symb_anafis` function, if one exists.
+fn numpy_function(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "sqrt" => "np.sqrt",
+        "cbrt" => "np.cbrt",
+        "exp" => "np.exp",
+        "ln" => "np.log",
+        "log10" => "np.log10",
+        "log2" => "np.log2",
+        "sin" | "sen" => "np.sin",
+        "cos" => "np.cos",
+        "tan" => "np.tan",
+        "asin" => "np.arcsin",
+        "acos" => "np.arccos",
+        "atan" => "np.arctan",
+        "atan2" => "np.arctan2",
+        "sinh" => "np.sinh",
+        "cosh" => "np.cosh",
+        "tanh" => "np.tanh",
+        "asinh" => "np.arcsinh",
+        "acosh" => "np.arccosh",
+        "atanh" => "np.arctanh",
+        "abs" => "np.abs",
+        "signum" => "np.sign",
+        "floor" => "np.floor",
+        "ceil" => "np.ceil",
+        "round" => "np.round",
+        _ => return None,
+    })
+}
+
+/// Convert a `symb_anafis` expression tree to LaTeX
+///
+/// Fractions become `\frac{}{}`, roots `\sqrt{}`, powers `^{}` and
+/// Greek-named symbols (`alpha`, `beta`, ...) their LaTeX commands.
+/// Uncertainty symbols `sigma_<name>` are written as `\sigma_{<name>}`.
+///
+/// # Examples
+/// ```
+/// # use anafis_lib::scientific::uncertainty_propagation::excel_conversion::symb_anafis_to_latex;
+/// # use symb_anafis::Expr;
+/// let expr = Expr::mul_expr(Expr::symbol("alpha"), Expr::symbol("sigma_x"));
+/// assert_eq!(symb_anafis_to_latex(&expr), r"\alpha \cdot \sigma_{x}");
+/// ```
+#[must_use]
+pub fn symb_anafis_to_latex(expr: &Expr) -> String {
+    let mut renamed = expr.clone();
+    for name in expr.variables() {
+        if let Some(variable) = name.strip_prefix("sigma_")
+            && !variable.is_empty()
+        {
+            let symbol = format!(r"\sigma_{{{}}}", Expr::symbol(variable).to_latex());
+            renamed = renamed.substitute(&name, &Expr::symbol(symbol));
+        }
+    }
+    renamed.to_latex()
+}
+
 fn replace_identifiers<S: BuildHasher>(
     formula: &str,
     var_map: &HashMap<String, String, S>,
@@ -274,6 +395,8 @@ pub fn create_cell_ref(col: &str, row: usize) -> String {
     reason = "Tests use unwrap for brevity and sequential shadowing for state progression"
 )]
 mod tests {
+    use std::collections::HashSet;
+
     use super::*;
 
     #[test]
@@ -424,6 +547,59 @@ mod tests {
         assert_eq!(result, "ASSOC_LEGENDRE(2, 1, A1)");
     }
 
+    #[test]
+    fn test_symb_anafis_to_python() {
+        let mut var_map = HashMap::new();
+        var_map.insert("x".to_owned(), "x".to_owned());
+        var_map.insert("sigma_x".to_owned(), "sigma_x".to_owned());
+        var_map.insert("y".to_owned(), "length".to_owned());
+
+        let result = symb_anafis_to_python("sqrt((2*x*sigma_x)^2 + y^2)", &var_map).unwrap();
+        assert_eq!(result, "np.sqrt((2*x*sigma_x)**2 + length**2)");
+
+        let result = symb_anafis_to_python("atan2(y, x) + pi*exp(1e-3*x)", &var_map).unwrap();
+        assert_eq!(result, "np.arctan2(length, x) + np.pi*np.exp(1e-3*x)");
+
+        assert!(matches!(
+            symb_anafis_to_python("gamma(x)", &var_map),
+            Err(ConversionError::UnsupportedFunction(_))
+        ));
+        assert!(matches!(
+            symb_anafis_to_python("z*x", &var_map),
+            Err(ConversionError::IdentifierNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_symb_anafis_to_latex_textbook_products() {
+        let known: HashSet<String> = ["x", "y"].iter().map(|&s| s.to_owned()).collect();
+        let product = symb_anafis::parse("x*y", &known, &HashSet::new(), None).unwrap();
+        let sigma = symb_anafis::uncertainty_propagation(&product, &["x", "y"], None).unwrap();
+        // σ_f = √(y²σx² + x²σy²) for f = xy.
+        assert_eq!(
+            symb_anafis_to_latex(&sigma),
+            r"\sqrt{\left(\sigma_{x} \cdot y\right)^{2} + \left(\sigma_{y} \cdot x\right)^{2}}"
+        );
+
+        let quotient = symb_anafis::parse("x/y", &known, &HashSet::new(), None).unwrap();
+        let sigma = symb_anafis::uncertainty_propagation(&quotient, &["x", "y"], None).unwrap();
+        let latex = symb_anafis_to_latex(&sigma);
+        assert!(latex.starts_with(r"\frac{\sqrt{"), "{latex}");
+        assert!(latex.contains(r"\sigma_{x}") && latex.contains(r"\sigma_{y}"));
+        assert!(!latex.contains("sigma_x") && !latex.contains("sigma_y"));
+    }
+
+    #[test]
+    fn test_symb_anafis_to_latex_greek_symbols() {
+        let expr = Expr::mul_expr(
+            Expr::symbol("beta"),
+            Expr::pow_static(Expr::symbol("sigma_alpha"), Expr::number(2.0)),
+        );
+        let latex = symb_anafis_to_latex(&expr);
+        assert!(latex.contains(r"\beta"));
+        assert!(latex.contains(r"\sigma_{\alpha}^{2}"), "{latex}");
+    }
+
     #[test]
     fn test_parse_single_cell() {
         let range = parse_excel_range("A1").unwrap();
@@ -8,22 +8,35 @@ pub mod calculator;
 pub mod confidence;
 /// Tools for converting expressions to Excel formulas.
 pub mod excel_conversion;
+/// Monte Carlo uncertainty propagation.
+pub mod monte_carlo;
 /// Shared types for uncertainty propagation.
 pub mod types;
 
 // Re-export calculator commands and types
-pub use calculator::{CalculationResult, CalculatorVariable, LatexResult};
+pub use calculator::{CalculationResult, CalculatorVariable, LatexDerivative, LatexResult};
 pub use confidence::{confidence_to_sigma, sigma_to_confidence, validate_confidence_level};
-pub use excel_conversion::{create_cell_ref, parse_excel_range, symb_anafis_to_excel};
-pub use types::{ExcelRange, UncertaintyFormulas, Variable};
+pub use excel_conversion::{
+    create_cell_ref, parse_excel_range, symb_anafis_to_excel, symb_anafis_to_latex,
+    symb_anafis_to_python,
+};
+pub use monte_carlo::generate_uncertainty_formulas_mc;
+pub use types::{
+    ExcelRange, FormulaOutputFormat, InputDistribution, MonteCarloRow, UncertaintyFormulas,
+    Variable,
+};
 
 // Note: generate_uncertainty_formulas is defined in this module (mod.rs)
 // and is already a #[tauri::command] function exported directly
 
 use self::confidence::ConfidenceError;
 use self::excel_conversion::{ConversionError, RangeError};
+use crate::error::{CommandResult, validation_error};
 use std::collections::{HashMap, HashSet};
-use symb_anafis::{parse, uncertainty_propagation};
+use symb_anafis::{
+    CovEntry, CovarianceMatrix, DiffError, Expr, Symbol, gradient, parse, symb,
+    uncertainty_propagation,
+};
 use thiserror::Error;
 
 /// Errors that can occur during uncertainty propagation.
@@ -53,15 +66,32 @@ pub enum UncertaintyError {
     #[error("Variable '{0}' has mismatched value and uncertainty range lengths")]
     MismatchedVariableRanges(String),
 
+    /// Monte Carlo sampling could not produce a result.
+    #[error("Monte Carlo propagation failed: {0}")]
+    MonteCarlo(String),
+
+    /// Correlation coefficients are out of range, inconsistent, or name unknown variables.
+    #[error("Invalid correlation: {0}")]
+    InvalidCorrelation(String),
+
+    /// The requested formula output format cannot be produced.
+    #[error("Invalid output format: {0}")]
+    InvalidOutputFormat(String),
+
     /// Different variables have different range lengths.
     #[error("All variable ranges must have the same length")]
     MismatchedRangeLengths,
 }
 
-/// Generate Excel formulas for uncertainty propagation (synchronous)
+/// Generate Excel formulas (or Python lambdas or LaTeX) for uncertainty
+/// propagation (synchronous)
+///
+/// `output_format` defaults to Excel; the Python format returns a single
+/// `numpy` lambda each for the value and the uncertainty, and the LaTeX
+/// format a single equation each.
 ///
 /// # Errors
-/// Returns an error message if formula parsing or Excel conversion fails.
+/// Returns an error message if formula parsing or formula conversion fails.
 #[tauri::command]
 #[allow(
     clippy::needless_pass_by_value,
@@ -71,14 +101,60 @@ pub fn generate_uncertainty_formulas(
     variables: Vec<Variable>,
     formula: String,
     output_confidence: f64,
-) -> Result<UncertaintyFormulas, String> {
-    match generate_uncertainty_formulas_inner(&variables, &formula, output_confidence) {
+    output_format: Option<FormulaOutputFormat>,
+) -> CommandResult<UncertaintyFormulas> {
+    match generate_uncertainty_formulas_inner(
+        &variables,
+        &formula,
+        output_confidence,
+        false,
+        &output_format.unwrap_or_default(),
+    ) {
         Ok(result) => Ok(result),
         Err(e) => Ok(UncertaintyFormulas {
             value_formulas: vec![],
             uncertainty_formulas: vec![],
             success: false,
             error: Some(e.to_string()),
+            monte_carlo: None,
+        }),
+    }
+}
+
+/// Generate Excel formulas for uncertainty propagation with optional
+/// second-order (curvature) terms (synchronous)
+///
+/// With `include_second_order` the variance gains `½ Σ (∂²f/∂xᵢ²)² σᵢ⁴`, which
+/// matters near extrema where the first derivatives vanish; without it the
+/// output matches `generate_uncertainty_formulas`.
+///
+/// # Errors
+/// Returns an error message if formula parsing or Excel conversion fails.
+#[tauri::command]
+#[allow(
+    clippy::needless_pass_by_value,
+    reason = "Tauri commands require owned types for arguments"
+)]
+pub fn generate_uncertainty_formulas_second_order(
+    variables: Vec<Variable>,
+    formula: String,
+    output_confidence: f64,
+    include_second_order: bool,
+) -> CommandResult<UncertaintyFormulas> {
+    match generate_uncertainty_formulas_inner(
+        &variables,
+        &formula,
+        output_confidence,
+        include_second_order,
+        &FormulaOutputFormat::Excel,
+    ) {
+        Ok(result) => Ok(result),
+        Err(e) => Ok(UncertaintyFormulas {
+            value_formulas: vec![],
+            uncertainty_formulas: vec![],
+            success: false,
+            error: Some(e.to_string()),
+            monte_carlo: None,
         }),
     }
 }
@@ -95,8 +171,9 @@ pub fn generate_uncertainty_formulas(
     clippy::needless_pass_by_value,
     reason = "Tauri commands require owned types for arguments"
 )]
-pub fn convert_confidence_to_sigma(confidence_percent: f64) -> Result<f64, String> {
-    confidence::confidence_to_sigma(confidence_percent).map_err(|e| e.to_string())
+pub fn convert_confidence_to_sigma(confidence_percent: f64) -> CommandResult<f64> {
+    confidence::confidence_to_sigma(confidence_percent)
+        .map_err(|e| validation_error(e.to_string(), Some("confidencePercent".to_owned())))
 }
 
 /// Convert a sigma value (z-score) to its corresponding two-sided
@@ -111,15 +188,191 @@ pub fn convert_confidence_to_sigma(confidence_percent: f64) -> Result<f64, Strin
     clippy::needless_pass_by_value,
     reason = "Tauri commands require owned types for arguments"
 )]
-pub fn convert_sigma_to_confidence(sigma: f64) -> Result<f64, String> {
-    confidence::sigma_to_confidence(sigma).map_err(|e| e.to_string())
+pub fn convert_sigma_to_confidence(sigma: f64) -> CommandResult<f64> {
+    confidence::sigma_to_confidence(sigma)
+        .map_err(|e| validation_error(e.to_string(), Some("sigma".to_owned())))
+}
+
+/// Correlation matrix assembled from the variables' `correlations` maps, or
+/// `None` when every variable is independent.
+fn correlation_matrix(
+    variables: &[Variable],
+    normalized_names: &[String],
+) -> Result<Option<Vec<Vec<f64>>>, UncertaintyError> {
+    if variables.iter().all(|var| var.correlations.is_none()) {
+        return Ok(None);
+    }
+    let n = variables.len();
+    let mut matrix = vec![vec![0.0; n]; n];
+    for (i, var) in variables.iter().enumerate() {
+        matrix[i][i] = 1.0;
+        for (other, &rho) in var.correlations.iter().flatten() {
+            let j = normalized_names
+                .iter()
+                .position(|name| *name == other.to_lowercase())
+                .ok_or_else(|| {
+                    UncertaintyError::InvalidCorrelation(format!(
+                        "'{}' is correlated with unknown variable '{other}'",
+                        var.name
+                    ))
+                })?;
+            if i == j || !rho.is_finite() || rho.abs() > 1.0 {
+                return Err(UncertaintyError::InvalidCorrelation(format!(
+                    "correlation of '{}' with '{other}' must be in [-1, 1] between distinct variables, got {rho}",
+                    var.name
+                )));
+            }
+            if matrix[i][j] != 0.0 && (matrix[i][j] - rho).abs() > 1e-12 {
+                return Err(UncertaintyError::InvalidCorrelation(format!(
+                    "'{}' and '{other}' declare different correlations ({} and {rho})",
+                    var.name, matrix[i][j]
+                )));
+            }
+            matrix[i][j] = rho;
+            matrix[j][i] = rho;
+        }
+    }
+    Ok(Some(matrix))
+}
+
+/// First-order uncertainty expression `sqrt(gᵀ Σ g)` with
+/// `Σᵢⱼ = ρᵢⱼ σᵢ σⱼ` in `sigma_<name>` symbols.
+fn correlated_sigma(
+    expr: &Expr,
+    variables: &[&str],
+    correlations: &[Vec<f64>],
+) -> Result<Expr, UncertaintyError> {
+    let entries = correlations
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(j, &rho)| {
+                    let sigma_i = Expr::symbol(format!("sigma_{}", variables[i]));
+                    let sigma_j = Expr::symbol(format!("sigma_{}", variables[j]));
+                    if i == j {
+                        CovEntry::Symbolic(Expr::pow_static(sigma_i, Expr::number(2.0)))
+                    } else if rho == 0.0 {
+                        CovEntry::Num(0.0)
+                    } else {
+                        CovEntry::Symbolic(Expr::mul_expr(
+                            Expr::number(rho),
+                            Expr::mul_expr(sigma_i, sigma_j),
+                        ))
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    let covariance = CovarianceMatrix::new(entries)
+        .map_err(|e| UncertaintyError::UncertaintyPropagation(e.to_string()))?;
+    uncertainty_propagation(expr, variables, Some(&covariance))
+        .map_err(|e| UncertaintyError::UncertaintyPropagation(e.to_string()))
+}
+
+/// Second-order Taylor uncertainty expression:
+/// `sqrt(gᵀ Σ g + ½ Σ (∂²f/∂xᵢ²)² σᵢ⁴)` in `sigma_<name>` symbols. The
+/// curvature term assumes independent inputs; correlations only enter `gᵀ Σ g`.
+fn second_order_sigma(
+    expr: &Expr,
+    variables: &[&str],
+    correlations: Option<&[Vec<f64>]>,
+) -> Result<Expr, UncertaintyError> {
+    let failed = |e: DiffError| UncertaintyError::UncertaintyPropagation(e.to_string());
+    let symbols: Vec<Symbol> = variables.iter().map(|name| symb(name)).collect();
+    let symbol_refs: Vec<&Symbol> = symbols.iter().collect();
+    let first_derivatives = gradient(expr, &symbol_refs).map_err(failed)?;
+
+    let mut terms = Vec::new();
+    for (i, j, rho) in correlations
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .flat_map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .skip(i + 1)
+                .map(move |(j, &rho)| (i, j, rho))
+        })
+    {
+        if rho != 0.0 && !first_derivatives[i].is_zero_num() && !first_derivatives[j].is_zero_num()
+        {
+            terms.push(Expr::mul_expr(
+                Expr::number(2.0 * rho),
+                Expr::mul_expr(
+                    Expr::mul_expr(first_derivatives[i].clone(), first_derivatives[j].clone()),
+                    Expr::mul_expr(
+                        Expr::symbol(format!("sigma_{}", variables[i])),
+                        Expr::symbol(format!("sigma_{}", variables[j])),
+                    ),
+                ),
+            ));
+        }
+    }
+    for ((name, symbol), first) in variables.iter().zip(&symbols).zip(first_derivatives) {
+        let second = gradient(&first, &[symbol])
+            .map_err(failed)?
+            .pop()
+            .unwrap_or_else(|| Expr::number(0.0));
+        let sigma = Expr::symbol(format!("sigma_{name}"));
+        if !first.is_zero_num() {
+            terms.push(Expr::mul_expr(
+                Expr::pow_static(first, Expr::number(2.0)),
+                Expr::pow_static(sigma.clone(), Expr::number(2.0)),
+            ));
+        }
+        if !second.is_zero_num() {
+            terms.push(Expr::mul_expr(
+                Expr::number(0.5),
+                Expr::mul_expr(
+                    Expr::pow_static(second, Expr::number(2.0)),
+                    Expr::pow_static(sigma, Expr::number(4.0)),
+                ),
+            ));
+        }
+    }
+
+    let variance = Expr::sum(terms).simplified().map_err(failed)?;
+    Expr::call("sqrt", [variance]).simplified().map_err(failed)
 }
 
 fn generate_uncertainty_formulas_inner(
     variables: &[Variable],
     formula: &str,
     output_confidence: f64,
+    second_order: bool,
+    output_format: &FormulaOutputFormat,
 ) -> Result<UncertaintyFormulas, UncertaintyError> {
+    let (formula_normalized, expr, sigma_expr) =
+        propagated_uncertainty(variables, formula, second_order)?;
+    match output_format {
+        FormulaOutputFormat::Excel => excel_formulas(
+            variables,
+            &formula_normalized,
+            &sigma_expr,
+            output_confidence,
+        ),
+        FormulaOutputFormat::Python { variable_names } => python_formulas(
+            variables,
+            variable_names,
+            &formula_normalized,
+            &sigma_expr,
+            output_confidence,
+        ),
+        FormulaOutputFormat::LaTeX => {
+            Ok(latex_formulas(variables, &expr, &sigma_expr, second_order))
+        }
+    }
+}
+
+/// Parse `formula` and build its uncertainty expression in `sigma_<name>`
+/// symbols, returning the lowercased formula and its parsed form alongside it.
+fn propagated_uncertainty(
+    variables: &[Variable],
+    formula: &str,
+    second_order: bool,
+) -> Result<(String, Expr, Expr), UncertaintyError> {
     let formula_normalized = formula.to_lowercase();
     let var_names: Vec<String> = variables.iter().map(|v| v.name.clone()).collect();
     let normalized_var_names: Vec<String> = var_names.iter().map(|v| v.to_lowercase()).collect();
@@ -137,6 +390,26 @@ fn generate_uncertainty_formulas_inner(
     let expr = parse(&formula_normalized, &known_symbols, &HashSet::new(), None)
         .map_err(|e| UncertaintyError::ParseError(e.to_string()))?;
 
+    // Get uncertainty expression from symb_anafis
+    let all_vars: Vec<&str> = normalized_var_names.iter().map(String::as_str).collect();
+    let correlations = correlation_matrix(variables, &normalized_var_names)?;
+    let sigma_expr = match (second_order, correlations.as_deref()) {
+        (true, correlations) => second_order_sigma(&expr, &all_vars, correlations)?,
+        (false, Some(correlations)) => correlated_sigma(&expr, &all_vars, correlations)?,
+        (false, None) => uncertainty_propagation(&expr, &all_vars, None)
+            .map_err(|e| UncertaintyError::UncertaintyPropagation(e.to_string()))?,
+    };
+
+    Ok((formula_normalized, expr, sigma_expr))
+}
+
+/// One Excel formula per row for the value and its uncertainty.
+fn excel_formulas(
+    variables: &[Variable],
+    formula_normalized: &str,
+    sigma_expr: &Expr,
+    output_confidence: f64,
+) -> Result<UncertaintyFormulas, UncertaintyError> {
     // Parse ranges and validate
     let mut row_count = 0;
     let mut var_info = Vec::new();
@@ -165,11 +438,6 @@ fn generate_uncertainty_formulas_inner(
     // Get output sigma for confidence conversion
     let output_sigma = confidence_to_sigma(output_confidence)?;
 
-    // Get uncertainty expression from symb_anafis
-    let all_vars: Vec<&str> = normalized_var_names.iter().map(String::as_str).collect();
-    let sigma_expr = uncertainty_propagation(&expr, &all_vars, None)
-        .map_err(|e| UncertaintyError::UncertaintyPropagation(e.to_string()))?;
-
     let mut value_formulas = Vec::new();
     let mut uncertainty_formulas = Vec::new();
 
@@ -183,7 +451,7 @@ fn generate_uncertainty_formulas_inner(
             }
         }
 
-        let value_formula = format!("={}", symb_anafis_to_excel(&formula_normalized, &var_map)?);
+        let value_formula = format!("={}", symb_anafis_to_excel(formula_normalized, &var_map)?);
         value_formulas.push(value_formula);
 
         // Uncertainty formula: substitute both variables and sigma variables
@@ -221,9 +489,114 @@ fn generate_uncertainty_formulas_inner(
         uncertainty_formulas,
         success: true,
         error: None,
+        monte_carlo: None,
+    })
+}
+
+/// `numpy` lambdas for the value and its uncertainty; the uncertainty lambda
+/// also takes a `sigma_<name>` parameter per variable.
+fn python_formulas(
+    variables: &[Variable],
+    python_names: &[String],
+    formula_normalized: &str,
+    sigma_expr: &Expr,
+    output_confidence: f64,
+) -> Result<UncertaintyFormulas, UncertaintyError> {
+    if python_names.len() != variables.len() {
+        return Err(UncertaintyError::InvalidOutputFormat(format!(
+            "expected {} Python variable names, got {}",
+            variables.len(),
+            python_names.len()
+        )));
+    }
+    let mut seen = HashSet::new();
+    for name in python_names {
+        let valid = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && name != "np";
+        if !valid || !seen.insert(name) {
+            return Err(UncertaintyError::InvalidOutputFormat(format!(
+                "'{name}' is not a unique Python identifier"
+            )));
+        }
+    }
+
+    let output_sigma = confidence_to_sigma(output_confidence)?;
+    let mut var_map: HashMap<String, String> = HashMap::new();
+    let mut sigma_params = Vec::with_capacity(variables.len());
+    for (var, python_name) in variables.iter().zip(python_names) {
+        let name = var.name.to_lowercase();
+        var_map.insert(name.clone(), python_name.clone());
+        let sigma_param = format!("sigma_{python_name}");
+        let conversion_factor = output_sigma / confidence_to_sigma(var.confidence)?;
+        let converted_sigma = if (conversion_factor - 1.0).abs() < 1e-10 {
+            sigma_param.clone()
+        } else {
+            format!("({sigma_param} * {conversion_factor})")
+        };
+        var_map.insert(format!("sigma_{name}"), converted_sigma);
+        sigma_params.push(sigma_param);
+    }
+
+    let value_params = python_names.join(", ");
+    let value_formula = format!(
+        "lambda {value_params}: {}",
+        symb_anafis_to_python(formula_normalized, &var_map)?
+    );
+    let uncertainty_formula = format!(
+        "lambda {value_params}, {}: {}",
+        sigma_params.join(", "),
+        symb_anafis_to_python(&sigma_expr.to_string(), &var_map)?
+    );
+    Ok(UncertaintyFormulas {
+        value_formulas: vec![value_formula],
+        uncertainty_formulas: vec![uncertainty_formula],
+        success: true,
+        error: None,
+        monte_carlo: None,
     })
 }
 
+/// LaTeX equations for the value and its uncertainty.
+///
+/// For independent first-order propagation the uncertainty reads
+/// `\sigma_f = \sqrt{\left(\frac{\partial f}{\partial x}\right)^{2} \sigma_{x}^{2} + ...} = ...`,
+/// giving the general form before the evaluated one.
+fn latex_formulas(
+    variables: &[Variable],
+    expr: &Expr,
+    sigma_expr: &Expr,
+    second_order: bool,
+) -> UncertaintyFormulas {
+    let explicit = symb_anafis_to_latex(sigma_expr);
+    let independent = variables.iter().all(|var| var.correlations.is_none());
+    let uncertainty_formula = if independent && !second_order && !variables.is_empty() {
+        let general = variables
+            .iter()
+            .map(|var| {
+                let name = Expr::symbol(var.name.to_lowercase()).to_latex();
+                format!(
+                    r"\left(\frac{{\partial f}}{{\partial {name}}}\right)^{{2}} \sigma_{{{name}}}^{{2}}"
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" + ");
+        format!(r"\sigma_f = \sqrt{{{general}}} = {explicit}")
+    } else {
+        format!(r"\sigma_f = {explicit}")
+    };
+    UncertaintyFormulas {
+        value_formulas: vec![format!("f = {}", symb_anafis_to_latex(expr))],
+        uncertainty_formulas: vec![uncertainty_formula],
+        success: true,
+        error: None,
+        monte_carlo: None,
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
 mod tests {
@@ -237,16 +610,31 @@ mod tests {
                 value_range: "A1:A2".to_owned(),
                 uncertainty_range: "B1:B2".to_owned(),
                 confidence: 95.0,
+                values: Vec::new(),
+                uncertainties: Vec::new(),
+                distribution: InputDistribution::Normal,
+                correlations: None,
             },
             Variable {
                 name: "b".to_owned(),
                 value_range: "C1:C2".to_owned(),
                 uncertainty_range: "D1:D2".to_owned(),
                 confidence: 95.0,
+                values: Vec::new(),
+                uncertainties: Vec::new(),
+                distribution: InputDistribution::Normal,
+                correlations: None,
             },
         ];
 
-        let result = generate_uncertainty_formulas_inner(&variables, "sin(a) * b", 95.0).unwrap();
+        let result = generate_uncertainty_formulas_inner(
+            &variables,
+            "sin(a) * b",
+            95.0,
+            false,
+            &FormulaOutputFormat::Excel,
+        )
+        .unwrap();
 
         assert!(result.success);
         assert_eq!(result.uncertainty_formulas.len(), 2);
@@ -262,11 +650,234 @@ mod tests {
             value_range: "A1:A1".to_owned(),
             uncertainty_range: "B1:B1".to_owned(),
             confidence: 95.0,
+            values: Vec::new(),
+            uncertainties: Vec::new(),
+            distribution: InputDistribution::Normal,
+            correlations: None,
         }];
 
-        let result = generate_uncertainty_formulas_inner(&variables, "AlotA^2", 95.0).unwrap();
+        let result = generate_uncertainty_formulas_inner(
+            &variables,
+            "AlotA^2",
+            95.0,
+            false,
+            &FormulaOutputFormat::Excel,
+        )
+        .unwrap();
         assert!(result.success);
         assert_eq!(result.value_formulas, vec!["=A1^2".to_owned()]);
         assert!(!result.uncertainty_formulas[0].contains("sigma_alota"));
     }
+
+    #[test]
+    fn test_second_order_adds_curvature_term() {
+        let variables = vec![Variable {
+            name: "x".to_owned(),
+            value_range: "A1:A1".to_owned(),
+            uncertainty_range: "B1:B1".to_owned(),
+            confidence: 95.0,
+            values: Vec::new(),
+            uncertainties: Vec::new(),
+            distribution: InputDistribution::Normal,
+            correlations: None,
+        }];
+
+        let first = generate_uncertainty_formulas_inner(
+            &variables,
+            "x^2",
+            95.0,
+            false,
+            &FormulaOutputFormat::Excel,
+        )
+        .unwrap();
+        let second = generate_uncertainty_formulas_inner(
+            &variables,
+            "x^2",
+            95.0,
+            true,
+            &FormulaOutputFormat::Excel,
+        )
+        .unwrap();
+        assert!(second.success);
+        assert_eq!(first.value_formulas, second.value_formulas);
+        let formula = &second.uncertainty_formulas[0];
+        assert!(formula.to_uppercase().contains("SQRT"));
+        assert!(formula.contains("B1^4"));
+        assert_ne!(&first.uncertainty_formulas[0], formula);
+
+        // A linear formula has no curvature, so both orders agree.
+        let linear = generate_uncertainty_formulas_inner(
+            &variables,
+            "3*x",
+            95.0,
+            true,
+            &FormulaOutputFormat::Excel,
+        )
+        .unwrap();
+        assert!(!linear.uncertainty_formulas[0].contains("^4"));
+    }
+
+    #[test]
+    fn test_correlated_inputs_add_cross_terms() {
+        let variable = |name: &str, columns: [&str; 2], correlations| Variable {
+            name: name.to_owned(),
+            value_range: format!("{}1:{}1", columns[0], columns[0]),
+            uncertainty_range: format!("{}1:{}1", columns[1], columns[1]),
+            confidence: 95.0,
+            values: Vec::new(),
+            uncertainties: Vec::new(),
+            distribution: InputDistribution::Normal,
+            correlations,
+        };
+        let pressure = variable(
+            "p",
+            ["A", "B"],
+            Some(HashMap::from([("T".to_owned(), 0.3)])),
+        );
+        let temperature = variable("t", ["C", "D"], None);
+
+        let result = generate_uncertainty_formulas_inner(
+            &[pressure.clone(), temperature.clone()],
+            "p*t",
+            95.0,
+            false,
+            &FormulaOutputFormat::Excel,
+        )
+        .unwrap();
+        let formula = &result.uncertainty_formulas[0];
+        assert!(formula.contains("B1") && formula.contains("D1"));
+        // Cross term 2 * (∂f/∂p) * (∂f/∂t) * ρ * σp * σt with ρ = 0.3.
+        assert!(formula.contains("0.6"));
+
+        let independent = variable("p", ["A", "B"], None);
+        let plain = generate_uncertainty_formulas_inner(
+            &[independent, temperature.clone()],
+            "p*t",
+            95.0,
+            false,
+            &FormulaOutputFormat::Excel,
+        )
+        .unwrap();
+        assert_ne!(&plain.uncertainty_formulas[0], formula);
+
+        let mut invalid = pressure;
+        invalid.correlations = Some(HashMap::from([("q".to_owned(), 0.5)]));
+        assert!(
+            generate_uncertainty_formulas_inner(
+                &[invalid.clone(), temperature.clone()],
+                "p*t",
+                95.0,
+                false,
+                &FormulaOutputFormat::Excel
+            )
+            .is_err()
+        );
+        invalid.correlations = Some(HashMap::from([("t".to_owned(), 1.5)]));
+        assert!(
+            generate_uncertainty_formulas_inner(
+                &[invalid, temperature],
+                "p*t",
+                95.0,
+                false,
+                &FormulaOutputFormat::Excel
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_python_output_format() {
+        let variable = |name: &str, confidence| Variable {
+            name: name.to_owned(),
+            value_range: String::new(),
+            uncertainty_range: String::new(),
+            confidence,
+            values: Vec::new(),
+            uncertainties: Vec::new(),
+            distribution: InputDistribution::Normal,
+            correlations: None,
+        };
+        let format = FormulaOutputFormat::Python {
+            variable_names: vec!["x".to_owned(), "length".to_owned()],
+        };
+
+        let result = generate_uncertainty_formulas_inner(
+            &[variable("a", 68.268_949_213_708_58), variable("b", 95.0)],
+            "a^2 + sqrt(b)",
+            95.0,
+            false,
+            &format,
+        )
+        .unwrap();
+        assert_eq!(
+            result.value_formulas,
+            vec!["lambda x, length: x**2 + np.sqrt(length)".to_owned()]
+        );
+        let formula = &result.uncertainty_formulas[0];
+        assert!(formula.starts_with("lambda x, length, sigma_x, sigma_length: np.sqrt("));
+        assert!(formula.contains("(sigma_x * 1.95996"));
+        assert!(!formula.contains('^'));
+
+        let mismatched = FormulaOutputFormat::Python {
+            variable_names: vec!["x".to_owned()],
+        };
+        assert!(
+            generate_uncertainty_formulas_inner(
+                &[variable("a", 95.0), variable("b", 95.0)],
+                "a*b",
+                95.0,
+                false,
+                &mismatched,
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_latex_output_matches_textbook_forms() {
+        let variable = |name: &str| Variable {
+            name: name.to_owned(),
+            value_range: String::new(),
+            uncertainty_range: String::new(),
+            confidence: 95.0,
+            values: Vec::new(),
+            uncertainties: Vec::new(),
+            distribution: InputDistribution::Normal,
+            correlations: None,
+        };
+
+        // Product rule: σ_f = √(y²σx² + x²σy²).
+        let product = generate_uncertainty_formulas_inner(
+            &[variable("x"), variable("y")],
+            "x*y",
+            95.0,
+            false,
+            &FormulaOutputFormat::LaTeX,
+        )
+        .unwrap();
+        assert_eq!(product.value_formulas, vec![r"f = x \cdot y".to_owned()]);
+        assert_eq!(
+            product.uncertainty_formulas[0],
+            concat!(
+                r"\sigma_f = \sqrt{\left(\frac{\partial f}{\partial x}\right)^{2} \sigma_{x}^{2}",
+                r" + \left(\frac{\partial f}{\partial y}\right)^{2} \sigma_{y}^{2}}",
+                r" = \sqrt{\left(\sigma_{x} \cdot y\right)^{2} + \left(\sigma_{y} \cdot x\right)^{2}}"
+            )
+        );
+
+        // Power law with a Greek-named variable: σ_f = 2|α| σ_α.
+        let power = generate_uncertainty_formulas_inner(
+            &[variable("alpha")],
+            "alpha^2",
+            95.0,
+            false,
+            &FormulaOutputFormat::LaTeX,
+        )
+        .unwrap();
+        assert_eq!(power.value_formulas, vec![r"f = \alpha^{2}".to_owned()]);
+        let formula = &power.uncertainty_formulas[0];
+        assert!(formula.contains(r"\partial \alpha"));
+        assert!(formula.contains(r"\sigma_{\alpha}"), "{formula}");
+        assert!(formula.ends_with(r"= 2 \cdot \left|\alpha \cdot \sigma_{\alpha}\right|"));
+    }
 }
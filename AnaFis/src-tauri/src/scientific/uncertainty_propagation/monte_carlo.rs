@@ -0,0 +1,330 @@
+//! Monte Carlo uncertainty propagation.
+//!
+//! Alternative to the first-order Taylor formulas for strongly nonlinear
+//! formulas or non-Gaussian inputs. For every spreadsheet row the inputs are
+//! sampled from their distributions, the formula is evaluated in one batch with
+//! a [`CompiledEvaluator`], and the output uncertainty is the empirical
+//! standard deviation, scaled to the output confidence level like the Taylor
+//! formulas. Quantile intervals are reported alongside, since the output
+//! distribution need not be symmetric.
+
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::{Rng, RngCore, SeedableRng};
+use rand_distr::StandardNormal;
+use rand_pcg::Pcg64;
+use symb_anafis::{CompiledEvaluator, parse};
+
+use super::UncertaintyError;
+use super::confidence::confidence_to_sigma;
+use super::excel_conversion::{parse_excel_range, symb_anafis_to_excel};
+use super::types::{InputDistribution, MonteCarloRow, UncertaintyFormulas, Variable};
+use crate::error::CommandResult;
+use crate::scientific::primitives::descriptive::{mean, quantile_sorted, sample_variance};
+
+/// Smallest accepted number of Monte Carlo samples per row.
+pub const MIN_MC_SAMPLES: usize = 100;
+/// Largest accepted number of Monte Carlo samples per row.
+pub const MAX_MC_SAMPLES: usize = 10_000_000;
+
+/// Generate value formulas and Monte Carlo uncertainties (synchronous)
+///
+/// Each variable must carry the cell values of its ranges in `values` and
+/// `uncertainties`. Uncertainty cells receive constants, since a sampled
+/// uncertainty has no closed-form formula; per-row means, standard deviations
+/// and quantile intervals are returned in `monte_carlo`.
+///
+/// # Errors
+/// Returns an error message only if the command itself cannot run; failures
+/// are reported through `success` and `error` like the Taylor command.
+#[tauri::command]
+#[allow(
+    clippy::needless_pass_by_value,
+    reason = "Tauri commands require owned types for arguments"
+)]
+pub fn generate_uncertainty_formulas_mc(
+    variables: Vec<Variable>,
+    formula: String,
+    output_confidence: f64,
+    n_samples: usize,
+    seed: Option<u64>,
+) -> CommandResult<UncertaintyFormulas> {
+    let seed = seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| {
+                elapsed.as_secs() ^ u64::from(elapsed.subsec_nanos())
+            })
+    });
+    match propagate(&variables, &formula, output_confidence, n_samples, seed) {
+        Ok(result) => Ok(result),
+        Err(e) => Ok(UncertaintyFormulas {
+            value_formulas: vec![],
+            uncertainty_formulas: vec![],
+            success: false,
+            error: Some(e.to_string()),
+            monte_carlo: None,
+        }),
+    }
+}
+
+/// Per-variable sampling inputs.
+struct SampledVariable {
+    name: String,
+    cells: Vec<String>,
+    values: Vec<f64>,
+    /// Standard (one-sigma) uncertainties.
+    sigmas: Vec<f64>,
+    distribution: InputDistribution,
+}
+
+fn propagate(
+    variables: &[Variable],
+    formula: &str,
+    output_confidence: f64,
+    n_samples: usize,
+    seed: u64,
+) -> Result<UncertaintyFormulas, UncertaintyError> {
+    if !(MIN_MC_SAMPLES..=MAX_MC_SAMPLES).contains(&n_samples) {
+        return Err(UncertaintyError::MonteCarlo(format!(
+            "Sample count must be between {MIN_MC_SAMPLES} and {MAX_MC_SAMPLES}, got {n_samples}"
+        )));
+    }
+    let output_sigma = confidence_to_sigma(output_confidence)?;
+    let sampled = prepare_variables(variables)?;
+    let row_count = sampled.first().map_or(0, |variable| variable.values.len());
+
+    let formula_normalized = formula.to_lowercase();
+    let known_symbols: HashSet<String> = sampled.iter().map(|v| v.name.clone()).collect();
+    let expr = parse(&formula_normalized, &known_symbols, &HashSet::new(), None)
+        .map_err(|e| UncertaintyError::ParseError(e.to_string()))?;
+    let order: Vec<&str> = sampled.iter().map(|v| v.name.as_str()).collect();
+    let evaluator = CompiledEvaluator::compile(&expr, &order, None)
+        .map_err(|e| UncertaintyError::ParseError(format!("{e:?}")))?;
+
+    let mut rng = Pcg64::seed_from_u64(seed);
+    let mut columns = vec![vec![0.0; n_samples]; sampled.len()];
+    let mut outputs = vec![0.0; n_samples];
+    let mut value_formulas = Vec::with_capacity(row_count);
+    let mut uncertainty_formulas = Vec::with_capacity(row_count);
+    let mut rows = Vec::with_capacity(row_count);
+    for row in 0..row_count {
+        let var_map: HashMap<String, String> = sampled
+            .iter()
+            .map(|v| (v.name.clone(), v.cells[row].clone()))
+            .collect();
+        value_formulas.push(format!(
+            "={}",
+            symb_anafis_to_excel(&formula_normalized, &var_map)?
+        ));
+
+        for (variable, column) in sampled.iter().zip(columns.iter_mut()) {
+            fill_samples(
+                &mut rng,
+                variable.distribution,
+                variable.values[row],
+                variable.sigmas[row],
+                column,
+            );
+        }
+        let column_refs: Vec<&[f64]> = columns.iter().map(Vec::as_slice).collect();
+        evaluator
+            .eval_batch(&column_refs, &mut outputs, None)
+            .map_err(|e| UncertaintyError::MonteCarlo(format!("{e:?}")))?;
+
+        let summary = summarize(&outputs, output_confidence / 100.0).ok_or_else(|| {
+            UncertaintyError::MonteCarlo(format!(
+                "Row {}: too few samples evaluated to finite values",
+                row + 1
+            ))
+        })?;
+        uncertainty_formulas.push(format!("={}", summary.std_dev * output_sigma));
+        rows.push(summary);
+    }
+
+    Ok(UncertaintyFormulas {
+        value_formulas,
+        uncertainty_formulas,
+        success: true,
+        error: None,
+        monte_carlo: Some(rows),
+    })
+}
+
+/// Check names and cell values, converting uncertainties to one sigma.
+fn prepare_variables(variables: &[Variable]) -> Result<Vec<SampledVariable>, UncertaintyError> {
+    let mut seen = HashSet::new();
+    let mut row_count = None;
+    let mut sampled = Vec::with_capacity(variables.len());
+    for var in variables {
+        let name = var.name.to_lowercase();
+        if !seen.insert(name.clone()) {
+            return Err(UncertaintyError::ParseError(format!(
+                "Variable names must be unique ignoring case (collision on '{name}')"
+            )));
+        }
+        let range = parse_excel_range(&var.value_range)?;
+        let rows = range.row_count();
+        if *row_count.get_or_insert(rows) != rows {
+            return Err(UncertaintyError::MismatchedRangeLengths);
+        }
+        let uncertainties = if var.uncertainty_range.is_empty() {
+            vec![0.0; rows]
+        } else {
+            var.uncertainties.clone()
+        };
+        if var.values.len() != rows || uncertainties.len() != rows {
+            return Err(UncertaintyError::MismatchedVariableRanges(var.name.clone()));
+        }
+        if var
+            .values
+            .iter()
+            .chain(&uncertainties)
+            .any(|value| !value.is_finite())
+            || uncertainties.iter().any(|sigma| *sigma < 0.0)
+        {
+            return Err(UncertaintyError::MonteCarlo(format!(
+                "Variable '{}' has non-finite values or negative uncertainties",
+                var.name
+            )));
+        }
+        let input_sigma = confidence_to_sigma(var.confidence)?;
+        sampled.push(SampledVariable {
+            cells: (0..rows).filter_map(|row| range.cell_at(row)).collect(),
+            values: var.values.clone(),
+            sigmas: uncertainties.iter().map(|u| u / input_sigma).collect(),
+            distribution: var.distribution,
+            name,
+        });
+    }
+    Ok(sampled)
+}
+
+/// Overwrite `column` with draws centred on `value` with standard deviation `sigma`.
+fn fill_samples(
+    rng: &mut Pcg64,
+    distribution: InputDistribution,
+    value: f64,
+    sigma: f64,
+    column: &mut [f64],
+) {
+    match distribution {
+        InputDistribution::Normal => {
+            for sample in column.iter_mut() {
+                *sample = sigma.mul_add(rng.sample(StandardNormal), value);
+            }
+        }
+        InputDistribution::Uniform => {
+            let half_width = 3.0_f64.sqrt() * sigma;
+            for sample in column.iter_mut() {
+                *sample = half_width.mul_add(2.0_f64.mul_add(unit_interval(rng), -1.0), value);
+            }
+        }
+    }
+}
+
+/// Uniform draw in [0, 1).
+fn unit_interval(rng: &mut Pcg64) -> f64 {
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "53 random bits are exactly representable in f64"
+    )]
+    let mantissa = (rng.next_u64() >> 11) as f64;
+    mantissa * f64::EPSILON / 2.0
+}
+
+/// Moments and central quantile interval of the finite outputs.
+fn summarize(outputs: &[f64], coverage: f64) -> Option<MonteCarloRow> {
+    let mut finite: Vec<f64> = outputs.iter().copied().filter(|y| y.is_finite()).collect();
+    if finite.len() < MIN_MC_SAMPLES {
+        return None;
+    }
+    finite.sort_by(f64::total_cmp);
+    let tail = (1.0 - coverage) / 2.0;
+    Some(MonteCarloRow {
+        mean: mean(&finite)?,
+        std_dev: sample_variance(&finite)?.sqrt(),
+        ci_lower: quantile_sorted(&finite, tail)?,
+        ci_upper: quantile_sorted(&finite, 1.0 - tail)?,
+        rejected_samples: outputs.len() - finite.len(),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    fn variable(
+        name: &str,
+        columns: [&str; 2],
+        values: Vec<f64>,
+        uncertainties: Vec<f64>,
+    ) -> Variable {
+        let rows = values.len();
+        Variable {
+            name: name.to_owned(),
+            value_range: format!("{0}1:{0}{rows}", columns[0]),
+            uncertainty_range: format!("{0}1:{0}{rows}", columns[1]),
+            confidence: 68.268_949_213_708_58,
+            values,
+            uncertainties,
+            distribution: InputDistribution::Normal,
+            correlations: None,
+        }
+    }
+
+    fn rows_uncertainty(formula: &str) -> f64 {
+        formula.trim_start_matches('=').parse().unwrap()
+    }
+
+    #[test]
+    fn test_linear_formula_matches_taylor() {
+        let variables = vec![
+            variable("a", ["A", "B"], vec![1.0, 5.0], vec![0.3, 0.3]),
+            variable("b", ["C", "D"], vec![2.0, 2.0], vec![0.4, 0.4]),
+        ];
+        let result = propagate(&variables, "a + b", 68.268_949_213_708_58, 200_000, 3).unwrap();
+        assert!(result.success);
+        assert!(result.value_formulas[1].contains("A2") && result.value_formulas[1].contains("C2"));
+        assert!((rows_uncertainty(&result.uncertainty_formulas[0]) - 0.5).abs() < 0.005);
+        let rows = result.monte_carlo.unwrap();
+        assert!((rows[0].mean - 3.0).abs() < 0.01);
+        assert!((rows[0].std_dev - 0.5).abs() < 0.005);
+        assert_eq!(rows[0].rejected_samples, 0);
+    }
+
+    #[test]
+    fn test_nonlinear_formula_exceeds_linearization() {
+        // Taylor gives zero uncertainty for x^2 at x = 0; the true spread of a
+        // squared standard normal is sqrt(2), with a one-sided interval.
+        let variables = vec![variable("x", ["A", "B"], vec![0.0], vec![1.0])];
+        let result = propagate(&variables, "x^2", 95.0, 200_000, 5).unwrap();
+        let row = &result.monte_carlo.unwrap()[0];
+        assert!((row.mean - 1.0).abs() < 0.02);
+        assert!((row.std_dev - 2.0_f64.sqrt()).abs() < 0.03);
+        assert!(row.ci_lower >= 0.0 && row.ci_lower < 0.01);
+        assert!((row.ci_upper - 5.024).abs() < 0.1);
+
+        let repeat = propagate(&variables, "x^2", 95.0, 200_000, 5).unwrap();
+        assert_eq!(result.uncertainty_formulas, repeat.uncertainty_formulas);
+    }
+
+    #[test]
+    fn test_uniform_inputs_and_validation() {
+        let mut uniform = variable("x", ["A", "B"], vec![10.0], vec![2.0]);
+        uniform.distribution = InputDistribution::Uniform;
+        let result = propagate(&[uniform], "x", 95.0, 100_000, 1).unwrap();
+        let row = &result.monte_carlo.unwrap()[0];
+        let half_width = 2.0 * 3.0_f64.sqrt();
+        assert!(row.ci_lower > 10.0 - half_width && row.ci_upper < 10.0 + half_width);
+        assert!((row.std_dev - 2.0).abs() < 0.02);
+
+        let mut missing = variable("x", ["A", "B"], vec![1.0], vec![0.1]);
+        missing.value_range = "A1:A2".to_owned();
+        assert!(propagate(&[missing], "x", 95.0, 1000, 1).is_err());
+        let ok = variable("x", ["A", "B"], vec![1.0], vec![0.1]);
+        assert!(propagate(&[ok], "x", 95.0, 10, 1).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 // ============================================================
 // SPREADSHEET TYPES (for Excel formula generation)
@@ -15,19 +16,77 @@ pub struct Variable {
     pub uncertainty_range: String, // e.g., "B1:B10"
     /// The confidence level of the input uncertainties in percent (e.g., 95.0).
     pub confidence: f64, // confidence level in percent (e.g., 95.0)
+    /// Cell values of `value_range`, required for Monte Carlo propagation.
+    #[serde(default)]
+    pub values: Vec<f64>,
+    /// Cell values of `uncertainty_range`, required for Monte Carlo propagation.
+    #[serde(default)]
+    pub uncertainties: Vec<f64>,
+    /// Input distribution for Monte Carlo sampling (normal by default).
+    #[serde(default)]
+    pub distribution: InputDistribution,
+    /// Correlation coefficients with other variables, keyed by their names.
+    #[serde(default)]
+    pub correlations: Option<HashMap<String, f64>>,
+}
+
+/// Distribution from which Monte Carlo input samples are drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputDistribution {
+    /// Gaussian with the variable's standard uncertainty.
+    #[default]
+    Normal,
+    /// Rectangular with the same standard deviation (half-width `sqrt(3)` sigma).
+    Uniform,
+}
+
+/// Target language of generated formulas.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum FormulaOutputFormat {
+    /// One spreadsheet formula per row, referencing the variables' cells.
+    #[default]
+    Excel,
+    /// A single `numpy` lambda each for the value and the uncertainty.
+    Python {
+        /// Python parameter names, aligned with the variables.
+        variable_names: Vec<String>,
+    },
+    /// LaTeX equations for the value and the propagated uncertainty.
+    #[serde(rename = "latex")]
+    LaTeX,
+}
+
+/// Empirical output distribution of one row in Monte Carlo propagation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonteCarloRow {
+    /// Mean of the sampled outputs.
+    pub mean: f64,
+    /// Sample standard deviation of the outputs.
+    pub std_dev: f64,
+    /// Lower quantile bound at the output confidence level.
+    pub ci_lower: f64,
+    /// Upper quantile bound at the output confidence level.
+    pub ci_upper: f64,
+    /// Samples discarded because the formula evaluated to a non-finite value.
+    pub rejected_samples: usize,
 }
 
 /// Result of uncertainty formula generation
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UncertaintyFormulas {
-    /// Excel formulas for calculating the values.
+    /// Formulas for calculating the values (Excel per row, or one Python or LaTeX formula).
     pub value_formulas: Vec<String>, // Excel formulas for calculated values
-    /// Excel formulas for calculating the propagated uncertainties.
+    /// Formulas for calculating the propagated uncertainties.
     pub uncertainty_formulas: Vec<String>, // Excel formulas for propagated uncertainties
     /// Whether the generation was successful.
     pub success: bool,
     /// Optional error message if generation failed.
     pub error: Option<String>,
+    /// Per-row output statistics when the uncertainties come from Monte Carlo sampling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monte_carlo: Option<Vec<MonteCarloRow>>,
 }
 
 /// Represents a parsed Excel range (e.g., "A1:A10")
@@ -0,0 +1,281 @@
+//! Opening files passed on the command line or forwarded by a second launch.
+//!
+//! File-open requests are queued until the frontend invokes `frontend_ready`;
+//! after that they are emitted as events as soon as they arrive.
+
+use serde::Serialize;
+use std::env::args;
+use std::path::{Path, PathBuf, absolute};
+use std::sync::{Mutex, PoisonError};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tracing::warn;
+
+use crate::import::validate_and_canonicalize_path;
+use crate::utils::log_info;
+
+/// Event carrying a [`FileOpenRequest`] for the frontend to import.
+pub const OPEN_FILE_EVENT: &str = "anafis://open-file";
+/// Event carrying a [`FileOpenError`] for a file that could not be opened.
+pub const FILE_OPEN_ERROR_EVENT: &str = "anafis://file-open-error";
+
+/// Extensions accepted from the command line; each is also its import format.
+const SUPPORTED_EXTENSIONS: [&str; 4] = ["anafispread", "csv", "tsv", "parquet"];
+
+/// A file the frontend should open.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOpenRequest {
+    pub path: String,
+    /// `anafispread`, or a format accepted by `import_spreadsheet_file`.
+    pub format: String,
+}
+
+/// A file from the command line that failed validation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOpenError {
+    pub path: String,
+    pub message: String,
+}
+
+/// Outcome of a file-open request, waiting to be sent to the frontend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileOpenItem {
+    Open(FileOpenRequest),
+    Failed(FileOpenError),
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+    frontend_ready: bool,
+    pending: Vec<FileOpenItem>,
+}
+
+/// Holds file-open requests until the frontend can receive them.
+#[derive(Debug, Default)]
+pub struct FileOpenQueue(Mutex<QueueState>);
+
+impl FileOpenQueue {
+    /// Queue `items` if the frontend is not ready yet; returns the items to
+    /// emit right away.
+    pub fn submit(&self, items: Vec<FileOpenItem>) -> Vec<FileOpenItem> {
+        let mut state = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        if !state.frontend_ready {
+            state.pending.extend(items);
+            return Vec::new();
+        }
+        drop(state);
+        items
+    }
+
+    /// Mark the frontend ready and take everything queued so far.
+    pub fn mark_ready(&self) -> Vec<FileOpenItem> {
+        let mut state = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        state.frontend_ready = true;
+        std::mem::take(&mut state.pending)
+    }
+}
+
+/// Arguments after the program name, with paths made absolute.
+pub fn command_line_arguments() -> Vec<String> {
+    args()
+        .skip(1)
+        .map(|arg| {
+            if arg.starts_with('-') {
+                arg
+            } else {
+                absolute(&arg).map_or(arg, |path| path.to_string_lossy().into_owned())
+            }
+        })
+        .collect()
+}
+
+/// Arguments of a second launch started in `cwd`, without the program name
+/// and with relative paths resolved against `cwd`.
+pub fn forwarded_arguments(argv: Vec<String>, cwd: &str) -> Vec<String> {
+    argv.into_iter()
+        .skip(1)
+        .map(|arg| {
+            if arg.starts_with('-') || Path::new(&arg).is_absolute() {
+                arg
+            } else {
+                PathBuf::from(cwd).join(arg).to_string_lossy().into_owned()
+            }
+        })
+        .collect()
+}
+
+/// Pick the openable files out of command-line arguments (without the
+/// program name), in order and without duplicates.
+pub fn parse_file_arguments<I: IntoIterator<Item = String>>(args: I) -> Vec<FileOpenRequest> {
+    let mut requests: Vec<FileOpenRequest> = Vec::new();
+    for arg in args {
+        if arg.starts_with('-') {
+            continue;
+        }
+        // Windows accepts both separators; normalise so duplicates compare equal
+        #[cfg(windows)]
+        let path = arg.replace('\\', "/");
+        #[cfg(not(windows))]
+        let path = arg;
+        let Some(format) = Path::new(&path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase)
+            .filter(|extension| SUPPORTED_EXTENSIONS.contains(&extension.as_str()))
+        else {
+            continue;
+        };
+        if requests.iter().all(|request| request.path != path) {
+            requests.push(FileOpenRequest { path, format });
+        }
+    }
+    requests
+}
+
+/// Validate the files named in `args` and send them to the frontend, or
+/// queue them until it is ready.
+pub fn open_files<I: IntoIterator<Item = String>>(app: &AppHandle, args: I) {
+    let items: Vec<FileOpenItem> = parse_file_arguments(args)
+        .into_iter()
+        .map(
+            |request| match validate_and_canonicalize_path(&request.path) {
+                Ok(_) => {
+                    log_info(&format!("Opening file from association: {}", request.path));
+                    FileOpenItem::Open(request)
+                }
+                Err(message) => FileOpenItem::Failed(FileOpenError {
+                    path: request.path,
+                    message,
+                }),
+            },
+        )
+        .collect();
+    if items.is_empty() {
+        return;
+    }
+    if let Some(queue) = app.try_state::<FileOpenQueue>() {
+        emit_items(app, queue.submit(items));
+    }
+}
+
+/// Bring the main window to the front, e.g. when a second instance forwards
+/// files to this one.
+pub fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        drop(window.unminimize());
+        drop(window.show());
+        drop(window.set_focus());
+    }
+}
+
+fn emit_items(app: &AppHandle, items: Vec<FileOpenItem>) {
+    for item in items {
+        let emitted = match &item {
+            FileOpenItem::Open(request) => app.emit(OPEN_FILE_EVENT, request),
+            FileOpenItem::Failed(error) => {
+                warn!("Cannot open {}: {}", error.path, error.message);
+                app.emit(FILE_OPEN_ERROR_EVENT, error)
+            }
+        };
+        if let Err(e) = emitted {
+            warn!("Failed to emit file-open event: {e}");
+        }
+    }
+}
+
+/// Called by the frontend once its file-open listeners are registered;
+/// emits every request queued since startup.
+#[tauri::command]
+#[allow(
+    clippy::needless_pass_by_value,
+    reason = "Tauri commands require owned State"
+)]
+pub fn frontend_ready(app: AppHandle, queue: State<'_, FileOpenQueue>) {
+    emit_items(&app, queue.mark_ready());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(path: &str, format: &str) -> FileOpenItem {
+        FileOpenItem::Open(FileOpenRequest {
+            path: path.to_owned(),
+            format: format.to_owned(),
+        })
+    }
+
+    #[test]
+    fn test_parse_accepts_multiple_supported_files() {
+        let args = [
+            "--flag",
+            "/data/run.CSV",
+            "/tmp/notes.txt",
+            "/tmp/sheet.anafispread",
+            "/tmp/table.parquet",
+            "/tmp/sheet.anafispread",
+            "/tmp/log.tsv",
+        ]
+        .map(str::to_owned);
+        let requests = parse_file_arguments(args);
+        let summary: Vec<(&str, &str)> = requests
+            .iter()
+            .map(|request| (request.path.as_str(), request.format.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("/data/run.CSV", "csv"),
+                ("/tmp/sheet.anafispread", "anafispread"),
+                ("/tmp/table.parquet", "parquet"),
+                ("/tmp/log.tsv", "tsv"),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_parse_keeps_backslashes_in_unix_file_names() {
+        let requests = parse_file_arguments(["/tmp/run\\1.csv".to_owned()]);
+        assert_eq!(requests[0].path, "/tmp/run\\1.csv");
+    }
+
+    #[test]
+    fn test_forwarded_arguments_resolve_against_launch_directory() {
+        let argv =
+            ["anafis", "--flag", "data/run.csv", "/tmp/sheet.anafispread"].map(str::to_owned);
+        let launch_dir = std::env::temp_dir();
+        let forwarded = forwarded_arguments(argv.to_vec(), &launch_dir.to_string_lossy());
+        assert_eq!(
+            forwarded,
+            [
+                "--flag".to_owned(),
+                launch_dir
+                    .join("data/run.csv")
+                    .to_string_lossy()
+                    .into_owned(),
+                "/tmp/sheet.anafispread".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_queue_holds_requests_until_frontend_is_ready() {
+        let queue = FileOpenQueue::default();
+        assert!(queue.submit(vec![open("/a.csv", "csv")]).is_empty());
+        let failed = FileOpenItem::Failed(FileOpenError {
+            path: "/missing.tsv".to_owned(),
+            message: "File not found".to_owned(),
+        });
+        assert!(queue.submit(vec![failed.clone()]).is_empty());
+
+        assert_eq!(queue.mark_ready(), vec![open("/a.csv", "csv"), failed]);
+        assert!(queue.mark_ready().is_empty());
+
+        // Once ready, new requests pass straight through.
+        let late = vec![open("/b.parquet", "parquet")];
+        assert_eq!(queue.submit(late.clone()), late);
+        assert!(queue.mark_ready().is_empty());
+    }
+}
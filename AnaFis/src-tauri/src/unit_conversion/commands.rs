@@ -1,18 +1,33 @@
 // src-tauri/src/unit_conversion/commands.rs
 
 use crate::error::{CommandResult, conversion_error, internal_error, validation_error};
+use crate::scientific::curve_fitting::{DimensionConflict, verify_dimensions};
+use crate::unit_conversion::constants::{PHYSICAL_CONSTANTS, PhysicalConstant, physical_constant};
 use crate::unit_conversion::core::{
-    ConversionPreview, ConversionRequest, ConversionResult, Dimension, UNIT_CONVERTER,
+    ColumnConversionRequest, ColumnConversionResult, ConversionPreview, ConversionRequest,
+    ConversionResult, CustomUnit, Dimension, QuantityKind, UNIT_CONVERTER, UnitCompatibility,
     UnitConverter, UnitInfo,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
 use std::string::ToString;
-use tauri::command;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, command};
+
+/// File in the app data dir holding the user's custom units
+const CUSTOM_UNITS_FILE: &str = "custom_units.json";
+
+/// Held from taking a snapshot of the custom units until it is written, so an
+/// older snapshot never overwrites a newer one
+static CUSTOM_UNITS_SAVE: Mutex<()> = Mutex::new(());
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DimensionalAnalysisResult {
     pub unit_formula: String,
+    pub expanded_formula: String,
     pub dimensional_formula: String,
     pub si_factor: f64,
     pub is_valid: bool,
@@ -30,6 +45,16 @@ pub struct CompatibilityAnalysisResult {
     pub analysis_details: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct DimensionVerificationResult {
+    pub formula: String,
+    /// Dimension of the formula, absent when its terms disagree
+    pub output_dimension: Option<String>,
+    pub expected_dimension: Option<String>,
+    pub is_consistent: bool,
+    pub conflicts: Vec<DimensionConflict>,
+}
+
 fn with_converter<T>(operation: impl FnOnce(&UnitConverter) -> T) -> CommandResult<T> {
     let converter = UNIT_CONVERTER
         .lock()
@@ -71,8 +96,11 @@ pub async fn get_conversion_preview(
 }
 
 #[command]
-pub async fn check_unit_compatibility(from_unit: String, to_unit: String) -> CommandResult<bool> {
-    with_converter(|converter| converter.check_unit_compatibility(&from_unit, &to_unit))
+pub async fn check_unit_compatibility(
+    from_unit: String,
+    to_unit: String,
+) -> CommandResult<UnitCompatibility> {
+    with_converter(|converter| converter.unit_compatibility(&from_unit, &to_unit))
 }
 
 #[command]
@@ -80,6 +108,57 @@ pub async fn get_available_units() -> CommandResult<HashMap<String, UnitInfo>> {
     with_converter(UnitConverter::get_available_units)
 }
 
+/// Convert a whole column, and its uncertainties, with a single lookup of the
+/// conversion; the converter lock is released before the column is processed
+#[command]
+pub async fn convert_column(
+    request: ColumnConversionRequest,
+) -> CommandResult<ColumnConversionResult> {
+    let map = with_converter_result(|converter| {
+        converter.conversion_map(&request.from_unit, &request.to_unit)
+    })?;
+    map.convert_column(&request)
+        .map_err(|e| validation_error(e, Some("uncertainties".to_owned())))
+}
+
+/// Convert a plain array of values; offsets such as °C to °F are applied
+#[command]
+pub async fn batch_convert_values(
+    values: Vec<f64>,
+    from_unit: String,
+    to_unit: String,
+) -> CommandResult<Vec<f64>> {
+    convert_column(ColumnConversionRequest {
+        values,
+        uncertainties: None,
+        from_unit,
+        to_unit,
+        is_uncertainty_column: false,
+        quantity_kind: QuantityKind::Absolute,
+    })
+    .await
+    .map(|result| result.values)
+}
+
+/// Convert an array of uncertainties; only the scale factor applies, never the offset
+#[command]
+pub async fn batch_convert_uncertainties(
+    uncertainties: Vec<f64>,
+    from_unit: String,
+    to_unit: String,
+) -> CommandResult<Vec<f64>> {
+    convert_column(ColumnConversionRequest {
+        values: uncertainties,
+        uncertainties: None,
+        from_unit,
+        to_unit,
+        is_uncertainty_column: true,
+        quantity_kind: QuantityKind::Difference,
+    })
+    .await
+    .map(|result| result.values)
+}
+
 // ===== QUICK CONVERSION FOR MENU BUTTONS =====
 
 #[command]
@@ -87,8 +166,16 @@ pub async fn quick_convert_value(
     value: f64,
     from_unit: String,
     to_unit: String,
+    quantity_kind: Option<QuantityKind>,
 ) -> CommandResult<Option<f64>> {
-    with_converter(|converter| converter.quick_convert(value, &from_unit, &to_unit))
+    with_converter(|converter| {
+        converter.quick_convert_quantity(
+            value,
+            &from_unit,
+            &to_unit,
+            quantity_kind.unwrap_or_default(),
+        )
+    })
 }
 
 #[command]
@@ -97,6 +184,7 @@ pub async fn get_conversion_factor(from_unit: String, to_unit: String) -> Comman
         value: 1.0,
         from_unit,
         to_unit,
+        quantity_kind: QuantityKind::Absolute,
     };
 
     with_converter_result(|converter| {
@@ -113,6 +201,7 @@ pub async fn parse_unit_formula(unit_formula: String) -> Result<DimensionalAnaly
     match with_converter_string_result(|converter| converter.parse_unit(&unit_formula)) {
         Ok(parsed) => Ok(DimensionalAnalysisResult {
             unit_formula,
+            expanded_formula: parsed.expanded,
             dimensional_formula: format_dimension(&parsed.dimension),
             si_factor: parsed.si_factor,
             is_valid: true,
@@ -120,6 +209,7 @@ pub async fn parse_unit_formula(unit_formula: String) -> Result<DimensionalAnaly
         }),
         Err(e) => Ok(DimensionalAnalysisResult {
             unit_formula,
+            expanded_formula: String::new(),
             dimensional_formula: String::new(),
             si_factor: 0.0,
             is_valid: false,
@@ -204,6 +294,60 @@ pub async fn get_unit_dimensional_formula(unit: String) -> CommandResult<String>
     }
 }
 
+/// Check that a formula is dimensionally consistent
+///
+/// Each variable is given a unit (`N`, `m/s^2`) or a bracketed dimensional
+/// formula (`[M·L]`); the result is compared with `expected_dimension`,
+/// written the same way, when one is supplied
+#[command]
+pub async fn verify_formula_dimensions(
+    formula: String,
+    variable_dimensions: HashMap<String, String>,
+    expected_dimension: Option<String>,
+) -> CommandResult<DimensionVerificationResult> {
+    let mut variables = variable_dimensions
+        .iter()
+        .map(|(name, text)| {
+            parse_dimension(text)
+                .map(|dimension| (name.clone(), dimension))
+                .map_err(|e| validation_error(format!("'{name}': {e}"), Some(name.clone())))
+        })
+        .collect::<CommandResult<Vec<_>>>()?;
+    variables.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let expected = expected_dimension
+        .as_deref()
+        .map(parse_dimension)
+        .transpose()
+        .map_err(|e| validation_error(e, Some("expected_dimension".to_owned())))?;
+
+    let verification = verify_dimensions(&formula, &variables, expected.as_ref())
+        .map_err(|e| validation_error(e.to_string(), Some("formula".to_owned())))?;
+    Ok(DimensionVerificationResult {
+        formula,
+        output_dimension: verification.dimension.as_ref().map(format_dimension),
+        expected_dimension: expected.as_ref().map(format_dimension),
+        is_consistent: verification.conflicts.is_empty(),
+        conflicts: verification.conflicts,
+    })
+}
+
+// ===== PHYSICAL CONSTANTS =====
+
+#[command]
+pub async fn get_physical_constant(symbol: String) -> CommandResult<PhysicalConstant> {
+    physical_constant(symbol.trim()).copied().ok_or_else(|| {
+        validation_error(
+            format!("Unknown physical constant: {symbol}"),
+            Some("symbol".to_owned()),
+        )
+    })
+}
+
+#[command]
+pub async fn get_all_physical_constants() -> CommandResult<Vec<PhysicalConstant>> {
+    Ok(PHYSICAL_CONSTANTS.to_vec())
+}
+
 // ===== UTILITY COMMANDS =====
 
 #[command]
@@ -220,7 +364,7 @@ pub async fn validate_unit_string(unit: String) -> CommandResult<bool> {
 
 #[command]
 pub async fn get_supported_categories() -> CommandResult<Vec<String>> {
-    const SUPPORTED_CATEGORIES: [&str; 12] = [
+    const SUPPORTED_CATEGORIES: [&str; 13] = [
         "length",
         "mass",
         "time",
@@ -232,6 +376,7 @@ pub async fn get_supported_categories() -> CommandResult<Vec<String>> {
         "frequency",
         "current",
         "temperature",
+        "custom",
         "other",
     ];
     Ok(SUPPORTED_CATEGORIES
@@ -240,9 +385,126 @@ pub async fn get_supported_categories() -> CommandResult<Vec<String>> {
         .collect())
 }
 
+// ===== CUSTOM UNITS =====
+
+/// Register a user-defined unit, `value_si = value * si_factor + si_offset`,
+/// and save it for later sessions
+#[command]
+pub async fn register_custom_unit(
+    app: AppHandle,
+    symbol: String,
+    dimension_formula: String,
+    si_factor: f64,
+    si_offset: f64,
+) -> CommandResult<()> {
+    let path = custom_units_path(&app)?;
+    let _save = CUSTOM_UNITS_SAVE
+        .lock()
+        .map_err(|e| internal_error(format!("Failed to lock custom units file: {e}")))?;
+    let json = {
+        let mut converter = UNIT_CONVERTER
+            .lock()
+            .map_err(|e| internal_error(format!("Failed to lock converter: {e}")))?;
+        converter
+            .register_custom_unit(CustomUnit {
+                symbol: symbol.clone(),
+                dimension_formula,
+                si_factor,
+                si_offset,
+            })
+            .map_err(|e| validation_error(e, None))?;
+        converter.custom_units_json().map_err(|e| {
+            drop(converter.unregister_custom_unit(&symbol));
+            internal_error(e)
+        })?
+    };
+    // Written without holding the converter lock; undone if the write fails
+    save_custom_units(&path, &json).inspect_err(|_| {
+        if let Ok(mut converter) = UNIT_CONVERTER.lock() {
+            drop(converter.unregister_custom_unit(&symbol));
+        }
+    })
+}
+
+/// Remove a user-defined unit and save the remaining ones
+#[command]
+pub async fn unregister_custom_unit(app: AppHandle, symbol: String) -> CommandResult<()> {
+    let path = custom_units_path(&app)?;
+    let _save = CUSTOM_UNITS_SAVE
+        .lock()
+        .map_err(|e| internal_error(format!("Failed to lock custom units file: {e}")))?;
+    let (unit, json) = {
+        let mut converter = UNIT_CONVERTER
+            .lock()
+            .map_err(|e| internal_error(format!("Failed to lock converter: {e}")))?;
+        let unit = converter
+            .unregister_custom_unit(&symbol)
+            .map_err(|e| validation_error(e, Some("symbol".to_owned())))?;
+        match converter.custom_units_json() {
+            Ok(json) => {
+                drop(converter);
+                (unit, json)
+            }
+            Err(e) => {
+                drop(converter.register_custom_unit(unit));
+                drop(converter);
+                return Err(internal_error(e));
+            }
+        }
+    };
+    save_custom_units(&path, &json).inspect_err(|_| {
+        if let Ok(mut converter) = UNIT_CONVERTER.lock() {
+            drop(converter.register_custom_unit(unit));
+        }
+    })
+}
+
+/// Register the custom units saved in the app data dir; returns how many
+/// were loaded, and a missing file loads none
+pub fn load_custom_units(app: &AppHandle) -> Result<usize, String> {
+    let path = custom_units_path(app).map_err(|e| e.to_string())?;
+    let json = match read_to_string(&path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(format!("Failed to read {}: {e}", path.display())),
+    };
+    let mut converter = UNIT_CONVERTER
+        .lock()
+        .map_err(|e| format!("Failed to lock converter: {e}"))?;
+    converter.load_custom_units_json(&json)
+}
+
+fn custom_units_path(app: &AppHandle) -> CommandResult<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(CUSTOM_UNITS_FILE))
+        .map_err(|e| internal_error(format!("Failed to get app data dir: {e}")))
+}
+
+fn save_custom_units(path: &Path, json: &str) -> CommandResult<()> {
+    if let Some(dir) = path.parent() {
+        create_dir_all(dir)
+            .map_err(|e| internal_error(format!("Failed to create app data dir: {e}")))?;
+    }
+    write(path, json).map_err(|e| internal_error(format!("Failed to save custom units: {e}")))
+}
+
 // ===== HELPER FUNCTIONS =====
 
-fn format_dimension(dim: &Dimension) -> String {
+/// Dimension of a unit, or of a bracketed dimensional formula such as `[M·L]`
+fn parse_dimension(text: &str) -> Result<Dimension, String> {
+    let text = text.trim();
+    if text.starts_with('[') {
+        return Dimension::parse(text);
+    }
+    let converter = UNIT_CONVERTER
+        .lock()
+        .map_err(|e| format!("Failed to lock converter: {e}"))?;
+    converter.parse_unit(text).map(|parsed| parsed.dimension)
+}
+
+/// Dimensional formula such as `[M·L^2·T^-3]`, or `[dimensionless]`
+pub fn format_dimension(dim: &Dimension) -> String {
     let mut parts = Vec::new();
 
     if dim.mass != 0 {
@@ -0,0 +1,252 @@
+// src-tauri/src/unit_conversion/constants.rs
+
+use serde::Serialize;
+
+const CODATA_2018: &str = "CODATA 2018";
+
+/// A physical constant with its standard uncertainty; exact constants of the
+/// 2019 SI have zero uncertainty
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct PhysicalConstant {
+    pub symbol: &'static str,
+    /// Other spellings accepted on lookup, e.g. `ε_0` for `epsilon_0`
+    pub aliases: &'static [&'static str],
+    pub name: &'static str,
+    pub value: f64,
+    pub standard_uncertainty: f64,
+    pub relative_standard_uncertainty: f64,
+    /// Unit in a form accepted by the unit parser; empty when dimensionless
+    pub unit: &'static str,
+    pub reference: &'static str,
+}
+
+impl PhysicalConstant {
+    const fn codata(
+        symbol: &'static str,
+        aliases: &'static [&'static str],
+        name: &'static str,
+        value: f64,
+        standard_uncertainty: f64,
+        unit: &'static str,
+    ) -> Self {
+        Self {
+            symbol,
+            aliases,
+            name,
+            value,
+            standard_uncertainty,
+            relative_standard_uncertainty: standard_uncertainty / value,
+            unit,
+            reference: CODATA_2018,
+        }
+    }
+}
+
+/// Constants in the order they are listed to the user
+pub static PHYSICAL_CONSTANTS: [PhysicalConstant; 20] = [
+    PhysicalConstant::codata(
+        "c",
+        &["c_0"],
+        "speed of light in vacuum",
+        299_792_458.0,
+        0.0,
+        "m/s",
+    ),
+    PhysicalConstant::codata(
+        "h",
+        &[],
+        "Planck constant",
+        6.626_070_15e-34,
+        0.0,
+        "J\u{b7}s",
+    ),
+    PhysicalConstant::codata(
+        "hbar",
+        &["\u{127}"],
+        "reduced Planck constant",
+        1.054_571_817e-34,
+        0.0,
+        "J\u{b7}s",
+    ),
+    PhysicalConstant::codata(
+        "G",
+        &[],
+        "Newtonian constant of gravitation",
+        6.674_30e-11,
+        0.000_15e-11,
+        "m^3/(kg\u{b7}s^2)",
+    ),
+    PhysicalConstant::codata(
+        "g_n",
+        &[],
+        "standard acceleration of gravity",
+        9.806_65,
+        0.0,
+        "m/s^2",
+    ),
+    PhysicalConstant::codata(
+        "k_B",
+        &["k"],
+        "Boltzmann constant",
+        1.380_649e-23,
+        0.0,
+        "J/K",
+    ),
+    PhysicalConstant::codata("e", &[], "elementary charge", 1.602_176_634e-19, 0.0, "C"),
+    PhysicalConstant::codata(
+        "m_e",
+        &[],
+        "electron mass",
+        9.109_383_701_5e-31,
+        0.000_000_002_8e-31,
+        "kg",
+    ),
+    PhysicalConstant::codata(
+        "m_p",
+        &[],
+        "proton mass",
+        1.672_621_923_69e-27,
+        0.000_000_000_51e-27,
+        "kg",
+    ),
+    PhysicalConstant::codata(
+        "m_n",
+        &[],
+        "neutron mass",
+        1.674_927_498_04e-27,
+        0.000_000_000_95e-27,
+        "kg",
+    ),
+    PhysicalConstant::codata(
+        "m_u",
+        &["u"],
+        "atomic mass constant",
+        1.660_539_066_60e-27,
+        0.000_000_000_50e-27,
+        "kg",
+    ),
+    PhysicalConstant::codata(
+        "N_A",
+        &[],
+        "Avogadro constant",
+        6.022_140_76e23,
+        0.0,
+        "mol^-1",
+    ),
+    PhysicalConstant::codata(
+        "R",
+        &[],
+        "molar gas constant",
+        8.314_462_618,
+        0.0,
+        "J/(mol\u{b7}K)",
+    ),
+    PhysicalConstant::codata("F", &[], "Faraday constant", 96_485.332_12, 0.0, "C/mol"),
+    PhysicalConstant::codata(
+        "epsilon_0",
+        &["\u{3b5}_0", "\u{3b5}\u{2080}"],
+        "vacuum electric permittivity",
+        8.854_187_812_8e-12,
+        0.000_000_001_3e-12,
+        "F/m",
+    ),
+    PhysicalConstant::codata(
+        "mu_0",
+        &["\u{3bc}_0", "\u{3bc}\u{2080}"],
+        "vacuum magnetic permeability",
+        1.256_637_062_12e-6,
+        0.000_000_000_19e-6,
+        "N/A^2",
+    ),
+    PhysicalConstant::codata(
+        "alpha",
+        &["\u{3b1}"],
+        "fine-structure constant",
+        7.297_352_569_3e-3,
+        0.000_000_001_1e-3,
+        "",
+    ),
+    PhysicalConstant::codata(
+        "sigma",
+        &["\u{3c3}"],
+        "Stefan-Boltzmann constant",
+        5.670_374_419e-8,
+        0.0,
+        "W/(m^2\u{b7}K^4)",
+    ),
+    PhysicalConstant::codata(
+        "a_0",
+        &["a\u{2080}"],
+        "Bohr radius",
+        5.291_772_109_03e-11,
+        0.000_000_000_80e-11,
+        "m",
+    ),
+    PhysicalConstant::codata(
+        "R_inf",
+        &["R_\u{221e}"],
+        "Rydberg constant",
+        10_973_731.568_160,
+        0.000_021,
+        "m^-1",
+    ),
+];
+
+/// Look a constant up by its symbol or one of its aliases
+pub fn physical_constant(symbol: &str) -> Option<&'static PhysicalConstant> {
+    PHYSICAL_CONSTANTS
+        .iter()
+        .find(|constant| constant.symbol == symbol || constant.aliases.contains(&symbol))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use crate::unit_conversion::core::UnitConverter;
+    use approx::assert_relative_eq;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_constants_are_consistent() {
+        let converter = UnitConverter::new();
+        let mut names = HashSet::new();
+        for constant in &PHYSICAL_CONSTANTS {
+            for name in constant.aliases.iter().chain([&constant.symbol]) {
+                assert!(names.insert(*name), "{name} is listed twice");
+            }
+            if !constant.unit.is_empty() {
+                assert!(
+                    converter.parse_unit(constant.unit).is_ok(),
+                    "{}",
+                    constant.unit
+                );
+            }
+            assert!(constant.relative_standard_uncertainty < 1e-4);
+        }
+
+        // Derived exact constants agree with their definitions
+        let get = |symbol: &str| physical_constant(symbol).unwrap().value;
+        assert_relative_eq!(
+            get("hbar"),
+            get("h") / (2.0 * std::f64::consts::PI),
+            max_relative = 1e-9
+        );
+        assert_relative_eq!(get("R"), get("N_A") * get("k_B"), max_relative = 1e-9);
+        assert_relative_eq!(get("F"), get("N_A") * get("e"), max_relative = 1e-9);
+        assert_relative_eq!(
+            get("\u{3b5}\u{2080}") * get("mu_0") * get("c").powi(2),
+            1.0,
+            max_relative = 1e-9
+        );
+
+        let gravitation = physical_constant("G").unwrap();
+        assert_relative_eq!(
+            gravitation.relative_standard_uncertainty,
+            2.2e-5,
+            max_relative = 0.03
+        );
+        assert_eq!(gravitation.reference, "CODATA 2018");
+        assert!(physical_constant("g").is_none());
+    }
+}
@@ -6,11 +6,23 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{LazyLock, Mutex};
 
+/// Whether a value is a point on a unit's scale or a difference between two
+/// points; only absolute values are shifted by offsets such as °C to K
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum QuantityKind {
+    #[default]
+    Absolute,
+    Difference,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ConversionRequest {
     pub value: f64,
     pub from_unit: String,
     pub to_unit: String,
+    #[serde(default)]
+    pub quantity_kind: QuantityKind,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -33,6 +45,127 @@ pub struct ConversionPreview {
     pub preview_text: String,
     pub conversion_factor: f64,
     pub is_valid: bool,
+    /// The conversion shifts the zero point, so differences convert differently
+    pub is_affine: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UnitCompatibility {
+    pub compatible: bool,
+    pub is_affine: bool,
+    /// Explains how absolute values and differences convert for affine pairs
+    pub note: Option<String>,
+}
+
+/// A whole column of values, with optional absolute uncertainties, to convert in one call
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ColumnConversionRequest {
+    pub values: Vec<f64>,
+    /// Absolute uncertainties, one per value
+    #[serde(default)]
+    pub uncertainties: Option<Vec<f64>>,
+    pub from_unit: String,
+    pub to_unit: String,
+    /// `values` are themselves uncertainties (an uncertainty column converted on its own)
+    #[serde(default)]
+    pub is_uncertainty_column: bool,
+    #[serde(default)]
+    pub quantity_kind: QuantityKind,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ColumnConversionResult {
+    pub values: Vec<f64>,
+    pub uncertainties: Option<Vec<f64>>,
+    pub conversion_factor: f64,
+    /// Added after scaling; non-zero only for affine conversions such as °C to K
+    pub offset: f64,
+    /// Rows whose value or uncertainty was not finite; those entries are NaN
+    pub non_finite_indices: Vec<usize>,
+}
+
+/// Conversion `value * factor + offset` between two units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConversionMap {
+    pub factor: f64,
+    pub offset: f64,
+}
+
+impl ConversionMap {
+    /// Whether the conversion shifts the zero point (e.g. °C to K)
+    pub fn is_affine(&self) -> bool {
+        self.offset != 0.0
+    }
+
+    /// Convert a column with this map. Uncertainties are differences, so only
+    /// the factor applies to them.
+    pub fn convert_column(
+        &self,
+        request: &ColumnConversionRequest,
+    ) -> Result<ColumnConversionResult, String> {
+        let is_difference = request.quantity_kind == QuantityKind::Difference;
+        if request.is_uncertainty_column && !is_difference && self.is_affine() {
+            return Err(format!(
+                "{} to {} shifts the zero point by {}, which does not apply to uncertainties; \
+                 convert the uncertainties together with their values, or as differences",
+                request.from_unit, request.to_unit, self.offset
+            ));
+        }
+        if let Some(uncertainties) = &request.uncertainties
+            && uncertainties.len() != request.values.len()
+        {
+            return Err(format!(
+                "Expected {} uncertainties, got {}",
+                request.values.len(),
+                uncertainties.len()
+            ));
+        }
+
+        let offset = if request.is_uncertainty_column || is_difference {
+            0.0
+        } else {
+            self.offset
+        };
+        let mut non_finite_indices = Vec::new();
+        let values = request
+            .values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                if value.is_finite() {
+                    value.mul_add(self.factor, offset)
+                } else {
+                    non_finite_indices.push(index);
+                    f64::NAN
+                }
+            })
+            .collect();
+        let uncertainties = request.uncertainties.as_ref().map(|uncertainties| {
+            let scale = self.factor.abs();
+            uncertainties
+                .iter()
+                .enumerate()
+                .map(|(index, uncertainty)| {
+                    if uncertainty.is_finite() {
+                        uncertainty * scale
+                    } else {
+                        non_finite_indices.push(index);
+                        f64::NAN
+                    }
+                })
+                .collect()
+        });
+        non_finite_indices.sort_unstable();
+        non_finite_indices.dedup();
+
+        Ok(ColumnConversionResult {
+            values,
+            uncertainties,
+            conversion_factor: self.factor,
+            offset,
+            non_finite_indices,
+        })
+    }
 }
 
 /// Represents the dimensional formula of a unit in terms of SI base units
@@ -90,20 +223,102 @@ impl Dimension {
             luminosity: self.luminosity * exponent,
         }
     }
+
+    /// Parse a dimensional formula such as `[M\u{b7}L^2\u{b7}T^-2]`, `M L^2 / T^2`
+    /// or `[dimensionless]`; factors are separated by `\u{b7}`, `*` or spaces
+    pub fn parse(formula: &str) -> Result<Self, String> {
+        let inner = formula
+            .trim()
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .trim();
+        let mut dimension = Self::new();
+        if inner.is_empty() || inner == "1" || inner.eq_ignore_ascii_case("dimensionless") {
+            return Ok(dimension);
+        }
+
+        for (index, group) in inner.split('/').enumerate() {
+            if index > 1 {
+                return Err(format!("Only one '/' is allowed in '{formula}'"));
+            }
+            let sign = if index == 0 { 1 } else { -1 };
+            for factor in group
+                .split(['\u{b7}', '*', ' '])
+                .filter(|factor| !factor.is_empty())
+            {
+                let (symbol, exponent) = match factor.split_once('^') {
+                    Some((symbol, exponent)) => (
+                        symbol,
+                        exponent
+                            .parse::<i32>()
+                            .map_err(|e| format!("Invalid exponent in '{factor}': {e}"))?,
+                    ),
+                    None => (factor, 1),
+                };
+                let slot = match symbol {
+                    "M" => &mut dimension.mass,
+                    "L" => &mut dimension.length,
+                    "T" => &mut dimension.time,
+                    "I" => &mut dimension.current,
+                    "\u{398}" => &mut dimension.temperature,
+                    "N" => &mut dimension.amount,
+                    "J" => &mut dimension.luminosity,
+                    _ => {
+                        return Err(format!(
+                            "Unknown dimension '{symbol}'; use M, L, T, I, \u{398}, N or J"
+                        ));
+                    }
+                };
+                *slot += sign * exponent;
+            }
+        }
+        Ok(dimension)
+    }
+
+    /// Coherent SI unit of this dimension, e.g. `kg\u{b7}m^2\u{b7}s^-2`; empty
+    /// for dimensionless quantities
+    pub fn si_unit(&self) -> String {
+        [
+            ("kg", self.mass),
+            ("m", self.length),
+            ("s", self.time),
+            ("A", self.current),
+            ("K", self.temperature),
+            ("mol", self.amount),
+            ("cd", self.luminosity),
+        ]
+        .iter()
+        .filter(|(_, exponent)| *exponent != 0)
+        .map(|(symbol, exponent)| {
+            if *exponent == 1 {
+                (*symbol).to_owned()
+            } else {
+                format!("{symbol}^{exponent}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\u{b7}")
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct BaseUnit {
-    #[allow(
-        dead_code,
-        reason = "Symbol is currently only used for identification in code and storage"
-    )]
     pub symbol: String,
     pub name: String,
     pub dimension: Dimension,
     pub si_factor: f64, // Conversion factor to SI base unit
 }
 
+/// A unit registered by the user, stored as `value_si = value * si_factor + si_offset`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomUnit {
+    pub symbol: String,
+    pub dimension_formula: String,
+    pub si_factor: f64,
+    #[serde(default)]
+    pub si_offset: f64,
+}
+
 #[derive(Debug, Clone)]
 pub struct ParsedUnit {
     pub dimension: Dimension,
@@ -113,6 +328,8 @@ pub struct ParsedUnit {
         reason = "Original string is kept for debugging or re-parsing"
     )]
     pub original: String,
+    /// The formula with every prefixed unit spelled out, e.g. `(1e-3·s)` for `ms`
+    pub expanded: String,
 }
 
 pub struct UnitConverter {
@@ -120,6 +337,7 @@ pub struct UnitConverter {
     prefixes: HashMap<String, f64>,
     categories: HashMap<String, Vec<String>>,
     quick_conversions: HashMap<String, HashMap<String, f64>>, // For fast menu button conversions
+    custom_units: HashMap<String, CustomUnit>,
 }
 
 impl UnitConverter {
@@ -129,6 +347,7 @@ impl UnitConverter {
             prefixes: HashMap::new(),
             categories: HashMap::new(),
             quick_conversions: HashMap::new(),
+            custom_units: HashMap::new(),
         };
 
         converter.initialize_base_units();
@@ -208,6 +427,7 @@ impl UnitConverter {
                 dimension: base_unit.dimension.clone(),
                 si_factor: base_unit.si_factor,
                 original: unit_str.to_owned(),
+                expanded: unit_str.to_owned(),
             });
         }
 
@@ -219,15 +439,18 @@ impl UnitConverter {
             .replace("**", "\u{a7}\u{a7}") // Temporary placeholder for **
             .replace('*', " ")
             .replace("\u{b7}", " ")
+            .replace('\u{b5}', "\u{3bc}") // Micro sign as typed on most keyboards
             .replace("\u{a7}\u{a7}", "**") // Restore **
             .replace('/', " / ");
         let parts: Vec<&str> = normalized.split_whitespace().collect();
 
         let mut dividing = false;
+        let mut expanded = String::new();
 
         for part in parts {
             if part == "/" {
                 dividing = true;
+                expanded.push('/');
                 continue;
             }
 
@@ -267,25 +490,45 @@ impl UnitConverter {
             let actual_power = if dividing { -power } else { power };
 
             // Try to find the unit (with potential prefix)
-            if let Some((unit, factor)) = self.parse_unit_with_prefix(unit_part) {
-                total_dimension = total_dimension.multiply(&unit.dimension.power(actual_power));
-                total_factor *= factor.powi(actual_power);
-            } else {
+            let Some((unit, prefix_factor)) = self.parse_unit_with_prefix(unit_part) else {
                 return Err(format!("Unknown unit: {unit_part}"));
+            };
+            let factor = unit.si_factor * prefix_factor.unwrap_or(1.0);
+            total_dimension = total_dimension.multiply(&unit.dimension.power(actual_power));
+            total_factor *= factor.powi(actual_power);
+
+            let token = prefix_factor.map_or_else(
+                || part.to_owned(),
+                |prefix_factor| {
+                    format!(
+                        "({prefix_factor:e}\u{b7}{}){}",
+                        unit.symbol,
+                        power_part.unwrap_or_default()
+                    )
+                },
+            );
+            if !expanded.is_empty() && !expanded.ends_with('/') {
+                expanded.push('\u{b7}');
             }
+            expanded.push_str(&token);
         }
 
         Ok(ParsedUnit {
             dimension: total_dimension,
             si_factor: total_factor,
             original: unit_str.to_owned(),
+            expanded,
         })
     }
 
-    fn parse_unit_with_prefix(&self, unit_str: &str) -> Option<(&BaseUnit, f64)> {
+    /// Resolve one unit symbol to its unit and the factor of its SI prefix.
+    /// An exact match wins, so `min` is minutes rather than milli-inches;
+    /// otherwise the longest prefix followed by a known unit is taken, so
+    /// `ms` is milli-second. Units written with a degree sign take no prefix.
+    fn parse_unit_with_prefix(&self, unit_str: &str) -> Option<(&BaseUnit, Option<f64>)> {
         // First try exact match
         if let Some(unit) = self.base_units.get(unit_str) {
-            return Some((unit, unit.si_factor));
+            return Some((unit, None));
         }
 
         // Try with prefixes (longest first to avoid partial-prefix ambiguity, e.g. `da` vs `d`)
@@ -298,8 +541,10 @@ impl UnitConverter {
             if unit_str.starts_with(prefix) {
                 #[allow(clippy::string_slice, reason = "Safe boundary after starts_with check")]
                 let base_unit_str = &unit_str[prefix.len()..];
-                if let Some(base_unit) = self.base_units.get(base_unit_str) {
-                    return Some((base_unit, base_unit.si_factor * prefix_factor));
+                if let Some(base_unit) = self.base_units.get(base_unit_str)
+                    && !base_unit.symbol.starts_with('\u{b0}')
+                {
+                    return Some((base_unit, Some(*prefix_factor)));
                 }
             }
         }
@@ -355,8 +600,62 @@ impl UnitConverter {
         None
     }
 
+    /// `quick_convert` of an absolute value or of a difference; differences
+    /// skip the offset of affine pairs such as °C to K
+    pub fn quick_convert_quantity(
+        &self,
+        value: f64,
+        from: &str,
+        to: &str,
+        kind: QuantityKind,
+    ) -> Option<f64> {
+        if kind == QuantityKind::Difference {
+            let offset = self.quick_convert(0.0, from, to)?;
+            if offset != 0.0 {
+                return Some(value * (self.quick_convert(1.0, from, to)? - offset));
+            }
+        }
+        self.quick_convert(value, from, to)
+    }
+
     /// Advanced conversion using dimensional analysis
     pub fn convert(&self, request: &ConversionRequest) -> Result<ConversionResult, String> {
+        // Differences only take the factor of affine conversions
+        if request.quantity_kind == QuantityKind::Difference {
+            let map = self.conversion_map(&request.from_unit, &request.to_unit)?;
+            if map.is_affine() {
+                let value = request.value * map.factor;
+                return Ok(ConversionResult {
+                    value,
+                    formatted_result: Self::format_result(
+                        request.value,
+                        &request.from_unit,
+                        value,
+                        &request.to_unit,
+                    ),
+                    conversion_factor: map.factor,
+                });
+            }
+        }
+
+        // Custom units with an offset go through the coherent SI unit
+        if let Some(value) = self.convert_custom_offset(request)? {
+            return Ok(ConversionResult {
+                value,
+                formatted_result: Self::format_result(
+                    request.value,
+                    &request.from_unit,
+                    value,
+                    &request.to_unit,
+                ),
+                conversion_factor: if request.value == 0.0 {
+                    0.0
+                } else {
+                    value / request.value
+                },
+            });
+        }
+
         // Try quick conversion first for common cases
         if let Some(quick_result) =
             self.quick_convert(request.value, &request.from_unit, &request.to_unit)
@@ -405,12 +704,78 @@ impl UnitConverter {
         })
     }
 
+    /// Absolute conversion from or to a custom unit with an SI offset, made
+    /// through the coherent SI unit of its dimension; `None` when neither
+    /// unit is one
+    fn convert_custom_offset(&self, request: &ConversionRequest) -> Result<Option<f64>, String> {
+        let offset_unit = |symbol: &str| {
+            self.custom_units
+                .get(symbol)
+                .filter(|unit| unit.si_offset != 0.0)
+        };
+        let from = offset_unit(&request.from_unit);
+        let to = offset_unit(&request.to_unit);
+        if from.is_none() && to.is_none() {
+            return Ok(None);
+        }
+
+        let dimension = self.parse_unit(&request.from_unit)?.dimension;
+        if !dimension.is_compatible(&self.parse_unit(&request.to_unit)?.dimension) {
+            return Err(format!(
+                "Incompatible dimensions: {} and {} cannot be converted",
+                request.from_unit, request.to_unit
+            ));
+        }
+        let si_unit = dimension.si_unit();
+        let convert = |value: f64, from_unit: &str, to_unit: &str| {
+            self.convert(&ConversionRequest {
+                value,
+                from_unit: from_unit.to_owned(),
+                to_unit: to_unit.to_owned(),
+                quantity_kind: QuantityKind::Absolute,
+            })
+            .map(|result| result.value)
+        };
+
+        let si_value = match from {
+            Some(unit) => request.value.mul_add(unit.si_factor, unit.si_offset),
+            None => convert(request.value, &request.from_unit, &si_unit)?,
+        };
+        let value = match to {
+            Some(unit) => (si_value - unit.si_offset) / unit.si_factor,
+            None => convert(si_value, &si_unit, &request.to_unit)?,
+        };
+        Ok(Some(value))
+    }
+
+    /// Factor and offset of the conversion between two units, found by
+    /// converting 0 and 1 so that temperature scales keep their offsets
+    pub fn conversion_map(&self, from_unit: &str, to_unit: &str) -> Result<ConversionMap, String> {
+        let convert = |value: f64| {
+            self.convert(&ConversionRequest {
+                value,
+                from_unit: from_unit.to_owned(),
+                to_unit: to_unit.to_owned(),
+                quantity_kind: QuantityKind::Absolute,
+            })
+            .map(|result| result.value)
+        };
+        let offset = convert(0.0)?;
+        Ok(ConversionMap {
+            factor: convert(1.0)? - offset,
+            offset,
+        })
+    }
+
+    /// Preview of converting 1 unit; affine pairs show both the absolute
+    /// value and the difference
     pub fn get_conversion_preview(&self, from_unit: &str, to_unit: &str) -> ConversionPreview {
         if from_unit == to_unit {
             return ConversionPreview {
                 preview_text: format!("1 {from_unit} = 1 {to_unit}"),
                 conversion_factor: 1.0,
                 is_valid: true,
+                is_affine: false,
             };
         }
 
@@ -418,22 +783,57 @@ impl UnitConverter {
             value: 1.0,
             from_unit: from_unit.to_owned(),
             to_unit: to_unit.to_owned(),
+            quantity_kind: QuantityKind::Absolute,
         };
 
         match self.convert(&dummy_request) {
-            Ok(result) => ConversionPreview {
-                preview_text: result.formatted_result,
-                conversion_factor: result.conversion_factor,
-                is_valid: true,
+            Ok(result) => match self.conversion_map(from_unit, to_unit) {
+                Ok(map) if map.is_affine() => ConversionPreview {
+                    preview_text: format!(
+                        "{} (absolute); difference: {}",
+                        result.formatted_result,
+                        Self::format_result(1.0, from_unit, map.factor, to_unit)
+                    ),
+                    conversion_factor: map.factor,
+                    is_valid: true,
+                    is_affine: true,
+                },
+                _ => ConversionPreview {
+                    preview_text: result.formatted_result,
+                    conversion_factor: result.conversion_factor,
+                    is_valid: true,
+                    is_affine: false,
+                },
             },
             Err(error) => ConversionPreview {
                 preview_text: error,
                 conversion_factor: 0.0,
                 is_valid: false,
+                is_affine: false,
             },
         }
     }
 
+    /// Compatibility of two units, noting when the conversion is affine
+    pub fn unit_compatibility(&self, from_unit: &str, to_unit: &str) -> UnitCompatibility {
+        let compatible = self.check_unit_compatibility(from_unit, to_unit);
+        let affine_map = self
+            .conversion_map(from_unit, to_unit)
+            .ok()
+            .filter(|map| compatible && map.is_affine());
+        UnitCompatibility {
+            compatible,
+            is_affine: affine_map.is_some(),
+            note: affine_map.map(|map| {
+                format!(
+                    "{from_unit} to {to_unit} is affine: absolute values are shifted by {}, \
+                     differences are only scaled by {}",
+                    map.offset, map.factor
+                )
+            }),
+        }
+    }
+
     pub fn check_unit_compatibility(&self, from_unit: &str, to_unit: &str) -> bool {
         if from_unit == to_unit {
             return true;
@@ -445,6 +845,100 @@ impl UnitConverter {
         }
     }
 
+    /// Register a user-defined unit. The symbol must be new (also as a
+    /// prefixed unit) and the dimension must be that of an existing unit.
+    pub fn register_custom_unit(&mut self, unit: CustomUnit) -> Result<(), String> {
+        let symbol = unit.symbol.as_str();
+        if symbol.is_empty()
+            || !symbol.chars().all(|character| {
+                character.is_ascii_alphabetic() || "\u{3bc}\u{b0}".contains(character)
+            })
+        {
+            return Err(format!(
+                "Invalid unit symbol '{symbol}': use letters, '\u{3bc}' and '\u{b0}' only"
+            ));
+        }
+        if self.parse_unit_with_prefix(symbol).is_some() {
+            return Err(format!("Unit '{symbol}' already exists"));
+        }
+        if !unit.si_factor.is_finite() || unit.si_factor <= 0.0 {
+            return Err("SI factor must be a finite positive number".to_owned());
+        }
+        if !unit.si_offset.is_finite() {
+            return Err("SI offset must be a finite number".to_owned());
+        }
+        let dimension = Dimension::parse(&unit.dimension_formula)?;
+        if !self
+            .base_units
+            .values()
+            .any(|existing| existing.dimension == dimension)
+        {
+            return Err(format!(
+                "No existing unit has the dimension {}",
+                unit.dimension_formula
+            ));
+        }
+
+        self.base_units.insert(
+            unit.symbol.clone(),
+            BaseUnit {
+                symbol: unit.symbol.clone(),
+                name: unit.symbol.clone(),
+                dimension,
+                si_factor: unit.si_factor,
+            },
+        );
+        self.custom_units.insert(unit.symbol.clone(), unit);
+        Ok(())
+    }
+
+    /// Remove a user-defined unit; built-in units cannot be removed
+    pub fn unregister_custom_unit(&mut self, symbol: &str) -> Result<CustomUnit, String> {
+        let unit = self
+            .custom_units
+            .remove(symbol)
+            .ok_or_else(|| format!("'{symbol}' is not a custom unit"))?;
+        self.base_units.remove(symbol);
+        Ok(unit)
+    }
+
+    /// User-defined units sorted by symbol
+    pub fn custom_units(&self) -> Vec<CustomUnit> {
+        let mut units: Vec<CustomUnit> = self.custom_units.values().cloned().collect();
+        units.sort_by(|left, right| left.symbol.cmp(&right.symbol));
+        units
+    }
+
+    /// User-defined units as the JSON saved between sessions
+    pub fn custom_units_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(&self.custom_units())
+            .map_err(|e| format!("Failed to serialize custom units: {e}"))
+    }
+
+    /// Register the units of a saved custom unit list; units that no longer
+    /// validate are skipped and reported in the error
+    pub fn load_custom_units_json(&mut self, json: &str) -> Result<usize, String> {
+        let units: Vec<CustomUnit> =
+            serde_json::from_str(json).map_err(|e| format!("Invalid custom unit file: {e}"))?;
+        let mut loaded = 0;
+        let mut errors = Vec::new();
+        for unit in units {
+            let symbol = unit.symbol.clone();
+            match self.register_custom_unit(unit) {
+                Ok(()) => loaded += 1,
+                Err(e) => errors.push(format!("{symbol}: {e}")),
+            }
+        }
+        if errors.is_empty() {
+            Ok(loaded)
+        } else {
+            Err(format!(
+                "Loaded {loaded} custom units; skipped {}",
+                errors.join("; ")
+            ))
+        }
+    }
+
     pub fn get_available_units(&self) -> HashMap<String, UnitInfo> {
         let categories = get_unit_categories();
         let mut category_map = HashMap::new();
@@ -459,17 +953,33 @@ impl UnitConverter {
         self.base_units
             .iter()
             .map(|(symbol, unit)| {
-                let category = category_map
-                    .get(symbol)
-                    .cloned()
-                    .unwrap_or_else(|| "other".to_owned());
+                let (category, description) = self.custom_units.get(symbol).map_or_else(
+                    || {
+                        (
+                            category_map
+                                .get(symbol)
+                                .cloned()
+                                .unwrap_or_else(|| "other".to_owned()),
+                            format!("SI factor: {}", unit.si_factor),
+                        )
+                    },
+                    |custom| {
+                        (
+                            "custom".to_owned(),
+                            format!(
+                                "Custom unit, SI factor: {}, SI offset: {}",
+                                custom.si_factor, custom.si_offset
+                            ),
+                        )
+                    },
+                );
                 (
                     symbol.clone(),
                     UnitInfo {
                         symbol: symbol.clone(),
                         name: unit.name.clone(),
                         category,
-                        description: format!("SI factor: {}", unit.si_factor),
+                        description,
                     },
                 )
             })
@@ -505,3 +1015,288 @@ impl UnitConverter {
 // Global converter instance
 pub static UNIT_CONVERTER: LazyLock<Mutex<UnitConverter>> =
     LazyLock::new(|| Mutex::new(UnitConverter::new()));
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use std::time::{Duration, Instant};
+
+    fn column(
+        values: Vec<f64>,
+        uncertainties: Option<Vec<f64>>,
+        from: &str,
+        to: &str,
+    ) -> ColumnConversionRequest {
+        ColumnConversionRequest {
+            values,
+            uncertainties,
+            from_unit: from.to_owned(),
+            to_unit: to.to_owned(),
+            is_uncertainty_column: false,
+            quantity_kind: QuantityKind::Absolute,
+        }
+    }
+
+    #[test]
+    fn test_column_uncertainties_ignore_offsets() {
+        let converter = UnitConverter::new();
+        let map = converter.conversion_map("\u{b0}F", "\u{b0}C").unwrap();
+        assert!(map.is_affine());
+        let request = column(
+            vec![32.0, 212.0, f64::INFINITY],
+            Some(vec![1.8, f64::NAN, 0.9]),
+            "\u{b0}F",
+            "\u{b0}C",
+        );
+        let result = map.convert_column(&request).unwrap();
+        assert_relative_eq!(result.values[0], 0.0, epsilon = 1e-12);
+        assert_relative_eq!(result.values[1], 100.0, epsilon = 1e-12);
+        assert!(result.values[2].is_nan());
+        let uncertainties = result.uncertainties.unwrap();
+        assert_relative_eq!(uncertainties[0], 1.0, epsilon = 1e-12);
+        assert!(uncertainties[1].is_nan());
+        assert_relative_eq!(uncertainties[2], 0.5, epsilon = 1e-12);
+        assert_eq!(result.non_finite_indices, [1, 2]);
+
+        // An uncertainty column on its own cannot take the offset
+        let alone = ColumnConversionRequest {
+            is_uncertainty_column: true,
+            ..column(vec![1.8], None, "\u{b0}F", "\u{b0}C")
+        };
+        let error = map.convert_column(&alone).unwrap_err();
+        assert!(error.contains("zero point"), "{error}");
+        // Without an offset it is scaled like any other column
+        let km = converter.conversion_map("m", "km").unwrap();
+        let scaled = km
+            .convert_column(&ColumnConversionRequest {
+                is_uncertainty_column: true,
+                ..column(vec![250.0], None, "m", "km")
+            })
+            .unwrap();
+        assert_relative_eq!(scaled.values[0], 0.25, epsilon = 1e-12);
+
+        let mismatched = column(vec![1.0, 2.0], Some(vec![0.1]), "m", "km");
+        assert!(km.convert_column(&mismatched).is_err());
+        assert!(converter.conversion_map("m", "s").is_err());
+    }
+
+    #[test]
+    fn test_million_row_column_is_fast() {
+        let converter = UnitConverter::new();
+        let values: Vec<f64> = (0..1_000_000).map(f64::from).collect();
+        let request = column(values.clone(), Some(values), "km/h", "m/s");
+
+        let start = Instant::now();
+        let map = converter
+            .conversion_map(&request.from_unit, &request.to_unit)
+            .unwrap();
+        let result = map.convert_column(&request).unwrap();
+        let elapsed = start.elapsed();
+
+        assert_relative_eq!(
+            result.values[999_999],
+            999_999.0 / 3.6,
+            max_relative = 1e-12
+        );
+        assert!(result.non_finite_indices.is_empty());
+        // Generous bound for unoptimized single-threaded builds
+        assert!(elapsed < Duration::from_secs(2), "took {elapsed:?}");
+    }
+
+    #[test]
+    fn test_temperature_differences_skip_the_offset() {
+        let converter = UnitConverter::new();
+        let convert = |value: f64, from: &str, to: &str, quantity_kind| {
+            converter
+                .convert(&ConversionRequest {
+                    value,
+                    from_unit: from.to_owned(),
+                    to_unit: to.to_owned(),
+                    quantity_kind,
+                })
+                .unwrap()
+                .value
+        };
+        let celsius = "\u{b0}C";
+        let fahrenheit = "\u{b0}F";
+        assert_relative_eq!(convert(10.0, celsius, "K", QuantityKind::Absolute), 283.15);
+        assert_relative_eq!(convert(10.0, celsius, "K", QuantityKind::Difference), 10.0);
+        assert_relative_eq!(
+            convert(9.0, fahrenheit, celsius, QuantityKind::Difference),
+            5.0,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            converter
+                .quick_convert_quantity(18.0, fahrenheit, "K", QuantityKind::Difference)
+                .unwrap(),
+            10.0,
+            max_relative = 1e-12
+        );
+        // Non-affine pairs convert differences like absolute values
+        assert_eq!(
+            convert(3.0, "km", "m", QuantityKind::Difference).to_bits(),
+            convert(3.0, "km", "m", QuantityKind::Absolute).to_bits()
+        );
+
+        // A column of differences may carry its uncertainties alone
+        let map = converter.conversion_map(fahrenheit, celsius).unwrap();
+        let gradients = ColumnConversionRequest {
+            quantity_kind: QuantityKind::Difference,
+            is_uncertainty_column: true,
+            ..column(vec![1.8], None, fahrenheit, celsius)
+        };
+        assert_relative_eq!(
+            map.convert_column(&gradients).unwrap().values[0],
+            1.0,
+            max_relative = 1e-12
+        );
+
+        let preview = converter.get_conversion_preview(celsius, "K");
+        assert!(preview.is_affine);
+        assert!(
+            preview.preview_text.contains("difference: 1 \u{b0}C = 1 K"),
+            "{}",
+            preview.preview_text
+        );
+        let compatibility = converter.unit_compatibility(celsius, "K");
+        assert!(compatibility.compatible && compatibility.is_affine);
+        assert!(compatibility.note.is_some());
+        assert!(!converter.unit_compatibility("m", "km").is_affine);
+    }
+
+    fn custom(symbol: &str, dimension_formula: &str, si_factor: f64, si_offset: f64) -> CustomUnit {
+        CustomUnit {
+            symbol: symbol.to_owned(),
+            dimension_formula: dimension_formula.to_owned(),
+            si_factor,
+            si_offset,
+        }
+    }
+
+    #[test]
+    fn test_si_prefixes_are_resolved() {
+        let converter = UnitConverter::new();
+        let factor = |unit: &str| converter.parse_unit(unit).unwrap().si_factor;
+
+        assert_relative_eq!(factor("ms"), 1e-3);
+        assert_relative_eq!(factor("kWh"), 3.6e6);
+        assert_relative_eq!(factor("daN"), 10.0);
+        assert_relative_eq!(factor("cm^2"), 1e-4);
+        assert_relative_eq!(factor("\u{b5}s"), factor("\u{3bc}s"));
+        assert_relative_eq!(factor("\u{b5}m"), 1e-6);
+        assert_relative_eq!(factor("mK"), 1e-3);
+        assert_eq!(
+            converter.parse_unit("ms").unwrap().dimension,
+            converter.parse_unit("s").unwrap().dimension
+        );
+
+        // Exact symbols win over prefix splits, and degree units take no prefix
+        assert_relative_eq!(factor("min"), 60.0);
+        assert!(converter.parse_unit("m\u{b0}C").is_err());
+        assert!(converter.parse_unit("k\u{b0}F").is_err());
+
+        assert_eq!(
+            converter.parse_unit("kN*m/Mm^2").unwrap().expanded,
+            "(1e3\u{b7}N)\u{b7}m/(1e6\u{b7}m)^2"
+        );
+        assert_eq!(
+            converter.parse_unit("kg*ms").unwrap().expanded,
+            "kg\u{b7}ms"
+        );
+        assert_eq!(
+            converter.parse_unit("mK").unwrap().expanded,
+            "(1e-3\u{b7}K)"
+        );
+    }
+
+    #[test]
+    fn test_dimension_formulas() {
+        let energy = Dimension::parse("[M\u{b7}L^2\u{b7}T^-2]").unwrap();
+        assert_eq!(energy, Dimension::parse("M L^2 / T^2").unwrap());
+        assert_eq!(energy.si_unit(), "kg\u{b7}m^2\u{b7}s^-2");
+        assert_eq!(
+            Dimension::parse("[dimensionless]").unwrap(),
+            Dimension::new()
+        );
+        assert!(Dimension::parse("[Q]").is_err());
+        assert!(Dimension::parse("L^x").is_err());
+    }
+
+    #[test]
+    fn test_custom_units() {
+        let mut converter = UnitConverter::new();
+        let convert = |units: &UnitConverter, value: f64, from: &str, to: &str| {
+            units
+                .convert(&ConversionRequest {
+                    value,
+                    from_unit: from.to_owned(),
+                    to_unit: to.to_owned(),
+                    quantity_kind: QuantityKind::Absolute,
+                })
+                .unwrap()
+                .value
+        };
+
+        converter
+            .register_custom_unit(custom("smoot", "[L]", 1.7018, 0.0))
+            .unwrap();
+        assert_relative_eq!(convert(&converter, 1.0, "smoot", "cm"), 170.18);
+        assert_relative_eq!(convert(&converter, 2.0, "smoot/s", "m/s"), 3.4036);
+
+        // Rømer: 7.5 at the freezing point of water, 60 at its boiling point
+        let romer = custom(
+            "degRo",
+            "[\u{398}]",
+            40.0 / 21.0,
+            273.15 - 7.5 * 40.0 / 21.0,
+        );
+        converter.register_custom_unit(romer.clone()).unwrap();
+        assert_relative_eq!(
+            convert(&converter, 60.0, "degRo", "\u{b0}C"),
+            100.0,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            convert(&converter, 212.0, "\u{b0}F", "degRo"),
+            60.0,
+            max_relative = 1e-12
+        );
+        let map = converter.conversion_map("degRo", "K").unwrap();
+        assert!(map.is_affine());
+        assert_relative_eq!(map.factor, 40.0 / 21.0, max_relative = 1e-12);
+
+        let units = converter.get_available_units();
+        assert_eq!(units["degRo"].category, "custom");
+        assert_eq!(units["m"].category, "length");
+
+        // Symbols must be new, dimensions must exist and factors must be usable
+        for invalid in [
+            custom("pc", "[L]", 1.0, 0.0),
+            custom("km", "[L]", 1.0, 0.0),
+            custom("smoot", "[L]", 1.0, 0.0),
+            custom("x1", "[L]", 1.0, 0.0),
+            custom("wobble", "[M^5]", 1.0, 0.0),
+            custom("wobble", "[L]", 0.0, 0.0),
+        ] {
+            assert!(converter.register_custom_unit(invalid).is_err());
+        }
+
+        // Saved units load into a fresh converter
+        let json = converter.custom_units_json().unwrap();
+        let mut restored = UnitConverter::new();
+        assert_eq!(restored.load_custom_units_json(&json).unwrap(), 2);
+        assert_relative_eq!(
+            convert(&restored, 7.5, "degRo", "\u{b0}C"),
+            0.0,
+            epsilon = 1e-9
+        );
+        assert_relative_eq!(convert(&restored, 1.0, "smoot", "m"), 1.7018);
+
+        assert_eq!(converter.unregister_custom_unit("degRo").unwrap(), romer);
+        assert!(converter.parse_unit("degRo").is_err());
+        assert!(converter.unregister_custom_unit("m").is_err());
+    }
+}
@@ -1,4 +1,5 @@
 // src-tauri/src/unit_conversion/mod.rs
 pub mod commands;
+pub mod constants;
 pub mod core;
 pub mod units;
@@ -0,0 +1,120 @@
+// Cancellation of in-flight computations
+//
+// A computation started with an id registers a flag here for as long as it
+// runs; `cancel_computation` sets the flag and the computation checks it at
+// its own pace, returning early once it is set. The flag is removed when the
+// computation ends, cancelled or not.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use tauri::State;
+
+use crate::error::{CommandResult, validation_error};
+
+type FlagTable = Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>;
+
+fn lock(flags: &FlagTable) -> MutexGuard<'_, HashMap<String, Arc<AtomicBool>>> {
+    flags.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Cancellation flags of the running computations, by computation id.
+#[derive(Default)]
+pub struct CancellationRegistry {
+    flags: FlagTable,
+}
+
+impl CancellationRegistry {
+    /// Register a running computation. A computation already registered
+    /// under `id` keeps its own flag but can no longer be cancelled by id.
+    pub fn register(&self, id: &str) -> CancellationGuard {
+        let flag = Arc::new(AtomicBool::new(false));
+        lock(&self.flags).insert(id.to_owned(), Arc::clone(&flag));
+        CancellationGuard {
+            flags: Arc::clone(&self.flags),
+            id: id.to_owned(),
+            flag,
+        }
+    }
+
+    /// Ask the computation registered under `id` to stop. Returns whether
+    /// such a computation is running.
+    pub fn cancel(&self, id: &str) -> bool {
+        let flag = lock(&self.flags).get(id).map(Arc::clone);
+        flag.inspect(|flag| flag.store(true, Ordering::Relaxed))
+            .is_some()
+    }
+}
+
+/// Registration of one computation, removed from the registry on drop.
+pub struct CancellationGuard {
+    flags: FlagTable,
+    id: String,
+    flag: Arc<AtomicBool>,
+}
+
+impl CancellationGuard {
+    /// Flag set when the computation is cancelled.
+    pub fn flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.flag)
+    }
+}
+
+impl Drop for CancellationGuard {
+    fn drop(&mut self) {
+        let mut flags = lock(&self.flags);
+        if flags
+            .get(&self.id)
+            .is_some_and(|flag| Arc::ptr_eq(flag, &self.flag))
+        {
+            flags.remove(&self.id);
+        }
+    }
+}
+
+/// Cancel the running computation started with `computation_id`. Returns
+/// false when no such computation is running, e.g. because it already ended.
+///
+/// # Errors
+/// Returns a validation error if `computation_id` is empty.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn cancel_computation(
+    computation_id: String,
+    registry: State<CancellationRegistry>,
+) -> CommandResult<bool> {
+    if computation_id.trim().is_empty() {
+        return Err(validation_error(
+            "Computation id cannot be empty",
+            Some("computation_id".to_owned()),
+        ));
+    }
+    Ok(registry.cancel(&computation_id))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_sets_flag_and_guard_unregisters() {
+        let registry = CancellationRegistry::default();
+        assert!(!registry.cancel("fit"));
+
+        let guard = registry.register("fit");
+        let flag = guard.flag();
+        assert!(!flag.load(Ordering::Relaxed));
+        assert!(registry.cancel("fit"));
+        assert!(flag.load(Ordering::Relaxed));
+
+        // A second run under the same id outlives the first registration
+        let rerun = registry.register("fit");
+        drop(guard);
+        assert!(registry.cancel("fit"));
+        assert!(rerun.flag().load(Ordering::Relaxed));
+        drop(rerun);
+        assert!(!registry.cancel("fit"));
+        assert!(lock(&registry.flags).is_empty());
+    }
+}
@@ -1,20 +1,202 @@
 // File operations utilities
 
 use base64::{Engine as Base64Engine, engine::general_purpose::STANDARD};
-use serde::Serialize;
+use chrono::Utc;
+use flate2::Crc;
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref};
+use serde::{Deserialize, Serialize};
 use std::fs::{create_dir_all, read_to_string, write};
-use std::path::Path;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::OnceLock;
+use svg2pdf::usvg::{self, PostProcessingSteps, TreeParsing, TreePostProc, fontdb::Database};
 
-fn ensure_parent_and_write(path: &str, content: impl AsRef<[u8]>) -> Result<(), String> {
+use crate::error::{
+    CommandError, CommandResult, codes, file_not_found, io_error, validation_error,
+};
+
+/// Characters that are not allowed in file names on at least one platform.
+const ILLEGAL_FILENAME_CHARS: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+/// Device names Windows reserves regardless of extension.
+const RESERVED_FILENAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+/// Length of the signature plus the IHDR chunk, which must come first.
+const PNG_HEADER_LEN: usize = 8 + 12 + 13;
+/// PDF points per SVG user unit (CSS pixels are 1/96 in, points 1/72 in).
+const POINTS_PER_PIXEL: f32 = 0.75;
+
+/// System fonts used to outline SVG text, loaded on first PDF export.
+static FONT_DATABASE: OnceLock<Database> = OnceLock::new();
+
+fn ensure_parent_and_write(path: &str, content: impl AsRef<[u8]>) -> CommandResult<()> {
     if let Some(parent) = Path::new(path).parent() {
-        create_dir_all(parent).map_err(|e| format!("Failed to create parent directory: {e}"))?;
+        create_dir_all(parent).map_err(|e| {
+            io_error(
+                Some(path.to_owned()),
+                format!("Failed to create parent directory: {e}"),
+            )
+        })?;
     }
 
-    write(path, content).map_err(|e| format!("Failed to write file: {e}"))?;
+    write(path, content)
+        .map_err(|e| io_error(Some(path.to_owned()), format!("Failed to write file: {e}")))?;
     Ok(())
 }
 
+/// Descriptive metadata embedded into exported images.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ImageMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Defaults to `AnaFis`.
+    pub software: Option<String>,
+    /// Defaults to the current UTC time in RFC 3339 format.
+    pub creation_time: Option<String>,
+    /// Fit parameters, stored as a JSON string.
+    pub fit_parameters: Option<serde_json::Value>,
+}
+
+impl ImageMetadata {
+    fn software(&self) -> String {
+        self.software.clone().unwrap_or_else(|| "AnaFis".to_owned())
+    }
+
+    fn creation_time(&self) -> String {
+        self.creation_time
+            .clone()
+            .unwrap_or_else(|| Utc::now().to_rfc3339())
+    }
+
+    /// PNG text entries, using the keywords registered in the PNG standard
+    /// where one exists.
+    fn png_entries(&self) -> Vec<(&'static str, String)> {
+        let mut entries = Vec::new();
+        if let Some(title) = &self.title {
+            entries.push(("Title", title.clone()));
+        }
+        if let Some(description) = &self.description {
+            entries.push(("Description", description.clone()));
+        }
+        entries.push(("Software", self.software()));
+        entries.push(("Creation Time", self.creation_time()));
+        if let Some(parameters) = &self.fit_parameters {
+            entries.push(("Fit Parameters", parameters.to_string()));
+        }
+        entries
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Svg,
+}
+
+impl ImageFormat {
+    const fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Svg => "svg",
+        }
+    }
+}
+
+/// One image of a batch export. Raster formats take `data_url`, SVG takes
+/// `svg_text`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImageSpec {
+    pub filename: String,
+    pub data_url: Option<String>,
+    pub svg_text: Option<String>,
+    pub format: ImageFormat,
+    pub metadata: Option<ImageMetadata>,
+}
+
+/// Outcome of one image of a batch export.
+#[derive(Debug, Serialize)]
+pub struct ImageSaveResult {
+    pub filename: String,
+    /// Path written, after sanitization and collision handling.
+    pub path: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Page layout for PDF export. Sizes are in PDF points (1/72 in).
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct PdfExportOptions {
+    /// Defaults to the figure width plus margins.
+    pub page_width: Option<f32>,
+    /// Defaults to the figure height plus margins.
+    pub page_height: Option<f32>,
+    /// Margin on every side (default 0). Figures are scaled to fit inside
+    /// the margins and centred.
+    pub margin: Option<f32>,
+}
+
+/// Why a PDF export failed; SVG syntax errors carry their location.
+#[derive(Debug)]
+pub struct PdfExportError {
+    pub message: String,
+    /// Index of the SVG that could not be converted.
+    pub figure: Option<usize>,
+    /// 1-based line of an XML syntax error.
+    pub line: Option<u32>,
+    /// 1-based column of an XML syntax error.
+    pub column: Option<u32>,
+}
+
+impl PdfExportError {
+    const fn new(message: String) -> Self {
+        Self {
+            message,
+            figure: None,
+            line: None,
+            column: None,
+        }
+    }
+
+    fn invalid_svg(figure: usize, error: &usvg::Error) -> Self {
+        let position = match error {
+            usvg::Error::ParsingFailed(xml_error) => Some(xml_error.pos()),
+            _ => None,
+        };
+        Self {
+            message: format!("Invalid SVG in figure {}: {error}", figure + 1),
+            figure: Some(figure),
+            line: position.map(|pos| pos.row),
+            column: position.map(|pos| pos.col),
+        }
+    }
+}
+
+/// Invalid SVGs are parsing errors against their figure; the location of an
+/// XML syntax error is appended to the detail.
+impl From<PdfExportError> for CommandError {
+    fn from(error: PdfExportError) -> Self {
+        let Some(figure) = error.figure else {
+            return validation_error(error.message, None);
+        };
+        let detail = match (error.line, error.column) {
+            (Some(line), Some(column)) => {
+                format!("{} (line {line}, column {column})", error.message)
+            }
+            _ => error.message,
+        };
+        Self::Validation {
+            code: codes::PARSING_ERROR,
+            field: Some(format!("svgTexts[{figure}]")),
+            detail,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct FfmpegAvailability {
     pub available: bool,
@@ -50,61 +232,466 @@ fn find_ffmpeg_path() -> Option<String> {
         .map(str::to_owned)
 }
 
+/// Make `name` safe to use as a file name on every platform.
+///
+/// Illegal and control characters become `_`, trailing dots and spaces are
+/// dropped, and reserved Windows device names get a leading `_`.
+pub fn sanitize_filename(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| {
+            if c.is_control() || ILLEGAL_FILENAME_CHARS.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let trimmed = replaced.trim().trim_end_matches(['.', ' ']);
+    let stem = trimmed.split('.').next().unwrap_or_default();
+    if trimmed.is_empty() {
+        "untitled".to_owned()
+    } else if RESERVED_FILENAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+    {
+        format!("_{trimmed}")
+    } else {
+        trimmed.to_owned()
+    }
+}
+
+/// `path` with its file name sanitized. Used for paths picked in a save
+/// dialog, where overwriting has already been confirmed.
+fn sanitized_path(path: &str) -> PathBuf {
+    let path = Path::new(path);
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => parent.join(sanitize_filename(&name.to_string_lossy())),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Path in `directory` for `filename`, sanitized and given `extension` if it
+/// lacks it. Existing files are never overwritten: `-1`, `-2`, ... is
+/// appended to the stem until the name is free.
+pub fn unique_path(directory: &Path, filename: &str, extension: &str) -> PathBuf {
+    let name = sanitize_filename(filename);
+    let stem = match name.rsplit_once('.') {
+        Some((stem, existing)) if !stem.is_empty() && existing.eq_ignore_ascii_case(extension) => {
+            stem.to_owned()
+        }
+        _ => name,
+    };
+    let mut candidate = directory.join(format!("{stem}.{extension}"));
+    let mut suffix = 1;
+    while candidate.exists() {
+        candidate = directory.join(format!("{stem}-{suffix}.{extension}"));
+        suffix += 1;
+    }
+    candidate
+}
+
+/// Bytes of a base64 data URL (`data:image/[type];base64,[data]`).
+fn decode_data_url(data_url: &str) -> Result<Vec<u8>, String> {
+    let parts: Vec<&str> = data_url.split(',').collect();
+    if parts.len() != 2 {
+        return Err(
+            "Invalid data URL format. Expected 'data:image/[type];base64,[data]'".to_owned(),
+        );
+    }
+
+    STANDARD
+        .decode(parts[1])
+        .map_err(|e| format!("Failed to decode base64 data: {e}"))
+}
+
+fn png_chunk(kind: [u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut crc = Crc::new();
+    crc.update(&kind);
+    crc.update(data);
+    #[allow(
+        clippy::cast_possible_truncation,
+        reason = "Metadata chunks are far below 4 GiB"
+    )]
+    let length = data.len() as u32;
+    let mut chunk = Vec::with_capacity(data.len() + 12);
+    chunk.extend_from_slice(&length.to_be_bytes());
+    chunk.extend_from_slice(&kind);
+    chunk.extend_from_slice(data);
+    chunk.extend_from_slice(&crc.sum().to_be_bytes());
+    chunk
+}
+
+/// A `tEXt` chunk when `text` fits Latin-1, otherwise an uncompressed `iTXt`
+/// chunk holding UTF-8.
+fn png_text_chunk(keyword: &str, text: &str) -> Vec<u8> {
+    let mut data = keyword.as_bytes().to_vec();
+    data.push(0);
+    let latin1: Option<Vec<u8>> = text.chars().map(|c| u8::try_from(c).ok()).collect();
+    if let Some(bytes) = latin1 {
+        data.extend_from_slice(&bytes);
+        png_chunk(*b"tEXt", &data)
+    } else {
+        // Compression flag and method, then empty language and translated keyword.
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        data.extend_from_slice(text.as_bytes());
+        png_chunk(*b"iTXt", &data)
+    }
+}
+
+/// Insert `metadata` as text chunks right after the PNG header.
+fn embed_png_metadata(png: &[u8], metadata: &ImageMetadata) -> Result<Vec<u8>, String> {
+    if png.len() < PNG_HEADER_LEN || png[..8] != PNG_SIGNATURE || &png[12..16] != b"IHDR" {
+        return Err("Image data is not a valid PNG file".to_owned());
+    }
+    let mut output = png[..PNG_HEADER_LEN].to_vec();
+    for (keyword, text) in metadata.png_entries() {
+        output.extend(png_text_chunk(keyword, &text));
+    }
+    output.extend_from_slice(&png[PNG_HEADER_LEN..]);
+    Ok(output)
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Insert `<title>`, `<desc>` and a JSON `<metadata>` element as the first
+/// children of the root `<svg>` element.
+fn embed_svg_metadata(svg: &str, metadata: &ImageMetadata) -> Result<String, String> {
+    let start = svg
+        .find("<svg")
+        .ok_or_else(|| "SVG content has no <svg> element".to_owned())?;
+    let mut quote = None;
+    let mut end = None;
+    for (offset, c) in svg.split_at(start).1.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if open == c => quote = None,
+            (None, '>') => {
+                end = Some(start + offset);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let end = end.ok_or_else(|| "SVG content has an unterminated <svg> tag".to_owned())?;
+    let (open_tag, rest) = svg.split_at(end + 1);
+    if open_tag.ends_with("/>") {
+        return Err("SVG root element is empty".to_owned());
+    }
+
+    let mut elements = String::new();
+    if let Some(title) = &metadata.title {
+        elements.push_str("<title>");
+        elements.push_str(&escape_xml(title));
+        elements.push_str("</title>");
+    }
+    if let Some(description) = &metadata.description {
+        elements.push_str("<desc>");
+        elements.push_str(&escape_xml(description));
+        elements.push_str("</desc>");
+    }
+    let info = serde_json::json!({
+        "software": metadata.software(),
+        "creationTime": metadata.creation_time(),
+        "fitParameters": metadata.fit_parameters,
+    });
+    elements.push_str("<metadata id=\"anafis-metadata\">");
+    elements.push_str(&escape_xml(&info.to_string()));
+    elements.push_str("</metadata>");
+    Ok(format!("{open_tag}{elements}{rest}"))
+}
+
+/// Encoded file contents for one batch image.
+fn image_bytes(image: &ImageSpec) -> Result<Vec<u8>, String> {
+    match image.format {
+        ImageFormat::Svg => {
+            let svg = image
+                .svg_text
+                .as_deref()
+                .ok_or_else(|| "SVG images need svg_text".to_owned())?;
+            image.metadata.as_ref().map_or_else(
+                || Ok(svg.as_bytes().to_vec()),
+                |metadata| embed_svg_metadata(svg, metadata).map(String::into_bytes),
+            )
+        }
+        ImageFormat::Png | ImageFormat::Jpeg => {
+            let data_url = image
+                .data_url
+                .as_deref()
+                .ok_or_else(|| "Raster images need a data_url".to_owned())?;
+            let bytes = decode_data_url(data_url)?;
+            match (image.format, &image.metadata) {
+                (ImageFormat::Png, Some(metadata)) => embed_png_metadata(&bytes, metadata),
+                _ => Ok(bytes),
+            }
+        }
+    }
+}
+
+fn font_database() -> &'static Database {
+    FONT_DATABASE.get_or_init(|| {
+        let mut database = Database::new();
+        database.load_system_fonts();
+        database
+    })
+}
+
+/// Convert each SVG into one PDF page, keeping all vector content.
+///
+/// Text is converted to paths with the system fonts, so the PDF renders the
+/// same without the fonts installed.
+fn svgs_to_pdf(svgs: &[String], options: PdfExportOptions) -> Result<Vec<u8>, PdfExportError> {
+    if svgs.is_empty() {
+        return Err(PdfExportError::new("No figures to export".to_owned()));
+    }
+    let margin = options.margin.unwrap_or(0.0);
+    if !(margin.is_finite() && margin >= 0.0) {
+        return Err(PdfExportError::new(format!(
+            "Margin must be non-negative, got {margin}"
+        )));
+    }
+
+    let usvg_options = usvg::Options::default();
+    let trees = svgs
+        .iter()
+        .enumerate()
+        .map(|(index, svg)| {
+            let mut tree = usvg::Tree::from_str(svg, &usvg_options)
+                .map_err(|e| PdfExportError::invalid_svg(index, &e))?;
+            tree.postprocess(PostProcessingSteps::default(), font_database());
+            Ok(tree)
+        })
+        .collect::<Result<Vec<_>, PdfExportError>>()?;
+
+    let catalog = Ref::new(1);
+    let page_tree = Ref::new(2);
+    let mut next = Ref::new(3);
+    let figure_name = Name(b"Figure");
+    let mut pdf = Pdf::new();
+    let mut pages = Vec::with_capacity(trees.len());
+    for tree in &trees {
+        let figure_width = tree.size.width() * POINTS_PER_PIXEL;
+        let figure_height = tree.size.height() * POINTS_PER_PIXEL;
+        let page_width = options
+            .page_width
+            .unwrap_or_else(|| 2.0_f32.mul_add(margin, figure_width));
+        let page_height = options
+            .page_height
+            .unwrap_or_else(|| 2.0_f32.mul_add(margin, figure_height));
+        let box_width = 2.0_f32.mul_add(-margin, page_width);
+        let box_height = 2.0_f32.mul_add(-margin, page_height);
+        if !(box_width > 0.0 && box_height > 0.0) {
+            return Err(PdfExportError::new(format!(
+                "A {page_width} x {page_height} pt page leaves no room inside {margin} pt margins"
+            )));
+        }
+        let scale = (box_width / figure_width).min(box_height / figure_height);
+        let (width, height) = (figure_width * scale, figure_height * scale);
+
+        let page_ref = next;
+        let content_ref = Ref::new(next.get() + 1);
+        let figure_ref = Ref::new(next.get() + 2);
+        let mut page = pdf.page(page_ref);
+        page.media_box(Rect::new(0.0, 0.0, page_width, page_height));
+        page.parent(page_tree);
+        page.contents(content_ref);
+        page.resources().x_objects().pair(figure_name, figure_ref);
+        page.finish();
+
+        // The figure is a unit-square form XObject; scale it into the box.
+        let mut content = Content::new();
+        content
+            .transform([
+                width,
+                0.0,
+                0.0,
+                height,
+                margin + (box_width - width) / 2.0,
+                margin + (box_height - height) / 2.0,
+            ])
+            .x_object(figure_name);
+        pdf.stream(content_ref, &content.finish());
+        next = svg2pdf::convert_tree_into(tree, svg2pdf::Options::default(), &mut pdf, figure_ref);
+        pages.push(page_ref);
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        reason = "Figure counts are far below i32::MAX"
+    )]
+    let page_count = pages.len() as i32;
+    pdf.catalog(catalog).pages(page_tree);
+    pdf.pages(page_tree).kids(pages).count(page_count);
+    Ok(pdf.finish())
+}
+
+fn save_pdf(
+    svgs: &[String],
+    file_path: &str,
+    options: Option<PdfExportOptions>,
+) -> CommandResult<()> {
+    let bytes = svgs_to_pdf(svgs, options.unwrap_or_default())?;
+    ensure_parent_and_write(&sanitized_path(file_path).to_string_lossy(), bytes)
+}
+
 /// Read a text file and return its contents as a String.
 #[tauri::command]
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
-pub fn read_file_text(path: String) -> Result<String, String> {
-    read_to_string(&path).map_err(|e| format!("Failed to read file '{path}': {e}"))
+pub fn read_file_text(path: String) -> CommandResult<String> {
+    read_to_string(&path).map_err(|e| {
+        if e.kind() == ErrorKind::NotFound {
+            file_not_found(path.clone())
+        } else {
+            io_error(
+                Some(path.clone()),
+                format!("Failed to read file '{path}': {e}"),
+            )
+        }
+    })
 }
 
-/// Save a PNG file from base64-encoded data
+/// Save a PNG file from base64-encoded data, optionally embedding
+/// `metadata` as text chunks
 #[tauri::command]
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
-pub fn save_png_file(path: String, data: String) -> Result<(), String> {
+pub fn save_png_file(
+    path: String,
+    data: String,
+    metadata: Option<ImageMetadata>,
+) -> CommandResult<()> {
     // Decode base64 data
-    let bytes = STANDARD
-        .decode(&data)
-        .map_err(|e| format!("Failed to decode base64 data: {e}"))?;
+    let mut bytes = STANDARD.decode(&data).map_err(|e| {
+        validation_error(
+            format!("Failed to decode base64 data: {e}"),
+            Some("data".to_owned()),
+        )
+    })?;
+    if let Some(metadata) = &metadata {
+        bytes = embed_png_metadata(&bytes, metadata)
+            .map_err(|e| validation_error(e, Some("data".to_owned())))?;
+    }
 
-    ensure_parent_and_write(&path, bytes)
+    ensure_parent_and_write(&sanitized_path(&path).to_string_lossy(), bytes)
 }
 
-/// Save an image file from a data URL (format: "data:image/png;base64,...")
+/// Save an image file from a data URL (format: "data:image/png;base64,...").
+/// `metadata` is embedded into PNG images and ignored for other formats.
 #[tauri::command]
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
-pub fn save_image_from_data_url(data_url: String, path: String) -> Result<(), String> {
-    // Split the data URL to extract base64 data
-    let parts: Vec<&str> = data_url.split(',').collect();
-    if parts.len() != 2 {
-        return Err(
-            "Invalid data URL format. Expected 'data:image/[type];base64,[data]'".to_owned(),
-        );
+pub fn save_image_from_data_url(
+    data_url: String,
+    path: String,
+    metadata: Option<ImageMetadata>,
+) -> CommandResult<()> {
+    let invalid = |e| validation_error(e, Some("dataUrl".to_owned()));
+    let mut bytes = decode_data_url(&data_url).map_err(invalid)?;
+    if let Some(metadata) = &metadata
+        && data_url.starts_with("data:image/png")
+    {
+        bytes = embed_png_metadata(&bytes, metadata).map_err(invalid)?;
     }
 
-    let base64_data = parts[1];
+    ensure_parent_and_write(&sanitized_path(&path).to_string_lossy(), bytes)
+}
 
-    // Decode base64 data
-    let bytes = STANDARD
-        .decode(base64_data)
-        .map_err(|e| format!("Failed to decode base64 data: {e}"))?;
+/// Save an SVG file from SVG content string, optionally adding `<title>`,
+/// `<desc>` and `<metadata>` elements
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn save_svg_file(
+    svg_content: String,
+    path: String,
+    metadata: Option<ImageMetadata>,
+) -> CommandResult<()> {
+    let content = match &metadata {
+        Some(metadata) => embed_svg_metadata(&svg_content, metadata)
+            .map_err(|e| validation_error(e, Some("svgContent".to_owned())))?,
+        None => svg_content,
+    };
+    ensure_parent_and_write(&sanitized_path(&path).to_string_lossy(), content)
+}
 
-    ensure_parent_and_write(&path, bytes)
+/// Save an SVG figure as a single-page vector PDF
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn save_pdf_from_svg(
+    svg_text: String,
+    file_path: String,
+    options: Option<PdfExportOptions>,
+) -> CommandResult<()> {
+    save_pdf(&[svg_text], &file_path, options)
+}
+
+/// Save several SVG figures as consecutive pages of one vector PDF
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn save_pdf_from_svgs(
+    svg_texts: Vec<String>,
+    file_path: String,
+    options: Option<PdfExportOptions>,
+) -> CommandResult<()> {
+    save_pdf(&svg_texts, &file_path, options)
 }
 
-/// Save an SVG file from SVG content string
+/// Save several images into `directory`, e.g. all plots of a fit report.
+///
+/// File names are sanitized and never overwrite existing files. Each image
+/// reports its own success or error; only an unusable directory fails the
+/// whole batch.
 #[tauri::command]
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
-pub fn save_svg_file(svg_content: String, path: String) -> Result<(), String> {
-    ensure_parent_and_write(&path, svg_content)
+pub fn save_images_batch(
+    images: Vec<ImageSpec>,
+    directory: String,
+) -> CommandResult<Vec<ImageSaveResult>> {
+    create_dir_all(&directory).map_err(|e| {
+        io_error(
+            Some(directory.clone()),
+            format!("Failed to create directory: {e}"),
+        )
+    })?;
+    let directory = Path::new(&directory);
+
+    Ok(images
+        .iter()
+        .map(|image| {
+            let written = image_bytes(image).and_then(|bytes| {
+                let path = unique_path(directory, &image.filename, image.format.extension());
+                write(&path, bytes).map_err(|e| format!("Failed to write file: {e}"))?;
+                Ok(path)
+            });
+            match written {
+                Ok(path) => ImageSaveResult {
+                    filename: image.filename.clone(),
+                    path: Some(path.to_string_lossy().into_owned()),
+                    error: None,
+                },
+                Err(error) => ImageSaveResult {
+                    filename: image.filename.clone(),
+                    path: None,
+                    error: Some(error),
+                },
+            }
+        })
+        .collect())
 }
 
 /// Save a generic binary file from base64-encoded data.
 #[tauri::command]
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
-pub fn save_binary_file(path: String, data_base64: String) -> Result<(), String> {
-    let bytes = STANDARD
-        .decode(&data_base64)
-        .map_err(|e| format!("Failed to decode base64 data: {e}"))?;
+pub fn save_binary_file(path: String, data_base64: String) -> CommandResult<()> {
+    let bytes = STANDARD.decode(&data_base64).map_err(|e| {
+        validation_error(
+            format!("Failed to decode base64 data: {e}"),
+            Some("dataBase64".to_owned()),
+        )
+    })?;
 
     ensure_parent_and_write(&path, bytes)
 }
@@ -150,9 +737,9 @@ pub fn check_ffmpeg_available() -> FfmpegAvailability {
 pub fn transcode_webm_to_mp4(
     input_webm_path: String,
     output_mp4_path: String,
-) -> Result<VideoExportResult, String> {
+) -> CommandResult<VideoExportResult> {
     if !Path::new(&input_webm_path).exists() {
-        return Err(format!("Input WebM file does not exist: {input_webm_path}"));
+        return Err(file_not_found(input_webm_path));
     }
 
     let ffmpeg_status = check_ffmpeg_available();
@@ -165,8 +752,12 @@ pub fn transcode_webm_to_mp4(
     }
 
     if let Some(parent) = Path::new(&output_mp4_path).parent() {
-        create_dir_all(parent)
-            .map_err(|e| format!("Failed to create output directory for MP4 export: {e}"))?;
+        create_dir_all(parent).map_err(|e| {
+            io_error(
+                Some(output_mp4_path.clone()),
+                format!("Failed to create output directory for MP4 export: {e}"),
+            )
+        })?;
     }
 
     let ffmpeg_bin = ffmpeg_status.path.as_deref().unwrap_or("ffmpeg");
@@ -211,3 +802,236 @@ pub fn transcode_webm_to_mp4(
         }),
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+    use std::fs::{read, remove_dir_all};
+
+    /// A 1x1 greyscale PNG.
+    const TINY_PNG: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAAAAAA6fptVAAAACklEQVR4nGNgAAAAAgABSK+kcQAAAABJRU5ErkJggg==";
+
+    /// `(type, keyword, text)` of every text chunk, checking each CRC.
+    fn text_chunks(png: &[u8]) -> Vec<(String, String, String)> {
+        assert_eq!(png[..8], PNG_SIGNATURE);
+        let mut chunks = Vec::new();
+        let mut offset = 8;
+        while offset < png.len() {
+            let length = u32::from_be_bytes(png[offset..offset + 4].try_into().unwrap()) as usize;
+            let kind = &png[offset + 4..offset + 8];
+            let data = &png[offset + 8..offset + 8 + length];
+            let mut crc = Crc::new();
+            crc.update(kind);
+            crc.update(data);
+            let stored = &png[offset + 8 + length..offset + 12 + length];
+            assert_eq!(stored, crc.sum().to_be_bytes());
+            if kind == b"tEXt" || kind == b"iTXt" {
+                let split = data.iter().position(|&b| b == 0).unwrap();
+                let text = if kind == b"tEXt" {
+                    data[split + 1..].iter().map(|&b| char::from(b)).collect()
+                } else {
+                    String::from_utf8(data[split + 5..].to_vec()).unwrap()
+                };
+                chunks.push((
+                    String::from_utf8(kind.to_vec()).unwrap(),
+                    String::from_utf8(data[..split].to_vec()).unwrap(),
+                    text,
+                ));
+            }
+            offset += length + 12;
+        }
+        chunks
+    }
+
+    #[test]
+    fn test_png_metadata_chunks_are_written() {
+        let dir = temp_dir().join(format!("anafis-png-test-{}", std::process::id()));
+        let path = dir.join("fit?.png");
+        let metadata = ImageMetadata {
+            title: Some("D\u{e9}cay fit".to_owned()),
+            creation_time: Some("2026-01-01T00:00:00Z".to_owned()),
+            fit_parameters: Some(serde_json::json!({ "tau": 2.5, "unit": "\u{3bc}s" })),
+            ..ImageMetadata::default()
+        };
+        save_png_file(
+            path.to_string_lossy().into_owned(),
+            TINY_PNG.to_owned(),
+            Some(metadata),
+        )
+        .unwrap();
+
+        let chunks = text_chunks(&read(dir.join("fit_.png")).unwrap());
+        let find = |keyword: &str| chunks.iter().find(|chunk| chunk.1 == keyword).unwrap();
+        assert_eq!(
+            find("Title"),
+            &("tEXt".into(), "Title".into(), "D\u{e9}cay fit".into())
+        );
+        assert_eq!(find("Software").2, "AnaFis");
+        assert_eq!(find("Creation Time").2, "2026-01-01T00:00:00Z");
+        // Greek mu is outside Latin-1, so the parameters need an iTXt chunk.
+        assert_eq!(
+            find("Fit Parameters"),
+            &(
+                "iTXt".into(),
+                "Fit Parameters".into(),
+                "{\"tau\":2.5,\"unit\":\"\u{3bc}s\"}".into()
+            )
+        );
+        assert!(chunks.iter().all(|chunk| chunk.1 != "Description"));
+        remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_batch_names_are_sanitized_and_never_collide() {
+        let dir = temp_dir().join(format!("anafis-batch-test-{}", std::process::id()));
+        let png = |filename: &str| ImageSpec {
+            filename: filename.to_owned(),
+            data_url: Some(format!("data:image/png;base64,{TINY_PNG}")),
+            svg_text: None,
+            format: ImageFormat::Png,
+            metadata: None,
+        };
+        let svg = ImageSpec {
+            filename: "residuals".to_owned(),
+            data_url: None,
+            svg_text: Some(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" a=">"><g/></svg>"#.to_owned(),
+            ),
+            format: ImageFormat::Svg,
+            metadata: Some(ImageMetadata {
+                title: Some("A < B".to_owned()),
+                ..ImageMetadata::default()
+            }),
+        };
+        let missing = ImageSpec {
+            svg_text: None,
+            ..svg.clone()
+        };
+        let images = vec![
+            png("plot"),
+            png("plot.png"),
+            png("plot"),
+            svg,
+            missing,
+            png("CON"),
+        ];
+
+        let results = save_images_batch(images, dir.to_string_lossy().into_owned()).unwrap();
+        let names: Vec<Option<String>> = results
+            .iter()
+            .map(|result| {
+                result.path.as_ref().map(|path| {
+                    Path::new(path)
+                        .file_name()
+                        .unwrap()
+                        .to_string_lossy()
+                        .into_owned()
+                })
+            })
+            .collect();
+        assert_eq!(
+            names,
+            [
+                Some("plot.png".to_owned()),
+                Some("plot-1.png".to_owned()),
+                Some("plot-2.png".to_owned()),
+                Some("residuals.svg".to_owned()),
+                None,
+                Some("_CON.png".to_owned()),
+            ]
+        );
+        assert!(results[4].error.is_some());
+        let svg_text = read_to_string(dir.join("residuals.svg")).unwrap();
+        assert!(svg_text.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" a=">"><title>A &lt; B</title><metadata"#
+        ));
+        remove_dir_all(&dir).unwrap();
+    }
+
+    const FIGURE_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="400" height="300">
+  <rect width="400" height="300" fill="#ffffff"/>
+  <path d="M 20 280 L 200 40 L 380 200" stroke="#1f77b4" stroke-width="2" fill="none"/>
+  <circle cx="200" cy="150" r="60" fill="#ff0000" fill-opacity="0.3"/>
+  <text x="20" y="24" font-family="sans-serif" font-size="16">Residuals</text>
+</svg>"##;
+
+    fn media_box(document: &lopdf::Document, page: lopdf::ObjectId) -> Vec<f32> {
+        document
+            .get_object(page)
+            .and_then(lopdf::Object::as_dict)
+            .and_then(|dict| dict.get(b"MediaBox"))
+            .and_then(lopdf::Object::as_array)
+            .unwrap()
+            .iter()
+            .map(|value| value.as_float().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_pdf_export_writes_requested_page_size() {
+        let dir = temp_dir().join(format!("anafis-pdf-test-{}", std::process::id()));
+        let path = dir.join("figure.pdf");
+        let a4 = PdfExportOptions {
+            page_width: Some(595.0),
+            page_height: Some(842.0),
+            margin: Some(36.0),
+        };
+        save_pdf_from_svg(
+            FIGURE_SVG.to_owned(),
+            path.to_string_lossy().into_owned(),
+            Some(a4),
+        )
+        .unwrap();
+
+        let document = lopdf::Document::load(&path).unwrap();
+        let pages = document.get_pages();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(media_box(&document, pages[&1]), [0.0, 0.0, 595.0, 842.0]);
+
+        // Without a page size each page fits its figure: 400 x 300 px at 0.75 pt/px.
+        let svgs = vec![FIGURE_SVG.to_owned(), FIGURE_SVG.replace("400", "800")];
+        save_pdf_from_svgs(svgs, path.to_string_lossy().into_owned(), None).unwrap();
+        let multi_page = lopdf::Document::load(&path).unwrap();
+        let page_ids = multi_page.get_pages();
+        assert_eq!(page_ids.len(), 2);
+        assert_eq!(
+            media_box(&multi_page, page_ids[&1]),
+            [0.0, 0.0, 300.0, 225.0]
+        );
+        assert_eq!(
+            media_box(&multi_page, page_ids[&2]),
+            [0.0, 0.0, 600.0, 225.0]
+        );
+        remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_svg_reports_location() {
+        let broken =
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"10\" height=\"10\">\n  <g>\n</svg>";
+        let error = svgs_to_pdf(
+            &[FIGURE_SVG.to_owned(), broken.to_owned()],
+            PdfExportOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(error.figure, Some(1));
+        assert_eq!(error.line, Some(3));
+        assert!(error.column.is_some());
+        assert!(error.message.starts_with("Invalid SVG in figure 2"));
+
+        let response = CommandError::from(error);
+        assert_eq!(response.code(), codes::PARSING_ERROR);
+        assert_eq!(response.field(), Some("svgTexts[1]"));
+        assert!(response.detail().contains("(line 3, column"));
+    }
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("a/b:c*?.png"), "a_b_c__.png");
+        assert_eq!(sanitize_filename(" report. "), "report");
+        assert_eq!(sanitize_filename("nul.txt"), "_nul.txt");
+        assert_eq!(sanitize_filename("..."), "untitled");
+    }
+}
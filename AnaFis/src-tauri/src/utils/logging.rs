@@ -1,31 +1,157 @@
-use std::env::temp_dir;
-use std::fs::OpenOptions;
-use std::io::{Result, stderr};
-use tracing::info;
+use std::fs::{File, OpenOptions, create_dir_all, read_to_string, remove_file, rename};
+use std::io::{self, Result, Write, stderr};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use tracing::{Level, error, info, warn};
 use tracing_subscriber::{EnvFilter, fmt::layer, prelude::*, registry};
 
-/// Initialize structured logging with file and console output
-pub fn init_logging() -> Result<()> {
-    let log_temp_dir = temp_dir();
-    let log_path = log_temp_dir.join("anafis_debug.log");
+use crate::error::{CommandError, CommandResult, codes, io_error, validation_error};
 
-    // Create a file appender
-    let file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&log_path)?;
+/// Name of the active log file; rotated files get a `.1`, `.2`, ... suffix.
+const LOG_FILE_NAME: &str = "anafis.log";
+/// Size at which the active log file is rotated.
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotated files kept besides the active one.
+const KEPT_LOG_FILES: usize = 4;
+/// Lines returned by `get_recent_logs` when no limit is given.
+const DEFAULT_LOG_LINES: usize = 500;
 
-    // Setup tracing with both file and console output
-    let file_layer = layer().with_writer(file).with_ansi(false);
+/// Path of the active log file, set once logging writes to a file.
+static LOG_FILE: OnceLock<PathBuf> = OnceLock::new();
 
+struct RotatingState {
+    file: Option<File>,
+    size: u64,
+}
+
+/// Size-rotated log file that falls back to stderr when it cannot be written.
+pub struct RotatingFileWriter {
+    path: PathBuf,
+    max_bytes: u64,
+    kept_files: usize,
+    state: Mutex<RotatingState>,
+}
+
+impl RotatingFileWriter {
+    pub const fn new(path: PathBuf, max_bytes: u64, kept_files: usize) -> Self {
+        Self {
+            path,
+            max_bytes,
+            kept_files,
+            state: Mutex::new(RotatingState {
+                file: None,
+                size: 0,
+            }),
+        }
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{index}"));
+        PathBuf::from(name)
+    }
+
+    /// Shift `anafis.log.N` to `.N+1`, dropping the oldest, and start afresh.
+    fn rotate(&self, state: &mut RotatingState) {
+        state.file = None;
+        state.size = 0;
+        drop(remove_file(self.rotated_path(self.kept_files)));
+        for index in (1..self.kept_files).rev() {
+            drop(rename(
+                self.rotated_path(index),
+                self.rotated_path(index + 1),
+            ));
+        }
+        if self.kept_files > 0 {
+            drop(rename(&self.path, self.rotated_path(1)));
+        } else {
+            drop(remove_file(&self.path));
+        }
+    }
+
+    fn open(&self, state: &mut RotatingState) -> Option<()> {
+        if state.file.is_none() {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .ok()?;
+            state.size = file.metadata().map_or(0, |metadata| metadata.len());
+            state.file = Some(file);
+        }
+        Some(())
+    }
+}
+
+impl Write for &RotatingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let written = self
+            .open(&mut state)
+            .and_then(|()| state.file.as_mut()?.write_all(buf).ok());
+        if written.is_none() {
+            // Unwritable log directory: keep the message rather than failing.
+            state.file = None;
+            drop(state);
+            drop(stderr().write_all(buf));
+            return Ok(buf.len());
+        }
+        state.size += buf.len() as u64;
+        if state.size >= self.max_bytes {
+            self.rotate(&mut state);
+        }
+        drop(state);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let flushed = state.file.as_mut().map(Write::flush);
+        drop(state);
+        drop(flushed);
+        Ok(())
+    }
+}
+
+/// Initialize levelled logging to stderr and, when `log_dir` is given, to a
+/// rotating file inside it.
+///
+/// The level defaults to `info` and can be overridden with `RUST_LOG`.
+///
+/// # Errors
+/// Returns an error if a global logger is already installed.
+pub fn init_logging(log_dir: Option<&Path>) -> Result<()> {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
     let console_layer = layer().with_writer(stderr);
+
+    let log_path = log_dir.map(|dir| {
+        if let Err(e) = create_dir_all(dir) {
+            // The writer falls back to stderr, so only report it.
+            drop(writeln!(
+                stderr(),
+                "Cannot create log directory {}: {e}",
+                dir.display()
+            ));
+        }
+        dir.join(LOG_FILE_NAME)
+    });
+    let file_layer = log_path.as_ref().map(|path| {
+        let writer = RotatingFileWriter::new(path.clone(), MAX_LOG_BYTES, KEPT_LOG_FILES);
+        layer().with_writer(Arc::new(writer)).with_ansi(false)
+    });
+
     registry()
-        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .with(filter)
         .with(file_layer)
         .with(console_layer)
-        .init();
+        .try_init()
+        .map_err(io::Error::other)?;
 
-    info!(log_file = ?log_path, "Logging initialized");
+    if let Some(path) = log_path {
+        info!(log_file = ?path, "Logging initialized");
+        drop(LOG_FILE.set(path));
+    }
     Ok(())
 }
 
@@ -33,3 +159,147 @@ pub fn init_logging() -> Result<()> {
 pub fn log_info(message: &str) {
     info!(message = message);
 }
+
+/// Log a recoverable problem
+pub fn log_warn(message: &str) {
+    warn!(message = message);
+}
+
+/// Log a failure
+pub fn log_error(message: &str) {
+    error!(message = message);
+}
+
+/// Level of a formatted log line, read from the token after the timestamp.
+fn line_level(line: &str) -> Option<Level> {
+    line.split_whitespace()
+        .nth(1)
+        .and_then(|token| Level::from_str(token).ok())
+}
+
+/// The last `max_lines` lines of `text` at least as severe as `min_level`.
+///
+/// Lines without a level, such as continuation lines of a multi-line
+/// message, take the level of the line before them.
+pub fn filter_log_lines(text: &str, min_level: Level, max_lines: usize) -> Vec<String> {
+    let mut current = Level::INFO;
+    let mut kept: Vec<&str> = Vec::new();
+    for line in text.lines() {
+        if let Some(level) = line_level(line) {
+            current = level;
+        }
+        // More verbose levels compare greater in `tracing`.
+        if current <= min_level {
+            kept.push(line);
+        }
+    }
+    let skip = kept.len().saturating_sub(max_lines);
+    kept.into_iter().skip(skip).map(str::to_owned).collect()
+}
+
+/// Most recent log lines at or above `level_filter` (default `info`), oldest
+/// first, reading into the previous rotated file when needed.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn get_recent_logs(
+    level_filter: Option<String>,
+    max_lines: Option<usize>,
+) -> CommandResult<Vec<String>> {
+    let path = LOG_FILE.get().ok_or_else(file_logging_disabled)?;
+    let min_level = level_filter
+        .as_deref()
+        .map_or(Ok(Level::INFO), Level::from_str)
+        .map_err(|e| {
+            validation_error(
+                format!("Unknown log level: {e}"),
+                Some("levelFilter".to_owned()),
+            )
+        })?;
+    let max_lines = max_lines.unwrap_or(DEFAULT_LOG_LINES);
+
+    let mut previous = path.as_os_str().to_owned();
+    previous.push(".1");
+    let mut text = read_to_string(PathBuf::from(previous)).unwrap_or_default();
+    text.push_str(&read_to_string(path).map_err(|e| {
+        io_error(
+            Some(path.to_string_lossy().into_owned()),
+            format!("Failed to read log file: {e}"),
+        )
+    })?);
+    Ok(filter_log_lines(&text, min_level, max_lines))
+}
+
+/// Path of the active log file.
+#[tauri::command]
+pub fn get_log_file_path() -> CommandResult<String> {
+    LOG_FILE
+        .get()
+        .map(|path| path.to_string_lossy().into_owned())
+        .ok_or_else(file_logging_disabled)
+}
+
+/// Error for log commands when `init_logging` got no log directory.
+fn file_logging_disabled() -> CommandError {
+    CommandError::Io {
+        code: codes::NOT_FOUND,
+        path: None,
+        detail: "Logging to a file is not enabled".to_owned(),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+    use std::fs::{create_dir_all, remove_dir_all};
+
+    #[test]
+    fn test_writer_rotates_and_keeps_limited_files() {
+        let dir = temp_dir().join(format!("anafis-log-test-{}", std::process::id()));
+        create_dir_all(&dir).unwrap();
+        let writer = RotatingFileWriter::new(dir.join(LOG_FILE_NAME), 100, 2);
+        let line = [b'x'; 60];
+        for _ in 0..10 {
+            (&writer).write_all(&line).unwrap();
+        }
+        assert!(writer.rotated_path(1).exists());
+        assert!(writer.rotated_path(2).exists());
+        assert!(!writer.rotated_path(3).exists());
+        // Each rotated file holds the two writes that crossed the limit.
+        assert_eq!(
+            std::fs::metadata(writer.rotated_path(1)).unwrap().len(),
+            120
+        );
+        remove_dir_all(&dir).unwrap();
+
+        // A missing directory must not make logging fail.
+        let unwritable = RotatingFileWriter::new(dir.join("gone").join(LOG_FILE_NAME), 100, 2);
+        assert!((&unwritable).write_all(b"fallback\n").is_ok());
+    }
+
+    #[test]
+    fn test_recent_logs_filter_by_level() {
+        let text = "\
+2026-01-01T00:00:00Z  INFO anafis_lib: started
+2026-01-01T00:00:01Z  WARN anafis_lib: disk almost full
+  continued detail
+2026-01-01T00:00:02Z DEBUG anafis_lib: noise
+2026-01-01T00:00:03Z ERROR anafis_lib: failed
+";
+        assert_eq!(
+            filter_log_lines(text, Level::WARN, 10),
+            [
+                "2026-01-01T00:00:01Z  WARN anafis_lib: disk almost full",
+                "  continued detail",
+                "2026-01-01T00:00:03Z ERROR anafis_lib: failed",
+            ]
+        );
+        assert_eq!(filter_log_lines(text, Level::INFO, 10).len(), 4);
+        assert_eq!(filter_log_lines(text, Level::TRACE, 10).len(), 5);
+        assert_eq!(
+            filter_log_lines(text, Level::TRACE, 1),
+            ["2026-01-01T00:00:03Z ERROR anafis_lib: failed"]
+        );
+    }
+}
@@ -1,7 +1,12 @@
 // Utils module - contains utility functions and logging
 
+pub mod cancellation;
 pub mod file_operations;
 pub mod logging;
+pub mod progress;
+pub mod python_runtime;
+pub mod recovery;
+pub mod tasks;
 
 // Re-export commonly used functions
-pub use logging::{init_logging, log_info};
+pub use logging::{init_logging, log_error, log_info, log_warn};
@@ -0,0 +1,192 @@
+// Progress events of long computations
+//
+// A request carrying a computation id gets a `ProgressReporter`. The
+// computation reports every iteration, and the reporter forwards a
+// `computation-progress` event every `EMIT_EVERY_ITERATIONS` iterations or
+// `EMIT_INTERVAL`, whichever comes first, plus one when the computation
+// ends, so the frontend can match events to the request that started it.
+// The reporter also carries the cancellation flag of the computation, as it
+// is the one handle threaded through the solver loops.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tracing::warn;
+
+/// Event carrying a `ComputationProgress` payload.
+pub const PROGRESS_EVENT: &str = "computation-progress";
+/// Iterations between two events.
+const EMIT_EVERY_ITERATIONS: usize = 10;
+/// Longest time between two events while iterations keep coming.
+const EMIT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Progress of one computation.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComputationProgress {
+    /// Computation id given in the request
+    pub id: String,
+    /// Iterations completed
+    pub iteration: usize,
+    /// Iteration cap of the computation
+    pub max_iterations: usize,
+    /// Current chi-squared, for fits
+    pub chi_squared: Option<f64>,
+    /// Milliseconds since the reporter was created
+    pub elapsed_ms: u64,
+}
+
+type ProgressSink = Box<dyn Fn(&ComputationProgress) + Send + Sync>;
+
+struct LastEmit {
+    iteration: usize,
+    at: Instant,
+}
+
+/// Throttled progress reports of one computation.
+///
+/// Shared by reference between threads, so parallel parts of a computation
+/// may report through the same reporter.
+pub struct ProgressReporter {
+    id: String,
+    started: Instant,
+    last: Mutex<LastEmit>,
+    sink: ProgressSink,
+    cancelled: Option<Arc<AtomicBool>>,
+}
+
+impl ProgressReporter {
+    /// Reporter handing each event to `sink`.
+    pub fn new(id: String, sink: impl Fn(&ComputationProgress) + Send + Sync + 'static) -> Self {
+        let started = Instant::now();
+        Self {
+            id,
+            started,
+            last: Mutex::new(LastEmit {
+                iteration: 0,
+                at: started,
+            }),
+            sink: Box::new(sink),
+            cancelled: None,
+        }
+    }
+
+    /// Reporter whose computation stops once `cancelled` is set.
+    #[must_use]
+    pub fn with_cancellation(mut self, cancelled: Arc<AtomicBool>) -> Self {
+        self.cancelled = Some(cancelled);
+        self
+    }
+
+    /// Whether the computation was asked to stop.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+            .as_ref()
+            .is_some_and(|cancelled| cancelled.load(Ordering::Relaxed))
+    }
+
+    /// Reporter emitting `computation-progress` events to every window.
+    pub fn for_app(app: AppHandle, id: String) -> Self {
+        Self::new(id, move |progress| {
+            if let Err(e) = app.emit(PROGRESS_EVENT, progress) {
+                warn!("Failed to emit progress of '{}': {e}", progress.id);
+            }
+        })
+    }
+
+    /// Record that `iteration` iterations are done, emitting an event when
+    /// ten iterations or 250 ms have passed since the last one.
+    pub fn report(&self, iteration: usize, max_iterations: usize, chi_squared: Option<f64>) {
+        let now = Instant::now();
+        let mut last = self.last.lock().unwrap_or_else(PoisonError::into_inner);
+        let due = iteration >= last.iteration.saturating_add(EMIT_EVERY_ITERATIONS)
+            || (iteration > last.iteration && now.duration_since(last.at) >= EMIT_INTERVAL);
+        if due {
+            *last = LastEmit { iteration, at: now };
+            drop(last);
+            self.emit(iteration, max_iterations, chi_squared, now);
+        }
+    }
+
+    /// Count iterations from zero again, for a later stage of the
+    /// computation such as the bootstrap after a fit.
+    pub fn restart(&self) {
+        *self.last.lock().unwrap_or_else(PoisonError::into_inner) = LastEmit {
+            iteration: 0,
+            at: Instant::now(),
+        };
+    }
+
+    /// Emit the final state of the computation regardless of the throttle.
+    pub fn finish(&self, iteration: usize, max_iterations: usize, chi_squared: Option<f64>) {
+        let now = Instant::now();
+        *self.last.lock().unwrap_or_else(PoisonError::into_inner) = LastEmit { iteration, at: now };
+        self.emit(iteration, max_iterations, chi_squared, now);
+    }
+
+    fn emit(
+        &self,
+        iteration: usize,
+        max_iterations: usize,
+        chi_squared: Option<f64>,
+        now: Instant,
+    ) {
+        (self.sink)(&ComputationProgress {
+            id: self.id.clone(),
+            iteration,
+            max_iterations,
+            chi_squared,
+            elapsed_ms: u64::try_from(now.duration_since(self.started).as_millis())
+                .unwrap_or(u64::MAX),
+        });
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+
+    fn recording_reporter() -> (ProgressReporter, Arc<Mutex<Vec<ComputationProgress>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink_events = Arc::clone(&events);
+        let reporter = ProgressReporter::new("fit-1".to_owned(), move |progress| {
+            sink_events.lock().unwrap().push(progress.clone());
+        });
+        (reporter, events)
+    }
+
+    #[test]
+    fn test_emits_every_ten_iterations_or_interval() {
+        let (reporter, events) = recording_reporter();
+        for iteration in 0..=25 {
+            reporter.report(iteration, 100, Some(1.0));
+        }
+        let throttled: Vec<usize> = events.lock().unwrap().iter().map(|e| e.iteration).collect();
+        assert_eq!(throttled, [10, 20]);
+
+        reporter.report(21, 100, Some(0.5));
+        sleep(EMIT_INTERVAL + Duration::from_millis(20));
+        reporter.report(22, 100, Some(0.25));
+        reporter.finish(23, 100, Some(0.125));
+
+        let recorded = events.lock().unwrap().clone();
+        let iterations: Vec<usize> = recorded.iter().map(|e| e.iteration).collect();
+        assert_eq!(iterations, [10, 20, 22, 23]);
+        let last = recorded.last().unwrap();
+        assert_eq!(last.id, "fit-1");
+        assert_eq!(last.max_iterations, 100);
+        assert_eq!(last.chi_squared, Some(0.125));
+        assert!(last.elapsed_ms >= 250);
+
+        reporter.restart();
+        for resample in 1..=10 {
+            reporter.report(resample, 50, None);
+        }
+        let restarted = events.lock().unwrap().last().unwrap().clone();
+        assert_eq!((restarted.iteration, restarted.max_iterations), (10, 50));
+    }
+}
@@ -0,0 +1,183 @@
+// Python runtime detection
+
+use serde::Serialize;
+use std::env::var_os;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use tauri::State;
+
+/// Separator between entries of the `PATH` variable on this platform.
+#[cfg(target_os = "windows")]
+const PATH_SEPARATOR: char = ';';
+#[cfg(not(target_os = "windows"))]
+const PATH_SEPARATOR: char = ':';
+
+/// Interpreter names looked up in each `PATH` entry, in order of preference.
+const PYTHON_NAMES: [&str; 3] = ["python3", "python", "python.exe"];
+/// How long `python --version` may run before it is killed.
+const VERSION_TIMEOUT: Duration = Duration::from_secs(2);
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PythonStatus {
+    pub found: bool,
+    pub path: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Result of the first probe, shared by later `get_python_status` calls.
+#[derive(Debug, Default)]
+pub struct PythonRuntimeState(OnceLock<PythonStatus>);
+
+impl PythonRuntimeState {
+    /// Cached probe result, probing on first use.
+    pub fn status(&self) -> &PythonStatus {
+        self.0.get_or_init(probe_python)
+    }
+}
+
+/// Split a `PATH`-style list on `separator`, dropping empty entries.
+pub fn split_path_list(list: &str, separator: char) -> Vec<PathBuf> {
+    list.split(separator)
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// First Python interpreter found in `directories`.
+pub fn find_python(directories: &[PathBuf]) -> Option<PathBuf> {
+    directories.iter().find_map(|directory| {
+        PYTHON_NAMES
+            .iter()
+            .map(|name| directory.join(name))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// Version number from `python --version` output, e.g. `3.12.1` from
+/// `Python 3.12.1`.
+pub fn parse_python_version(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("Python ")
+            .map(str::trim)
+            .filter(|version| version.starts_with(|c: char| c.is_ascii_digit()))
+            .map(str::to_owned)
+    })
+}
+
+/// Run `python --version`, giving up after [`VERSION_TIMEOUT`].
+fn query_version(python: &Path) -> Option<String> {
+    let mut child = Command::new(python)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+    let started = Instant::now();
+    loop {
+        if child.try_wait().ok()?.is_some() {
+            break;
+        }
+        if started.elapsed() >= VERSION_TIMEOUT {
+            drop(child.kill());
+            drop(child.wait());
+            return None;
+        }
+        sleep(POLL_INTERVAL);
+    }
+    // Python 2 prints its version to stderr.
+    let mut output = String::new();
+    if let Some(mut stdout) = child.stdout.take() {
+        drop(stdout.read_to_string(&mut output));
+    }
+    if let Some(mut stderr) = child.stderr.take() {
+        drop(stderr.read_to_string(&mut output));
+    }
+    parse_python_version(&output)
+}
+
+/// Look for a Python interpreter on `PATH` and read its version.
+pub fn probe_python() -> PythonStatus {
+    let directories = var_os("PATH")
+        .map(|path| split_path_list(&path.to_string_lossy(), PATH_SEPARATOR))
+        .unwrap_or_default();
+    find_python(&directories).map_or(
+        PythonStatus {
+            found: false,
+            path: None,
+            version: None,
+        },
+        |python| PythonStatus {
+            found: true,
+            version: query_version(&python),
+            path: Some(python.to_string_lossy().into_owned()),
+        },
+    )
+}
+
+/// Report whether a Python interpreter is available on this machine.
+#[tauri::command]
+#[allow(
+    clippy::needless_pass_by_value,
+    reason = "Tauri commands require owned State"
+)]
+pub fn get_python_status(state: State<'_, PythonRuntimeState>) -> PythonStatus {
+    state.status().clone()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_list_splits_on_either_separator() {
+        assert_eq!(
+            split_path_list("/usr/local/bin::/usr/bin:", ':'),
+            [PathBuf::from("/usr/local/bin"), PathBuf::from("/usr/bin")]
+        );
+        assert_eq!(
+            split_path_list(r"C:\Python312;C:\Windows\System32;;", ';'),
+            [
+                PathBuf::from(r"C:\Python312"),
+                PathBuf::from(r"C:\Windows\System32")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_version_is_parsed_from_either_stream() {
+        assert_eq!(
+            parse_python_version("Python 3.12.1\n").as_deref(),
+            Some("3.12.1")
+        );
+        assert_eq!(
+            parse_python_version("\nPython 2.7.18\r\n").as_deref(),
+            Some("2.7.18")
+        );
+        assert_eq!(parse_python_version("Python was not found"), None);
+        assert_eq!(parse_python_version(""), None);
+    }
+
+    #[test]
+    fn test_find_python_prefers_earlier_directories() {
+        let root = std::env::temp_dir().join(format!("anafis-python-test-{}", std::process::id()));
+        let (first, second) = (root.join("first"), root.join("second"));
+        std::fs::create_dir_all(&first).unwrap();
+        std::fs::create_dir_all(&second).unwrap();
+        std::fs::write(second.join("python3"), "").unwrap();
+        std::fs::write(first.join("python"), "").unwrap();
+
+        let found = find_python(&[root.join("missing"), first.clone(), second]);
+        assert_eq!(found, Some(first.join("python")));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,416 @@
+// Crash-recovery snapshots of the active workbook
+//
+// The frontend sends the workbook on a timer; each snapshot is written as an
+// .anafispread envelope (with its checksums) into the app data recovery
+// directory. Writes go to a temporary file that is renamed into place, so a
+// crash mid-write never leaves a half-written snapshot behind. Only the last
+// `MAX_SNAPSHOTS` are kept.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Value, to_vec};
+use std::fs::{File, create_dir_all, read, read_dir, remove_file, rename};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State, command};
+use tokio::task::spawn_blocking;
+use tracing::warn;
+
+use crate::error::{CommandResult, import_error, internal_error, validation_error};
+use crate::export::anafispread::{PayloadCompression, encode_anafispread, sha256_hex};
+use crate::import::anafispread::decode_anafispread;
+
+/// Subdirectory of the app data dir holding the snapshots
+const RECOVERY_DIR: &str = "recovery";
+/// Extension of snapshot files, named `<id>.anafispread`
+const SNAPSHOT_EXTENSION: &str = "anafispread";
+/// Extension of snapshots still being written
+const TEMP_EXTENSION: &str = "tmp";
+/// Snapshots kept after pruning
+const MAX_SNAPSHOTS: usize = 5;
+/// Minimum time between two written snapshots
+const MIN_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// What `save_recovery_snapshot` did with the workbook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotOutcome {
+    /// A new snapshot was written
+    Saved,
+    /// The workbook matches the last snapshot
+    Unchanged,
+    /// The last snapshot is too recent
+    Throttled,
+}
+
+/// A snapshot offered for restoration
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoverySnapshot {
+    /// Identifier passed to `load_recovery_snapshot`
+    pub id: String,
+    /// RFC 3339 time the snapshot was taken
+    pub created_at: String,
+    /// Name of the workbook, when it has one
+    pub workbook_name: Option<String>,
+    /// File size in bytes
+    pub size: u64,
+}
+
+/// Hash and time of the last written snapshot
+#[derive(Debug, Default)]
+struct LastSnapshot {
+    hash: Option<String>,
+    written_at: Option<Instant>,
+    id: u64,
+}
+
+/// Recovery directory and the throttling state of the current session
+pub struct RecoveryStore {
+    dir: Option<PathBuf>,
+    min_interval: Duration,
+    last: Mutex<LastSnapshot>,
+}
+
+impl RecoveryStore {
+    /// Store in the app data dir; without one, snapshots are silently dropped.
+    #[must_use]
+    pub fn load(app: &AppHandle) -> Self {
+        let dir = app
+            .path()
+            .app_data_dir()
+            .ok()
+            .map(|dir| dir.join(RECOVERY_DIR));
+        Self::new(dir, MIN_SNAPSHOT_INTERVAL)
+    }
+
+    fn new(dir: Option<PathBuf>, min_interval: Duration) -> Self {
+        Self {
+            dir,
+            min_interval,
+            last: Mutex::new(LastSnapshot::default()),
+        }
+    }
+
+    /// Write a snapshot unless the workbook is unchanged or the last one is
+    /// too recent, then prune old snapshots
+    fn save(&self, workbook: &Value, now: Instant) -> Result<SnapshotOutcome, String> {
+        let Some(dir) = &self.dir else {
+            return Ok(SnapshotOutcome::Unchanged);
+        };
+        let json = to_vec(workbook).map_err(|e| format!("Failed to serialize workbook: {e}"))?;
+        let hash = sha256_hex(&json);
+
+        let mut last = self.last.lock().unwrap_or_else(PoisonError::into_inner);
+        if last.hash.as_ref() == Some(&hash) {
+            return Ok(SnapshotOutcome::Unchanged);
+        }
+        if last
+            .written_at
+            .is_some_and(|written_at| now.duration_since(written_at) < self.min_interval)
+        {
+            return Ok(SnapshotOutcome::Throttled);
+        }
+
+        create_dir_all(dir).map_err(|e| format!("Failed to create recovery dir: {e}"))?;
+        // Ids are creation times in milliseconds, bumped to stay unique and ordered
+        let id = snapshot_ids(dir)
+            .last()
+            .map_or(last.id, |&newest| newest.max(last.id))
+            .saturating_add(1)
+            .max(current_millis());
+        let bytes = encode_anafispread(workbook, PayloadCompression::Zstd)?;
+        write_atomically(&snapshot_path(dir, id), &bytes)?;
+        *last = LastSnapshot {
+            hash: Some(hash),
+            written_at: Some(now),
+            id,
+        };
+        // Pruning under the lock keeps it from deleting another save's temp file
+        prune_snapshots(dir);
+        drop(last);
+        Ok(SnapshotOutcome::Saved)
+    }
+
+    /// Valid snapshots, newest first; corrupted ones are skipped
+    fn list(&self) -> Vec<RecoverySnapshot> {
+        let Some(dir) = &self.dir else {
+            return Vec::new();
+        };
+        snapshot_ids(dir)
+            .into_iter()
+            .rev()
+            .filter_map(|id| {
+                let path = snapshot_path(dir, id);
+                let bytes = read(&path).ok()?;
+                let workbook = decode_anafispread(&bytes)
+                    .inspect_err(|e| warn!("Ignoring recovery snapshot {}: {e}", path.display()))
+                    .ok()?;
+                let created_at = i64::try_from(id)
+                    .ok()
+                    .and_then(DateTime::<Utc>::from_timestamp_millis)?
+                    .to_rfc3339();
+                Some(RecoverySnapshot {
+                    id: id.to_string(),
+                    created_at,
+                    workbook_name: workbook
+                        .get("name")
+                        .and_then(Value::as_str)
+                        .filter(|name| !name.is_empty())
+                        .map(str::to_owned),
+                    size: bytes.len() as u64,
+                })
+            })
+            .collect()
+    }
+
+    /// Workbook stored in snapshot `id`
+    fn load_snapshot(&self, id: &str) -> Result<Value, String> {
+        let dir = self.dir.as_ref().ok_or("No recovery directory available")?;
+        let id: u64 = id
+            .parse()
+            .map_err(|e| format!("Invalid recovery snapshot id '{id}': {e}"))?;
+        let bytes = read(snapshot_path(dir, id))
+            .map_err(|e| format!("Failed to read recovery snapshot: {e}"))?;
+        decode_anafispread(&bytes)
+    }
+
+    /// Delete every snapshot and forget the last hash
+    fn discard(&self) -> Result<(), String> {
+        *self.last.lock().unwrap_or_else(PoisonError::into_inner) = LastSnapshot::default();
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        let paths: Vec<PathBuf> = files_with_extension(dir, SNAPSHOT_EXTENSION)
+            .chain(files_with_extension(dir, TEMP_EXTENSION))
+            .collect();
+        for path in paths {
+            remove_file(&path).map_err(|e| {
+                format!("Failed to remove recovery snapshot {}: {e}", path.display())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Remove all but the newest `MAX_SNAPSHOTS` snapshots and leftover temp files
+fn prune_snapshots(dir: &Path) {
+    let ids = snapshot_ids(dir);
+    let stale = ids.len().saturating_sub(MAX_SNAPSHOTS);
+    let paths = ids[..stale]
+        .iter()
+        .map(|&id| snapshot_path(dir, id))
+        .chain(files_with_extension(dir, TEMP_EXTENSION));
+    for path in paths {
+        if let Err(e) = remove_file(&path) {
+            warn!(
+                "Failed to remove old recovery snapshot {}: {e}",
+                path.display()
+            );
+        }
+    }
+}
+
+/// Path of snapshot `id`
+fn snapshot_path(dir: &Path, id: u64) -> PathBuf {
+    dir.join(format!("{id}.{SNAPSHOT_EXTENSION}"))
+}
+
+/// Milliseconds since the Unix epoch
+fn current_millis() -> u64 {
+    u64::try_from(Utc::now().timestamp_millis()).unwrap_or_default()
+}
+
+/// Files in `dir` with the given extension; a missing dir has none
+fn files_with_extension<'ext>(
+    dir: &Path,
+    extension: &'ext str,
+) -> impl Iterator<Item = PathBuf> + 'ext {
+    read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(move |path| path.extension().is_some_and(|ext| ext == extension))
+}
+
+/// Ids of the snapshot files in `dir`, oldest first
+fn snapshot_ids(dir: &Path) -> Vec<u64> {
+    let mut ids: Vec<u64> = files_with_extension(dir, SNAPSHOT_EXTENSION)
+        .filter_map(|path| path.file_stem()?.to_str()?.parse().ok())
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+
+/// Write `bytes` to a temporary file next to `path`, then rename it into place
+fn write_atomically(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let temp_path = path.with_extension(TEMP_EXTENSION);
+    let written = File::create(&temp_path).and_then(|mut file| {
+        file.write_all(bytes)?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|()| rename(&temp_path, path)) {
+        drop(remove_file(&temp_path));
+        return Err(format!("Failed to write recovery snapshot: {e}"));
+    }
+    Ok(())
+}
+
+/// Run `operation` on the recovery store off the async executor
+async fn with_store<T: Send + 'static>(
+    app: AppHandle,
+    operation: impl FnOnce(&RecoveryStore) -> CommandResult<T> + Send + 'static,
+) -> CommandResult<T> {
+    spawn_blocking(move || {
+        let store: State<'_, RecoveryStore> = app
+            .try_state()
+            .ok_or_else(|| internal_error("Recovery store is not initialized"))?;
+        operation(&store)
+    })
+    .await
+    .map_err(|e| internal_error(format!("Recovery task panicked: {e}")))?
+}
+
+/// Snapshot the active workbook for crash recovery (called on a timer)
+#[command]
+pub async fn save_recovery_snapshot(
+    app: AppHandle,
+    workbook_json: Value,
+) -> CommandResult<SnapshotOutcome> {
+    with_store(app, move |store| {
+        store
+            .save(&workbook_json, Instant::now())
+            .map_err(internal_error)
+    })
+    .await
+}
+
+/// List the snapshots left by a previous session, newest first
+#[command]
+pub async fn check_recovery_snapshots(app: AppHandle) -> CommandResult<Vec<RecoverySnapshot>> {
+    with_store(app, |store| Ok(store.list())).await
+}
+
+/// Load the workbook of a recovery snapshot
+#[command]
+pub async fn load_recovery_snapshot(app: AppHandle, id: String) -> CommandResult<Value> {
+    if id.is_empty() || !id.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(validation_error(
+            format!("Invalid recovery snapshot id: {id}"),
+            Some("id".to_owned()),
+        ));
+    }
+    with_store(app, move |store| {
+        store
+            .load_snapshot(&id)
+            .map_err(|e| import_error(format!("Recovery snapshot import failed: {e}")))
+    })
+    .await
+}
+
+/// Delete all recovery snapshots
+#[command]
+pub async fn discard_recovery_snapshots(app: AppHandle) -> CommandResult<()> {
+    with_store(app, |store| store.discard().map_err(internal_error)).await
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::env::temp_dir;
+    use std::fs::{remove_dir_all, write};
+
+    fn store(name: &str, min_interval: Duration) -> RecoveryStore {
+        let dir = temp_dir().join(format!("anafis-recovery-{name}-{}", std::process::id()));
+        drop(remove_dir_all(&dir));
+        RecoveryStore::new(Some(dir), min_interval)
+    }
+
+    fn workbook(value: u32) -> Value {
+        json!({ "name": "Pendulum", "sheets": { "s1": { "cellData": { "0": { "0": { "v": value } } } } } })
+    }
+
+    #[test]
+    fn test_rotation_keeps_newest_snapshots() {
+        let store = store("rotation", Duration::ZERO);
+        let now = Instant::now();
+        for value in 0..8 {
+            assert_eq!(
+                store.save(&workbook(value), now).unwrap(),
+                SnapshotOutcome::Saved
+            );
+        }
+        let dir = store.dir.clone().unwrap();
+        assert_eq!(snapshot_ids(&dir).len(), MAX_SNAPSHOTS);
+        assert_eq!(files_with_extension(&dir, TEMP_EXTENSION).count(), 0);
+
+        let snapshots = store.list();
+        assert_eq!(snapshots.len(), MAX_SNAPSHOTS);
+        assert_eq!(snapshots[0].workbook_name.as_deref(), Some("Pendulum"));
+        assert_eq!(store.load_snapshot(&snapshots[0].id).unwrap(), workbook(7));
+        assert_eq!(
+            store
+                .load_snapshot(&snapshots[MAX_SNAPSHOTS - 1].id)
+                .unwrap(),
+            workbook(3)
+        );
+
+        store.discard().unwrap();
+        assert!(store.list().is_empty());
+        remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_unchanged_and_throttled_snapshots_are_skipped() {
+        let store = store("skip", Duration::from_mins(1));
+        let start = Instant::now();
+        assert_eq!(
+            store.save(&workbook(1), start).unwrap(),
+            SnapshotOutcome::Saved
+        );
+        let later = start + Duration::from_mins(2);
+        assert_eq!(
+            store.save(&workbook(1), later).unwrap(),
+            SnapshotOutcome::Unchanged
+        );
+        let soon = start + Duration::from_secs(5);
+        assert_eq!(
+            store.save(&workbook(2), soon).unwrap(),
+            SnapshotOutcome::Throttled
+        );
+        assert_eq!(
+            store.save(&workbook(2), later).unwrap(),
+            SnapshotOutcome::Saved
+        );
+
+        let dir = store.dir.unwrap();
+        assert_eq!(snapshot_ids(&dir).len(), 2);
+        remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupted_snapshot_is_ignored() {
+        let store = store("corrupted", Duration::ZERO);
+        let now = Instant::now();
+        store.save(&workbook(1), now).unwrap();
+        store.save(&workbook(2), now).unwrap();
+        let dir = store.dir.clone().unwrap();
+        let newest = *snapshot_ids(&dir).last().unwrap();
+        let path = snapshot_path(&dir, newest);
+        let mut bytes = read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        write(&path, bytes).unwrap();
+
+        let snapshots = store.list();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(store.load_snapshot(&snapshots[0].id).unwrap(), workbook(1));
+        assert!(store.load_snapshot(&newest.to_string()).is_err());
+        remove_dir_all(dir).unwrap();
+    }
+}
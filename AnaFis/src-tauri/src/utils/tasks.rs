@@ -0,0 +1,295 @@
+// Bounded execution of heavy computations
+//
+// Synchronous commands run on the IPC thread, so a long fit or forecast
+// blocks every other command until it returns. Heavy commands instead hand
+// their work to `run_heavy`, which runs it on the blocking thread pool while
+// holding one of a fixed number of slots; extra tasks wait for a slot
+// without occupying a thread.
+//
+// Slots are acquired on the async runtime before the work is spawned and
+// released by the blocking thread when the work ends. Rayon workers never
+// wait for a slot, so a task fanning out over the global rayon pool cannot
+// deadlock against tasks queued behind it.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::Instant;
+use tauri::async_runtime::spawn_blocking;
+use tokio::sync::Semaphore;
+
+/// Environment variable overriding the number of concurrent heavy tasks.
+const MAX_TASKS_ENV: &str = "ANAFIS_MAX_HEAVY_TASKS";
+
+/// Runner shared by the heavy commands, created on first use.
+static HEAVY_TASKS: OnceLock<TaskRunner> = OnceLock::new();
+
+/// Whether a task holds a slot yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskState {
+    /// Waiting for a free slot
+    Waiting,
+    /// Computing on the blocking pool
+    Running,
+}
+
+/// A heavy task submitted and not finished yet.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskInfo {
+    /// Identifier, increasing in submission order
+    pub id: u64,
+    /// Name of the command that submitted the task
+    pub name: String,
+    /// Whether the task holds a slot
+    pub state: TaskState,
+    /// Milliseconds since the task was submitted
+    pub elapsed_ms: u64,
+    /// Milliseconds since the task got a slot, while running
+    pub running_ms: Option<u64>,
+}
+
+struct TaskEntry {
+    name: String,
+    submitted: Instant,
+    started: Option<Instant>,
+}
+
+type TaskTable = Arc<Mutex<BTreeMap<u64, TaskEntry>>>;
+
+/// Removes a task from the table when it finishes or is abandoned.
+struct TaskGuard {
+    tasks: TaskTable,
+    id: u64,
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        lock(&self.tasks).remove(&self.id);
+    }
+}
+
+fn lock(tasks: &TaskTable) -> MutexGuard<'_, BTreeMap<u64, TaskEntry>> {
+    tasks.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Runs blocking jobs, a limited number of them at a time.
+pub struct TaskRunner {
+    slots: Arc<Semaphore>,
+    next_id: AtomicU64,
+    tasks: TaskTable,
+}
+
+impl TaskRunner {
+    /// Runner allowing `max_concurrent` tasks at once (at least one).
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            slots: Arc::new(Semaphore::new(max_concurrent.max(1))),
+            next_id: AtomicU64::new(1),
+            tasks: Arc::default(),
+        }
+    }
+
+    /// Run `job` on the blocking pool once a slot is free.
+    ///
+    /// The task is listed by `running_tasks` from submission until `job`
+    /// returns. Dropping the returned future while waiting gives up the
+    /// place in the queue; once running, the job completes and frees its
+    /// slot regardless.
+    ///
+    /// # Errors
+    /// Returns an error if the job panics.
+    pub async fn run<T, F>(&self, name: &str, job: F) -> Result<T, String>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        lock(&self.tasks).insert(
+            id,
+            TaskEntry {
+                name: name.to_owned(),
+                submitted: Instant::now(),
+                started: None,
+            },
+        );
+        let guard = TaskGuard {
+            tasks: Arc::clone(&self.tasks),
+            id,
+        };
+
+        let permit = Arc::clone(&self.slots)
+            .acquire_owned()
+            .await
+            .map_err(|e| format!("Task '{name}' could not get a slot: {e}"))?;
+        if let Some(entry) = lock(&self.tasks).get_mut(&id) {
+            entry.started = Some(Instant::now());
+        }
+
+        spawn_blocking(move || {
+            let output = job();
+            drop((permit, guard));
+            output
+        })
+        .await
+        .map_err(|e| format!("Task '{name}' failed: {e}"))
+    }
+
+    /// Tasks submitted and not finished, oldest first.
+    pub fn running_tasks(&self) -> Vec<TaskInfo> {
+        let now = Instant::now();
+        let millis = |since: Instant| {
+            u64::try_from(now.duration_since(since).as_millis()).unwrap_or(u64::MAX)
+        };
+        lock(&self.tasks)
+            .iter()
+            .map(|(&id, entry)| TaskInfo {
+                id,
+                name: entry.name.clone(),
+                state: if entry.started.is_some() {
+                    TaskState::Running
+                } else {
+                    TaskState::Waiting
+                },
+                elapsed_ms: millis(entry.submitted),
+                running_ms: entry.started.map(millis),
+            })
+            .collect()
+    }
+}
+
+/// Concurrent heavy tasks allowed by default: `ANAFIS_MAX_HEAVY_TASKS` when
+/// set, otherwise one less than the physical cores, leaving one for the
+/// interface. Logical cores would double the cap on SMT machines and
+/// oversubscribe rayon.
+fn default_max_tasks() -> usize {
+    env::var(MAX_TASKS_ENV)
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .unwrap_or_else(|| num_cpus::get_physical().saturating_sub(1))
+        .max(1)
+}
+
+/// Runner shared by the heavy commands.
+pub fn heavy_tasks() -> &'static TaskRunner {
+    HEAVY_TASKS.get_or_init(|| TaskRunner::new(default_max_tasks()))
+}
+
+/// Run `job` on the shared heavy-task runner.
+///
+/// # Errors
+/// Returns an error if the job panics.
+pub async fn run_heavy<T, F>(name: &str, job: F) -> Result<T, String>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    heavy_tasks().run(name, job).await
+}
+
+/// Heavy tasks submitted and not finished, oldest first.
+#[tauri::command]
+pub fn get_running_tasks() -> Vec<TaskInfo> {
+    heavy_tasks().running_tasks()
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::unwrap_used,
+    clippy::panic,
+    reason = "Tests use unwrap for brevity and panic to fail a job"
+)]
+mod tests {
+    use super::*;
+    use rayon::prelude::*;
+    use std::sync::mpsc::channel;
+    use std::thread::yield_now;
+    use std::time::Duration;
+    use tauri::async_runtime::{JoinHandle, block_on, spawn};
+
+    /// Submit `job` to `runner` from a separate async task.
+    fn submit<T: Send + 'static>(
+        runner: &Arc<TaskRunner>,
+        name: String,
+        job: impl FnOnce() -> T + Send + 'static,
+    ) -> JoinHandle<Result<T, String>> {
+        let runner = Arc::clone(runner);
+        spawn(async move { runner.run(&name, job).await })
+    }
+
+    #[test]
+    fn test_third_task_waits_while_two_run() {
+        let runner = Arc::new(TaskRunner::new(2));
+        let (started_tx, started_rx) = channel();
+        let mut releases = Vec::new();
+
+        let mut handles = Vec::new();
+        for index in 0..3 {
+            let started_tx = started_tx.clone();
+            let (release_tx, release_rx) = channel::<()>();
+            releases.push(release_tx);
+            handles.push(submit(&runner, format!("job {index}"), move || {
+                started_tx.send(index).unwrap();
+                // Released when the sender is dropped
+                assert!(release_rx.recv().is_err());
+                index
+            }));
+        }
+
+        // Two jobs hold both slots until released; the third must wait
+        let timeout = Duration::from_secs(5);
+        started_rx.recv_timeout(timeout).unwrap();
+        started_rx.recv_timeout(timeout).unwrap();
+        let deadline = Instant::now() + timeout;
+        let listed = loop {
+            let listed = runner.running_tasks();
+            if listed.len() == 3 {
+                break listed;
+            }
+            assert!(Instant::now() < deadline, "third task was never submitted");
+            yield_now();
+        };
+        let running = listed
+            .iter()
+            .filter(|task| task.state == TaskState::Running)
+            .count();
+        assert_eq!(running, 2);
+        let waiting: Vec<&TaskInfo> = listed
+            .iter()
+            .filter(|task| task.state == TaskState::Waiting)
+            .collect();
+        assert_eq!(waiting.len(), 1);
+        assert!(waiting[0].running_ms.is_none());
+        assert!(started_rx.try_recv().is_err());
+
+        drop(releases);
+        let mut results: Vec<usize> = handles
+            .into_iter()
+            .map(|handle| block_on(handle).unwrap().unwrap())
+            .collect();
+        results.sort_unstable();
+        assert_eq!(results, [0, 1, 2]);
+        assert!(runner.running_tasks().is_empty());
+    }
+
+    #[test]
+    fn test_rayon_jobs_and_panics_release_slots() {
+        let runner = Arc::new(TaskRunner::new(1));
+        let large = submit(&runner, "sum".to_owned(), || {
+            (0..10_000_u64).into_par_iter().sum::<u64>()
+        });
+        let small = submit(&runner, "sum".to_owned(), || {
+            (0..100_u64).into_par_iter().sum::<u64>()
+        });
+        assert_eq!(block_on(large).unwrap().unwrap(), 49_995_000);
+        assert_eq!(block_on(small).unwrap().unwrap(), 4_950);
+
+        let panicked = block_on(runner.run("panic", || -> u64 { panic!("fit diverged") }));
+        assert!(panicked.unwrap_err().contains("Task 'panic' failed"));
+        assert_eq!(block_on(runner.run("after", || 1_u8)).unwrap(), 1);
+        assert!(runner.running_tasks().is_empty());
+    }
+}
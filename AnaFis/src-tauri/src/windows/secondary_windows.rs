@@ -1,6 +1,8 @@
 // src-tauri/src/secondary_windows.rs
 
-use crate::windows::window_manager::{WindowConfig, create_or_focus_window};
+use crate::error::{CommandResult, window_error};
+use crate::windows::window_manager::{WindowConfig, close_window, create_or_focus_window};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::thread::{sleep, spawn};
 use std::time::Duration;
@@ -11,11 +13,179 @@ use tokio::time::timeout;
 use tracing::{error, info};
 use urlencoding::encode;
 
+/// Label of the LaTeX preview window, which is rebuilt per formula rather
+/// than toggled.
+const LATEX_PREVIEW_LABEL: &str = "latex-preview";
+
+/// Static description of a tool window opened from the main window.
+#[allow(
+    clippy::struct_excessive_bools,
+    reason = "Window specs naturally involve many flags"
+)]
+pub struct ToolWindowSpec {
+    pub label: &'static str,
+    pub title: &'static str,
+    pub url: &'static str,
+    pub width: f64,
+    pub height: f64,
+    pub min_width: f64,
+    pub min_height: f64,
+    pub resizable: bool,
+    pub transparent: bool,
+    /// Attach to the main window so it stays above it and closes with it.
+    pub parent_modal: bool,
+    pub focus_on_create: bool,
+}
+
+impl ToolWindowSpec {
+    fn config(&self) -> WindowConfig {
+        WindowConfig {
+            title: self.title.to_owned(),
+            url: self.url.to_owned(),
+            width: self.width,
+            height: self.height,
+            resizable: self.resizable,
+            decorations: false,
+            transparent: self.transparent,
+            always_on_top: true,
+            skip_taskbar: true,
+            parent: self.parent_modal.then(|| "main".to_owned()),
+            min_width: Some(self.min_width),
+            min_height: Some(self.min_height),
+            focus_on_create: self.focus_on_create,
+        }
+    }
+}
+
+/// Every tool window that `toggle_tool_window` can open.
+pub const TOOL_WINDOWS: &[ToolWindowSpec] = &[
+    ToolWindowSpec {
+        label: "uncertainty-calculator",
+        title: "Uncertainty Calculator",
+        url: "uncertainty-calculator.html",
+        // Wide and tall enough for the two-column layout with the rendered
+        // formula section always visible.
+        width: 600.0,
+        height: 670.0,
+        min_width: 600.0,
+        min_height: 670.0,
+        resizable: true,
+        transparent: false,
+        parent_modal: true,
+        focus_on_create: true,
+    },
+    ToolWindowSpec {
+        label: "settings",
+        title: "AnaFis Settings",
+        url: "settings.html",
+        width: 650.0,
+        height: 700.0,
+        min_width: 500.0,
+        min_height: 500.0,
+        resizable: true,
+        transparent: true,
+        parent_modal: true,
+        focus_on_create: true,
+    },
+    ToolWindowSpec {
+        label: "data-library",
+        title: "Data Library",
+        url: "data-library.html",
+        width: 1000.0,
+        height: 700.0,
+        min_width: 700.0,
+        min_height: 500.0,
+        resizable: true,
+        transparent: true,
+        parent_modal: true,
+        focus_on_create: false,
+    },
+];
+
+/// Look up a tool window by label.
+pub fn tool_window_spec(window_id: &str) -> Option<&'static ToolWindowSpec> {
+    TOOL_WINDOWS.iter().find(|spec| spec.label == window_id)
+}
+
+/// Labels of every secondary window that must close with the main window.
+pub fn child_window_labels() -> impl Iterator<Item = &'static str> {
+    TOOL_WINDOWS
+        .iter()
+        .map(|spec| spec.label)
+        .chain([LATEX_PREVIEW_LABEL])
+}
+
+/// What `toggle_tool_window` should do with a window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolWindowAction {
+    /// Create the window, or focus it if it is already open.
+    #[default]
+    Open,
+    /// Close the window if it is open.
+    Close,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolWindowOptions {
+    #[serde(default)]
+    pub action: ToolWindowAction,
+}
+
+/// Step taken for a requested action, given whether the window exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToolWindowStep {
+    Create,
+    Focus,
+    Close,
+    Nothing,
+}
+
+const fn plan_tool_window(exists: bool, action: ToolWindowAction) -> ToolWindowStep {
+    match (action, exists) {
+        (ToolWindowAction::Open, false) => ToolWindowStep::Create,
+        (ToolWindowAction::Open, true) => ToolWindowStep::Focus,
+        (ToolWindowAction::Close, true) => ToolWindowStep::Close,
+        (ToolWindowAction::Close, false) => ToolWindowStep::Nothing,
+    }
+}
+
+fn apply_tool_window_action(
+    app: &AppHandle,
+    window_id: &str,
+    action: ToolWindowAction,
+) -> CommandResult<()> {
+    let spec = tool_window_spec(window_id)
+        .ok_or_else(|| window_error(format!("Unknown tool window '{window_id}'")))?;
+    let exists = app.get_webview_window(spec.label).is_some();
+    match plan_tool_window(exists, action) {
+        ToolWindowStep::Create | ToolWindowStep::Focus => {
+            create_or_focus_window(app, spec.label, spec.config())
+        }
+        ToolWindowStep::Close => close_window(app, spec.label),
+        ToolWindowStep::Nothing => Ok(()),
+    }
+}
+
+/// Open, focus or close a registered tool window.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub async fn toggle_tool_window(
+    app: AppHandle,
+    window_id: String,
+    options: Option<ToolWindowOptions>,
+) -> CommandResult<()> {
+    // NOTE: window creation in sync commands can deadlock on Windows (WebView2 issue).
+    // Keeping this command async avoids that platform-specific lockup.
+    let action = options.unwrap_or_default().action;
+    apply_tool_window_action(&app, &window_id, action)
+}
+
 #[tauri::command]
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
-pub fn close_uncertainty_calculator_window(app: AppHandle) -> Result<(), String> {
-    crate::windows::window_manager::close_window(&app, "uncertainty-calculator")
-        .map_err(|e| e.to_string())
+pub fn close_uncertainty_calculator_window(app: AppHandle) -> CommandResult<()> {
+    apply_tool_window_action(&app, "uncertainty-calculator", ToolWindowAction::Close)
 }
 
 #[tauri::command]
@@ -24,93 +194,38 @@ pub fn resize_uncertainty_calculator_window(
     app: AppHandle,
     width: f64,
     height: f64,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     crate::windows::window_manager::resize_window(&app, "uncertainty-calculator", width, height)
-        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
-pub async fn open_uncertainty_calculator_window(app: AppHandle) -> Result<(), String> {
-    // NOTE: window creation in sync commands can deadlock on Windows (WebView2 issue).
-    // Keeping this command async avoids that platform-specific lockup.
-    let config = WindowConfig {
-        title: "Uncertainty Calculator".to_owned(),
-        url: "uncertainty-calculator.html".to_owned(),
-        width: 600.0,  // Wider default to accommodate two-column layout properly
-        height: 670.0, // Increased default height for more content
-        resizable: true,
-        decorations: false,
-        transparent: false,
-        always_on_top: true,
-        skip_taskbar: true,
-        parent: Some("main".to_owned()),
-        min_width: Some(600.0), // More reasonable minimum width for two columns
-        min_height: Some(670.0), // Increased minimum height to ensure rendered formula section is always visible
-        focus_on_create: true,
-    };
-
-    create_or_focus_window(&app, "uncertainty-calculator", config).map_err(|e| e.to_string())
+pub async fn open_uncertainty_calculator_window(app: AppHandle) -> CommandResult<()> {
+    toggle_tool_window(app, "uncertainty-calculator".to_owned(), None).await
 }
 
 #[tauri::command]
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
-pub fn close_settings_window(app: AppHandle) -> Result<(), String> {
-    crate::windows::window_manager::close_window(&app, "settings").map_err(|e| e.to_string())
+pub fn close_settings_window(app: AppHandle) -> CommandResult<()> {
+    apply_tool_window_action(&app, "settings", ToolWindowAction::Close)
 }
 
 #[tauri::command]
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
-pub async fn open_settings_window(app: AppHandle) -> Result<(), String> {
-    // NOTE: window creation in sync commands can deadlock on Windows (WebView2 issue).
-    // Keeping this command async avoids that platform-specific lockup.
-    let config = WindowConfig {
-        title: "AnaFis Settings".to_owned(),
-        url: "settings.html".to_owned(),
-        width: 650.0,
-        height: 700.0,
-        resizable: true,
-        decorations: false,
-        transparent: true,
-        always_on_top: true,
-        skip_taskbar: true,
-        parent: Some("main".to_owned()),
-        min_width: Some(500.0),
-        min_height: Some(500.0),
-        focus_on_create: true,
-    };
-
-    create_or_focus_window(&app, "settings", config).map_err(|e| e.to_string())
+pub async fn open_settings_window(app: AppHandle) -> CommandResult<()> {
+    toggle_tool_window(app, "settings".to_owned(), None).await
 }
 
 #[tauri::command]
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
-pub fn close_data_library_window(app: AppHandle) -> Result<(), String> {
-    crate::windows::window_manager::close_window(&app, "data-library").map_err(|e| e.to_string())
+pub fn close_data_library_window(app: AppHandle) -> CommandResult<()> {
+    apply_tool_window_action(&app, "data-library", ToolWindowAction::Close)
 }
 
 #[tauri::command]
 #[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
-pub async fn open_data_library_window(app: AppHandle) -> Result<(), String> {
-    // NOTE: window creation in sync commands can deadlock on Windows (WebView2 issue).
-    // Keeping this command async avoids that platform-specific lockup.
-    let config = WindowConfig {
-        title: "Data Library".to_owned(),
-        url: "data-library.html".to_owned(),
-        width: 1000.0,
-        height: 700.0,
-        resizable: true,
-        decorations: false,
-        transparent: true,
-        always_on_top: true,
-        skip_taskbar: true,
-        parent: Some("main".to_owned()),
-        min_width: Some(700.0),
-        min_height: Some(500.0),
-        focus_on_create: false,
-    };
-
-    create_or_focus_window(&app, "data-library", config).map_err(|e| e.to_string())
+pub async fn open_data_library_window(app: AppHandle) -> CommandResult<()> {
+    toggle_tool_window(app, "data-library".to_owned(), None).await
 }
 
 #[tauri::command]
@@ -119,7 +234,7 @@ pub async fn open_latex_preview_window(
     app: AppHandle,
     latex_formula: String,
     title: String,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     // Debug logging
     info!(
         "Opening LaTeX preview window with formula: {}..., title: {}",
@@ -133,7 +248,7 @@ pub async fn open_latex_preview_window(
     let new_url = format!("latex-preview.html?formula={encoded_formula}&title={encoded_title}");
 
     // Check if window already exists and destroy it
-    if let Some(existing_window) = app.get_webview_window("latex-preview") {
+    if let Some(existing_window) = app.get_webview_window(LATEX_PREVIEW_LABEL) {
         info!("Destroying existing LaTeX preview window");
 
         // Create a notify to wait for window destruction
@@ -158,7 +273,9 @@ pub async fn open_latex_preview_window(
                 "Failed to destroy existing LaTeX preview window: {}",
                 destroy_err
             );
-            return Err(format!("Failed to destroy existing window: {destroy_err}"));
+            return Err(window_error(format!(
+                "Failed to destroy existing window: {destroy_err}"
+            )));
         }
 
         // Wait for the window to be fully destroyed with a shorter timeout
@@ -170,31 +287,31 @@ pub async fn open_latex_preview_window(
             info!("Existing LaTeX preview window destroyed successfully");
         } else {
             error!("Timeout waiting for window destruction - window may not be fully destroyed");
-            return Err(
-                "Failed to destroy existing window: timeout waiting for destruction confirmation"
-                    .to_owned(),
-            );
+            return Err(window_error(
+                "Failed to destroy existing window: timeout waiting for destruction confirmation",
+            ));
         }
     }
 
     info!("Creating new LaTeX preview window");
-    let window = WebviewWindowBuilder::new(&app, "latex-preview", WebviewUrl::App(new_url.into()))
-        .title(&title)
-        .decorations(false)
-        .resizable(true)
-        .inner_size(500.0_f64, 225.0_f64)
-        .min_inner_size(400.0_f64, 225.0_f64)
-        .max_inner_size(1600.0_f64, 225.0_f64)
-        .transparent(false)
-        .always_on_top(true)
-        .skip_taskbar(true)
-        .closable(true)
-        .visible(false)
-        .build()
-        .map_err(|e| {
-            error!("Failed to create window: {}", e);
-            format!("Failed to create window: {e}")
-        })?;
+    let window =
+        WebviewWindowBuilder::new(&app, LATEX_PREVIEW_LABEL, WebviewUrl::App(new_url.into()))
+            .title(&title)
+            .decorations(false)
+            .resizable(true)
+            .inner_size(500.0_f64, 225.0_f64)
+            .min_inner_size(400.0_f64, 225.0_f64)
+            .max_inner_size(1600.0_f64, 225.0_f64)
+            .transparent(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .closable(true)
+            .visible(false)
+            .build()
+            .map_err(|e| {
+                error!("Failed to create window: {}", e);
+                window_error(format!("Failed to create window: {e}"))
+            })?;
 
     // Ensure initial background is dark/transparent while hidden.
     drop(window.set_background_color(Some(Color(0, 0, 0, 0))));
@@ -210,7 +327,7 @@ pub async fn open_latex_preview_window(
     let app_handle = app.clone();
     spawn(move || {
         sleep(Duration::from_millis(2200));
-        if let Some(fallback_window) = app_handle.get_webview_window("latex-preview")
+        if let Some(fallback_window) = app_handle.get_webview_window(LATEX_PREVIEW_LABEL)
             && matches!(fallback_window.is_visible(), Ok(false))
         {
             drop(fallback_window.show());
@@ -221,3 +338,53 @@ pub async fn open_latex_preview_window(
     info!("LaTeX preview window opened successfully");
     Ok(())
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_creates_missing_window_and_focuses_existing_one() {
+        assert_eq!(
+            plan_tool_window(false, ToolWindowAction::Open),
+            ToolWindowStep::Create
+        );
+        // A second open must focus the window rather than spawn a duplicate.
+        assert_eq!(
+            plan_tool_window(true, ToolWindowAction::Open),
+            ToolWindowStep::Focus
+        );
+        assert_eq!(
+            plan_tool_window(true, ToolWindowAction::Close),
+            ToolWindowStep::Close
+        );
+        assert_eq!(
+            plan_tool_window(false, ToolWindowAction::Close),
+            ToolWindowStep::Nothing
+        );
+    }
+
+    #[test]
+    fn test_every_registered_window_is_cleaned_up_with_main() {
+        let labels: Vec<&str> = child_window_labels().collect();
+        for spec in TOOL_WINDOWS {
+            assert!(
+                labels.contains(&spec.label),
+                "{} not cleaned up",
+                spec.label
+            );
+            assert_eq!(tool_window_spec(spec.label).map(|s| s.url), Some(spec.url));
+        }
+        assert!(labels.contains(&LATEX_PREVIEW_LABEL));
+        assert!(tool_window_spec("main").is_none());
+    }
+
+    #[test]
+    fn test_options_default_to_open() {
+        let defaults: ToolWindowOptions = serde_json::from_str("{}").unwrap();
+        assert_eq!(defaults.action, ToolWindowAction::Open);
+        let closing: ToolWindowOptions = serde_json::from_str(r#"{"action":"close"}"#).unwrap();
+        assert_eq!(closing.action, ToolWindowAction::Close);
+    }
+}
@@ -1,9 +1,27 @@
 // src-tauri/src/window_manager.rs
-use crate::error::{CommandResult, window_error};
+use crate::error::{CommandResult, internal_error, io_error, window_error};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+use std::fs::{create_dir_all, read_to_string, remove_file, write};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
 use std::thread::{sleep, spawn};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tauri::webview::Color;
-use tauri::{AppHandle, Listener, Manager, PhysicalSize, Size, WebviewUrl, WebviewWindowBuilder};
+use tauri::{
+    AppHandle, Listener, Manager, Monitor, PhysicalPosition, PhysicalSize, Position, Size,
+    WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent,
+};
+use tracing::warn;
+
+/// File in the app config dir holding the saved window layout.
+const LAYOUT_FILE: &str = "window-layout.json";
+/// Quiet period after the last move or resize before the layout is written.
+const LAYOUT_SAVE_DELAY: Duration = Duration::from_millis(500);
+/// Overlap, in pixels per axis, a window needs with a monitor to count as visible.
+const MIN_VISIBLE_PIXELS: i64 = 64;
 
 #[allow(
     clippy::struct_excessive_bools,
@@ -50,16 +68,17 @@ pub fn create_or_focus_window(
     window_id: &str,
     config: WindowConfig,
 ) -> CommandResult<()> {
-    // Check if window already exists
+    // Bring an existing window back instead of spawning a duplicate
     if let Some(existing_window) = app.get_webview_window(window_id) {
+        existing_window
+            .unminimize()
+            .map_err(|e| window_error(e.to_string()))?;
         existing_window
             .show()
             .map_err(|e| window_error(e.to_string()))?;
-        if config.focus_on_create {
-            existing_window
-                .set_focus()
-                .map_err(|e| window_error(e.to_string()))?;
-        }
+        existing_window
+            .set_focus()
+            .map_err(|e| window_error(e.to_string()))?;
         return Ok(());
     }
 
@@ -101,6 +120,10 @@ pub fn create_or_focus_window(
     // Ensure transparent background is set (redundant but safe)
     drop(window.set_background_color(Some(Color(0, 0, 0, 0))));
 
+    // Put the window back where the user left it; it is still hidden here.
+    restore_window_layout(app, &window);
+    track_window_layout(app, &window);
+
     // Show only after the frontend has rendered at least one frame.
     // This avoids white/blank flashes on WebView2 during window startup.
     let focus_on_ready = config.focus_on_create;
@@ -175,6 +198,450 @@ pub fn set_window_size(
     window_id: String,
     width: f64,
     height: f64,
-) -> Result<(), String> {
-    resize_window(&app, &window_id, width, height).map_err(|e| e.message)
+) -> CommandResult<()> {
+    resize_window(&app, &window_id, width, height)
+}
+
+/// Saved size and position of one window, in physical pixels.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// Whether the window was maximized; the other fields keep its normal geometry.
+    #[serde(default)]
+    pub maximized: bool,
+    /// Name of the monitor the window was on, if known.
+    #[serde(default)]
+    pub monitor: Option<String>,
+}
+
+/// Saved geometry of every tracked window, keyed by window label.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowLayout {
+    pub windows: BTreeMap<String, WindowGeometry>,
+}
+
+impl WindowLayout {
+    /// Parse a saved layout, falling back to an empty one if it is corrupted.
+    #[must_use]
+    pub fn from_json(text: &str) -> Self {
+        serde_json::from_str(text).unwrap_or_default()
+    }
+
+    pub fn to_json(&self) -> CommandResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| internal_error(format!("Failed to serialize window layout: {e}")))
+    }
+}
+
+/// Screen area of a monitor, in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonitorBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl From<&Monitor> for MonitorBounds {
+    fn from(monitor: &Monitor) -> Self {
+        Self {
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+        }
+    }
+}
+
+impl MonitorBounds {
+    /// Whether enough of `geometry` lies on this monitor to grab and move it.
+    fn shows(&self, geometry: &WindowGeometry) -> bool {
+        let overlap = |start: i32, length: u32, other_start: i32, other_length: u32| {
+            let end = (i64::from(start) + i64::from(length))
+                .min(i64::from(other_start) + i64::from(other_length));
+            end - i64::from(start.max(other_start))
+        };
+        let horizontal = overlap(self.x, self.width, geometry.x, geometry.width);
+        let vertical = overlap(self.y, self.height, geometry.y, geometry.height);
+        horizontal >= MIN_VISIBLE_PIXELS.min(i64::from(geometry.width))
+            && vertical >= MIN_VISIBLE_PIXELS.min(i64::from(geometry.height))
+    }
+}
+
+/// Keep `geometry` if it is visible on one of `monitors`; otherwise shrink it
+/// to fit and move it onto `primary` (or the first monitor).
+#[must_use]
+pub fn clamp_to_monitors(
+    geometry: &WindowGeometry,
+    monitors: &[MonitorBounds],
+    primary: Option<MonitorBounds>,
+) -> WindowGeometry {
+    if monitors.iter().any(|monitor| monitor.shows(geometry)) {
+        return geometry.clone();
+    }
+    let Some(target) = primary.or_else(|| monitors.first().copied()) else {
+        return geometry.clone();
+    };
+    let width = geometry.width.min(target.width);
+    let height = geometry.height.min(target.height);
+    let clamp = |start: i32, length: u32, origin: i32, extent: u32| {
+        let last = i64::from(origin) + i64::from(extent) - i64::from(length);
+        let clamped = i64::from(start).clamp(i64::from(origin), last);
+        i32::try_from(clamped).unwrap_or(origin)
+    };
+    WindowGeometry {
+        x: clamp(geometry.x, width, target.x, target.width),
+        y: clamp(geometry.y, height, target.y, target.height),
+        width,
+        height,
+        maximized: geometry.maximized,
+        monitor: None,
+    }
+}
+
+/// Trailing-edge debounce: a flush becomes due once no event arrived for `delay`.
+#[derive(Debug)]
+pub struct Debounce {
+    delay: Duration,
+    deadline: Option<Instant>,
+}
+
+impl Debounce {
+    #[must_use]
+    pub const fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            deadline: None,
+        }
+    }
+
+    /// Record an event at `now`; returns `true` if no flush was pending, in
+    /// which case the caller starts one.
+    pub fn touch(&mut self, now: Instant) -> bool {
+        let idle = self.deadline.is_none();
+        self.deadline = Some(now + self.delay);
+        idle
+    }
+
+    /// Time left before the pending flush, or `None` once it is due (which
+    /// ends the pending cycle) or if nothing is pending.
+    pub fn remaining(&mut self, now: Instant) -> Option<Duration> {
+        let deadline = self.deadline?;
+        if deadline > now {
+            Some(deadline - now)
+        } else {
+            self.deadline = None;
+            None
+        }
+    }
+}
+
+/// Window layout kept in memory and mirrored to the app config dir.
+pub struct WindowLayoutStore {
+    path: Option<PathBuf>,
+    layout: Mutex<WindowLayout>,
+    debounce: Mutex<Debounce>,
+}
+
+impl WindowLayoutStore {
+    /// Load the saved layout; a missing config dir or file yields an empty layout.
+    #[must_use]
+    pub fn load(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .ok()
+            .map(|dir| dir.join(LAYOUT_FILE));
+        let layout = path
+            .as_ref()
+            .and_then(|file| read_to_string(file).ok())
+            .map(|text| WindowLayout::from_json(&text))
+            .unwrap_or_default();
+        Self {
+            path,
+            layout: Mutex::new(layout),
+            debounce: Mutex::new(Debounce::new(LAYOUT_SAVE_DELAY)),
+        }
+    }
+
+    fn layout(&self) -> WindowLayout {
+        self.layout
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn save(&self) -> CommandResult<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = self.layout().to_json()?;
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir)
+                .map_err(|e| internal_error(format!("Failed to create config dir: {e}")))?;
+        }
+        write(path, json).map_err(|e| internal_error(format!("Failed to save window layout: {e}")))
+    }
+}
+
+/// Current geometry of `window`, or `None` while it is minimized.
+fn current_geometry(window: &WebviewWindow) -> Option<WindowGeometry> {
+    if window.is_minimized().unwrap_or(false) {
+        return None;
+    }
+    let position = window.outer_position().ok()?;
+    let size = window.inner_size().ok()?;
+    Some(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: window.is_maximized().unwrap_or(false),
+        monitor: window
+            .current_monitor()
+            .ok()
+            .flatten()
+            .and_then(|monitor| monitor.name().cloned()),
+    })
+}
+
+/// Store the geometry of `window` and schedule a debounced write.
+fn record_geometry(app: &AppHandle, window: &WebviewWindow) {
+    let (Some(store), Some(geometry)) = (
+        app.try_state::<WindowLayoutStore>(),
+        current_geometry(window),
+    ) else {
+        return;
+    };
+    {
+        let mut layout = store.layout.lock().unwrap_or_else(PoisonError::into_inner);
+        match layout.windows.entry(window.label().to_owned()) {
+            // A maximized window keeps its normal geometry for un-maximizing.
+            Entry::Occupied(mut saved) if geometry.maximized => {
+                saved.get_mut().maximized = true;
+            }
+            Entry::Occupied(mut saved) => {
+                *saved.get_mut() = geometry;
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(geometry);
+            }
+        }
+    }
+
+    let start_flush = store
+        .debounce
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .touch(Instant::now());
+    if start_flush {
+        let app_handle = app.clone();
+        spawn(move || {
+            let Some(flusher) = app_handle.try_state::<WindowLayoutStore>() else {
+                return;
+            };
+            loop {
+                let remaining = flusher
+                    .debounce
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .remaining(Instant::now());
+                match remaining {
+                    Some(wait) => sleep(wait),
+                    None => break,
+                }
+            }
+            if let Err(e) = flusher.save() {
+                warn!("{e}");
+            }
+        });
+    }
+}
+
+/// Save the geometry of `window` whenever it is moved or resized.
+pub fn track_window_layout(app: &AppHandle, window: &WebviewWindow) {
+    let app_handle = app.clone();
+    let tracked = window.clone();
+    window.on_window_event(move |event| {
+        if matches!(event, WindowEvent::Moved(_) | WindowEvent::Resized(_)) {
+            record_geometry(&app_handle, &tracked);
+        }
+    });
+}
+
+/// Apply the saved geometry of `window`, clamped onto a connected monitor.
+pub fn restore_window_layout(app: &AppHandle, window: &WebviewWindow) {
+    let Some(store) = app.try_state::<WindowLayoutStore>() else {
+        return;
+    };
+    let Some(saved) = store.layout().windows.get(window.label()).cloned() else {
+        return;
+    };
+    let monitors: Vec<MonitorBounds> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(MonitorBounds::from)
+        .collect();
+    let primary = window
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .map(|monitor| MonitorBounds::from(&monitor));
+    let geometry = clamp_to_monitors(&saved, &monitors, primary);
+
+    drop(window.set_size(Size::Physical(PhysicalSize {
+        width: geometry.width,
+        height: geometry.height,
+    })));
+    drop(window.set_position(Position::Physical(PhysicalPosition {
+        x: geometry.x,
+        y: geometry.y,
+    })));
+    if geometry.maximized {
+        drop(window.maximize());
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn get_window_layout(app: AppHandle) -> CommandResult<WindowLayout> {
+    app.try_state::<WindowLayoutStore>()
+        .map(|store| store.layout())
+        .ok_or_else(|| internal_error("Window layout is not initialized"))
+}
+
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value, reason = "Tauri command")]
+pub fn reset_window_layout(app: AppHandle) -> CommandResult<()> {
+    let store = app
+        .try_state::<WindowLayoutStore>()
+        .ok_or_else(|| internal_error("Window layout is not initialized"))?;
+    store
+        .layout
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .windows
+        .clear();
+    store
+        .path
+        .as_ref()
+        .map_or(Ok(()), |path| match remove_file(path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(io_error(
+                Some(path.to_string_lossy().into_owned()),
+                format!("Failed to remove window layout: {e}"),
+            )),
+            _ => Ok(()),
+        })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, reason = "Tests use unwrap for brevity")]
+mod tests {
+    use super::*;
+
+    const PRIMARY: MonitorBounds = MonitorBounds {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+    };
+
+    fn geometry(x: i32, y: i32, width: u32, height: u32) -> WindowGeometry {
+        WindowGeometry {
+            x,
+            y,
+            width,
+            height,
+            maximized: false,
+            monitor: Some("DP-1".to_owned()),
+        }
+    }
+
+    #[test]
+    fn test_layout_round_trips_through_json() {
+        let mut layout = WindowLayout::default();
+        layout
+            .windows
+            .insert("data-library".to_owned(), geometry(-1200, 40, 1000, 700));
+        let mut main = geometry(0, 0, 1280, 800);
+        main.maximized = true;
+        layout.windows.insert("main".to_owned(), main);
+
+        let json = layout.to_json().unwrap();
+        assert!(json.contains("\"maximized\": true"));
+        assert_eq!(WindowLayout::from_json(&json), layout);
+        assert_eq!(
+            WindowLayout::from_json("{not json"),
+            WindowLayout::default()
+        );
+    }
+
+    #[test]
+    fn test_off_screen_window_is_clamped_to_primary() {
+        let secondary = MonitorBounds {
+            x: 1920,
+            y: 0,
+            width: 2560,
+            height: 1440,
+        };
+        let on_secondary = geometry(2500, 200, 1000, 700);
+        assert_eq!(
+            clamp_to_monitors(&on_secondary, &[PRIMARY, secondary], Some(PRIMARY)),
+            on_secondary
+        );
+
+        // The secondary monitor was unplugged.
+        let clamped = clamp_to_monitors(&on_secondary, &[PRIMARY], Some(PRIMARY));
+        assert_eq!((clamped.x, clamped.y), (920, 200));
+        assert_eq!((clamped.width, clamped.height), (1000, 700));
+
+        // Larger than the primary monitor: shrink to fit at its origin.
+        let huge = clamp_to_monitors(&geometry(5000, -3000, 3000, 2000), &[PRIMARY], None);
+        assert_eq!(
+            huge,
+            WindowGeometry {
+                monitor: None,
+                ..geometry(0, 0, 1920, 1080)
+            }
+        );
+
+        // A sliver of a window is not enough to grab it.
+        let sliver = clamp_to_monitors(&geometry(1900, 100, 600, 400), &[PRIMARY], Some(PRIMARY));
+        assert_eq!((sliver.x, sliver.y), (1320, 100));
+
+        // Without any monitor information the geometry is left alone.
+        let unknown = geometry(9000, 9000, 800, 600);
+        assert_eq!(clamp_to_monitors(&unknown, &[], None), unknown);
+    }
+
+    #[test]
+    fn test_debounce_writes_once_per_drag() {
+        let mut debounce = Debounce::new(LAYOUT_SAVE_DELAY);
+        let start = Instant::now();
+        let mut flushes_started = 0;
+        for step in 0..100 {
+            let now = start + Duration::from_millis(10 * step);
+            if debounce.touch(now) {
+                flushes_started += 1;
+            }
+            assert!(debounce.remaining(now).is_some());
+        }
+        assert_eq!(flushes_started, 1);
+
+        let last_event = start + Duration::from_millis(990);
+        assert_eq!(
+            debounce.remaining(last_event + Duration::from_millis(200)),
+            Some(Duration::from_millis(300))
+        );
+        assert_eq!(debounce.remaining(last_event + LAYOUT_SAVE_DELAY), None);
+        assert_eq!(debounce.remaining(last_event + LAYOUT_SAVE_DELAY), None);
+
+        // The next drag starts a new flush.
+        assert!(debounce.touch(last_event + Duration::from_secs(5)));
+    }
 }
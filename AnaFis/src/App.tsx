@@ -12,6 +12,7 @@ import {
 } from '@dnd-kit/core';
 import { Box } from '@mui/material';
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import React, {
   lazy,
   useCallback,
@@ -37,6 +38,68 @@ import { DraggableTabBar } from '@/shared/components/DraggableTabBar';
 import DragOverlayComponent from '@/shared/components/DragOverlayComponent';
 import OptimizedTabRenderer from '@/shared/components/OptimizedTabRenderer';
 import { anafisTheme } from '@/shared/theme/unifiedTheme';
+import {
+  DEFAULT_SHEET_COLS,
+  DEFAULT_SHEET_ROWS,
+} from '@/tabs/spreadsheet/univer/utils/constants';
+
+/** File the backend asks the frontend to open (see `startup` module). */
+interface FileOpenRequest {
+  path: string;
+  format: 'anafispread' | 'csv' | 'tsv' | 'parquet';
+}
+
+/** File from the command line that failed backend validation. */
+interface FileOpenError {
+  path: string;
+  message: string;
+}
+
+interface ImportedSheets {
+  sheets: Record<string, (string | number | null)[][]>;
+}
+
+/**
+ * Build a workbook snapshot from plain imported sheets so they open through
+ * the same pending-data path as `.anafispread` files.
+ */
+function sheetsToWorkbookSnapshot(
+  name: string,
+  sheets: ImportedSheets['sheets']
+): WorkbookData {
+  const snapshotSheets: Record<string, unknown> = {};
+  const sheetOrder: string[] = [];
+  Object.entries(sheets).forEach(([sheetName, rows], index) => {
+    const id = `sheet-${String(index + 1).padStart(2, '0')}`;
+    const cellData: Record<number, Record<number, { v: string | number }>> =
+      {};
+    rows.forEach((row, r) => {
+      row.forEach((value, c) => {
+        if (value !== null) {
+          (cellData[r] ??= {})[c] = { v: value };
+        }
+      });
+    });
+    const widest = rows.reduce((max, row) => Math.max(max, row.length), 0);
+    snapshotSheets[id] = {
+      id,
+      name: sheetName || 'Imported Data',
+      cellData,
+      rowCount: Math.max(rows.length, DEFAULT_SHEET_ROWS),
+      columnCount: Math.max(widest, DEFAULT_SHEET_COLS),
+    };
+    sheetOrder.push(id);
+  });
+  return {
+    id: `workbook-${Date.now()}`,
+    name,
+    appVersion: '1.0.0',
+    locale: 'EN_US',
+    styles: {},
+    sheets: snapshotSheets,
+    sheetOrder,
+  } as unknown as WorkbookData;
+}
 
 // Lazy load tab components for code splitting
 const HomeTab = lazy(() => import('@/tabs/home/HomeTab'));
@@ -157,51 +220,44 @@ function App() {
     }
   }, [tabs.length, storeAddTab, createTabContent]);
 
-  // Check for file associations on mount
+  // Open files from file association or a forwarded second launch. The
+  // backend queues them until `frontend_ready` confirms the listeners exist.
   useEffect(() => {
     let isMounted = true;
+    let unlisteners: UnlistenFn[] = [];
 
-    // We only want to process this once on mount
-    const checkStartupFile = async () => {
+    const openFile = async ({ path, format }: FileOpenRequest) => {
       try {
-        const filePath = await invoke<string | null>('get_startup_file');
-        if (filePath && isMounted) {
-          console.log('Startup file detected:', filePath);
-
-          // Import the .anafispread file
-          const workbookData = await invoke<WorkbookData>(
+        const fileName = path.split('/').pop() ?? 'Opened File';
+        let workbookData: WorkbookData;
+        if (format === 'anafispread') {
+          workbookData = await invoke<WorkbookData>(
             'import_anafis_spread_direct',
-            { filePath }
+            { filePath: path }
           );
-
-          const fileName =
-            filePath
-              .split('/')
-              .pop()
-              ?.replace(/\.anafispread|\.anafis/i, '') ?? 'Opened File';
-          const tabId = `spreadsheet-opened-${Date.now()}`;
-
-          // Instead of immediate adding via ref which could be missing, schedule it
-          // if handleAddTabRef isn't perfectly registered yet
-          if (handleAddTabRef.current) {
-            handleAddTabRef.current(tabId, fileName, undefined, workbookData);
-          } else {
-            // Give the app a moment to finish mounting the callback ref
-            setTimeout(() => {
-              if (handleAddTabRef.current) {
-                handleAddTabRef.current(
-                  tabId,
-                  fileName,
-                  undefined,
-                  workbookData
-                );
-              }
-            }, 100);
-          }
+        } else {
+          const imported = await invoke<ImportedSheets>(
+            'import_spreadsheet_file',
+            {
+              filePath: path,
+              options: {
+                format,
+                skipRows: 0,
+                delimiter: '',
+                encoding: 'utf8',
+              },
+            }
+          );
+          workbookData = sheetsToWorkbookSnapshot(fileName, imported.sheets);
         }
+        if (!isMounted) return;
+
+        const title = fileName.replace(/\.(anafispread|csv|tsv|parquet)$/i, '');
+        const tabId = `spreadsheet-opened-${crypto.randomUUID()}`;
+        handleAddTabRef.current?.(tabId, title, undefined, workbookData);
       } catch (error) {
         if (!isMounted) return;
-        console.error('Failed to open startup file:', error);
+        console.error('Failed to open file:', error);
         const errorMessage =
           error instanceof Error ? error.message : 'Unknown error occurred';
         showNotification({
@@ -211,10 +267,31 @@ function App() {
       }
     };
 
-    void checkStartupFile();
+    const registerFileOpenListeners = async () => {
+      const listeners = await Promise.all([
+        listen<FileOpenRequest>('anafis://open-file', (event) => {
+          void openFile(event.payload);
+        }),
+        listen<FileOpenError>('anafis://file-open-error', (event) => {
+          showNotification({
+            type: 'error',
+            message: `Cannot open ${event.payload.path}: ${event.payload.message}`,
+          });
+        }),
+      ]);
+      if (!isMounted) {
+        listeners.forEach((unlisten) => unlisten());
+        return;
+      }
+      unlisteners = listeners;
+      await invoke('frontend_ready');
+    };
+
+    void registerFileOpenListeners();
 
     return () => {
       isMounted = false;
+      unlisteners.forEach((unlisten) => unlisten());
     };
   }, [showNotification]);
 
@@ -1,34 +1,35 @@
 // Standardized error types for Tauri commands
 //
-// This module provides consistent error response types that match the Rust backend.
-// Error responses include error codes, messages, and optional details for better
-// frontend error handling and user experience.
+// This module mirrors `CommandError` from the Rust backend. Every failed command
+// rejects with `{ code, detail, field }`, plus `path` for file system errors, so
+// the frontend can branch on the code and localize the message.
 
 enum ErrorCode {
   // Generic errors
   InternalError = 'INTERNAL_ERROR',
   InvalidInput = 'INVALID_INPUT',
   NotFound = 'NOT_FOUND',
-  PermissionDenied = 'PERMISSION_DENIED',
-  Timeout = 'TIMEOUT',
+  Cancelled = 'CANCELLED',
 
   // File system errors
   FileNotFound = 'FILE_NOT_FOUND',
   FileAccessDenied = 'FILE_ACCESS_DENIED',
   FileCorrupted = 'FILE_CORRUPTED',
-  PathValidationFailed = 'PATH_VALIDATION_FAILED',
 
   // Database errors
   DatabaseError = 'DATABASE_ERROR',
-  DatabaseConnectionFailed = 'DATABASE_CONNECTION_FAILED',
-  RecordNotFound = 'RECORD_NOT_FOUND',
-  DuplicateRecord = 'DUPLICATE_RECORD',
 
   // Conversion/calculation errors
   ConversionFailed = 'CONVERSION_FAILED',
-  InvalidUnit = 'INVALID_UNIT',
-  IncompatibleUnits = 'INCOMPATIBLE_UNITS',
   CalculationError = 'CALCULATION_ERROR',
+  MatrixSingular = 'MATRIX_SINGULAR',
+  InsufficientData = 'INSUFFICIENT_DATA',
+  ZeroVariance = 'ZERO_VARIANCE',
+  NotImplemented = 'NOT_IMPLEMENTED',
+
+  // Formula errors
+  UnknownSymbols = 'UNKNOWN_SYMBOLS',
+  CompileFailed = 'COMPILE_FAILED',
 
   // Import/Export errors
   ImportFailed = 'IMPORT_FAILED',
@@ -38,16 +39,16 @@ enum ErrorCode {
 
   // Data validation errors
   ValidationError = 'VALIDATION_ERROR',
-  MissingRequiredField = 'MISSING_REQUIRED_FIELD',
-  InvalidDataType = 'INVALID_DATA_TYPE',
+
+  // Window errors
+  WindowError = 'WINDOW_ERROR',
 }
 
 export interface ErrorResponse {
-  version: string;
   code: ErrorCode;
-  message: string;
-  details?: string;
-  field?: string;
+  detail: string;
+  field: string | null;
+  path?: string | null;
 }
 
 // Type alias for command results using standardized errors
@@ -61,13 +62,21 @@ export function isErrorResponse<T>(
     typeof result === 'object' &&
     result !== null &&
     'code' in result &&
-    'message' in result
+    'detail' in result
   );
 }
 
 export function getErrorMessage(error: ErrorResponse): string {
   if (error.field) {
-    return `${error.field}: ${error.message}`;
+    return `${error.field}: ${error.detail}`;
+  }
+  return error.detail;
+}
+
+// Message for anything a failed command or a throwing helper rejects with
+export function describeError(error: unknown): string {
+  if (isErrorResponse(error)) {
+    return getErrorMessage(error);
   }
-  return error.message;
+  return error instanceof Error ? error.message : String(error);
 }
@@ -7,7 +7,7 @@ import type { SpreadsheetRef } from '@/tabs/spreadsheet/types/SpreadsheetInterfa
  * Export format types - streamlined hierarchy
  *
  * PRIMARY (Lossless): anafispread - native format for full workbook preservation
- * SIMPLE INTERCHANGE: csv, tsv, txt, parquet - for external application interaction
+ * SIMPLE INTERCHANGE: csv, tsv, txt, json, parquet, arrow, xml, hdf5 - for external application interaction
  * READ-ONLY DOCUMENTS: html, markdown, tex - for reports and documentation (no options, just custom delimiter for txt)
  */
 export type ExportFormat =
@@ -15,7 +15,11 @@ export type ExportFormat =
   | 'csv'
   | 'tsv'
   | 'txt'
-  | 'parquet' // Simple interchange formats
+  | 'json'
+  | 'parquet'
+  | 'arrow' // Arrow IPC (Feather v2)
+  | 'xml'
+  | 'hdf5' // Simple interchange formats
   | 'html'
   | 'markdown'
   | 'tex'; // Read-only document formats
@@ -39,6 +43,24 @@ export interface ExportOptions {
   delimiter?: string; // Only for txt format (csv=comma, tsv=tab fixed)
   encoding?: 'utf8' | 'latin1' | 'utf16'; // For supporting formats
   trackedBounds?: Record<string, { maxRow: number; maxCol: number }> | null; // Performance optimization
+  includeHeaders?: boolean; // Treat the first row as headers (tex, html)
+  latexStyle?: 'plain' | 'booktabs';
+  latexSiunitx?: boolean; // siunitx S columns for numeric data
+  uncertaintyColumnPairs?: [number, number][]; // [value, uncertainty] column indices merged as 12.34(5)
+  htmlStandalone?: boolean; // Complete HTML document with embedded CSS
+  htmlTheme?: 'light' | 'dark';
+  htmlZebra?: boolean; // Shade every other row
+  htmlStickyHeader?: boolean; // Header row stays visible while scrolling
+  htmlSortable?: boolean; // Embedded script sorts by the clicked header
+  inferTypes?: boolean; // Parse numeric/boolean strings into JSON types
+  jsonSchema?: boolean; // Emit column names and inferred types
+  jsonFormat?: 'json' | 'jsonl';
+  compress?: boolean; // Gzip the output (json)
+  dateFormat?: string; // chrono format of date strings to convert to ISO 8601 (json)
+  xmlRootElement?: string; // Root element name (default: data)
+  xmlRowElement?: string; // Element name of each row (default: row)
+  xmlCellAsAttribute?: boolean; // Cells as row attributes instead of child elements
+  overwrite?: boolean; // Replace existing files when exporting one file per sheet
 }
 
 /**
@@ -41,6 +41,10 @@ export interface FileMetadata {
   extension: string;
   rowCount?: number;
   columnCount?: number;
+  /** Format version of native .anafispread files */
+  formatVersion?: number;
+  /** Delimiter detected in csv, tsv and txt files */
+  detectedDelimiter?: string;
 }
 
 /**
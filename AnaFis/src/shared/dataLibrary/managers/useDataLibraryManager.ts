@@ -8,6 +8,7 @@ import type {
   UpdateSequenceRequest,
 } from '@/core/types/dataLibrary';
 import {
+  describeError,
   type ErrorResponse,
   getErrorMessage,
   isErrorResponse,
@@ -106,9 +107,7 @@ export const useDataLibrary = () => {
       setError(null);
     } catch (err) {
       console.error('Failed to load sequences:', err);
-      setError(
-        `Failed to load sequences: ${err instanceof Error ? err.message : String(err)}`
-      );
+      setError(`Failed to load sequences: ${describeError(err)}`);
     } finally {
       setIsLoadingSequences(false);
     }
@@ -126,9 +125,7 @@ export const useDataLibrary = () => {
       setError(null);
     } catch (err) {
       console.error('Failed to load tags:', err);
-      setError(
-        `Failed to load tags: ${err instanceof Error ? err.message : String(err)}`
-      );
+      setError(`Failed to load tags: ${describeError(err)}`);
     } finally {
       setIsLoadingTags(false);
     }
@@ -148,9 +145,7 @@ export const useDataLibrary = () => {
       setError(null);
     } catch (err) {
       console.error('Failed to load statistics:', err);
-      setError(
-        `Failed to load statistics: ${err instanceof Error ? err.message : String(err)}`
-      );
+      setError(`Failed to load statistics: ${describeError(err)}`);
     }
   }, []);
 
@@ -194,9 +189,7 @@ export const useDataLibrary = () => {
         }
       } catch (err) {
         console.error('Failed to pin sequence:', err);
-        setError(
-          `Failed to pin sequence: ${err instanceof Error ? err.message : String(err)}`
-        );
+        setError(`Failed to pin sequence: ${describeError(err)}`);
       }
     },
     [selectedSequence, loadSequences]
@@ -222,9 +215,7 @@ export const useDataLibrary = () => {
         await loadSequences();
       } catch (err) {
         console.error('Failed to delete sequence:', err);
-        setError(
-          `Failed to delete sequence: ${err instanceof Error ? err.message : String(err)}`
-        );
+        setError(`Failed to delete sequence: ${describeError(err)}`);
       }
     },
     [selectedSequence, loadSequences]
@@ -308,9 +299,7 @@ export const useDataLibrary = () => {
       } catch (err) {
         // This should rarely happen since we're using allSettled, but handle unexpected errors
         console.error('Unexpected error during bulk delete:', err);
-        setError(
-          `Unexpected error during bulk delete: ${err instanceof Error ? err.message : String(err)}`
-        );
+        setError(`Unexpected error during bulk delete: ${describeError(err)}`);
         // Still reload sequences to be safe
         await loadSequences();
       }
@@ -333,9 +322,7 @@ export const useDataLibrary = () => {
         await loadSequences();
       } catch (err) {
         console.error('Failed to duplicate sequence:', err);
-        setError(
-          `Failed to duplicate sequence: ${err instanceof Error ? err.message : String(err)}`
-        );
+        setError(`Failed to duplicate sequence: ${describeError(err)}`);
       }
     },
     [loadSequences]
@@ -375,9 +362,7 @@ export const useDataLibrary = () => {
         }
       } catch (err) {
         console.error('Failed to update sequence:', err);
-        setError(
-          `Failed to update sequence: ${err instanceof Error ? err.message : String(err)}`
-        );
+        setError(`Failed to update sequence: ${describeError(err)}`);
       }
     },
     [selectedSequence, loadSequences]
@@ -18,6 +18,7 @@ import {
 import { invoke } from '@tauri-apps/api/core';
 import { useCallback, useEffect, useMemo, useState } from 'react';
 import type { DataSequence } from '@/core/types/dataLibrary';
+import { describeError } from '@/core/types/error';
 import { anafisTheme } from '@/shared/theme/unifiedTheme';
 import { openWithMemory } from '@/shared/utils/dialogMemory';
 import CsvSettingsDialog from '@/tabs/fitting/components/CsvSettingsDialog';
@@ -189,7 +190,7 @@ export default function DataSourceSection({
         });
       } catch (error) {
         onDataImported(null);
-        setImportError(error instanceof Error ? error.message : String(error));
+        setImportError(describeError(error));
       }
     },
     [onDataImported]
@@ -250,7 +251,7 @@ export default function DataSourceSection({
       onDataImported(parsed);
     } catch (error) {
       onDataImported(null);
-      setImportError(error instanceof Error ? error.message : String(error));
+      setImportError(describeError(error));
     }
   }, [csvSettings, onDataImported]);
 
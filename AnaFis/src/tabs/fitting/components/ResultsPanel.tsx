@@ -87,6 +87,8 @@ function terminationLabel(reason: string): {
       return { text: 'Damping saturated', color: 'warning' };
     case 'maxIterations':
       return { text: 'Max iterations', color: 'warning' };
+    case 'linearSolution':
+      return { text: 'Solved (linear model)', color: 'success' };
     default:
       return { text: reason, color: 'info' };
   }
@@ -1,6 +1,7 @@
 // Central state management hook for the Fit tab
 
 import { useCallback, useMemo, useState } from 'react';
+import { describeError } from '@/core/types/error';
 import {
   AXES,
   INITIAL_FIT_STATE,
@@ -329,7 +330,7 @@ export function useFitState() {
       setState((s) => ({
         ...s,
         fitStatus: 'error' as FitStatus,
-        fitError: err instanceof Error ? err.message : String(err),
+        fitError: describeError(err),
       }));
     }
   }, [buildRequest]);
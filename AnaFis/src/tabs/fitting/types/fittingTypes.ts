@@ -31,6 +31,24 @@ export interface OdrFitRequest {
   initialDamping?: number;
   confidenceLevel?: number;
   pointCorrelations?: number[][][]; // [point][dim][dim]
+  verifyGradients?: boolean;
+  numericalGradients?: boolean;
+  multiStart?: MultiStartConfig;
+  allowFastPath?: boolean;
+}
+
+export interface MultiStartConfig {
+  nStarts: number;
+  parameterRanges: [number, number][]; // [min, max] per parameter
+  seed?: number;
+}
+
+export interface StartSummary {
+  initialParameters: number[];
+  chiSquared?: number;
+  iterations: number;
+  converged: boolean;
+  error?: string;
 }
 
 export interface OdrFitResponse {
@@ -68,6 +86,7 @@ export interface OdrFitResponse {
   welchSatterthwaiteDof?: number;
   coverageDegreesOfFreedom?: number;
   assumptions: string[];
+  startsSummary?: StartSummary[];
 }
 
 export interface GridEvaluationResponse {
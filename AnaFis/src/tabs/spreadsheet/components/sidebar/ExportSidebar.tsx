@@ -244,6 +244,7 @@ const ExportSidebar = React.memo<ExportSidebarProps>(
                   </MenuItem>
                   <MenuItem value="tsv">📊 TSV (Tab-separated values)</MenuItem>
                   <MenuItem value="txt">📊 TXT (Custom delimiter)</MenuItem>
+                  <MenuItem value="json">📊 JSON / JSON Lines (.json)</MenuItem>
                   <MenuItem value="parquet">📊 Parquet (.parquet)</MenuItem>
 
                   {/* READ-ONLY DOCUMENTS: Report formats */}
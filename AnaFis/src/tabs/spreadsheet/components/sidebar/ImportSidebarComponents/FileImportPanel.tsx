@@ -29,6 +29,7 @@ import type {
   ImportService,
   ImportTargetMode,
 } from '@/core/types/import';
+import { describeError } from '@/core/types/error';
 import { anafisColors } from '@/shared/theme';
 import { RangeValidationWarning } from '@/tabs/spreadsheet/components/sidebar/ImportSidebarComponents/RangeValidationWarning';
 import { useImportValidation } from '@/tabs/spreadsheet/components/sidebar/logic/useImportValidation';
@@ -181,9 +182,7 @@ export const FileImportPanel: React.FC<FileImportPanelProps> = ({
         setError(result.error.message);
       }
     } catch (err) {
-      setError(
-        `Import failed: ${err instanceof Error ? err.message : String(err)}`
-      );
+      setError(`Import failed: ${describeError(err)}`);
     } finally {
       setIsImporting(false);
     }
@@ -18,6 +18,7 @@ import type React from 'react';
 import { useCallback, useEffect, useState } from 'react';
 import type { DataSequence } from '@/core/types/dataLibrary';
 import {
+  describeError,
   type ErrorResponse,
   getErrorMessage,
   isErrorResponse,
@@ -123,9 +124,7 @@ export const LibraryImportPanel: React.FC<LibraryImportPanelProps> = ({
 
       setAvailableSequences(result.sequences);
     } catch (err) {
-      setError(
-        `Failed to load sequences: ${err instanceof Error ? err.message : String(err)}`
-      );
+      setError(`Failed to load sequences: ${describeError(err)}`);
     } finally {
       setIsLoadingSequences(false);
     }
@@ -233,9 +232,7 @@ export const LibraryImportPanel: React.FC<LibraryImportPanelProps> = ({
         `✅ Imported "${sequence.name}" (${sequence.data.length} data points${includeUncertainties && sequence.uncertainties ? `, ${sequence.uncertainties.length} uncertainties` : ''})`
       );
     } catch (err) {
-      setError(
-        `Import failed: ${err instanceof Error ? err.message : String(err)}`
-      );
+      setError(`Import failed: ${describeError(err)}`);
     } finally {
       setIsImporting(false);
     }
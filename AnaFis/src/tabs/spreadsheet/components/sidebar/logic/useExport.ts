@@ -14,6 +14,7 @@ import type {
   ExportOptions,
   ExportService,
 } from '@/core/types/export';
+import { describeError } from '@/core/types/error';
 import type {
   ExportFormat,
   ExportMode,
@@ -193,9 +194,7 @@ export function useExport({
         setError(result.error.message);
       }
     } catch (err) {
-      setError(
-        `Failed to save to Data Library: ${err instanceof Error ? err.message : String(err)}`
-      );
+      setError(`Failed to save to Data Library: ${describeError(err)}`);
     } finally {
       setIsExporting(false);
     }
@@ -13,6 +13,7 @@ import type {
   ImportOptions,
   ImportService,
 } from '@/core/types/import';
+import { describeError } from '@/core/types/error';
 import type {
   ImportMode,
   ImportSidebarState,
@@ -90,9 +91,7 @@ export function useImport({
       }
       return false;
     } catch (error) {
-      setError(
-        `File selection failed: ${error instanceof Error ? error.message : String(error)}`
-      );
+      setError(`File selection failed: ${describeError(error)}`);
       return false;
     }
   }, [importService]);
@@ -124,9 +123,7 @@ export function useImport({
           setError(result.error.message);
         }
       } catch (error) {
-        setError(
-          `Import failed: ${error instanceof Error ? error.message : String(error)}`
-        );
+        setError(`Import failed: ${describeError(error)}`);
       } finally {
         setIsImporting(false);
       }
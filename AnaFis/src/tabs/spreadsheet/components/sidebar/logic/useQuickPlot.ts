@@ -13,6 +13,7 @@
 import { invoke } from '@tauri-apps/api/core';
 import { save } from '@tauri-apps/plugin-dialog';
 import { useCallback, useMemo, useRef, useState } from 'react';
+import { describeError } from '@/core/types/error';
 import { Plotly } from '@/shared/components/PlotlyChart';
 import { CHART_COLORS, getThemeLayout } from '@/shared/components/plotlyTheme';
 import type {
@@ -191,9 +192,7 @@ export function useQuickPlot({
       setHasPlot(true);
       return true;
     } catch (err) {
-      setError(
-        `Range validation failed: ${err instanceof Error ? err.message : String(err)}`
-      );
+      setError(`Range validation failed: ${describeError(err)}`);
       return false;
     }
   }, [xRange, yRange, errorRange, showErrorBars, spreadsheetRef]);
@@ -416,7 +415,7 @@ export function useQuickPlot({
       setExportDialogOpen(false);
       setIsExporting(false);
     } catch (error) {
-      setError(`Export failed: ${String(error)}`);
+      setError(`Export failed: ${describeError(error)}`);
       setExportDialogOpen(false);
       setIsExporting(false);
     }
@@ -466,7 +465,7 @@ export function useQuickPlot({
       setYUnit('');
       setSequenceTags(['quick_plot']);
     } catch (error) {
-      setError(`Failed to save: ${String(error)}`);
+      setError(`Failed to save: ${describeError(error)}`);
     }
   }, [
     xSequenceName,
@@ -42,7 +42,11 @@ export const useSpreadsheetUnitConversion = ({
   // Check unit compatibility when both units are selected
   const checkCompatibility = useCallback(async () => {
     try {
-      const compatible: boolean = await invoke('check_unit_compatibility', {
+      const { compatible } = await invoke<{
+        compatible: boolean;
+        is_affine: boolean;
+        note: string | null;
+      }>('check_unit_compatibility', {
         fromUnit,
         toUnit,
       });
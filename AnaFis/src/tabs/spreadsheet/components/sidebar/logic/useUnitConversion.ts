@@ -7,6 +7,7 @@
 
 import { invoke } from '@tauri-apps/api/core';
 import { useCallback, useEffect, useState } from 'react';
+import { describeError } from '@/core/types/error';
 
 interface UseUnitConversionOptions {
   onSelectionChange?: (selection: string) => void;
@@ -30,6 +31,7 @@ interface ConversionRequest {
   value: number;
   from_unit: string;
   to_unit: string;
+  quantity_kind?: 'absolute' | 'difference'; // Differences skip offsets such as °C → K
 }
 
 interface ConversionResult {
@@ -127,7 +129,7 @@ export function useUnitConversion({
     } catch (error) {
       return {
         success: false,
-        error: error instanceof Error ? error.message : 'Conversion failed',
+        error: describeError(error),
       };
     }
   }, [value, category, fromUnit, toUnit]);
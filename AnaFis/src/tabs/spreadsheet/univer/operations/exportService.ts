@@ -26,10 +26,14 @@ type ExportFormat =
   | 'csv'
   | 'tsv'
   | 'txt'
+  | 'json'
   | 'parquet'
   | 'html'
   | 'markdown'
   | 'tex'
+  | 'xml'
+  | 'hdf5'
+  | 'arrow'
   | 'anafispread';
 
 interface ExportResult {
@@ -50,10 +54,14 @@ const FILE_FILTERS: Record<
   csv: { name: 'CSV Files', extensions: ['csv'] },
   tsv: { name: 'TSV Files', extensions: ['tsv'] },
   txt: { name: 'Text Files', extensions: ['txt'] },
+  json: { name: 'JSON Files', extensions: ['json', 'jsonl', 'gz'] },
   parquet: { name: 'Parquet Files', extensions: ['parquet'] },
   html: { name: 'HTML Files', extensions: ['html'] },
   markdown: { name: 'Markdown Files', extensions: ['md'] },
   tex: { name: 'LaTeX Files', extensions: ['tex'] },
+  xml: { name: 'XML Files', extensions: ['xml'] },
+  hdf5: { name: 'HDF5 Files', extensions: ['h5', 'hdf5'] },
+  arrow: { name: 'Arrow IPC / Feather Files', extensions: ['arrow', 'feather'] },
   anafispread: { name: 'AnaFis Spreadsheet', extensions: ['anafispread'] },
 };
 
@@ -148,6 +156,24 @@ export class ExportService implements ExportService {
         format: options.format,
         config: {
           delimiter: options.delimiter ?? ',',
+          includeHeaders: options.includeHeaders ?? false,
+          latexStyle: options.latexStyle ?? 'plain',
+          latexSiunitx: options.latexSiunitx ?? false,
+          uncertaintyColumnPairs: options.uncertaintyColumnPairs ?? [],
+          htmlStandalone: options.htmlStandalone ?? true,
+          htmlTheme: options.htmlTheme ?? 'light',
+          htmlZebra: options.htmlZebra ?? true,
+          htmlStickyHeader: options.htmlStickyHeader ?? false,
+          htmlSortable: options.htmlSortable ?? false,
+          inferTypes: options.inferTypes ?? true,
+          jsonSchema: options.jsonSchema ?? false,
+          jsonFormat: options.jsonFormat ?? 'json',
+          compress: options.compress ?? false,
+          dateFormat: options.dateFormat ?? null,
+          xmlRootElement: options.xmlRootElement ?? 'data',
+          xmlRowElement: options.xmlRowElement ?? 'row',
+          xmlCellAsAttribute: options.xmlCellAsAttribute ?? false,
+          overwrite: options.overwrite ?? false,
         },
       });
 
@@ -309,10 +309,19 @@ export function normalizeError(
     return classifyError(error, operation);
   }
 
-  // Object with message
-  if (error && typeof error === 'object' && 'message' in error) {
-    const err = error as { message: unknown; code?: unknown; type?: unknown };
-    const message = String(err.message);
+  // Object with message, or a backend CommandError with detail
+  if (
+    error &&
+    typeof error === 'object' &&
+    ('message' in error || 'detail' in error)
+  ) {
+    const err = error as {
+      message?: unknown;
+      detail?: unknown;
+      code?: unknown;
+      type?: unknown;
+    };
+    const message = String(err.message ?? err.detail);
     const code =
       err.code !== null && typeof err.code === 'string' ? err.code : undefined;
     const type =
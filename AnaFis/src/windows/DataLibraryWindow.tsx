@@ -37,6 +37,7 @@ import type {
   DataSequence,
   UpdateSequenceRequest,
 } from '@/core/types/dataLibrary';
+import { describeError } from '@/core/types/error';
 import CustomTitleBar from '@/shared/components/CustomTitleBar';
 import { UnifiedButton } from '@/shared/components/UnifiedButton';
 import SequenceDetails from '@/shared/dataLibrary/components/SequenceDetails';
@@ -355,9 +356,7 @@ export const DataLibraryWindowContent: React.FC = () => {
       setEditDialogOpen(false);
     } catch (err) {
       console.error('Failed to update sequence:', err);
-      setError(
-        `Failed to update sequence: ${err instanceof Error ? err.message : String(err)}`
-      );
+      setError(`Failed to update sequence: ${describeError(err)}`);
     }
   };
 
@@ -402,9 +401,7 @@ export const DataLibraryWindowContent: React.FC = () => {
       }
     } catch (err) {
       console.error('Export failed:', err);
-      setError(
-        `Export failed: ${err instanceof Error ? err.message : String(err)}`
-      );
+      setError(`Export failed: ${describeError(err)}`);
     }
   };
 
@@ -21,6 +21,7 @@ import { memo, useCallback, useRef, useState } from 'react';
 import { createRoot } from 'react-dom/client';
 import 'katex/dist/katex.min.css';
 import { BlockMath } from 'react-katex';
+import { describeError } from '@/core/types/error';
 import CustomTitleBar from '@/shared/components/CustomTitleBar';
 import { createNoTransitionTheme } from '@/shared/theme';
 import { anafisTheme } from '@/shared/theme/unifiedTheme';
@@ -410,7 +411,7 @@ function UncertaintyCalculatorWindow() {
       );
     } catch (error) {
       console.error('Calculation error:', error);
-      setCalculationResult(`Error: ${String(error)}`);
+      setCalculationResult(`Error: ${describeError(error)}`);
     }
   }, [formula, variables]);
 
@@ -420,11 +421,21 @@ function UncertaintyCalculatorWindow() {
       return;
     }
 
+    if (variables.some((v) => !v.value)) {
+      alert('Please enter values for all variables.');
+      return;
+    }
+
     try {
-      const variableNames = variables.map((v) => v.name);
+      const backendVariables = variables.map((v) => ({
+        name: v.name,
+        value: parseFloat(v.value),
+        uncertainty: parseFloat(v.uncertainty || '0'),
+      }));
       const result = await invoke('generate_latex', {
         formula,
-        variables: variableNames,
+        variables: backendVariables,
+        inlineMath: false,
       });
 
       const latexResult = result as { string: string; latex: string };
@@ -432,8 +443,8 @@ function UncertaintyCalculatorWindow() {
       setLatexFormula(latexResult.latex);
     } catch (error) {
       console.error('LaTeX generation error:', error);
-      setStringRepresentation(`Error: ${String(error)}`);
-      setLatexFormula(`Error: ${String(error)}`);
+      setStringRepresentation(`Error: ${describeError(error)}`);
+      setLatexFormula(`Error: ${describeError(error)}`);
     }
   }, [formula, variables]);
 